@@ -6,6 +6,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .include_file("mod.rs")
         .type_attribute("TracesData", "#[allow(dead_code)]")
         .type_attribute("LogsData", "#[allow(dead_code)]")
+        .type_attribute("MetricsData", "#[allow(dead_code)]")
         .out_dir("./src/opentelemetry_proto/")
         .compile_protos(
             &[
@@ -14,6 +15,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                 "./proto/opentelemetry/trace.proto",
                 "./proto/opentelemetry/trace_service.proto",
                 "./proto/opentelemetry/logs.proto",
+                "./proto/opentelemetry/metrics.proto",
             ],
             &["proto"],
         )?;
@@ -59,6 +59,7 @@ pub async fn get_datapoints(
     match service::fetch_datapoints_page(
         auth.project_id,
         query.dataset,
+        query.version_id,
         query.limit,
         query.offset,
         clickhouse_ro,
@@ -7,7 +7,14 @@ use uuid::Uuid;
 
 use crate::{
     cache::Cache,
-    datasets::service::{self, CreateDatapointsOutcome, DatasetIdentifier, NewDatapoint},
+    datasets::{
+        export::{self, ExportFilter, ExportFormat},
+        materialize::{
+            self, DatapointMapping, MaterializeOutcome, MaterializeSource, SqlSourceContext,
+        },
+        service::{self, CreateDatapointsOutcome, DatasetIdentifier, NewDatapoint},
+        versions::{self, DEFAULT_DIFF_LIMIT, DiffVersionsOutcome},
+    },
     db::{self, DB, project_api_keys::ProjectApiKey},
     query_engine::QueryEngine,
     routes::{PaginatedResponse, types::ResponseResult},
@@ -48,6 +55,9 @@ async fn get_datasets(
 pub(crate) struct GetDatapointsRequestParams {
     #[serde(flatten)]
     pub dataset: DatasetIdentifier,
+    /// Read the datapoints as of this dataset version
+    #[serde(default)]
+    pub version_id: Option<Uuid>,
     pub limit: i64,
     pub offset: i64,
 }
@@ -76,6 +86,7 @@ async fn get_datapoints(
     match service::fetch_datapoints_page(
         project_id,
         query.dataset,
+        query.version_id,
         query.limit,
         query.offset,
         clickhouse_ro,
@@ -231,3 +242,268 @@ async fn get_parquet(
 
     Ok(response.streaming(get_response.map(|e| Ok::<_, anyhow::Error>(e))))
 }
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum ExportDestination {
+    /// Stream the file in the response body
+    #[default]
+    Response,
+    /// Upload the file to the exports bucket and return its download name
+    Storage,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ExportDatapointsRequest {
+    #[serde(flatten)]
+    pub dataset: DatasetIdentifier,
+    pub format: ExportFormat,
+    #[serde(default)]
+    pub destination: ExportDestination,
+    #[serde(default)]
+    pub filter: ExportFilter,
+}
+
+/// Export a dataset, or a filtered subset of its datapoints, as JSONL, CSV or Parquet
+#[post("/datasets/export")]
+async fn export_datapoints(
+    req: web::Json<ExportDatapointsRequest>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+    storage: web::Data<Arc<Storage>>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let project_id = project_api_key.project_id;
+    let request = req.into_inner();
+
+    let Some(dataset_id) = service::resolve_dataset_id(project_id, request.dataset, &db).await?
+    else {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Dataset not found"
+        })));
+    };
+
+    match request.destination {
+        ExportDestination::Response => {
+            let body = export::export_datapoints(
+                &clickhouse,
+                project_id,
+                dataset_id,
+                request.format,
+                &request.filter,
+            )?;
+            let filename = format!("{}.{}", dataset_id, request.format.extension());
+
+            Ok(HttpResponse::Ok()
+                .content_type(request.format.content_type())
+                .insert_header((
+                    "Content-Disposition",
+                    format!("attachment; filename=\"{}\"", filename),
+                ))
+                .insert_header(("Cache-Control", "no-cache"))
+                .streaming(body))
+        }
+        ExportDestination::Storage => {
+            let Ok(bucket) = std::env::var(crate::env::storage::S3_EXPORTS_BUCKET) else {
+                return Ok(HttpResponse::InternalServerError().json(serde_json::json!({
+                    "error": "exports storage is not configured"
+                })));
+            };
+            let stored = export::export_to_storage(
+                &clickhouse,
+                &storage,
+                &db,
+                &bucket,
+                project_id,
+                dataset_id,
+                request.format,
+                &request.filter,
+            )
+            .await?;
+
+            Ok(HttpResponse::Created().json(serde_json::json!({
+                "datasetId": dataset_id,
+                "name": stored.name,
+                "size": stored.size,
+                "downloadPath": format!("/v1/datasets/{}/parquets/{}", dataset_id, stored.name),
+            })))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CreateDatasetVersionRequest {
+    #[serde(flatten)]
+    pub dataset: DatasetIdentifier,
+    #[serde(default)]
+    pub version_name: Option<String>,
+}
+
+/// Snapshot a dataset's current datapoints as an immutable version
+#[post("/datasets/versions")]
+async fn create_dataset_version(
+    req: web::Json<CreateDatasetVersionRequest>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let request = req.into_inner();
+
+    let version = versions::create_dataset_version(
+        project_api_key.project_id,
+        request.dataset,
+        request.version_name.as_deref(),
+        db.into_inner(),
+        clickhouse.into_inner().as_ref().clone(),
+    )
+    .await?;
+
+    match version {
+        Some(version) => Ok(HttpResponse::Created().json(version)),
+        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Dataset not found"
+        }))),
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GetDatasetVersionsRequest {
+    #[serde(flatten)]
+    pub dataset: DatasetIdentifier,
+}
+
+#[get("/datasets/versions")]
+async fn get_dataset_versions(
+    params: web::Query<GetDatasetVersionsRequest>,
+    db: web::Data<DB>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let request = params.into_inner();
+
+    match versions::get_dataset_versions(
+        project_api_key.project_id,
+        request.dataset,
+        db.into_inner(),
+    )
+    .await?
+    {
+        Some(versions) => Ok(HttpResponse::Ok().json(versions)),
+        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Dataset not found"
+        }))),
+    }
+}
+
+fn default_diff_limit() -> usize {
+    DEFAULT_DIFF_LIMIT
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DiffDatasetVersionsRequest {
+    pub from_version_id: Uuid,
+    pub to_version_id: Uuid,
+    /// Maximum number of changed datapoints to list
+    #[serde(default = "default_diff_limit")]
+    pub limit: usize,
+}
+
+/// Datapoints added, removed or changed between two versions of a dataset
+#[get("/datasets/versions/diff")]
+async fn diff_dataset_versions(
+    params: web::Query<DiffDatasetVersionsRequest>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let request = params.into_inner();
+
+    let outcome = versions::diff_dataset_versions(
+        project_api_key.project_id,
+        request.from_version_id,
+        request.to_version_id,
+        request.limit,
+        db.into_inner(),
+        clickhouse.into_inner().as_ref().clone(),
+    )
+    .await?;
+
+    match outcome {
+        DiffVersionsOutcome::Diff(diff) => Ok(HttpResponse::Ok().json(diff)),
+        DiffVersionsOutcome::VersionNotFound => {
+            Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": "Dataset version not found"
+            })))
+        }
+        DiffVersionsOutcome::DifferentDatasets => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({
+                "error": "Versions must belong to the same dataset"
+            })))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MaterializeDatapointsRequest {
+    #[serde(flatten)]
+    pub dataset: DatasetIdentifier,
+    #[serde(default)]
+    pub create_dataset: bool,
+    pub source: MaterializeSource,
+    #[serde(default)]
+    pub mapping: DatapointMapping,
+}
+
+/// Create datapoints from labeling queue items or from spans selected by SQL
+#[post("/datasets/materialize")]
+async fn materialize_datapoints(
+    req: web::Json<MaterializeDatapointsRequest>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+    clickhouse_ro: web::Data<Option<Arc<ClickhouseReadonlyClient>>>,
+    query_engine: web::Data<Arc<QueryEngine>>,
+    http_client: web::Data<reqwest::Client>,
+    cache: web::Data<Cache>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let request = req.into_inner();
+    let sql_context = SqlSourceContext {
+        clickhouse_ro: clickhouse_ro.as_ref().clone(),
+        query_engine: query_engine.into_inner().as_ref().clone(),
+        http_client: http_client.into_inner(),
+        cache: cache.into_inner(),
+    };
+
+    let outcome = materialize::materialize_datapoints(
+        project_api_key.project_id,
+        request.dataset,
+        request.create_dataset,
+        request.source,
+        request.mapping,
+        db.into_inner(),
+        clickhouse.into_inner().as_ref().clone(),
+        sql_context,
+    )
+    .await?;
+
+    match outcome {
+        MaterializeOutcome::Created(outcome) => Ok(create_datapoints_response(outcome)),
+        MaterializeOutcome::QueueNotFound => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Labeling queue not found"
+        }))),
+        MaterializeOutcome::InvalidMapping(error) => {
+            Ok(HttpResponse::BadRequest().json(serde_json::json!({ "error": error })))
+        }
+        MaterializeOutcome::TooManyRows => Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": format!(
+                "At most {} datapoints can be materialized at once",
+                materialize::MAX_MATERIALIZED_DATAPOINTS
+            )
+        }))),
+        MaterializeOutcome::QueryFailed(e) => Err(e.into()),
+    }
+}
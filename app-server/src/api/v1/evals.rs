@@ -2,21 +2,27 @@ use std::{collections::HashMap, sync::Arc};
 
 use crate::{
     cache::Cache,
+    ch::evaluation_datapoints::get_linked_datapoint_scores,
     db::{self, DB, project_api_keys::ProjectApiKey},
     evaluations::{
         EvaluationDatapointResult, UpdatedDatapointStrings,
+        compare::{
+            DEFAULT_ALPHA, DEFAULT_REGRESSED_LIMIT, average_by_datapoint, compare_evaluations,
+        },
         insert_evaluation_datapoints,
+        judges::schedule_judges,
         realtime::{
             RealtimeDatapoint, cache_inserted_datapoint_trace_ids, send_datapoint_updates,
         },
         update_evaluation_datapoint,
     },
+    mq::MessageQueue,
     names::NameGenerator,
     pubsub::PubSub,
     routes::types::ResponseResult,
 };
 use actix_web::{
-    HttpResponse, post,
+    HttpResponse, get, post,
     web::{self, Json},
 };
 use serde::{Deserialize, Serialize};
@@ -30,6 +36,9 @@ pub struct InitEvalRequest {
     pub group_name: Option<String>,
     #[serde(default)]
     pub metadata: Option<Value>,
+    /// The dataset version the evaluation runs on
+    #[serde(default)]
+    pub dataset_version_id: Option<Uuid>,
 }
 
 #[post("/evals")]
@@ -49,9 +58,25 @@ pub async fn init_eval(
         name_generator.next().await
     };
 
-    let evaluation =
-        db::evaluations::create_evaluation(&db.pool, &name, project_id, &group_name, &metadata)
-            .await?;
+    if let Some(dataset_version_id) = req.dataset_version_id
+        && db::datasets::get_dataset_version(&db.pool, project_id, dataset_version_id)
+            .await?
+            .is_none()
+    {
+        return Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Dataset version not found"
+        })));
+    }
+
+    let evaluation = db::evaluations::create_evaluation(
+        &db.pool,
+        &name,
+        project_id,
+        &group_name,
+        &metadata,
+        req.dataset_version_id,
+    )
+    .await?;
 
     Ok(HttpResponse::Ok().json(evaluation))
 }
@@ -71,6 +96,7 @@ pub async fn save_eval_datapoints(
     clickhouse: web::Data<clickhouse::Client>,
     cache: web::Data<Cache>,
     pubsub: web::Data<Arc<PubSub>>,
+    queue: web::Data<Arc<MessageQueue>>,
     project_api_key: ProjectApiKey,
 ) -> ResponseResult {
     let eval_id = eval_id.into_inner();
@@ -103,6 +129,23 @@ pub async fn save_eval_datapoints(
     )
     .await;
 
+    // Datapoints without an output have nothing to judge yet; the SDK sends
+    // the output in a later update.
+    let judge_datapoint_ids = ch_rows
+        .iter()
+        .filter(|row| !row.executor_output.is_empty())
+        .map(|row| row.id)
+        .collect();
+    schedule_judges(
+        &db.pool,
+        queue.as_ref().clone(),
+        project_id,
+        eval_id,
+        &group_name,
+        judge_datapoint_ids,
+    )
+    .await;
+
     Ok(HttpResponse::Ok().json(eval_id))
 }
 
@@ -122,6 +165,7 @@ pub async fn update_eval_datapoint(
     db: web::Data<DB>,
     clickhouse: web::Data<clickhouse::Client>,
     pubsub: web::Data<Arc<PubSub>>,
+    queue: web::Data<Arc<MessageQueue>>,
     project_api_key: ProjectApiKey,
 ) -> ResponseResult {
     let (eval_id, datapoint_id) = path.into_inner();
@@ -161,5 +205,99 @@ pub async fn update_eval_datapoint(
     )
     .await;
 
+    if !ch_executor_output.is_empty() {
+        schedule_judges(
+            &db.pool,
+            queue.as_ref().clone(),
+            project_id,
+            eval_id,
+            &group_id,
+            vec![datapoint_id],
+        )
+        .await;
+    }
+
     Ok(HttpResponse::Ok().json(datapoint_id))
 }
+
+fn default_alpha() -> f64 {
+    DEFAULT_ALPHA
+}
+
+fn default_regressed_limit() -> usize {
+    DEFAULT_REGRESSED_LIMIT
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompareEvalsQuery {
+    pub baseline_id: Uuid,
+    pub candidate_id: Uuid,
+    #[serde(default = "default_alpha")]
+    pub alpha: f64,
+    /// Maximum number of regressed datapoints to return
+    #[serde(default = "default_regressed_limit")]
+    pub limit: usize,
+}
+
+/// Compares two evaluations datapoint by datapoint, pairing them by the dataset
+/// datapoint they were run on.
+#[get("/evals/compare")]
+pub async fn compare_evals(
+    query: web::Query<CompareEvalsQuery>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let query = query.into_inner();
+    let project_id = project_api_key.project_id;
+
+    if !(query.alpha > 0.0 && query.alpha < 1.0) {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "alpha must be between 0 and 1"
+        })));
+    }
+    if query.baseline_id == query.candidate_id {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "baselineId and candidateId must be different evaluations"
+        })));
+    }
+
+    for evaluation_id in [query.baseline_id, query.candidate_id] {
+        if db::evaluations::get_evaluation(&db.pool, project_id, evaluation_id)
+            .await?
+            .is_none()
+        {
+            return Ok(HttpResponse::NotFound().json(serde_json::json!({
+                "error": format!("Evaluation {} not found", evaluation_id)
+            })));
+        }
+    }
+
+    let rows = get_linked_datapoint_scores(
+        &clickhouse,
+        &project_id,
+        &[query.baseline_id, query.candidate_id],
+    )
+    .await?;
+    let run_scores = |evaluation_id: Uuid| {
+        average_by_datapoint(
+            rows.iter()
+                .filter(|row| row.evaluation_id == evaluation_id)
+                .map(|row| (row.dataset_datapoint_id, row.scores.as_str())),
+        )
+    };
+    let baseline = run_scores(query.baseline_id);
+    let candidate = run_scores(query.candidate_id);
+
+    let comparison = compare_evaluations(
+        query.baseline_id,
+        query.candidate_id,
+        &baseline,
+        &candidate,
+        query.alpha,
+        query.limit,
+    );
+
+    Ok(HttpResponse::Ok().json(comparison))
+}
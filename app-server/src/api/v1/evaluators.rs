@@ -0,0 +1,93 @@
+//! LLM-as-judge evaluators, run server-side on evaluation datapoints that
+//! arrive without their score (see `evaluations::judges`).
+
+use actix_web::{HttpResponse, delete, get, post, web};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{self, DB, project_api_keys::ProjectApiKey},
+    evaluations::judges::{EVALUATOR_TYPE_LLM_JUDGE, LlmJudgeDefinition},
+    routes::types::ResponseResult,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEvaluatorRequest {
+    /// Also the name of the score the evaluator writes.
+    pub name: String,
+    #[serde(flatten)]
+    pub definition: LlmJudgeDefinition,
+}
+
+// /v1/evaluators
+#[post("/evaluators")]
+pub async fn create_evaluator(
+    req: web::Json<CreateEvaluatorRequest>,
+    db: web::Data<DB>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let CreateEvaluatorRequest { name, definition } = req.into_inner();
+    let name = name.trim();
+
+    if name.is_empty() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": "name must not be empty"
+        })));
+    }
+    if let Err(e) = definition.validate() {
+        return Ok(HttpResponse::BadRequest().json(serde_json::json!({
+            "error": e.to_string()
+        })));
+    }
+
+    let evaluator = db::evaluators::create_evaluator(
+        &db.pool,
+        &project_api_key.project_id,
+        name,
+        EVALUATOR_TYPE_LLM_JUDGE,
+        &serde_json::to_value(&definition)?,
+    )
+    .await?;
+
+    Ok(HttpResponse::Created().json(evaluator))
+}
+
+// /v1/evaluators
+#[get("/evaluators")]
+pub async fn get_evaluators(db: web::Data<DB>, project_api_key: ProjectApiKey) -> ResponseResult {
+    let evaluators = db::evaluators::get_evaluators_by_type(
+        &db.pool,
+        &project_api_key.project_id,
+        EVALUATOR_TYPE_LLM_JUDGE,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(evaluators))
+}
+
+// /v1/evaluators/{evaluator_id}
+#[delete("/evaluators/{evaluator_id}")]
+pub async fn delete_evaluator(
+    path: web::Path<Uuid>,
+    db: web::Data<DB>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let evaluator_id = path.into_inner();
+
+    let deleted = db::evaluators::delete_evaluator(
+        &db.pool,
+        &project_api_key.project_id,
+        &evaluator_id,
+        EVALUATOR_TYPE_LLM_JUDGE,
+    )
+    .await?;
+
+    if deleted {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Evaluator not found"
+        })))
+    }
+}
@@ -7,7 +7,7 @@ use prost::Message;
 use crate::{
     db::{DB, project_api_keys::ProjectApiKey},
     features::{Feature, is_feature_enabled},
-    logs::producer::push_logs_to_queue,
+    logs::{opentelemetry_json::decode_export_logs_service_request, producer::push_logs_to_queue},
     mq::MessageQueue,
     opentelemetry_proto::opentelemetry::proto::collector::logs::v1::ExportLogsServiceRequest,
     routes::types::ResponseResult,
@@ -27,9 +27,13 @@ pub async fn process_logs(
 ) -> ResponseResult {
     let db = db.into_inner();
     let cache = cache.into_inner();
-    let request = ExportLogsServiceRequest::decode(body).map_err(|e| {
-        anyhow::anyhow!("Failed to decode ExportLogsServiceRequest from bytes. {e}")
-    })?;
+    let request = match decode_export_logs_request(&req, body) {
+        Ok(request) => request,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest()
+                .body(format!("Failed to decode ExportLogsServiceRequest: {e}")));
+        }
+    };
     let logs_message_queue = logs_message_queue.as_ref().clone();
 
     if is_feature_enabled(Feature::UsageLimit) {
@@ -64,3 +68,23 @@ pub async fn process_logs(
         Ok(HttpResponse::Ok().finish())
     }
 }
+
+/// Dispatch on `Content-Type`: `application/json` is OTLP/HTTP+JSON, anything else
+/// (including missing) falls through to OTLP/HTTP+protobuf, same as `/v1/traces`.
+fn decode_export_logs_request(
+    req: &HttpRequest,
+    body: Bytes,
+) -> Result<ExportLogsServiceRequest, anyhow::Error> {
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if content_type.starts_with("application/json") {
+        decode_export_logs_service_request(&body)
+            .map_err(|e| anyhow::anyhow!("OTLP/JSON decode failed: {e}"))
+    } else {
+        ExportLogsServiceRequest::decode(body)
+            .map_err(|e| anyhow::anyhow!("OTLP/protobuf decode failed: {e}"))
+    }
+}
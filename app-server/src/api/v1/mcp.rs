@@ -16,8 +16,10 @@ use uuid::Uuid;
 
 use crate::{
     cache::Cache,
+    ch::logs::CHTraceLog,
     db::{DB, project_api_keys::ProjectApiKey},
     llm::LlmClient,
+    opentelemetry_proto::opentelemetry::proto::collector::logs::v1::SeverityNumber,
     query_engine::QueryEngine,
     sql::{self, ClickhouseReadonlyClient, SqlQuerySource},
 };
@@ -75,6 +77,8 @@ scoped to your project — never filter on or reference a `project_id` column. O
 const MCP_SQL_EXTRAS: &str = r#"<joins>
 - spans.trace_id = traces.id
 - signal_events.trace_id = traces.id
+- logs.span_id = spans.span_id attaches a log to the span it was emitted in; spans.log_count,
+  spans.warning_log_count and spans.error_log_count already count them per span.
 - has(signal_events.clusters, clusters.id) to match events to the specific clusters they belong to
   (clusters.signal_id = signal_events.signal_id only scopes by signal — it is a many-to-many cross
   product, NOT an event-to-cluster match).
@@ -90,6 +94,7 @@ Use the `parameters` argument for {name:Type} placeholders in the query, e.g. {t
 - Recent traces: SELECT id, start_time, total_cost FROM traces ORDER BY start_time DESC LIMIT 10
 - LLM spans: SELECT name, model, input, output FROM spans WHERE span_type = 'LLM' LIMIT 20
 - Errors: SELECT trace_id, name, status FROM spans WHERE status = 'error' LIMIT 20
+- Spans that logged errors: SELECT trace_id, name, error_log_count FROM spans WHERE error_log_count > 0 LIMIT 20
 - Top clusters: SELECT name, num_signal_events FROM clusters ORDER BY num_signal_events DESC LIMIT 10
 </examples>"#;
 
@@ -213,6 +218,7 @@ impl LaminarMcpServer {
     /// - Duration and timing for each span
     /// - Full input/output for LLM spans (truncated for others)
     /// - Exception details if any spans failed
+    /// - Logs emitted during the trace, attached to the span they were emitted in
     ///
     /// If you need full information that is truncated, you should use query_laminar_sql tool to query spans that you're interested in.
     ///
@@ -276,6 +282,7 @@ impl LaminarMcpServer {
         project_id: Uuid,
         trace_id: Uuid,
     ) -> anyhow::Result<String> {
+        use crate::ch::logs::get_trace_logs;
         use crate::signals::private::compression::{TraceCompressor, render};
         use crate::signals::private::spans::get_trace_ch_spans;
         use crate::traces::previews::PreviewExtractor;
@@ -303,7 +310,25 @@ impl LaminarMcpServer {
             .await
             .map_err(|e| anyhow::anyhow!("Trace compression failed: {}", e))?;
 
-        Ok(render(&compressed))
+        let mut rendered = render(&compressed);
+        // Logs are best-effort context: a failed lookup still returns the trace.
+        match get_trace_logs(
+            self.clickhouse.clone(),
+            project_id,
+            trace_id,
+            MAX_TRACE_CONTEXT_LOGS,
+        )
+        .await
+        {
+            Ok(logs) if !logs.is_empty() => {
+                rendered.push_str("\n\n");
+                rendered.push_str(&render_trace_logs(&logs));
+            }
+            Ok(_) => {}
+            Err(e) => log::warn!("Failed to fetch logs for trace {trace_id}: {e:?}"),
+        }
+
+        Ok(rendered)
     }
 
     /// Run the agent for one question, returning `(answer, conversation_id)`. An existing
@@ -403,6 +428,53 @@ impl LaminarMcpServer {
     }
 }
 
+/// Most recent logs appended to `get_trace_context` output, rendered oldest first.
+#[cfg_attr(not(feature = "signals"), allow(dead_code))]
+const MAX_TRACE_CONTEXT_LOGS: u32 = 200;
+/// Log bodies longer than this are truncated in `get_trace_context` output.
+#[cfg_attr(not(feature = "signals"), allow(dead_code))]
+const MAX_TRACE_CONTEXT_LOG_BODY_CHARS: usize = 500;
+
+/// Render logs as one line each, keyed by the span they were emitted in so
+/// they can be matched against the span tree above them.
+#[cfg_attr(not(feature = "signals"), allow(dead_code))]
+fn render_trace_logs(logs: &[CHTraceLog]) -> String {
+    let mut out = format!("Logs ({}):", logs.len());
+    for log in logs {
+        let time = chrono::DateTime::from_timestamp_nanos(log.time).to_rfc3339();
+        let severity = if log.severity_text.is_empty() {
+            SeverityNumber::try_from(log.severity_number as i32)
+                .map(|s| {
+                    s.as_str_name()
+                        .trim_start_matches("SEVERITY_NUMBER_")
+                        .to_string()
+                })
+                .unwrap_or_default()
+        } else {
+            log.severity_text.clone()
+        };
+        let span = if log.span_id.is_nil() {
+            "-".to_string()
+        } else {
+            log.span_id.to_string()
+        };
+        let mut body: String = log
+            .body
+            .chars()
+            .take(MAX_TRACE_CONTEXT_LOG_BODY_CHARS)
+            .collect();
+        if body.len() < log.body.len() {
+            body.push_str("...");
+        }
+        out.push_str(&format!("\n[{time}] {severity} span={span}"));
+        if !log.event_name.is_empty() {
+            out.push_str(&format!(" event={}", log.event_name));
+        }
+        out.push_str(&format!(": {body}"));
+    }
+    out
+}
+
 #[cfg(not(feature = "signals"))]
 impl LaminarMcpServer {
     async fn get_trace_context_for_mcp(
@@ -625,4 +697,36 @@ mod tests {
             "get_trace_context description changed unexpectedly: {trace}"
         );
     }
+
+    /// Logs render one per line with their span, falling back to the severity number's name when
+    /// the sender left `severity_text` empty.
+    #[test]
+    fn render_trace_logs_keys_lines_by_span() {
+        let span_id = Uuid::new_v4();
+        let logs = vec![
+            CHTraceLog {
+                span_id,
+                time: 1_700_000_000_000_000_000,
+                severity_number: 17,
+                severity_text: String::new(),
+                body: "\"checkout failed\"".to_string(),
+                event_name: String::new(),
+            },
+            CHTraceLog {
+                span_id: Uuid::nil(),
+                time: 1_700_000_001_000_000_000,
+                severity_number: 9,
+                severity_text: "info".to_string(),
+                body: "x".repeat(MAX_TRACE_CONTEXT_LOG_BODY_CHARS + 1),
+                event_name: "page.load".to_string(),
+            },
+        ];
+
+        let rendered = render_trace_logs(&logs);
+        let lines: Vec<&str> = rendered.lines().collect();
+        assert_eq!(lines[0], "Logs (2):");
+        assert!(lines[1].contains(&format!("ERROR span={span_id}: \"checkout failed\"")));
+        assert!(lines[2].contains("info span=- event=page.load: "));
+        assert!(lines[2].ends_with("..."));
+    }
 }
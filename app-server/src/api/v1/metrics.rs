@@ -1,13 +1,66 @@
-use actix_web::{HttpRequest, HttpResponse, post};
+use std::sync::Arc;
 
-use crate::routes::types::ResponseResult;
+use actix_web::{HttpRequest, HttpResponse, post, web};
+use bytes::Bytes;
+use prost::Message;
+
+use crate::{
+    db::{DB, project_api_keys::ProjectApiKey},
+    features::{Feature, is_feature_enabled},
+    metrics::{
+        opentelemetry_json::decode_export_metrics_service_request, producer::push_metrics_to_queue,
+    },
+    mq::MessageQueue,
+    opentelemetry_proto::opentelemetry::proto::collector::metrics::v1::ExportMetricsServiceRequest,
+    routes::types::ResponseResult,
+    utils::limits::get_workspace_bytes_limit_exceeded,
+};
 
 // /v1/metrics
 #[post("")]
-pub async fn process_metrics(req: HttpRequest) -> ResponseResult {
-    // This is a placeholder that just returns ok, so that client otel exporters
-    // don't fail when trying to send metrics to the server.
-    // TODO: Implement metrics processing
+pub async fn process_metrics(
+    req: HttpRequest,
+    body: Bytes,
+    project_api_key: ProjectApiKey,
+    metrics_message_queue: web::Data<Arc<MessageQueue>>,
+    cache: web::Data<crate::cache::Cache>,
+    db: web::Data<DB>,
+    clickhouse: web::Data<clickhouse::Client>,
+) -> ResponseResult {
+    let db = db.into_inner();
+    let cache = cache.into_inner();
+    let request = match decode_export_metrics_request(&req, body) {
+        Ok(request) => request,
+        Err(e) => {
+            return Ok(HttpResponse::BadRequest()
+                .body(format!("Failed to decode ExportMetricsServiceRequest: {e}")));
+        }
+    };
+    let metrics_message_queue = metrics_message_queue.as_ref().clone();
+
+    if is_feature_enabled(Feature::UsageLimit) {
+        let bytes_limit_exceeded = get_workspace_bytes_limit_exceeded(
+            db,
+            clickhouse.as_ref().clone(),
+            cache,
+            project_api_key.project_id,
+        )
+        .await
+        .map_err(|e| {
+            log::error!("Failed to get workspace limits: {:?}", e);
+        });
+
+        if bytes_limit_exceeded.is_ok_and(|exceeded| exceeded) {
+            return Ok(HttpResponse::Forbidden().json("Workspace data limit exceeded"));
+        }
+    }
+
+    let response =
+        push_metrics_to_queue(request, project_api_key.project_id, metrics_message_queue).await?;
+    if response.partial_success.is_some() {
+        return Err(anyhow::anyhow!("There has been an error during metrics processing.").into());
+    }
+
     let keep_alive = req.headers().get("connection").map_or(false, |v| {
         v.to_str().unwrap_or_default().trim().to_lowercase() == "keep-alive"
     });
@@ -17,3 +70,23 @@ pub async fn process_metrics(req: HttpRequest) -> ResponseResult {
         Ok(HttpResponse::Ok().finish())
     }
 }
+
+/// Dispatch on `Content-Type`: `application/json` is OTLP/HTTP+JSON, anything else
+/// (including missing) falls through to OTLP/HTTP+protobuf, same as `/v1/traces`.
+fn decode_export_metrics_request(
+    req: &HttpRequest,
+    body: Bytes,
+) -> Result<ExportMetricsServiceRequest, anyhow::Error> {
+    let content_type = req
+        .headers()
+        .get("content-type")
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    if content_type.starts_with("application/json") {
+        decode_export_metrics_service_request(&body)
+            .map_err(|e| anyhow::anyhow!("OTLP/JSON decode failed: {e}"))
+    } else {
+        ExportMetricsServiceRequest::decode(body)
+            .map_err(|e| anyhow::anyhow!("OTLP/protobuf decode failed: {e}"))
+    }
+}
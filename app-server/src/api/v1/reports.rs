@@ -0,0 +1,200 @@
+//! Custom scheduled reports: a saved SQL query delivered on a schedule, managed
+//! with a project API key.
+
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, delete, get, post, web};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    db::{
+        self, DB,
+        project_api_keys::ProjectApiKey,
+        reports::{NewCustomReport, NewReportTarget},
+    },
+    notifications::TargetType,
+    query_engine::QueryEngine,
+    reports::custom::{CustomReportDefinition, REPORT_TYPE_CUSTOM_QUERY, RenderOptions},
+    routes::types::ResponseResult,
+    sql,
+};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportTargetRequest {
+    pub r#type: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReportRequest {
+    pub name: String,
+    /// 0 = Monday … 6 = Sunday
+    pub weekdays: Vec<i32>,
+    /// Hour of the day, UTC
+    pub hour: i32,
+    pub query: String,
+    pub render: RenderOptions,
+    #[serde(default)]
+    pub targets: Vec<ReportTargetRequest>,
+}
+
+fn bad_request(error: impl std::fmt::Display) -> HttpResponse {
+    HttpResponse::BadRequest().json(serde_json::json!({ "error": error.to_string() }))
+}
+
+/// Checks a target and maps it to its row. Slack targets need a workspace
+/// integration and are managed in the app.
+fn parse_target(target: ReportTargetRequest) -> Result<NewReportTarget, String> {
+    let target_type = target.r#type.parse::<TargetType>()?;
+    match target_type {
+        TargetType::Email => match target.email {
+            Some(email) if email.contains('@') => Ok(NewReportTarget {
+                r#type: target_type.to_string(),
+                email: Some(email),
+                url: None,
+            }),
+            _ => Err("EMAIL targets require an email".to_string()),
+        },
+        TargetType::Webhook | TargetType::Teams | TargetType::Discord => match target.url {
+            Some(url) if url.starts_with("https://") || url.starts_with("http://") => {
+                Ok(NewReportTarget {
+                    r#type: target_type.to_string(),
+                    email: None,
+                    url: Some(url),
+                })
+            }
+            _ => Err(format!("{} targets require an http(s) url", target_type)),
+        },
+        TargetType::Slack => {
+            Err("SLACK targets can only be added from the project settings".to_string())
+        }
+    }
+}
+
+// /v1/reports
+#[post("/reports")]
+pub async fn create_report(
+    body: web::Json<CreateReportRequest>,
+    db: web::Data<DB>,
+    query_engine: web::Data<Arc<QueryEngine>>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let request = body.into_inner();
+    let project_id = project_api_key.project_id;
+
+    if request.name.trim().is_empty() {
+        return Ok(bad_request("name must not be empty"));
+    }
+    if request.weekdays.is_empty() || request.weekdays.iter().any(|d| !(0..=6).contains(d)) {
+        return Ok(bad_request("weekdays must be a non-empty list of days 0-6"));
+    }
+    if !(0..=23).contains(&request.hour) {
+        return Ok(bad_request("hour must be between 0 and 23"));
+    }
+
+    let definition = CustomReportDefinition {
+        query: request.query,
+        render: request.render,
+    };
+    if let Err(e) = definition.validate() {
+        return Ok(bad_request(e));
+    }
+    // Rejects queries the scheduled run would fail on, e.g. unknown tables.
+    sql::validate_query(
+        definition.query.clone(),
+        project_id,
+        query_engine.as_ref().clone(),
+    )
+    .await?;
+
+    let targets = match request
+        .targets
+        .into_iter()
+        .map(parse_target)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(targets) => targets,
+        Err(e) => return Ok(bad_request(e)),
+    };
+    let emails: Vec<String> = targets.iter().filter_map(|t| t.email.clone()).collect();
+    if !emails.is_empty() {
+        let non_members =
+            db::reports::get_non_member_emails(&db.pool, &project_id, &emails).await?;
+        if !non_members.is_empty() {
+            return Ok(bad_request(format!(
+                "EMAIL targets must be workspace members: {}",
+                non_members.join(", ")
+            )));
+        }
+    }
+
+    let mut weekdays = request.weekdays;
+    weekdays.sort();
+    weekdays.dedup();
+
+    let report_id = db::reports::create_custom_report(
+        &db.pool,
+        NewCustomReport {
+            project_id,
+            report_type: REPORT_TYPE_CUSTOM_QUERY,
+            name: request.name.trim(),
+            weekdays: &weekdays,
+            hour: request.hour,
+            metadata: serde_json::to_value(&definition)?,
+            targets: &targets,
+        },
+    )
+    .await?;
+
+    match report_id {
+        Some(report_id) => Ok(HttpResponse::Created().json(serde_json::json!({ "id": report_id }))),
+        None => Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Project not found"
+        }))),
+    }
+}
+
+// /v1/reports
+#[get("/reports")]
+pub async fn get_reports(db: web::Data<DB>, project_api_key: ProjectApiKey) -> ResponseResult {
+    let reports = db::reports::get_custom_reports_for_project(
+        &db.pool,
+        &project_api_key.project_id,
+        REPORT_TYPE_CUSTOM_QUERY,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(reports))
+}
+
+// /v1/reports/{report_id}
+#[delete("/reports/{report_id}")]
+pub async fn delete_report(
+    path: web::Path<Uuid>,
+    db: web::Data<DB>,
+    project_api_key: ProjectApiKey,
+) -> ResponseResult {
+    let report_id = path.into_inner();
+
+    let deleted = db::reports::delete_custom_report(
+        &db.pool,
+        &project_api_key.project_id,
+        &report_id,
+        REPORT_TYPE_CUSTOM_QUERY,
+    )
+    .await?;
+
+    if deleted {
+        Ok(HttpResponse::Ok().finish())
+    } else {
+        Ok(HttpResponse::NotFound().json(serde_json::json!({
+            "error": "Report not found"
+        })))
+    }
+}
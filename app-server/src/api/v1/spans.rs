@@ -93,6 +93,7 @@ pub async fn create_spans(
             end_time: req.end_time,
             status: None,
             events: vec![],
+            links: vec![],
             tags: None,
             input_url: None,
             output_url: None,
@@ -16,6 +16,9 @@ use crate::api::utils::get_api_key_from_raw_value;
 use crate::cache::Cache;
 use crate::db::DB;
 use crate::db::project_api_keys::ProjectApiKey;
+use crate::project_api_keys::restrictions::{
+    ApiKeyScope, Rejection, check_restrictions, client_ip, required_scope,
+};
 
 pub mod cli_user;
 
@@ -52,7 +55,8 @@ async fn validate_project_api_key(
         .unwrap()
         .into_inner();
 
-    match get_api_key_from_raw_value(&db.pool, cache, credentials.token().to_string()).await {
+    let token = credentials.token().to_string();
+    match get_api_key_from_raw_value(&db.pool, cache.clone(), token).await {
         Ok(api_key) => {
             // Check if ingest-only keys are allowed for this endpoint
             if !allow_ingest_only && api_key.is_ingest_only {
@@ -67,6 +71,33 @@ async fn validate_project_api_key(
                     req,
                 ));
             }
+            let ip = client_ip(
+                req.peer_addr().map(|addr| addr.ip()),
+                req.headers()
+                    .get_all("x-forwarded-for")
+                    .filter_map(|value| value.to_str().ok()),
+            );
+            if let Err(rejection) =
+                check_restrictions(&api_key, required_scope(req.path()), ip, cache).await
+            {
+                log::warn!(
+                    "API key restriction rejected request: project_id={}, path={}, reason={:?}",
+                    api_key.project_id,
+                    req.path(),
+                    rejection
+                );
+                let response = match rejection {
+                    Rejection::RateLimited => actix_web::HttpResponse::TooManyRequests(),
+                    Rejection::MissingScope | Rejection::IpNotAllowed => {
+                        actix_web::HttpResponse::Forbidden()
+                    }
+                }
+                .body(rejection.message());
+                return Err((
+                    actix_web::error::InternalError::from_response("", response).into(),
+                    req,
+                ));
+            }
             req.extensions_mut().insert(api_key);
             Ok(req)
         }
@@ -93,16 +124,44 @@ pub async fn project_ingestion_validator(
     validate_project_api_key(req, credentials, true).await
 }
 
-/// Authenticates gRPC trace ingestion requests.
+/// Authenticates gRPC ingestion requests and applies the key's restrictions
+/// for `scope`.
 /// Note: This endpoint accepts both default and ingest-only API keys,
-/// as it's used for writing trace data to the project.
-pub async fn authenticate_request(
-    metadata: &tonic::metadata::MetadataMap,
+/// as it's used for writing telemetry data to the project.
+pub async fn authenticate_request<T>(
+    request: &tonic::Request<T>,
+    scope: ApiKeyScope,
     pool: &PgPool,
     cache: Arc<Cache>,
-) -> anyhow::Result<ProjectApiKey> {
-    let token = extract_bearer_token(metadata)?;
-    get_api_key_from_raw_value(pool, cache, token).await
+) -> Result<ProjectApiKey, Status> {
+    let token = extract_bearer_token(request.metadata())
+        .map_err(|_| Status::unauthenticated("Failed to authenticate request"))?;
+    let api_key = get_api_key_from_raw_value(pool, cache.clone(), token)
+        .await
+        .map_err(|_| Status::unauthenticated("Failed to authenticate request"))?;
+
+    let ip = client_ip(
+        request.remote_addr().map(|addr| addr.ip()),
+        request
+            .metadata()
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok()),
+    );
+    if let Err(rejection) = check_restrictions(&api_key, Some(scope), ip, cache).await {
+        log::warn!(
+            "API key restriction rejected gRPC request: project_id={}, reason={:?}",
+            api_key.project_id,
+            rejection
+        );
+        return Err(match rejection {
+            Rejection::RateLimited => Status::resource_exhausted(rejection.message()),
+            Rejection::MissingScope | Rejection::IpNotAllowed => {
+                Status::permission_denied(rejection.message())
+            }
+        });
+    }
+    Ok(api_key)
 }
 
 fn extract_bearer_token(metadata: &tonic::metadata::MetadataMap) -> anyhow::Result<String> {
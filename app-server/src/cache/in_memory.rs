@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 use std::result::Result;
 use std::sync::Arc;
 use std::time::Duration;
@@ -11,7 +11,9 @@ const DEFAULT_CACHE_SIZE: u64 = 100;
 pub struct InMemoryCache {
     cache: moka::future::Cache<String, Vec<u8>>,
     locks: Arc<RwLock<HashMap<String, tokio::time::Instant>>>,
-    sorted_sets: Arc<RwLock<HashMap<String, HashSet<String>>>>,
+    sorted_sets: Arc<RwLock<HashMap<String, HashMap<String, f64>>>>,
+    /// Items and expiry of each list. Like Redis, every push resets the TTL.
+    lists: Arc<RwLock<HashMap<String, (tokio::time::Instant, Vec<Vec<u8>>)>>>,
 }
 
 impl InMemoryCache {
@@ -20,6 +22,7 @@ impl InMemoryCache {
             cache: moka::future::Cache::new(capacity.unwrap_or(DEFAULT_CACHE_SIZE)),
             locks: Arc::new(RwLock::new(HashMap::new())),
             sorted_sets: Arc::new(RwLock::new(HashMap::new())),
+            lists: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 }
@@ -123,11 +126,13 @@ impl CacheTrait for InMemoryCache {
         Ok(())
     }
 
-    async fn zadd(&self, key: &str, _score: f64, member: &str) -> Result<(), CacheError> {
+    async fn zadd(&self, key: &str, score: f64, member: &str) -> Result<(), CacheError> {
         let mut sets = self.sorted_sets.write().await;
+        // NX semantics, same as Redis: an existing member keeps its score
         sets.entry(key.to_string())
-            .or_insert_with(HashSet::new)
-            .insert(member.to_string());
+            .or_insert_with(HashMap::new)
+            .entry(member.to_string())
+            .or_insert(score);
         Ok(())
     }
 
@@ -138,6 +143,76 @@ impl CacheTrait for InMemoryCache {
         Ok(())
     }
 
+    async fn zrem(&self, key: &str, member: &str) -> Result<bool, CacheError> {
+        let mut sets = self.sorted_sets.write().await;
+        Ok(sets
+            .get_mut(key)
+            .is_some_and(|set| set.remove(member).is_some()))
+    }
+
+    async fn zpop_by_score(
+        &self,
+        key: &str,
+        max_score: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, CacheError> {
+        let mut sets = self.sorted_sets.write().await;
+        let Some(set) = sets.get_mut(key) else {
+            return Ok(Vec::new());
+        };
+        let mut due: Vec<(String, f64)> = set
+            .iter()
+            .filter(|(_, score)| **score <= max_score)
+            .map(|(member, score)| (member.clone(), *score))
+            .collect();
+        due.sort_by(|a, b| a.1.total_cmp(&b.1));
+        due.truncate(limit);
+        for (member, _) in &due {
+            set.remove(member);
+        }
+        Ok(due.into_iter().map(|(member, _)| member).collect())
+    }
+
+    async fn push_to_list<T>(
+        &self,
+        key: &str,
+        values: Vec<T>,
+        ttl_seconds: u64,
+    ) -> Result<(), CacheError>
+    where
+        T: Serialize + Send,
+    {
+        let mut serialized = Vec::with_capacity(values.len());
+        for value in &values {
+            serialized.push(serde_json::to_vec(value).map_err(|e| CacheError::SerDeError(e))?);
+        }
+        let mut lists = self.lists.write().await;
+        let now = tokio::time::Instant::now();
+        // Clean up expired lists, e.g. buffers that were never taken
+        lists.retain(|_, (expires_at, _)| *expires_at > now);
+
+        let (expires_at, items) = lists
+            .entry(key.to_string())
+            .or_insert_with(|| (now, Vec::new()));
+        *expires_at = now + Duration::from_secs(ttl_seconds);
+        items.extend(serialized);
+        Ok(())
+    }
+
+    async fn take_list<T>(&self, key: &str) -> Result<Vec<T>, CacheError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let items = match self.lists.write().await.remove(key) {
+            Some((expires_at, items)) if expires_at > tokio::time::Instant::now() => items,
+            _ => Vec::new(),
+        };
+        items
+            .iter()
+            .map(|bytes| serde_json::from_slice::<T>(bytes).map_err(|e| CacheError::SerDeError(e)))
+            .collect()
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, CacheError> {
         // Check both regular cache and sorted sets
         let in_cache = self.cache.get(key).await.is_some();
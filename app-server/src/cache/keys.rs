@@ -1,9 +1,15 @@
 //! This module contains the prefixes for the cache keys.
 //! Keys are used across modules and need to be stored in a single place
 
-pub const CUSTOM_MODEL_COSTS_CACHE_KEY: &str = "custom_model_costs";
-pub const MODEL_COSTS_CACHE_KEY: &str = "model_costs";
+// Model cost entries hold a `ModelCostsHistory` (all price versions) rather
+// than a single costs blob, so the keys are versioned to keep old entries from
+// being read as the new shape. The frontend invalidates custom model costs and
+// pricing discounts by these keys; keep them in sync with `frontend/lib/cache.ts`.
+pub const CUSTOM_MODEL_COSTS_CACHE_KEY: &str = "custom_model_costs_v2";
+pub const MODEL_COSTS_CACHE_KEY: &str = "model_costs_v2";
+pub const PRICING_DISCOUNTS_CACHE_KEY: &str = "pricing_discounts";
 pub const PROJECT_API_KEY_CACHE_KEY: &str = "project_api_key";
+pub const PROJECT_API_KEY_RATE_LIMIT_CACHE_KEY: &str = "project_api_key_ratelimit";
 pub const PROJECT_CACHE_KEY: &str = "project";
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const SIGNAL_TRIGGERS_CACHE_KEY: &str = "signal_triggers";
@@ -34,6 +40,7 @@ pub const WORKSPACE_DEPLOYMENTS_BY_WORKSPACE_CACHE_KEY: &str = "workspace_deploy
 pub const DATA_PLANE_AUTH_TOKEN_CACHE_KEY: &str = "data_plane_auth_token";
 pub const REPORT_SCHEDULER_LOCK_CACHE_KEY: &str = "report_scheduler_lock";
 pub const REPORT_SCHEDULER_LAST_CHECK_CACHE_KEY: &str = "report_scheduler_last_check";
+pub const METRIC_ALERTS_SCHEDULER_LOCK_CACHE_KEY: &str = "metric_alerts_scheduler_lock";
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const SAMPLING_FACTORS_CACHE_KEY: &str = "sampling_factors";
 pub const WORKSPACE_USAGE_WARNINGS_CACHE_KEY: &str = "workspace_usage_warnings";
@@ -43,14 +50,23 @@ pub const SYS_PROMPT_SUMMARY_CACHE_KEY: &str = "sys_prompt_summary_v2";
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const SPAN_KEEP_DEFAULT_RULES_CACHE_KEY: &str = "signals_span_keep_default_rules";
 pub const TRACE_EVALUATION_ID_CACHE_KEY: &str = "trace_evaluation_id";
+// Tail-based trace sampling. Buffer and decision keys are namespaced by
+// `(project_id, trace_id)`; the pending key is a single sorted set of
+// `{project_id}:{trace_id}` members scored by decision deadline.
+pub const TRACE_SAMPLING_BUFFER_CACHE_KEY: &str = "trace_sampling_buffer";
+pub const TRACE_SAMPLING_DECISION_CACHE_KEY: &str = "trace_sampling_decision";
+pub const TRACE_SAMPLING_PENDING_CACHE_KEY: &str = "trace_sampling_pending";
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const TRACE_INPUT_REGEX_CACHE_KEY: &str = "signals_trace_input_regex";
 
 pub const INGESTION_RATE_LIMIT_PROJECT_ID_CACHE_KEY: &str = "ingestion_rate_limit_project_id";
 pub const PROJECT_MEMBERSHIP_CACHE_KEY: &str = "project_membership";
 pub const AGENT_VERSION_HASH_CACHE_KEY: &str = "agent_version_hash";
+#[cfg_attr(not(feature = "signals"), allow(dead_code))]
+pub const ANTHROPIC_BATCH_METADATA_CACHE_KEY: &str = "anthropic_batch_metadata";
 pub const AGENT_STABLE_PROMPT_REGEX_CACHE_KEY: &str = "agent_stable_prompt_regex";
 pub const AGENT_CLASSIFY_LOCK_CACHE_KEY: &str = "agent_classify_lock";
+pub const COST_BACKFILL_LOCK_CACHE_KEY: &str = "cost_backfill_lock";
 
 // Debugger replay cache (LAM-1715). Concrete Redis keys are namespaced by
 // `(project_id, replay_trace_id)` — see `traces/debug_cache.rs`.
@@ -66,6 +66,34 @@ pub trait CacheTrait {
     /// Uses pipelining for Redis, sequential for InMemory
     async fn pipe_zadd(&self, key: &str, members: &[String]) -> Result<(), CacheError>;
 
+    /// Remove a member from a sorted set. Returns true if the member was present.
+    async fn zrem(&self, key: &str, member: &str) -> Result<bool, CacheError>;
+
+    /// Remove and return up to `limit` members whose score is <= `max_score`.
+    /// Every returned member was removed by this call, so concurrent callers
+    /// never receive the same member twice.
+    async fn zpop_by_score(
+        &self,
+        key: &str,
+        max_score: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, CacheError>;
+
+    /// Append values to the end of a list and (re)set the list's TTL.
+    async fn push_to_list<T>(
+        &self,
+        key: &str,
+        values: Vec<T>,
+        ttl_seconds: u64,
+    ) -> Result<(), CacheError>
+    where
+        T: Serialize + Send;
+
+    /// Atomically read and delete a whole list. Missing lists are empty.
+    async fn take_list<T>(&self, key: &str) -> Result<Vec<T>, CacheError>
+    where
+        T: for<'de> Deserialize<'de>;
+
     /// Check if a key exists in the cache
     async fn exists(&self, key: &str) -> Result<bool, CacheError>;
 
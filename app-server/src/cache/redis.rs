@@ -214,6 +214,123 @@ impl CacheTrait for RedisCache {
         Ok(())
     }
 
+    async fn zrem(&self, key: &str, member: &str) -> Result<bool, CacheError> {
+        let result: RedisResult<i64> = self.connection.current_clone().zrem(key, member).await;
+        match result {
+            Ok(removed) => Ok(removed > 0),
+            Err(e) => {
+                self.on_error("zrem", &e);
+                Err(CacheError::InternalError(anyhow::Error::from(e)))
+            }
+        }
+    }
+
+    async fn zpop_by_score(
+        &self,
+        key: &str,
+        max_score: f64,
+        limit: usize,
+    ) -> Result<Vec<String>, CacheError> {
+        let result: RedisResult<Vec<String>> = redis::cmd("ZRANGEBYSCORE")
+            .arg(key)
+            .arg("-inf")
+            .arg(max_score)
+            .arg("LIMIT")
+            .arg(0)
+            .arg(limit)
+            .query_async(&mut self.connection.current_clone())
+            .await;
+        let members = result.map_err(|e| {
+            self.on_error("zpop_by_score", &e);
+            CacheError::InternalError(anyhow::Error::from(e))
+        })?;
+
+        // ZREM is the claim: only the caller whose ZREM actually removed the
+        // member gets it back.
+        let mut claimed = Vec::with_capacity(members.len());
+        for member in members {
+            if self.zrem(key, &member).await? {
+                claimed.push(member);
+            }
+        }
+        Ok(claimed)
+    }
+
+    async fn push_to_list<T>(
+        &self,
+        key: &str,
+        values: Vec<T>,
+        ttl_seconds: u64,
+    ) -> Result<(), CacheError>
+    where
+        T: Serialize + Send,
+    {
+        if values.is_empty() {
+            return Ok(());
+        }
+
+        let mut serialized = Vec::with_capacity(values.len());
+        for value in &values {
+            match serde_json::to_vec(value) {
+                Ok(bytes) => serialized.push(bytes),
+                Err(e) => {
+                    log::error!("Serialization error: {}", e);
+                    return Err(CacheError::SerDeError(e));
+                }
+            }
+        }
+
+        let _: () = redis::pipe()
+            .atomic()
+            .cmd("RPUSH")
+            .arg(key)
+            .arg(serialized)
+            .ignore()
+            .cmd("EXPIRE")
+            .arg(key)
+            .arg(ttl_seconds)
+            .ignore()
+            .query_async(&mut self.connection.current_clone())
+            .await
+            .map_err(|e| {
+                self.on_error("push_to_list", &e);
+                CacheError::InternalError(anyhow::Error::from(e))
+            })?;
+
+        Ok(())
+    }
+
+    async fn take_list<T>(&self, key: &str) -> Result<Vec<T>, CacheError>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let (items,): (Vec<Vec<u8>>,) = redis::pipe()
+            .atomic()
+            .cmd("LRANGE")
+            .arg(key)
+            .arg(0)
+            .arg(-1)
+            .cmd("DEL")
+            .arg(key)
+            .ignore()
+            .query_async(&mut self.connection.current_clone())
+            .await
+            .map_err(|e| {
+                self.on_error("take_list", &e);
+                CacheError::InternalError(anyhow::Error::from(e))
+            })?;
+
+        items
+            .iter()
+            .map(|bytes| {
+                serde_json::from_slice::<T>(bytes).map_err(|e| {
+                    log::error!("Deserialization error: {}", e);
+                    CacheError::SerDeError(e)
+                })
+            })
+            .collect()
+    }
+
     async fn exists(&self, key: &str) -> Result<bool, CacheError> {
         let result: RedisResult<bool> = self.connection.current_clone().exists(key).await;
         result.map_err(|e| {
@@ -16,6 +16,7 @@ use crate::db::workspaces::WorkspaceDeployment;
 use super::deduped_content::CHDedupedContent;
 use super::notification_deliveries::CHNotificationDelivery;
 use super::notifications::CHNotification;
+use super::span_metrics::CHSpanMetricsRollup;
 use super::spans::CHSpan;
 use super::traces::CHTrace;
 use super::{ClickhouseInsertable, ClickhouseTrait};
@@ -30,6 +31,7 @@ pub enum DataPlaneBatch {
     NotificationDeliveries(Vec<CHNotificationDelivery>),
     Notifications(Vec<CHNotification>),
     DedupedContent(Vec<CHDedupedContent>),
+    SpanMetrics(Vec<CHSpanMetricsRollup>),
 }
 
 /// Data plane ClickHouse client that sends data to a remote data plane server.
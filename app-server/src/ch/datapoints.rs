@@ -97,3 +97,96 @@ pub async fn insert_datapoints(
         )),
     }
 }
+
+/// Copies the latest version of every datapoint in the dataset into
+/// `dataset_snapshot_datapoints` under `snapshot_id` and returns the number of
+/// datapoints copied.
+pub async fn snapshot_datapoints(
+    clickhouse: &clickhouse::Client,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    snapshot_id: Uuid,
+) -> Result<u64> {
+    clickhouse
+        .query(
+            "INSERT INTO dataset_snapshot_datapoints
+                (snapshot_id, id, dataset_id, project_id, created_at, data, target, metadata)
+            SELECT toUUID(?), id, dataset_id, project_id, created_at, data, target, metadata
+            FROM dataset_datapoints
+            WHERE project_id = ? AND dataset_id = ?
+            ORDER BY id, created_at DESC
+            LIMIT 1 BY id",
+        )
+        .bind(snapshot_id)
+        .bind(project_id)
+        .bind(dataset_id)
+        .execute()
+        .await?;
+
+    let count = clickhouse
+        .query(
+            "SELECT count()
+            FROM dataset_snapshot_datapoints
+            WHERE project_id = ? AND dataset_id = ? AND snapshot_id = ?",
+        )
+        .bind(project_id)
+        .bind(dataset_id)
+        .bind(snapshot_id)
+        .fetch_one::<u64>()
+        .await?;
+
+    Ok(count)
+}
+
+/// A datapoint that differs between two snapshots of a dataset.
+#[derive(Row, Deserialize, Debug)]
+pub struct CHSnapshotDiffRow {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub id: Uuid,
+    /// `added`, `removed` or `changed`
+    pub change: String,
+}
+
+/// Datapoints added, removed or changed (in `data`, `target` or `metadata`)
+/// between two snapshots of the same dataset.
+pub async fn diff_snapshots(
+    clickhouse: &clickhouse::Client,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    from_snapshot_id: Uuid,
+    to_snapshot_id: Uuid,
+) -> Result<Vec<CHSnapshotDiffRow>> {
+    let rows = clickhouse
+        .query(
+            "SELECT
+                if(empty(from_dp.id), to_dp.id, from_dp.id) AS id,
+                multiIf(empty(from_dp.id), 'added', empty(to_dp.id), 'removed', 'changed') AS change
+            FROM (
+                SELECT id, data, target, metadata
+                FROM dataset_snapshot_datapoints
+                WHERE project_id = ? AND dataset_id = ? AND snapshot_id = ?
+            ) AS from_dp
+            FULL OUTER JOIN (
+                SELECT id, data, target, metadata
+                FROM dataset_snapshot_datapoints
+                WHERE project_id = ? AND dataset_id = ? AND snapshot_id = ?
+            ) AS to_dp ON from_dp.id = to_dp.id
+            WHERE empty(from_dp.id)
+                OR empty(to_dp.id)
+                OR from_dp.data != to_dp.data
+                OR from_dp.target != to_dp.target
+                OR from_dp.metadata != to_dp.metadata
+            ORDER BY toUInt128(id) ASC
+            SETTINGS join_use_nulls = 0",
+        )
+        .bind(project_id)
+        .bind(dataset_id)
+        .bind(from_snapshot_id)
+        .bind(project_id)
+        .bind(dataset_id)
+        .bind(to_snapshot_id)
+        .fetch_all::<CHSnapshotDiffRow>()
+        .await?;
+
+    Ok(rows)
+}
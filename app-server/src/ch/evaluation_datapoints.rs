@@ -130,3 +130,85 @@ pub async fn ch_insert_evaluation_datapoints(
         )),
     }
 }
+
+/// The parts of a datapoint that server-side evaluators read.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHEvaluationDatapointInputs {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub id: Uuid,
+    pub data: String,
+    pub target: String,
+    pub metadata: String,
+    pub executor_output: String,
+    pub scores: String,
+}
+
+pub async fn get_evaluation_datapoint_inputs(
+    clickhouse: &clickhouse::Client,
+    project_id: &Uuid,
+    evaluation_id: &Uuid,
+    datapoint_ids: &[Uuid],
+) -> Result<Vec<CHEvaluationDatapointInputs>> {
+    if datapoint_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let placeholders: Vec<String> = datapoint_ids.iter().map(|_| "?".to_string()).collect();
+    // PREWHERE on id hits the bloom filter skip index before FINAL is applied.
+    let query_str = format!(
+        "SELECT id, data, target, metadata, executor_output, scores
+         FROM evaluation_datapoints FINAL
+         PREWHERE id IN ({})
+         WHERE project_id = ? AND evaluation_id = ?",
+        placeholders.join(",")
+    );
+
+    let mut query = clickhouse.query(&query_str);
+    for datapoint_id in datapoint_ids {
+        query = query.bind(datapoint_id);
+    }
+    query = query.bind(project_id).bind(evaluation_id);
+
+    let rows = query.fetch_all::<CHEvaluationDatapointInputs>().await?;
+
+    Ok(rows)
+}
+
+/// Scores of a datapoint that is linked to a dataset datapoint.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHLinkedDatapointScores {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub evaluation_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub dataset_datapoint_id: Uuid,
+    pub scores: String,
+}
+
+pub async fn get_linked_datapoint_scores(
+    clickhouse: &clickhouse::Client,
+    project_id: &Uuid,
+    evaluation_ids: &[Uuid],
+) -> Result<Vec<CHLinkedDatapointScores>> {
+    if evaluation_ids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let placeholders: Vec<String> = evaluation_ids.iter().map(|_| "?".to_string()).collect();
+    let query_str = format!(
+        "SELECT evaluation_id, dataset_datapoint_id, scores
+         FROM evaluation_datapoints FINAL
+         WHERE project_id = ?
+           AND evaluation_id IN ({})
+           AND notEmpty(dataset_datapoint_id)",
+        placeholders.join(",")
+    );
+
+    let mut query = clickhouse.query(&query_str).bind(project_id);
+    for evaluation_id in evaluation_ids {
+        query = query.bind(evaluation_id);
+    }
+
+    let rows = query.fetch_all::<CHLinkedDatapointScores>().await?;
+
+    Ok(rows)
+}
@@ -37,3 +37,50 @@ pub async fn insert_labeling_queue_items(
     insert.end().await?;
     Ok(())
 }
+
+/// The content of a labeling queue item, as needed to turn it into a datapoint.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHLabelingQueueItemContent {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub id: Uuid,
+    pub payload: String,
+    pub edit: String,
+    pub metadata: String,
+}
+
+/// Items of a queue in queue order. Only approved items unless
+/// `include_unlabelled`; only `ids` when given.
+pub async fn get_labeling_queue_item_contents(
+    clickhouse: &clickhouse::Client,
+    project_id: Uuid,
+    queue_id: Uuid,
+    ids: Option<&[Uuid]>,
+    include_unlabelled: bool,
+    limit: u64,
+) -> Result<Vec<CHLabelingQueueItemContent>> {
+    let mut query_str = String::from(
+        "SELECT id, payload, edit, metadata
+        FROM labeling_queue_items FINAL
+        WHERE project_id = ? AND queue_id = ?",
+    );
+    if !include_unlabelled {
+        query_str.push_str(" AND status = 1");
+    }
+    if let Some(ids) = ids {
+        let placeholders = vec!["?"; ids.len()].join(",");
+        query_str.push_str(&format!(" AND id IN ({placeholders})"));
+    }
+    query_str.push_str(" ORDER BY created_at ASC, id ASC LIMIT ?");
+
+    let mut query = clickhouse.query(&query_str).bind(project_id).bind(queue_id);
+    for id in ids.unwrap_or_default() {
+        query = query.bind(id);
+    }
+
+    let items = query
+        .bind(limit)
+        .fetch_all::<CHLabelingQueueItemContent>()
+        .await?;
+
+    Ok(items)
+}
@@ -91,3 +91,43 @@ pub async fn insert_logs_batch(clickhouse: clickhouse::Client, logs: &[CHLog]) -
         }
     }
 }
+
+/// A log record as attached to its span in trace context.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHTraceLog {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub span_id: Uuid,
+    /// Time in nanoseconds
+    pub time: i64,
+    pub severity_number: u8,
+    pub severity_text: String,
+    pub body: String,
+    pub event_name: String,
+}
+
+/// The latest `limit` logs emitted within a trace, returned oldest first.
+/// `span_id` is nil for logs that carry a trace id but no span id.
+pub async fn get_trace_logs(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    trace_id: Uuid,
+    limit: u32,
+) -> Result<Vec<CHTraceLog>> {
+    let mut logs = clickhouse
+        .query(
+            "SELECT span_id, time, severity_number, severity_text, body, event_name
+            FROM logs
+            WHERE project_id = {project_id:UUID}
+              AND trace_id = {trace_id:UUID}
+            ORDER BY time DESC
+            LIMIT {limit:UInt32}",
+        )
+        .param("project_id", project_id)
+        .param("trace_id", trace_id)
+        .param("limit", limit)
+        .fetch_all::<CHTraceLog>()
+        .await?;
+    logs.reverse();
+
+    Ok(logs)
+}
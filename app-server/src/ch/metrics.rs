@@ -0,0 +1,119 @@
+use anyhow::Result;
+use clickhouse::Row;
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{metrics::producer::MetricPoint, utils::sanitize_string};
+
+use super::utils::chrono_to_nanoseconds;
+
+/// ClickHouse representation of a single metric data point.
+#[derive(Row, Serialize, Deserialize, Debug, Clone)]
+pub struct CHMetric {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub project_id: Uuid,
+    /// Time in nanoseconds
+    pub time: i64,
+    /// Start time in nanoseconds
+    pub start_time: i64,
+    pub name: String,
+    pub description: String,
+    pub unit: String,
+    /// 0 = gauge, 1 = sum, 2 = histogram, 3 = exponential histogram, 4 = summary
+    pub metric_type: u8,
+    /// OTel AggregationTemporality: 0 = unspecified, 1 = delta, 2 = cumulative
+    pub aggregation_temporality: u8,
+    pub is_monotonic: bool,
+    pub value: f64,
+    pub count: u64,
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub bucket_counts: Vec<u64>,
+    pub explicit_bounds: Vec<f64>,
+    pub scale: i32,
+    pub zero_count: u64,
+    pub positive_offset: i32,
+    pub positive_bucket_counts: Vec<u64>,
+    pub negative_offset: i32,
+    pub negative_bucket_counts: Vec<u64>,
+    pub quantiles: Vec<f64>,
+    pub quantile_values: Vec<f64>,
+    pub attributes: String,
+    pub resource_attributes: String,
+    pub scope_name: String,
+    pub scope_version: String,
+    pub flags: u32,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub trace_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub span_id: Uuid,
+    pub size_bytes: u64,
+}
+
+impl CHMetric {
+    pub fn from_metric_point(point: &MetricPoint) -> Self {
+        CHMetric {
+            id: point.id,
+            project_id: point.project_id,
+            time: chrono_to_nanoseconds(point.time),
+            start_time: chrono_to_nanoseconds(point.start_time),
+            name: point.name.clone(),
+            description: point.description.clone(),
+            unit: point.unit.clone(),
+            metric_type: point.metric_type.as_u8(),
+            aggregation_temporality: point.aggregation_temporality as u8,
+            is_monotonic: point.is_monotonic,
+            value: point.value,
+            count: point.count,
+            sum: point.sum,
+            min: point.min,
+            max: point.max,
+            bucket_counts: point.bucket_counts.clone(),
+            explicit_bounds: point.explicit_bounds.clone(),
+            scale: point.scale,
+            zero_count: point.zero_count,
+            positive_offset: point.positive_offset,
+            positive_bucket_counts: point.positive_bucket_counts.clone(),
+            negative_offset: point.negative_offset,
+            negative_bucket_counts: point.negative_bucket_counts.clone(),
+            quantiles: point.quantiles.clone(),
+            quantile_values: point.quantile_values.clone(),
+            attributes: sanitize_string(&point.attributes.to_string()),
+            resource_attributes: sanitize_string(&point.resource_attributes.to_string()),
+            scope_name: point.scope_name.clone(),
+            scope_version: point.scope_version.clone(),
+            flags: point.flags,
+            trace_id: point.trace_id.unwrap_or(Uuid::nil()),
+            span_id: point.span_id.unwrap_or(Uuid::nil()),
+            size_bytes: point.estimate_size_bytes() as u64,
+        }
+    }
+}
+
+#[instrument(skip(clickhouse, metrics))]
+pub async fn insert_metrics_batch(
+    clickhouse: clickhouse::Client,
+    metrics: &[CHMetric],
+) -> Result<()> {
+    if metrics.is_empty() {
+        return Ok(());
+    }
+
+    let mut ch_insert = clickhouse
+        .insert::<CHMetric>("metrics")
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to insert metrics batch into Clickhouse: {:?}", e))?;
+
+    for metric in metrics {
+        ch_insert.write(metric).await?;
+    }
+
+    ch_insert
+        .end()
+        .await
+        .map_err(|e| anyhow::anyhow!("Clickhouse batch metric insertion failed: {:?}", e))
+}
@@ -7,11 +7,13 @@ pub mod evaluation_datapoints;
 pub mod labeling_queue_items;
 pub mod limits;
 pub mod logs;
+pub mod metrics;
 pub mod notification_deliveries;
 pub mod notifications;
 pub mod service;
 pub mod signal_events;
 pub mod signal_run_messages;
+pub mod span_metrics;
 pub mod spans;
 pub mod traces;
 pub mod utils;
@@ -60,6 +62,7 @@ pub enum Table {
     NotificationDeliveries,
     Notifications,
     DedupedContent,
+    SpanMetrics,
 }
 
 impl Table {
@@ -70,6 +73,7 @@ impl Table {
             Table::NotificationDeliveries => "notification_deliveries",
             Table::Notifications => "notifications",
             Table::DedupedContent => "deduped_content",
+            Table::SpanMetrics => "span_metrics_ingest",
         }
     }
 }
@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+
+use clickhouse::Row;
+use clickhouse::insert::Insert;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{
+    ClickhouseInsertable, DataPlaneBatch, SPANS_CH_ASYNC_INSERT_BUSY_TIMEOUT_MAX_MS, Table,
+};
+use crate::db::spans::Span;
+use crate::traces::spans::SpanUsage;
+
+/// Per-minute span rollup row, pre-aggregated per ingest batch.
+///
+/// Inserted into the Null-engine `span_metrics_ingest` table; the
+/// `span_metrics_mv` materialized view folds rows into aggregate states in the
+/// `span_metrics` AggregatingMergeTree, so rows for the same minute and
+/// dimensions coming from different batches merge there.
+///
+/// Field order matches the `span_metrics_ingest` column order.
+#[derive(Row, Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CHSpanMetricsRollup {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub project_id: Uuid,
+    /// Start of the minute bucket, in seconds since the unix epoch
+    pub minute: u32,
+    pub model: String,
+    pub provider: String,
+    pub path: String,
+    pub span_type: u8,
+    /// Normalized to 'success' or 'error', same as `spans_v0`
+    pub status: String,
+    /// Sorted and deduplicated so the same tag set always lands in one group
+    pub tags: Vec<String>,
+    pub span_count: u64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub total_tokens: i64,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub total_cost: f64,
+    /// Duration of every span in the group, in seconds. Feeds the quantile
+    /// state in `span_metrics`; the Null ingest table never stores it.
+    pub durations: Vec<f64>,
+}
+
+#[derive(Hash, PartialEq, Eq)]
+struct RollupKey {
+    project_id: Uuid,
+    minute: u32,
+    model: String,
+    provider: String,
+    path: String,
+    span_type: u8,
+    status: String,
+    tags: Vec<String>,
+}
+
+impl CHSpanMetricsRollup {
+    /// Group a batch of Spans and their SpanUsage into per-minute rollups.
+    /// Spans are bucketed by the minute they started in.
+    pub fn from_spans(spans: &[&Span], span_usage_vec: &[&SpanUsage]) -> Vec<Self> {
+        let mut rollups: HashMap<RollupKey, CHSpanMetricsRollup> = HashMap::new();
+
+        for (span, usage) in spans.iter().zip(span_usage_vec.iter()) {
+            let mut tags = span.attributes.tags();
+            tags.sort();
+            tags.dedup();
+
+            let key = RollupKey {
+                project_id: span.project_id,
+                minute: minute_bucket(span.start_time.timestamp()),
+                model: usage
+                    .response_model
+                    .clone()
+                    .or(usage.request_model.clone())
+                    .unwrap_or_default(),
+                provider: usage.provider_name.clone().unwrap_or_default(),
+                path: span.attributes.flat_path().unwrap_or_default(),
+                span_type: span.span_type.clone().into(),
+                status: if span.status.as_deref() == Some("error") {
+                    String::from("error")
+                } else {
+                    String::from("success")
+                },
+                tags,
+            };
+
+            let duration = (span.end_time - span.start_time)
+                .num_nanoseconds()
+                .map(|ns| ns.max(0) as f64 / 1_000_000_000.0)
+                .unwrap_or(0.0);
+
+            let entry = rollups
+                .entry(key)
+                .or_insert_with_key(|key| CHSpanMetricsRollup {
+                    project_id: key.project_id,
+                    minute: key.minute,
+                    model: key.model.clone(),
+                    provider: key.provider.clone(),
+                    path: key.path.clone(),
+                    span_type: key.span_type,
+                    status: key.status.clone(),
+                    tags: key.tags.clone(),
+                    span_count: 0,
+                    input_tokens: 0,
+                    output_tokens: 0,
+                    total_tokens: 0,
+                    input_cost: 0.0,
+                    output_cost: 0.0,
+                    total_cost: 0.0,
+                    durations: Vec::new(),
+                });
+
+            entry.span_count += 1;
+            entry.input_tokens += usage.input_tokens;
+            entry.output_tokens += usage.output_tokens;
+            entry.total_tokens += usage.total_tokens;
+            entry.input_cost += usage.input_cost;
+            entry.output_cost += usage.output_cost;
+            entry.total_cost += usage.total_cost;
+            entry.durations.push(duration);
+        }
+
+        rollups.into_values().collect()
+    }
+}
+
+fn minute_bucket(unix_seconds: i64) -> u32 {
+    (unix_seconds - unix_seconds.rem_euclid(60)).clamp(0, u32::MAX as i64) as u32
+}
+
+impl ClickhouseInsertable for CHSpanMetricsRollup {
+    const TABLE: Table = Table::SpanMetrics;
+
+    // Rides the same ingest path as `spans`, so it gets the same async-insert cap.
+    fn configure_insert(insert: Insert<Self>) -> Insert<Self> {
+        insert.with_setting(
+            "async_insert_busy_timeout_max_ms",
+            SPANS_CH_ASYNC_INSERT_BUSY_TIMEOUT_MAX_MS.as_str(),
+        )
+    }
+
+    fn to_data_plane_batch(items: Vec<Self>) -> DataPlaneBatch {
+        DataPlaneBatch::SpanMetrics(items)
+    }
+}
+
+/// Totals over a time window of `span_metrics`, as read by metric-threshold alerts.
+#[derive(Row, Deserialize, Debug, Clone, Default)]
+pub struct SpanMetricsWindow {
+    pub span_count: u64,
+    pub error_count: u64,
+    pub total_tokens: i64,
+    pub total_cost: f64,
+    /// Sum of span durations, in seconds
+    pub duration_sum: f64,
+    /// p50, p90, p95 and p99 span duration, in seconds. NaN when there are no spans.
+    pub duration_quantiles: Vec<f64>,
+}
+
+/// Dimension filters for [`get_span_metrics_window`]. `None` matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct SpanMetricsFilter<'a> {
+    pub model: Option<&'a str>,
+    pub provider: Option<&'a str>,
+    pub path: Option<&'a str>,
+    pub span_type: Option<u8>,
+}
+
+/// Aggregate the per-minute rollups of a project over `[start_ts, end_ts)`,
+/// both in seconds since the unix epoch and aligned to minutes.
+pub async fn get_span_metrics_window(
+    clickhouse: &clickhouse::Client,
+    project_id: &Uuid,
+    filter: &SpanMetricsFilter<'_>,
+    start_ts: i64,
+    end_ts: i64,
+) -> anyhow::Result<SpanMetricsWindow> {
+    let mut conditions = String::new();
+    for (column, value) in [
+        ("model", filter.model),
+        ("provider", filter.provider),
+        ("path", filter.path),
+    ] {
+        if value.is_some() {
+            conditions.push_str(&format!(" AND {} = ?", column));
+        }
+    }
+    if filter.span_type.is_some() {
+        conditions.push_str(" AND span_type = ?");
+    }
+
+    let query_str = format!(
+        "SELECT sum(span_count) AS span_count,
+            sumIf(span_count, status = 'error') AS error_count,
+            sum(total_tokens) AS total_tokens,
+            sum(total_cost) AS total_cost,
+            sum(duration_sum) AS duration_sum,
+            quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)(duration_quantiles) AS duration_quantiles
+         FROM span_metrics
+         WHERE project_id = ?
+           AND minute >= toDateTime(?, 'UTC')
+           AND minute < toDateTime(?, 'UTC'){}",
+        conditions
+    );
+
+    let mut query = clickhouse
+        .query(&query_str)
+        .bind(project_id)
+        .bind(start_ts)
+        .bind(end_ts);
+    for value in [filter.model, filter.provider, filter.path]
+        .into_iter()
+        .flatten()
+    {
+        query = query.bind(value);
+    }
+    if let Some(span_type) = filter.span_type {
+        query = query.bind(span_type);
+    }
+
+    let row = query.fetch_optional::<SpanMetricsWindow>().await?;
+
+    Ok(row.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    use super::*;
+    use crate::db::spans::SpanType;
+    use crate::traces::spans::SpanAttributes;
+
+    fn span(project_id: Uuid, start_secs: i64, duration_ms: i64, status: Option<&str>) -> Span {
+        let start_time = Utc.timestamp_opt(start_secs, 0).unwrap();
+        Span {
+            span_id: Uuid::new_v4(),
+            project_id,
+            trace_id: Uuid::new_v4(),
+            name: "openai.chat".to_string(),
+            attributes: SpanAttributes::new(HashMap::from([
+                (
+                    "lmnr.span.path".to_string(),
+                    json!(["agent", "openai.chat"]),
+                ),
+                (
+                    "lmnr.association.properties.tags".to_string(),
+                    json!(["b", "a", "b"]),
+                ),
+            ])),
+            span_type: SpanType::LLM,
+            start_time,
+            end_time: start_time + chrono::Duration::milliseconds(duration_ms),
+            status: status.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    fn usage(total_tokens: i64, total_cost: f64) -> SpanUsage {
+        SpanUsage {
+            input_tokens: total_tokens / 2,
+            output_tokens: total_tokens - total_tokens / 2,
+            total_tokens,
+            cache_read_input_tokens: 0,
+            cache_creation_input_tokens: 0,
+            reasoning_tokens: 0,
+            input_cost: total_cost / 2.0,
+            output_cost: total_cost / 2.0,
+            total_cost,
+            request_model: Some("gpt-4o".to_string()),
+            response_model: Some("gpt-4o-2024-08-06".to_string()),
+            provider_name: Some("openai".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_rollup_groups_by_minute_and_status() {
+        let project_id = Uuid::new_v4();
+        let spans = [
+            span(project_id, 1_700_000_040, 500, None),
+            span(project_id, 1_700_000_055, 1500, Some("success")),
+            span(project_id, 1_700_000_110, 200, None),
+            span(project_id, 1_700_000_041, 3000, Some("error")),
+        ];
+        let usages = [
+            usage(10, 0.1),
+            usage(20, 0.2),
+            usage(30, 0.3),
+            usage(40, 0.4),
+        ];
+        let span_refs: Vec<&Span> = spans.iter().collect();
+        let usage_refs: Vec<&SpanUsage> = usages.iter().collect();
+
+        let mut rollups = CHSpanMetricsRollup::from_spans(&span_refs, &usage_refs);
+        rollups.sort_by(|a, b| (a.minute, &a.status).cmp(&(b.minute, &b.status)));
+
+        assert_eq!(rollups.len(), 3);
+
+        let first = &rollups[1];
+        assert_eq!(first.minute, 1_700_000_040);
+        assert_eq!(first.status, "success");
+        assert_eq!(first.span_count, 2);
+        assert_eq!(first.total_tokens, 30);
+        assert!((first.total_cost - 0.3).abs() < 1e-9);
+        assert_eq!(first.durations, vec![0.5, 1.5]);
+        assert_eq!(first.model, "gpt-4o-2024-08-06");
+        assert_eq!(first.provider, "openai");
+        assert_eq!(first.path, "agent.openai.chat");
+        assert_eq!(first.tags, vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(first.span_type, 1);
+
+        let error = &rollups[0];
+        assert_eq!(error.status, "error");
+        assert_eq!(error.span_count, 1);
+        assert_eq!(error.durations, vec![3.0]);
+
+        let next_minute = &rollups[2];
+        assert_eq!(next_minute.minute, first.minute + 60);
+        assert_eq!(next_minute.span_count, 1);
+    }
+
+    #[test]
+    fn test_minute_bucket() {
+        assert_eq!(minute_bucket(0), 0);
+        assert_eq!(minute_bucket(59), 0);
+        assert_eq!(minute_bucket(60), 60);
+        assert_eq!(minute_bucket(1_700_000_099), 1_700_000_040);
+        assert_eq!(minute_bucket(-5), 0);
+    }
+}
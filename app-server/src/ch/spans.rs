@@ -143,8 +143,18 @@ pub struct CHSpan {
     /// view as a virtual `tool_definitions` column via `deduped_content_dict`.
     #[serde(default)]
     pub tool_definitions_hash: [u8; 32],
+    /// Span links stored as
+    /// Array(Tuple(trace_id UUID, span_id UUID, attributes String))
+    #[serde(default)]
+    pub links: Vec<(CHUuid, CHUuid, String)>,
 }
 
+/// UUID that can be nested in tuples and arrays, where field-level
+/// `#[serde(with = "clickhouse::serde::uuid")]` can't reach.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct CHUuid(#[serde(with = "clickhouse::serde::uuid")] pub Uuid);
+
 impl CHSpan {
     pub fn from_db_span(span: &Span, usage: &SpanUsage, project_id: Uuid) -> Self {
         let session_id = span.attributes.session_id();
@@ -223,6 +233,17 @@ impl CHSpan {
             output_message_hashes: Vec::new(),
             output_new_message_indices: Vec::new(),
             tool_definitions_hash: [0u8; 32],
+            links: span
+                .links
+                .iter()
+                .map(|link| {
+                    (
+                        CHUuid(link.trace_id),
+                        CHUuid(link.span_id),
+                        link.attributes.to_string(),
+                    )
+                })
+                .collect(),
         }
     }
 }
@@ -286,6 +307,185 @@ pub async fn is_span_in_project(
     Ok(result > 0)
 }
 
+/// An unpriced LLM span, with what is needed to price it again.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct UnpricedSpanRow {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub span_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub trace_id: Uuid,
+    pub name: String,
+    /// Start time in nanoseconds
+    pub span_start_time: i64,
+    pub attributes: String,
+}
+
+/// Fetch the next page of zero-cost LLM spans in `[start_time, end_time)`,
+/// ordered by `(start_time, span_id)` and strictly after the cursor.
+/// An empty `model` matches every model.
+pub async fn get_unpriced_llm_spans_page(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    start_time: i64,
+    end_time: i64,
+    model: &str,
+    cursor: (i64, Uuid),
+    limit: u32,
+) -> Result<Vec<UnpricedSpanRow>> {
+    let (cursor_start_time, cursor_span_id) = cursor;
+    let llm_span_type: u8 = SpanType::LLM.into();
+    let rows = clickhouse
+        .query(
+            "SELECT
+                span_id,
+                trace_id,
+                name,
+                toUnixTimestamp64Nano(start_time) AS span_start_time,
+                attributes
+            FROM spans
+            WHERE project_id = {project_id:UUID}
+              AND span_type = {span_type:UInt8}
+              AND total_cost = 0
+              AND start_time >= fromUnixTimestamp64Nano({start_time:Int64}, 'UTC')
+              AND start_time < fromUnixTimestamp64Nano({end_time:Int64}, 'UTC')
+              AND (start_time, span_id) > (
+                fromUnixTimestamp64Nano({cursor_start_time:Int64}, 'UTC'),
+                {cursor_span_id:UUID}
+              )
+              AND ({model:String} = '' OR model = {model:String})
+            ORDER BY start_time ASC, span_id ASC
+            LIMIT {limit:UInt32}",
+        )
+        .param("project_id", project_id)
+        .param("span_type", llm_span_type)
+        .param("start_time", start_time)
+        .param("end_time", end_time)
+        .param("cursor_start_time", cursor_start_time)
+        .param("cursor_span_id", cursor_span_id)
+        .param("model", model)
+        .param("limit", limit)
+        .fetch_all::<UnpricedSpanRow>()
+        .await?;
+
+    Ok(rows)
+}
+
+/// Corrected costs for one span.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpanCostUpdate {
+    pub span_id: Uuid,
+    /// Start time in nanoseconds, used to narrow the mutation to the parts
+    /// holding the span.
+    pub start_time: i64,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub total_cost: f64,
+}
+
+/// Overwrite the costs of the given spans with a single synchronous mutation.
+///
+/// `spans` is a plain MergeTree, so there is no replacing path for corrected
+/// rows: each call is an `ALTER TABLE ... UPDATE` that rewrites the cost
+/// columns of every data part holding one of the spans, and ClickHouse runs
+/// mutations one at a time per table. Callers should batch as many spans as
+/// they can into one call. The `start_time` bounds let ClickHouse skip parts
+/// outside the batch's time range through the primary key.
+pub async fn update_span_costs(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    updates: &[SpanCostUpdate],
+) -> Result<()> {
+    if updates.is_empty() {
+        return Ok(());
+    }
+
+    let span_ids: Vec<Uuid> = updates.iter().map(|u| u.span_id).collect();
+    let input_costs: Vec<f64> = updates.iter().map(|u| u.input_cost).collect();
+    let output_costs: Vec<f64> = updates.iter().map(|u| u.output_cost).collect();
+    let total_costs: Vec<f64> = updates.iter().map(|u| u.total_cost).collect();
+    let min_start_time = updates.iter().map(|u| u.start_time).min().unwrap_or(0);
+    let max_start_time = updates.iter().map(|u| u.start_time).max().unwrap_or(0);
+
+    clickhouse
+        .query(
+            "ALTER TABLE spans UPDATE
+                input_cost = transform(span_id, {span_ids:Array(UUID)}, {input_costs:Array(Float64)}, input_cost),
+                output_cost = transform(span_id, {span_ids:Array(UUID)}, {output_costs:Array(Float64)}, output_cost),
+                total_cost = transform(span_id, {span_ids:Array(UUID)}, {total_costs:Array(Float64)}, total_cost)
+            WHERE project_id = {project_id:UUID}
+              AND start_time >= fromUnixTimestamp64Nano({min_start_time:Int64}, 'UTC')
+              AND start_time <= fromUnixTimestamp64Nano({max_start_time:Int64}, 'UTC')
+              AND span_id IN {span_ids:Array(UUID)}",
+        )
+        .param("project_id", project_id)
+        .param("min_start_time", min_start_time)
+        .param("max_start_time", max_start_time)
+        .param("span_ids", span_ids)
+        .param("input_costs", input_costs)
+        .param("output_costs", output_costs)
+        .param("total_costs", total_costs)
+        .with_setting("mutations_sync", "1")
+        .execute()
+        .await?;
+
+    Ok(())
+}
+
+/// Cost totals of a trace, summed over its spans.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct TraceCosts {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub trace_id: Uuid,
+    pub input_cost: f64,
+    pub output_cost: f64,
+    pub total_cost: f64,
+}
+
+/// Sum the costs of the given traces over all of their spans, taking the
+/// costs in `updates` in place of the stored ones for those spans.
+///
+/// The result does not depend on whether `update_span_costs` has already run
+/// for `updates`, so a trace total can be written before the span mutation
+/// and written again with the same value when a batch is retried.
+pub async fn get_trace_costs_with_updates(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    trace_ids: Vec<Uuid>,
+    updates: &[SpanCostUpdate],
+) -> Result<Vec<TraceCosts>> {
+    if trace_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let span_ids: Vec<Uuid> = updates.iter().map(|u| u.span_id).collect();
+    let input_costs: Vec<f64> = updates.iter().map(|u| u.input_cost).collect();
+    let output_costs: Vec<f64> = updates.iter().map(|u| u.output_cost).collect();
+    let total_costs: Vec<f64> = updates.iter().map(|u| u.total_cost).collect();
+
+    let rows = clickhouse
+        .query(
+            "SELECT
+                trace_id,
+                sum(transform(span_id, {span_ids:Array(UUID)}, {input_costs:Array(Float64)}, input_cost)) AS input_cost,
+                sum(transform(span_id, {span_ids:Array(UUID)}, {output_costs:Array(Float64)}, output_cost)) AS output_cost,
+                sum(transform(span_id, {span_ids:Array(UUID)}, {total_costs:Array(Float64)}, total_cost)) AS total_cost
+            FROM spans
+            WHERE project_id = {project_id:UUID}
+              AND trace_id IN {trace_ids:Array(UUID)}
+            GROUP BY trace_id",
+        )
+        .param("project_id", project_id)
+        .param("trace_ids", trace_ids)
+        .param("span_ids", span_ids)
+        .param("input_costs", input_costs)
+        .param("output_costs", output_costs)
+        .param("total_costs", total_costs)
+        .fetch_all::<TraceCosts>()
+        .await?;
+
+    Ok(rows)
+}
+
 /// One LLM/CACHED span of a replay trace, with the reconstructed input and the
 /// raw output-bearing attributes needed by the debugger warmup (LAM-1715).
 ///
@@ -354,3 +554,64 @@ pub async fn query_debug_cache_spans_page(
 
     Ok(rows)
 }
+
+/// One span link, flattened: the linking span and the span it points at.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHSpanLinkRow {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub trace_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub span_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub linked_trace_id: Uuid,
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub linked_span_id: Uuid,
+    pub attributes: String,
+    /// Start time of the linking span, in nanoseconds.
+    pub span_start_time: i64,
+}
+
+/// Links that touch any of `trace_ids`: those recorded on their spans
+/// (outgoing) and those recorded on other spans pointing into them
+/// (incoming), restricted to linking spans that started in
+/// `[start_time, end_time]` (nanoseconds). The time range prunes the scan to
+/// the matching partitions; `hasAny` on `link_trace_ids` then lets the bloom
+/// filter skip granules for the incoming side.
+pub async fn get_span_links_for_traces(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    trace_ids: Vec<Uuid>,
+    start_time: i64,
+    end_time: i64,
+) -> Result<Vec<CHSpanLinkRow>> {
+    let rows = clickhouse
+        .query(
+            "SELECT
+                trace_id,
+                span_id,
+                link.trace_id AS linked_trace_id,
+                link.span_id AS linked_span_id,
+                link.attributes AS attributes,
+                toUnixTimestamp64Nano(start_time) AS span_start_time
+            FROM spans
+            ARRAY JOIN links AS link
+            WHERE project_id = {project_id:UUID}
+              AND start_time >= fromUnixTimestamp64Nano({start_time:Int64}, 'UTC')
+              AND start_time <= fromUnixTimestamp64Nano({end_time:Int64}, 'UTC')
+              AND (
+                trace_id IN {trace_ids:Array(UUID)}
+                OR (
+                    hasAny(link_trace_ids, {trace_ids:Array(UUID)})
+                    AND link.trace_id IN {trace_ids:Array(UUID)}
+                )
+              )",
+        )
+        .param("project_id", project_id)
+        .param("trace_ids", trace_ids)
+        .param("start_time", start_time)
+        .param("end_time", end_time)
+        .fetch_all::<CHSpanLinkRow>()
+        .await?;
+
+    Ok(rows)
+}
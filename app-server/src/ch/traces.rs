@@ -152,16 +152,19 @@ pub struct TraceAggregation {
     pub span_names: HashSet<String>,
     pub root_span_input: Option<String>,
     pub root_span_output: Option<String>,
+    /// Tail-sampling decision for the trace, set by the processor after
+    /// aggregation. Only the first recorded decision is kept on the row.
+    pub sampling_decision: Option<serde_json::Value>,
 }
 
 impl TraceAggregation {
-    /// Aggregate statistics from a batch of Spans and SpanUsage grouped by trace_id
-    pub fn from_spans(spans: &[Span], span_usage_vec: &[SpanUsage]) -> Vec<Self> {
+    /// Aggregate statistics from Spans and their SpanUsage grouped by trace_id
+    pub fn from_spans<'a>(spans: impl IntoIterator<Item = (&'a Span, &'a SpanUsage)>) -> Vec<Self> {
         use std::collections::HashMap;
 
         let mut trace_aggregations: HashMap<Uuid, TraceAggregation> = HashMap::new();
 
-        for (span, span_usage) in spans.iter().zip(span_usage_vec.iter()) {
+        for (span, span_usage) in spans {
             let entry =
                 trace_aggregations
                     .entry(span.trace_id)
@@ -193,6 +196,7 @@ impl TraceAggregation {
                         span_names: HashSet::new(),
                         root_span_input: None,
                         root_span_output: None,
+                        sampling_decision: None,
                     });
 
             // Aggregate min start_time
@@ -292,3 +296,38 @@ impl TraceAggregation {
         trace_aggregations.into_values().collect()
     }
 }
+
+/// Summary of a trace for the cross-trace link graph.
+#[derive(Row, Deserialize, Debug, Clone)]
+pub struct CHTraceSummary {
+    #[serde(with = "clickhouse::serde::uuid")]
+    pub id: Uuid,
+    pub top_span_name: String,
+    /// Start time in nanoseconds
+    pub start_time: i64,
+    /// End time in nanoseconds
+    pub end_time: i64,
+    pub status: String,
+    pub total_cost: f64,
+    pub num_spans: u64,
+}
+
+pub async fn get_trace_summaries(
+    clickhouse: clickhouse::Client,
+    project_id: Uuid,
+    trace_ids: Vec<Uuid>,
+) -> anyhow::Result<Vec<CHTraceSummary>> {
+    let rows = clickhouse
+        .query(
+            "SELECT id, top_span_name, start_time, end_time, status, total_cost, num_spans
+            FROM traces_replacing FINAL
+            WHERE project_id = {project_id:UUID}
+              AND id IN {trace_ids:Array(UUID)}",
+        )
+        .param("project_id", project_id)
+        .param("trace_ids", trace_ids)
+        .fetch_all::<CHTraceSummary>()
+        .await?;
+
+    Ok(rows)
+}
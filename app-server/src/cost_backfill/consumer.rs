@@ -0,0 +1,265 @@
+//! Consumer for the cost backfill queue.
+//!
+//! Each message drives one batch of a job. After a batch is committed the
+//! handler enqueues the next one, so a job is a chain of messages that ends
+//! when the spans in range run out. Any error marks the job `failed` with the
+//! cursor left at the last committed batch; resuming re-enqueues the chain.
+//!
+//! Within a batch, trace totals are recomputed from their spans and written
+//! before the span costs, and the cursor is moved last. Every step can be
+//! replayed, so a batch that fails part way is simply processed again.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::DateTime;
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{CostBackfillMessage, affected_trace_ids, push_to_cost_backfill_queue};
+use crate::{
+    cache::{Cache, CacheTrait, keys::COST_BACKFILL_LOCK_CACHE_KEY},
+    ch::{
+        ClickhouseTrait,
+        cloud::CloudClickhouse,
+        spans::{
+            SpanCostUpdate, get_trace_costs_with_updates, get_unpriced_llm_spans_page,
+            update_span_costs,
+        },
+        traces::CHTrace,
+        utils::chrono_to_nanoseconds,
+    },
+    db::{
+        DB,
+        cost_backfill_jobs::{
+            COST_BACKFILL_STATUS_COMPLETED, COST_BACKFILL_STATUS_FAILED,
+            COST_BACKFILL_STATUS_RUNNING, CostBackfillJob, advance_cost_backfill_job,
+            get_cost_backfill_job, set_cost_backfill_job_status,
+        },
+        trace::set_trace_costs_batch,
+    },
+    mq::MessageQueue,
+    traces::{spans::SpanAttributes, utils::get_llm_usage_for_span},
+    worker::{HandlerError, MessageHandler},
+};
+
+/// Spans read from ClickHouse per query. Rows carry the full attributes blob,
+/// so this bounds the memory held while pricing.
+const READ_PAGE_SIZE: u32 = 1_000;
+
+/// Spans scanned per message. All corrections of a batch go into a single
+/// `ALTER TABLE spans UPDATE`, which rewrites whole data parts, so batches
+/// are kept large to keep the number of mutations low.
+const BATCH_SIZE: usize = 20_000;
+
+/// TTL on the per-job lock. Must exceed the time to price a batch and wait
+/// for the synchronous span mutation.
+const COST_BACKFILL_LOCK_TTL_SECONDS: u64 = 60 * 15;
+
+/// Pause before requeueing a message whose job is locked. The worker requeues
+/// immediately, so without it the message would spin until the batch ends.
+const LOCK_CONTENTION_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+pub struct CostBackfillHandler {
+    pub db: Arc<DB>,
+    pub cache: Arc<Cache>,
+    pub clickhouse: clickhouse::Client,
+    pub queue: Arc<MessageQueue>,
+}
+
+#[async_trait]
+impl MessageHandler for CostBackfillHandler {
+    type Message = CostBackfillMessage;
+
+    async fn handle(&self, message: Self::Message) -> Result<(), HandlerError> {
+        // Only one batch of a job runs at a time. A message that finds the
+        // lock held is requeued rather than dropped, so a redelivery during a
+        // long batch cannot end the chain.
+        let lock_key = format!("{COST_BACKFILL_LOCK_CACHE_KEY}:{}", message.job_id);
+        let acquired = self
+            .cache
+            .try_acquire_lock(&lock_key, COST_BACKFILL_LOCK_TTL_SECONDS)
+            .await
+            .map_err(|e| HandlerError::transient(anyhow::anyhow!("{e:?}")))?;
+        if !acquired {
+            tokio::time::sleep(LOCK_CONTENTION_RETRY_DELAY).await;
+            return Err(HandlerError::transient(anyhow::anyhow!(
+                "Cost backfill job {} is already being processed",
+                message.job_id
+            )));
+        }
+
+        let result = self.process_batch(&message).await;
+
+        // Release before enqueueing the next batch, otherwise its message can
+        // be picked up while the lock is still held.
+        if let Err(e) = self.cache.release_lock(&lock_key).await {
+            log::warn!("Failed to release cost backfill lock {lock_key}: {e:?}");
+        }
+
+        match result {
+            Ok(true) => {
+                if let Err(e) = push_to_cost_backfill_queue(&message, self.queue.clone()).await {
+                    self.fail_job(message.job_id, &e).await;
+                }
+            }
+            Ok(false) => {}
+            Err(e) => self.fail_job(message.job_id, &e).await,
+        }
+
+        Ok(())
+    }
+}
+
+impl CostBackfillHandler {
+    /// Process the batch after the job's cursor. Returns whether there may be
+    /// more batches.
+    async fn process_batch(&self, message: &CostBackfillMessage) -> Result<bool> {
+        let Some(job) =
+            get_cost_backfill_job(&self.db.pool, message.project_id, message.job_id).await?
+        else {
+            return Ok(false);
+        };
+        if job.status != COST_BACKFILL_STATUS_RUNNING {
+            return Ok(false);
+        }
+
+        let start_time = chrono_to_nanoseconds(job.start_time);
+        let end_time = chrono_to_nanoseconds(job.end_time);
+        let mut cursor = (
+            job.cursor_start_time
+                .map(chrono_to_nanoseconds)
+                .unwrap_or(start_time),
+            job.cursor_span_id.unwrap_or(Uuid::nil()),
+        );
+
+        let mut processed = 0;
+        let mut exhausted = false;
+        let mut updates: Vec<(Uuid, SpanCostUpdate)> = Vec::new();
+        while processed < BATCH_SIZE {
+            let rows = get_unpriced_llm_spans_page(
+                self.clickhouse.clone(),
+                job.project_id,
+                start_time,
+                end_time,
+                job.model.as_deref().unwrap_or(""),
+                cursor,
+                READ_PAGE_SIZE,
+            )
+            .await?;
+            let Some(last) = rows.last() else {
+                exhausted = true;
+                break;
+            };
+            cursor = (last.span_start_time, last.span_id);
+            processed += rows.len();
+
+            for row in &rows {
+                let attributes: HashMap<String, Value> =
+                    serde_json::from_str(&row.attributes).unwrap_or_default();
+                let mut attributes = SpanAttributes::new(attributes);
+                let usage = get_llm_usage_for_span(
+                    &mut attributes,
+                    self.db.clone(),
+                    self.cache.clone(),
+                    &row.name,
+                    &job.project_id,
+                    DateTime::from_timestamp_nanos(row.span_start_time),
+                )
+                .await;
+
+                // Still no price for this model
+                if usage.total_cost <= 0.0 {
+                    continue;
+                }
+                updates.push((
+                    row.trace_id,
+                    SpanCostUpdate {
+                        span_id: row.span_id,
+                        start_time: row.span_start_time,
+                        input_cost: usage.input_cost,
+                        output_cost: usage.output_cost,
+                        total_cost: usage.total_cost,
+                    },
+                ));
+            }
+
+            if rows.len() < READ_PAGE_SIZE as usize {
+                exhausted = true;
+                break;
+            }
+        }
+
+        if processed == 0 {
+            self.complete_job(&job).await?;
+            return Ok(false);
+        }
+
+        let span_updates: Vec<SpanCostUpdate> =
+            updates.iter().map(|(_, update)| update.clone()).collect();
+
+        // Trace totals are recomputed from all of the trace's spans with the
+        // new costs substituted in, and overwritten rather than added to.
+        // They come out the same whether or not the span mutation below has
+        // already run, so a retried batch cannot double-count.
+        let trace_costs = get_trace_costs_with_updates(
+            self.clickhouse.clone(),
+            job.project_id,
+            affected_trace_ids(&updates),
+            &span_updates,
+        )
+        .await?;
+        let traces = set_trace_costs_batch(&self.db.pool, job.project_id, &trace_costs).await?;
+        let ch_traces: Vec<CHTrace> = traces.iter().map(CHTrace::from_db_trace).collect();
+        CloudClickhouse::new(self.clickhouse.clone())
+            .insert_batch(&ch_traces, None)
+            .await?;
+
+        update_span_costs(self.clickhouse.clone(), job.project_id, &span_updates).await?;
+
+        advance_cost_backfill_job(
+            &self.db.pool,
+            job.id,
+            DateTime::from_timestamp_nanos(cursor.0),
+            cursor.1,
+            processed as i64,
+            span_updates.len() as i64,
+        )
+        .await?;
+
+        if exhausted {
+            self.complete_job(&job).await?;
+            return Ok(false);
+        }
+
+        Ok(true)
+    }
+
+    async fn complete_job(&self, job: &CostBackfillJob) -> Result<()> {
+        set_cost_backfill_job_status(&self.db.pool, job.id, COST_BACKFILL_STATUS_COMPLETED, None)
+            .await?;
+        log::info!(
+            "[Cost Backfill] Job {} for project {} completed",
+            job.id,
+            job.project_id
+        );
+        Ok(())
+    }
+
+    async fn fail_job(&self, job_id: Uuid, error: &anyhow::Error) {
+        log::error!("[Cost Backfill] Job {job_id} failed: {error:?}");
+        if let Err(e) = set_cost_backfill_job_status(
+            &self.db.pool,
+            job_id,
+            COST_BACKFILL_STATUS_FAILED,
+            Some(&error.to_string()),
+        )
+        .await
+        {
+            log::error!("[Cost Backfill] Failed to mark job {job_id} as failed: {e:?}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Cost recomputation for historical LLM spans.
+//!
+//! Spans whose model had no known price when they were ingested are stored
+//! with zero cost. After the missing price is added (e.g. as a custom model
+//! cost), a backfill job walks the project's zero-cost LLM spans in a time
+//! range, one batch per queue message. Each span is priced again with the
+//! price version in effect at its start time. The totals of the affected
+//! traces are recomputed from all of their spans and overwritten, then the
+//! new span costs are written to ClickHouse in one mutation per batch.
+//!
+//! Progress and the page cursor are stored on the `cost_backfill_jobs` row,
+//! so a failed or stalled job can be resumed from the last committed batch.
+//! The `span_metrics` rollups are only fed at ingest time and are not
+//! corrected.
+
+pub mod consumer;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    ch::spans::SpanCostUpdate,
+    mq::{MessageQueue, MessageQueueTrait},
+};
+
+pub const COST_BACKFILL_QUEUE: &str = "cost_backfill_queue";
+pub const COST_BACKFILL_EXCHANGE: &str = "cost_backfill_exchange";
+pub const COST_BACKFILL_ROUTING_KEY: &str = "cost_backfill_routing_key";
+
+/// Asks a worker to process the next batch of a backfill job.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CostBackfillMessage {
+    pub project_id: Uuid,
+    pub job_id: Uuid,
+}
+
+pub async fn push_to_cost_backfill_queue(
+    message: &CostBackfillMessage,
+    queue: Arc<MessageQueue>,
+) -> Result<()> {
+    let serialized = serde_json::to_vec(message)?;
+
+    queue
+        .publish(
+            &serialized,
+            COST_BACKFILL_EXCHANGE,
+            COST_BACKFILL_ROUTING_KEY,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// The distinct traces touched by a batch of span corrections, in a stable order.
+pub fn affected_trace_ids(updates: &[(Uuid, SpanCostUpdate)]) -> Vec<Uuid> {
+    updates
+        .iter()
+        .map(|(trace_id, _)| *trace_id)
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update() -> SpanCostUpdate {
+        SpanCostUpdate {
+            span_id: Uuid::new_v4(),
+            start_time: 0,
+            input_cost: 0.1,
+            output_cost: 0.2,
+            total_cost: 0.3,
+        }
+    }
+
+    #[test]
+    fn test_affected_trace_ids_are_distinct() {
+        let trace_a = Uuid::new_v4();
+        let trace_b = Uuid::new_v4();
+
+        let trace_ids = affected_trace_ids(&[
+            (trace_a, update()),
+            (trace_b, update()),
+            (trace_a, update()),
+        ]);
+
+        let mut expected = vec![trace_a, trace_b];
+        expected.sort();
+        assert_eq!(trace_ids, expected);
+    }
+
+    #[test]
+    fn test_affected_trace_ids_empty_batch() {
+        assert!(affected_trace_ids(&[]).is_empty());
+    }
+}
@@ -21,7 +21,6 @@ fn get_key_from_env() -> Result<xchacha20poly1305_ietf::Key> {
         .ok_or_else(|| anyhow!("Failed to create key from bytes"))
 }
 
-#[allow(dead_code)]
 pub fn encrypt(workspace_id: Uuid, val: &str) -> Result<(String, String)> {
     let key = get_key_from_env()?;
 
@@ -0,0 +1,290 @@
+//! On-demand dataset exports as JSONL, CSV or Parquet.
+//!
+//! Exports are streamed straight from ClickHouse: CSV and Parquet use the
+//! native output formats, while JSONL is encoded here so that `data`, `target`
+//! and `metadata` come out as JSON values rather than escaped strings. The
+//! stream is either returned in the response or uploaded to the exports
+//! bucket and registered like the parquets of export jobs.
+
+use anyhow::Result;
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures_util::stream;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    ch::{datapoints::CHDatapoint, utils::chrono_to_nanoseconds},
+    db::{self, DB},
+    storage::{Storage, StorageTrait, UploadStream},
+};
+
+use super::datapoints::Datapoint;
+
+/// JSONL rows are flushed to the stream in chunks of roughly this size.
+const JSONL_CHUNK_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Jsonl,
+    Csv,
+    Parquet,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "jsonl",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Parquet => "parquet",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Jsonl => "application/x-ndjson",
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Parquet => "application/octet-stream",
+        }
+    }
+}
+
+/// Narrows an export to a subset of the dataset's datapoints. Only the latest
+/// version of each datapoint is exported, ordered by id, and `limit`/`offset`
+/// apply after that dedup. [`super::service::fetch_datapoints_page`] does not
+/// dedup, so its pages can differ from an export with the same window.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFilter {
+    #[serde(default)]
+    pub ids: Option<Vec<Uuid>>,
+    #[serde(default)]
+    pub created_after: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub created_before: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub limit: Option<u64>,
+    #[serde(default)]
+    pub offset: Option<u64>,
+}
+
+/// A file written to the exports bucket.
+pub struct StoredExport {
+    pub name: String,
+    pub size: u64,
+}
+
+/// Builds the export query. Only the latest version of each datapoint is
+/// exported, and the `created_*` filters apply to that version. JSONL selects
+/// the `CHDatapoint` row layout; the tabular formats get string ids so they
+/// stay readable outside ClickHouse.
+fn export_query(format: ExportFormat, filter: &ExportFilter) -> String {
+    let columns = match format {
+        ExportFormat::Jsonl => {
+            "latest.id AS id, dataset_id, project_id, created_at, data, target, metadata"
+        }
+        ExportFormat::Csv | ExportFormat::Parquet => {
+            "toString(latest.id) AS id, toString(dataset_id) AS dataset_id, created_at, data, target, metadata"
+        }
+    };
+
+    let mut latest = String::from(
+        "SELECT id, dataset_id, project_id, created_at, data, target, metadata
+            FROM dataset_datapoints
+            WHERE project_id = ? AND dataset_id = ?",
+    );
+    if let Some(ids) = &filter.ids {
+        let placeholders = vec!["?"; ids.len()].join(",");
+        latest.push_str(&format!(" AND id IN ({placeholders})"));
+    }
+    latest.push_str(" ORDER BY id, created_at DESC LIMIT 1 BY id");
+
+    let mut query = format!(
+        "SELECT {columns}
+        FROM ({latest}) AS latest
+        WHERE 1 = 1"
+    );
+    if filter.created_after.is_some() {
+        query.push_str(" AND created_at >= fromUnixTimestamp64Nano(?)");
+    }
+    if filter.created_before.is_some() {
+        query.push_str(" AND created_at < fromUnixTimestamp64Nano(?)");
+    }
+    query.push_str(" ORDER BY toUInt128(latest.id) ASC");
+    if let Some(limit) = filter.limit {
+        query.push_str(&format!(" LIMIT {limit}"));
+    }
+    if let Some(offset) = filter.offset {
+        query.push_str(&format!(" OFFSET {offset}"));
+    }
+    query
+}
+
+/// Streams the dataset's datapoints in `format`. The dataset must already be
+/// resolved to one of the project's datasets.
+pub fn export_datapoints(
+    clickhouse: &clickhouse::Client,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    format: ExportFormat,
+    filter: &ExportFilter,
+) -> Result<UploadStream> {
+    let mut query = clickhouse
+        .query(&export_query(format, filter))
+        .bind(project_id)
+        .bind(dataset_id);
+    if let Some(ids) = &filter.ids {
+        for id in ids {
+            query = query.bind(id);
+        }
+    }
+    if let Some(created_after) = filter.created_after {
+        query = query.bind(chrono_to_nanoseconds(created_after));
+    }
+    if let Some(created_before) = filter.created_before {
+        query = query.bind(chrono_to_nanoseconds(created_before));
+    }
+
+    let stream: UploadStream = match format {
+        ExportFormat::Jsonl => {
+            let cursor = query.fetch::<CHDatapoint>()?;
+            Box::pin(stream::unfold(Some(cursor), |cursor| async move {
+                let mut cursor = cursor?;
+                let mut buffer = Vec::with_capacity(JSONL_CHUNK_BYTES);
+                loop {
+                    match cursor.next().await {
+                        Ok(Some(row)) => {
+                            if let Err(e) = write_jsonl_line(&mut buffer, &row.into()) {
+                                return Some((Err(e.into()), None));
+                            }
+                            if buffer.len() >= JSONL_CHUNK_BYTES {
+                                return Some((Ok(Bytes::from(buffer)), Some(cursor)));
+                            }
+                        }
+                        Ok(None) if buffer.is_empty() => return None,
+                        Ok(None) => return Some((Ok(Bytes::from(buffer)), None)),
+                        Err(e) => return Some((Err(e.into()), None)),
+                    }
+                }
+            }))
+        }
+        ExportFormat::Csv | ExportFormat::Parquet => {
+            let clickhouse_format = match format {
+                ExportFormat::Csv => "CSVWithNames",
+                _ => "Parquet",
+            };
+            let cursor = query.fetch_bytes(clickhouse_format)?;
+            Box::pin(stream::unfold(Some(cursor), |cursor| async move {
+                let mut cursor = cursor?;
+                match cursor.next().await {
+                    Ok(Some(chunk)) => Some((Ok(chunk), Some(cursor))),
+                    Ok(None) => None,
+                    Err(e) => Some((Err(e.into()), None)),
+                }
+            }))
+        }
+    };
+
+    Ok(stream)
+}
+
+/// Exports the dataset into the exports bucket and registers the file, so it
+/// can be downloaded from `/v1/datasets/{dataset_id}/parquets/{name}`.
+#[allow(clippy::too_many_arguments)]
+pub async fn export_to_storage(
+    clickhouse: &clickhouse::Client,
+    storage: &Storage,
+    db: &DB,
+    bucket: &str,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    format: ExportFormat,
+    filter: &ExportFilter,
+) -> Result<StoredExport> {
+    let export_id = Uuid::new_v4();
+    let name = format!("export-{}.{}", export_id, format.extension());
+    let path = format!("exports/{}/{}/{}", project_id, dataset_id, name);
+
+    let body = export_datapoints(clickhouse, project_id, dataset_id, format, filter)?;
+    let size = storage.put_stream(bucket, &path, body).await?;
+
+    db::datasets::insert_dataset_export(&db.pool, project_id, dataset_id, export_id, &name, &path)
+        .await?;
+
+    Ok(StoredExport { name, size })
+}
+
+fn write_jsonl_line(buffer: &mut Vec<u8>, datapoint: &Datapoint) -> serde_json::Result<()> {
+    serde_json::to_writer(&mut *buffer, datapoint)?;
+    buffer.push(b'\n');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_query_filters() {
+        let filter = ExportFilter {
+            ids: Some(vec![Uuid::nil(), Uuid::max()]),
+            created_after: Some(Utc::now()),
+            created_before: None,
+            limit: Some(10),
+            offset: Some(20),
+        };
+        let query = export_query(ExportFormat::Csv, &filter);
+
+        assert!(query.starts_with("SELECT toString(latest.id) AS id"));
+        assert!(query.contains("AND id IN (?,?)"));
+        assert!(query.contains("AND created_at >= fromUnixTimestamp64Nano(?)"));
+        assert!(!query.contains("created_at <"));
+        assert!(query.ends_with("ORDER BY toUInt128(latest.id) ASC LIMIT 10 OFFSET 20"));
+    }
+
+    #[test]
+    fn test_export_query_keeps_only_the_latest_version() {
+        // A datapoint edited once has two rows with the same id; the export
+        // must pick the newer row before filtering, limiting or paginating.
+        let filter = ExportFilter {
+            ids: None,
+            created_after: Some(Utc::now()),
+            created_before: None,
+            limit: Some(1),
+            offset: None,
+        };
+        let query = export_query(ExportFormat::Jsonl, &filter);
+
+        let dedup = query
+            .find("ORDER BY id, created_at DESC LIMIT 1 BY id")
+            .expect("latest-version dedup");
+        let created_filter = query.find("AND created_at >=").unwrap();
+        let limit = query.rfind(" LIMIT 1").unwrap();
+        assert!(dedup < created_filter);
+        assert!(created_filter < limit);
+        assert!(query.contains(") AS latest"));
+    }
+
+    #[test]
+    fn test_write_jsonl_line_keeps_json_values() {
+        let row = CHDatapoint {
+            id: Uuid::nil(),
+            dataset_id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            created_at: 0,
+            data: r#"{"question":"2+2?"}"#.to_string(),
+            target: r#"{"answer":4}"#.to_string(),
+            metadata: r#"{"split":"train"}"#.to_string(),
+        };
+        let mut buffer = Vec::new();
+        write_jsonl_line(&mut buffer, &row.into()).unwrap();
+
+        let line = String::from_utf8(buffer).unwrap();
+        assert!(line.ends_with('\n'));
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["data"]["question"], "2+2?");
+        assert_eq!(value["target"]["answer"], 4);
+        assert_eq!(value["metadata"]["split"], "train");
+    }
+}
@@ -0,0 +1,619 @@
+//! Bulk "materialize to dataset": turn reviewed labeling queue items or the
+//! spans selected by a SQL query into datapoints.
+//!
+//! Every source row is a JSON object of named columns. A [`DatapointMapping`]
+//! picks `data`, `target` and `metadata` out of it with paths such as
+//! `input`, `output.choices[0].message` or `item_metadata.traceId`; columns
+//! holding stringified JSON (span `input`/`output`, queue payloads) are parsed
+//! on the way. Each datapoint records where it came from under the
+//! [`PROVENANCE_KEY`] metadata key.
+
+use std::{collections::HashMap, sync::Arc};
+
+use indexmap::IndexMap;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use uuid::Uuid;
+
+use crate::{
+    cache::Cache,
+    ch::labeling_queue_items::{CHLabelingQueueItemContent, get_labeling_queue_item_contents},
+    db::{self, DB},
+    query_engine::QueryEngine,
+    sql::{self, ClickhouseReadonlyClient, SqlQueryError, SqlQuerySource},
+};
+
+use super::service::{self, CreateDatapointsOutcome, DatasetIdentifier, NewDatapoint};
+
+/// Metadata key holding a datapoint's source span or queue item.
+pub const PROVENANCE_KEY: &str = "provenance";
+/// Largest number of datapoints a single request may create.
+pub const MAX_MATERIALIZED_DATAPOINTS: usize = 10_000;
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum MaterializeSource {
+    /// Items of a labeling queue. Columns: `id`, `data`, `target` (the
+    /// reviewed `edit`), `original_target`, `metadata` and `item_metadata`.
+    #[serde(rename_all = "camelCase")]
+    LabelingQueue {
+        queue_id: Uuid,
+        #[serde(default)]
+        item_ids: Option<Vec<Uuid>>,
+        /// Also take items that have not been approved yet
+        #[serde(default)]
+        include_unlabelled: bool,
+    },
+    /// Rows of a SQL query over `spans`. Columns are the selected ones and
+    /// must include `span_id`.
+    #[serde(rename_all = "camelCase")]
+    Sql {
+        query: String,
+        #[serde(default)]
+        parameters: HashMap<String, Value>,
+    },
+}
+
+/// Either a single path, or an object whose values are paths.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum FieldMapping {
+    Path(String),
+    Fields(IndexMap<String, String>),
+}
+
+/// Where `data`, `target` and `metadata` come from. Unset fields fall back to
+/// the source's defaults: `data`/`target`/`metadata` for labeling queues and
+/// `input`/`output` for SQL.
+#[derive(Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DatapointMapping {
+    #[serde(default)]
+    pub data: Option<FieldMapping>,
+    #[serde(default)]
+    pub target: Option<FieldMapping>,
+    #[serde(default)]
+    pub metadata: Option<FieldMapping>,
+}
+
+/// Clients needed to run a SQL source.
+pub struct SqlSourceContext {
+    pub clickhouse_ro: Option<Arc<ClickhouseReadonlyClient>>,
+    pub query_engine: Arc<QueryEngine>,
+    pub http_client: Arc<reqwest::Client>,
+    pub cache: Arc<Cache>,
+}
+
+/// Outcome of [`materialize_datapoints`]. Each non-`Created` variant is a
+/// business rule the handler maps to a specific HTTP status.
+pub enum MaterializeOutcome {
+    Created(CreateDatapointsOutcome),
+    /// Labeling queue doesn't exist in the project → 404.
+    QueueNotFound,
+    /// Unparsable path, or a row that doesn't fit the mapping → 400.
+    InvalidMapping(String),
+    /// More than [`MAX_MATERIALIZED_DATAPOINTS`] source rows → 400.
+    TooManyRows,
+    /// The SQL query failed validation or execution.
+    QueryFailed(SqlQueryError),
+}
+
+#[derive(Debug, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// `column(.key|[index])*`
+#[derive(Debug, PartialEq)]
+struct FieldPath {
+    column: String,
+    segments: Vec<PathSegment>,
+}
+
+impl FieldPath {
+    fn parse(path: &str) -> Result<Self, String> {
+        let invalid = |reason: &str| format!("Invalid path `{}`: {}", path, reason);
+
+        let mut segments = Vec::new();
+        let mut current = String::new();
+        let mut after_index = false;
+        let mut chars = path.trim().chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if current.is_empty() && !after_index {
+                        return Err(invalid("empty key"));
+                    }
+                    if !current.is_empty() {
+                        segments.push(PathSegment::Key(std::mem::take(&mut current)));
+                    }
+                    after_index = false;
+                }
+                '[' => {
+                    if !current.is_empty() {
+                        segments.push(PathSegment::Key(std::mem::take(&mut current)));
+                    } else if segments.is_empty() {
+                        return Err(invalid("must start with a column"));
+                    }
+                    let mut index = String::new();
+                    let mut closed = false;
+                    for c in chars.by_ref() {
+                        if c == ']' {
+                            closed = true;
+                            break;
+                        }
+                        index.push(c);
+                    }
+                    if !closed {
+                        return Err(invalid("unclosed `[`"));
+                    }
+                    let index = index
+                        .trim()
+                        .parse()
+                        .map_err(|_| invalid("array index must be a number"))?;
+                    segments.push(PathSegment::Index(index));
+                    after_index = true;
+                }
+                _ if after_index => return Err(invalid("expected `.` or `[` after `]`")),
+                _ => current.push(c),
+            }
+        }
+        if !current.is_empty() {
+            segments.push(PathSegment::Key(current));
+        } else if !after_index {
+            return Err(invalid("empty key"));
+        }
+
+        match segments.remove(0) {
+            PathSegment::Key(column) => Ok(Self { column, segments }),
+            PathSegment::Index(_) => Err(invalid("must start with a column")),
+        }
+    }
+
+    /// The value at this path, `null` when any step is missing.
+    fn resolve(&self, row: &Map<String, Value>) -> Value {
+        let mut value = row.get(&self.column).cloned().unwrap_or(Value::Null);
+        for segment in &self.segments {
+            value = match (segment, parse_embedded_json(value)) {
+                (PathSegment::Key(key), Value::Object(mut map)) => {
+                    map.remove(key).unwrap_or(Value::Null)
+                }
+                (PathSegment::Index(index), Value::Array(mut items)) if *index < items.len() => {
+                    items.swap_remove(*index)
+                }
+                _ => Value::Null,
+            };
+        }
+        parse_embedded_json(value)
+    }
+}
+
+/// Strings holding a JSON object or array are parsed; anything else is kept.
+fn parse_embedded_json(value: Value) -> Value {
+    if let Value::String(s) = &value {
+        let trimmed = s.trim_start();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && let Ok(parsed) = serde_json::from_str(trimmed)
+        {
+            return parsed;
+        }
+    }
+    value
+}
+
+enum CompiledField {
+    Path(FieldPath),
+    Fields(Vec<(String, FieldPath)>),
+}
+
+impl CompiledField {
+    fn compile(mapping: &FieldMapping) -> Result<Self, String> {
+        match mapping {
+            FieldMapping::Path(path) => Ok(Self::Path(FieldPath::parse(path)?)),
+            FieldMapping::Fields(fields) => fields
+                .iter()
+                .map(|(key, path)| Ok((key.clone(), FieldPath::parse(path)?)))
+                .collect::<Result<Vec<_>, String>>()
+                .map(Self::Fields),
+        }
+    }
+
+    fn resolve(&self, row: &Map<String, Value>) -> Value {
+        match self {
+            Self::Path(path) => path.resolve(row),
+            Self::Fields(fields) => Value::Object(
+                fields
+                    .iter()
+                    .map(|(key, path)| (key.clone(), path.resolve(row)))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+struct CompiledMapping {
+    data: CompiledField,
+    target: Option<CompiledField>,
+    metadata: Option<CompiledField>,
+}
+
+impl CompiledMapping {
+    fn compile(mapping: DatapointMapping, defaults: DatapointMapping) -> Result<Self, String> {
+        let compile = |field: Option<FieldMapping>| field.as_ref().map(CompiledField::compile);
+        let data = mapping
+            .data
+            .or(defaults.data)
+            .ok_or("A `data` mapping is required")?;
+
+        Ok(Self {
+            data: CompiledField::compile(&data)?,
+            target: compile(mapping.target.or(defaults.target)).transpose()?,
+            metadata: compile(mapping.metadata.or(defaults.metadata)).transpose()?,
+        })
+    }
+
+    fn to_datapoint(
+        &self,
+        row: &Map<String, Value>,
+        provenance: Value,
+    ) -> Result<NewDatapoint, String> {
+        let target = self
+            .target
+            .as_ref()
+            .map(|target| target.resolve(row))
+            .filter(|target| !target.is_null());
+        let mut metadata: HashMap<String, Value> =
+            match self.metadata.as_ref().map(|m| m.resolve(row)) {
+                None | Some(Value::Null) => HashMap::new(),
+                Some(Value::Object(metadata)) => metadata.into_iter().collect(),
+                Some(_) => return Err("`metadata` must resolve to an object".to_string()),
+            };
+        metadata.insert(PROVENANCE_KEY.to_string(), provenance);
+
+        Ok(NewDatapoint {
+            id: None,
+            data: self.data.resolve(row),
+            target,
+            metadata,
+        })
+    }
+}
+
+fn default_mapping(source: &MaterializeSource) -> DatapointMapping {
+    let path = |p: &str| Some(FieldMapping::Path(p.to_string()));
+    match source {
+        MaterializeSource::LabelingQueue { .. } => DatapointMapping {
+            data: path("data"),
+            target: path("target"),
+            metadata: path("metadata"),
+        },
+        MaterializeSource::Sql { .. } => DatapointMapping {
+            data: path("input"),
+            target: path("output"),
+            metadata: None,
+        },
+    }
+}
+
+/// The columns of a queue item and its provenance.
+fn queue_item_row(queue_id: Uuid, item: CHLabelingQueueItemContent) -> (Map<String, Value>, Value) {
+    let payload: Value = serde_json::from_str(&item.payload).unwrap_or(Value::Null);
+    let original_target = payload.get("target").cloned().unwrap_or(Value::Null);
+    // `edit` mirrors the reviewed target; rows that predate it leave it empty.
+    let target = if item.edit.is_empty() {
+        original_target.clone()
+    } else {
+        serde_json::from_str(&item.edit).unwrap_or_else(|_| original_target.clone())
+    };
+    let item_metadata: Value =
+        serde_json::from_str(&item.metadata).unwrap_or_else(|_| Value::Object(Map::new()));
+
+    let mut provenance = json!({
+        "type": "labeling_queue",
+        "queueId": queue_id,
+        "itemId": item.id,
+    });
+    if item_metadata.get("source").and_then(Value::as_str) == Some("span") {
+        provenance["spanId"] = item_metadata.get("id").cloned().unwrap_or(Value::Null);
+        provenance["traceId"] = item_metadata.get("traceId").cloned().unwrap_or(Value::Null);
+    }
+
+    let mut row = Map::new();
+    row.insert("id".to_string(), json!(item.id));
+    row.insert(
+        "data".to_string(),
+        payload.get("data").cloned().unwrap_or(Value::Null),
+    );
+    row.insert("target".to_string(), target);
+    row.insert("original_target".to_string(), original_target);
+    row.insert(
+        "metadata".to_string(),
+        payload.get("metadata").cloned().unwrap_or(Value::Null),
+    );
+    row.insert("item_metadata".to_string(), item_metadata);
+
+    (row, provenance)
+}
+
+/// The provenance of a span row, or `None` without a `span_id` column.
+fn span_provenance(row: &Map<String, Value>) -> Option<Value> {
+    let span_id = row
+        .get("span_id")
+        .filter(|id| id.as_str().is_some_and(|s| !s.is_empty()))?;
+    Some(json!({
+        "type": "span",
+        "spanId": span_id,
+        "traceId": row.get("trace_id").cloned().unwrap_or(Value::Null),
+    }))
+}
+
+/// Cap a user query at `limit` rows, so an oversized result is never fetched.
+/// The closing parenthesis goes on its own line in case the query ends with a
+/// line comment.
+fn limit_sql_query(query: &str, limit: usize) -> String {
+    let query = query.trim().trim_end_matches(';');
+    format!("SELECT * FROM (\n{}\n) LIMIT {}", query, limit)
+}
+
+/// Fetch the source rows, map them to datapoints and create them in the
+/// dataset (see [`service::create_datapoints`] for the dataset rules).
+#[allow(clippy::too_many_arguments)]
+pub async fn materialize_datapoints(
+    project_id: Uuid,
+    dataset: DatasetIdentifier,
+    create_dataset: bool,
+    source: MaterializeSource,
+    mapping: DatapointMapping,
+    db: Arc<DB>,
+    clickhouse: clickhouse::Client,
+    sql_context: SqlSourceContext,
+) -> anyhow::Result<MaterializeOutcome> {
+    let mapping = match CompiledMapping::compile(mapping, default_mapping(&source)) {
+        Ok(mapping) => mapping,
+        Err(e) => return Ok(MaterializeOutcome::InvalidMapping(e)),
+    };
+
+    let rows: Vec<(Map<String, Value>, Value)> = match source {
+        MaterializeSource::LabelingQueue {
+            queue_id,
+            item_ids,
+            include_unlabelled,
+        } => {
+            if !db::labeling_queues::queue_exists(&db.pool, queue_id, project_id).await? {
+                return Ok(MaterializeOutcome::QueueNotFound);
+            }
+            if item_ids.as_ref().is_some_and(|ids| ids.is_empty()) {
+                vec![]
+            } else {
+                get_labeling_queue_item_contents(
+                    &clickhouse,
+                    project_id,
+                    queue_id,
+                    item_ids.as_deref(),
+                    include_unlabelled,
+                    MAX_MATERIALIZED_DATAPOINTS as u64 + 1,
+                )
+                .await?
+                .into_iter()
+                .map(|item| queue_item_row(queue_id, item))
+                .collect()
+            }
+        }
+        MaterializeSource::Sql { query, parameters } => {
+            let clickhouse_ro = sql_context.clickhouse_ro.ok_or(anyhow::anyhow!(
+                "ClickHouse read-only client is not configured"
+            ))?;
+            let result = sql::execute_sql_query(
+                limit_sql_query(&query, MAX_MATERIALIZED_DATAPOINTS + 1),
+                project_id,
+                parameters,
+                SqlQuerySource::Public,
+                clickhouse_ro,
+                sql_context.query_engine,
+                sql_context.http_client,
+                db.clone(),
+                sql_context.cache,
+            )
+            .await;
+            let result = match result {
+                Ok(result) => result,
+                Err(e) => return Ok(MaterializeOutcome::QueryFailed(e)),
+            };
+
+            let mut rows = Vec::with_capacity(result.len());
+            for (i, row) in result.into_iter().enumerate() {
+                let Value::Object(row) = row else {
+                    return Ok(MaterializeOutcome::InvalidMapping(format!(
+                        "Row {} is not an object",
+                        i
+                    )));
+                };
+                let Some(provenance) = span_provenance(&row) else {
+                    return Ok(MaterializeOutcome::InvalidMapping(
+                        "The query must select the span_id column".to_string(),
+                    ));
+                };
+                rows.push((row, provenance));
+            }
+            rows
+        }
+    };
+
+    if rows.len() > MAX_MATERIALIZED_DATAPOINTS {
+        return Ok(MaterializeOutcome::TooManyRows);
+    }
+
+    let mut datapoints = Vec::with_capacity(rows.len());
+    for (i, (row, provenance)) in rows.into_iter().enumerate() {
+        match mapping.to_datapoint(&row, provenance) {
+            Ok(datapoint) => datapoints.push(datapoint),
+            Err(e) => {
+                return Ok(MaterializeOutcome::InvalidMapping(format!(
+                    "Row {}: {}",
+                    i, e
+                )));
+            }
+        }
+    }
+
+    let outcome = service::create_datapoints(
+        project_id,
+        dataset,
+        datapoints,
+        create_dataset,
+        db,
+        clickhouse,
+    )
+    .await?;
+
+    Ok(MaterializeOutcome::Created(outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(map) => map,
+            _ => panic!("expected an object"),
+        }
+    }
+
+    #[test]
+    fn test_parse_field_path() {
+        assert_eq!(
+            FieldPath::parse("output.choices[0].message").unwrap(),
+            FieldPath {
+                column: "output".to_string(),
+                segments: vec![
+                    PathSegment::Key("choices".to_string()),
+                    PathSegment::Index(0),
+                    PathSegment::Key("message".to_string()),
+                ],
+            }
+        );
+        assert_eq!(FieldPath::parse(" input ").unwrap().column, "input");
+
+        for invalid in ["", "a..b", "a.", "[0]", "a[x]", "a[0", "a[0]b"] {
+            assert!(FieldPath::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_resolve_parses_stringified_json() {
+        let row = row(json!({
+            "input": r#"[{"role":"user","content":"hi"}]"#,
+            "output": "plain text",
+        }));
+
+        let content = FieldPath::parse("input[0].content").unwrap();
+        assert_eq!(content.resolve(&row), json!("hi"));
+        let input = FieldPath::parse("input").unwrap();
+        assert_eq!(
+            input.resolve(&row),
+            json!([{"role": "user", "content": "hi"}])
+        );
+        let output = FieldPath::parse("output").unwrap();
+        assert_eq!(output.resolve(&row), json!("plain text"));
+        let missing = FieldPath::parse("input[3].content").unwrap();
+        assert_eq!(missing.resolve(&row), Value::Null);
+    }
+
+    #[test]
+    fn test_sql_row_to_datapoint() {
+        let source = MaterializeSource::Sql {
+            query: String::new(),
+            parameters: HashMap::new(),
+        };
+        let mapping = DatapointMapping {
+            data: None,
+            target: None,
+            metadata: Some(FieldMapping::Fields(IndexMap::from([(
+                "model".to_string(),
+                "model".to_string(),
+            )]))),
+        };
+        let mapping = CompiledMapping::compile(mapping, default_mapping(&source)).unwrap();
+        let row = row(json!({
+            "span_id": "00000000-0000-0000-0000-000000000001",
+            "trace_id": "00000000-0000-0000-0000-000000000002",
+            "input": r#"{"question":"2+2?"}"#,
+            "output": "4",
+            "model": "gpt-4o",
+        }));
+
+        let provenance = span_provenance(&row).unwrap();
+        let datapoint = mapping.to_datapoint(&row, provenance).unwrap();
+
+        assert_eq!(datapoint.data, json!({"question": "2+2?"}));
+        assert_eq!(datapoint.target, Some(json!("4")));
+        assert_eq!(datapoint.metadata["model"], json!("gpt-4o"));
+        assert_eq!(
+            datapoint.metadata[PROVENANCE_KEY]["spanId"],
+            json!("00000000-0000-0000-0000-000000000001")
+        );
+        assert!(span_provenance(&Map::new()).is_none());
+    }
+
+    #[test]
+    fn test_queue_item_uses_reviewed_edit() {
+        let queue_id = Uuid::from_u128(1);
+        let item = CHLabelingQueueItemContent {
+            id: Uuid::from_u128(2),
+            payload: r#"{"data":{"q":"x"},"target":"old","metadata":{"split":"train"}}"#
+                .to_string(),
+            edit: r#""new""#.to_string(),
+            metadata: r#"{"source":"span","id":"span-1","traceId":"trace-1"}"#.to_string(),
+        };
+        let source = MaterializeSource::LabelingQueue {
+            queue_id,
+            item_ids: None,
+            include_unlabelled: false,
+        };
+        let mapping =
+            CompiledMapping::compile(DatapointMapping::default(), default_mapping(&source))
+                .unwrap();
+
+        let (row, provenance) = queue_item_row(queue_id, item);
+        let datapoint = mapping.to_datapoint(&row, provenance).unwrap();
+
+        assert_eq!(datapoint.data, json!({"q": "x"}));
+        assert_eq!(datapoint.target, Some(json!("new")));
+        assert_eq!(datapoint.metadata["split"], json!("train"));
+        assert_eq!(
+            datapoint.metadata[PROVENANCE_KEY]["spanId"],
+            json!("span-1")
+        );
+        assert_eq!(
+            datapoint.metadata[PROVENANCE_KEY]["type"],
+            json!("labeling_queue")
+        );
+    }
+
+    #[test]
+    fn test_metadata_must_be_an_object() {
+        let mapping = DatapointMapping {
+            data: Some(FieldMapping::Path("input".to_string())),
+            target: None,
+            metadata: Some(FieldMapping::Path("input".to_string())),
+        };
+        let mapping = CompiledMapping::compile(mapping, DatapointMapping::default()).unwrap();
+
+        let result = mapping.to_datapoint(&row(json!({"input": "text"})), Value::Null);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_limit_sql_query() {
+        assert_eq!(
+            limit_sql_query("SELECT span_id FROM spans LIMIT 5; ", 10),
+            "SELECT * FROM (\nSELECT span_id FROM spans LIMIT 5\n) LIMIT 10"
+        );
+        assert_eq!(
+            limit_sql_query("SELECT span_id FROM spans -- recent", 10),
+            "SELECT * FROM (\nSELECT span_id FROM spans -- recent\n) LIMIT 10"
+        );
+    }
+}
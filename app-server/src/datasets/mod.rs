@@ -1,2 +1,5 @@
 pub mod datapoints;
+pub mod export;
+pub mod materialize;
 pub mod service;
+pub mod versions;
@@ -77,13 +77,39 @@ pub enum CreateDatapointsOutcome {
     DatasetNotFound,
 }
 
-/// Fetch one page of a dataset's datapoints (ordered by id, paginated). Returns
-/// `Ok(None)` when a dataset referenced by name doesn't exist (→ 404 at the
-/// handler); `Ok(Some((items, total_count)))` otherwise.
+/// Resolve a dataset identifier to the id of a dataset in the project. Returns
+/// `Ok(None)` when no such dataset exists (→ 404 at the handler).
+pub async fn resolve_dataset_id(
+    project_id: Uuid,
+    dataset: DatasetIdentifier,
+    db: &DB,
+) -> anyhow::Result<Option<Uuid>> {
+    match dataset {
+        DatasetIdentifier::Name(name) => {
+            db::datasets::get_dataset_id_by_name(&db.pool, &name.dataset_name, project_id).await
+        }
+        DatasetIdentifier::Id(id) => {
+            // Object-level authZ: a body-supplied dataset id must belong to the
+            // authorized project, else a project-A member could read project-B
+            // data. Mirror the write path; `None` → 404 (don't leak existence).
+            if !db::datasets::dataset_exists(&db.pool, id.dataset_id, project_id).await? {
+                return Ok(None);
+            }
+            Ok(Some(id.dataset_id))
+        }
+    }
+}
+
+/// Fetch one page of a dataset's datapoints (ordered by id, paginated). With a
+/// `version_id`, the page is read from that version's snapshot instead of the
+/// live dataset. Returns `Ok(None)` when a dataset referenced by name doesn't
+/// exist, or the version isn't one of the dataset's (→ 404 at the handler);
+/// `Ok(Some((items, total_count)))` otherwise.
 #[allow(clippy::too_many_arguments)]
 pub async fn fetch_datapoints_page(
     project_id: Uuid,
     dataset: DatasetIdentifier,
+    version_id: Option<Uuid>,
     limit: i64,
     offset: i64,
     clickhouse_ro: Arc<ClickhouseReadonlyClient>,
@@ -92,27 +118,39 @@ pub async fn fetch_datapoints_page(
     db: Arc<DB>,
     cache: Arc<Cache>,
 ) -> anyhow::Result<Option<(Vec<Datapoint>, u64)>> {
-    let dataset_id = match dataset {
-        DatasetIdentifier::Name(name) => {
-            match db::datasets::get_dataset_id_by_name(&db.pool, &name.dataset_name, project_id)
-                .await?
-            {
-                Some(id) => id,
-                None => return Ok(None),
+    let Some(dataset_id) = resolve_dataset_id(project_id, dataset, &db).await? else {
+        return Ok(None);
+    };
+
+    // Versions are immutable snapshots, read from their own table.
+    let (table, version_filter) = match version_id {
+        Some(version_id) => {
+            match db::datasets::get_dataset_version(&db.pool, project_id, version_id).await? {
+                Some(version) if version.dataset_id == dataset_id => (
+                    "dataset_snapshot_datapoints",
+                    "AND snapshot_id = {version_id:UUID}",
+                ),
+                _ => return Ok(None),
             }
         }
-        DatasetIdentifier::Id(id) => {
-            // Object-level authZ: a body-supplied dataset id must belong to the
-            // authorized project, else a project-A member could read project-B
-            // data. Mirror the write path; `None` → 404 (don't leak existence).
-            if !db::datasets::dataset_exists(&db.pool, id.dataset_id, project_id).await? {
-                return Ok(None);
-            }
-            id.dataset_id
+        None => ("dataset_datapoints", ""),
+    };
+    let filter_parameters = || {
+        let mut parameters = HashMap::from([(
+            "dataset_id".to_string(),
+            Value::String(dataset_id.to_string()),
+        )]);
+        if let Some(version_id) = version_id {
+            parameters.insert(
+                "version_id".to_string(),
+                Value::String(version_id.to_string()),
+            );
         }
+        parameters
     };
 
-    let select_query = "
+    let select_query = format!(
+        "
         SELECT
             id,
             dataset_id,
@@ -120,23 +158,19 @@ pub async fn fetch_datapoints_page(
             data,
             target,
             metadata
-        FROM dataset_datapoints
-        WHERE dataset_id = {dataset_id:UUID}
+        FROM {table}
+        WHERE dataset_id = {{dataset_id:UUID}} {version_filter}
         ORDER BY toUInt128(id) ASC
-        LIMIT {limit:Int64}
-        OFFSET {offset:Int64}
-    ";
-    let parameters = HashMap::from([
-        (
-            "dataset_id".to_string(),
-            Value::String(dataset_id.to_string()),
-        ),
-        ("limit".to_string(), Value::Number(limit.into())),
-        ("offset".to_string(), Value::Number(offset.into())),
-    ]);
+        LIMIT {{limit:Int64}}
+        OFFSET {{offset:Int64}}
+    "
+    );
+    let mut parameters = filter_parameters();
+    parameters.insert("limit".to_string(), Value::Number(limit.into()));
+    parameters.insert("offset".to_string(), Value::Number(offset.into()));
 
     let select_query_result = sql::execute_sql_query(
-        select_query.to_string(),
+        select_query,
         project_id,
         parameters,
         SqlQuerySource::Internal,
@@ -148,18 +182,17 @@ pub async fn fetch_datapoints_page(
     )
     .await?;
 
-    let total_count_query = "
-        SELECT COUNT(*) as count FROM dataset_datapoints
-        WHERE dataset_id = {dataset_id:UUID}
-    ";
+    let total_count_query = format!(
+        "
+        SELECT COUNT(*) as count FROM {table}
+        WHERE dataset_id = {{dataset_id:UUID}} {version_filter}
+    "
+    );
 
     let total_count_result = sql::execute_sql_query(
-        total_count_query.to_string(),
+        total_count_query,
         project_id,
-        HashMap::from([(
-            "dataset_id".to_string(),
-            Value::String(dataset_id.to_string()),
-        )]),
+        filter_parameters(),
         SqlQuerySource::Internal,
         clickhouse_ro,
         query_engine,
@@ -0,0 +1,211 @@
+//! Dataset versions: immutable snapshots of a dataset's datapoints.
+//!
+//! Creating a version copies the latest version of every datapoint into
+//! ClickHouse under the version id, so reads "as of" a version
+//! ([`super::service::fetch_datapoints_page`]) and diffs between versions are
+//! unaffected by later edits and deletions. Like [`super::service`], these
+//! return domain outcomes and leave HTTP to the handlers.
+
+use std::sync::Arc;
+
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    ch::datapoints::{self as ch_datapoints, CHSnapshotDiffRow},
+    db::{self, DB, datasets::DatasetVersion},
+};
+
+use super::service::{DatasetIdentifier, resolve_dataset_id};
+
+pub const DEFAULT_DIFF_LIMIT: usize = 1000;
+
+#[derive(Serialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DatapointChange {
+    pub id: Uuid,
+    pub change: String,
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetVersionDiff {
+    pub dataset_id: Uuid,
+    pub from_version_id: Uuid,
+    pub to_version_id: Uuid,
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub unchanged: usize,
+    /// Changed datapoints ordered by id, truncated to the requested limit.
+    pub datapoints: Vec<DatapointChange>,
+}
+
+/// Outcome of [`diff_dataset_versions`].
+pub enum DiffVersionsOutcome {
+    Diff(DatasetVersionDiff),
+    /// Either version doesn't exist in the project → 404.
+    VersionNotFound,
+    /// The versions belong to different datasets → 400.
+    DifferentDatasets,
+}
+
+/// Snapshot the dataset's current datapoints as a new version. Returns
+/// `Ok(None)` when the dataset doesn't exist (→ 404 at the handler).
+pub async fn create_dataset_version(
+    project_id: Uuid,
+    dataset: DatasetIdentifier,
+    name: Option<&str>,
+    db: Arc<DB>,
+    clickhouse: clickhouse::Client,
+) -> anyhow::Result<Option<DatasetVersion>> {
+    let Some(dataset_id) = resolve_dataset_id(project_id, dataset, &db).await? else {
+        return Ok(None);
+    };
+
+    // Datapoints are copied first: a version row only exists once its
+    // snapshot is complete.
+    let version_id = Uuid::new_v4();
+    let datapoint_count =
+        ch_datapoints::snapshot_datapoints(&clickhouse, project_id, dataset_id, version_id).await?;
+
+    let version = db::datasets::create_dataset_version(
+        &db.pool,
+        version_id,
+        project_id,
+        dataset_id,
+        name,
+        datapoint_count as i64,
+    )
+    .await?;
+
+    Ok(Some(version))
+}
+
+/// Versions of a dataset, newest first. `Ok(None)` when the dataset doesn't exist.
+pub async fn get_dataset_versions(
+    project_id: Uuid,
+    dataset: DatasetIdentifier,
+    db: Arc<DB>,
+) -> anyhow::Result<Option<Vec<DatasetVersion>>> {
+    let Some(dataset_id) = resolve_dataset_id(project_id, dataset, &db).await? else {
+        return Ok(None);
+    };
+
+    let versions = db::datasets::get_dataset_versions(&db.pool, project_id, dataset_id).await?;
+
+    Ok(Some(versions))
+}
+
+pub async fn diff_dataset_versions(
+    project_id: Uuid,
+    from_version_id: Uuid,
+    to_version_id: Uuid,
+    limit: usize,
+    db: Arc<DB>,
+    clickhouse: clickhouse::Client,
+) -> anyhow::Result<DiffVersionsOutcome> {
+    let from = db::datasets::get_dataset_version(&db.pool, project_id, from_version_id).await?;
+    let to = db::datasets::get_dataset_version(&db.pool, project_id, to_version_id).await?;
+    let (Some(from), Some(to)) = (from, to) else {
+        return Ok(DiffVersionsOutcome::VersionNotFound);
+    };
+    if from.dataset_id != to.dataset_id {
+        return Ok(DiffVersionsOutcome::DifferentDatasets);
+    }
+
+    let rows =
+        ch_datapoints::diff_snapshots(&clickhouse, project_id, from.dataset_id, from.id, to.id)
+            .await?;
+
+    Ok(DiffVersionsOutcome::Diff(summarize_diff(
+        &from, &to, rows, limit,
+    )))
+}
+
+fn summarize_diff(
+    from: &DatasetVersion,
+    to: &DatasetVersion,
+    rows: Vec<CHSnapshotDiffRow>,
+    limit: usize,
+) -> DatasetVersionDiff {
+    let count = |change: &str| rows.iter().filter(|row| row.change == change).count();
+    let added = count("added");
+    let removed = count("removed");
+    let changed = count("changed");
+
+    DatasetVersionDiff {
+        dataset_id: from.dataset_id,
+        from_version_id: from.id,
+        to_version_id: to.id,
+        added,
+        removed,
+        changed,
+        unchanged: (from.datapoint_count as usize).saturating_sub(removed + changed),
+        datapoints: rows
+            .into_iter()
+            .take(limit)
+            .map(|row| DatapointChange {
+                id: row.id,
+                change: row.change,
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn version(datapoint_count: i64) -> DatasetVersion {
+        DatasetVersion {
+            id: Uuid::new_v4(),
+            created_at: Utc::now(),
+            dataset_id: Uuid::nil(),
+            project_id: Uuid::nil(),
+            name: None,
+            datapoint_count,
+        }
+    }
+
+    fn row(id: u128, change: &str) -> CHSnapshotDiffRow {
+        CHSnapshotDiffRow {
+            id: Uuid::from_u128(id),
+            change: change.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_summarize_diff() {
+        let from = version(10);
+        let to = version(11);
+        let rows = vec![
+            row(1, "changed"),
+            row(2, "added"),
+            row(3, "added"),
+            row(4, "removed"),
+        ];
+
+        let diff = summarize_diff(&from, &to, rows, 2);
+
+        assert_eq!(diff.added, 2);
+        assert_eq!(diff.removed, 1);
+        assert_eq!(diff.changed, 1);
+        assert_eq!(diff.unchanged, 8);
+        assert_eq!(
+            diff.datapoints,
+            vec![
+                DatapointChange {
+                    id: Uuid::from_u128(1),
+                    change: "changed".to_string(),
+                },
+                DatapointChange {
+                    id: Uuid::from_u128(2),
+                    change: "added".to_string(),
+                },
+            ]
+        );
+    }
+}
@@ -73,6 +73,9 @@ pub struct AlertDeliveryTarget {
     pub email: Option<String>,
     pub channel_id: Option<String>,
     pub integration_id: Option<Uuid>,
+    pub url: Option<String>,
+    pub signing_secret: Option<String>,
+    pub signing_secret_nonce: Option<String>,
 }
 
 /// Fetch all delivery targets for a specific alert by its ID and project.
@@ -82,7 +85,8 @@ pub async fn get_targets_for_alert(
     project_id: &Uuid,
 ) -> anyhow::Result<Vec<AlertDeliveryTarget>> {
     let targets = sqlx::query_as::<_, AlertDeliveryTarget>(
-        "SELECT at.id, at.type, at.email, at.channel_id, at.integration_id
+        "SELECT at.id, at.type, at.email, at.channel_id, at.integration_id,
+            at.url, at.signing_secret, at.signing_secret_nonce
          FROM alert_targets at
          JOIN alerts a ON a.id = at.alert_id
          WHERE at.alert_id = $1 AND a.project_id = $2",
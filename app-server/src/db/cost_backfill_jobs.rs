@@ -0,0 +1,171 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+pub const COST_BACKFILL_STATUS_RUNNING: &str = "running";
+pub const COST_BACKFILL_STATUS_COMPLETED: &str = "completed";
+pub const COST_BACKFILL_STATUS_FAILED: &str = "failed";
+
+/// A `running` job whose row has not been touched for this long has lost its
+/// message chain (e.g. the worker died mid-batch) and may be resumed. Must
+/// exceed the consumer's per-job lock TTL so a live batch is never resumed.
+pub const COST_BACKFILL_STALE_AFTER_MINUTES: i32 = 30;
+
+/// A cost recomputation job over a project's historical LLM spans.
+///
+/// `cursor_start_time` / `cursor_span_id` point at the last span of the last
+/// committed batch, so a failed or stalled job resumes right after it.
+#[derive(FromRow, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CostBackfillJob {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Only spans of this model are recomputed; `None` means all models.
+    pub model: Option<String>,
+    pub status: String,
+    pub cursor_start_time: Option<DateTime<Utc>>,
+    pub cursor_span_id: Option<Uuid>,
+    pub processed_spans: i64,
+    pub updated_spans: i64,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+const COST_BACKFILL_JOB_COLUMNS: &str = "id,
+    project_id,
+    start_time,
+    end_time,
+    model,
+    status,
+    cursor_start_time,
+    cursor_span_id,
+    processed_spans,
+    updated_spans,
+    error,
+    created_at,
+    updated_at";
+
+pub async fn create_cost_backfill_job(
+    pool: &PgPool,
+    project_id: Uuid,
+    start_time: DateTime<Utc>,
+    end_time: DateTime<Utc>,
+    model: Option<&str>,
+) -> Result<CostBackfillJob> {
+    let job = sqlx::query_as::<_, CostBackfillJob>(&format!(
+        "INSERT INTO cost_backfill_jobs (project_id, start_time, end_time, model, status)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING {COST_BACKFILL_JOB_COLUMNS}"
+    ))
+    .bind(project_id)
+    .bind(start_time)
+    .bind(end_time)
+    .bind(model)
+    .bind(COST_BACKFILL_STATUS_RUNNING)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(job)
+}
+
+pub async fn get_cost_backfill_job(
+    pool: &PgPool,
+    project_id: Uuid,
+    job_id: Uuid,
+) -> Result<Option<CostBackfillJob>> {
+    let job = sqlx::query_as::<_, CostBackfillJob>(&format!(
+        "SELECT {COST_BACKFILL_JOB_COLUMNS}
+        FROM cost_backfill_jobs
+        WHERE id = $1 AND project_id = $2"
+    ))
+    .bind(job_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(job)
+}
+
+/// Move the cursor past a committed batch and add its counts to the totals.
+pub async fn advance_cost_backfill_job(
+    pool: &PgPool,
+    job_id: Uuid,
+    cursor_start_time: DateTime<Utc>,
+    cursor_span_id: Uuid,
+    processed_spans: i64,
+    updated_spans: i64,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE cost_backfill_jobs
+        SET cursor_start_time = $2,
+            cursor_span_id = $3,
+            processed_spans = processed_spans + $4,
+            updated_spans = updated_spans + $5,
+            updated_at = now()
+        WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(cursor_start_time)
+    .bind(cursor_span_id)
+    .bind(processed_spans)
+    .bind(updated_spans)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn set_cost_backfill_job_status(
+    pool: &PgPool,
+    job_id: Uuid,
+    status: &str,
+    error: Option<&str>,
+) -> Result<()> {
+    sqlx::query(
+        "UPDATE cost_backfill_jobs
+        SET status = $2, error = $3, updated_at = now()
+        WHERE id = $1",
+    )
+    .bind(job_id)
+    .bind(status)
+    .bind(error)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Put a failed or stalled job back into the running state. Returns the job
+/// if it could be resumed, `None` otherwise. Resuming bumps `updated_at`, so a
+/// stalled job is not resumed twice and driven by two message chains.
+pub async fn resume_cost_backfill_job(
+    pool: &PgPool,
+    project_id: Uuid,
+    job_id: Uuid,
+) -> Result<Option<CostBackfillJob>> {
+    let job = sqlx::query_as::<_, CostBackfillJob>(&format!(
+        "UPDATE cost_backfill_jobs
+        SET status = $3, error = NULL, updated_at = now()
+        WHERE id = $1
+          AND project_id = $2
+          AND (
+            status = $4
+            OR (status = $3 AND updated_at < now() - make_interval(mins => $5))
+          )
+        RETURNING {COST_BACKFILL_JOB_COLUMNS}"
+    ))
+    .bind(job_id)
+    .bind(project_id)
+    .bind(COST_BACKFILL_STATUS_RUNNING)
+    .bind(COST_BACKFILL_STATUS_FAILED)
+    .bind(COST_BACKFILL_STALE_AFTER_MINUTES)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(job)
+}
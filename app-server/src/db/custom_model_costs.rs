@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
@@ -13,26 +14,29 @@ pub struct DBCustomModelCost {
     #[allow(unused)]
     pub model: String,
     pub costs: Value,
+    pub effective_from: DateTime<Utc>,
 }
 
-/// Look up a single custom model cost for a project by exact provider + model name.
+/// Look up all price versions of a custom model cost for a project by exact
+/// provider + model name, oldest first.
 /// The provider column is NOT NULL with DEFAULT '' in the DB, so we use simple equality.
-pub async fn get_custom_model_cost(
+pub async fn get_custom_model_cost_versions(
     pool: &PgPool,
     project_id: &Uuid,
     provider: &str,
     model: &str,
-) -> anyhow::Result<Option<DBCustomModelCost>> {
-    let row = sqlx::query_as::<_, DBCustomModelCost>(
-        "SELECT id, project_id, provider, model, costs
+) -> anyhow::Result<Vec<DBCustomModelCost>> {
+    let rows = sqlx::query_as::<_, DBCustomModelCost>(
+        "SELECT id, project_id, provider, model, costs, effective_from
          FROM custom_model_costs
-         WHERE project_id = $1 AND provider = $2 AND model = $3",
+         WHERE project_id = $1 AND provider = $2 AND model = $3
+         ORDER BY effective_from ASC",
     )
     .bind(project_id)
     .bind(provider)
     .bind(model)
-    .fetch_optional(pool)
+    .fetch_all(pool)
     .await?;
 
-    Ok(row)
+    Ok(rows)
 }
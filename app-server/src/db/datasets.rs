@@ -106,3 +106,101 @@ pub async fn get_parquet_path(
 
     Ok(parquet_path)
 }
+
+/// Registers an exported file so it can be downloaded by `name` like the
+/// parquets generated by export jobs.
+pub async fn insert_dataset_export(
+    pool: &PgPool,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    job_id: Uuid,
+    name: &str,
+    path: &str,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO dataset_parquets (project_id, dataset_id, job_id, name, parquet_path)
+        VALUES ($1, $2, $3, $4, $5)",
+    )
+    .bind(project_id)
+    .bind(dataset_id)
+    .bind(job_id)
+    .bind(name)
+    .bind(path)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[derive(Serialize, FromRow, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatasetVersion {
+    pub id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub dataset_id: Uuid,
+    pub project_id: Uuid,
+    pub name: Option<String>,
+    pub datapoint_count: i64,
+}
+
+pub async fn create_dataset_version(
+    pool: &PgPool,
+    id: Uuid,
+    project_id: Uuid,
+    dataset_id: Uuid,
+    name: Option<&str>,
+    datapoint_count: i64,
+) -> Result<DatasetVersion> {
+    let version = sqlx::query_as::<_, DatasetVersion>(
+        "INSERT INTO dataset_versions (id, project_id, dataset_id, name, datapoint_count)
+        VALUES ($1, $2, $3, $4, $5)
+        RETURNING id, created_at, dataset_id, project_id, name, datapoint_count",
+    )
+    .bind(id)
+    .bind(project_id)
+    .bind(dataset_id)
+    .bind(name)
+    .bind(datapoint_count)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(version)
+}
+
+pub async fn get_dataset_version(
+    pool: &PgPool,
+    project_id: Uuid,
+    version_id: Uuid,
+) -> Result<Option<DatasetVersion>> {
+    let version = sqlx::query_as::<_, DatasetVersion>(
+        "SELECT id, created_at, dataset_id, project_id, name, datapoint_count
+        FROM dataset_versions
+        WHERE id = $1 AND project_id = $2",
+    )
+    .bind(version_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(version)
+}
+
+/// Versions of a dataset, newest first.
+pub async fn get_dataset_versions(
+    pool: &PgPool,
+    project_id: Uuid,
+    dataset_id: Uuid,
+) -> Result<Vec<DatasetVersion>> {
+    let versions = sqlx::query_as::<_, DatasetVersion>(
+        "SELECT id, created_at, dataset_id, project_id, name, datapoint_count
+        FROM dataset_versions
+        WHERE project_id = $1 AND dataset_id = $2
+        ORDER BY created_at DESC",
+    )
+    .bind(project_id)
+    .bind(dataset_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(versions)
+}
@@ -19,6 +19,8 @@ pub struct Evaluation {
     /// Conceptually, evaluations with different group ids are used to test different features.
     pub group_id: String,
     pub metadata: Option<Value>,
+    /// The dataset version the evaluation ran on, if it declared one
+    pub dataset_version_id: Option<Uuid>,
 }
 
 #[derive(FromRow)]
@@ -32,22 +34,25 @@ pub async fn create_evaluation(
     project_id: Uuid,
     group_id: &str,
     metadata: &Option<Value>,
+    dataset_version_id: Option<Uuid>,
 ) -> Result<Evaluation> {
     let evaluation = sqlx::query_as::<_, Evaluation>(
-        "INSERT INTO evaluations (name, project_id, group_id, metadata)
-        VALUES ($1, $2, $3, $4)
+        "INSERT INTO evaluations (name, project_id, group_id, metadata, dataset_version_id)
+        VALUES ($1, $2, $3, $4, $5)
         RETURNING
             id,
             created_at,
             name,
             project_id,
             group_id,
-            metadata",
+            metadata,
+            dataset_version_id",
     )
     .bind(name)
     .bind(project_id)
     .bind(group_id)
     .bind(metadata)
+    .bind(dataset_version_id)
     .fetch_one(pool)
     .await?;
 
@@ -90,3 +95,21 @@ pub async fn is_shared_evaluation(
 
     Ok(shared_eval)
 }
+
+pub async fn get_evaluation(
+    pool: &PgPool,
+    project_id: Uuid,
+    evaluation_id: Uuid,
+) -> Result<Option<Evaluation>> {
+    let evaluation = sqlx::query_as::<_, Evaluation>(
+        "SELECT id, created_at, name, project_id, group_id, metadata, dataset_version_id
+         FROM evaluations
+         WHERE id = $1 AND project_id = $2",
+    )
+    .bind(evaluation_id)
+    .bind(project_id)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(evaluation)
+}
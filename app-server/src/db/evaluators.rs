@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+#[derive(FromRow, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluator {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub evaluator_type: String,
+    pub definition: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+pub async fn get_evaluators_by_type(
+    pool: &PgPool,
+    project_id: &Uuid,
+    evaluator_type: &str,
+) -> anyhow::Result<Vec<Evaluator>> {
+    let evaluators = sqlx::query_as::<_, Evaluator>(
+        "SELECT id, project_id, name, evaluator_type, COALESCE(definition, '{}'::jsonb) AS definition,
+            created_at
+         FROM evaluators
+         WHERE project_id = $1 AND evaluator_type = $2
+         ORDER BY created_at",
+    )
+    .bind(project_id)
+    .bind(evaluator_type)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(evaluators)
+}
+
+pub async fn has_evaluators_of_type(
+    pool: &PgPool,
+    project_id: &Uuid,
+    evaluator_type: &str,
+) -> anyhow::Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+            SELECT 1 FROM evaluators WHERE project_id = $1 AND evaluator_type = $2
+        )",
+    )
+    .bind(project_id)
+    .bind(evaluator_type)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
+pub async fn create_evaluator(
+    pool: &PgPool,
+    project_id: &Uuid,
+    name: &str,
+    evaluator_type: &str,
+    definition: &Value,
+) -> anyhow::Result<Evaluator> {
+    let evaluator = sqlx::query_as::<_, Evaluator>(
+        "INSERT INTO evaluators (project_id, name, evaluator_type, definition)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id, project_id, name, evaluator_type,
+            COALESCE(definition, '{}'::jsonb) AS definition, created_at",
+    )
+    .bind(project_id)
+    .bind(name)
+    .bind(evaluator_type)
+    .bind(definition)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(evaluator)
+}
+
+/// Returns whether an evaluator of `evaluator_type` was deleted.
+pub async fn delete_evaluator(
+    pool: &PgPool,
+    project_id: &Uuid,
+    evaluator_id: &Uuid,
+    evaluator_type: &str,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query(
+        "DELETE FROM evaluators WHERE id = $1 AND project_id = $2 AND evaluator_type = $3",
+    )
+    .bind(evaluator_id)
+    .bind(project_id)
+    .bind(evaluator_type)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected() > 0)
+}
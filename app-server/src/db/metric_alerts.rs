@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+use crate::notifications::AlertType;
+
+/// A metric-threshold alert joined with its evaluation state. The state columns
+/// are `None` until the alert has been evaluated once (or snoozed).
+#[derive(Debug, Clone, FromRow)]
+pub struct MetricAlert {
+    pub id: Uuid,
+    pub name: String,
+    pub project_id: Uuid,
+    pub workspace_id: Uuid,
+    /// The threshold rule, parsed by the evaluator so one malformed alert
+    /// doesn't fail the whole batch.
+    pub metadata: serde_json::Value,
+    pub firing: Option<bool>,
+    pub last_notified_at: Option<DateTime<Utc>>,
+    pub snoozed_until: Option<DateTime<Utc>>,
+}
+
+pub async fn get_metric_alerts(pool: &PgPool) -> anyhow::Result<Vec<MetricAlert>> {
+    let alerts = sqlx::query_as::<_, MetricAlert>(
+        "SELECT a.id, a.name, a.project_id, p.workspace_id, a.metadata,
+            s.firing, s.last_notified_at, s.snoozed_until
+         FROM alerts a
+         JOIN projects p ON p.id = a.project_id
+         LEFT JOIN metric_alert_states s ON s.alert_id = a.id
+         WHERE a.type = $1",
+    )
+    .bind(AlertType::MetricThreshold.as_str())
+    .fetch_all(pool)
+    .await?;
+
+    Ok(alerts)
+}
+
+/// Record the outcome of one evaluation. `notified_at` is only set when a
+/// notification went out; otherwise the previous value is kept. `snoozed_until`
+/// is owned by the frontend and never touched here.
+pub async fn upsert_metric_alert_state(
+    pool: &PgPool,
+    alert_id: Uuid,
+    project_id: Uuid,
+    firing: bool,
+    value: Option<f64>,
+    evaluated_at: DateTime<Utc>,
+    notified_at: Option<DateTime<Utc>>,
+) -> anyhow::Result<()> {
+    sqlx::query(
+        "INSERT INTO metric_alert_states
+            (alert_id, project_id, firing, last_value, last_evaluated_at, last_notified_at)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (alert_id) DO UPDATE SET
+            firing = EXCLUDED.firing,
+            last_value = EXCLUDED.last_value,
+            last_evaluated_at = EXCLUDED.last_evaluated_at,
+            last_notified_at = COALESCE(
+                EXCLUDED.last_notified_at,
+                metric_alert_states.last_notified_at
+            )",
+    )
+    .bind(alert_id)
+    .bind(project_id)
+    .bind(firing)
+    .bind(value)
+    .bind(evaluated_at)
+    .bind(notified_at)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
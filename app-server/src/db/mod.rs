@@ -5,13 +5,19 @@ use crate::env;
 
 pub mod agents;
 pub mod alert_targets;
+pub mod cost_backfill_jobs;
 pub mod custom_model_costs;
 pub mod datasets;
 pub mod debugger_sessions;
 pub mod evaluations;
+pub mod evaluators;
 pub mod events;
 pub mod labeling_queues;
+pub mod metric_alerts;
 pub mod model_costs;
+pub mod notification_dead_letters;
+pub mod pii_vault;
+pub mod pricing_discounts;
 pub mod project_api_keys;
 pub mod projects;
 pub mod reports;
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::{FromRow, PgPool};
 
@@ -8,18 +9,27 @@ use sqlx::{FromRow, PgPool};
 pub struct DBModelCost {
     pub model: String,
     pub costs: Value,
+    pub effective_from: DateTime<Utc>,
 }
 
+/// All price versions for each of `models`, oldest first.
 pub async fn get_model_costs_batch(
     pool: &PgPool,
     models: &[String],
-) -> anyhow::Result<HashMap<String, DBModelCost>> {
+) -> anyhow::Result<HashMap<String, Vec<DBModelCost>>> {
     let rows = sqlx::query_as::<_, DBModelCost>(
-        "SELECT model, costs FROM model_costs WHERE model = ANY($1)",
+        "SELECT model, costs, effective_from
+         FROM model_costs
+         WHERE model = ANY($1)
+         ORDER BY effective_from ASC",
     )
     .bind(models)
     .fetch_all(pool)
     .await?;
 
-    Ok(rows.into_iter().map(|r| (r.model.clone(), r)).collect())
+    let mut result: HashMap<String, Vec<DBModelCost>> = HashMap::new();
+    for row in rows {
+        result.entry(row.model.clone()).or_default().push(row);
+    }
+    Ok(result)
 }
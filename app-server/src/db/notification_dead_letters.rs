@@ -0,0 +1,40 @@
+use sqlx::PgPool;
+use uuid::Uuid;
+
+/// A notification delivery that exhausted its retries. The request body is kept
+/// verbatim so the delivery can be inspected and replayed.
+#[derive(Debug, Clone)]
+pub struct NewNotificationDeadLetter<'a> {
+    pub workspace_id: Uuid,
+    pub project_id: Option<Uuid>,
+    pub target_id: Uuid,
+    pub target_type: String,
+    pub url: &'a str,
+    pub payload: &'a serde_json::Value,
+    pub error: String,
+    pub attempts: i32,
+}
+
+pub async fn insert_dead_letter(
+    pool: &PgPool,
+    dead_letter: &NewNotificationDeadLetter<'_>,
+) -> anyhow::Result<Uuid> {
+    let id = sqlx::query_scalar::<_, Uuid>(
+        "INSERT INTO notification_dead_letters
+            (workspace_id, project_id, target_id, target_type, url, payload, error, attempts)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+         RETURNING id",
+    )
+    .bind(dead_letter.workspace_id)
+    .bind(dead_letter.project_id)
+    .bind(dead_letter.target_id)
+    .bind(&dead_letter.target_type)
+    .bind(dead_letter.url)
+    .bind(dead_letter.payload)
+    .bind(&dead_letter.error)
+    .bind(dead_letter.attempts)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(id)
+}
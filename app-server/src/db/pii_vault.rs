@@ -0,0 +1,89 @@
+use anyhow::Result;
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// One encrypted original behind a pseudonym. `nonce`/`value` are the hex
+/// pair produced by `data_plane::crypto::encrypt`.
+#[derive(FromRow, Debug, Clone)]
+pub struct PiiVaultEntry {
+    pub pseudonym: String,
+    pub label: String,
+    pub nonce: String,
+    pub value: String,
+}
+
+/// Insert entries, keeping the first stored original for a pseudonym, and
+/// return the pseudonyms that were newly inserted. The rest already had an
+/// entry, which the caller must check holds the same original.
+pub async fn insert_pii_vault_entries(
+    pool: &PgPool,
+    project_id: Uuid,
+    entries: &[PiiVaultEntry],
+) -> Result<Vec<String>> {
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let pseudonyms: Vec<&str> = entries.iter().map(|e| e.pseudonym.as_str()).collect();
+    let labels: Vec<&str> = entries.iter().map(|e| e.label.as_str()).collect();
+    let nonces: Vec<&str> = entries.iter().map(|e| e.nonce.as_str()).collect();
+    let values: Vec<&str> = entries.iter().map(|e| e.value.as_str()).collect();
+
+    let inserted = sqlx::query_scalar::<_, String>(
+        "INSERT INTO pii_vault_entries (project_id, pseudonym, label, nonce, value)
+        SELECT $1, pseudonym, label, nonce, value
+        FROM UNNEST($2::text[], $3::text[], $4::text[], $5::text[])
+            AS e(pseudonym, label, nonce, value)
+        ON CONFLICT (project_id, pseudonym) DO NOTHING
+        RETURNING pseudonym",
+    )
+    .bind(project_id)
+    .bind(&pseudonyms)
+    .bind(&labels)
+    .bind(&nonces)
+    .bind(&values)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(inserted)
+}
+
+pub async fn get_pii_vault_entries(
+    pool: &PgPool,
+    project_id: Uuid,
+    pseudonyms: &[String],
+) -> Result<Vec<PiiVaultEntry>> {
+    let entries = sqlx::query_as::<_, PiiVaultEntry>(
+        "SELECT pseudonym, label, nonce, value
+        FROM pii_vault_entries
+        WHERE project_id = $1 AND pseudonym = ANY($2)",
+    )
+    .bind(project_id)
+    .bind(pseudonyms)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(entries)
+}
+
+/// Record a reveal: who asked for which pseudonyms and how many resolved.
+pub async fn insert_pii_reveal_event(
+    pool: &PgPool,
+    project_id: Uuid,
+    user_id: Uuid,
+    pseudonyms: &[String],
+    revealed_count: usize,
+) -> Result<()> {
+    sqlx::query(
+        "INSERT INTO pii_reveal_events (project_id, user_id, pseudonyms, revealed_count)
+        VALUES ($1, $2, $3, $4)",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .bind(pseudonyms)
+    .bind(revealed_count as i32)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,36 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, PgPool};
+use uuid::Uuid;
+
+/// A negotiated discount on list model prices for a workspace.
+#[derive(FromRow, Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspacePricingDiscount {
+    /// Provider the discount applies to, or empty for all providers.
+    pub provider: String,
+    /// Factor applied to the list price, e.g. `0.8` for a 20% discount.
+    pub multiplier: f64,
+    pub effective_from: DateTime<Utc>,
+}
+
+pub async fn get_pricing_discounts_for_project(
+    pool: &PgPool,
+    project_id: &Uuid,
+) -> anyhow::Result<Vec<WorkspacePricingDiscount>> {
+    let rows = sqlx::query_as::<_, WorkspacePricingDiscount>(
+        "SELECT
+            workspace_pricing_discounts.provider,
+            workspace_pricing_discounts.multiplier,
+            workspace_pricing_discounts.effective_from
+        FROM workspace_pricing_discounts
+        JOIN projects ON projects.workspace_id = workspace_pricing_discounts.workspace_id
+        WHERE projects.id = $1
+        ORDER BY workspace_pricing_discounts.effective_from ASC",
+    )
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(rows)
+}
@@ -16,6 +16,16 @@ pub struct ProjectApiKey {
     // deserializable (they decode to `None` = never expires).
     #[serde(default)]
     pub expires_at: Option<DateTime<Utc>>,
+    /// Scope names (see `project_api_keys::restrictions::ApiKeyScope`).
+    /// Empty = unrestricted, which is every key created before scopes existed.
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    /// CIDRs requests must come from. Empty = any address.
+    #[serde(default)]
+    pub allowed_cidrs: Vec<String>,
+    /// Per-key request budget on top of the project-wide rate limits.
+    #[serde(default)]
+    pub rate_limit_per_minute: Option<i32>,
 }
 
 pub async fn get_api_key(pool: &PgPool, hash: &String) -> Result<ProjectApiKey> {
@@ -27,7 +37,10 @@ pub async fn get_api_key(pool: &PgPool, hash: &String) -> Result<ProjectApiKey>
             project_api_keys.id,
             project_api_keys.shorthand,
             project_api_keys.is_ingest_only,
-            project_api_keys.expires_at
+            project_api_keys.expires_at,
+            project_api_keys.scopes,
+            project_api_keys.allowed_cidrs,
+            project_api_keys.rate_limit_per_minute
         FROM
             project_api_keys
         WHERE
@@ -1,9 +1,13 @@
+use std::collections::HashMap;
+
 use anyhow::Result;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow, PgPool, types::Json};
 use uuid::Uuid;
 
+use crate::traces::sampling::TraceSamplingRule;
+
 /// Read-only view of `projects.settings` JSONB. Writes happen exclusively
 /// from the Next.js side; the Rust app-server only deserializes. New
 /// settings = add a `#[serde(default)]` field — no migration. Unknown keys
@@ -15,6 +19,72 @@ pub struct ProjectSettings {
     /// PII redaction toggle. Enabling routes every span on this project
     /// through the pii-redactor before storage. Pro-tier gated frontend-side.
     pub remove_pii: bool,
+    /// How the pii-redactor rewrites detected PII for this project.
+    pub pii_redaction_mode: PiiRedactionMode,
+    /// Pseudonym mode only: keep each pseudonym's original encrypted in
+    /// `pii_vault_entries` so authorized users can reveal it.
+    pub pii_vault_enabled: bool,
+    /// Which labels, confidences and fields the pii-redactor redacts.
+    pub pii_redaction_policy: PiiRedactionPolicy,
+    /// Ordered tail-based sampling rules. Empty means every trace is kept
+    /// and spans are never buffered. See `traces::sampling`.
+    pub trace_sampling_rules: Vec<TraceSamplingRule>,
+}
+
+#[derive(Deserialize, Serialize, Default, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PiiRedactionMode {
+    /// `[REDACTED_{LABEL}]` for every detected value.
+    #[default]
+    Placeholder,
+    /// A per-project keyed hash, so equal values get equal pseudonyms.
+    Pseudonym,
+}
+
+/// Narrows what gets redacted on an opted-in project. The defaults redact
+/// every label in input, output and shared content, as before policies
+/// existed. Everything but `fields` is forwarded on the `Redact` RPC; see
+/// `pii-redactor/proto/pii_redactor.proto` for the exact semantics.
+#[derive(Deserialize, Serialize, Default, Clone, Debug)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PiiRedactionPolicy {
+    /// Labels to redact (`private_email`, `credit_card`, ...). Empty = all.
+    pub labels: Vec<String>,
+    /// Per-label minimum model confidence, 0-1.
+    pub min_confidence: HashMap<String, f32>,
+    /// Threshold for labels missing from `min_confidence`.
+    pub default_min_confidence: f32,
+    /// Field-path deny list on top of the redactor's default skip keys,
+    /// e.g. `metadata/*/email`.
+    pub skip_paths: Vec<String>,
+    /// Field-path allow list. Empty = every field.
+    pub redact_paths: Vec<String>,
+    pub fields: PiiRedactionFields,
+}
+
+/// Which span buffers are sent to the redactor.
+#[derive(Deserialize, Serialize, Clone, Copy, Debug)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PiiRedactionFields {
+    /// `span.input` and the trace-new input messages indexed in Quickwit.
+    pub input: bool,
+    /// `span.output` and the trace-new output messages.
+    pub output: bool,
+    /// Span attributes, minus the structural ones (`lmnr.*`, model names).
+    pub attributes: bool,
+    /// Deduplicated message rows bound for ClickHouse `shared_content`.
+    pub shared_content: bool,
+}
+
+impl Default for PiiRedactionFields {
+    fn default() -> Self {
+        Self {
+            input: true,
+            output: true,
+            attributes: false,
+            shared_content: true,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, FromRow, Clone)]
@@ -198,6 +268,27 @@ pub async fn project_has_member(
     Ok(exists)
 }
 
+/// Whether the user is an owner or admin of the project's workspace.
+pub async fn project_has_admin(
+    pool: &PgPool,
+    user_id: &Uuid,
+    project_id: &Uuid,
+) -> anyhow::Result<bool> {
+    let exists = sqlx::query_scalar::<_, bool>(
+        "SELECT EXISTS (
+            SELECT 1 FROM projects p
+            JOIN members_of_workspaces m ON m.workspace_id = p.workspace_id
+            WHERE p.id = $1 AND m.user_id = $2 AND m.member_role IN ('owner', 'admin')
+        )",
+    )
+    .bind(project_id)
+    .bind(user_id)
+    .fetch_one(pool)
+    .await?;
+
+    Ok(exists)
+}
+
 /// A project the user can access, with its owning workspace. Backs the CLI
 /// `GET /v1/cli/projects` discovery endpoint.
 #[derive(Serialize, FromRow, Clone)]
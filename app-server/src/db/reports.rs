@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use serde::Serialize;
 use sqlx::{FromRow, PgPool};
 use uuid::Uuid;
 
@@ -38,16 +39,20 @@ pub struct ReportTarget {
     pub email: Option<String>,
     pub channel_id: Option<String>,
     pub integration_id: Option<Uuid>,
+    pub url: Option<String>,
+    pub signing_secret: Option<String>,
+    pub signing_secret_nonce: Option<String>,
 }
 
-/// Fetch all report targets (EMAIL and SLACK) for a given report in a single query.
+/// Fetch all report targets (email, Slack and webhook) for a given report in a single query.
 pub async fn get_report_targets(
     pool: &PgPool,
     report_id: &Uuid,
     workspace_id: &Uuid,
 ) -> anyhow::Result<Vec<ReportTarget>> {
     let targets = sqlx::query_as::<_, ReportTarget>(
-        "SELECT rt.id, rt.type, rt.email, rt.channel_id, rt.integration_id
+        "SELECT rt.id, rt.type, rt.email, rt.channel_id, rt.integration_id,
+            rt.url, rt.signing_secret, rt.signing_secret_nonce
          FROM report_targets rt
          JOIN reports r ON rt.report_id = r.id
          WHERE rt.report_id = $1 AND r.workspace_id = $2",
@@ -84,3 +89,163 @@ pub async fn get_signals_for_workspace(
 
     Ok(signals)
 }
+
+/// A `CUSTOM_QUERY` report with the name of the project it runs against.
+#[derive(FromRow, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomReport {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub project_id: Uuid,
+    pub project_name: String,
+    pub name: String,
+    pub weekdays: Vec<i32>,
+    pub hour: i32,
+    pub metadata: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
+
+const CUSTOM_REPORT_COLUMNS: &str = "r.id, r.workspace_id, r.project_id, p.name AS project_name,
+    COALESCE(r.name, '') AS name, r.weekdays, r.hour, r.metadata, r.created_at";
+
+pub async fn get_custom_report(
+    pool: &PgPool,
+    report_id: &Uuid,
+    report_type: &str,
+) -> anyhow::Result<Option<CustomReport>> {
+    let report = sqlx::query_as::<_, CustomReport>(&format!(
+        "SELECT {CUSTOM_REPORT_COLUMNS}
+         FROM reports r
+         JOIN projects p ON r.project_id = p.id
+         WHERE r.id = $1 AND r.type = $2"
+    ))
+    .bind(report_id)
+    .bind(report_type)
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(report)
+}
+
+pub async fn get_custom_reports_for_project(
+    pool: &PgPool,
+    project_id: &Uuid,
+    report_type: &str,
+) -> anyhow::Result<Vec<CustomReport>> {
+    let reports = sqlx::query_as::<_, CustomReport>(&format!(
+        "SELECT {CUSTOM_REPORT_COLUMNS}
+         FROM reports r
+         JOIN projects p ON r.project_id = p.id
+         WHERE r.project_id = $1 AND r.type = $2
+         ORDER BY r.created_at"
+    ))
+    .bind(project_id)
+    .bind(report_type)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(reports)
+}
+
+pub struct NewReportTarget {
+    pub r#type: String,
+    pub email: Option<String>,
+    pub url: Option<String>,
+}
+
+pub struct NewCustomReport<'a> {
+    pub project_id: Uuid,
+    pub report_type: &'a str,
+    pub name: &'a str,
+    pub weekdays: &'a [i32],
+    pub hour: i32,
+    pub metadata: serde_json::Value,
+    pub targets: &'a [NewReportTarget],
+}
+
+/// Creates a custom report and its targets in one transaction. Returns `None`
+/// if the project does not exist.
+pub async fn create_custom_report(
+    pool: &PgPool,
+    report: NewCustomReport<'_>,
+) -> anyhow::Result<Option<Uuid>> {
+    let mut tx = pool.begin().await?;
+
+    let created = sqlx::query_as::<_, (Uuid, Uuid)>(
+        "INSERT INTO reports (workspace_id, project_id, type, name, weekdays, hour, metadata)
+         SELECT p.workspace_id, p.id, $2, $3, $4, $5, $6
+         FROM projects p
+         WHERE p.id = $1
+         RETURNING id, workspace_id",
+    )
+    .bind(report.project_id)
+    .bind(report.report_type)
+    .bind(report.name)
+    .bind(report.weekdays)
+    .bind(report.hour)
+    .bind(&report.metadata)
+    .fetch_optional(&mut *tx)
+    .await?;
+
+    let Some((report_id, workspace_id)) = created else {
+        return Ok(None);
+    };
+
+    for target in report.targets {
+        sqlx::query(
+            "INSERT INTO report_targets (workspace_id, report_id, type, email, url)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(workspace_id)
+        .bind(report_id)
+        .bind(&target.r#type)
+        .bind(&target.email)
+        .bind(&target.url)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(Some(report_id))
+}
+
+/// Returns whether a report of `report_type` was deleted. Targets cascade.
+pub async fn delete_custom_report(
+    pool: &PgPool,
+    project_id: &Uuid,
+    report_id: &Uuid,
+    report_type: &str,
+) -> anyhow::Result<bool> {
+    let result = sqlx::query("DELETE FROM reports WHERE id = $1 AND project_id = $2 AND type = $3")
+        .bind(report_id)
+        .bind(project_id)
+        .bind(report_type)
+        .execute(pool)
+        .await?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Emails, out of `emails`, that do not belong to a member of the project's workspace.
+pub async fn get_non_member_emails(
+    pool: &PgPool,
+    project_id: &Uuid,
+    emails: &[String],
+) -> anyhow::Result<Vec<String>> {
+    let non_members = sqlx::query_scalar::<_, String>(
+        "SELECT e.email FROM UNNEST($2::text[]) AS e(email)
+         WHERE NOT EXISTS (
+            SELECT 1 FROM users u
+            JOIN members_of_workspaces m ON m.user_id = u.id
+            JOIN projects p ON p.workspace_id = m.workspace_id
+            WHERE p.id = $1 AND u.email = e.email
+         )",
+    )
+    .bind(project_id)
+    .bind(emails)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(non_members)
+}
@@ -6,7 +6,12 @@ use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::{db::events::Event, traces::spans::SpanAttributes};
+use crate::{
+    db::{events::Event, utils::span_id_to_uuid},
+    opentelemetry_proto::opentelemetry_proto_trace_v1::span::Link as OtelLink,
+    traces::{spans::SpanAttributes, utils::convert_any_value_to_json_value},
+    utils::estimate_json_size,
+};
 
 // Temporary measure for backwards compatibility with old spans
 fn deserialize_null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
@@ -90,6 +95,43 @@ pub struct Span {
     pub output_url: Option<String>,
     #[serde(default)]
     pub size_bytes: usize,
+    #[serde(default, deserialize_with = "deserialize_null_as_default")]
+    pub links: Vec<SpanLink>,
+}
+
+/// An OpenTelemetry span link: a causal reference to a span that is usually
+/// in another trace, e.g. the producer of a queue message or the agent that
+/// handed off to this one.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SpanLink {
+    pub trace_id: Uuid,
+    pub span_id: Uuid,
+    pub attributes: Value,
+}
+
+impl SpanLink {
+    /// `None` for links with a malformed trace or span id.
+    pub fn from_otel(link: OtelLink) -> Option<Self> {
+        let trace_id = Uuid::from_slice(&link.trace_id).ok()?;
+        if link.span_id.len() != 8 {
+            return None;
+        }
+        let attributes = link
+            .attributes
+            .into_iter()
+            .map(|kv| (kv.key, convert_any_value_to_json_value(kv.value)))
+            .collect::<serde_json::Map<String, Value>>();
+        Some(Self {
+            trace_id,
+            span_id: span_id_to_uuid(&link.span_id),
+            attributes: Value::Object(attributes),
+        })
+    }
+
+    pub fn estimate_size_bytes(&self) -> usize {
+        16 + 16 + estimate_json_size(&self.attributes)
+    }
 }
 
 #[cfg(test)]
@@ -171,6 +213,7 @@ mod tests {
             input: Some(json!("test input")),
             output: Some(json!("test output")),
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -394,6 +437,7 @@ mod tests {
             input: Some(json!("test input")),
             output: Some(json!("test output")),
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -652,6 +696,7 @@ mod tests {
             input: Some(json!("test input")),
             output: Some(json!("test output")),
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -762,4 +807,36 @@ mod tests {
             Some(&json!("node@23.3.0"))
         );
     }
+
+    #[test]
+    fn test_span_link_from_otel() {
+        use crate::opentelemetry_proto::opentelemetry_proto_common_v1::{
+            AnyValue, KeyValue, any_value::Value as OtelValue,
+        };
+
+        let trace_id = Uuid::new_v4();
+        let link = OtelLink {
+            trace_id: trace_id.as_bytes().to_vec(),
+            span_id: vec![0, 0, 0, 0, 0, 0, 0, 7],
+            attributes: vec![KeyValue {
+                key: "messaging.message.id".to_string(),
+                value: Some(AnyValue {
+                    value: Some(OtelValue::StringValue("msg-1".to_string())),
+                }),
+            }],
+            ..Default::default()
+        };
+
+        let link = SpanLink::from_otel(link).unwrap();
+        assert_eq!(link.trace_id, trace_id);
+        assert_eq!(link.span_id, span_id_to_uuid(&[0, 0, 0, 0, 0, 0, 0, 7]));
+        assert_eq!(link.attributes, json!({"messaging.message.id": "msg-1"}));
+
+        let malformed = OtelLink {
+            trace_id: vec![1, 2, 3],
+            span_id: vec![0; 8],
+            ..Default::default()
+        };
+        assert!(SpanLink::from_otel(malformed).is_none());
+    }
 }
@@ -6,6 +6,7 @@ use sqlx::PgPool;
 use tracing::instrument;
 use uuid::Uuid;
 
+use crate::ch::spans::TraceCosts;
 use crate::ch::traces::TraceAggregation;
 use crate::db::spans::Span;
 use crate::db::utils::{
@@ -290,9 +291,10 @@ pub async fn upsert_trace_statistics_batch(
                 root_span_output,
                 cache_read_input_tokens,
                 reasoning_tokens,
-                cache_creation_input_tokens
+                cache_creation_input_tokens,
+                sampling_decision
             )
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20, $21, $22, $23, $24, $25, $26, $27, $28)
             ON CONFLICT (project_id, id) DO UPDATE SET
                 start_time = LEAST(traces.start_time, EXCLUDED.start_time),
                 end_time = GREATEST(traces.end_time, EXCLUDED.end_time),
@@ -323,7 +325,9 @@ pub async fn upsert_trace_statistics_batch(
                 -- `||` operator merges span_names objects to keep unique names
                 span_names = COALESCE(traces.span_names || EXCLUDED.span_names, EXCLUDED.span_names, traces.span_names),
                 root_span_input = COALESCE(EXCLUDED.root_span_input, traces.root_span_input),
-                root_span_output = COALESCE(EXCLUDED.root_span_output, traces.root_span_output)
+                root_span_output = COALESCE(EXCLUDED.root_span_output, traces.root_span_output),
+                -- the decision is taken once per trace; later batches only follow it
+                sampling_decision = COALESCE(traces.sampling_decision, EXCLUDED.sampling_decision)
             RETURNING
                 id,
                 project_id,
@@ -381,6 +385,7 @@ pub async fn upsert_trace_statistics_batch(
         .bind(agg.cache_read_input_tokens)
         .bind(agg.reasoning_tokens)
         .bind(agg.cache_creation_input_tokens)
+        .bind(&agg.sampling_decision)
         .fetch_one(pool)
         .await?;
 
@@ -401,6 +406,73 @@ pub async fn trace_exists(pool: &PgPool, project_id: Uuid, trace_id: Uuid) -> Re
     Ok(exists)
 }
 
+/// Overwrite the cost totals of existing traces with values recomputed from
+/// their spans. Unlike `upsert_trace_statistics_batch`, this is not additive,
+/// so writing the same totals twice leaves the trace unchanged.
+///
+/// Traces that don't exist are skipped, so a trace deleted since its spans
+/// were read does not come back as a cost-only row.
+#[instrument(skip(pool, costs))]
+pub async fn set_trace_costs_batch(
+    pool: &PgPool,
+    project_id: Uuid,
+    costs: &[TraceCosts],
+) -> Result<Vec<Trace>> {
+    let ids = costs.iter().map(|c| c.trace_id).collect::<Vec<_>>();
+    let input_costs = costs.iter().map(|c| c.input_cost).collect::<Vec<_>>();
+    let output_costs = costs.iter().map(|c| c.output_cost).collect::<Vec<_>>();
+    let total_costs = costs.iter().map(|c| c.total_cost).collect::<Vec<_>>();
+
+    let traces = sqlx::query_as::<_, Trace>(
+        r#"
+        UPDATE traces
+        SET input_cost = c.new_input_cost,
+            output_cost = c.new_output_cost,
+            cost = c.new_cost
+        FROM UNNEST($1::uuid[], $2::float8[], $3::float8[], $4::float8[])
+            AS c(trace_id, new_input_cost, new_output_cost, new_cost)
+        WHERE traces.id = c.trace_id AND traces.project_id = $5
+        RETURNING
+            id,
+            project_id,
+            start_time,
+            end_time,
+            type,
+            top_span_id,
+            top_span_name,
+            top_span_type,
+            session_id,
+            metadata,
+            user_id,
+            input_token_count,
+            output_token_count,
+            total_token_count,
+            input_cost,
+            output_cost,
+            cost,
+            status,
+            tags,
+            num_spans,
+            has_browser_session,
+            span_names,
+            root_span_input,
+            root_span_output,
+            cache_read_input_tokens,
+            reasoning_tokens,
+            cache_creation_input_tokens
+        "#,
+    )
+    .bind(&ids)
+    .bind(&input_costs)
+    .bind(&output_costs)
+    .bind(&total_costs)
+    .bind(project_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(traces)
+}
+
 /// A post-factum metadata patch applied to an existing trace by the
 /// `POST /v1/traces/metadata` endpoint via a virtual metadata-only span.
 #[derive(Debug, Clone)]
@@ -589,6 +661,7 @@ mod tests {
             start_time: Utc::now(),
             end_time: Utc::now(),
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -5,16 +5,22 @@
 //! and the read logic stays in `llm/`. `SIGNALS_ALWAYS_USE_REALTIME` is a
 //! boolean toggle with a `false` default.
 
-use super::{BoolEnv, NumEnv};
+use super::{BoolEnv, NumEnv, StringEnv};
 
-/// `openai` | `gemini` | `bedrock` | `mock`. The single provider switch.
+/// `openai` | `gemini` | `anthropic` | `bedrock` | `mock`. The single provider switch.
 pub const PROVIDER: &str = "LLM_PROVIDER";
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const PARSING_PROVIDER: &str = "SIGNALS_PARSING_LLM_PROVIDER";
-/// Shared single-provider API key (openai / gemini).
+/// Shared single-provider API key (openai / gemini / anthropic).
 pub const API_KEY: &str = "LLM_API_KEY";
 /// Optional OpenAI-compatible base URL override (provider-specific default).
+/// With `LLM_PROVIDER=openai` this may point at a self-hosted server (vLLM,
+/// Ollama, LM Studio), in which case `LLM_API_KEY` is optional.
 pub const BASE_URL: &str = "LLM_BASE_URL";
+/// `auto` | `native` | `json`: how the openai provider sends tools. `json`
+/// describes them in the prompt for servers without tool-call support; `auto`
+/// switches to it the first time the server rejects native tools.
+pub const TOOL_CALL_MODE: StringEnv = StringEnv::new("LLM_TOOL_CALL_MODE", "auto");
 /// Optional JSON map of default headers sent on every LLM request.
 pub const DEFAULT_HEADERS_JSON: &str = "LLM_DEFAULT_HEADERS_JSON";
 
@@ -23,6 +29,28 @@ pub const MODEL_SMALL: &str = "LLM_MODEL_SMALL";
 pub const MODEL_MEDIUM: &str = "LLM_MODEL_MEDIUM";
 pub const MODEL_LARGE: &str = "LLM_MODEL_LARGE";
 
+/// Comma-separated providers tried, in order, when the primary is exhausted or
+/// failing (e.g. `bedrock`). Only providers whose credentials are present
+/// initialize; since `LLM_API_KEY` belongs to `LLM_PROVIDER`, Bedrock is the
+/// usual secondary. Per-size variants override it for that size.
+pub const FALLBACK_PROVIDERS: &str = "LLM_FALLBACK_PROVIDERS";
+pub const FALLBACK_PROVIDERS_SMALL: &str = "LLM_FALLBACK_PROVIDERS_SMALL";
+pub const FALLBACK_PROVIDERS_MEDIUM: &str = "LLM_FALLBACK_PROVIDERS_MEDIUM";
+pub const FALLBACK_PROVIDERS_LARGE: &str = "LLM_FALLBACK_PROVIDERS_LARGE";
+
+/// Retries per provider on retryable errors, before failing over.
+pub const MAX_RETRIES: NumEnv<u32> = NumEnv::new("LLM_MAX_RETRIES", 2);
+pub const RETRY_BASE_DELAY_MS: NumEnv<u64> = NumEnv::new("LLM_RETRY_BASE_DELAY_MS", 500);
+pub const RETRY_MAX_DELAY_MS: NumEnv<u64> = NumEnv::new("LLM_RETRY_MAX_DELAY_MS", 8_000);
+
+/// Consecutive failures that open a provider's circuit, and how long it stays open.
+pub const CIRCUIT_FAILURE_THRESHOLD: NumEnv<u32> = NumEnv::new("LLM_CIRCUIT_FAILURE_THRESHOLD", 5);
+pub const CIRCUIT_COOLDOWN_SECS: NumEnv<u64> = NumEnv::new("LLM_CIRCUIT_COOLDOWN_SECS", 30);
+
+/// Internal project that receives one `llm_attempt` span per provider call
+/// made by `LlmClient`. Unset disables attempt tracing.
+pub const INTERNAL_PROJECT_ID: &str = "LLM_INTERNAL_PROJECT_ID";
+
 /// Force the realtime signal path even when the provider supports batch.
 #[cfg_attr(not(feature = "signals"), allow(dead_code))]
 pub const ALWAYS_USE_REALTIME: BoolEnv = BoolEnv::new("SIGNALS_ALWAYS_USE_REALTIME", false);
@@ -1,9 +1,26 @@
 //! Public-facing URLs used to build user-clickable links in notifications and
-//! CLI auth. No static default (defaults are context-specific at the call
-//! site), so bare names.
+//! CLI auth, plus delivery tuning for webhook-style targets. The URLs have no
+//! static default (defaults are context-specific at the call site), so they
+//! are bare names.
+
+use super::{BoolEnv, NumEnv};
 
 /// Frontend public URL — self-hosted instances point links at their own host.
 pub const NEXT_PUBLIC_URL: &str = "NEXT_PUBLIC_URL";
 /// Internal URL preferred over the public one for server-to-server calls
 /// (e.g. the CLI-auth JWKS fetch).
 pub const NEXT_INTERNAL_URL: &str = "NEXT_INTERNAL_URL";
+
+/// Attempts per webhook / Teams / Discord delivery before it is dead-lettered.
+pub const WEBHOOK_MAX_ATTEMPTS: NumEnv<u32> = NumEnv::new("NOTIFICATIONS_WEBHOOK_MAX_ATTEMPTS", 5);
+/// First retry delay; doubles per attempt up to `WEBHOOK_RETRY_MAX_DELAY_MS`.
+pub const WEBHOOK_RETRY_BASE_DELAY_MS: NumEnv<u64> =
+    NumEnv::new("NOTIFICATIONS_WEBHOOK_RETRY_BASE_DELAY_MS", 1_000);
+pub const WEBHOOK_RETRY_MAX_DELAY_MS: NumEnv<u64> =
+    NumEnv::new("NOTIFICATIONS_WEBHOOK_RETRY_MAX_DELAY_MS", 30_000);
+/// Per-attempt HTTP timeout.
+pub const WEBHOOK_TIMEOUT_SECS: NumEnv<u64> = NumEnv::new("NOTIFICATIONS_WEBHOOK_TIMEOUT_SECS", 10);
+/// Allow deliveries to loopback, private and link-local addresses. Off by
+/// default: webhook URLs are user supplied.
+pub const WEBHOOK_ALLOW_PRIVATE_DESTINATIONS: BoolEnv =
+    BoolEnv::new("NOTIFICATIONS_WEBHOOK_ALLOW_PRIVATE_DESTINATIONS", false);
@@ -10,6 +10,8 @@ pub const AWS_REGION: &str = "AWS_REGION";
 
 /// 32-byte hex AEAD key for data-plane payload encryption.
 pub const AEAD_SECRET_KEY: &str = "AEAD_SECRET_KEY";
+/// Hex HMAC key from which per-project PII pseudonym keys are derived.
+pub const PII_PSEUDONYM_SECRET: &str = "PII_PSEUDONYM_SECRET";
 /// Hex key for Slack token encrypt/decrypt (mirrors the frontend).
 pub const SLACK_ENCRYPTION_KEY: &str = "SLACK_ENCRYPTION_KEY";
 
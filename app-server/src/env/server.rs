@@ -1,6 +1,6 @@
 //! HTTP / gRPC server ports and request payload limits.
 
-use super::NumEnv;
+use super::{NumEnv, StringEnv};
 
 /// REST API port.
 pub const PORT: NumEnv<u16> = NumEnv::new("PORT", 8000);
@@ -14,3 +14,9 @@ pub const CONSUMER_PORT: NumEnv<u16> = NumEnv::new("CONSUMER_PORT", 8002);
 pub const HTTP_PAYLOAD_LIMIT: NumEnv<usize> = NumEnv::new("HTTP_PAYLOAD_LIMIT", 5_242_880);
 /// Max gRPC request payload in bytes. Default 25 MB.
 pub const GRPC_PAYLOAD_LIMIT: NumEnv<usize> = NumEnv::new("GRPC_PAYLOAD_LIMIT", 26_214_400);
+
+/// Comma-separated CIDRs of the load balancers / reverse proxies in front of
+/// the server. `X-Forwarded-For` hops are only believed from these, so API
+/// key IP allow lists see the real client. Empty = no proxy is trusted and
+/// the peer address is the client.
+pub const TRUSTED_PROXY_CIDRS: StringEnv = StringEnv::new("TRUSTED_PROXY_CIDRS", "");
@@ -9,8 +9,11 @@ pub const NUM_DATA_PLANE_SPANS: NumEnv<usize> = NumEnv::new("NUM_DATA_PLANE_SPAN
 pub const NUM_SPANS_INDEXER: NumEnv<u8> = NumEnv::new("NUM_SPANS_INDEXER_WORKERS", 4);
 pub const NUM_BROWSER_EVENTS: NumEnv<u8> = NumEnv::new("NUM_BROWSER_EVENTS_WORKERS", 4);
 pub const NUM_LOGS: NumEnv<u8> = NumEnv::new("NUM_LOGS_WORKERS", 4);
+pub const NUM_METRICS: NumEnv<u8> = NumEnv::new("NUM_METRICS_WORKERS", 2);
 pub const NUM_REPORTS: NumEnv<u8> = NumEnv::new("NUM_REPORTS_WORKERS", 2);
 pub const NUM_CHECKPOINTS: NumEnv<u8> = NumEnv::new("NUM_CHECKPOINTS_WORKERS", 2);
+pub const NUM_EVALUATION_JUDGES: NumEnv<u8> = NumEnv::new("NUM_EVALUATION_JUDGES_WORKERS", 2);
+pub const NUM_COST_BACKFILL: NumEnv<u8> = NumEnv::new("NUM_COST_BACKFILL_WORKERS", 1);
 
 pub const NUM_NOTIFICATION: NumEnv<u8> = NumEnv::new("NUM_NOTIFICATION_WORKERS", 2);
 pub const NUM_NOTIFICATION_DELIVERY: NumEnv<u8> =
@@ -0,0 +1,544 @@
+//! Statistical comparison of two evaluation runs.
+//!
+//! Datapoints of the two runs are paired by the dataset datapoint they were
+//! produced from. When a run scored the same dataset datapoint several times,
+//! its scores are averaged first. For every score present on both sides of a
+//! pair the comparison reports the mean delta (candidate − baseline), its
+//! confidence interval, a paired t-test and a Wilcoxon signed-rank test.
+//!
+//! Higher scores are assumed to be better: a regression is a negative delta.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::f64::consts::{PI, SQRT_2};
+
+use serde::Serialize;
+use uuid::Uuid;
+
+pub const DEFAULT_ALPHA: f64 = 0.05;
+pub const DEFAULT_REGRESSED_LIMIT: usize = 100;
+
+/// Averaged scores of one run, keyed by dataset datapoint id.
+pub type DatapointScores = HashMap<Uuid, HashMap<String, f64>>;
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResult {
+    /// Infinite, serialized as `null`, when paired differences have zero variance.
+    pub statistic: f64,
+    pub p_value: f64,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfidenceInterval {
+    pub low: f64,
+    pub high: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreComparison {
+    pub name: String,
+    /// Number of dataset datapoints scored with this score in both runs.
+    pub pairs: usize,
+    pub baseline_mean: f64,
+    pub candidate_mean: f64,
+    pub mean_delta: f64,
+    /// Two-sided `1 - alpha` interval of the mean delta; needs two pairs.
+    pub confidence_interval: Option<ConfidenceInterval>,
+    pub t_test: Option<TestResult>,
+    pub wilcoxon: Option<TestResult>,
+    pub significant_regression: bool,
+    pub significant_improvement: bool,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressedDatapoint {
+    pub dataset_datapoint_id: Uuid,
+    pub score_name: String,
+    pub baseline: f64,
+    pub candidate: f64,
+    pub delta: f64,
+}
+
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EvaluationComparison {
+    pub baseline_evaluation_id: Uuid,
+    pub candidate_evaluation_id: Uuid,
+    pub alpha: f64,
+    pub matched_datapoints: usize,
+    pub baseline_only_datapoints: usize,
+    pub candidate_only_datapoints: usize,
+    pub scores: Vec<ScoreComparison>,
+    /// Dataset datapoints with at least one lower score in the candidate run.
+    pub regressed_datapoint_count: usize,
+    /// Largest drops first, truncated to the requested limit.
+    pub regressed_datapoints: Vec<RegressedDatapoint>,
+    /// Whether any score dropped significantly at `alpha`.
+    pub significant_regression: bool,
+}
+
+/// Parses a stringified `evaluation_datapoints.scores` object, skipping
+/// non-numeric values. Empty or malformed input yields no scores.
+pub fn parse_scores(raw: &str) -> HashMap<String, f64> {
+    serde_json::from_str::<HashMap<String, serde_json::Value>>(raw)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(name, value)| value.as_f64().map(|v| (name, v)))
+        .collect()
+}
+
+/// Averages the scores of repeated runs of the same dataset datapoint.
+pub fn average_by_datapoint<'a>(
+    rows: impl IntoIterator<Item = (Uuid, &'a str)>,
+) -> DatapointScores {
+    let mut sums: HashMap<Uuid, HashMap<String, (f64, usize)>> = HashMap::new();
+    for (dataset_datapoint_id, raw_scores) in rows {
+        let entry = sums.entry(dataset_datapoint_id).or_default();
+        for (name, value) in parse_scores(raw_scores) {
+            let (sum, count) = entry.entry(name).or_insert((0.0, 0));
+            *sum += value;
+            *count += 1;
+        }
+    }
+
+    sums.into_iter()
+        .map(|(id, scores)| {
+            let averaged = scores
+                .into_iter()
+                .map(|(name, (sum, count))| (name, sum / count as f64))
+                .collect();
+            (id, averaged)
+        })
+        .collect()
+}
+
+pub fn compare_evaluations(
+    baseline_evaluation_id: Uuid,
+    candidate_evaluation_id: Uuid,
+    baseline: &DatapointScores,
+    candidate: &DatapointScores,
+    alpha: f64,
+    regressed_limit: usize,
+) -> EvaluationComparison {
+    let mut matched: Vec<&Uuid> = baseline
+        .keys()
+        .filter(|id| candidate.contains_key(id))
+        .collect();
+    matched.sort();
+
+    // score name -> (dataset datapoint id, baseline, candidate), in datapoint order
+    let mut pairs: BTreeMap<&str, Vec<(Uuid, f64, f64)>> = BTreeMap::new();
+    for id in &matched {
+        let baseline_scores = &baseline[*id];
+        let candidate_scores = &candidate[*id];
+        for (name, baseline_value) in baseline_scores {
+            if let Some(candidate_value) = candidate_scores.get(name) {
+                pairs.entry(name.as_str()).or_default().push((
+                    **id,
+                    *baseline_value,
+                    *candidate_value,
+                ));
+            }
+        }
+    }
+
+    let mut regressed = Vec::new();
+    let mut scores = Vec::with_capacity(pairs.len());
+    for (name, values) in pairs {
+        let baselines: Vec<f64> = values.iter().map(|(_, b, _)| *b).collect();
+        let candidates: Vec<f64> = values.iter().map(|(_, _, c)| *c).collect();
+        let deltas: Vec<f64> = values.iter().map(|(_, b, c)| c - b).collect();
+
+        regressed.extend(values.iter().filter(|(_, b, c)| c < b).map(|(id, b, c)| {
+            RegressedDatapoint {
+                dataset_datapoint_id: *id,
+                score_name: name.to_string(),
+                baseline: *b,
+                candidate: *c,
+                delta: c - b,
+            }
+        }));
+
+        let mean_delta = mean(&deltas);
+        let t_test = paired_t_test(&deltas);
+        let significant = t_test.as_ref().is_some_and(|t| t.p_value < alpha);
+        scores.push(ScoreComparison {
+            name: name.to_string(),
+            pairs: values.len(),
+            baseline_mean: mean(&baselines),
+            candidate_mean: mean(&candidates),
+            mean_delta,
+            confidence_interval: mean_confidence_interval(&deltas, alpha),
+            t_test,
+            wilcoxon: wilcoxon_signed_rank(&deltas),
+            significant_regression: significant && mean_delta < 0.0,
+            significant_improvement: significant && mean_delta > 0.0,
+        });
+    }
+
+    let regressed_datapoint_count = regressed
+        .iter()
+        .map(|r| r.dataset_datapoint_id)
+        .collect::<HashSet<_>>()
+        .len();
+    regressed.sort_by(|a, b| a.delta.total_cmp(&b.delta));
+    regressed.truncate(regressed_limit);
+
+    EvaluationComparison {
+        baseline_evaluation_id,
+        candidate_evaluation_id,
+        alpha,
+        matched_datapoints: matched.len(),
+        baseline_only_datapoints: baseline.len() - matched.len(),
+        candidate_only_datapoints: candidate.len() - matched.len(),
+        significant_regression: scores.iter().any(|s| s.significant_regression),
+        scores,
+        regressed_datapoint_count,
+        regressed_datapoints: regressed,
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Standard error of the mean, using the sample standard deviation.
+fn standard_error(values: &[f64]) -> f64 {
+    let n = values.len() as f64;
+    let m = mean(values);
+    let variance = values.iter().map(|v| (v - m).powi(2)).sum::<f64>() / (n - 1.0);
+    (variance / n).sqrt()
+}
+
+fn paired_t_test(deltas: &[f64]) -> Option<TestResult> {
+    if deltas.len() < 2 {
+        return None;
+    }
+    let m = mean(deltas);
+    let se = standard_error(deltas);
+    if se == 0.0 {
+        // Every pair moved by the same amount: the change is exact.
+        return Some(TestResult {
+            statistic: if m == 0.0 {
+                0.0
+            } else {
+                f64::INFINITY.copysign(m)
+            },
+            p_value: if m == 0.0 { 1.0 } else { 0.0 },
+        });
+    }
+    let statistic = m / se;
+    Some(TestResult {
+        statistic,
+        p_value: student_t_two_sided_p(statistic, (deltas.len() - 1) as f64),
+    })
+}
+
+fn mean_confidence_interval(deltas: &[f64], alpha: f64) -> Option<ConfidenceInterval> {
+    if deltas.len() < 2 {
+        return None;
+    }
+    let m = mean(deltas);
+    let margin = student_t_critical(alpha, (deltas.len() - 1) as f64) * standard_error(deltas);
+    Some(ConfidenceInterval {
+        low: m - margin,
+        high: m + margin,
+    })
+}
+
+/// Wilcoxon signed-rank test with the normal approximation, average ranks for
+/// ties, tie-corrected variance and continuity correction. Zero differences
+/// are dropped. The statistic is the sum of positive ranks.
+fn wilcoxon_signed_rank(deltas: &[f64]) -> Option<TestResult> {
+    let mut nonzero: Vec<f64> = deltas.iter().copied().filter(|d| *d != 0.0).collect();
+    if nonzero.is_empty() {
+        return None;
+    }
+    nonzero.sort_by(|a, b| a.abs().total_cmp(&b.abs()));
+
+    let n = nonzero.len();
+    let mut positive_rank_sum = 0.0;
+    let mut tie_correction = 0.0;
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && nonzero[j + 1].abs() == nonzero[i].abs() {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let ties = (j - i + 1) as f64;
+        tie_correction += ties.powi(3) - ties;
+        positive_rank_sum += rank * nonzero[i..=j].iter().filter(|d| **d > 0.0).count() as f64;
+        i = j + 1;
+    }
+
+    let n = n as f64;
+    let expected = n * (n + 1.0) / 4.0;
+    let variance = n * (n + 1.0) * (2.0 * n + 1.0) / 24.0 - tie_correction / 48.0;
+    if variance <= 0.0 {
+        return None;
+    }
+    let deviation = ((positive_rank_sum - expected).abs() - 0.5).max(0.0);
+    let z = deviation / variance.sqrt();
+    Some(TestResult {
+        statistic: positive_rank_sum,
+        p_value: erfc(z / SQRT_2).min(1.0),
+    })
+}
+
+/// Two-sided p-value of Student's t distribution.
+fn student_t_two_sided_p(t: f64, df: f64) -> f64 {
+    regularized_incomplete_beta(df / 2.0, 0.5, df / (df + t * t))
+}
+
+/// The `t` with a two-sided p-value of `alpha`, found by bisection.
+fn student_t_critical(alpha: f64, df: f64) -> f64 {
+    let mut low = 0.0;
+    let mut high = 1.0;
+    while student_t_two_sided_p(high, df) > alpha && high < 1e12 {
+        high *= 2.0;
+    }
+    for _ in 0..200 {
+        let mid = (low + high) / 2.0;
+        if student_t_two_sided_p(mid, df) > alpha {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+    (low + high) / 2.0
+}
+
+/// Lanczos approximation of `ln Γ(x)`.
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_81,
+        676.520_368_121_885,
+        -1_259.139_216_722_4,
+        771.323_428_777_653,
+        -176.615_029_162_141,
+        12.507_343_278_686_9,
+        -0.138_571_095_265_72,
+        9.984_369_578_019_57e-6,
+        1.505_632_735_149_31e-7,
+    ];
+
+    if x < 0.5 {
+        return (PI / (PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let t = x + G + 0.5;
+    let series = COEFFICIENTS
+        .iter()
+        .enumerate()
+        .skip(1)
+        .fold(COEFFICIENTS[0], |acc, (i, c)| acc + c / (x + i as f64));
+    0.5 * (2.0 * PI).ln() + (x + 0.5) * t.ln() - t + series.ln()
+}
+
+/// Regularized incomplete beta function `I_x(a, b)`.
+fn regularized_incomplete_beta(a: f64, b: f64, x: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let front =
+        (ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln()).exp();
+    if x < (a + 1.0) / (a + b + 2.0) {
+        front * beta_continued_fraction(a, b, x) / a
+    } else {
+        1.0 - front * beta_continued_fraction(b, a, 1.0 - x) / b
+    }
+}
+
+/// Continued fraction of the incomplete beta function (modified Lentz).
+fn beta_continued_fraction(a: f64, b: f64, x: f64) -> f64 {
+    const MAX_ITERATIONS: usize = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let clamp = |v: f64| if v.abs() < TINY { TINY } else { v };
+
+    let mut c = 1.0;
+    let mut d = 1.0 / clamp(1.0 - (a + b) * x / (a + 1.0));
+    let mut h = d;
+    for m in 1..=MAX_ITERATIONS {
+        let m = m as f64;
+        let even = m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m));
+        d = 1.0 / clamp(1.0 + even * d);
+        c = clamp(1.0 + even / c);
+        h *= d * c;
+
+        let odd = -(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0));
+        d = 1.0 / clamp(1.0 + odd * d);
+        c = clamp(1.0 + odd / c);
+        let step = d * c;
+        h *= step;
+        if (step - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    h
+}
+
+/// Complementary error function, fractional error below 1.2e-7.
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let polynomial = -1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let result = t * (-z * z + polynomial).exp();
+    if x >= 0.0 { result } else { 2.0 - result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn approx(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() < tolerance
+    }
+
+    fn id(n: u128) -> Uuid {
+        Uuid::from_u128(n)
+    }
+
+    #[test]
+    fn test_parse_scores() {
+        let scores = parse_scores(r#"{"accuracy":0.5,"label":"x","missing":null}"#);
+        assert_eq!(scores.len(), 1);
+        assert_eq!(scores["accuracy"], 0.5);
+        assert!(parse_scores("").is_empty());
+    }
+
+    #[test]
+    fn test_average_by_datapoint() {
+        let rows = vec![
+            (id(1), r#"{"accuracy":1.0}"#),
+            (id(1), r#"{"accuracy":0.0,"f1":0.5}"#),
+            (id(2), r#"{"accuracy":0.25}"#),
+        ];
+        let averaged = average_by_datapoint(rows);
+        assert_eq!(averaged[&id(1)]["accuracy"], 0.5);
+        assert_eq!(averaged[&id(1)]["f1"], 0.5);
+        assert_eq!(averaged[&id(2)]["accuracy"], 0.25);
+    }
+
+    #[test]
+    fn test_student_t_distribution() {
+        // Reference values from standard t tables.
+        assert!(approx(student_t_two_sided_p(2.228, 10.0), 0.05, 1e-4));
+        assert!(approx(student_t_two_sided_p(12.706, 1.0), 0.05, 1e-4));
+        assert!(approx(student_t_two_sided_p(0.0, 5.0), 1.0, 1e-12));
+        assert!(approx(student_t_critical(0.05, 10.0), 2.228, 1e-3));
+        assert!(approx(student_t_critical(0.01, 30.0), 2.750, 1e-3));
+    }
+
+    #[test]
+    fn test_erfc() {
+        assert!(approx(erfc(0.0), 1.0, 1e-7));
+        assert!(approx(erfc(1.959_964 / SQRT_2), 0.05, 1e-6));
+        assert!(approx(erfc(-1.0), 1.842_700_79, 1e-6));
+    }
+
+    #[test]
+    fn test_paired_t_test() {
+        let deltas = [-0.1, -0.2, -0.15, -0.05, -0.1];
+        let result = paired_t_test(&deltas).unwrap();
+        // mean -0.12, sd 0.0570, se 0.0255, t = -4.707 with df = 4
+        assert!(approx(result.statistic, -4.707, 1e-3));
+        assert!(approx(result.p_value, 0.00926, 1e-4));
+
+        let constant = paired_t_test(&[0.5, 0.5, 0.5]).unwrap();
+        assert_eq!(constant.p_value, 0.0);
+        assert!(paired_t_test(&[0.5]).is_none());
+    }
+
+    #[test]
+    fn test_wilcoxon_signed_rank() {
+        let deltas = [1.0, 2.0, -3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let result = wilcoxon_signed_rank(&deltas).unwrap();
+        assert_eq!(result.statistic, 33.0);
+        // z = (|33 - 18| - 0.5) / sqrt(51)
+        assert!(approx(
+            result.p_value,
+            erfc(14.5 / 51.0_f64.sqrt() / SQRT_2),
+            1e-12
+        ));
+
+        assert!(wilcoxon_signed_rank(&[0.0, 0.0]).is_none());
+    }
+
+    #[test]
+    fn test_compare_evaluations_detects_regression() {
+        let baseline_id = id(100);
+        let candidate_id = id(200);
+        let mut baseline = DatapointScores::new();
+        let mut candidate = DatapointScores::new();
+        for i in 0..10u128 {
+            baseline.insert(id(i), HashMap::from([("accuracy".to_string(), 0.9)]));
+            let drop = if i == 3 { 0.6 } else { 0.1 + i as f64 * 0.01 };
+            candidate.insert(id(i), HashMap::from([("accuracy".to_string(), 0.9 - drop)]));
+        }
+        baseline.insert(id(50), HashMap::from([("accuracy".to_string(), 1.0)]));
+
+        let comparison =
+            compare_evaluations(baseline_id, candidate_id, &baseline, &candidate, 0.05, 2);
+
+        assert_eq!(comparison.matched_datapoints, 10);
+        assert_eq!(comparison.baseline_only_datapoints, 1);
+        assert_eq!(comparison.candidate_only_datapoints, 0);
+        assert!(comparison.significant_regression);
+
+        let accuracy = &comparison.scores[0];
+        assert_eq!(accuracy.name, "accuracy");
+        assert_eq!(accuracy.pairs, 10);
+        assert!(accuracy.significant_regression);
+        assert!(!accuracy.significant_improvement);
+        let interval = accuracy.confidence_interval.as_ref().unwrap();
+        assert!(interval.low < accuracy.mean_delta && accuracy.mean_delta < interval.high);
+        assert!(interval.high < 0.0);
+
+        assert_eq!(comparison.regressed_datapoint_count, 10);
+        assert_eq!(comparison.regressed_datapoints.len(), 2);
+        assert_eq!(
+            comparison.regressed_datapoints[0].dataset_datapoint_id,
+            id(3)
+        );
+    }
+
+    #[test]
+    fn test_compare_evaluations_without_change() {
+        let scores: DatapointScores = (0..5u128)
+            .map(|i| {
+                (
+                    id(i),
+                    HashMap::from([("accuracy".to_string(), i as f64 / 5.0)]),
+                )
+            })
+            .collect();
+
+        let comparison = compare_evaluations(id(100), id(200), &scores, &scores, 0.05, 10);
+
+        assert!(!comparison.significant_regression);
+        assert_eq!(comparison.scores[0].mean_delta, 0.0);
+        assert_eq!(comparison.scores[0].t_test.as_ref().unwrap().p_value, 1.0);
+        assert!(comparison.scores[0].wilcoxon.is_none());
+        assert!(comparison.regressed_datapoints.is_empty());
+    }
+}
@@ -0,0 +1,545 @@
+//! Server-side LLM-as-judge evaluators.
+//!
+//! A project defines `LLM_JUDGE` evaluators (a prompt template, an output
+//! schema and a model size). When an evaluation datapoint arrives with an
+//! executor output but without one of those scores, the API enqueues it here;
+//! the handler runs each missing judge through [`LlmClient`], merges the
+//! scores into the datapoint and pushes them to the evaluation's realtime
+//! channel. CI runners therefore only need a project API key.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use super::{
+    EVALUATION_JUDGES_EXCHANGE, EVALUATION_JUDGES_ROUTING_KEY, UpdatedDatapointStrings,
+    realtime::{RealtimeDatapoint, send_datapoint_updates},
+    update_evaluation_datapoint,
+};
+use crate::{
+    ch::evaluation_datapoints::{CHEvaluationDatapointInputs, get_evaluation_datapoint_inputs},
+    db::{
+        DB,
+        evaluators::{Evaluator, get_evaluators_by_type, has_evaluators_of_type},
+    },
+    llm::{
+        LlmClient, ProviderContent, ProviderPart, ProviderRequest,
+        models::{ModelSize, ProviderFunctionDeclaration, ProviderGenerationConfig, ProviderTool},
+    },
+    mq::{MessageQueue, MessageQueueTrait, utils::mq_max_payload},
+    pubsub::PubSub,
+    worker::{HandlerError, MessageHandler},
+};
+
+/// `evaluators.evaluator_type` of LLM-as-judge evaluators.
+pub const EVALUATOR_TYPE_LLM_JUDGE: &str = "LLM_JUDGE";
+
+/// Name of the tool the judge must call to submit its verdict.
+const JUDGE_TOOL_NAME: &str = "submit_score";
+/// Longest datapoint field substituted into a prompt, in characters.
+const MAX_TEMPLATE_VALUE_CHARS: usize = 20_000;
+
+const JUDGE_INSTRUCTION: &str = "You are an evaluator scoring the output of an AI system. \
+    Follow the evaluation instructions exactly and always submit your verdict by calling the \
+    submit_score tool. Never respond with plain text.";
+
+fn default_model_size() -> ModelSize {
+    ModelSize::Small
+}
+
+/// The definition of an `LLM_JUDGE` evaluator, stored in `evaluators.definition`.
+/// The evaluator's name is the score name it writes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LlmJudgeDefinition {
+    /// Instructions with `{{data}}`, `{{target}}`, `{{output}}` and
+    /// `{{metadata}}` placeholders.
+    pub prompt_template: String,
+    /// JSON schema of the tool arguments. Must have a numeric or boolean
+    /// `score` property; defaults to `{score, reasoning}`.
+    #[serde(default)]
+    pub output_schema: Option<Value>,
+    #[serde(default = "default_model_size")]
+    pub model_size: ModelSize,
+}
+
+impl LlmJudgeDefinition {
+    pub fn from_definition(definition: &Value) -> Result<Self> {
+        let judge: Self = serde_json::from_value(definition.clone())?;
+        judge.validate()?;
+        Ok(judge)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if !self.prompt_template.contains("{{output}}") {
+            anyhow::bail!("promptTemplate must reference {{{{output}}}}");
+        }
+        if let Some(schema) = &self.output_schema {
+            if schema.get("type").and_then(Value::as_str) != Some("object") {
+                anyhow::bail!("outputSchema must be an object schema");
+            }
+            let score_type = schema
+                .pointer("/properties/score/type")
+                .and_then(Value::as_str);
+            if !matches!(score_type, Some("number" | "integer" | "boolean")) {
+                anyhow::bail!(
+                    "outputSchema must have a number, integer or boolean `score` property"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn output_schema(&self) -> Value {
+        let mut schema = self.output_schema.clone().unwrap_or_else(|| {
+            json!({
+                "type": "object",
+                "properties": {
+                    "reasoning": {
+                        "type": "string",
+                        "description": "A short justification of the score."
+                    },
+                    "score": {
+                        "type": "number",
+                        "description": "The score of the output."
+                    }
+                }
+            })
+        });
+        // The score is the only field we read back, so it must always be there.
+        if let Some(object) = schema.as_object_mut() {
+            let required = object.entry("required").or_insert_with(|| json!([]));
+            if let Some(required) = required.as_array_mut() {
+                if !required.iter().any(|r| r == "score") {
+                    required.push(json!("score"));
+                }
+            }
+        }
+        schema
+    }
+
+    /// Substitutes placeholders in a single pass over the template, so
+    /// placeholder-like text inside a substituted value is never expanded.
+    fn render_prompt(&self, datapoint: &CHEvaluationDatapointInputs) -> String {
+        let mut prompt = String::with_capacity(self.prompt_template.len());
+        let mut rest = self.prompt_template.as_str();
+        while let Some(start) = rest.find("{{") {
+            prompt.push_str(&rest[..start]);
+            let after_open = &rest[start + 2..];
+            let value = after_open.find("}}").and_then(|end| {
+                let value = match &after_open[..end] {
+                    "data" => &datapoint.data,
+                    "target" => &datapoint.target,
+                    "output" => &datapoint.executor_output,
+                    "metadata" => &datapoint.metadata,
+                    _ => return None,
+                };
+                Some((value, end))
+            });
+            match value {
+                Some((value, end)) => {
+                    prompt.push_str(&truncate_chars(value, MAX_TEMPLATE_VALUE_CHARS));
+                    rest = &after_open[end + 2..];
+                }
+                None => {
+                    prompt.push('{');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        prompt.push_str(rest);
+        prompt
+    }
+
+    fn build_request(&self, prompt: String) -> ProviderRequest {
+        ProviderRequest {
+            contents: vec![ProviderContent {
+                role: Some("user".to_string()),
+                parts: Some(vec![ProviderPart {
+                    text: Some(prompt),
+                    ..Default::default()
+                }]),
+            }],
+            system_instruction: Some(ProviderContent {
+                role: None,
+                parts: Some(vec![ProviderPart {
+                    text: Some(JUDGE_INSTRUCTION.to_string()),
+                    ..Default::default()
+                }]),
+            }),
+            tools: Some(vec![ProviderTool {
+                function_declarations: vec![ProviderFunctionDeclaration {
+                    name: JUDGE_TOOL_NAME.to_string(),
+                    description: "REQUIRED: Submit the score of the evaluated output.".to_string(),
+                    parameters: self.output_schema(),
+                }],
+            }]),
+            generation_config: Some(ProviderGenerationConfig {
+                temperature: Some(0.0),
+                ..Default::default()
+            }),
+            service_tier: None,
+            provider: None,
+            model_size: Some(self.model_size),
+        }
+    }
+}
+
+fn truncate_chars(s: &str, max_chars: usize) -> String {
+    match s.char_indices().nth(max_chars) {
+        Some((byte_idx, _)) => format!("{}...", &s[..byte_idx]),
+        None => s.to_string(),
+    }
+}
+
+/// Reads the score from the judge's tool-call arguments. Booleans score 1 or 0.
+fn parse_score(args: &Value) -> Option<f64> {
+    match args.get("score")? {
+        Value::Number(n) => n.as_f64().filter(|v| v.is_finite()),
+        Value::Bool(b) => Some(if *b { 1.0 } else { 0.0 }),
+        Value::String(s) => s.trim().parse::<f64>().ok().filter(|v| v.is_finite()),
+        _ => None,
+    }
+}
+
+/// Score names already set on a datapoint, from the ClickHouse `scores` string.
+fn existing_score_names(scores: &str) -> Vec<String> {
+    if scores.is_empty() {
+        return vec![];
+    }
+    serde_json::from_str::<HashMap<String, Value>>(scores)
+        .map(|scores| {
+            scores
+                .into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, _)| k)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvaluationJudgesMessage {
+    pub project_id: Uuid,
+    pub evaluation_id: Uuid,
+    pub group_id: String,
+    pub datapoint_ids: Vec<Uuid>,
+}
+
+/// Enqueues datapoints for judging if the project has any judge evaluators.
+/// Best effort: ingestion never fails because judging could not be scheduled.
+pub async fn schedule_judges(
+    pool: &PgPool,
+    queue: Arc<MessageQueue>,
+    project_id: Uuid,
+    evaluation_id: Uuid,
+    group_id: &str,
+    datapoint_ids: Vec<Uuid>,
+) {
+    if datapoint_ids.is_empty() {
+        return;
+    }
+
+    match has_evaluators_of_type(pool, &project_id, EVALUATOR_TYPE_LLM_JUDGE).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            log::error!("Failed to look up judge evaluators for project {project_id}: {e:?}");
+            return;
+        }
+    }
+
+    let message = EvaluationJudgesMessage {
+        project_id,
+        evaluation_id,
+        group_id: group_id.to_string(),
+        datapoint_ids,
+    };
+    if let Err(e) = push_to_judges_queue(&message, queue).await {
+        log::error!("Failed to push evaluation {evaluation_id} datapoints for judging: {e:?}");
+    }
+}
+
+async fn push_to_judges_queue(
+    message: &EvaluationJudgesMessage,
+    queue: Arc<MessageQueue>,
+) -> Result<()> {
+    let serialized = serde_json::to_vec(message)?;
+
+    if serialized.len() >= mq_max_payload() {
+        anyhow::bail!(
+            "MQ payload limit exceeded. payload size: [{}]",
+            serialized.len()
+        );
+    }
+
+    queue
+        .publish(
+            &serialized,
+            EVALUATION_JUDGES_EXCHANGE,
+            EVALUATION_JUDGES_ROUTING_KEY,
+            None,
+        )
+        .await?;
+
+    Ok(())
+}
+
+pub struct EvaluationJudgesHandler {
+    pub db: Arc<DB>,
+    pub clickhouse: clickhouse::Client,
+    pub pubsub: Arc<PubSub>,
+    pub llm_client: Option<Arc<LlmClient>>,
+}
+
+#[async_trait]
+impl MessageHandler for EvaluationJudgesHandler {
+    type Message = EvaluationJudgesMessage;
+
+    async fn handle(&self, message: Self::Message) -> Result<(), HandlerError> {
+        let Some(llm_client) = self.llm_client.as_ref() else {
+            log::warn!(
+                "[Evaluation Judges] LLM client not configured, skipping judges for evaluation {}",
+                message.evaluation_id
+            );
+            return Ok(());
+        };
+
+        let evaluators =
+            get_evaluators_by_type(&self.db.pool, &message.project_id, EVALUATOR_TYPE_LLM_JUDGE)
+                .await
+                .map_err(HandlerError::transient)?;
+
+        let judges: Vec<(Evaluator, LlmJudgeDefinition)> = evaluators
+            .into_iter()
+            .filter_map(|evaluator| {
+                match LlmJudgeDefinition::from_definition(&evaluator.definition) {
+                    Ok(judge) => Some((evaluator, judge)),
+                    Err(e) => {
+                        log::warn!(
+                            "[Evaluation Judges] Skipping invalid evaluator {}: {:?}",
+                            evaluator.id,
+                            e
+                        );
+                        None
+                    }
+                }
+            })
+            .collect();
+        if judges.is_empty() {
+            return Ok(());
+        }
+
+        let datapoints = get_evaluation_datapoint_inputs(
+            &self.clickhouse,
+            &message.project_id,
+            &message.evaluation_id,
+            &message.datapoint_ids,
+        )
+        .await
+        .map_err(HandlerError::transient)?;
+
+        for datapoint in &datapoints {
+            // Per-datapoint best effort: retrying the message would re-run
+            // (and re-bill) judges that already succeeded.
+            if let Err(e) = self
+                .judge_datapoint(&message, datapoint, &judges, llm_client)
+                .await
+            {
+                log::error!(
+                    "[Evaluation Judges] Failed to judge datapoint {} of evaluation {}: {:?}",
+                    datapoint.id,
+                    message.evaluation_id,
+                    e
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl EvaluationJudgesHandler {
+    async fn judge_datapoint(
+        &self,
+        message: &EvaluationJudgesMessage,
+        datapoint: &CHEvaluationDatapointInputs,
+        judges: &[(Evaluator, LlmJudgeDefinition)],
+        llm_client: &LlmClient,
+    ) -> Result<()> {
+        if datapoint.executor_output.is_empty() {
+            return Ok(());
+        }
+
+        let existing = existing_score_names(&datapoint.scores);
+        let pending: Vec<&(Evaluator, LlmJudgeDefinition)> = judges
+            .iter()
+            .filter(|(evaluator, _)| !existing.contains(&evaluator.name))
+            .collect();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let results = join_all(pending.iter().map(|(evaluator, judge)| async move {
+            let request = judge.build_request(judge.render_prompt(datapoint));
+            let response = llm_client.generate_content(&request).await;
+            (evaluator, response)
+        }))
+        .await;
+
+        let mut scores: HashMap<String, Option<f64>> = HashMap::new();
+        for (evaluator, response) in results {
+            let args = match response {
+                Ok(response) => response
+                    .candidates
+                    .and_then(|c| c.into_iter().next())
+                    .and_then(|c| c.content)
+                    .and_then(|content| content.parts)
+                    .and_then(|parts| {
+                        parts.into_iter().find_map(|p| {
+                            p.function_call
+                                .filter(|fc| fc.name == JUDGE_TOOL_NAME)
+                                .and_then(|fc| fc.args)
+                        })
+                    }),
+                Err(e) => {
+                    log::warn!(
+                        "[Evaluation Judges] Evaluator {} LLM call failed: {:?}",
+                        evaluator.id,
+                        e
+                    );
+                    continue;
+                }
+            };
+            match args.as_ref().and_then(parse_score) {
+                Some(score) => {
+                    scores.insert(evaluator.name.clone(), Some(score));
+                }
+                None => log::warn!(
+                    "[Evaluation Judges] Evaluator {} returned no usable score",
+                    evaluator.id
+                ),
+            }
+        }
+        if scores.is_empty() {
+            return Ok(());
+        }
+
+        let UpdatedDatapointStrings {
+            executor_output,
+            scores,
+        } = update_evaluation_datapoint(
+            &self.db.pool,
+            self.clickhouse.clone(),
+            message.evaluation_id,
+            message.project_id,
+            datapoint.id,
+            &message.group_id,
+            None,
+            scores,
+            None,
+        )
+        .await?;
+
+        let realtime_point =
+            RealtimeDatapoint::from_update_strings(datapoint.id, None, &executor_output, &scores);
+        send_datapoint_updates(
+            self.pubsub.as_ref(),
+            &message.project_id,
+            &message.evaluation_id,
+            std::slice::from_ref(&realtime_point),
+        )
+        .await;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datapoint() -> CHEvaluationDatapointInputs {
+        CHEvaluationDatapointInputs {
+            id: Uuid::nil(),
+            data: r#"{"question":"2+2?"}"#.to_string(),
+            target: "4".to_string(),
+            metadata: String::new(),
+            executor_output: "4".to_string(),
+            scores: r#"{"exact":1.0}"#.to_string(),
+        }
+    }
+
+    #[test]
+    fn definition_requires_output_placeholder_and_score_property() {
+        let ok = json!({ "promptTemplate": "Is {{output}} correct for {{data}}?" });
+        let judge = LlmJudgeDefinition::from_definition(&ok).unwrap();
+        assert_eq!(judge.model_size, ModelSize::Small);
+
+        let no_output = json!({ "promptTemplate": "Is it correct?" });
+        assert!(LlmJudgeDefinition::from_definition(&no_output).is_err());
+
+        let no_score = json!({
+            "promptTemplate": "{{output}}",
+            "outputSchema": { "type": "object", "properties": { "grade": { "type": "string" } } }
+        });
+        assert!(LlmJudgeDefinition::from_definition(&no_score).is_err());
+    }
+
+    #[test]
+    fn prompt_substitutes_datapoint_fields() {
+        let judge = LlmJudgeDefinition::from_definition(&json!({
+            "promptTemplate": "Q: {{data}} Expected: {{target}} Got: {{output}} {{unknown}}"
+        }))
+        .unwrap();
+        assert_eq!(
+            judge.render_prompt(&datapoint()),
+            r#"Q: {"question":"2+2?"} Expected: 4 Got: 4 {{unknown}}"#
+        );
+    }
+
+    #[test]
+    fn prompt_does_not_expand_placeholders_inside_values() {
+        let judge = LlmJudgeDefinition::from_definition(&json!({
+            "promptTemplate": "Output: {{output}} Expected: {{target}}"
+        }))
+        .unwrap();
+        let mut datapoint = datapoint();
+        datapoint.executor_output = "{{target}} {{".to_string();
+        assert_eq!(
+            judge.render_prompt(&datapoint),
+            "Output: {{target}} {{ Expected: 4"
+        );
+    }
+
+    #[test]
+    fn output_schema_always_requires_score() {
+        let judge = LlmJudgeDefinition::from_definition(&json!({
+            "promptTemplate": "{{output}}",
+            "outputSchema": {
+                "type": "object",
+                "properties": { "score": { "type": "boolean" } },
+                "required": []
+            }
+        }))
+        .unwrap();
+        assert_eq!(judge.output_schema()["required"], json!(["score"]));
+    }
+
+    #[test]
+    fn scores_parse_from_tool_arguments() {
+        assert_eq!(parse_score(&json!({ "score": 0.5 })), Some(0.5));
+        assert_eq!(parse_score(&json!({ "score": true })), Some(1.0));
+        assert_eq!(parse_score(&json!({ "score": "3" })), Some(3.0));
+        assert_eq!(parse_score(&json!({ "reasoning": "fine" })), None);
+        assert_eq!(existing_score_names(&datapoint().scores), vec!["exact"]);
+        assert!(existing_score_names("").is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+pub mod compare;
+pub mod judges;
 pub mod realtime;
 
 use std::collections::HashMap;
@@ -32,6 +34,10 @@ struct TraceIdRow {
 
 pub const DEFAULT_GROUP_NAME: &str = "default";
 
+pub const EVALUATION_JUDGES_QUEUE: &str = "evaluation_judges_queue";
+pub const EVALUATION_JUDGES_EXCHANGE: &str = "evaluation_judges_exchange";
+pub const EVALUATION_JUDGES_ROUTING_KEY: &str = "evaluation_judges_routing_key";
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct EvaluationDatapointDatasetLink {
@@ -70,12 +70,14 @@ pub fn is_feature_enabled(feature: Feature) -> bool {
                 .map(|s| s.trim().to_lowercase())
                 .unwrap_or_default();
             let has_llm_api_key = std::env::var(env::llm::API_KEY).is_ok_and(|s| !s.is_empty());
+            let has_llm_base_url = std::env::var(env::llm::BASE_URL).is_ok_and(|s| !s.is_empty());
             let has_aws = std::env::var(env::secrets::AWS_ACCESS_KEY_ID)
                 .is_ok_and(|s| !s.is_empty())
                 && std::env::var(env::secrets::AWS_SECRET_ACCESS_KEY).is_ok_and(|s| !s.is_empty())
                 && std::env::var(env::secrets::AWS_REGION).is_ok_and(|s| !s.is_empty());
             match provider.as_str() {
-                "gemini" | "openai" => has_llm_api_key,
+                "gemini" | "anthropic" => has_llm_api_key,
+                "openai" => has_llm_api_key || has_llm_base_url,
                 "bedrock" => has_aws,
                 "mock" => true,
                 _ => false,
@@ -9,6 +9,24 @@ use super::ModelCosts;
 pub struct CostEntry {
     pub input_cost: f64,
     pub output_cost: f64,
+    /// Per-request and per-unit fees (web search calls, generated images)
+    /// that are not tied to token counts
+    pub request_cost: f64,
+}
+
+impl CostEntry {
+    pub fn total_cost(&self) -> f64 {
+        self.input_cost + self.output_cost + self.request_cost
+    }
+
+    /// Scale every cost component, e.g. by a workspace discount multiplier
+    pub fn scaled(self, multiplier: f64) -> Self {
+        CostEntry {
+            input_cost: self.input_cost * multiplier,
+            output_cost: self.output_cost * multiplier,
+            request_cost: self.request_cost * multiplier,
+        }
+    }
 }
 
 /// All token and attribute info extracted from a span, needed for cost calculation.
@@ -29,6 +47,10 @@ pub struct SpanCostInput {
     pub service_tier: Option<String>,
     /// Whether this is a batch request
     pub is_batch: bool,
+    /// Number of web search tool calls made by the provider
+    pub web_search_calls: i64,
+    /// Number of image generation units (images, or provider-specific units)
+    pub image_generation_units: i64,
 }
 
 /// Regex to match threshold fields like `input_cost_per_token_above_200k_tokens`
@@ -258,8 +280,32 @@ pub fn calculate_span_cost(model_costs: &ModelCosts, input: &SpanCostInput) -> C
         total_output_cost += input.audio_output_tokens as f64 * audio_cost;
     }
 
+    // === PER-REQUEST FEES ===
+    let mut request_cost = resolve_cost_key(costs, "cost_per_request", tier).unwrap_or(0.0);
+
+    // Web search calls, falling back to LiteLLM's medium search context price per query
+    if input.web_search_calls > 0 {
+        let web_search_rate = resolve_cost_key(costs, "cost_per_web_search_call", tier)
+            .or_else(|| {
+                costs
+                    .get("search_context_cost_per_query")
+                    .and_then(|v| get_cost(v, "search_context_size_medium"))
+            })
+            .unwrap_or(0.0);
+        request_cost += input.web_search_calls as f64 * web_search_rate;
+    }
+
+    // Image generation units, falling back to LiteLLM's per-image output price
+    if input.image_generation_units > 0 {
+        let image_rate = resolve_cost_key(costs, "cost_per_image_generation_unit", tier)
+            .or_else(|| resolve_cost_key(costs, "output_cost_per_image", tier))
+            .unwrap_or(0.0);
+        request_cost += input.image_generation_units as f64 * image_rate;
+    }
+
     CostEntry {
         input_cost: total_input_cost,
         output_cost: total_output_cost,
+        request_cost,
     }
 }
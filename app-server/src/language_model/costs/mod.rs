@@ -1,5 +1,6 @@
 use std::sync::{Arc, LazyLock};
 
+use chrono::{DateTime, Utc};
 use regex::Regex;
 
 use uuid::Uuid;
@@ -7,9 +8,14 @@ use uuid::Uuid;
 use crate::{
     cache::{
         Cache, CacheTrait,
-        keys::{CUSTOM_MODEL_COSTS_CACHE_KEY, MODEL_COSTS_CACHE_KEY},
+        keys::{CUSTOM_MODEL_COSTS_CACHE_KEY, MODEL_COSTS_CACHE_KEY, PRICING_DISCOUNTS_CACHE_KEY},
+    },
+    db::{
+        DB,
+        custom_model_costs::get_custom_model_cost_versions,
+        model_costs::get_model_costs_batch,
+        pricing_discounts::{WorkspacePricingDiscount, get_pricing_discounts_for_project},
     },
-    db::{DB, custom_model_costs::get_custom_model_cost, model_costs::get_model_costs_batch},
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -29,11 +35,70 @@ const MODEL_COSTS_CACHE_TTL_SECONDS: u64 = 60 * 60 * 24; // 24 hours
 /// Cache invalidation handles the common case, but this limits the blast
 /// radius if invalidation misses a variant (e.g. uncommon model name form).
 const MODEL_COSTS_NEGATIVE_CACHE_TTL_SECONDS: u64 = 60 * 30; // 30 minutes
+/// Discounts are invalidated by the workspace settings when edited; the short
+/// TTL bounds staleness if that invalidation fails.
+const PRICING_DISCOUNTS_CACHE_TTL_SECONDS: u64 = 60 * 10; // 10 minutes
 
 /// Costs JSON blob from the `model_costs` table, cached as-is.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ModelCosts(pub Value);
 
+/// One price version of a model, in effect from `effective_from` until the
+/// next version's `effective_from`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModelCostsVersion {
+    pub effective_from: DateTime<Utc>,
+    pub costs: ModelCosts,
+}
+
+/// All price versions of a model, sorted by `effective_from` ascending.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ModelCostsHistory(pub Vec<ModelCostsVersion>);
+
+impl ModelCostsHistory {
+    /// The price version in effect at `time`, i.e. the latest one that
+    /// started at or before it.
+    pub fn at(&self, time: DateTime<Utc>) -> Option<&ModelCosts> {
+        self.0
+            .iter()
+            .rev()
+            .find(|version| version.effective_from <= time)
+            .map(|version| &version.costs)
+    }
+}
+
+/// Model costs resolved for a span, along with the workspace discount to
+/// apply on top of them.
+#[derive(Clone, Debug)]
+pub struct ResolvedModelCosts {
+    pub costs: ModelCosts,
+    /// Workspace discount multiplier. Always `1.0` for project custom costs,
+    /// which are already the price the project pays.
+    pub multiplier: f64,
+}
+
+/// The discount multiplier in effect at `time` for `provider`. A discount for
+/// the exact provider takes priority over a workspace-wide one (empty provider).
+pub fn discount_multiplier_at(
+    discounts: &[WorkspacePricingDiscount],
+    provider: Option<&str>,
+    time: DateTime<Utc>,
+) -> f64 {
+    let latest_for = |provider: &str| {
+        discounts
+            .iter()
+            .filter(|d| d.provider.eq_ignore_ascii_case(provider) && d.effective_from <= time)
+            .max_by_key(|d| d.effective_from)
+            .map(|d| d.multiplier)
+    };
+
+    provider
+        .filter(|p| !p.is_empty())
+        .and_then(latest_for)
+        .or_else(|| latest_for(""))
+        .unwrap_or(1.0)
+}
+
 /// Extract provider and raw model name from span attributes.
 #[derive(Debug, Clone)]
 pub struct ModelInfo {
@@ -133,11 +198,12 @@ impl ModelInfo {
     }
 }
 
-/// Look up custom model costs for a specific project, then fall back to universal costs.
+/// Look up custom model costs for a specific project, then fall back to universal costs,
+/// using the price versions in effect at `time`.
 ///
 /// Priority:
 /// 1. Project-specific custom model costs (cache → DB)
-/// 2. Universal model costs (cache → DB)
+/// 2. Universal model costs (cache → DB), with the workspace discount applied
 pub async fn get_model_costs_for_project(
     db: Arc<DB>,
     cache: Arc<Cache>,
@@ -145,7 +211,8 @@ pub async fn get_model_costs_for_project(
     project_id: &Uuid,
     orig_provider_name: &str,
     orig_model_name: &str,
-) -> Option<ModelCosts> {
+    time: DateTime<Utc>,
+) -> Option<ResolvedModelCosts> {
     // Lowercase to match the frontend which lowercases provider/model before DB storage
     let orig_provider = orig_provider_name.to_lowercase();
     let orig_model = orig_model_name.to_lowercase();
@@ -159,15 +226,71 @@ pub async fn get_model_costs_for_project(
         project_id,
     )
     .await
+    .and_then(|history| history.at(time).cloned())
     {
-        return Some(costs);
+        return Some(ResolvedModelCosts {
+            costs,
+            multiplier: 1.0,
+        });
     }
 
     // Fall back to universal model costs
-    get_model_costs(db, cache, model_info).await
+    let costs = get_model_costs(db.clone(), cache.clone(), model_info)
+        .await?
+        .at(time)
+        .cloned()?;
+
+    let discounts = get_pricing_discounts(db, cache, project_id).await;
+    let multiplier = discount_multiplier_at(&discounts, model_info.provider.as_deref(), time);
+
+    Some(ResolvedModelCosts { costs, multiplier })
+}
+
+/// Look up the workspace pricing discounts for a project from cache or DB.
+/// Errors are logged and treated as no discount.
+async fn get_pricing_discounts(
+    db: Arc<DB>,
+    cache: Arc<Cache>,
+    project_id: &Uuid,
+) -> Vec<WorkspacePricingDiscount> {
+    let cache_key = format!("{}:{}", PRICING_DISCOUNTS_CACHE_KEY, project_id);
+
+    match cache.get::<Vec<WorkspacePricingDiscount>>(&cache_key).await {
+        Ok(Some(discounts)) => return discounts,
+        Ok(None) => {} // Cache miss
+        Err(e) => {
+            log::warn!(
+                "Cache error looking up pricing discounts for {}: {:?}",
+                cache_key,
+                e
+            );
+        }
+    }
+
+    let discounts = match get_pricing_discounts_for_project(&db.pool, project_id).await {
+        Ok(discounts) => discounts,
+        Err(e) => {
+            log::error!(
+                "DB error looking up pricing discounts for project {}: {:?}",
+                project_id,
+                e
+            );
+            return Vec::new();
+        }
+    };
+
+    let _ = cache
+        .insert_with_ttl(
+            &cache_key,
+            discounts.clone(),
+            PRICING_DISCOUNTS_CACHE_TTL_SECONDS,
+        )
+        .await;
+
+    discounts
 }
 
-/// Look up custom model costs for a project from cache or DB.
+/// Look up all price versions of a custom model cost for a project from cache or DB.
 /// Uses exact model name match — custom costs are user-entered, so
 /// the span's model string must match the DB entry exactly.
 async fn get_custom_model_costs(
@@ -176,7 +299,7 @@ async fn get_custom_model_costs(
     provider: &str,
     model: &str,
     project_id: &Uuid,
-) -> Option<ModelCosts> {
+) -> Option<ModelCostsHistory> {
     if model.is_empty() {
         return None;
     }
@@ -187,7 +310,7 @@ async fn get_custom_model_costs(
     );
 
     // Check cache first
-    match cache.get::<Option<ModelCosts>>(&cache_key).await {
+    match cache.get::<Option<ModelCostsHistory>>(&cache_key).await {
         Ok(Some(maybe_costs)) => {
             return maybe_costs;
         }
@@ -202,17 +325,25 @@ async fn get_custom_model_costs(
     }
 
     // Exact match on model name
-    let result = match get_custom_model_cost(&db.pool, project_id, provider, model).await {
-        Ok(Some(entry)) => {
+    let result = match get_custom_model_cost_versions(&db.pool, project_id, provider, model).await {
+        Ok(entries) if !entries.is_empty() => {
             log::debug!(
                 "Found custom costs in DB for project: {}, model: {}, provider: {}",
                 project_id,
                 model,
                 provider
             );
-            Some(ModelCosts(entry.costs))
+            Some(ModelCostsHistory(
+                entries
+                    .into_iter()
+                    .map(|entry| ModelCostsVersion {
+                        effective_from: entry.effective_from,
+                        costs: ModelCosts(entry.costs),
+                    })
+                    .collect(),
+            ))
         }
-        Ok(None) => None,
+        Ok(_) => None,
         Err(e) => {
             log::error!(
                 "DB error looking up custom model costs for project {}, model {}, provider {}: {:?}",
@@ -236,7 +367,7 @@ async fn get_custom_model_costs(
     result
 }
 
-/// Look up model costs from cache or DB, trying lookup keys in priority order.
+/// Look up all price versions of a model from cache or DB, trying lookup keys in priority order.
 ///
 /// Uses a single canonical cache key derived from the normalized ModelInfo fields,
 /// so all equivalent model strings share one cache entry.
@@ -244,7 +375,7 @@ pub async fn get_model_costs(
     db: Arc<DB>,
     cache: Arc<Cache>,
     model_info: &ModelInfo,
-) -> Option<ModelCosts> {
+) -> Option<ModelCostsHistory> {
     log::debug!(
         "Getting model costs for model: {}, provider: {:?}",
         model_info.model,
@@ -254,7 +385,7 @@ pub async fn get_model_costs(
     let cache_key = model_info.cache_key();
 
     // Check canonical cache key
-    match cache.get::<Option<ModelCosts>>(&cache_key).await {
+    match cache.get::<Option<ModelCostsHistory>>(&cache_key).await {
         Ok(Some(maybe_costs)) => {
             return maybe_costs;
         }
@@ -286,7 +417,17 @@ pub async fn get_model_costs(
     let result = keys
         .iter()
         .find_map(|key| db_results.get(key))
-        .map(|entry| ModelCosts(entry.costs.clone()));
+        .map(|entries| {
+            ModelCostsHistory(
+                entries
+                    .iter()
+                    .map(|entry| ModelCostsVersion {
+                        effective_from: entry.effective_from,
+                        costs: ModelCosts(entry.costs.clone()),
+                    })
+                    .collect(),
+            )
+        });
 
     // Cache under the canonical key
     if result.is_some() {
@@ -302,7 +443,7 @@ pub async fn get_model_costs(
             keys
         );
         let _ = cache
-            .insert_with_ttl::<Option<ModelCosts>>(
+            .insert_with_ttl::<Option<ModelCostsHistory>>(
                 &cache_key,
                 None,
                 MODEL_COSTS_NEGATIVE_CACHE_TTL_SECONDS,
@@ -1,7 +1,9 @@
+use chrono::{DateTime, TimeZone, Utc};
 use serde_json::json;
 
 use super::cost_calculator::{SpanCostInput, calculate_span_cost, find_applicable_threshold};
-use super::{ModelCosts, ModelInfo};
+use super::{ModelCosts, ModelCostsHistory, ModelCostsVersion, ModelInfo, discount_multiplier_at};
+use crate::db::pricing_discounts::WorkspacePricingDiscount;
 
 fn make_costs(value: serde_json::Value) -> ModelCosts {
     ModelCosts(value)
@@ -1128,6 +1130,186 @@ fn test_find_threshold_no_threshold_keys() {
     assert!(find_applicable_threshold(&costs, 300_000).is_none());
 }
 
+// ===== Per-request fee tests =====
+
+#[test]
+fn test_per_request_fee() {
+    let costs = make_costs(json!({
+        "input_cost_per_token": 0.000003,
+        "output_cost_per_token": 0.000015,
+        "cost_per_request": 0.001,
+    }));
+    let input = SpanCostInput {
+        prompt_tokens: 1000,
+        completion_tokens: 500,
+        ..default_input()
+    };
+    let result = calculate_span_cost(&costs, &input);
+    assert_float_eq(result.input_cost, 1000.0 * 0.000003);
+    assert_float_eq(result.output_cost, 500.0 * 0.000015);
+    assert_float_eq(result.request_cost, 0.001);
+    assert_float_eq(
+        result.total_cost(),
+        1000.0 * 0.000003 + 500.0 * 0.000015 + 0.001,
+    );
+}
+
+#[test]
+fn test_web_search_and_image_fees() {
+    let costs = make_costs(json!({
+        "cost_per_web_search_call": 0.01,
+        "cost_per_image_generation_unit": 0.04,
+    }));
+    let input = SpanCostInput {
+        web_search_calls: 3,
+        image_generation_units: 2,
+        ..default_input()
+    };
+    let result = calculate_span_cost(&costs, &input);
+    assert_float_eq(result.request_cost, 3.0 * 0.01 + 2.0 * 0.04);
+}
+
+#[test]
+fn test_web_search_and_image_fees_litellm_fallback() {
+    let costs = make_costs(json!({
+        "search_context_cost_per_query": {
+            "search_context_size_low": 0.025,
+            "search_context_size_medium": 0.0275,
+            "search_context_size_high": 0.03,
+        },
+        "output_cost_per_image": 0.02,
+    }));
+    let input = SpanCostInput {
+        web_search_calls: 2,
+        image_generation_units: 1,
+        ..default_input()
+    };
+    let result = calculate_span_cost(&costs, &input);
+    assert_float_eq(result.request_cost, 2.0 * 0.0275 + 0.02);
+}
+
+#[test]
+fn test_unit_fees_not_charged_without_units() {
+    let costs = make_costs(json!({
+        "cost_per_web_search_call": 0.01,
+        "cost_per_image_generation_unit": 0.04,
+    }));
+    let result = calculate_span_cost(&costs, &default_input());
+    assert_float_eq(result.request_cost, 0.0);
+}
+
+#[test]
+fn test_cost_entry_scaled() {
+    let costs = make_costs(json!({
+        "input_cost_per_token": 0.000003,
+        "output_cost_per_token": 0.000015,
+        "cost_per_request": 0.001,
+    }));
+    let input = SpanCostInput {
+        prompt_tokens: 1000,
+        completion_tokens: 500,
+        ..default_input()
+    };
+    let full = calculate_span_cost(&costs, &input);
+    let discounted = full.clone().scaled(0.8);
+    assert_float_eq(discounted.input_cost, full.input_cost * 0.8);
+    assert_float_eq(discounted.output_cost, full.output_cost * 0.8);
+    assert_float_eq(discounted.request_cost, full.request_cost * 0.8);
+}
+
+// ===== Versioned price tests =====
+
+fn date(year: i32, month: u32, day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(year, month, day, 0, 0, 0).unwrap()
+}
+
+fn input_rate(costs: Option<&ModelCosts>) -> Option<f64> {
+    costs.and_then(|c| c.0.get("input_cost_per_token")?.as_f64())
+}
+
+#[test]
+fn test_history_picks_version_in_effect() {
+    let history = ModelCostsHistory(vec![
+        ModelCostsVersion {
+            effective_from: DateTime::UNIX_EPOCH,
+            costs: make_costs(json!({ "input_cost_per_token": 0.000003 })),
+        },
+        ModelCostsVersion {
+            effective_from: date(2025, 6, 1),
+            costs: make_costs(json!({ "input_cost_per_token": 0.000002 })),
+        },
+    ]);
+    assert_eq!(input_rate(history.at(date(2025, 5, 31))), Some(0.000003));
+    assert_eq!(input_rate(history.at(date(2025, 6, 1))), Some(0.000002));
+    assert_eq!(input_rate(history.at(date(2026, 1, 1))), Some(0.000002));
+}
+
+#[test]
+fn test_history_none_before_first_version() {
+    let history = ModelCostsHistory(vec![ModelCostsVersion {
+        effective_from: date(2025, 6, 1),
+        costs: make_costs(json!({ "input_cost_per_token": 0.000002 })),
+    }]);
+    assert!(history.at(date(2025, 1, 1)).is_none());
+}
+
+// ===== Workspace discount tests =====
+
+fn discount(
+    provider: &str,
+    multiplier: f64,
+    effective_from: DateTime<Utc>,
+) -> WorkspacePricingDiscount {
+    WorkspacePricingDiscount {
+        provider: provider.to_string(),
+        multiplier,
+        effective_from,
+    }
+}
+
+#[test]
+fn test_discount_none() {
+    assert_float_eq(
+        discount_multiplier_at(&[], Some("openai"), date(2025, 1, 1)),
+        1.0,
+    );
+}
+
+#[test]
+fn test_discount_provider_specific_over_workspace_wide() {
+    let discounts = vec![
+        discount("", 0.9, DateTime::UNIX_EPOCH),
+        discount("openai", 0.7, DateTime::UNIX_EPOCH),
+    ];
+    let at = date(2025, 1, 1);
+    assert_float_eq(discount_multiplier_at(&discounts, Some("openai"), at), 0.7);
+    assert_float_eq(
+        discount_multiplier_at(&discounts, Some("anthropic"), at),
+        0.9,
+    );
+    assert_float_eq(discount_multiplier_at(&discounts, None, at), 0.9);
+}
+
+#[test]
+fn test_discount_respects_effective_from() {
+    let discounts = vec![
+        discount("", 0.9, date(2025, 1, 1)),
+        discount("", 0.8, date(2025, 7, 1)),
+    ];
+    assert_float_eq(
+        discount_multiplier_at(&discounts, None, date(2024, 12, 31)),
+        1.0,
+    );
+    assert_float_eq(
+        discount_multiplier_at(&discounts, None, date(2025, 3, 1)),
+        0.9,
+    );
+    assert_float_eq(
+        discount_multiplier_at(&discounts, None, date(2025, 8, 1)),
+        0.8,
+    );
+}
+
 // ===== Helper =====
 
 fn assert_float_eq(a: f64, b: f64) {
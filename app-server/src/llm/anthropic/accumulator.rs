@@ -3,20 +3,24 @@
 use serde_json::Value;
 use tokio::sync::mpsc::UnboundedSender;
 
-use super::{map_stop_reason, parse_usage};
+use super::conversions::{map_stop_reason, parse_usage};
 use crate::llm::models::{
     ProviderCandidate, ProviderContent, ProviderFunctionCall, ProviderPart, ProviderResponse,
     ProviderStreamChunk,
 };
+use crate::llm::sse::StreamAccumulator;
 
+/// Accumulates Anthropic Messages stream events. Used for both the Anthropic
+/// SSE stream and the Bedrock event stream, which carries the same events as
+/// opaque JSON payloads.
 #[derive(Default)]
-pub(super) struct BedrockStreamAccumulator {
-    blocks: Vec<BedrockBlock>,
+pub(crate) struct AnthropicStreamAccumulator {
+    blocks: Vec<ContentBlock>,
     stop_reason: Option<String>,
     usage: Option<Value>,
 }
 
-enum BedrockBlock {
+enum ContentBlock {
     Text {
         text: String,
     },
@@ -31,12 +35,10 @@ enum BedrockBlock {
     },
 }
 
-impl BedrockStreamAccumulator {
-    pub(super) fn ingest(
-        &mut self,
-        value: &Value,
-        chunk_tx: &UnboundedSender<ProviderStreamChunk>,
-    ) {
+impl StreamAccumulator for AnthropicStreamAccumulator {
+    type Chunk = Value;
+
+    fn ingest(&mut self, value: Value, chunk_tx: &UnboundedSender<ProviderStreamChunk>) {
         let event_type = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
         match event_type {
             "message_start" => {
@@ -56,7 +58,7 @@ impl BedrockStreamAccumulator {
                     .and_then(|t| t.as_str())
                     .unwrap_or("");
                 let new_block = match block_type {
-                    "thinking" => BedrockBlock::Thinking {
+                    "thinking" => ContentBlock::Thinking {
                         text: block
                             .and_then(|b| b.get("thinking"))
                             .and_then(|t| t.as_str())
@@ -67,7 +69,7 @@ impl BedrockStreamAccumulator {
                             .and_then(|s| s.as_str())
                             .map(|s| s.to_string()),
                     },
-                    "tool_use" => BedrockBlock::ToolUse {
+                    "tool_use" => ContentBlock::ToolUse {
                         id: block
                             .and_then(|b| b.get("id"))
                             .and_then(|v| v.as_str())
@@ -79,7 +81,7 @@ impl BedrockStreamAccumulator {
                             .to_string(),
                         input_json: String::new(),
                     },
-                    _ => BedrockBlock::Text {
+                    _ => ContentBlock::Text {
                         text: block
                             .and_then(|b| b.get("text"))
                             .and_then(|t| t.as_str())
@@ -88,7 +90,7 @@ impl BedrockStreamAccumulator {
                     },
                 };
                 while self.blocks.len() <= index {
-                    self.blocks.push(BedrockBlock::Text {
+                    self.blocks.push(ContentBlock::Text {
                         text: String::new(),
                     });
                 }
@@ -105,14 +107,14 @@ impl BedrockStreamAccumulator {
                     return;
                 };
                 match (delta_type, block) {
-                    ("text_delta", BedrockBlock::Text { text }) => {
+                    ("text_delta", ContentBlock::Text { text }) => {
                         if let Some(t) = delta.and_then(|d| d.get("text")).and_then(|t| t.as_str())
                         {
                             text.push_str(t);
                             let _ = chunk_tx.send(ProviderStreamChunk::Text(t.to_string()));
                         }
                     }
-                    ("thinking_delta", BedrockBlock::Thinking { text, .. }) => {
+                    ("thinking_delta", ContentBlock::Thinking { text, .. }) => {
                         if let Some(t) = delta
                             .and_then(|d| d.get("thinking"))
                             .and_then(|t| t.as_str())
@@ -121,7 +123,7 @@ impl BedrockStreamAccumulator {
                             let _ = chunk_tx.send(ProviderStreamChunk::Thought(t.to_string()));
                         }
                     }
-                    ("signature_delta", BedrockBlock::Thinking { signature, .. }) => {
+                    ("signature_delta", ContentBlock::Thinking { signature, .. }) => {
                         if let Some(s) = delta
                             .and_then(|d| d.get("signature"))
                             .and_then(|s| s.as_str())
@@ -135,7 +137,7 @@ impl BedrockStreamAccumulator {
                             });
                         }
                     }
-                    ("input_json_delta", BedrockBlock::ToolUse { input_json, .. }) => {
+                    ("input_json_delta", ContentBlock::ToolUse { input_json, .. }) => {
                         if let Some(j) = delta
                             .and_then(|d| d.get("partial_json"))
                             .and_then(|j| j.as_str())
@@ -158,27 +160,27 @@ impl BedrockStreamAccumulator {
                     self.merge_usage(usage);
                 }
             }
-            _ => {}
-        }
-    }
-
-    // Anthropic splits input tokens onto `message_start` and output tokens onto `message_delta`.
-    fn merge_usage(&mut self, usage: &Value) {
-        let target = self
-            .usage
-            .get_or_insert_with(|| Value::Object(Default::default()));
-        if let (Some(target_obj), Some(src_obj)) = (target.as_object_mut(), usage.as_object()) {
-            for (k, v) in src_obj {
-                target_obj.insert(k.clone(), v.clone());
+            // Mid-stream failures (e.g. `overloaded_error`) arrive as an `error`
+            // event after a 200. Surface the error type as the finish reason so
+            // callers see a non-success, retryable `ProviderFinishReason::Other`.
+            "error" => {
+                let error_type = value
+                    .get("error")
+                    .and_then(|e| e.get("type"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or("error");
+                log::error!("Anthropic stream error event: {}", value);
+                self.stop_reason = Some(error_type.to_string());
             }
+            _ => {}
         }
     }
 
-    pub(super) fn into_response(self, model: &str) -> ProviderResponse {
+    fn into_response(self, model: &str) -> ProviderResponse {
         let mut parts = Vec::new();
         for block in self.blocks {
             match block {
-                BedrockBlock::Text { text } => {
+                ContentBlock::Text { text } => {
                     if !text.is_empty() {
                         parts.push(ProviderPart {
                             text: Some(text),
@@ -186,7 +188,7 @@ impl BedrockStreamAccumulator {
                         });
                     }
                 }
-                BedrockBlock::Thinking { text, signature } => {
+                ContentBlock::Thinking { text, signature } => {
                     parts.push(ProviderPart {
                         text: Some(text),
                         thought: Some(true),
@@ -194,7 +196,7 @@ impl BedrockStreamAccumulator {
                         ..Default::default()
                     });
                 }
-                BedrockBlock::ToolUse {
+                ContentBlock::ToolUse {
                     id,
                     name,
                     input_json,
@@ -225,3 +227,17 @@ impl BedrockStreamAccumulator {
         }
     }
 }
+
+impl AnthropicStreamAccumulator {
+    // Anthropic splits input tokens onto `message_start` and output tokens onto `message_delta`.
+    fn merge_usage(&mut self, usage: &Value) {
+        let target = self
+            .usage
+            .get_or_insert_with(|| Value::Object(Default::default()));
+        if let (Some(target_obj), Some(src_obj)) = (target.as_object_mut(), usage.as_object()) {
+            for (k, v) in src_obj {
+                target_obj.insert(k.clone(), v.clone());
+            }
+        }
+    }
+}
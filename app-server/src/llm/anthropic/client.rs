@@ -0,0 +1,653 @@
+#![cfg_attr(not(feature = "signals"), allow(dead_code))]
+
+use super::accumulator::AnthropicStreamAccumulator;
+use super::conversions::{
+    batch_custom_id, batch_custom_id_index, batch_result_to_inline_response, batch_state,
+    build_messages_body, parse_response_body,
+};
+use super::{ANTHROPIC_API_VERSION, AnthropicError, MessageBatch, MessageBatchResultLine};
+use crate::cache::{Cache, CacheTrait, keys::ANTHROPIC_BATCH_METADATA_CACHE_KEY};
+use crate::env;
+use crate::llm::{
+    LanguageModelClient, ProviderResult, default_headers_from_env,
+    models::{
+        ProviderBatchOperation, ProviderBatchOutput, ProviderRequest, ProviderRequestItem,
+        ProviderResponse, ProviderStreamChunk,
+    },
+    sse::accumulate_sse,
+};
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Batches end within 24 hours; the rest is headroom for late polling.
+const BATCH_METADATA_TTL_SECONDS: u64 = 7 * 24 * 3600;
+/// Once results have been read, metadata only needs to outlive repeated polls
+/// of the same ended batch.
+const BATCH_METADATA_READ_TTL_SECONDS: u64 = 3600;
+
+#[derive(Clone)]
+pub struct AnthropicClient {
+    client: reqwest::Client,
+    api_key: String,
+    api_base_url: String,
+    /// Holds `ProviderRequestItem::metadata` by batch id. The Batches API
+    /// does not echo anything but `custom_id` back, so metadata is kept in the
+    /// shared cache, where any replica polling the batch can read it.
+    cache: Arc<Cache>,
+}
+
+pub type AnthropicResult<T> = Result<T, AnthropicError>;
+
+impl AnthropicClient {
+    pub fn new(cache: Arc<Cache>) -> AnthropicResult<Self> {
+        let api_key = std::env::var(env::llm::API_KEY)
+            .map_err(|_| AnthropicError::config("LLM_API_KEY environment variable not set"))?;
+
+        let raw_base_url = std::env::var(env::llm::BASE_URL)
+            .ok()
+            .filter(|s| !s.trim().is_empty())
+            .unwrap_or_else(|| "https://api.anthropic.com/v1".to_string());
+
+        Self::with_config(api_key, &raw_base_url, cache)
+    }
+
+    fn with_config(
+        api_key: String,
+        raw_base_url: &str,
+        cache: Arc<Cache>,
+    ) -> AnthropicResult<Self> {
+        let api_base_url = raw_base_url.trim_end_matches('/').to_string();
+        let default_headers = default_headers_from_env().map_err(AnthropicError::config)?;
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(120))
+            .default_headers(default_headers)
+            .build()
+            .map_err(|e| AnthropicError::config(format!("Failed to build HTTP client: {}", e)))?;
+
+        Ok(Self {
+            client,
+            api_key,
+            api_base_url,
+            cache,
+        })
+    }
+
+    pub fn api_base_url(&self) -> &str {
+        &self.api_base_url
+    }
+
+    fn authorized(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        request
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> AnthropicResult<reqwest::Response> {
+        let response = self.authorized(request).send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            log::error!("Anthropic API error ({}): {}", status, error_text);
+            return Err(AnthropicError::from_response(status.as_u16(), error_text));
+        }
+
+        Ok(response)
+    }
+
+    async fn post_json(&self, path: &str, body: &Value) -> AnthropicResult<reqwest::Response> {
+        let url = format!("{}{}", self.api_base_url, path);
+        self.send(
+            self.client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .json(body),
+        )
+        .await
+    }
+
+    /// Download and parse the `.jsonl` results of an ended batch, ordered by
+    /// request index.
+    async fn get_batch_results(
+        &self,
+        batch_id: &str,
+        results_url: &str,
+    ) -> AnthropicResult<ProviderBatchOutput> {
+        let response = self.send(self.client.get(results_url)).await?;
+        let text = response.text().await?;
+
+        let mut lines = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str::<MessageBatchResultLine>)
+            .collect::<Result<Vec<_>, _>>()?;
+        lines.sort_by_key(|line| batch_custom_id_index(&line.custom_id).unwrap_or(usize::MAX));
+
+        let metadata_key = batch_metadata_key(batch_id);
+        let metadata = match self.cache.get::<Vec<Option<Value>>>(&metadata_key).await {
+            Ok(metadata) => metadata.unwrap_or_default(),
+            Err(e) => {
+                log::warn!("Failed to read metadata of Anthropic batch {batch_id}: {e:?}");
+                Vec::new()
+            }
+        };
+        // Not removed outright: another replica may be polling the same batch
+        if let Err(e) = self
+            .cache
+            .set_ttl(&metadata_key, BATCH_METADATA_READ_TTL_SECONDS)
+            .await
+        {
+            log::warn!("Failed to shorten metadata TTL of Anthropic batch {batch_id}: {e:?}");
+        }
+
+        let responses = lines
+            .into_iter()
+            .map(|line| {
+                let metadata = batch_custom_id_index(&line.custom_id)
+                    .and_then(|i| metadata.get(i).cloned().flatten());
+                // Succeeded results carry their own `message.model`.
+                batch_result_to_inline_response("", line.result, metadata)
+            })
+            .collect();
+
+        Ok(ProviderBatchOutput { responses })
+    }
+}
+
+impl LanguageModelClient for AnthropicClient {
+    fn supports_batch(&self) -> bool {
+        true
+    }
+
+    async fn generate_content(
+        &self,
+        model: &str,
+        request: &ProviderRequest,
+    ) -> ProviderResult<ProviderResponse> {
+        let mut body = build_messages_body(model, request);
+        body["model"] = json!(model);
+
+        let response = self.post_json("/messages", &body).await?;
+        let response_text = response.text().await.map_err(AnthropicError::from)?;
+        let response_json: Value =
+            serde_json::from_str(&response_text).map_err(AnthropicError::from)?;
+
+        Ok(parse_response_body(model, &response_json))
+    }
+
+    async fn generate_content_stream(
+        &self,
+        model: &str,
+        request: &ProviderRequest,
+        chunk_tx: &UnboundedSender<ProviderStreamChunk>,
+    ) -> ProviderResult<ProviderResponse> {
+        let mut body = build_messages_body(model, request);
+        body["model"] = json!(model);
+        body["stream"] = json!(true);
+
+        let response = self.post_json("/messages", &body).await?;
+
+        accumulate_sse::<AnthropicStreamAccumulator, AnthropicError>(
+            response.bytes_stream(),
+            model,
+            chunk_tx,
+        )
+        .await
+        .map_err(Into::into)
+    }
+
+    async fn create_batch(
+        &self,
+        model: &str,
+        requests: Vec<ProviderRequestItem>,
+        _display_name: Option<String>,
+    ) -> ProviderResult<ProviderBatchOperation> {
+        let mut metadata = Vec::with_capacity(requests.len());
+        let batch_requests: Vec<Value> = requests
+            .into_iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let mut params = build_messages_body(model, &item.request);
+                params["model"] = json!(model);
+                metadata.push(item.metadata);
+                json!({
+                    "custom_id": batch_custom_id(i),
+                    "params": params,
+                })
+            })
+            .collect();
+
+        let response = self
+            .post_json("/messages/batches", &json!({ "requests": batch_requests }))
+            .await?;
+        let batch: MessageBatch = response.json().await.map_err(AnthropicError::from)?;
+
+        // The batch exists at this point, so failing here would only make the
+        // caller submit it again. Its results still come back in request
+        // order, with `metadata: None`.
+        if let Err(e) = self
+            .cache
+            .insert_with_ttl(
+                &batch_metadata_key(&batch.id),
+                metadata,
+                BATCH_METADATA_TTL_SECONDS,
+            )
+            .await
+        {
+            log::error!(
+                "Failed to store metadata of Anthropic batch {}: {e:?}",
+                batch.id
+            );
+        }
+
+        Ok(ProviderBatchOperation {
+            name: batch.id.clone(),
+            done: false,
+            response: None,
+            error: None,
+            state: Some(batch_state(&batch)),
+        })
+    }
+
+    async fn get_batch(&self, batch_name: &str) -> ProviderResult<ProviderBatchOperation> {
+        let url = format!("{}/messages/batches/{}", self.api_base_url, batch_name);
+        let batch: MessageBatch = self
+            .send(self.client.get(&url))
+            .await?
+            .json()
+            .await
+            .map_err(AnthropicError::from)?;
+
+        let state = batch_state(&batch);
+        let done = batch.processing_status == "ended";
+        let response = match (done, batch.results_url.as_deref()) {
+            (true, Some(results_url)) => {
+                Some(self.get_batch_results(&batch.id, results_url).await?)
+            }
+            _ => None,
+        };
+        if done && response.is_none() {
+            log::warn!("Anthropic batch {} ended without a results_url", batch.id);
+        }
+
+        Ok(ProviderBatchOperation {
+            name: batch.id,
+            done,
+            response,
+            error: None,
+            state: Some(state),
+        })
+    }
+}
+
+fn batch_metadata_key(batch_id: &str) -> String {
+    format!("{ANTHROPIC_BATCH_METADATA_CACHE_KEY}:{batch_id}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::ProviderError;
+    use crate::llm::models::{
+        ProviderBatchState, ProviderContent, ProviderFinishReason, ProviderFunctionDeclaration,
+        ProviderGenerationConfig, ProviderPart, ProviderThinkingConfig, ProviderThinkingLevel,
+        ProviderTool,
+    };
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn make_cache() -> Arc<Cache> {
+        Arc::new(Cache::InMemory(
+            crate::cache::in_memory::InMemoryCache::new(None),
+        ))
+    }
+
+    fn client_with_cache(server: &MockServer, cache: Arc<Cache>) -> AnthropicClient {
+        AnthropicClient::with_config(
+            "test-key".to_string(),
+            &format!("{}/v1/", server.uri()),
+            cache,
+        )
+        .unwrap()
+    }
+
+    async fn client_for(server: &MockServer) -> AnthropicClient {
+        client_with_cache(server, make_cache())
+    }
+
+    fn request(text: &str) -> ProviderRequest {
+        ProviderRequest {
+            contents: vec![ProviderContent {
+                role: Some("user".to_string()),
+                parts: Some(vec![ProviderPart {
+                    text: Some(text.to_string()),
+                    ..Default::default()
+                }]),
+            }],
+            system_instruction: Some(ProviderContent {
+                role: None,
+                parts: Some(vec![ProviderPart {
+                    text: Some("Be terse".to_string()),
+                    ..Default::default()
+                }]),
+            }),
+            tools: None,
+            generation_config: None,
+            service_tier: None,
+            provider: None,
+            model_size: None,
+        }
+    }
+
+    fn parts(response: &ProviderResponse) -> Vec<ProviderPart> {
+        response.candidates.as_ref().unwrap()[0]
+            .content
+            .as_ref()
+            .unwrap()
+            .parts
+            .clone()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn generate_content_maps_text_tool_use_and_usage() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "test-key"))
+            .and(header("anthropic-version", ANTHROPIC_API_VERSION))
+            .and(body_partial_json(json!({
+                "model": "claude-sonnet-4-6",
+                "system": [{"type": "text", "text": "Be terse"}],
+                "tools": [{"name": "lookup", "input_schema": {"type": "object"}}],
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "model": "claude-sonnet-4-6",
+                "content": [
+                    {"type": "text", "text": "Looking it up."},
+                    {"type": "tool_use", "id": "toolu_1", "name": "lookup", "input": {"q": "otters"}}
+                ],
+                "stop_reason": "tool_use",
+                "usage": {
+                    "input_tokens": 10,
+                    "output_tokens": 5,
+                    "cache_read_input_tokens": 4,
+                    "cache_creation_input_tokens": 0
+                }
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut req = request("find otters");
+        req.tools = Some(vec![ProviderTool {
+            function_declarations: vec![ProviderFunctionDeclaration {
+                name: "lookup".to_string(),
+                description: "find a thing".to_string(),
+                parameters: json!({"type": "object"}),
+            }],
+        }]);
+
+        let client = client_for(&server).await;
+        let response = client
+            .generate_content("claude-sonnet-4-6", &req)
+            .await
+            .unwrap();
+
+        let parts = parts(&response);
+        assert_eq!(parts[0].text.as_deref(), Some("Looking it up."));
+        let call = parts[1].function_call.as_ref().unwrap();
+        assert_eq!(call.id.as_deref(), Some("toolu_1"));
+        assert_eq!(call.name, "lookup");
+        assert_eq!(call.args, Some(json!({"q": "otters"})));
+        assert_eq!(
+            response.candidates.as_ref().unwrap()[0].finish_reason,
+            Some(ProviderFinishReason::Stop)
+        );
+        let usage = response.usage_metadata.unwrap();
+        assert_eq!(usage.prompt_token_count, Some(14));
+        assert_eq!(usage.candidates_token_count, Some(5));
+        assert_eq!(usage.cache_read_input_tokens, Some(4));
+    }
+
+    #[tokio::test]
+    async fn generate_content_stream_forwards_thinking_as_thought_chunks() {
+        let body = concat!(
+            "event: message_start\n",
+            "data: {\"type\":\"message_start\",\"message\":{\"usage\":{\"input_tokens\":12,\"output_tokens\":1}}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":0,\"content_block\":{\"type\":\"thinking\",\"thinking\":\"\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"thinking_delta\",\"thinking\":\"Let me think\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"signature_delta\",\"signature\":\"sig\"}}\n\n",
+            "event: ping\n",
+            "data: {\"type\":\"ping\"}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":1,\"content_block\":{\"type\":\"text\",\"text\":\"\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"Hel\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":1,\"delta\":{\"type\":\"text_delta\",\"text\":\"lo\"}}\n\n",
+            "event: content_block_start\n",
+            "data: {\"type\":\"content_block_start\",\"index\":2,\"content_block\":{\"type\":\"tool_use\",\"id\":\"toolu_1\",\"name\":\"lookup\",\"input\":{}}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":2,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"{\\\"q\\\": \\\"ot\"}}\n\n",
+            "event: content_block_delta\n",
+            "data: {\"type\":\"content_block_delta\",\"index\":2,\"delta\":{\"type\":\"input_json_delta\",\"partial_json\":\"ters\\\"}\"}}\n\n",
+            "event: message_delta\n",
+            "data: {\"type\":\"message_delta\",\"delta\":{\"stop_reason\":\"tool_use\"},\"usage\":{\"output_tokens\":30}}\n\n",
+            "event: message_stop\n",
+            "data: {\"type\":\"message_stop\"}\n\n",
+        );
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(body_partial_json(json!({
+                "stream": true,
+                "thinking": {"type": "enabled", "budget_tokens": 16384},
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let mut req = request("hi");
+        req.generation_config = Some(ProviderGenerationConfig {
+            thinking_config: Some(ProviderThinkingConfig {
+                include_thoughts: Some(true),
+                thinking_level: Some(ProviderThinkingLevel::High),
+            }),
+            ..Default::default()
+        });
+
+        let client = client_for(&server).await;
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ProviderStreamChunk>();
+        let response = client
+            .generate_content_stream("claude-sonnet-4-6", &req, &tx)
+            .await
+            .unwrap();
+        drop(tx);
+
+        let mut thoughts = Vec::new();
+        let mut texts = Vec::new();
+        while let Ok(chunk) = rx.try_recv() {
+            match chunk {
+                ProviderStreamChunk::Thought(t) => thoughts.push(t),
+                ProviderStreamChunk::Text(t) => texts.push(t),
+            }
+        }
+        assert_eq!(thoughts, vec!["Let me think".to_string()]);
+        assert_eq!(texts, vec!["Hel".to_string(), "lo".to_string()]);
+
+        let parts = parts(&response);
+        assert_eq!(parts[0].thought, Some(true));
+        assert_eq!(parts[0].thought_signature.as_deref(), Some("sig"));
+        assert_eq!(parts[1].text.as_deref(), Some("Hello"));
+        assert_eq!(
+            parts[2].function_call.as_ref().unwrap().args,
+            Some(json!({"q": "otters"}))
+        );
+        let usage = response.usage_metadata.unwrap();
+        assert_eq!(usage.prompt_token_count, Some(12));
+        assert_eq!(usage.candidates_token_count, Some(30));
+    }
+
+    #[tokio::test]
+    async fn overloaded_error_is_retryable_resource_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(529).set_body_json(json!({
+                "type": "error",
+                "error": {"type": "overloaded_error", "message": "Overloaded"}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server).await;
+        let err = client
+            .generate_content("claude-sonnet-4-6", &request("hi"))
+            .await
+            .unwrap_err();
+
+        assert!(err.is_retryable());
+        assert!(err.is_resource_exhausted());
+        let ProviderError::ApiError {
+            status_code,
+            message,
+            ..
+        } = err
+        else {
+            panic!("expected ApiError");
+        };
+        assert_eq!(status_code, 529);
+        assert_eq!(message, "Overloaded");
+    }
+
+    #[tokio::test]
+    async fn invalid_request_error_is_not_retryable() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "type": "error",
+                "error": {"type": "invalid_request_error", "message": "max_tokens: required"}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server).await;
+        let err = client
+            .generate_content("claude-sonnet-4-6", &request("hi"))
+            .await
+            .unwrap_err();
+
+        assert!(!err.is_retryable());
+        assert!(!err.is_resource_exhausted());
+    }
+
+    #[tokio::test]
+    async fn batch_results_come_back_in_request_order_with_metadata() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches"))
+            .and(body_partial_json(json!({
+                "requests": [
+                    {"custom_id": "request-0", "params": {"model": "claude-haiku-4-5"}},
+                    {"custom_id": "request-1", "params": {"model": "claude-haiku-4-5"}},
+                ]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "msgbatch_1",
+                "type": "message_batch",
+                "processing_status": "in_progress",
+                "request_counts": {"processing": 2, "succeeded": 0, "errored": 0, "canceled": 0, "expired": 0},
+                "results_url": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "msgbatch_1",
+                "type": "message_batch",
+                "processing_status": "ended",
+                "request_counts": {"processing": 0, "succeeded": 1, "errored": 1, "canceled": 0, "expired": 0},
+                "results_url": format!("{}/v1/messages/batches/msgbatch_1/results", server.uri())
+            })))
+            .mount(&server)
+            .await;
+        let results = [
+            json!({
+                "custom_id": "request-1",
+                "result": {"type": "errored", "error": {"type": "error", "error": {"type": "invalid_request_error", "message": "bad"}}}
+            }),
+            json!({
+                "custom_id": "request-0",
+                "result": {"type": "succeeded", "message": {
+                    "model": "claude-haiku-4-5",
+                    "content": [{"type": "text", "text": "ok"}],
+                    "stop_reason": "end_turn",
+                    "usage": {"input_tokens": 3, "output_tokens": 1}
+                }}
+            }),
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1/results"))
+            .and(header("x-api-key", "test-key"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(results))
+            .mount(&server)
+            .await;
+
+        // Created by one replica, polled by another
+        let cache = make_cache();
+        let client = client_with_cache(&server, cache.clone());
+        let other_replica = client_with_cache(&server, cache);
+        let requests = vec![
+            ProviderRequestItem {
+                request: request("first"),
+                metadata: Some(json!({"run": 0})),
+            },
+            ProviderRequestItem {
+                request: request("second"),
+                metadata: Some(json!({"run": 1})),
+            },
+        ];
+        let op = client
+            .create_batch("claude-haiku-4-5", requests, None)
+            .await
+            .unwrap();
+        assert_eq!(op.name, "msgbatch_1");
+        assert!(!op.done);
+        assert_eq!(op.state, Some(ProviderBatchState::Running));
+
+        let op = other_replica.get_batch("msgbatch_1").await.unwrap();
+        assert!(op.done);
+        assert_eq!(op.state, Some(ProviderBatchState::Succeeded));
+        let responses = op.response.unwrap().responses;
+        assert_eq!(responses.len(), 2);
+
+        assert_eq!(responses[0].metadata, Some(json!({"run": 0})));
+        let first = responses[0].response.as_ref().unwrap();
+        assert_eq!(parts(first)[0].text.as_deref(), Some("ok"));
+        assert_eq!(first.model_version.as_deref(), Some("claude-haiku-4-5"));
+
+        assert_eq!(responses[1].metadata, Some(json!({"run": 1})));
+        let error = responses[1].error.as_ref().unwrap();
+        assert_eq!(error.code, 400);
+        assert_eq!(error.message, "bad");
+    }
+}
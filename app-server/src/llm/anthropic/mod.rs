@@ -0,0 +1,135 @@
+#![cfg_attr(not(feature = "signals"), allow(dead_code))]
+
+pub(crate) mod accumulator;
+pub mod client;
+pub(crate) mod conversions;
+
+pub use client::AnthropicClient;
+
+use serde::Deserialize;
+use serde_json::Value;
+use thiserror::Error;
+
+/// `anthropic-version` header sent on every request.
+pub const ANTHROPIC_API_VERSION: &str = "2023-06-01";
+
+#[derive(Debug, Error)]
+pub enum AnthropicError {
+    #[error("Request failed: {0}")]
+    RequestError(#[from] reqwest::Error),
+
+    #[error("Failed to parse response: {0}")]
+    ParseError(#[from] serde_json::Error),
+
+    #[error("Configuration error: {0}")]
+    ConfigError(String),
+
+    #[error("Anthropic API error ({status_code}): {message}")]
+    ApiError { status_code: u16, message: String },
+}
+
+impl AnthropicError {
+    pub fn config<S: Into<String>>(msg: S) -> Self {
+        AnthropicError::ConfigError(msg.into())
+    }
+
+    /// Build an `ApiError` from a non-2xx response body, which is
+    /// `{"type": "error", "error": {"type": "...", "message": "..."}}`.
+    pub fn from_response(status_code: u16, error_text: String) -> Self {
+        let message = serde_json::from_str::<Value>(&error_text)
+            .ok()
+            .and_then(|v| {
+                v.get("error")
+                    .and_then(|e| e.get("message"))
+                    .and_then(|m| m.as_str())
+                    .map(|s| s.to_string())
+            })
+            .unwrap_or(error_text);
+        AnthropicError::ApiError {
+            status_code,
+            message,
+        }
+    }
+}
+
+impl From<AnthropicError> for super::ProviderError {
+    fn from(e: AnthropicError) -> Self {
+        match e {
+            AnthropicError::RequestError(e) => super::ProviderError::RequestError(e.to_string()),
+            AnthropicError::ParseError(e) => super::ProviderError::ParseError(e.to_string()),
+            AnthropicError::ConfigError(s) => super::ProviderError::ConfigError(s),
+            AnthropicError::ApiError {
+                status_code,
+                message,
+            } => {
+                // 529 is Anthropic's `overloaded_error`: back off like a 429.
+                let resource_exhausted = status_code == 429 || status_code == 529;
+                let retryable = resource_exhausted || status_code >= 500;
+                super::ProviderError::ApiError {
+                    status_code,
+                    message,
+                    retryable,
+                    resource_exhausted,
+                }
+            }
+        }
+    }
+}
+
+/// HTTP status Anthropic returns for an error `type`, used to give errored
+/// batch results the same codes as the equivalent synchronous failure.
+pub fn error_type_status_code(error_type: &str) -> u16 {
+    match error_type {
+        "invalid_request_error" => 400,
+        "authentication_error" => 401,
+        "permission_error" => 403,
+        "not_found_error" => 404,
+        "request_too_large" => 413,
+        "rate_limit_error" => 429,
+        "overloaded_error" => 529,
+        _ => 500,
+    }
+}
+
+// https://docs.anthropic.com/en/api/creating-message-batches
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageBatch {
+    pub id: String,
+    /// `in_progress` | `canceling` | `ended`
+    pub processing_status: String,
+    #[serde(default)]
+    pub request_counts: MessageBatchRequestCounts,
+    #[serde(default)]
+    pub results_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Default)]
+pub struct MessageBatchRequestCounts {
+    #[serde(default)]
+    pub processing: u64,
+    #[serde(default)]
+    pub succeeded: u64,
+    #[serde(default)]
+    pub errored: u64,
+    #[serde(default)]
+    pub canceled: u64,
+    #[serde(default)]
+    pub expired: u64,
+}
+
+/// One line of the `.jsonl` batch results file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageBatchResultLine {
+    pub custom_id: String,
+    pub result: MessageBatchResult,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessageBatchResult {
+    Succeeded { message: Value },
+    Errored { error: Value },
+    Canceled,
+    Expired,
+}
@@ -1,10 +1,13 @@
+pub mod anthropic;
 pub mod bedrock;
 pub mod gemini;
 pub mod mock;
 pub mod models;
 pub mod openai;
+pub(crate) mod routing;
 pub(crate) mod sse;
 
+pub use anthropic::AnthropicClient;
 pub use bedrock::BedrockClient;
 pub use gemini::GeminiClient;
 pub use mock::MockProviderClient;
@@ -14,11 +17,19 @@ pub use openai::OpenAIClient;
 use enum_dispatch::enum_dispatch;
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
 use std::collections::HashMap;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use thiserror::Error;
 use tokio::sync::mpsc::UnboundedSender;
+use tracing::Instrument;
+use uuid::Uuid;
 
+use crate::cache::Cache;
 use crate::env;
+use crate::instrumentation::spans::{
+    InternalSpan, SpanType, record_error, set_attr_i64, set_attr_str, set_usage,
+};
+use routing::{AttemptOutcome, CircuitBreakers, RoutingPolicy};
 
 #[derive(Debug, Error)]
 pub enum ProviderError {
@@ -140,6 +151,7 @@ pub(crate) enum ProviderClient {
     Gemini(GeminiClient),
     Bedrock(BedrockClient),
     OpenAI(OpenAIClient),
+    Anthropic(AnthropicClient),
     Mock(MockProviderClient),
 }
 
@@ -170,8 +182,8 @@ pub fn parsing_provider() -> Option<String> {
 }
 
 /// `LLM_API_KEY` is the single key shared by single-key providers (gemini,
-/// openai). It belongs to whichever provider `LLM_PROVIDER` names — gemini
-/// and openai cannot both initialize from it.
+/// openai, anthropic). It belongs to whichever provider `LLM_PROVIDER` names —
+/// no two of them can initialize from it at once.
 fn has_llm_api_key() -> bool {
     std::env::var(env::llm::API_KEY).is_ok_and(|v| !v.is_empty())
 }
@@ -181,9 +193,16 @@ fn has_gemini_credentials() -> bool {
     llm_provider_env() == "gemini" && has_llm_api_key()
 }
 
-/// True when `LLM_PROVIDER=openai` and `LLM_API_KEY` is set.
+/// True when `LLM_PROVIDER=openai` and either `LLM_API_KEY` is set or
+/// `LLM_BASE_URL` points at a (keyless) self-hosted server.
 fn has_openai_credentials() -> bool {
-    llm_provider_env() == "openai" && has_llm_api_key()
+    llm_provider_env() == "openai"
+        && (has_llm_api_key() || std::env::var(env::llm::BASE_URL).is_ok_and(|v| !v.is_empty()))
+}
+
+/// True when `LLM_PROVIDER=anthropic` and `LLM_API_KEY` is set.
+fn has_anthropic_credentials() -> bool {
+    llm_provider_env() == "anthropic" && has_llm_api_key()
 }
 
 /// Bedrock initializes whenever AWS creds are present, independent of
@@ -282,6 +301,128 @@ pub fn request_to_tools_attr(request: &ProviderRequest) -> Option<serde_json::Va
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::llm::mock::GenerateFailureMode;
+
+    fn routed_client(
+        primary: &MockProviderClient,
+        fallback: &MockProviderClient,
+        max_retries: u32,
+        failure_threshold: u32,
+    ) -> LlmClient {
+        let mut policy = RoutingPolicy::from_env().with_fallbacks(ModelSize::Medium, &["fallback"]);
+        policy.max_retries = max_retries;
+        policy.failure_threshold = failure_threshold;
+        policy.base_delay = std::time::Duration::from_millis(100);
+        LlmClient::from_provider("mock", ProviderClient::Mock(primary.clone())).with_routing(
+            vec![("fallback", ProviderClient::Mock(fallback.clone()))],
+            policy,
+        )
+    }
+
+    fn request() -> ProviderRequest {
+        ProviderRequest {
+            contents: vec![ProviderContent {
+                role: Some("user".to_string()),
+                parts: Some(vec![ProviderPart {
+                    text: Some("hi".to_string()),
+                    ..Default::default()
+                }]),
+            }],
+            system_instruction: None,
+            tools: None,
+            generation_config: None,
+            service_tier: None,
+            provider: None,
+            model_size: None,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retryable_errors_are_retried_on_the_same_provider() {
+        let primary = MockProviderClient::with_generate_failure(2, GenerateFailureMode::Timeout);
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 2, 5);
+
+        client.generate_content(&request()).await.unwrap();
+
+        assert_eq!(primary.generate_call_count(), 3);
+        assert_eq!(fallback.generate_call_count(), 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn exhausted_retries_fail_over_to_the_next_provider() {
+        let primary =
+            MockProviderClient::with_generate_failure(usize::MAX, GenerateFailureMode::Timeout);
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 1, 5);
+
+        client.generate_content(&request()).await.unwrap();
+
+        assert_eq!(primary.generate_call_count(), 2);
+        assert_eq!(fallback.generate_call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn resource_exhausted_fails_over_without_retrying() {
+        let primary = MockProviderClient::with_generate_failure(
+            usize::MAX,
+            GenerateFailureMode::Retryable429,
+        );
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 2, 1);
+
+        client.generate_content(&request()).await.unwrap();
+        client.generate_content(&request()).await.unwrap();
+
+        // Rate limits don't open the circuit, so the primary is tried each time.
+        assert_eq!(primary.generate_call_count(), 2);
+        assert_eq!(fallback.generate_call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn non_retryable_errors_are_returned_without_failover() {
+        let primary = MockProviderClient::with_generate_failure(
+            usize::MAX,
+            GenerateFailureMode::NonRetryable,
+        );
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 2, 5);
+
+        let err = client.generate_content(&request()).await.unwrap_err();
+
+        assert!(!err.is_retryable());
+        assert_eq!(primary.generate_call_count(), 1);
+        assert_eq!(fallback.generate_call_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn open_circuit_skips_the_provider() {
+        let primary =
+            MockProviderClient::with_generate_failure(usize::MAX, GenerateFailureMode::Timeout);
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 0, 1);
+
+        client.generate_content(&request()).await.unwrap();
+        client.generate_content(&request()).await.unwrap();
+
+        assert_eq!(primary.generate_call_count(), 1);
+        assert_eq!(fallback.generate_call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn flex_requests_get_a_single_attempt_on_the_primary() {
+        let primary = MockProviderClient::with_generate_failure(1, GenerateFailureMode::Timeout);
+        let fallback = MockProviderClient::new();
+        let client = routed_client(&primary, &fallback, 2, 5);
+        let mut request = request();
+        request.service_tier = Some("flex".to_string());
+
+        let err = client.generate_content(&request).await.unwrap_err();
+
+        assert!(err.is_retryable());
+        assert_eq!(primary.generate_call_count(), 1);
+        assert_eq!(fallback.generate_call_count(), 0);
+    }
 
     #[test]
     fn parse_default_headers_json_accepts_string_map() {
@@ -355,6 +496,9 @@ pub fn model_for_size(provider: &str, size: ModelSize) -> String {
         ("openai", ModelSize::Small) => "gpt-5.4-mini".to_string(),
         ("openai", ModelSize::Medium) => "gpt-5.4".to_string(),
         ("openai", ModelSize::Large) => "gpt-5.5".to_string(),
+        ("anthropic", ModelSize::Small) => "claude-haiku-4-5-20251001".to_string(),
+        ("anthropic", ModelSize::Medium) => "claude-sonnet-4-6".to_string(),
+        ("anthropic", ModelSize::Large) => "claude-opus-4-7".to_string(),
         _ => "".to_string(),
     }
 }
@@ -374,14 +518,23 @@ fn finalize_client(client: &ProviderClient) -> Result<(), ProviderError> {
 /// requests based on optional `provider` and `model_size` fields on
 /// [`ProviderRequest`]. Callers never deal with provider resolution --
 /// they just call `generate_content(&request)`.
+///
+/// Calls go through the [`RoutingPolicy`]: retryable errors are retried with
+/// backoff, `resource_exhausted` fails over to the next provider in the
+/// size's fallback chain, and providers whose circuit is open are skipped.
+/// Each attempt is traced as an `llm_attempt` internal span when
+/// `LLM_INTERNAL_PROJECT_ID` is set.
 #[derive(Clone)]
 pub struct LlmClient {
     providers: HashMap<String, ProviderClient>,
     default_provider: String,
+    policy: Arc<RoutingPolicy>,
+    breakers: Arc<CircuitBreakers>,
+    trace_project_id: Option<Uuid>,
 }
 
 impl LlmClient {
-    pub async fn new() -> Result<Self, ProviderError> {
+    pub async fn new(cache: Arc<Cache>) -> Result<Self, ProviderError> {
         let default_provider = resolve_provider_name()?;
 
         let mut providers = HashMap::new();
@@ -408,6 +561,17 @@ impl LlmClient {
             providers.insert("openai".to_string(), ProviderClient::OpenAI(client));
         }
 
+        if has_anthropic_credentials() {
+            let client = AnthropicClient::new(cache.clone()).map_err(|e| {
+                ProviderError::ConfigError(format!("Failed to create Anthropic client: {e}"))
+            })?;
+            log::info!(
+                "Initialized Anthropic provider at {}",
+                client.api_base_url()
+            );
+            providers.insert("anthropic".to_string(), ProviderClient::Anthropic(client));
+        }
+
         if default_provider == "mock" {
             let client = MockProviderClient::new();
             log::info!("Initialized Mock provider");
@@ -423,70 +587,303 @@ impl LlmClient {
 
         finalize_client(providers.get(&default_provider).unwrap())?;
 
+        let policy = RoutingPolicy::from_env();
+        for provider in policy.fallback_providers() {
+            if !providers.contains_key(provider) {
+                log::warn!(
+                    "LLM fallback provider '{}' is not initialized (missing credentials?); skipping it",
+                    provider
+                );
+            }
+        }
+
+        let trace_project_id = std::env::var(env::llm::INTERNAL_PROJECT_ID)
+            .ok()
+            .and_then(|v| Uuid::parse_str(v.trim()).ok());
+
         Ok(Self {
             providers,
             default_provider,
+            breakers: Arc::new(CircuitBreakers::new(&policy)),
+            policy: Arc::new(policy),
+            trace_project_id,
         })
     }
 
     /// Build an `LlmClient` directly from a `ProviderClient` for tests.
     #[cfg(test)]
     pub fn from_provider(name: &str, client: ProviderClient) -> Self {
+        let policy = RoutingPolicy::from_env();
         let mut providers = HashMap::new();
         providers.insert(name.to_string(), client);
         Self {
             providers,
             default_provider: name.to_string(),
+            breakers: Arc::new(CircuitBreakers::new(&policy)),
+            policy: Arc::new(policy),
+            trace_project_id: None,
         }
     }
 
+    /// Register an extra provider and replace the routing policy, for tests.
+    #[cfg(test)]
+    pub fn with_routing(
+        mut self,
+        extra: Vec<(&str, ProviderClient)>,
+        policy: RoutingPolicy,
+    ) -> Self {
+        for (name, client) in extra {
+            self.providers.insert(name.to_string(), client);
+        }
+        self.breakers = Arc::new(CircuitBreakers::new(&policy));
+        self.policy = Arc::new(policy);
+        self
+    }
+
     fn resolve(
         &self,
         request: &ProviderRequest,
-    ) -> Result<(&ProviderClient, String), ProviderError> {
+    ) -> Result<(&str, &ProviderClient, String), ProviderError> {
         let provider_name = request
             .provider
             .as_deref()
             .unwrap_or(&self.default_provider);
-        let (resolved_provider, client) = if let Some(c) = self.providers.get(provider_name) {
-            (provider_name, c)
-        } else if let Some(c) = self.providers.get(&self.default_provider) {
-            // Silent fallback. OSS deployments with a single registered
-            // provider will hit this on every cloud-pinned call (e.g.
-            // `provider: Some("bedrock")` while LLM_PROVIDER=openai),
-            // which is expected and not worth warning about.
-            (self.default_provider.as_str(), c)
-        } else {
-            return Err(ProviderError::ConfigError(format!(
-                "Provider '{}' not available and default '{}' also missing. Available: {:?}",
-                provider_name,
-                self.default_provider,
-                self.providers.keys().collect::<Vec<_>>()
-            )));
-        };
+        let (resolved_provider, client) =
+            if let Some((name, c)) = self.providers.get_key_value(provider_name) {
+                (name.as_str(), c)
+            } else if let Some(c) = self.providers.get(&self.default_provider) {
+                // Silent fallback. OSS deployments with a single registered
+                // provider will hit this on every cloud-pinned call (e.g.
+                // `provider: Some("bedrock")` while LLM_PROVIDER=openai),
+                // which is expected and not worth warning about.
+                (self.default_provider.as_str(), c)
+            } else {
+                return Err(ProviderError::ConfigError(format!(
+                    "Provider '{}' not available and default '{}' also missing. Available: {:?}",
+                    provider_name,
+                    self.default_provider,
+                    self.providers.keys().collect::<Vec<_>>()
+                )));
+            };
         let size = request.model_size.unwrap_or(ModelSize::Medium);
         let model = model_for_size(resolved_provider, size);
-        Ok((client, model))
+        Ok((resolved_provider, client, model))
+    }
+
+    /// The primary provider for `request` followed by the registered
+    /// providers of its size's fallback chain, each with its model id.
+    fn route(
+        &self,
+        request: &ProviderRequest,
+    ) -> Result<Vec<(&str, &ProviderClient, String)>, ProviderError> {
+        let mut chain = vec![self.resolve(request)?];
+        // Flex-tier callers own their flex -> standard fallback and need
+        // to see the first failure, so they only get the primary.
+        if request.service_tier.is_some() {
+            return Ok(chain);
+        }
+        let size = request.model_size.unwrap_or(ModelSize::Medium);
+        for name in self.policy.fallbacks(size) {
+            if chain.iter().any(|(n, _, _)| n == name) {
+                continue;
+            }
+            if let Some((name, client)) = self.providers.get_key_value(name) {
+                chain.push((name.as_str(), client, model_for_size(name, size)));
+            }
+        }
+        Ok(chain)
+    }
+
+    /// Run `call` against the routed providers under the routing policy.
+    /// `committed` reports whether output already reached the caller (e.g.
+    /// forwarded stream chunks), after which an error is final.
+    async fn call_with_policy<'a, F, Fut>(
+        &'a self,
+        request: &ProviderRequest,
+        call: F,
+        committed: impl Fn() -> bool,
+    ) -> ProviderResult<ProviderResponse>
+    where
+        F: Fn(&'a ProviderClient, String) -> Fut,
+        Fut: Future<Output = ProviderResult<ProviderResponse>>,
+    {
+        let chain = self.route(request)?;
+        let max_retries = if request.service_tier.is_some() {
+            0
+        } else {
+            self.policy.max_retries
+        };
+
+        let mut attempt = 0;
+        let mut last_error = None;
+        for (provider, client, model) in chain {
+            if !self.breakers.try_acquire(provider) {
+                log::warn!("Skipping LLM provider '{}': circuit open", provider);
+                if let Some(span) = self.attempt_span(provider, &model, attempt + 1) {
+                    set_attr_str(
+                        &span,
+                        "llm.attempt.outcome",
+                        AttemptOutcome::CircuitOpen.as_str(),
+                    );
+                }
+                last_error.get_or_insert_with(|| ProviderError::ApiError {
+                    status_code: 503,
+                    message: format!("Circuit open for LLM provider '{provider}'"),
+                    retryable: true,
+                    resource_exhausted: false,
+                });
+                continue;
+            }
+
+            for retry in 0..=max_retries {
+                attempt += 1;
+                let span = self.attempt_span(provider, &model, attempt);
+                let result = call(client, model.clone())
+                    .instrument(span.clone().unwrap_or_else(tracing::Span::none))
+                    .await;
+
+                let error = match result {
+                    Ok(response) => {
+                        self.breakers.record(provider, true);
+                        if let Some(span) = &span {
+                            set_attr_str(
+                                span,
+                                "llm.attempt.outcome",
+                                AttemptOutcome::Success.as_str(),
+                            );
+                            if let Some(usage) = response.usage_metadata.as_ref() {
+                                set_usage(
+                                    span,
+                                    usage.prompt_token_count,
+                                    usage.cache_read_input_tokens,
+                                    usage.candidates_token_count,
+                                );
+                            }
+                        }
+                        return Ok(response);
+                    }
+                    Err(e) => e,
+                };
+
+                // Rate limits aren't outages: they fail over without
+                // counting against the provider's circuit.
+                self.breakers.record(
+                    provider,
+                    !error.is_retryable() || error.is_resource_exhausted(),
+                );
+                if let Some(span) = &span {
+                    record_error(span, error.to_string());
+                }
+
+                let outcome = AttemptOutcome::after_error(
+                    &error,
+                    committed(),
+                    retry < max_retries,
+                    self.breakers.is_open(provider),
+                );
+                if let Some(span) = &span {
+                    set_attr_str(span, "llm.attempt.outcome", outcome.as_str());
+                }
+
+                match outcome {
+                    AttemptOutcome::Retry => {
+                        let delay = self.policy.backoff(retry);
+                        if let Some(span) = &span {
+                            set_attr_i64(span, "llm.attempt.backoff_ms", delay.as_millis() as i64);
+                        }
+                        log::warn!(
+                            "LLM provider '{}' failed (attempt {}), retrying in {:?}: {}",
+                            provider,
+                            attempt,
+                            delay,
+                            error
+                        );
+                        last_error = Some(error);
+                        tokio::time::sleep(delay).await;
+                    }
+                    AttemptOutcome::Failover => {
+                        log::warn!(
+                            "LLM provider '{}' failed (attempt {}), failing over: {}",
+                            provider,
+                            attempt,
+                            error
+                        );
+                        last_error = Some(error);
+                        break;
+                    }
+                    _ => return Err(error),
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ConfigError("No LLM provider available for request".to_string())
+        }))
+    }
+
+    /// One internal span per provider call, nested under the caller's
+    /// current span. `None` when attempt tracing is disabled.
+    fn attempt_span(&self, provider: &str, model: &str, attempt: u32) -> Option<tracing::Span> {
+        let project_id = self.trace_project_id?;
+        let span = InternalSpan::wrap(
+            tracing::info_span!(target: "lmnr::internal", "llm_attempt"),
+            SpanType::Default,
+        )
+        .project(Some(project_id))
+        .build();
+        set_attr_str(&span, "llm.attempt.provider", provider);
+        set_attr_str(&span, "llm.attempt.model", model);
+        set_attr_i64(&span, "llm.attempt.number", attempt as i64);
+        Some(span)
     }
 
     pub async fn generate_content(
         &self,
         request: &ProviderRequest,
     ) -> ProviderResult<ProviderResponse> {
-        let (client, model) = self.resolve(request)?;
-        client.generate_content(&model, request).await
+        self.call_with_policy(
+            request,
+            |client, model| async move { client.generate_content(&model, request).await },
+            || false,
+        )
+        .await
     }
 
+    /// Streams through a per-attempt channel so a failed attempt can be
+    /// retried only while nothing has been forwarded to `chunk_tx` yet.
     #[cfg_attr(not(feature = "signals"), allow(dead_code))]
     pub async fn generate_content_stream(
         &self,
         request: &ProviderRequest,
         chunk_tx: &UnboundedSender<ProviderStreamChunk>,
     ) -> ProviderResult<ProviderResponse> {
-        let (client, model) = self.resolve(request)?;
-        client
-            .generate_content_stream(&model, request, chunk_tx)
-            .await
+        let forwarded = AtomicBool::new(false);
+        self.call_with_policy(
+            request,
+            |client, model| {
+                let forwarded = &forwarded;
+                async move {
+                    let (attempt_tx, mut attempt_rx) = tokio::sync::mpsc::unbounded_channel();
+                    let call = async move {
+                        let result = client
+                            .generate_content_stream(&model, request, &attempt_tx)
+                            .await;
+                        drop(attempt_tx);
+                        result
+                    };
+                    let forward = async {
+                        while let Some(chunk) = attempt_rx.recv().await {
+                            forwarded.store(true, Ordering::Relaxed);
+                            let _ = chunk_tx.send(chunk);
+                        }
+                    };
+                    let (result, ()) = tokio::join!(call, forward);
+                    result
+                }
+            },
+            || forwarded.load(Ordering::Relaxed),
+        )
+        .await
     }
 
     /// Resolve `(model, provider)` strings for `request` without firing
@@ -508,18 +905,21 @@ impl LlmClient {
         (model, resolved_provider.to_string())
     }
 
+    /// Batches bypass the routing policy: a batch id only means something
+    /// to the provider that created it, and `get_batch` polls the default.
     #[cfg_attr(not(feature = "signals"), allow(dead_code))]
     pub async fn create_batch(
         &self,
         requests: Vec<ProviderRequestItem>,
         display_name: Option<String>,
     ) -> ProviderResult<ProviderBatchOperation> {
-        let (client, model) = requests
+        let (_, client, model) = requests
             .first()
             .map(|r| self.resolve(&r.request))
             .transpose()?
             .unwrap_or_else(|| {
                 (
+                    self.default_provider.as_str(),
                     self.providers.get(&self.default_provider).unwrap(),
                     model_for_size(&self.default_provider, ModelSize::Medium),
                 )
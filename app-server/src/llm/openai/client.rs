@@ -3,7 +3,8 @@
 use super::OpenAIError;
 use super::accumulator::OpenAIStreamAccumulator;
 use super::conversions::{
-    parse_openai_response, provider_request_to_openai_body, provider_request_to_openai_stream_body,
+    extract_text_tool_calls, into_stream_body, parse_openai_response,
+    provider_request_to_json_tools_body, provider_request_to_openai_body,
 };
 use crate::env;
 use crate::llm::{
@@ -12,14 +13,54 @@ use crate::llm::{
     sse::accumulate_sse,
 };
 use serde_json::Value;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
 
+/// How tool declarations reach the server (`LLM_TOOL_CALL_MODE`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToolCallMode {
+    /// Native `tools`, switching to [`ToolCallMode::Json`] for the rest of
+    /// the process the first time the server rejects them.
+    Auto,
+    /// Always native `tools`.
+    Native,
+    /// Tools described in the system prompt, calls parsed from JSON text.
+    /// For self-hosted servers without tool-call support.
+    Json,
+}
+
+impl ToolCallMode {
+    fn from_env() -> Self {
+        match env::llm::TOOL_CALL_MODE
+            .get()
+            .trim()
+            .to_lowercase()
+            .as_str()
+        {
+            "native" => ToolCallMode::Native,
+            "json" => ToolCallMode::Json,
+            "auto" | "" => ToolCallMode::Auto,
+            other => {
+                log::warn!(
+                    "Ignoring unrecognized LLM_TOOL_CALL_MODE={:?} (expected auto, native or json)",
+                    other
+                );
+                ToolCallMode::Auto
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct OpenAIClient {
     client: reqwest::Client,
-    api_key: String,
+    /// `None` for self-hosted servers that don't check keys.
+    api_key: Option<String>,
     api_base_url: String,
+    tool_call_mode: ToolCallMode,
+    native_tools_rejected: Arc<AtomicBool>,
 }
 
 pub type OpenAIResult<T> = Result<T, OpenAIError>;
@@ -27,12 +68,28 @@ pub type OpenAIResult<T> = Result<T, OpenAIError>;
 impl OpenAIClient {
     pub fn new() -> OpenAIResult<Self> {
         let api_key = std::env::var(env::llm::API_KEY)
-            .map_err(|_| OpenAIError::config("LLM_API_KEY environment variable not set"))?;
-
-        let raw_base_url = std::env::var(env::llm::BASE_URL)
             .ok()
-            .filter(|s| !s.trim().is_empty())
-            .unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+            .filter(|s| !s.trim().is_empty());
+        let base_url = std::env::var(env::llm::BASE_URL)
+            .ok()
+            .filter(|s| !s.trim().is_empty());
+
+        // A key is only optional when pointing at a self-hosted server.
+        if api_key.is_none() && base_url.is_none() {
+            return Err(OpenAIError::config(
+                "LLM_API_KEY environment variable not set (required unless LLM_BASE_URL is set)",
+            ));
+        }
+
+        let raw_base_url = base_url.unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+        Self::with_config(api_key, &raw_base_url, ToolCallMode::from_env())
+    }
+
+    fn with_config(
+        api_key: Option<String>,
+        raw_base_url: &str,
+        tool_call_mode: ToolCallMode,
+    ) -> OpenAIResult<Self> {
         let api_base_url = raw_base_url.trim_end_matches('/').to_string();
         let default_headers = default_headers_from_env().map_err(OpenAIError::config)?;
 
@@ -47,42 +104,36 @@ impl OpenAIClient {
             client,
             api_key,
             api_base_url,
+            tool_call_mode,
+            native_tools_rejected: Arc::new(AtomicBool::new(false)),
         })
     }
 
     pub fn api_base_url(&self) -> &str {
         &self.api_base_url
     }
-}
-
-impl LanguageModelClient for OpenAIClient {
-    async fn generate_content(
-        &self,
-        model: &str,
-        request: &ProviderRequest,
-    ) -> ProviderResult<ProviderResponse> {
-        let body = provider_request_to_openai_body(model, request);
 
+    async fn post_chat(&self, body: &Value) -> OpenAIResult<reqwest::Response> {
         let url = format!("{}/chat/completions", self.api_base_url);
-        let response = self
+        let mut request = self
             .client
             .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
             .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(OpenAIError::from)?;
+            .json(body);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("Authorization", format!("Bearer {}", api_key));
+        }
+        let response = request.send().await?;
 
         let status = response.status();
         if !status.is_success() {
             let error_text = response.text().await.unwrap_or_default();
             log::error!("OpenAI API error ({}): {}", status, error_text);
-            let message = serde_json::from_str::<serde_json::Value>(&error_text)
+            let message = serde_json::from_str::<Value>(&error_text)
                 .ok()
                 .and_then(|v| {
                     v.get("error")
-                        .and_then(|e| e.get("message"))
+                        .and_then(|e| e.get("message").or(Some(e)))
                         .and_then(|m| m.as_str())
                         .map(|s| s.to_string())
                 })
@@ -90,15 +141,84 @@ impl LanguageModelClient for OpenAIClient {
             return Err(OpenAIError::ApiError {
                 status_code: status.as_u16(),
                 message,
+            });
+        }
+
+        Ok(response)
+    }
+
+    /// Send a chat completion, choosing native or JSON-in-text tools per
+    /// [`ToolCallMode`]. In `Auto` mode a 400/422 that mentions tools (vLLM
+    /// without `--enable-auto-tool-choice`, Ollama models without tool
+    /// templates) is retried once as JSON-in-text, and later calls skip
+    /// straight to it.
+    async fn send_chat(
+        &self,
+        model: &str,
+        request: &ProviderRequest,
+        stream: bool,
+    ) -> OpenAIResult<reqwest::Response> {
+        let build_body = |json_tools: bool| {
+            let body = if json_tools {
+                provider_request_to_json_tools_body(model, request)
+            } else {
+                provider_request_to_openai_body(model, request)
+            };
+            if stream { into_stream_body(body) } else { body }
+        };
+
+        let has_tools = request
+            .tools
+            .iter()
+            .flatten()
+            .any(|t| !t.function_declarations.is_empty());
+        let native_tools = match self.tool_call_mode {
+            ToolCallMode::Native => true,
+            ToolCallMode::Json => false,
+            ToolCallMode::Auto => !self.native_tools_rejected.load(Ordering::Relaxed),
+        };
+
+        if has_tools && native_tools {
+            match self.post_chat(&build_body(false)).await {
+                Err(e) if self.tool_call_mode == ToolCallMode::Auto && rejects_native_tools(&e) => {
+                    log::warn!(
+                        "LLM server at {} rejected native tool calls, falling back to JSON-in-text tool calls: {}",
+                        self.api_base_url,
+                        e
+                    );
+                    self.native_tools_rejected.store(true, Ordering::Relaxed);
+                }
+                result => return result,
             }
-            .into());
         }
 
+        self.post_chat(&build_body(has_tools)).await
+    }
+}
+
+fn rejects_native_tools(error: &OpenAIError) -> bool {
+    matches!(
+        error,
+        OpenAIError::ApiError { status_code: 400 | 422, message }
+            if message.to_lowercase().contains("tool")
+    )
+}
+
+impl LanguageModelClient for OpenAIClient {
+    async fn generate_content(
+        &self,
+        model: &str,
+        request: &ProviderRequest,
+    ) -> ProviderResult<ProviderResponse> {
+        let response = self.send_chat(model, request, false).await?;
+
         let response_text = response.text().await.map_err(OpenAIError::from)?;
         let response_json: serde_json::Value =
             serde_json::from_str(&response_text).map_err(OpenAIError::from)?;
 
-        parse_openai_response(response_json).map_err(Into::into)
+        let mut response = parse_openai_response(response_json)?;
+        extract_text_tool_calls(&mut response, request);
+        Ok(response)
     }
 
     async fn generate_content_stream(
@@ -107,45 +227,212 @@ impl LanguageModelClient for OpenAIClient {
         request: &ProviderRequest,
         chunk_tx: &UnboundedSender<ProviderStreamChunk>,
     ) -> ProviderResult<ProviderResponse> {
-        let body = provider_request_to_openai_stream_body(model, request);
-
-        let url = format!("{}/chat/completions", self.api_base_url);
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await
-            .map_err(OpenAIError::from)?;
-
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            log::error!("OpenAI API error ({}): {}", status, error_text);
-            let message = serde_json::from_str::<Value>(&error_text)
-                .ok()
-                .and_then(|v| {
-                    v.get("error")
-                        .and_then(|e| e.get("message"))
-                        .and_then(|m| m.as_str())
-                        .map(|s| s.to_string())
-                })
-                .unwrap_or(error_text);
-            return Err(OpenAIError::ApiError {
-                status_code: status.as_u16(),
-                message,
-            }
-            .into());
-        }
+        let response = self.send_chat(model, request, true).await?;
 
-        accumulate_sse::<OpenAIStreamAccumulator, OpenAIError>(
+        // A tool call written as JSON text has already been forwarded as text
+        // chunks by the time it's recovered; only the final response has it.
+        let mut response = accumulate_sse::<OpenAIStreamAccumulator, OpenAIError>(
             response.bytes_stream(),
             model,
             chunk_tx,
         )
-        .await
-        .map_err(Into::into)
+        .await?;
+        extract_text_tool_calls(&mut response, request);
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::models::{
+        ProviderContent, ProviderFunctionDeclaration, ProviderPart, ProviderTool,
+    };
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Match, Mock, MockServer, Request, ResponseTemplate};
+
+    struct WithoutHeader(&'static str);
+
+    impl Match for WithoutHeader {
+        fn matches(&self, request: &Request) -> bool {
+            !request.headers.contains_key(self.0)
+        }
+    }
+
+    struct WithoutTools;
+
+    impl Match for WithoutTools {
+        fn matches(&self, request: &Request) -> bool {
+            serde_json::from_slice::<Value>(&request.body)
+                .is_ok_and(|body| body.get("tools").is_none())
+        }
+    }
+
+    fn client(server: &MockServer, api_key: Option<&str>, mode: ToolCallMode) -> OpenAIClient {
+        OpenAIClient::with_config(
+            api_key.map(str::to_string),
+            &format!("{}/v1/", server.uri()),
+            mode,
+        )
+        .unwrap()
+    }
+
+    fn request(with_tools: bool) -> ProviderRequest {
+        ProviderRequest {
+            contents: vec![ProviderContent {
+                role: Some("user".to_string()),
+                parts: Some(vec![ProviderPart {
+                    text: Some("Summarize".to_string()),
+                    ..Default::default()
+                }]),
+            }],
+            system_instruction: None,
+            tools: with_tools.then(|| {
+                vec![ProviderTool {
+                    function_declarations: vec![ProviderFunctionDeclaration {
+                        name: "submit_report_summary".to_string(),
+                        description: "Submit the summary".to_string(),
+                        parameters: json!({"type": "object"}),
+                    }],
+                }]
+            }),
+            generation_config: None,
+            service_tier: None,
+            provider: None,
+            model_size: None,
+        }
+    }
+
+    fn completion(message: Value) -> Value {
+        json!({
+            "model": "llama3.1:8b",
+            "choices": [{"message": message, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 2, "total_tokens": 5}
+        })
+    }
+
+    fn function_call(response: &ProviderResponse) -> Option<(String, Option<Value>)> {
+        response.candidates.as_ref()?[0]
+            .content
+            .as_ref()?
+            .parts
+            .as_ref()?
+            .iter()
+            .find_map(|p| p.function_call.as_ref())
+            .map(|fc| (fc.name.clone(), fc.args.clone()))
+    }
+
+    #[tokio::test]
+    async fn omits_authorization_without_an_api_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(WithoutHeader("authorization"))
+            .and(body_partial_json(json!({"model": "llama3.1:8b"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(completion(json!({"role": "assistant", "content": "hi"}))),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = client(&server, None, ToolCallMode::Auto)
+            .generate_content("llama3.1:8b", &request(false))
+            .await
+            .unwrap();
+
+        assert_eq!(response.model_version.as_deref(), Some("llama3.1:8b"));
+    }
+
+    #[tokio::test]
+    async fn auto_mode_falls_back_to_json_tools_once_rejected() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(body_partial_json(json!({"tools": [{"type": "function"}]})))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "object": "error",
+                "message": "\"auto\" tool choice requires --enable-auto-tool-choice and --tool-call-parser to be set",
+                "type": "BadRequestError",
+                "code": 400
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(WithoutTools)
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion(json!({
+                "role": "assistant",
+                "content": "```json\n{\"name\": \"submit_report_summary\", \"arguments\": {\"summary\": \"ok\"}}\n```"
+            }))))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = client(&server, Some("local"), ToolCallMode::Auto);
+        for _ in 0..2 {
+            let response = client
+                .generate_content("llama3.1:8b", &request(true))
+                .await
+                .unwrap();
+            assert_eq!(
+                function_call(&response),
+                Some((
+                    "submit_report_summary".to_string(),
+                    Some(json!({"summary": "ok"}))
+                ))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn native_mode_surfaces_tool_rejections() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": {"message": "llama3 does not support tools"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let err = client(&server, None, ToolCallMode::Native)
+            .generate_content("llama3", &request(true))
+            .await
+            .unwrap_err();
+
+        assert!(!err.is_retryable());
+        assert!(err.to_string().contains("does not support tools"));
+    }
+
+    #[tokio::test]
+    async fn json_mode_never_sends_native_tools() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(WithoutTools)
+            .respond_with(ResponseTemplate::new(200).set_body_json(completion(
+                json!({"role": "assistant", "content": "{\"summary\": \"bare\"}"}),
+            )))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let response = client(&server, None, ToolCallMode::Json)
+            .generate_content("llama3", &request(true))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            function_call(&response),
+            Some((
+                "submit_report_summary".to_string(),
+                Some(json!({"summary": "bare"}))
+            ))
+        );
     }
 }
@@ -92,10 +92,176 @@ pub fn provider_request_to_openai_body(model: &str, request: &ProviderRequest) -
     body
 }
 
-/// Same as [`provider_request_to_openai_body`] but flags the request for SSE streaming and asks
-/// the upstream to emit a final usage-only chunk (`stream_options.include_usage`).
-pub fn provider_request_to_openai_stream_body(model: &str, request: &ProviderRequest) -> Value {
+/// Build a request for servers without native tool calling (vLLM without a
+/// tool parser, many Ollama/LM Studio models). Tools are described in the
+/// system prompt and the model is asked to answer with a JSON object naming
+/// the tool, which [`extract_text_tool_calls`] turns back into a function
+/// call. Tool calls and results already in the history are rewritten as
+/// plain text since such servers also reject `tool_calls` / `role: "tool"`.
+pub fn provider_request_to_json_tools_body(model: &str, request: &ProviderRequest) -> Value {
     let mut body = provider_request_to_openai_body(model, request);
+    let Some(obj) = body.as_object_mut() else {
+        return body;
+    };
+    obj.remove("tools");
+
+    let mut messages: Vec<Value> = obj
+        .remove("messages")
+        .and_then(|m| match m {
+            Value::Array(messages) => Some(messages),
+            _ => None,
+        })
+        .unwrap_or_default()
+        .into_iter()
+        .map(tool_message_as_text)
+        .collect();
+
+    let instructions = json_tools_instructions(request);
+    match messages.first_mut() {
+        Some(first) if first["role"] == "system" => {
+            let text = first["content"].as_str().unwrap_or_default();
+            first["content"] = Value::String(format!("{text}\n\n{instructions}"));
+        }
+        _ => messages.insert(0, json!({ "role": "system", "content": instructions })),
+    }
+
+    obj.insert("messages".to_string(), Value::Array(messages));
+    body
+}
+
+fn json_tools_instructions(request: &ProviderRequest) -> String {
+    let mut out = String::from(
+        "You cannot call tools natively. To call a tool, respond with ONLY a JSON object of the \
+         form {\"name\": \"<tool name>\", \"arguments\": {...}} and no other text. \
+         Available tools:",
+    );
+    for f in request
+        .tools
+        .iter()
+        .flatten()
+        .flat_map(|t| &t.function_declarations)
+    {
+        out.push_str(&format!(
+            "\n- {}: {}\n  arguments JSON schema: {}",
+            f.name, f.description, f.parameters
+        ));
+    }
+    out
+}
+
+fn tool_message_as_text(mut message: Value) -> Value {
+    if message["role"] == "tool" {
+        return json!({
+            "role": "user",
+            "content": format!(
+                "Tool result for call {}: {}",
+                message["tool_call_id"].as_str().unwrap_or_default(),
+                message["content"].as_str().unwrap_or_default()
+            ),
+        });
+    }
+    let Some(Value::Array(tool_calls)) =
+        message.as_object_mut().and_then(|m| m.remove("tool_calls"))
+    else {
+        return message;
+    };
+    let mut text = message["content"].as_str().unwrap_or_default().to_string();
+    for tc in tool_calls {
+        let arguments = tc["function"]["arguments"]
+            .as_str()
+            .and_then(|a| serde_json::from_str::<Value>(a).ok())
+            .unwrap_or(Value::Object(Default::default()));
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(
+            &json!({ "name": tc["function"]["name"], "arguments": arguments }).to_string(),
+        );
+    }
+    message["content"] = Value::String(text);
+    message
+}
+
+/// Recover tool calls a model wrote as text: either because the request went
+/// through [`provider_request_to_json_tools_body`], or because a server
+/// accepted `tools` but its model template ignored them. Candidates that
+/// already carry a function call are left alone. The text must be a JSON
+/// object (optionally fenced) shaped `{"name", "arguments"}` naming a
+/// declared tool or, when exactly one tool is declared, that tool's bare
+/// arguments.
+pub fn extract_text_tool_calls(response: &mut ProviderResponse, request: &ProviderRequest) {
+    let declared: Vec<&str> = request
+        .tools
+        .iter()
+        .flatten()
+        .flat_map(|t| &t.function_declarations)
+        .map(|f| f.name.as_str())
+        .collect();
+    if declared.is_empty() {
+        return;
+    }
+
+    for candidate in response.candidates.iter_mut().flatten() {
+        let Some(parts) = candidate.content.as_mut().and_then(|c| c.parts.as_mut()) else {
+            continue;
+        };
+        if parts.iter().any(|p| p.function_call.is_some()) {
+            continue;
+        }
+        let text: String = parts
+            .iter()
+            .filter(|p| p.thought != Some(true))
+            .filter_map(|p| p.text.as_deref())
+            .collect();
+        let Some(value) = parse_json_object(&text) else {
+            continue;
+        };
+
+        let call = match (
+            value.get("name").and_then(|n| n.as_str()),
+            value.get("arguments"),
+        ) {
+            (Some(name), Some(arguments)) if declared.contains(&name) => {
+                // Some models double-encode `arguments` as a string.
+                let args = arguments
+                    .as_str()
+                    .and_then(|a| serde_json::from_str::<Value>(a).ok())
+                    .unwrap_or_else(|| arguments.clone());
+                (name.to_string(), args)
+            }
+            _ if declared.len() == 1 => (declared[0].to_string(), value),
+            _ => continue,
+        };
+
+        parts.retain(|p| p.thought == Some(true));
+        parts.push(ProviderPart {
+            function_call: Some(ProviderFunctionCall {
+                id: Some(uuid::Uuid::new_v4().to_string()),
+                name: call.0,
+                args: Some(call.1),
+            }),
+            ..Default::default()
+        });
+    }
+}
+
+/// Parse the outermost JSON object in `text`, tolerating ```json fences and
+/// surrounding prose.
+fn parse_json_object(text: &str) -> Option<Value> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str::<Value>(&text[start..=end])
+        .ok()
+        .filter(|v| v.is_object())
+}
+
+/// Flag a request body built by [`provider_request_to_openai_body`] (or its JSON-tools variant)
+/// for SSE streaming and ask the upstream to emit a final usage-only chunk
+/// (`stream_options.include_usage`).
+pub fn into_stream_body(mut body: Value) -> Value {
     body["stream"] = json!(true);
     body["stream_options"] = json!({ "include_usage": true });
     body
@@ -623,6 +789,142 @@ mod tests {
         assert_eq!(fc.args.as_ref().unwrap()["city"], "SF");
     }
 
+    fn classify_tool() -> ProviderTool {
+        ProviderTool {
+            function_declarations: vec![ProviderFunctionDeclaration {
+                name: "classify_agent".to_string(),
+                description: "Classify the agent".to_string(),
+                parameters: json!({"type": "object", "properties": {"verdict": {"type": "string"}}}),
+            }],
+        }
+    }
+
+    fn request_with_tools(
+        contents: Vec<ProviderContent>,
+        tools: Vec<ProviderTool>,
+    ) -> ProviderRequest {
+        ProviderRequest {
+            contents,
+            system_instruction: Some(ProviderContent {
+                role: None,
+                parts: Some(vec![text_part("Be terse")]),
+            }),
+            tools: Some(tools),
+            generation_config: None,
+            service_tier: None,
+            provider: None,
+            model_size: None,
+        }
+    }
+
+    fn text_response(text: &str) -> ProviderResponse {
+        parse_openai_response(json!({
+            "choices": [{
+                "message": {"role": "assistant", "content": text},
+                "finish_reason": "stop"
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn first_call(response: &ProviderResponse) -> Option<ProviderFunctionCall> {
+        response.candidates.as_ref().unwrap()[0]
+            .content
+            .as_ref()
+            .unwrap()
+            .parts
+            .as_ref()
+            .unwrap()
+            .iter()
+            .find_map(|p| p.function_call.clone())
+    }
+
+    #[test]
+    fn json_tools_body_moves_tools_into_the_system_prompt() {
+        let req = request_with_tools(
+            vec![
+                user("classify"),
+                assistant_with_tool_call(
+                    Some("call_1"),
+                    "classify_agent",
+                    json!({"verdict": "new"}),
+                ),
+                tool_response(Some("call_1"), "classify_agent", json!({"ok": true})),
+            ],
+            vec![classify_tool()],
+        );
+        let body = provider_request_to_json_tools_body("llama3", &req);
+
+        assert!(body.get("tools").is_none());
+        let messages = body["messages"].as_array().unwrap();
+        let system = messages[0]["content"].as_str().unwrap();
+        assert!(system.starts_with("Be terse\n\n"));
+        assert!(system.contains("- classify_agent: Classify the agent"));
+        assert!(messages[2].get("tool_calls").is_none());
+        assert_eq!(
+            messages[2]["content"],
+            "{\"name\":\"classify_agent\",\"arguments\":{\"verdict\":\"new\"}}"
+        );
+        assert_eq!(messages[3]["role"], "user");
+        assert_eq!(
+            messages[3]["content"],
+            "Tool result for call call_1: {\"ok\":true}"
+        );
+    }
+
+    #[test]
+    fn fenced_json_text_becomes_a_function_call() {
+        let req = request_with_tools(vec![user("classify")], vec![classify_tool()]);
+        let mut resp = text_response(
+            "Sure:\n```json\n{\"name\": \"classify_agent\", \"arguments\": {\"verdict\": \"new\"}}\n```",
+        );
+        extract_text_tool_calls(&mut resp, &req);
+
+        let call = first_call(&resp).unwrap();
+        assert_eq!(call.name, "classify_agent");
+        assert_eq!(call.args, Some(json!({"verdict": "new"})));
+        let parts = resp.candidates.as_ref().unwrap()[0]
+            .content
+            .as_ref()
+            .unwrap()
+            .parts
+            .as_ref()
+            .unwrap();
+        assert_eq!(parts.len(), 1);
+    }
+
+    #[test]
+    fn bare_arguments_map_to_the_only_declared_tool() {
+        let req = request_with_tools(vec![user("classify")], vec![classify_tool()]);
+        let mut resp = text_response("{\"verdict\": \"existing\"}");
+        extract_text_tool_calls(&mut resp, &req);
+
+        let call = first_call(&resp).unwrap();
+        assert_eq!(call.name, "classify_agent");
+        assert_eq!(call.args, Some(json!({"verdict": "existing"})));
+    }
+
+    #[test]
+    fn unknown_tool_names_and_prose_are_left_as_text() {
+        let mut tools = vec![classify_tool()];
+        tools[0]
+            .function_declarations
+            .push(ProviderFunctionDeclaration {
+                name: "other".to_string(),
+                description: String::new(),
+                parameters: json!({}),
+            });
+        let req = request_with_tools(vec![user("classify")], tools);
+
+        let mut resp = text_response("{\"name\": \"nope\", \"arguments\": {}}");
+        extract_text_tool_calls(&mut resp, &req);
+        assert!(first_call(&resp).is_none());
+
+        let mut resp = text_response("I can't decide.");
+        extract_text_tool_calls(&mut resp, &req);
+        assert!(first_call(&resp).is_none());
+    }
+
     #[test]
     fn maps_finish_reason_variants() {
         assert_eq!(map_finish_reason("stop"), ProviderFinishReason::Stop);
@@ -0,0 +1,376 @@
+//! Provider routing policy for [`LlmClient`](super::LlmClient): ordered
+//! fallback providers per [`ModelSize`], retries with exponential backoff on
+//! retryable errors, and a per-provider circuit breaker.
+//!
+//! Configured from `LLM_FALLBACK_PROVIDERS[_<SIZE>]`, `LLM_MAX_RETRIES`,
+//! `LLM_RETRY_*` and `LLM_CIRCUIT_*` (see `env::llm`). With no fallbacks
+//! configured the policy only retries the primary provider.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use tokio::time::Instant;
+
+use super::{ModelSize, ProviderError};
+use crate::env;
+
+#[derive(Debug, Clone)]
+pub struct RoutingPolicy {
+    /// Providers tried, in order, after the primary for each size.
+    fallbacks: HashMap<ModelSize, Vec<String>>,
+    /// Retries per provider on retryable, non-`resource_exhausted` errors.
+    pub max_retries: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    /// Consecutive failures that open a provider's circuit.
+    pub failure_threshold: u32,
+    /// How long an open circuit skips its provider before a trial call.
+    pub cooldown: Duration,
+}
+
+impl RoutingPolicy {
+    pub fn from_env() -> Self {
+        let shared = std::env::var(env::llm::FALLBACK_PROVIDERS).ok();
+        let fallbacks = [
+            (ModelSize::Small, env::llm::FALLBACK_PROVIDERS_SMALL),
+            (ModelSize::Medium, env::llm::FALLBACK_PROVIDERS_MEDIUM),
+            (ModelSize::Large, env::llm::FALLBACK_PROVIDERS_LARGE),
+        ]
+        .into_iter()
+        .filter_map(|(size, key)| {
+            let raw = std::env::var(key).ok().or_else(|| shared.clone())?;
+            let providers = parse_provider_list(&raw);
+            (!providers.is_empty()).then_some((size, providers))
+        })
+        .collect();
+
+        Self {
+            fallbacks,
+            max_retries: env::llm::MAX_RETRIES.get(),
+            base_delay: Duration::from_millis(env::llm::RETRY_BASE_DELAY_MS.get()),
+            max_delay: Duration::from_millis(env::llm::RETRY_MAX_DELAY_MS.get()),
+            failure_threshold: env::llm::CIRCUIT_FAILURE_THRESHOLD.get().max(1),
+            cooldown: Duration::from_secs(env::llm::CIRCUIT_COOLDOWN_SECS.get()),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_fallbacks(mut self, size: ModelSize, providers: &[&str]) -> Self {
+        self.fallbacks
+            .insert(size, providers.iter().map(|p| p.to_string()).collect());
+        self
+    }
+
+    pub fn fallbacks(&self, size: ModelSize) -> &[String] {
+        self.fallbacks.get(&size).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Every configured fallback provider name, for startup validation.
+    pub fn fallback_providers(&self) -> impl Iterator<Item = &str> {
+        self.fallbacks.values().flatten().map(String::as_str)
+    }
+
+    /// Delay before retry number `retry` (0-based): exponential from
+    /// `base_delay`, capped at `max_delay`, then jittered into its upper half
+    /// so callers that failed together don't retry together.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        let max_ms = exp.as_millis() as u64;
+        if max_ms == 0 {
+            return Duration::ZERO;
+        }
+        Duration::from_millis(rand::RngExt::random_range(
+            &mut rand::rng(),
+            max_ms / 2..=max_ms,
+        ))
+    }
+}
+
+/// What the routing loop does after one provider call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttemptOutcome {
+    Success,
+    /// Retryable error with retries left: back off, call the same provider.
+    Retry,
+    /// Move on to the next provider in the chain.
+    Failover,
+    /// Final error, returned to the caller.
+    Error,
+    /// Provider skipped without a call because its circuit is open.
+    CircuitOpen,
+}
+
+impl AttemptOutcome {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttemptOutcome::Success => "success",
+            AttemptOutcome::Retry => "retry",
+            AttemptOutcome::Failover => "failover",
+            AttemptOutcome::Error => "error",
+            AttemptOutcome::CircuitOpen => "circuit_open",
+        }
+    }
+
+    /// Classify a failed call. `resource_exhausted` and `NotSupported` fail
+    /// over straight away, other retryable errors retry until `retries_left`
+    /// runs out or the circuit opens, anything else is final. Once output
+    /// has been `committed` to the caller every error is final.
+    pub fn after_error(
+        error: &ProviderError,
+        committed: bool,
+        retries_left: bool,
+        circuit_open: bool,
+    ) -> Self {
+        let failover =
+            error.is_resource_exhausted() || matches!(error, ProviderError::NotSupported(_));
+        if committed || !(failover || error.is_retryable()) {
+            AttemptOutcome::Error
+        } else if failover || !retries_left || circuit_open {
+            AttemptOutcome::Failover
+        } else {
+            AttemptOutcome::Retry
+        }
+    }
+}
+
+fn parse_provider_list(raw: &str) -> Vec<String> {
+    let mut providers: Vec<String> = Vec::new();
+    for name in raw.split(',').map(|p| p.trim().to_lowercase()) {
+        if !name.is_empty() && !providers.contains(&name) {
+            providers.push(name);
+        }
+    }
+    providers
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+    /// When the single trial call after a cooldown went out. A trial that
+    /// never reports back (its future was dropped, e.g. on a client timeout)
+    /// is given up on after another `cooldown`, so the slot is not lost.
+    trial_started: Option<Instant>,
+}
+
+/// Per-provider circuit breakers. A provider's circuit opens after
+/// `failure_threshold` consecutive failures and stays open for `cooldown`;
+/// after that one trial call is let through, which closes the circuit on
+/// success and re-opens it on failure. A trial without an outcome expires
+/// after `cooldown`. In-process only, like the clients.
+#[derive(Debug)]
+pub struct CircuitBreakers {
+    states: DashMap<String, CircuitState>,
+    failure_threshold: u32,
+    cooldown: Duration,
+}
+
+impl CircuitBreakers {
+    pub fn new(policy: &RoutingPolicy) -> Self {
+        Self {
+            states: DashMap::new(),
+            failure_threshold: policy.failure_threshold,
+            cooldown: policy.cooldown,
+        }
+    }
+
+    /// Whether a call to `provider` may go out now. Claims the half-open
+    /// trial slot when the cooldown has elapsed.
+    pub fn try_acquire(&self, provider: &str) -> bool {
+        let mut state = self.states.entry(provider.to_string()).or_default();
+        let Some(open_until) = state.open_until else {
+            return true;
+        };
+        let now = Instant::now();
+        if now < open_until
+            || state
+                .trial_started
+                .is_some_and(|started| now < started + self.cooldown)
+        {
+            return false;
+        }
+        state.trial_started = Some(now);
+        true
+    }
+
+    pub fn is_open(&self, provider: &str) -> bool {
+        self.states
+            .get(provider)
+            .is_some_and(|state| state.open_until.is_some())
+    }
+
+    /// Record a call outcome. `healthy` is false only for failures that say
+    /// something about the provider's availability (5xx, timeouts); a 400 or
+    /// a rate limit is healthy.
+    pub fn record(&self, provider: &str, healthy: bool) {
+        let mut state = self.states.entry(provider.to_string()).or_default();
+        if healthy {
+            *state = CircuitState::default();
+            return;
+        }
+
+        state.consecutive_failures += 1;
+        let was_trial = state.trial_started.take().is_some();
+        if was_trial || state.consecutive_failures >= self.failure_threshold {
+            if state.open_until.is_none() || was_trial {
+                log::warn!(
+                    "Opening circuit for LLM provider '{}' for {:?} after {} consecutive failures",
+                    provider,
+                    self.cooldown,
+                    state.consecutive_failures
+                );
+            }
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(failure_threshold: u32, cooldown_secs: u64) -> RoutingPolicy {
+        RoutingPolicy {
+            fallbacks: HashMap::new(),
+            max_retries: 0,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1_000),
+            failure_threshold,
+            cooldown: Duration::from_secs(cooldown_secs),
+        }
+    }
+
+    #[test]
+    fn provider_list_is_trimmed_lowercased_and_deduplicated() {
+        assert_eq!(
+            parse_provider_list(" Bedrock, openai,,bedrock "),
+            vec!["bedrock".to_string(), "openai".to_string()]
+        );
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_and_is_capped() {
+        let policy = policy(5, 30);
+        for _ in 0..20 {
+            let first = policy.backoff(0);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let third = policy.backoff(2);
+            assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+            assert!(policy.backoff(30) <= Duration::from_millis(1_000));
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn circuit_opens_after_threshold_and_recovers_through_a_trial() {
+        let breakers = CircuitBreakers::new(&policy(2, 30));
+
+        breakers.record("gemini", false);
+        assert!(breakers.try_acquire("gemini"));
+        breakers.record("gemini", false);
+        assert!(breakers.is_open("gemini"));
+        assert!(!breakers.try_acquire("gemini"));
+
+        tokio::time::advance(Duration::from_secs(31)).await;
+        // Exactly one trial call goes out once the cooldown elapses.
+        assert!(breakers.try_acquire("gemini"));
+        assert!(!breakers.try_acquire("gemini"));
+
+        breakers.record("gemini", true);
+        assert!(!breakers.is_open("gemini"));
+        assert!(breakers.try_acquire("gemini"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn failed_trial_reopens_the_circuit() {
+        let breakers = CircuitBreakers::new(&policy(1, 10));
+
+        breakers.record("bedrock", false);
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(breakers.try_acquire("bedrock"));
+        breakers.record("bedrock", false);
+
+        assert!(!breakers.try_acquire("bedrock"));
+        tokio::time::advance(Duration::from_secs(11)).await;
+        assert!(breakers.try_acquire("bedrock"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn abandoned_trial_expires_after_cooldown() {
+        let breakers = CircuitBreakers::new(&policy(1, 10));
+
+        breakers.record("openai", false);
+        tokio::time::advance(Duration::from_secs(11)).await;
+        // The trial's future is dropped before it records an outcome
+        assert!(breakers.try_acquire("openai"));
+        assert!(!breakers.try_acquire("openai"));
+
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(!breakers.try_acquire("openai"));
+        tokio::time::advance(Duration::from_secs(6)).await;
+        assert!(breakers.try_acquire("openai"));
+
+        breakers.record("openai", true);
+        assert!(!breakers.is_open("openai"));
+    }
+
+    fn api_error(status_code: u16) -> ProviderError {
+        ProviderError::ApiError {
+            status_code,
+            message: String::new(),
+            retryable: status_code == 429 || status_code >= 500,
+            resource_exhausted: status_code == 429,
+        }
+    }
+
+    #[test]
+    fn errors_are_classified_into_retry_failover_or_final() {
+        use AttemptOutcome::*;
+
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(500), false, true, false),
+            Retry
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(500), false, false, false),
+            Failover
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(500), false, true, true),
+            Failover
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(429), false, true, false),
+            Failover
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(
+                &ProviderError::NotSupported(String::new()),
+                false,
+                true,
+                false
+            ),
+            Failover
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(400), false, true, false),
+            Error
+        );
+        assert_eq!(
+            AttemptOutcome::after_error(&api_error(500), true, true, false),
+            Error
+        );
+    }
+
+    #[test]
+    fn healthy_outcome_resets_the_failure_count() {
+        let breakers = CircuitBreakers::new(&policy(2, 30));
+        breakers.record("openai", false);
+        breakers.record("openai", true);
+        breakers.record("openai", false);
+        assert!(!breakers.is_open("openai"));
+    }
+}
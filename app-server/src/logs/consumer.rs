@@ -12,11 +12,12 @@ use crate::{
     db::DB,
     features::{Feature, is_feature_enabled},
     mq::MessageQueue,
+    pubsub::PubSub,
     utils::limits::update_workspace_bytes_ingested,
     worker::{HandlerError, MessageHandler},
 };
 
-use super::producer::RabbitMqLogMessage;
+use super::{producer::RabbitMqLogMessage, realtime::send_log_updates};
 
 /// Handler for log processing
 pub struct LogsHandler {
@@ -24,6 +25,7 @@ pub struct LogsHandler {
     pub cache: Arc<Cache>,
     pub clickhouse: clickhouse::Client,
     pub queue: Arc<MessageQueue>,
+    pub pubsub: Arc<PubSub>,
 }
 
 #[async_trait]
@@ -37,18 +39,20 @@ impl MessageHandler for LogsHandler {
             self.cache.clone(),
             self.clickhouse.clone(),
             self.queue.clone(),
+            self.pubsub.clone(),
         )
         .await
     }
 }
 
-#[instrument(skip(messages, db, cache, clickhouse, queue))]
+#[instrument(skip(messages, db, cache, clickhouse, queue, pubsub))]
 async fn process_logs_batch(
     messages: Vec<RabbitMqLogMessage>,
     db: Arc<DB>,
     cache: Arc<Cache>,
     clickhouse: clickhouse::Client,
     queue: Arc<MessageQueue>,
+    pubsub: Arc<PubSub>,
 ) -> Result<(), HandlerError> {
     if messages.is_empty() {
         return Ok(());
@@ -89,6 +93,9 @@ async fn process_logs_batch(
         project_id
     );
 
+    let logs: Vec<_> = messages.into_iter().map(|message| message.log).collect();
+    send_log_updates(&logs, &pubsub).await;
+
     // Update workspace limits cache
     if is_feature_enabled(Feature::UsageLimit) {
         if let Err(e) =
@@ -9,6 +9,7 @@ use crate::{
     opentelemetry_proto::opentelemetry::proto::collector::logs::v1::{
         ExportLogsServiceRequest, ExportLogsServiceResponse, logs_service_server::LogsService,
     },
+    project_api_keys::restrictions::ApiKeyScope,
     utils::limits::get_workspace_bytes_limit_exceeded,
 };
 use tonic::{Request, Response, Status};
@@ -44,9 +45,13 @@ impl LogsService for ProcessLogsService {
         &self,
         request: Request<ExportLogsServiceRequest>,
     ) -> Result<Response<ExportLogsServiceResponse>, Status> {
-        let api_key = authenticate_request(request.metadata(), &self.db.pool, self.cache.clone())
-            .await
-            .map_err(|_| Status::unauthenticated("Failed to authenticate request"))?;
+        let api_key = authenticate_request(
+            &request,
+            ApiKeyScope::IngestLogs,
+            &self.db.pool,
+            self.cache.clone(),
+        )
+        .await?;
         let project_id = api_key.project_id;
         let request = request.into_inner();
 
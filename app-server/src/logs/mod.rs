@@ -1,6 +1,8 @@
 pub mod consumer;
 pub mod grpc_service;
+pub mod opentelemetry_json;
 pub mod producer;
+pub mod realtime;
 
 pub const LOGS_QUEUE: &str = "logs_queue";
 pub const LOGS_EXCHANGE: &str = "logs_exchange";
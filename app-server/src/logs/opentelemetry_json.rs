@@ -0,0 +1,279 @@
+//! Serde shadow types for the OTLP/HTTP+JSON encoding of `ExportLogsServiceRequest`.
+//!
+//! Same approach as `traces::opentelemetry_json`: deserialise into shadow structs,
+//! then convert into the prost types `logs::producer` already speaks. Resource,
+//! scope, attribute and id handling is shared with the traces decoder.
+//!
+//! Shape per spec: <https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding>.
+
+use serde::Deserialize;
+
+use crate::opentelemetry_proto::opentelemetry::proto::collector::logs::v1 as logs;
+use crate::traces::opentelemetry_json::{
+    AnyValueJson, InstrumentationScopeJson, JsonDecodeError, KeyValueJson, ResourceJson,
+    decode_id_field, deserialize_u64_or_string,
+};
+
+pub fn decode_export_logs_service_request(
+    body: &[u8],
+) -> Result<logs::ExportLogsServiceRequest, JsonDecodeError> {
+    let raw: ExportLogsServiceRequestJson = serde_json::from_slice(body)?;
+    raw.try_into()
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ExportLogsServiceRequestJson {
+    resource_logs: Vec<ResourceLogsJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ResourceLogsJson {
+    resource: Option<ResourceJson>,
+    scope_logs: Vec<ScopeLogsJson>,
+    schema_url: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ScopeLogsJson {
+    scope: Option<InstrumentationScopeJson>,
+    log_records: Vec<LogRecordJson>,
+    schema_url: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct LogRecordJson {
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    observed_time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_severity_number")]
+    severity_number: i32,
+    severity_text: String,
+    body: Option<AnyValueJson>,
+    attributes: Vec<KeyValueJson>,
+    dropped_attributes_count: u32,
+    flags: u32,
+    trace_id: String,
+    span_id: String,
+    event_name: String,
+}
+
+// --- conversions ---------------------------------------------------------
+
+impl TryFrom<ExportLogsServiceRequestJson> for logs::ExportLogsServiceRequest {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ExportLogsServiceRequestJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_logs: v
+                .resource_logs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<ResourceLogsJson> for logs::ResourceLogs {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ResourceLogsJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource: v.resource.map(Into::into),
+            scope_logs: v
+                .scope_logs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            schema_url: v.schema_url,
+        })
+    }
+}
+
+impl TryFrom<ScopeLogsJson> for logs::ScopeLogs {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ScopeLogsJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            scope: v.scope.map(Into::into),
+            log_records: v
+                .log_records
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            schema_url: v.schema_url,
+        })
+    }
+}
+
+impl TryFrom<LogRecordJson> for logs::LogRecord {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: LogRecordJson) -> Result<Self, Self::Error> {
+        // Both ids are optional: a log emitted outside a span carries neither.
+        Ok(Self {
+            time_unix_nano: v.time_unix_nano,
+            observed_time_unix_nano: v.observed_time_unix_nano,
+            severity_number: v.severity_number,
+            severity_text: v.severity_text,
+            body: v.body.map(Into::into),
+            attributes: v.attributes.into_iter().map(Into::into).collect(),
+            dropped_attributes_count: v.dropped_attributes_count,
+            flags: v.flags,
+            trace_id: decode_id_field("trace_id", &v.trace_id, 16, true)?,
+            span_id: decode_id_field("span_id", &v.span_id, 8, true)?,
+            event_name: v.event_name,
+        })
+    }
+}
+
+// --- helpers -------------------------------------------------------------
+
+/// SeverityNumber is an enum: per spec serialised as the integer ordinal, but
+/// several SDKs emit the canonical string name.
+fn deserialize_severity_number<'de, D: serde::Deserializer<'de>>(d: D) -> Result<i32, D::Error> {
+    use serde::de::Error;
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Str(String),
+        Num(i32),
+    }
+    match Repr::deserialize(d)? {
+        Repr::Num(n) => Ok(n),
+        Repr::Str(s) => logs::SeverityNumber::from_str_name(&s)
+            .map(|n| n as i32)
+            .ok_or_else(|| D::Error::custom(format!("unknown SeverityNumber: {s}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opentelemetry_proto::opentelemetry_proto_common_v1 as common;
+    use prost::Message;
+
+    fn json_payload() -> &'static str {
+        // OTLP/HTTP+JSON payload from a browser logger: stringified nanos, an
+        // enum-name severity, hex ids tying the record to a span, a structured
+        // body, and a record emitted outside any span.
+        r#"{
+          "resourceLogs": [{
+            "resource": {
+              "attributes": [
+                {"key": "service.name", "value": {"stringValue": "web-app"}}
+              ]
+            },
+            "scopeLogs": [{
+              "scope": {"name": "console", "version": "1.0.0"},
+              "logRecords": [
+                {
+                  "timeUnixNano": "1544712660300000000",
+                  "observedTimeUnixNano": "1544712660300000001",
+                  "severityNumber": "SEVERITY_NUMBER_ERROR",
+                  "severityText": "ERROR",
+                  "body": {"kvlistValue": {"values": [
+                    {"key": "message", "value": {"stringValue": "checkout failed"}},
+                    {"key": "retries", "value": {"intValue": "3"}}
+                  ]}},
+                  "attributes": [
+                    {"key": "http.status_code", "value": {"intValue": 502}}
+                  ],
+                  "traceId": "5b8aa5a2d2c872e8321cf37308d69df2",
+                  "spanId": "051581bf3cb55c13",
+                  "flags": 1
+                },
+                {
+                  "timeUnixNano": 1544712661000000000,
+                  "severityNumber": 9,
+                  "body": {"stringValue": "page loaded"},
+                  "eventName": "page.load"
+                }
+              ]
+            }]
+          }]
+        }"#
+    }
+
+    #[test]
+    fn decodes_otlp_json_logs() {
+        let req = decode_export_logs_service_request(json_payload().as_bytes()).unwrap();
+        let scope_logs = &req.resource_logs[0].scope_logs[0];
+        assert_eq!(scope_logs.scope.as_ref().unwrap().name, "console");
+        assert_eq!(scope_logs.log_records.len(), 2);
+
+        let error = &scope_logs.log_records[0];
+        assert_eq!(error.time_unix_nano, 1544712660300000000);
+        assert_eq!(error.observed_time_unix_nano, 1544712660300000001);
+        assert_eq!(error.severity_number, logs::SeverityNumber::Error as i32);
+        assert_eq!(error.severity_text, "ERROR");
+        assert_eq!(error.trace_id.len(), 16);
+        assert_eq!(error.span_id.len(), 8);
+        assert_eq!(error.flags, 1);
+        match error.body.as_ref().unwrap().value.as_ref().unwrap() {
+            common::any_value::Value::KvlistValue(kv) => assert_eq!(kv.values.len(), 2),
+            _ => panic!("expected kvlist body"),
+        }
+
+        let info = &scope_logs.log_records[1];
+        assert_eq!(info.time_unix_nano, 1544712661000000000);
+        assert_eq!(info.severity_number, logs::SeverityNumber::Info as i32);
+        assert!(info.trace_id.is_empty());
+        assert!(info.span_id.is_empty());
+        assert_eq!(info.event_name, "page.load");
+    }
+
+    #[test]
+    fn json_and_proto_yield_same_request() {
+        let from_json = decode_export_logs_service_request(json_payload().as_bytes()).unwrap();
+
+        let mut buf = Vec::with_capacity(from_json.encoded_len());
+        from_json.encode(&mut buf).unwrap();
+        let from_proto = logs::ExportLogsServiceRequest::decode(buf.as_slice()).unwrap();
+
+        assert_eq!(from_json, from_proto);
+    }
+
+    #[test]
+    fn rejects_wrong_length_span_id() {
+        let payload = r#"{
+          "resourceLogs": [{
+            "scopeLogs": [{
+              "logRecords": [{
+                "traceId": "5b8aa5a2d2c872e8321cf37308d69df2",
+                "spanId": "010203040506"
+              }]
+            }]
+          }]
+        }"#;
+        let err = decode_export_logs_service_request(payload.as_bytes()).unwrap_err();
+        let msg = format!("{err}");
+        assert!(msg.contains("span_id"), "got: {msg}");
+        assert!(msg.contains("expected 8 bytes"), "got: {msg}");
+    }
+
+    #[test]
+    fn rejects_unknown_severity_name() {
+        let payload = r#"{
+          "resourceLogs": [{
+            "scopeLogs": [{
+              "logRecords": [{"severityNumber": "SEVERITY_NUMBER_LOUD"}]
+            }]
+          }]
+        }"#;
+        assert!(matches!(
+            decode_export_logs_service_request(payload.as_bytes()),
+            Err(JsonDecodeError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn empty_request_is_ok() {
+        let req = decode_export_logs_service_request(b"{}").unwrap();
+        assert!(req.resource_logs.is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+//! Realtime log updates on the trace channel, so logs appear next to their
+//! span while a trace is still streaming in.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    pubsub::PubSub,
+    realtime::{SseMessage, send_to_key},
+};
+
+use super::producer::Log;
+
+/// Realtime log data for frontend consumption
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RealtimeLog {
+    log_id: Uuid,
+    trace_id: Uuid,
+    span_id: Option<Uuid>,
+    time: DateTime<Utc>,
+    severity_number: i32,
+    severity_text: String,
+    body: Value,
+    attributes: Value,
+    event_name: String,
+}
+
+/// Send `log_update` events to the `trace_{trace_id}` channel of every trace
+/// the logs were emitted in. Logs without a trace id are not sent.
+pub async fn send_log_updates(logs: &[Log], pubsub: &PubSub) {
+    let mut logs_by_trace: HashMap<(Uuid, Uuid), Vec<RealtimeLog>> = HashMap::new();
+
+    for log in logs {
+        let Some(trace_id) = log.trace_id else {
+            continue;
+        };
+        logs_by_trace
+            .entry((log.project_id, trace_id))
+            .or_default()
+            .push(RealtimeLog {
+                log_id: log.log_id,
+                trace_id,
+                span_id: log.span_id,
+                time: log.time,
+                severity_number: log.severity_number,
+                severity_text: log.severity_text.clone(),
+                body: log.body.clone(),
+                attributes: log.attributes.clone(),
+                event_name: log.event_name.clone(),
+            });
+    }
+
+    for ((project_id, trace_id), logs_data) in logs_by_trace {
+        let message = SseMessage {
+            event_type: "log_update".to_string(),
+            data: serde_json::json!({
+                "logs": logs_data
+            }),
+        };
+
+        let trace_key = format!("trace_{}", trace_id);
+        send_to_key(pubsub, &project_id, &trace_key, message).await;
+    }
+}
@@ -27,6 +27,14 @@ use clustering::private::{
     EVENT_CLUSTERING_ROUTING_KEY, batching::ClusteringEventBatchingHandler, build_runner_from_env,
     handler::ClusteringHandler,
 };
+use cost_backfill::{
+    COST_BACKFILL_EXCHANGE, COST_BACKFILL_QUEUE, COST_BACKFILL_ROUTING_KEY,
+    consumer::CostBackfillHandler,
+};
+use evaluations::{
+    EVALUATION_JUDGES_EXCHANGE, EVALUATION_JUDGES_QUEUE, EVALUATION_JUDGES_ROUTING_KEY,
+    judges::EvaluationJudgesHandler,
+};
 use features::{Feature, is_feature_enabled};
 use lapin::{
     Connection, ConnectionProperties, ExchangeKind,
@@ -37,16 +45,23 @@ use logs::{
     LOGS_EXCHANGE, LOGS_QUEUE, LOGS_ROUTING_KEY, consumer::LogsHandler,
     grpc_service::ProcessLogsService,
 };
+use metrics::{
+    METRICS_EXCHANGE, METRICS_QUEUE, METRICS_ROUTING_KEY, consumer::MetricsHandler,
+    grpc_service::ProcessMetricsService,
+};
 use mq::MessageQueue;
 use names::NameGenerator;
 use notifications::{
     NOTIFICATIONS_EXCHANGE, NOTIFICATIONS_QUEUE, NOTIFICATIONS_ROUTING_KEY, NotificationHandler,
     delivery::{
         NOTIFICATION_DELIVERIES_EXCHANGE, NOTIFICATION_DELIVERIES_QUEUE,
-        NOTIFICATION_DELIVERIES_ROUTING_KEY, NotificationDeliveryHandler,
+        NOTIFICATION_DELIVERIES_RETRY_EXCHANGE, NOTIFICATION_DELIVERIES_RETRY_QUEUE,
+        NOTIFICATION_DELIVERIES_ROUTING_KEY, NOTIFICATION_DELIVERIES_RETRY_ROUTING_KEY,
+        NotificationDeliveryHandler,
     },
 };
 use opentelemetry_proto::opentelemetry::proto::collector::logs::v1::logs_service_server::LogsServiceServer;
+use opentelemetry_proto::opentelemetry::proto::collector::metrics::v1::metrics_service_server::MetricsServiceServer;
 use opentelemetry_proto::opentelemetry::proto::collector::trace::v1::trace_service_server::TraceServiceServer;
 use query_engine::QueryEngine;
 use reports::{REPORT_TRIGGERS_EXCHANGE, REPORT_TRIGGERS_QUEUE, REPORT_TRIGGERS_ROUTING_KEY};
@@ -100,7 +115,7 @@ use crate::features::{enable_consumer, enable_producer};
 use crate::worker::{QueueConfig, WorkerPool, WorkerType};
 use crate::{
     ch::{cloud::CloudClickhouse, data_plane::DataPlaneClickhouse, service::ClickhouseService},
-    reports::generator::ReportsGenerator,
+    reports::{custom::CustomQueryRunner, generator::ReportsGenerator},
 };
 
 #[cfg(feature = "signals")]
@@ -113,6 +128,7 @@ mod cache;
 mod ch;
 mod checkpoints;
 mod clustering;
+mod cost_backfill;
 mod data_plane;
 mod datasets;
 mod db;
@@ -124,6 +140,7 @@ mod instrumentation;
 mod language_model;
 mod llm;
 mod logs;
+mod metrics;
 mod mq;
 mod names;
 mod notifications;
@@ -507,6 +524,51 @@ fn main() -> anyhow::Result<()> {
                 .await
                 .unwrap();
 
+            // ==== 3.6c Notification Deliveries retry queue ====
+            // No consumer: retries wait out their TTL and are dead-lettered
+            // back to the deliveries exchange.
+            channel
+                .exchange_declare(
+                    NOTIFICATION_DELIVERIES_RETRY_EXCHANGE.into(),
+                    ExchangeKind::Fanout,
+                    ExchangeDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    FieldTable::default(),
+                )
+                .await
+                .unwrap();
+
+            let mut delivery_retry_queue_args = quorum_queue_args.clone();
+            delivery_retry_queue_args.insert(
+                "x-dead-letter-exchange".into(),
+                lapin::types::AMQPValue::LongString(NOTIFICATION_DELIVERIES_EXCHANGE.into()),
+            );
+
+            channel
+                .queue_declare(
+                    NOTIFICATION_DELIVERIES_RETRY_QUEUE.into(),
+                    QueueDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    delivery_retry_queue_args,
+                )
+                .await
+                .unwrap();
+
+            channel
+                .queue_bind(
+                    NOTIFICATION_DELIVERIES_RETRY_QUEUE.into(),
+                    NOTIFICATION_DELIVERIES_RETRY_EXCHANGE.into(),
+                    NOTIFICATION_DELIVERIES_RETRY_ROUTING_KEY.into(),
+                    lapin::options::QueueBindOptions::default(),
+                    FieldTable::default(),
+                )
+                .await
+                .unwrap();
+
             // ==== 3.7 Event Clustering message queue ====
             #[cfg(feature = "signals")]
             {
@@ -764,6 +826,84 @@ fn main() -> anyhow::Result<()> {
                 .await
                 .unwrap();
 
+            // ==== 3.14 Metrics message queue ====
+            channel
+                .exchange_declare(
+                    METRICS_EXCHANGE.into(),
+                    ExchangeKind::Fanout,
+                    ExchangeDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    FieldTable::default(),
+                )
+                .await
+                .unwrap();
+
+            channel
+                .queue_declare(
+                    METRICS_QUEUE.into(),
+                    QueueDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    quorum_queue_args.clone(),
+                )
+                .await
+                .unwrap();
+
+            // ==== 3.15 Evaluation judges message queue ====
+            channel
+                .exchange_declare(
+                    EVALUATION_JUDGES_EXCHANGE.into(),
+                    ExchangeKind::Fanout,
+                    ExchangeDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    FieldTable::default(),
+                )
+                .await
+                .unwrap();
+
+            channel
+                .queue_declare(
+                    EVALUATION_JUDGES_QUEUE.into(),
+                    QueueDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    quorum_queue_args.clone(),
+                )
+                .await
+                .unwrap();
+
+            // ==== 3.16 Cost backfill message queue ====
+            channel
+                .exchange_declare(
+                    COST_BACKFILL_EXCHANGE.into(),
+                    ExchangeKind::Fanout,
+                    ExchangeDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    FieldTable::default(),
+                )
+                .await
+                .unwrap();
+
+            channel
+                .queue_declare(
+                    COST_BACKFILL_QUEUE.into(),
+                    QueueDeclareOptions {
+                        durable: true,
+                        ..Default::default()
+                    },
+                    quorum_queue_args.clone(),
+                )
+                .await
+                .unwrap();
+
             let max_channel_pool_size = env::mq::MAX_CHANNEL_POOL_SIZE.get();
 
             log::info!("RabbitMQ channels: {}", max_channel_pool_size);
@@ -832,6 +972,12 @@ fn main() -> anyhow::Result<()> {
         queue.register_queue(REPORT_TRIGGERS_EXCHANGE, REPORT_TRIGGERS_QUEUE);
         // ==== 3.13 Checkpoints message queue ====
         queue.register_queue(CHECKPOINTS_EXCHANGE, CHECKPOINTS_QUEUE);
+        // ==== 3.14 Metrics message queue ====
+        queue.register_queue(METRICS_EXCHANGE, METRICS_QUEUE);
+        // ==== 3.15 Evaluation judges message queue ====
+        queue.register_queue(EVALUATION_JUDGES_EXCHANGE, EVALUATION_JUDGES_QUEUE);
+        // ==== 3.16 Cost backfill message queue ====
+        queue.register_queue(COST_BACKFILL_EXCHANGE, COST_BACKFILL_QUEUE);
         log::info!("Using tokio mpsc queue");
         Arc::new(queue.into())
     };
@@ -1015,7 +1161,7 @@ fn main() -> anyhow::Result<()> {
     // == LLM Client ==
     let llm_provider_client: Option<Arc<llm::LlmClient>> = if is_feature_enabled(Feature::Signals) {
         log::info!("Initializing LLM client");
-        match runtime_handle.block_on(llm::LlmClient::new()) {
+        match runtime_handle.block_on(llm::LlmClient::new(cache.clone())) {
             Ok(client) => Some(Arc::new(client)),
             Err(e) => {
                 log::warn!(
@@ -1051,6 +1197,23 @@ fn main() -> anyhow::Result<()> {
             log::info!("Reports feature disabled - skipping reports scheduler");
         }
 
+        {
+            log::info!("Starting metric alerts scheduler");
+            let db_for_scheduler = db.clone();
+            let clickhouse_for_scheduler = clickhouse.clone();
+            let queue_for_scheduler = queue.clone();
+            let cache_for_scheduler = cache.clone();
+            runtime_handle.spawn(async move {
+                notifications::metric_alerts::run_metric_alerts_scheduler(
+                    db_for_scheduler.pool.clone(),
+                    clickhouse_for_scheduler,
+                    queue_for_scheduler,
+                    cache_for_scheduler,
+                )
+                .await;
+            });
+        }
+
         let worker_pool = Arc::new(WorkerPool::new(queue.clone()));
         let batch_worker_pool = Arc::new(BatchWorkerPool::new(queue.clone()));
 
@@ -1079,12 +1242,18 @@ fn main() -> anyhow::Result<()> {
 
         let num_logs_workers = env::workers::NUM_LOGS.get();
 
+        let num_metrics_workers = env::workers::NUM_METRICS.get();
+
         let num_reports_workers = env::workers::NUM_REPORTS.get();
 
         let num_checkpoints_workers = env::workers::NUM_CHECKPOINTS.get();
 
+        let num_evaluation_judges_workers = env::workers::NUM_EVALUATION_JUDGES.get();
+
+        let num_cost_backfill_workers = env::workers::NUM_COST_BACKFILL.get();
+
         log::info!(
-            "Spans workers: {}, Data plane spans workers: {}, Spans indexer workers: {}, Browser events workers: {}, Signals workers: {}, Notification workers: {}, Notification delivery workers: {}, Clustering batching workers: {}, Clustering workers: {}, Trace Analysis LLM Batch Submissions workers: {}, Trace Analysis LLM Batch Pending workers: {}, Logs workers: {}, Reports workers: {}",
+            "Spans workers: {}, Data plane spans workers: {}, Spans indexer workers: {}, Browser events workers: {}, Signals workers: {}, Notification workers: {}, Notification delivery workers: {}, Clustering batching workers: {}, Clustering workers: {}, Trace Analysis LLM Batch Submissions workers: {}, Trace Analysis LLM Batch Pending workers: {}, Logs workers: {}, Metrics workers: {}, Reports workers: {}",
             num_spans_workers,
             num_data_plane_spans_workers,
             num_spans_indexer_workers,
@@ -1097,6 +1266,7 @@ fn main() -> anyhow::Result<()> {
             num_signal_job_submission_batch_workers,
             num_signal_job_pending_batch_workers,
             num_logs_workers,
+            num_metrics_workers,
             num_reports_workers
         );
 
@@ -1110,6 +1280,8 @@ fn main() -> anyhow::Result<()> {
         let quickwit_client_for_consumer = quickwit_client.clone();
         let pubsub_for_consumer = pubsub.clone();
         let pii_redactor_for_consumer = pii_redactor.clone();
+        let clickhouse_ro_for_consumer = clickhouse_readonly_client.clone();
+        let query_engine_for_consumer = query_engine.clone();
         let worker_pool_clone = worker_pool.clone();
         let batch_worker_pool_clone = batch_worker_pool.clone();
 
@@ -1315,6 +1487,7 @@ fn main() -> anyhow::Result<()> {
                     // Spawn notification delivery workers (stage 2: format + send + log)
                     {
                         let db = db_for_consumer.clone();
+                        let queue = mq_for_consumer.clone();
                         let client = reqwest::Client::new();
                         let resend = resend_client.clone();
                         let ch_service = Arc::new(ClickhouseService::new(
@@ -1333,6 +1506,7 @@ fn main() -> anyhow::Result<()> {
                                     client.clone(),
                                     resend.clone(),
                                     ch_service.clone(),
+                                    queue.clone(),
                                 )
                             },
                             QueueConfig::new(
@@ -1530,6 +1704,7 @@ fn main() -> anyhow::Result<()> {
                         let cache = cache_for_consumer.clone();
                         let clickhouse = clickhouse_for_consumer.clone();
                         let queue = mq_for_consumer.clone();
+                        let pubsub = pubsub_for_consumer.clone();
                         worker_pool_clone.spawn(
                             WorkerType::Logs,
                             num_logs_workers as usize,
@@ -1538,17 +1713,44 @@ fn main() -> anyhow::Result<()> {
                                 cache: cache.clone(),
                                 clickhouse: clickhouse.clone(),
                                 queue: queue.clone(),
+                                pubsub: pubsub.clone(),
                             },
                             QueueConfig::new(LOGS_QUEUE, LOGS_EXCHANGE, LOGS_ROUTING_KEY),
                         );
                     }
 
+                    // Spawn metrics workers
+                    {
+                        let db = db_for_consumer.clone();
+                        let cache = cache_for_consumer.clone();
+                        let clickhouse = clickhouse_for_consumer.clone();
+                        let queue = mq_for_consumer.clone();
+                        worker_pool_clone.spawn(
+                            WorkerType::Metrics,
+                            num_metrics_workers as usize,
+                            move || MetricsHandler {
+                                db: db.clone(),
+                                cache: cache.clone(),
+                                clickhouse: clickhouse.clone(),
+                                queue: queue.clone(),
+                            },
+                            QueueConfig::new(METRICS_QUEUE, METRICS_EXCHANGE, METRICS_ROUTING_KEY),
+                        );
+                    }
+
                     // Spawn reports workers
                     if is_feature_enabled(Feature::Reports) {
                         let db = db_for_consumer.clone();
                         let clickhouse = clickhouse_for_consumer.clone();
                         let queue = mq_for_consumer.clone();
                         let llm_client = llm_provider_client.clone();
+                        let custom_queries = CustomQueryRunner {
+                            clickhouse_ro: clickhouse_ro_for_consumer.clone(),
+                            query_engine: query_engine_for_consumer.clone(),
+                            http_client: Arc::new(http_client_for_consumer.clone()),
+                            db: db_for_consumer.clone(),
+                            cache: cache_for_consumer.clone(),
+                        };
                         worker_pool_clone.spawn(
                             WorkerType::Reports,
                             num_reports_workers as usize,
@@ -1557,6 +1759,7 @@ fn main() -> anyhow::Result<()> {
                                 clickhouse: clickhouse.clone(),
                                 queue: queue.clone(),
                                 llm_client: llm_client.clone(),
+                                custom_queries: custom_queries.clone(),
                             },
                             QueueConfig::new(
                                 REPORT_TRIGGERS_QUEUE,
@@ -1591,6 +1794,52 @@ fn main() -> anyhow::Result<()> {
                         );
                     }
 
+                    // Spawn evaluation judges workers
+                    {
+                        let db = db_for_consumer.clone();
+                        let clickhouse = clickhouse_for_consumer.clone();
+                        let pubsub = pubsub_for_consumer.clone();
+                        let llm_client = llm_provider_client.clone();
+                        worker_pool_clone.spawn(
+                            WorkerType::EvaluationJudges,
+                            num_evaluation_judges_workers as usize,
+                            move || EvaluationJudgesHandler {
+                                db: db.clone(),
+                                clickhouse: clickhouse.clone(),
+                                pubsub: pubsub.clone(),
+                                llm_client: llm_client.clone(),
+                            },
+                            QueueConfig::new(
+                                EVALUATION_JUDGES_QUEUE,
+                                EVALUATION_JUDGES_EXCHANGE,
+                                EVALUATION_JUDGES_ROUTING_KEY,
+                            ),
+                        );
+                    }
+
+                    // Spawn cost backfill workers
+                    {
+                        let db = db_for_consumer.clone();
+                        let cache = cache_for_consumer.clone();
+                        let clickhouse = clickhouse_for_consumer.clone();
+                        let queue = mq_for_consumer.clone();
+                        worker_pool_clone.spawn(
+                            WorkerType::CostBackfill,
+                            num_cost_backfill_workers as usize,
+                            move || CostBackfillHandler {
+                                db: db.clone(),
+                                cache: cache.clone(),
+                                clickhouse: clickhouse.clone(),
+                                queue: queue.clone(),
+                            },
+                            QueueConfig::new(
+                                COST_BACKFILL_QUEUE,
+                                COST_BACKFILL_EXCHANGE,
+                                COST_BACKFILL_ROUTING_KEY,
+                            ),
+                        );
+                    }
+
                     HttpServer::new(move || {
                         App::new()
                             .wrap(NormalizePath::trim())
@@ -1753,6 +2002,7 @@ fn main() -> anyhow::Result<()> {
                             .app_data(web::Data::new(query_engine.clone()))
                             .app_data(web::Data::new(sse_connections_for_http.clone()))
                             .app_data(web::Data::new(quickwit_client.clone()))
+                            .app_data(web::Data::new(pii_redactor.clone()))
                             .app_data(web::Data::new(pubsub.clone()))
                             .app_data(web::Data::new(http_client_for_http.clone()))
                             .app_data(web::Data::new(llm_provider_client_for_http.clone()))
@@ -1850,13 +2100,25 @@ fn main() -> anyhow::Result<()> {
                                 web::scope("/v1")
                                     .wrap(project_auth.clone())
                                     .service(api::v1::projects::get_current_project)
+                                    .service(api::v1::reports::create_report)
+                                    .service(api::v1::reports::get_reports)
+                                    .service(api::v1::reports::delete_report)
                                     .service(api::v1::datasets::get_datasets)
                                     .service(api::v1::datasets::get_datapoints)
                                     .service(api::v1::datasets::create_datapoints)
                                     .service(api::v1::datasets::get_parquet)
+                                    .service(api::v1::datasets::export_datapoints)
+                                    .service(api::v1::datasets::create_dataset_version)
+                                    .service(api::v1::datasets::get_dataset_versions)
+                                    .service(api::v1::datasets::diff_dataset_versions)
+                                    .service(api::v1::datasets::materialize_datapoints)
                                     .service(api::v1::evals::init_eval)
                                     .service(api::v1::evals::save_eval_datapoints)
                                     .service(api::v1::evals::update_eval_datapoint)
+                                    .service(api::v1::evals::compare_evals)
+                                    .service(api::v1::evaluators::create_evaluator)
+                                    .service(api::v1::evaluators::get_evaluators)
+                                    .service(api::v1::evaluators::delete_evaluator)
                                     // Debugger session lifecycle — SDK-driven
                                     // (project API key). update_name is CLI-only,
                                     // so it lives under /v1/cli, not here.
@@ -1873,8 +2135,13 @@ fn main() -> anyhow::Result<()> {
                                     .service(routes::sql::sql_to_json)
                                     .service(routes::sql::json_to_sql)
                                     .service(routes::spans::search_spans)
+                                    .service(routes::traces::get_trace_graph)
                                     .service(routes::signal_events::search_signal_events)
-                                    .service(routes::rollouts::update_session_name);
+                                    .service(routes::rollouts::update_session_name)
+                                    .service(routes::pii::reveal_pseudonyms)
+                                    .service(routes::pii::detect_pii)
+                                    .service(routes::cost_backfill::start_cost_backfill)
+                                    .service(routes::cost_backfill::resume_cost_backfill);
                                 #[cfg(feature = "signals")]
                                 let scope = scope
                                     .service(crate::signals::private::routes::submit_signal_job)
@@ -1922,6 +2189,13 @@ fn main() -> anyhow::Result<()> {
                         queue.clone(),
                     );
 
+                    let process_metrics_service = ProcessMetricsService::new(
+                        db.clone(),
+                        cache.clone(),
+                        clickhouse.clone(),
+                        queue.clone(),
+                    );
+
                     Server::builder()
                         .add_service(
                             TraceServiceServer::new(process_traces_service)
@@ -1935,6 +2209,12 @@ fn main() -> anyhow::Result<()> {
                                 .send_compressed(tonic::codec::CompressionEncoding::Gzip)
                                 .max_decoding_message_size(grpc_payload_limit),
                         )
+                        .add_service(
+                            MetricsServiceServer::new(process_metrics_service)
+                                .accept_compressed(tonic::codec::CompressionEncoding::Gzip)
+                                .send_compressed(tonic::codec::CompressionEncoding::Gzip)
+                                .max_decoding_message_size(grpc_payload_limit),
+                        )
                         .serve_with_shutdown(grpc_address, async {
                             wait_stop_signal("gRPC service").await;
                         })
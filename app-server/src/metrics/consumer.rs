@@ -0,0 +1,110 @@
+//! This module reads metric data points from RabbitMQ and processes them: writes to ClickHouse.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tracing::instrument;
+use uuid::Uuid;
+
+use crate::{
+    cache::Cache,
+    ch::{self, metrics::CHMetric},
+    db::DB,
+    features::{Feature, is_feature_enabled},
+    mq::MessageQueue,
+    utils::limits::update_workspace_bytes_ingested,
+    worker::{HandlerError, MessageHandler},
+};
+
+use super::producer::RabbitMqMetricMessage;
+
+/// Handler for metric processing
+pub struct MetricsHandler {
+    pub db: Arc<DB>,
+    pub cache: Arc<Cache>,
+    pub clickhouse: clickhouse::Client,
+    pub queue: Arc<MessageQueue>,
+}
+
+#[async_trait]
+impl MessageHandler for MetricsHandler {
+    type Message = Vec<RabbitMqMetricMessage>;
+
+    async fn handle(&self, messages: Self::Message) -> Result<(), HandlerError> {
+        process_metrics_batch(
+            messages,
+            self.db.clone(),
+            self.cache.clone(),
+            self.clickhouse.clone(),
+            self.queue.clone(),
+        )
+        .await
+    }
+}
+
+#[instrument(skip(messages, db, cache, clickhouse, queue))]
+async fn process_metrics_batch(
+    messages: Vec<RabbitMqMetricMessage>,
+    db: Arc<DB>,
+    cache: Arc<Cache>,
+    clickhouse: clickhouse::Client,
+    queue: Arc<MessageQueue>,
+) -> Result<(), HandlerError> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+
+    // Get project_id from the first data point (all points in the batch share the project_id)
+    let project_id = messages
+        .first()
+        .map(|m| m.point.project_id)
+        .unwrap_or(Uuid::nil());
+
+    let total_ingested_bytes: usize = messages.iter().map(|m| m.point.estimate_size_bytes()).sum();
+
+    let ch_metrics: Vec<CHMetric> = messages
+        .iter()
+        .map(|message| CHMetric::from_metric_point(&message.point))
+        .collect();
+
+    if let Err(e) = ch::metrics::insert_metrics_batch(clickhouse.clone(), &ch_metrics).await {
+        log::error!(
+            "Failed to record {} metric data points to ClickHouse: {:?}",
+            ch_metrics.len(),
+            e
+        );
+        // Requeue the message on transient errors
+        return Err(HandlerError::transient(anyhow::anyhow!(
+            "Failed to insert metrics to ClickHouse: {:?}",
+            e
+        )));
+    }
+
+    log::debug!(
+        "Successfully processed {} metric data points for project {}",
+        ch_metrics.len(),
+        project_id
+    );
+
+    // Update workspace limits cache
+    if is_feature_enabled(Feature::UsageLimit) {
+        if let Err(e) = update_workspace_bytes_ingested(
+            db,
+            clickhouse,
+            cache,
+            queue,
+            project_id,
+            total_ingested_bytes,
+        )
+        .await
+        {
+            log::error!(
+                "Failed to update workspace limit exceeded for project [{}]: {:?}",
+                project_id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
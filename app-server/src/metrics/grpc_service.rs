@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use crate::{
+    auth::authenticate_request,
+    cache::Cache,
+    db::DB,
+    features::{Feature, is_feature_enabled},
+    mq::MessageQueue,
+    opentelemetry_proto::opentelemetry::proto::collector::metrics::v1::{
+        ExportMetricsServiceRequest, ExportMetricsServiceResponse,
+        metrics_service_server::MetricsService,
+    },
+    project_api_keys::restrictions::ApiKeyScope,
+    utils::limits::get_workspace_bytes_limit_exceeded,
+};
+use tonic::{Request, Response, Status};
+
+use super::producer::push_metrics_to_queue;
+
+pub struct ProcessMetricsService {
+    db: Arc<DB>,
+    cache: Arc<Cache>,
+    clickhouse: clickhouse::Client,
+    queue: Arc<MessageQueue>,
+}
+
+impl ProcessMetricsService {
+    pub fn new(
+        db: Arc<DB>,
+        cache: Arc<Cache>,
+        clickhouse: clickhouse::Client,
+        queue: Arc<MessageQueue>,
+    ) -> Self {
+        Self {
+            db,
+            cache,
+            clickhouse,
+            queue,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl MetricsService for ProcessMetricsService {
+    async fn export(
+        &self,
+        request: Request<ExportMetricsServiceRequest>,
+    ) -> Result<Response<ExportMetricsServiceResponse>, Status> {
+        let api_key = authenticate_request(
+            &request,
+            ApiKeyScope::IngestTraces,
+            &self.db.pool,
+            self.cache.clone(),
+        )
+        .await?;
+        let project_id = api_key.project_id;
+        let request = request.into_inner();
+
+        if is_feature_enabled(Feature::UsageLimit) {
+            let bytes_limit_exceeded = get_workspace_bytes_limit_exceeded(
+                self.db.clone(),
+                self.clickhouse.clone(),
+                self.cache.clone(),
+                project_id,
+            )
+            .await
+            .map_err(|e| {
+                // Don't throw an error here. If there is a problem with us
+                // getting the limits, we don't want to block the user from
+                // sending metrics.
+                log::error!("Failed to get workspace limits: {:?}", e);
+            });
+
+            if bytes_limit_exceeded.is_ok_and(|exceeded| exceeded) {
+                return Err(Status::resource_exhausted("Workspace data limit exceeded"));
+            }
+        }
+
+        let response = push_metrics_to_queue(request, project_id, self.queue.clone())
+            .await
+            .map_err(|e| {
+                log::error!("Failed to process metrics: {:?}", e);
+                Status::internal("Failed to process metrics")
+            })?;
+
+        Ok(Response::new(response))
+    }
+}
@@ -0,0 +1,8 @@
+pub mod consumer;
+pub mod grpc_service;
+pub mod opentelemetry_json;
+pub mod producer;
+
+pub const METRICS_QUEUE: &str = "metrics_queue";
+pub const METRICS_EXCHANGE: &str = "metrics_exchange";
+pub const METRICS_ROUTING_KEY: &str = "metrics_routing_key";
@@ -0,0 +1,633 @@
+//! Serde shadow types for the OTLP/HTTP+JSON encoding of `ExportMetricsServiceRequest`.
+//!
+//! Same approach as `traces::opentelemetry_json`: deserialise into shadow structs,
+//! then convert into the prost types `metrics::producer` already speaks. Resource,
+//! scope, attribute and id handling is shared with the traces decoder.
+//!
+//! Shape per spec: <https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding>.
+
+use serde::Deserialize;
+
+use crate::opentelemetry_proto::opentelemetry::proto::collector::metrics::v1 as metrics;
+use crate::traces::opentelemetry_json::{
+    InstrumentationScopeJson, JsonDecodeError, KeyValueJson, ResourceJson, decode_id_field,
+    deserialize_i64_or_string_field, deserialize_u64_or_string,
+};
+
+pub fn decode_export_metrics_service_request(
+    body: &[u8],
+) -> Result<metrics::ExportMetricsServiceRequest, JsonDecodeError> {
+    let raw: ExportMetricsServiceRequestJson = serde_json::from_slice(body)?;
+    raw.try_into()
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ExportMetricsServiceRequestJson {
+    resource_metrics: Vec<ResourceMetricsJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ResourceMetricsJson {
+    resource: Option<ResourceJson>,
+    scope_metrics: Vec<ScopeMetricsJson>,
+    schema_url: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ScopeMetricsJson {
+    scope: Option<InstrumentationScopeJson>,
+    metrics: Vec<MetricJson>,
+    schema_url: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct MetricJson {
+    name: String,
+    description: String,
+    unit: String,
+    metadata: Vec<KeyValueJson>,
+    gauge: Option<GaugeJson>,
+    sum: Option<SumJson>,
+    histogram: Option<HistogramJson>,
+    exponential_histogram: Option<ExponentialHistogramJson>,
+    summary: Option<SummaryJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct GaugeJson {
+    data_points: Vec<NumberDataPointJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SumJson {
+    data_points: Vec<NumberDataPointJson>,
+    #[serde(deserialize_with = "deserialize_aggregation_temporality")]
+    aggregation_temporality: i32,
+    is_monotonic: bool,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct HistogramJson {
+    data_points: Vec<HistogramDataPointJson>,
+    #[serde(deserialize_with = "deserialize_aggregation_temporality")]
+    aggregation_temporality: i32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ExponentialHistogramJson {
+    data_points: Vec<ExponentialHistogramDataPointJson>,
+    #[serde(deserialize_with = "deserialize_aggregation_temporality")]
+    aggregation_temporality: i32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SummaryJson {
+    data_points: Vec<SummaryDataPointJson>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct NumberDataPointJson {
+    attributes: Vec<KeyValueJson>,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    start_time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    as_double: Option<f64>,
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    as_int: Option<i64>,
+    exemplars: Vec<ExemplarJson>,
+    flags: u32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct HistogramDataPointJson {
+    attributes: Vec<KeyValueJson>,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    start_time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    count: u64,
+    sum: Option<f64>,
+    #[serde(deserialize_with = "deserialize_u64_vec")]
+    bucket_counts: Vec<u64>,
+    explicit_bounds: Vec<f64>,
+    exemplars: Vec<ExemplarJson>,
+    flags: u32,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ExponentialHistogramDataPointJson {
+    attributes: Vec<KeyValueJson>,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    start_time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    count: u64,
+    sum: Option<f64>,
+    scale: i32,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    zero_count: u64,
+    positive: Option<BucketsJson>,
+    negative: Option<BucketsJson>,
+    flags: u32,
+    exemplars: Vec<ExemplarJson>,
+    min: Option<f64>,
+    max: Option<f64>,
+    zero_threshold: f64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct BucketsJson {
+    offset: i32,
+    #[serde(deserialize_with = "deserialize_u64_vec")]
+    bucket_counts: Vec<u64>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct SummaryDataPointJson {
+    attributes: Vec<KeyValueJson>,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    start_time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    count: u64,
+    sum: f64,
+    quantile_values: Vec<ValueAtQuantileJson>,
+    flags: u32,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ValueAtQuantileJson {
+    quantile: f64,
+    value: f64,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+struct ExemplarJson {
+    filtered_attributes: Vec<KeyValueJson>,
+    #[serde(deserialize_with = "deserialize_u64_or_string")]
+    time_unix_nano: u64,
+    as_double: Option<f64>,
+    #[serde(deserialize_with = "deserialize_optional_i64")]
+    as_int: Option<i64>,
+    span_id: String,
+    trace_id: String,
+}
+
+// --- conversions ---------------------------------------------------------
+
+impl TryFrom<ExportMetricsServiceRequestJson> for metrics::ExportMetricsServiceRequest {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ExportMetricsServiceRequestJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource_metrics: v
+                .resource_metrics
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl TryFrom<ResourceMetricsJson> for metrics::ResourceMetrics {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ResourceMetricsJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            resource: v.resource.map(Into::into),
+            scope_metrics: v
+                .scope_metrics
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            schema_url: v.schema_url,
+        })
+    }
+}
+
+impl TryFrom<ScopeMetricsJson> for metrics::ScopeMetrics {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ScopeMetricsJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            scope: v.scope.map(Into::into),
+            metrics: v
+                .metrics
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+            schema_url: v.schema_url,
+        })
+    }
+}
+
+impl TryFrom<MetricJson> for metrics::Metric {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: MetricJson) -> Result<Self, Self::Error> {
+        use metrics::metric::Data;
+        // `data` is a oneof; the JSON encoding sets exactly one of these keys.
+        let data = if let Some(gauge) = v.gauge {
+            Some(Data::Gauge(metrics::Gauge {
+                data_points: convert_all(gauge.data_points)?,
+            }))
+        } else if let Some(sum) = v.sum {
+            Some(Data::Sum(metrics::Sum {
+                data_points: convert_all(sum.data_points)?,
+                aggregation_temporality: sum.aggregation_temporality,
+                is_monotonic: sum.is_monotonic,
+            }))
+        } else if let Some(histogram) = v.histogram {
+            Some(Data::Histogram(metrics::Histogram {
+                data_points: convert_all(histogram.data_points)?,
+                aggregation_temporality: histogram.aggregation_temporality,
+            }))
+        } else if let Some(histogram) = v.exponential_histogram {
+            Some(Data::ExponentialHistogram(metrics::ExponentialHistogram {
+                data_points: convert_all(histogram.data_points)?,
+                aggregation_temporality: histogram.aggregation_temporality,
+            }))
+        } else {
+            v.summary.map(|summary| {
+                Data::Summary(metrics::Summary {
+                    data_points: summary.data_points.into_iter().map(Into::into).collect(),
+                })
+            })
+        };
+
+        Ok(Self {
+            name: v.name,
+            description: v.description,
+            unit: v.unit,
+            metadata: v.metadata.into_iter().map(Into::into).collect(),
+            data,
+        })
+    }
+}
+
+impl TryFrom<NumberDataPointJson> for metrics::NumberDataPoint {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: NumberDataPointJson) -> Result<Self, Self::Error> {
+        use metrics::number_data_point::Value;
+        Ok(Self {
+            attributes: v.attributes.into_iter().map(Into::into).collect(),
+            start_time_unix_nano: v.start_time_unix_nano,
+            time_unix_nano: v.time_unix_nano,
+            exemplars: convert_all(v.exemplars)?,
+            flags: v.flags,
+            value: v
+                .as_double
+                .map(Value::AsDouble)
+                .or(v.as_int.map(Value::AsInt)),
+        })
+    }
+}
+
+impl TryFrom<HistogramDataPointJson> for metrics::HistogramDataPoint {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: HistogramDataPointJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            attributes: v.attributes.into_iter().map(Into::into).collect(),
+            start_time_unix_nano: v.start_time_unix_nano,
+            time_unix_nano: v.time_unix_nano,
+            count: v.count,
+            sum: v.sum,
+            bucket_counts: v.bucket_counts,
+            explicit_bounds: v.explicit_bounds,
+            exemplars: convert_all(v.exemplars)?,
+            flags: v.flags,
+            min: v.min,
+            max: v.max,
+        })
+    }
+}
+
+impl TryFrom<ExponentialHistogramDataPointJson> for metrics::ExponentialHistogramDataPoint {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ExponentialHistogramDataPointJson) -> Result<Self, Self::Error> {
+        Ok(Self {
+            attributes: v.attributes.into_iter().map(Into::into).collect(),
+            start_time_unix_nano: v.start_time_unix_nano,
+            time_unix_nano: v.time_unix_nano,
+            count: v.count,
+            sum: v.sum,
+            scale: v.scale,
+            zero_count: v.zero_count,
+            positive: v.positive.map(Into::into),
+            negative: v.negative.map(Into::into),
+            flags: v.flags,
+            exemplars: convert_all(v.exemplars)?,
+            min: v.min,
+            max: v.max,
+            zero_threshold: v.zero_threshold,
+        })
+    }
+}
+
+impl From<BucketsJson> for metrics::exponential_histogram_data_point::Buckets {
+    fn from(v: BucketsJson) -> Self {
+        Self {
+            offset: v.offset,
+            bucket_counts: v.bucket_counts,
+        }
+    }
+}
+
+impl From<SummaryDataPointJson> for metrics::SummaryDataPoint {
+    fn from(v: SummaryDataPointJson) -> Self {
+        Self {
+            attributes: v.attributes.into_iter().map(Into::into).collect(),
+            start_time_unix_nano: v.start_time_unix_nano,
+            time_unix_nano: v.time_unix_nano,
+            count: v.count,
+            sum: v.sum,
+            quantile_values: v
+                .quantile_values
+                .into_iter()
+                .map(|q| metrics::summary_data_point::ValueAtQuantile {
+                    quantile: q.quantile,
+                    value: q.value,
+                })
+                .collect(),
+            flags: v.flags,
+        }
+    }
+}
+
+impl TryFrom<ExemplarJson> for metrics::Exemplar {
+    type Error = JsonDecodeError;
+
+    fn try_from(v: ExemplarJson) -> Result<Self, Self::Error> {
+        use metrics::exemplar::Value;
+        // Exemplar ids are optional: an exemplar recorded outside a sampled span
+        // carries neither.
+        Ok(Self {
+            filtered_attributes: v.filtered_attributes.into_iter().map(Into::into).collect(),
+            time_unix_nano: v.time_unix_nano,
+            span_id: decode_id_field("exemplar.span_id", &v.span_id, 8, true)?,
+            trace_id: decode_id_field("exemplar.trace_id", &v.trace_id, 16, true)?,
+            value: v
+                .as_double
+                .map(Value::AsDouble)
+                .or(v.as_int.map(Value::AsInt)),
+        })
+    }
+}
+
+// --- helpers -------------------------------------------------------------
+
+fn convert_all<J, P>(items: Vec<J>) -> Result<Vec<P>, JsonDecodeError>
+where
+    J: TryInto<P, Error = JsonDecodeError>,
+{
+    items.into_iter().map(TryInto::try_into).collect()
+}
+
+/// `sfixed64` values (`asInt`) are spec-encoded as strings; accept numbers too.
+fn deserialize_optional_i64<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> Result<Option<i64>, D::Error> {
+    deserialize_i64_or_string_field(d).map(Some)
+}
+
+/// `repeated fixed64` bucket counts are JSON-encoded as decimal strings; some
+/// senders emit raw numbers. Accept both, element-wise.
+fn deserialize_u64_vec<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Vec<u64>, D::Error> {
+    #[derive(Deserialize)]
+    struct Item(#[serde(deserialize_with = "deserialize_u64_or_string")] u64);
+    Ok(Vec::<Item>::deserialize(d)?
+        .into_iter()
+        .map(|Item(n)| n)
+        .collect())
+}
+
+/// AggregationTemporality is an enum: per spec serialised as the integer
+/// ordinal, but several SDKs emit the canonical string name.
+fn deserialize_aggregation_temporality<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> Result<i32, D::Error> {
+    use serde::de::Error;
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr {
+        Str(String),
+        Num(i32),
+    }
+    match Repr::deserialize(d)? {
+        Repr::Num(n) => Ok(n),
+        Repr::Str(s) => metrics::AggregationTemporality::from_str_name(&s)
+            .map(|t| t as i32)
+            .ok_or_else(|| D::Error::custom(format!("unknown AggregationTemporality: {s}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    fn json_payload() -> &'static str {
+        // OTLP/HTTP+JSON payload covering every metric kind: stringified nanos
+        // and counts, enum names for temporality, an int gauge, and a histogram
+        // exemplar pointing back at a trace.
+        r#"{
+          "resourceMetrics": [{
+            "resource": {
+              "attributes": [
+                {"key": "service.name", "value": {"stringValue": "agent"}}
+              ]
+            },
+            "scopeMetrics": [{
+              "scope": {"name": "lmnr", "version": "1.0"},
+              "metrics": [
+                {
+                  "name": "queue.depth",
+                  "unit": "{item}",
+                  "gauge": {"dataPoints": [{
+                    "timeUnixNano": "1544712660000000000",
+                    "asInt": "12"
+                  }]}
+                },
+                {
+                  "name": "gen_ai.client.token.usage",
+                  "unit": "{token}",
+                  "sum": {
+                    "aggregationTemporality": "AGGREGATION_TEMPORALITY_CUMULATIVE",
+                    "isMonotonic": true,
+                    "dataPoints": [{
+                      "attributes": [{"key": "gen_ai.token.type", "value": {"stringValue": "input"}}],
+                      "startTimeUnixNano": "1544712600000000000",
+                      "timeUnixNano": "1544712660000000000",
+                      "asDouble": 420.0
+                    }]
+                  }
+                },
+                {
+                  "name": "tool.latency",
+                  "unit": "s",
+                  "histogram": {
+                    "aggregationTemporality": 1,
+                    "dataPoints": [{
+                      "timeUnixNano": "1544712660000000000",
+                      "count": "3",
+                      "sum": 1.5,
+                      "bucketCounts": ["1", 2, "0"],
+                      "explicitBounds": [0.25, 1.0],
+                      "min": 0.1,
+                      "max": 1.0,
+                      "exemplars": [{
+                        "timeUnixNano": "1544712659000000000",
+                        "asDouble": 1.0,
+                        "traceId": "5b8aa5a2d2c872e8321cf37308d69df2",
+                        "spanId": "051581bf3cb55c13"
+                      }]
+                    }]
+                  }
+                },
+                {
+                  "name": "payload.size",
+                  "exponentialHistogram": {
+                    "aggregationTemporality": 2,
+                    "dataPoints": [{
+                      "timeUnixNano": "1544712660000000000",
+                      "count": "3",
+                      "scale": 3,
+                      "zeroCount": "1",
+                      "positive": {"offset": -2, "bucketCounts": ["1", "1"]}
+                    }]
+                  }
+                },
+                {
+                  "name": "request.duration",
+                  "summary": {"dataPoints": [{
+                    "timeUnixNano": "1544712660000000000",
+                    "count": "10",
+                    "sum": 5.0,
+                    "quantileValues": [
+                      {"quantile": 0.5, "value": 0.4},
+                      {"quantile": 0.99, "value": 1.2}
+                    ]
+                  }]}
+                }
+              ]
+            }]
+          }]
+        }"#
+    }
+
+    #[test]
+    fn decodes_otlp_json_metrics() {
+        use metrics::metric::Data;
+
+        let req = decode_export_metrics_service_request(json_payload().as_bytes()).unwrap();
+        assert_eq!(req.resource_metrics.len(), 1);
+
+        let scope_metrics = &req.resource_metrics[0].scope_metrics[0];
+        assert_eq!(scope_metrics.scope.as_ref().unwrap().name, "lmnr");
+        assert_eq!(scope_metrics.metrics.len(), 5);
+
+        match scope_metrics.metrics[0].data.as_ref().unwrap() {
+            Data::Gauge(g) => assert_eq!(
+                g.data_points[0].value,
+                Some(metrics::number_data_point::Value::AsInt(12))
+            ),
+            _ => panic!("expected gauge"),
+        }
+        match scope_metrics.metrics[1].data.as_ref().unwrap() {
+            Data::Sum(s) => {
+                assert_eq!(
+                    s.aggregation_temporality,
+                    metrics::AggregationTemporality::Cumulative as i32
+                );
+                assert!(s.is_monotonic);
+                assert_eq!(s.data_points[0].start_time_unix_nano, 1544712600000000000);
+            }
+            _ => panic!("expected sum"),
+        }
+        match scope_metrics.metrics[2].data.as_ref().unwrap() {
+            Data::Histogram(h) => {
+                let dp = &h.data_points[0];
+                assert_eq!(dp.count, 3);
+                assert_eq!(dp.bucket_counts, vec![1, 2, 0]);
+                assert_eq!(dp.exemplars[0].trace_id.len(), 16);
+                assert_eq!(dp.exemplars[0].span_id.len(), 8);
+            }
+            _ => panic!("expected histogram"),
+        }
+        match scope_metrics.metrics[3].data.as_ref().unwrap() {
+            Data::ExponentialHistogram(h) => {
+                let dp = &h.data_points[0];
+                assert_eq!(dp.scale, 3);
+                assert_eq!(dp.zero_count, 1);
+                assert_eq!(dp.positive.as_ref().unwrap().offset, -2);
+            }
+            _ => panic!("expected exponential histogram"),
+        }
+        match scope_metrics.metrics[4].data.as_ref().unwrap() {
+            Data::Summary(s) => assert_eq!(s.data_points[0].quantile_values.len(), 2),
+            _ => panic!("expected summary"),
+        }
+    }
+
+    #[test]
+    fn json_and_proto_yield_same_request() {
+        let from_json = decode_export_metrics_service_request(json_payload().as_bytes()).unwrap();
+
+        let mut buf = Vec::with_capacity(from_json.encoded_len());
+        from_json.encode(&mut buf).unwrap();
+        let from_proto = metrics::ExportMetricsServiceRequest::decode(buf.as_slice()).unwrap();
+
+        assert_eq!(from_json, from_proto);
+    }
+
+    #[test]
+    fn rejects_wrong_length_exemplar_trace_id() {
+        let payload = r#"{
+          "resourceMetrics": [{
+            "scopeMetrics": [{
+              "metrics": [{
+                "name": "x",
+                "gauge": {"dataPoints": [{
+                  "asDouble": 1.0,
+                  "exemplars": [{"traceId": "0102030405060708", "asDouble": 1.0}]
+                }]}
+              }]
+            }]
+          }]
+        }"#;
+        let err = decode_export_metrics_service_request(payload.as_bytes()).unwrap_err();
+        let msg = format!("{err}");
+        assert!(msg.contains("exemplar.trace_id"), "got: {msg}");
+    }
+
+    #[test]
+    fn empty_request_is_ok() {
+        let req = decode_export_metrics_service_request(b"{}").unwrap();
+        assert!(req.resource_metrics.is_empty());
+    }
+}
@@ -0,0 +1,598 @@
+//! This module takes metric exports and pushes them to RabbitMQ for further processing.
+//!
+//! OTLP metrics are nested (resource → scope → metric → data points) and each
+//! metric kind carries a different data point shape. We flatten every data
+//! point into one `MetricPoint` row so the consumer can write them to a single
+//! ClickHouse table; fields that don't apply to a kind keep their defaults.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use super::{METRICS_EXCHANGE, METRICS_ROUTING_KEY};
+use crate::{
+    db::utils::span_id_to_uuid,
+    mq::{MessageQueue, MessageQueueTrait, utils::mq_max_payload},
+    opentelemetry_proto::{
+        opentelemetry::proto::collector::metrics::v1::{
+            Exemplar, ExportMetricsPartialSuccess, ExportMetricsServiceRequest,
+            ExportMetricsServiceResponse, Metric, metric::Data, number_data_point,
+        },
+        opentelemetry_proto_common_v1::{InstrumentationScope, KeyValue},
+    },
+    traces::utils::convert_any_value_to_json_value,
+    utils::estimate_json_size,
+};
+
+/// OTLP metric kind. Stored as `UInt8` in ClickHouse.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MetricType {
+    Gauge,
+    Sum,
+    Histogram,
+    ExponentialHistogram,
+    Summary,
+}
+
+impl MetricType {
+    pub fn as_u8(&self) -> u8 {
+        match self {
+            MetricType::Gauge => 0,
+            MetricType::Sum => 1,
+            MetricType::Histogram => 2,
+            MetricType::ExponentialHistogram => 3,
+            MetricType::Summary => 4,
+        }
+    }
+}
+
+/// Internal representation of a single metric data point for processing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricPoint {
+    pub id: Uuid,
+    pub project_id: Uuid,
+    pub name: String,
+    pub description: String,
+    pub unit: String,
+    pub metric_type: MetricType,
+    pub time: DateTime<Utc>,
+    pub start_time: DateTime<Utc>,
+    pub aggregation_temporality: i32,
+    pub is_monotonic: bool,
+    /// Gauge / Sum value
+    pub value: f64,
+    /// Histogram / ExponentialHistogram / Summary population count
+    pub count: u64,
+    /// Histogram / ExponentialHistogram / Summary population sum
+    pub sum: f64,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub bucket_counts: Vec<u64>,
+    pub explicit_bounds: Vec<f64>,
+    pub scale: i32,
+    pub zero_count: u64,
+    pub positive_offset: i32,
+    pub positive_bucket_counts: Vec<u64>,
+    pub negative_offset: i32,
+    pub negative_bucket_counts: Vec<u64>,
+    pub quantiles: Vec<f64>,
+    pub quantile_values: Vec<f64>,
+    pub attributes: Value,
+    pub resource_attributes: Value,
+    pub scope_name: String,
+    pub scope_version: String,
+    pub flags: u32,
+    /// Trace of the first exemplar that carries one, so a data point can be
+    /// followed back to a trace that contributed to it.
+    pub trace_id: Option<Uuid>,
+    pub span_id: Option<Uuid>,
+}
+
+impl MetricPoint {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        metric: &Metric,
+        metric_type: MetricType,
+        context: &PointContext,
+        attributes: Vec<KeyValue>,
+        start_time_unix_nano: u64,
+        time_unix_nano: u64,
+        flags: u32,
+        exemplars: &[Exemplar],
+    ) -> Self {
+        let (trace_id, span_id) = exemplar_trace_context(exemplars);
+
+        Self {
+            id: Uuid::new_v4(),
+            project_id: context.project_id,
+            name: metric.name.clone(),
+            description: metric.description.clone(),
+            unit: metric.unit.clone(),
+            metric_type,
+            time: DateTime::from_timestamp_nanos(time_unix_nano as i64),
+            start_time: DateTime::from_timestamp_nanos(start_time_unix_nano as i64),
+            aggregation_temporality: 0,
+            is_monotonic: false,
+            value: 0.0,
+            count: 0,
+            sum: 0.0,
+            min: None,
+            max: None,
+            bucket_counts: Vec::new(),
+            explicit_bounds: Vec::new(),
+            scale: 0,
+            zero_count: 0,
+            positive_offset: 0,
+            positive_bucket_counts: Vec::new(),
+            negative_offset: 0,
+            negative_bucket_counts: Vec::new(),
+            quantiles: Vec::new(),
+            quantile_values: Vec::new(),
+            attributes: key_values_to_json(attributes),
+            resource_attributes: context.resource_attributes.clone(),
+            scope_name: context.scope_name.clone(),
+            scope_version: context.scope_version.clone(),
+            flags,
+            trace_id,
+            span_id,
+        }
+    }
+
+    /// Flatten all data points of a metric into `MetricPoint`s. Metrics without
+    /// data (no `data` oneof set) produce no points.
+    fn from_proto(mut metric: Metric, context: &PointContext) -> Vec<Self> {
+        let Some(data) = metric.data.take() else {
+            return Vec::new();
+        };
+
+        match data {
+            Data::Gauge(gauge) => gauge
+                .data_points
+                .into_iter()
+                .map(|dp| {
+                    let mut point = Self::new(
+                        &metric,
+                        MetricType::Gauge,
+                        context,
+                        dp.attributes,
+                        dp.start_time_unix_nano,
+                        dp.time_unix_nano,
+                        dp.flags,
+                        &dp.exemplars,
+                    );
+                    point.value = number_value(dp.value);
+                    point
+                })
+                .collect(),
+            Data::Sum(sum) => sum
+                .data_points
+                .into_iter()
+                .map(|dp| {
+                    let mut point = Self::new(
+                        &metric,
+                        MetricType::Sum,
+                        context,
+                        dp.attributes,
+                        dp.start_time_unix_nano,
+                        dp.time_unix_nano,
+                        dp.flags,
+                        &dp.exemplars,
+                    );
+                    point.aggregation_temporality = sum.aggregation_temporality;
+                    point.is_monotonic = sum.is_monotonic;
+                    point.value = number_value(dp.value);
+                    point
+                })
+                .collect(),
+            Data::Histogram(histogram) => histogram
+                .data_points
+                .into_iter()
+                .map(|dp| {
+                    let mut point = Self::new(
+                        &metric,
+                        MetricType::Histogram,
+                        context,
+                        dp.attributes,
+                        dp.start_time_unix_nano,
+                        dp.time_unix_nano,
+                        dp.flags,
+                        &dp.exemplars,
+                    );
+                    point.aggregation_temporality = histogram.aggregation_temporality;
+                    point.count = dp.count;
+                    point.sum = dp.sum.unwrap_or_default();
+                    point.min = dp.min;
+                    point.max = dp.max;
+                    point.bucket_counts = dp.bucket_counts;
+                    point.explicit_bounds = dp.explicit_bounds;
+                    point
+                })
+                .collect(),
+            Data::ExponentialHistogram(histogram) => histogram
+                .data_points
+                .into_iter()
+                .map(|dp| {
+                    let mut point = Self::new(
+                        &metric,
+                        MetricType::ExponentialHistogram,
+                        context,
+                        dp.attributes,
+                        dp.start_time_unix_nano,
+                        dp.time_unix_nano,
+                        dp.flags,
+                        &dp.exemplars,
+                    );
+                    point.aggregation_temporality = histogram.aggregation_temporality;
+                    point.count = dp.count;
+                    point.sum = dp.sum.unwrap_or_default();
+                    point.min = dp.min;
+                    point.max = dp.max;
+                    point.scale = dp.scale;
+                    point.zero_count = dp.zero_count;
+                    if let Some(positive) = dp.positive {
+                        point.positive_offset = positive.offset;
+                        point.positive_bucket_counts = positive.bucket_counts;
+                    }
+                    if let Some(negative) = dp.negative {
+                        point.negative_offset = negative.offset;
+                        point.negative_bucket_counts = negative.bucket_counts;
+                    }
+                    point
+                })
+                .collect(),
+            Data::Summary(summary) => summary
+                .data_points
+                .into_iter()
+                .map(|dp| {
+                    let mut point = Self::new(
+                        &metric,
+                        MetricType::Summary,
+                        context,
+                        dp.attributes,
+                        dp.start_time_unix_nano,
+                        dp.time_unix_nano,
+                        dp.flags,
+                        &[],
+                    );
+                    point.count = dp.count;
+                    point.sum = dp.sum;
+                    (point.quantiles, point.quantile_values) = dp
+                        .quantile_values
+                        .iter()
+                        .map(|q| (q.quantile, q.value))
+                        .unzip();
+                    point
+                })
+                .collect(),
+        }
+    }
+
+    /// Estimate the size of this data point in bytes.
+    pub fn estimate_size_bytes(&self) -> usize {
+        // Rough estimate based on field sizes
+        16 + // id
+        16 + // project_id
+        self.name.len() +
+        self.description.len() +
+        self.unit.len() +
+        1 + // metric_type
+        8 + // time
+        8 + // start_time
+        1 + // aggregation_temporality
+        1 + // is_monotonic
+        8 * 5 + // value, count, sum, min, max
+        8 * (self.bucket_counts.len() + self.explicit_bounds.len()) +
+        4 + 8 + 4 + 4 + // scale, zero_count, positive_offset, negative_offset
+        8 * (self.positive_bucket_counts.len() + self.negative_bucket_counts.len()) +
+        8 * (self.quantiles.len() + self.quantile_values.len()) +
+        estimate_json_size(&self.attributes) +
+        estimate_json_size(&self.resource_attributes) +
+        self.scope_name.len() +
+        self.scope_version.len() +
+        4 + // flags
+        16 + // trace_id
+        16 // span_id
+    }
+}
+
+/// Resource- and scope-level fields shared by every data point underneath them.
+struct PointContext {
+    project_id: Uuid,
+    resource_attributes: Value,
+    scope_name: String,
+    scope_version: String,
+}
+
+fn key_values_to_json(attributes: Vec<KeyValue>) -> Value {
+    attributes
+        .into_iter()
+        .map(|kv| (kv.key, convert_any_value_to_json_value(kv.value)))
+        .collect::<serde_json::Map<String, Value>>()
+        .into()
+}
+
+fn number_value(value: Option<number_data_point::Value>) -> f64 {
+    match value {
+        Some(number_data_point::Value::AsDouble(v)) => v,
+        Some(number_data_point::Value::AsInt(v)) => v as f64,
+        None => 0.0,
+    }
+}
+
+fn exemplar_trace_context(exemplars: &[Exemplar]) -> (Option<Uuid>, Option<Uuid>) {
+    exemplars
+        .iter()
+        .find(|e| e.trace_id.len() == 16 && e.trace_id.iter().any(|&b| b != 0))
+        .map(|e| {
+            let trace_id = Uuid::from_slice(&e.trace_id).ok();
+            let span_id = if e.span_id.len() == 8 && e.span_id.iter().any(|&b| b != 0) {
+                Some(span_id_to_uuid(&e.span_id))
+            } else {
+                None
+            };
+            (trace_id, span_id)
+        })
+        .unwrap_or((None, None))
+}
+
+/// Message format for RabbitMQ metric messages.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RabbitMqMetricMessage {
+    pub point: MetricPoint,
+}
+
+pub fn metric_points_from_request(
+    request: ExportMetricsServiceRequest,
+    project_id: Uuid,
+) -> Vec<MetricPoint> {
+    request
+        .resource_metrics
+        .into_iter()
+        .flat_map(|resource_metrics| {
+            let resource_attributes = key_values_to_json(
+                resource_metrics
+                    .resource
+                    .map(|r| r.attributes)
+                    .unwrap_or_default(),
+            );
+            resource_metrics
+                .scope_metrics
+                .into_iter()
+                .flat_map(move |scope_metrics| {
+                    let InstrumentationScope { name, version, .. } =
+                        scope_metrics.scope.unwrap_or_default();
+                    let context = PointContext {
+                        project_id,
+                        resource_attributes: resource_attributes.clone(),
+                        scope_name: name,
+                        scope_version: version,
+                    };
+                    scope_metrics
+                        .metrics
+                        .into_iter()
+                        .flat_map(move |metric| MetricPoint::from_proto(metric, &context))
+                })
+        })
+        .collect()
+}
+
+pub async fn push_metrics_to_queue(
+    request: ExportMetricsServiceRequest,
+    project_id: Uuid,
+    queue: Arc<MessageQueue>,
+) -> Result<ExportMetricsServiceResponse> {
+    let messages: Vec<RabbitMqMetricMessage> = metric_points_from_request(request, project_id)
+        .into_iter()
+        .map(|point| RabbitMqMetricMessage { point })
+        .collect();
+
+    if messages.is_empty() {
+        return Ok(ExportMetricsServiceResponse {
+            partial_success: None,
+        });
+    }
+
+    let point_count = messages.len();
+    let mq_message = serde_json::to_vec(&messages)?;
+
+    if mq_message.len() >= mq_max_payload() {
+        log::warn!(
+            "[METRICS] MQ payload limit exceeded. Project ID: [{}], payload size: [{}]. Data point count: [{}]",
+            project_id,
+            mq_message.len(),
+            point_count
+        );
+        // Return partial success to inform client that data points were rejected
+        return Ok(ExportMetricsServiceResponse {
+            partial_success: Some(ExportMetricsPartialSuccess {
+                rejected_data_points: point_count as i64,
+                error_message: format!(
+                    "Payload size {} exceeds limit. All {} data points rejected.",
+                    mq_message.len(),
+                    point_count
+                ),
+            }),
+        });
+    }
+
+    queue
+        .publish(&mq_message, METRICS_EXCHANGE, METRICS_ROUTING_KEY, None)
+        .await?;
+
+    Ok(ExportMetricsServiceResponse {
+        partial_success: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::opentelemetry_proto::{
+        opentelemetry::proto::collector::metrics::v1::{
+            ExponentialHistogram, ExponentialHistogramDataPoint, Histogram, HistogramDataPoint,
+            NumberDataPoint, ResourceMetrics, ScopeMetrics, Sum,
+            exponential_histogram_data_point::Buckets,
+        },
+        opentelemetry_proto_common_v1::{AnyValue, any_value},
+        opentelemetry_proto_resource_v1::Resource,
+    };
+
+    fn string_kv(key: &str, value: &str) -> KeyValue {
+        KeyValue {
+            key: key.to_string(),
+            value: Some(AnyValue {
+                value: Some(any_value::Value::StringValue(value.to_string())),
+            }),
+        }
+    }
+
+    fn request(metrics: Vec<Metric>) -> ExportMetricsServiceRequest {
+        ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: Some(Resource {
+                    attributes: vec![string_kv("service.name", "agent")],
+                    dropped_attributes_count: 0,
+                }),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Some(InstrumentationScope {
+                        name: "lmnr".to_string(),
+                        version: "1.0".to_string(),
+                        ..Default::default()
+                    }),
+                    metrics,
+                    schema_url: String::new(),
+                }],
+                schema_url: String::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn flattens_sum_data_points() {
+        let metric = Metric {
+            name: "gen_ai.client.token.usage".to_string(),
+            unit: "{token}".to_string(),
+            data: Some(Data::Sum(Sum {
+                data_points: vec![
+                    NumberDataPoint {
+                        attributes: vec![string_kv("gen_ai.token.type", "input")],
+                        start_time_unix_nano: 1_000,
+                        time_unix_nano: 2_000,
+                        value: Some(number_data_point::Value::AsInt(42)),
+                        ..Default::default()
+                    },
+                    NumberDataPoint {
+                        attributes: vec![string_kv("gen_ai.token.type", "output")],
+                        time_unix_nano: 2_000,
+                        value: Some(number_data_point::Value::AsDouble(7.5)),
+                        ..Default::default()
+                    },
+                ],
+                aggregation_temporality: 2,
+                is_monotonic: true,
+            })),
+            ..Default::default()
+        };
+
+        let project_id = Uuid::new_v4();
+        let points = metric_points_from_request(request(vec![metric]), project_id);
+
+        assert_eq!(points.len(), 2);
+        assert_eq!(points[0].project_id, project_id);
+        assert_eq!(points[0].metric_type, MetricType::Sum);
+        assert_eq!(points[0].value, 42.0);
+        assert_eq!(points[1].value, 7.5);
+        assert!(points[0].is_monotonic);
+        assert_eq!(points[0].aggregation_temporality, 2);
+        assert_eq!(points[0].attributes["gen_ai.token.type"], "input");
+        assert_eq!(points[0].resource_attributes["service.name"], "agent");
+        assert_eq!(points[0].scope_name, "lmnr");
+        assert_eq!(points[0].time.timestamp_nanos_opt(), Some(2_000));
+    }
+
+    #[test]
+    fn flattens_histogram_with_exemplar_trace() {
+        let trace_id = Uuid::new_v4();
+        let metric = Metric {
+            name: "tool.latency".to_string(),
+            unit: "s".to_string(),
+            data: Some(Data::Histogram(Histogram {
+                data_points: vec![HistogramDataPoint {
+                    count: 3,
+                    sum: Some(1.5),
+                    min: Some(0.1),
+                    max: Some(1.0),
+                    bucket_counts: vec![1, 2, 0],
+                    explicit_bounds: vec![0.25, 1.0],
+                    exemplars: vec![Exemplar {
+                        trace_id: trace_id.as_bytes().to_vec(),
+                        span_id: vec![0, 0, 0, 0, 0, 0, 0, 1],
+                        ..Default::default()
+                    }],
+                    ..Default::default()
+                }],
+                aggregation_temporality: 1,
+            })),
+            ..Default::default()
+        };
+
+        let points = metric_points_from_request(request(vec![metric]), Uuid::new_v4());
+
+        assert_eq!(points.len(), 1);
+        let point = &points[0];
+        assert_eq!(point.metric_type, MetricType::Histogram);
+        assert_eq!(point.count, 3);
+        assert_eq!(point.sum, 1.5);
+        assert_eq!(point.min, Some(0.1));
+        assert_eq!(point.bucket_counts, vec![1, 2, 0]);
+        assert_eq!(point.explicit_bounds, vec![0.25, 1.0]);
+        assert_eq!(point.trace_id, Some(trace_id));
+        assert!(point.span_id.is_some());
+    }
+
+    #[test]
+    fn flattens_exponential_histogram_buckets() {
+        let metric = Metric {
+            name: "queue.depth".to_string(),
+            data: Some(Data::ExponentialHistogram(ExponentialHistogram {
+                data_points: vec![ExponentialHistogramDataPoint {
+                    count: 4,
+                    scale: 2,
+                    zero_count: 1,
+                    positive: Some(Buckets {
+                        offset: -1,
+                        bucket_counts: vec![1, 2],
+                    }),
+                    ..Default::default()
+                }],
+                aggregation_temporality: 2,
+            })),
+            ..Default::default()
+        };
+
+        let points = metric_points_from_request(request(vec![metric]), Uuid::new_v4());
+
+        assert_eq!(points.len(), 1);
+        assert_eq!(points[0].metric_type, MetricType::ExponentialHistogram);
+        assert_eq!(points[0].scale, 2);
+        assert_eq!(points[0].zero_count, 1);
+        assert_eq!(points[0].positive_offset, -1);
+        assert_eq!(points[0].positive_bucket_counts, vec![1, 2]);
+        assert!(points[0].negative_bucket_counts.is_empty());
+        assert_eq!(points[0].trace_id, None);
+    }
+
+    #[test]
+    fn metric_without_data_produces_no_points() {
+        let metric = Metric {
+            name: "empty".to_string(),
+            ..Default::default()
+        };
+        assert!(metric_points_from_request(request(vec![metric]), Uuid::new_v4()).is_empty());
+    }
+}
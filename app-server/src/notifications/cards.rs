@@ -0,0 +1,402 @@
+//! Channel-agnostic rendering of notifications for the webhook-style chat
+//! targets (Teams, Discord). Each formatter maps [`NotificationCard`]s onto its
+//! own message schema so wording and links stay the same across channels.
+
+use std::fmt::Display;
+
+use uuid::Uuid;
+
+use super::NotificationKind;
+use super::utils::{
+    build_report_data_from_batch, frontend_url_webhook, inject_utm_into_links, with_utm,
+};
+
+/// One renderable unit: an alert, or one project section of a report.
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct NotificationCard {
+    pub title: String,
+    /// Markdown. `[text](url)` links render in both Teams and Discord.
+    pub body: Option<String>,
+    pub facts: Vec<(String, String)>,
+    /// Primary call to action as `(label, url)`.
+    pub action: Option<(String, String)>,
+    /// Alert severity (0 = info, 1 = warning, 2 = critical), if any.
+    pub severity: Option<u8>,
+}
+
+pub(super) fn severity_label(severity: u8) -> &'static str {
+    match severity {
+        0 => "Info",
+        1 => "Warning",
+        2 => "Critical",
+        _ => "Unknown",
+    }
+}
+
+pub(super) fn trace_url(project_id: impl Display, trace_id: impl Display) -> String {
+    format!(
+        "{}/project/{}/traces/{}?chat=true",
+        frontend_url_webhook(),
+        project_id,
+        trace_id
+    )
+}
+
+pub(super) fn traces_url(project_id: impl Display) -> String {
+    format!("{}/project/{}/traces", frontend_url_webhook(), project_id)
+}
+
+pub(super) fn signal_url(project_id: impl Display, signal_id: impl Display) -> String {
+    format!(
+        "{}/project/{}/signals/{}",
+        frontend_url_webhook(),
+        project_id,
+        signal_id
+    )
+}
+
+pub(super) fn cluster_url(project_id: &Uuid, signal_id: &Uuid, cluster_id: &Uuid) -> String {
+    format!(
+        "{}?clusterId={}",
+        signal_url(project_id, signal_id),
+        cluster_id
+    )
+}
+
+/// Truncate to `max_chars` characters, ending with `...` when anything was cut.
+pub(super) fn truncate_chars(text: &str, max_chars: usize) -> String {
+    const ELLIPSIS: &str = "...";
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut out: String = text
+        .chars()
+        .take(max_chars.saturating_sub(ELLIPSIS.len()))
+        .collect();
+    out.push_str(ELLIPSIS);
+    out
+}
+
+/// Build the cards for a delivery batch. Alerts and usage warnings yield one
+/// card per notification; reports are combined and yield one card per project.
+/// `source` is the UTM source stamped on every link (`teams`, `discord`).
+pub(super) fn build_cards(
+    notifications: &[NotificationKind],
+    workspace_id: Uuid,
+    source: &str,
+) -> Vec<NotificationCard> {
+    if let Some(NotificationKind::SignalsReport { .. }) = notifications.first() {
+        return build_report_cards(notifications, workspace_id, source);
+    }
+
+    notifications
+        .iter()
+        .filter_map(|kind| match kind {
+            NotificationKind::EventIdentification {
+                project_id,
+                trace_id,
+                event_name,
+                severity,
+                extracted_information,
+                alert_name,
+                ..
+            } => {
+                let body = extracted_information.as_ref().map(|info| {
+                    let text = match info.as_object() {
+                        Some(obj) => obj
+                            .iter()
+                            .map(|(key, value)| {
+                                let value = match value {
+                                    serde_json::Value::String(s) => s.clone(),
+                                    serde_json::Value::Null => String::new(),
+                                    other => other.to_string(),
+                                };
+                                format!("**{}**: {}", key, value)
+                            })
+                            .collect::<Vec<_>>()
+                            .join("\n\n"),
+                        None => info.to_string(),
+                    };
+                    inject_utm_into_links(&text, source, "signal_alert", "event_description")
+                });
+                Some(NotificationCard {
+                    title: format!("{}: New Event", event_name),
+                    body,
+                    facts: vec![
+                        (
+                            "Severity".to_string(),
+                            severity_label(*severity).to_string(),
+                        ),
+                        ("Signal".to_string(), event_name.clone()),
+                        ("Alert".to_string(), alert_name.clone()),
+                    ],
+                    action: Some((
+                        "View Trace".to_string(),
+                        with_utm(
+                            &trace_url(project_id, trace_id),
+                            source,
+                            "signal_alert",
+                            "view_trace",
+                        ),
+                    )),
+                    severity: Some(*severity),
+                })
+            }
+            NotificationKind::NewCluster {
+                project_id,
+                signal_id,
+                signal_name,
+                cluster_id,
+                cluster_name,
+                num_signal_events,
+                num_child_clusters,
+                alert_name,
+            } => Some(NotificationCard {
+                title: format!("{}: New Cluster", signal_name),
+                body: None,
+                facts: vec![
+                    ("Cluster".to_string(), cluster_name.clone()),
+                    ("Events".to_string(), num_signal_events.to_string()),
+                    ("Child clusters".to_string(), num_child_clusters.to_string()),
+                    ("Alert".to_string(), alert_name.clone()),
+                ],
+                action: Some((
+                    "View Cluster".to_string(),
+                    with_utm(
+                        &cluster_url(project_id, signal_id, cluster_id),
+                        source,
+                        "new_cluster_alert",
+                        "view_cluster",
+                    ),
+                )),
+                severity: None,
+            }),
+            NotificationKind::MetricThreshold {
+                project_id,
+                alert_name,
+                rule,
+                value,
+                span_count,
+                ..
+            } => {
+                let mut facts = vec![
+                    ("Value".to_string(), rule.metric.format_value(*value)),
+                    ("Condition".to_string(), rule.condition()),
+                ];
+                let filters = rule.filters.describe();
+                if !filters.is_empty() {
+                    facts.push(("Filters".to_string(), filters));
+                }
+                facts.push(("Spans".to_string(), span_count.to_string()));
+                facts.push(("Alert".to_string(), alert_name.clone()));
+                Some(NotificationCard {
+                    title: format!("{}: Threshold Breached", alert_name),
+                    body: None,
+                    facts,
+                    action: Some((
+                        "View Traces".to_string(),
+                        with_utm(
+                            &traces_url(project_id),
+                            source,
+                            "metric_alert",
+                            "view_traces",
+                        ),
+                    )),
+                    severity: Some(1),
+                })
+            }
+            NotificationKind::UsageWarning {
+                workspace_name,
+                usage_label,
+                formatted_limit,
+                ..
+            } => Some(NotificationCard {
+                title: "Usage Warning".to_string(),
+                body: Some(format!(
+                    "{} has reached **{}** of {}.",
+                    workspace_name, formatted_limit, usage_label
+                )),
+                facts: vec![],
+                action: None,
+                severity: Some(1),
+            }),
+            NotificationKind::CustomReport {
+                project_id,
+                project_name,
+                report_name,
+                period_start,
+                period_end,
+                table,
+                error,
+                ..
+            } => {
+                // One fact per row, keyed by the first column, so the table
+                // renders in both Teams fact sets and Discord fields.
+                let facts = if error.is_some() {
+                    vec![]
+                } else {
+                    table
+                        .rows
+                        .iter()
+                        .map(|row| {
+                            let value = table
+                                .columns
+                                .iter()
+                                .zip(row)
+                                .skip(1)
+                                .map(|(column, cell)| format!("{}: {}", column, cell))
+                                .collect::<Vec<_>>()
+                                .join(" · ");
+                            (row.first().cloned().unwrap_or_default(), value)
+                        })
+                        .collect()
+                };
+                let mut body = format!("{} · {} – {}", project_name, period_start, period_end);
+                if let Some(error) = error {
+                    body.push_str(&format!("\n\nThe report query failed: {}", error));
+                } else if table.rows.is_empty() {
+                    body.push_str("\n\nNo rows for this period.");
+                } else if table.total_rows > table.rows.len() {
+                    body.push_str(&format!(
+                        "\n\nShowing {} of {} rows",
+                        table.rows.len(),
+                        table.total_rows
+                    ));
+                }
+                Some(NotificationCard {
+                    title: report_name.clone(),
+                    body: Some(body),
+                    facts,
+                    action: Some((
+                        "Open SQL Editor".to_string(),
+                        with_utm(
+                            &format!("{}/project/{}/sql", frontend_url_webhook(), project_id),
+                            source,
+                            "custom_report",
+                            "open_sql_editor",
+                        ),
+                    )),
+                    severity: None,
+                })
+            }
+            NotificationKind::SignalsReport { .. } => None,
+        })
+        .collect()
+}
+
+fn build_report_cards(
+    notifications: &[NotificationKind],
+    workspace_id: Uuid,
+    source: &str,
+) -> Vec<NotificationCard> {
+    let Some((title, report)) = build_report_data_from_batch(notifications, workspace_id) else {
+        return vec![];
+    };
+
+    report
+        .projects
+        .iter()
+        .map(|project| {
+            let mut body = String::new();
+            if !project.ai_summary.is_empty() {
+                body.push_str(&format!("_{}_\n\n", project.ai_summary));
+            }
+            if !project.noteworthy_events.is_empty() {
+                body.push_str("**Noteworthy events**\n");
+                for event in &project.noteworthy_events {
+                    let link = with_utm(
+                        &trace_url(project.project_id, &event.trace_id),
+                        source,
+                        "signals_report",
+                        "view_trace",
+                    );
+                    body.push_str(&format!(
+                        "- `{}` – {} ({}) [View trace]({})\n",
+                        event.signal_name, event.summary, event.timestamp, link
+                    ));
+                }
+            }
+
+            let project_total: u64 = project.signal_event_counts.values().sum();
+            let mut facts = vec![
+                (
+                    "Period".to_string(),
+                    format!("{} – {}", report.period_start, report.period_end),
+                ),
+                ("Total events".to_string(), project_total.to_string()),
+            ];
+            facts.extend(
+                project
+                    .signal_event_counts
+                    .iter()
+                    .map(|(name, count)| (name.clone(), count.to_string())),
+            );
+
+            NotificationCard {
+                title: format!("{} – {}", title, project.project_name),
+                body: (!body.is_empty()).then_some(body),
+                facts,
+                action: Some((
+                    "Open project".to_string(),
+                    with_utm(
+                        &format!(
+                            "{}/project/{}/signals",
+                            frontend_url_webhook(),
+                            project.project_id
+                        ),
+                        source,
+                        "signals_report",
+                        "view_project",
+                    ),
+                )),
+                severity: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn truncate_keeps_short_text_and_marks_cuts() {
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("ééééééééé", 6), "ééé...");
+    }
+
+    #[test]
+    fn event_card_carries_severity_facts_and_trace_action() {
+        let project_id = Uuid::new_v4();
+        let trace_id = Uuid::new_v4();
+        let cards = build_cards(
+            &[NotificationKind::EventIdentification {
+                project_id,
+                project_name: "prod".to_string(),
+                signal_id: Uuid::new_v4(),
+                trace_id,
+                event_id: None,
+                event_name: "refund_requested".to_string(),
+                severity: 2,
+                extracted_information: Some(serde_json::json!({"reason": "late delivery"})),
+                alert_name: "Refunds".to_string(),
+            }],
+            Uuid::new_v4(),
+            "discord",
+        );
+
+        assert_eq!(cards.len(), 1);
+        let card = &cards[0];
+        assert_eq!(card.title, "refund_requested: New Event");
+        assert_eq!(card.body.as_deref(), Some("**reason**: late delivery"));
+        assert_eq!(card.severity, Some(2));
+        assert!(
+            card.facts
+                .contains(&("Severity".to_string(), "Critical".to_string()))
+        );
+        let (label, url) = card.action.clone().unwrap();
+        assert_eq!(label, "View Trace");
+        assert!(url.contains(&format!("/project/{}/traces/{}", project_id, trace_id)));
+        assert!(url.contains("utm_source=discord"));
+    }
+}
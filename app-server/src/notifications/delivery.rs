@@ -1,8 +1,12 @@
 //! notification_deliveries_consumer — stage 2: format + send + log
 //!
-//! This module handles the final delivery of notifications to email and Slack
-//! targets. It receives `NotificationDeliveryMessage`s from the deliveries queue,
-//! formats the content, sends it, and records the delivery in ClickHouse.
+//! This module handles the final delivery of notifications to email, Slack and
+//! webhook-style (generic webhook, Teams, Discord) targets. It receives
+//! `NotificationDeliveryMessage`s from the deliveries queue, formats the
+//! content, sends it, and records the delivery in ClickHouse. Webhook-style
+//! deliveries make one attempt per message; a retryable failure is republished
+//! with a delay through the retry queue, and deliveries that exhaust their
+//! attempts are dead-lettered to Postgres.
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,10 +18,12 @@ use resend_rs::types::{CreateAttachment, CreateEmailBaseOptions};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use super::{NotificationKind, TargetType, email, slack};
+use super::webhook::{self, DeliveryAttempt, RetryPolicy};
+use super::{NotificationKind, TargetType, discord, email, slack, teams};
 use crate::ch::notification_deliveries::CHNotificationDelivery;
 use crate::ch::service::ClickhouseService;
 use crate::db::DB;
+use crate::db::notification_dead_letters::{NewNotificationDeadLetter, insert_dead_letter};
 use crate::mq::{MessageQueue, MessageQueueTrait};
 use crate::worker::{HandlerError, MessageHandler};
 
@@ -26,6 +32,13 @@ use crate::worker::{HandlerError, MessageHandler};
 pub const NOTIFICATION_DELIVERIES_EXCHANGE: &str = "notification_deliveries";
 pub const NOTIFICATION_DELIVERIES_QUEUE: &str = "notification_deliveries";
 pub const NOTIFICATION_DELIVERIES_ROUTING_KEY: &str = "notification_deliveries";
+/// Consumer-less queue holding webhook retries until their per-message TTL
+/// expires, at which point RabbitMQ dead-letters them back to
+/// `NOTIFICATION_DELIVERIES_EXCHANGE`. Messages expire in order, so a retry
+/// can wait behind a longer one; delays are capped by the retry policy.
+pub const NOTIFICATION_DELIVERIES_RETRY_EXCHANGE: &str = "notification_deliveries_retry";
+pub const NOTIFICATION_DELIVERIES_RETRY_QUEUE: &str = "notification_deliveries_retry";
+pub const NOTIFICATION_DELIVERIES_RETRY_ROUTING_KEY: &str = "notification_deliveries_retry";
 
 // ── NotificationDeliveryMessage (notifications_consumer → deliveries queue) ──
 
@@ -37,11 +50,19 @@ pub struct DeliveryTarget {
     pub email: Option<String>,
     pub channel_id: Option<String>,
     pub integration_id: Option<Uuid>,
+    /// Destination of WEBHOOK / TEAMS / DISCORD targets.
+    #[serde(default)]
+    pub url: Option<String>,
+    /// AEAD-encrypted HMAC signing secret of WEBHOOK targets (see `data_plane::crypto`).
+    #[serde(default)]
+    pub signing_secret: Option<String>,
+    #[serde(default)]
+    pub signing_secret_nonce: Option<String>,
 }
 
 /// Message pushed to the `notification_deliveries` queue.
 /// Contains the target info at the top level and the list of notification events.
-/// The deliveries consumer combines all notifications into a single email/slack message.
+/// The deliveries consumer combines all notifications into a single message per target.
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct NotificationDeliveryMessage {
     pub workspace_id: Uuid,
@@ -50,6 +71,12 @@ pub struct NotificationDeliveryMessage {
     /// IDs assigned to each notification. Matches `notifications` order.
     pub notification_ids: Vec<Uuid>,
     pub notifications: Vec<NotificationKind>,
+    /// Webhook-style attempts already made for this delivery.
+    #[serde(default)]
+    pub attempt: u32,
+    /// Set on retries, so `X-Laminar-Delivery` stays the same across attempts.
+    #[serde(default)]
+    pub delivery_id: Option<Uuid>,
 }
 
 pub(crate) async fn push_to_deliveries_queue(
@@ -70,28 +97,78 @@ pub(crate) async fn push_to_deliveries_queue(
     Ok(())
 }
 
+/// Deliver `message` again after `delay`. The in-memory queue ignores TTLs and
+/// has no dead-lettering, so there a detached task republishes it instead.
+async fn push_to_deliveries_retry_queue(
+    message: &NotificationDeliveryMessage,
+    delay: Duration,
+    queue: Arc<MessageQueue>,
+) -> anyhow::Result<()> {
+    let serialized = serde_json::to_vec(message)?;
+
+    if let MessageQueue::TokioMpsc(_) = queue.as_ref() {
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if let Err(e) = queue
+                .publish(
+                    &serialized,
+                    NOTIFICATION_DELIVERIES_EXCHANGE,
+                    NOTIFICATION_DELIVERIES_ROUTING_KEY,
+                    None,
+                )
+                .await
+            {
+                log::error!("[NotificationDelivery] Failed to republish retry: {:?}", e);
+            }
+        });
+        return Ok(());
+    }
+
+    queue
+        .publish(
+            &serialized,
+            NOTIFICATION_DELIVERIES_RETRY_EXCHANGE,
+            NOTIFICATION_DELIVERIES_RETRY_ROUTING_KEY,
+            // RabbitMQ drops a zero TTL message immediately
+            Some((delay.as_millis() as u64).max(1)),
+        )
+        .await?;
+
+    Ok(())
+}
+
 const LAMINAR_LOGO_PNG: &[u8] = include_bytes!("../../data/logo.png");
 const LAMINAR_LOGO_CID: &str = "laminar-logo";
 
 pub struct NotificationDeliveryHandler {
     pub db: Arc<DB>,
-    pub slack_client: reqwest::Client,
+    /// Slack API calls.
+    pub http_client: reqwest::Client,
+    /// Webhook-style targets, restricted to public destinations.
+    pub webhook_client: reqwest::Client,
     pub resend: Option<Arc<Resend>>,
     pub ch_service: Arc<ClickhouseService>,
+    pub queue: Arc<MessageQueue>,
+    pub webhook_retry: RetryPolicy,
 }
 
 impl NotificationDeliveryHandler {
     pub fn new(
         db: Arc<DB>,
-        slack_client: reqwest::Client,
+        http_client: reqwest::Client,
         resend: Option<Arc<Resend>>,
         ch_service: Arc<ClickhouseService>,
+        queue: Arc<MessageQueue>,
     ) -> Self {
+        let webhook_retry = RetryPolicy::from_env();
         Self {
             db,
-            slack_client,
+            http_client,
+            webhook_client: webhook::http_client(&webhook_retry),
             resend,
             ch_service,
+            queue,
+            webhook_retry,
         }
     }
 }
@@ -104,6 +181,9 @@ impl MessageHandler for NotificationDeliveryHandler {
         let raw_message = match message.target.target_type {
             TargetType::Slack => self.handle_slack(&message).await?,
             TargetType::Email => self.handle_email(&message).await?,
+            TargetType::Webhook | TargetType::Teams | TargetType::Discord => {
+                self.handle_webhook(&message).await?
+            }
         };
 
         if let Some(raw_message) = raw_message {
@@ -118,7 +198,9 @@ impl MessageHandler for NotificationDeliveryHandler {
                     let project_id = match kind {
                         NotificationKind::EventIdentification { project_id, .. } => *project_id,
                         NotificationKind::NewCluster { project_id, .. } => *project_id,
+                        NotificationKind::MetricThreshold { project_id, .. } => *project_id,
                         NotificationKind::SignalsReport { project_id, .. } => *project_id,
+                        NotificationKind::CustomReport { project_id, .. } => *project_id,
                         NotificationKind::UsageWarning { .. } => Uuid::nil(),
                     };
                     CHNotificationDelivery {
@@ -199,7 +281,7 @@ impl NotificationDeliveryHandler {
             slack::format_message_blocks_batch(&message.notifications, message.workspace_id);
 
         slack::send_message(
-            &self.slack_client,
+            &self.http_client,
             &decrypted_token,
             channel_id,
             blocks.clone(),
@@ -273,6 +355,145 @@ impl NotificationDeliveryHandler {
     }
 }
 
+impl NotificationDeliveryHandler {
+    /// Format and POST a webhook, Teams or Discord message combining all
+    /// notifications in the batch. A retryable failure is republished to the
+    /// retry queue per `webhook_retry`; a delivery that runs out of attempts is
+    /// written to `notification_dead_letters`. Returns `Ok(Some(body))` with the
+    /// JSON body on success, `Ok(None)` if delivery was skipped, scheduled for
+    /// a retry or dead-lettered.
+    async fn handle_webhook(
+        &self,
+        message: &NotificationDeliveryMessage,
+    ) -> Result<Option<String>, HandlerError> {
+        let target = &message.target;
+        let Some(url) = target.url.as_deref().filter(|url| !url.is_empty()) else {
+            log::error!(
+                "[NotificationDelivery] {} target {} missing url",
+                target.target_type,
+                target.target_id
+            );
+            return Ok(None);
+        };
+
+        let delivery_id = message.delivery_id.unwrap_or_else(Uuid::new_v4);
+        let attempt = message.attempt + 1;
+        let body = match target.target_type {
+            TargetType::Teams => {
+                teams::format_message(&message.notifications, message.workspace_id)
+            }
+            TargetType::Discord => {
+                discord::format_message(&message.notifications, message.workspace_id)
+            }
+            _ => webhook::format_payload(message, delivery_id, chrono::Utc::now()),
+        };
+
+        let secret = match (
+            target.target_type,
+            &target.signing_secret,
+            &target.signing_secret_nonce,
+        ) {
+            (TargetType::Webhook, Some(secret), Some(nonce)) => Some(
+                crate::data_plane::crypto::decrypt(message.workspace_id, nonce, secret).map_err(
+                    |e| {
+                        HandlerError::permanent(anyhow::anyhow!(
+                            "Failed to decrypt webhook signing secret for target {}: {}",
+                            target.target_id,
+                            e
+                        ))
+                    },
+                )?,
+            ),
+            _ => None,
+        };
+
+        let event = message
+            .notifications
+            .first()
+            .map(webhook::event_name)
+            .unwrap_or_default();
+
+        match webhook::attempt_delivery(
+            &self.webhook_client,
+            &self.webhook_retry,
+            url,
+            &body,
+            secret.as_deref(),
+            delivery_id,
+            event,
+            attempt,
+        )
+        .await
+        {
+            DeliveryAttempt::Delivered => {
+                log::debug!(
+                    "[NotificationDelivery] {} notification sent to {} ({} items, {} attempts)",
+                    target.target_type,
+                    webhook::redact_url(url),
+                    message.notifications.len(),
+                    attempt,
+                );
+                Ok(Some(body.to_string()))
+            }
+            DeliveryAttempt::Retry { error, delay } => {
+                log::info!(
+                    "[NotificationDelivery] {} attempt {} to {} failed ({}), retrying in {:?}",
+                    target.target_type,
+                    attempt,
+                    webhook::redact_url(url),
+                    error,
+                    delay
+                );
+                let retry = NotificationDeliveryMessage {
+                    attempt,
+                    delivery_id: Some(delivery_id),
+                    ..message.clone()
+                };
+                push_to_deliveries_retry_queue(&retry, delay, self.queue.clone())
+                    .await
+                    .map_err(|e| {
+                        HandlerError::transient(anyhow::anyhow!(
+                            "Failed to schedule webhook retry: {}",
+                            e
+                        ))
+                    })?;
+                Ok(None)
+            }
+            DeliveryAttempt::Failed(failure) => {
+                log::warn!(
+                    "[NotificationDelivery] Dead-lettering {} delivery to {} after {} attempts: {}",
+                    target.target_type,
+                    webhook::redact_url(url),
+                    failure.attempts,
+                    failure.error,
+                );
+                insert_dead_letter(
+                    &self.db.pool,
+                    &NewNotificationDeadLetter {
+                        workspace_id: message.workspace_id,
+                        project_id: message.project_id,
+                        target_id: target.target_id,
+                        target_type: target.target_type.to_string(),
+                        url,
+                        payload: &body,
+                        error: failure.error,
+                        attempts: failure.attempts as i32,
+                    },
+                )
+                .await
+                .map_err(|e| {
+                    // Requeue rather than lose the notification when it can't be dead-lettered.
+                    HandlerError::transient(anyhow::anyhow!(
+                        "Failed to dead-letter webhook delivery: {}",
+                        e
+                    ))
+                })?;
+                Ok(None)
+            }
+        }
+    }
+}
+
 async fn send_email_with_retry(
     resend: &Resend,
     email: CreateEmailBaseOptions,
@@ -0,0 +1,154 @@
+//! Discord formatting for channel webhook URLs
+//! (`https://discord.com/api/webhooks/<id>/<token>`).
+
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use super::NotificationKind;
+use super::cards::{NotificationCard, build_cards, truncate_chars};
+
+// Discord embed limits: https://discord.com/developers/docs/resources/message#embed-object-embed-limits
+const MAX_EMBEDS: usize = 10;
+const MAX_TITLE_CHARS: usize = 256;
+const MAX_DESCRIPTION_CHARS: usize = 4_096;
+const MAX_FIELDS: usize = 25;
+const MAX_FIELD_NAME_CHARS: usize = 256;
+const MAX_FIELD_VALUE_CHARS: usize = 1_024;
+/// Combined characters across every embed of one message.
+const MAX_TOTAL_CHARS: usize = 6_000;
+
+const COLOR_CRITICAL: u32 = 0xE5484D;
+const COLOR_WARNING: u32 = 0xF5A524;
+const COLOR_INFO: u32 = 0x30A46C;
+const COLOR_DEFAULT: u32 = 0xD0754E;
+
+/// Format a Discord webhook message combining all notifications in the batch.
+/// Embeds that would push the message past Discord's size limits are dropped.
+pub fn format_message(notifications: &[NotificationKind], workspace_id: Uuid) -> Value {
+    let cards = build_cards(notifications, workspace_id, "discord");
+
+    let mut embeds = Vec::new();
+    let mut total_chars = 0;
+    for card in cards.iter().take(MAX_EMBEDS) {
+        let (embed, chars) = embed(card);
+        if total_chars + chars > MAX_TOTAL_CHARS && !embeds.is_empty() {
+            break;
+        }
+        total_chars += chars;
+        embeds.push(embed);
+    }
+
+    json!({
+        "username": "Laminar",
+        "embeds": embeds,
+        // Notification text is user/LLM-authored; never let it ping anyone.
+        "allowed_mentions": { "parse": [] },
+    })
+}
+
+/// Build one embed and return it with its character count toward the
+/// per-message total.
+fn embed(card: &NotificationCard) -> (Value, usize) {
+    let title = truncate_chars(&card.title, MAX_TITLE_CHARS);
+    let description = card
+        .body
+        .as_deref()
+        .map(|body| truncate_chars(body, MAX_DESCRIPTION_CHARS));
+    let fields: Vec<(String, String)> = card
+        .facts
+        .iter()
+        .take(MAX_FIELDS)
+        .map(|(name, value)| {
+            (
+                truncate_chars(name, MAX_FIELD_NAME_CHARS),
+                // Discord rejects empty field values.
+                truncate_chars(
+                    if value.is_empty() {
+                        "-"
+                    } else {
+                        value.as_str()
+                    },
+                    MAX_FIELD_VALUE_CHARS,
+                ),
+            )
+        })
+        .collect();
+
+    let chars = title.chars().count()
+        + description.as_deref().map_or(0, |d| d.chars().count())
+        + fields
+            .iter()
+            .map(|(name, value)| name.chars().count() + value.chars().count())
+            .sum::<usize>();
+
+    let color = match card.severity {
+        Some(2) => COLOR_CRITICAL,
+        Some(1) => COLOR_WARNING,
+        Some(0) => COLOR_INFO,
+        _ => COLOR_DEFAULT,
+    };
+
+    let mut embed = json!({
+        "title": title,
+        "color": color,
+        "fields": fields
+            .iter()
+            .map(|(name, value)| json!({ "name": name, "value": value, "inline": true }))
+            .collect::<Vec<_>>(),
+    });
+    if let Some(description) = description {
+        embed["description"] = json!(description);
+    }
+    if let Some((_, url)) = &card.action {
+        embed["url"] = json!(url);
+    }
+
+    (embed, chars)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage_warning(workspace_name: &str) -> NotificationKind {
+        NotificationKind::UsageWarning {
+            workspace_name: workspace_name.to_string(),
+            usage_label: "data ingested".to_string(),
+            formatted_limit: "3 GiB".to_string(),
+            usage_item: "bytes".to_string(),
+            at_tier_included_allowance: false,
+            tier_display_name: String::new(),
+            overage_billable: false,
+        }
+    }
+
+    #[test]
+    fn usage_warning_renders_as_a_warning_embed_without_mentions() {
+        let message = format_message(&[usage_warning("Acme")], Uuid::new_v4());
+
+        assert_eq!(message["allowed_mentions"]["parse"], json!([]));
+        let embed = &message["embeds"][0];
+        assert_eq!(embed["title"], "Usage Warning");
+        assert_eq!(embed["color"], COLOR_WARNING);
+        assert_eq!(
+            embed["description"],
+            "Acme has reached **3 GiB** of data ingested."
+        );
+    }
+
+    #[test]
+    fn embeds_are_capped_by_count_and_total_size() {
+        let many: Vec<_> = (0..15).map(|i| usage_warning(&i.to_string())).collect();
+        let message = format_message(&many, Uuid::new_v4());
+        assert_eq!(message["embeds"].as_array().unwrap().len(), MAX_EMBEDS);
+
+        let huge: Vec<_> = (0..3).map(|_| usage_warning(&"x".repeat(5_000))).collect();
+        let message = format_message(&huge, Uuid::new_v4());
+        let embeds = message["embeds"].as_array().unwrap();
+        assert_eq!(embeds.len(), 1);
+        assert_eq!(
+            embeds[0]["description"].as_str().unwrap().chars().count(),
+            MAX_DESCRIPTION_CHARS
+        );
+    }
+}
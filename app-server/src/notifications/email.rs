@@ -8,10 +8,12 @@
 use uuid::Uuid;
 
 use super::NotificationKind;
+use super::metric_alerts::MetricThresholdRule;
 use super::utils::{
     build_report_data_from_batch, frontend_url_email, inject_utm_into_links,
     md_links_to_html_escaped, with_utm,
 };
+use crate::reports::custom::ReportTable;
 use crate::reports::email_template::ReportData;
 
 const REPORT_FROM_EMAIL: &str = "Laminar <reports@mail.lmnr.ai>";
@@ -113,6 +115,32 @@ pub fn format_email_batch(notifications: &[NotificationKind], workspace_id: &Uui
                 alert_name,
             ),
         },
+        NotificationKind::MetricThreshold {
+            project_id,
+            alert_name,
+            rule,
+            value,
+            span_count,
+            window_start,
+            window_end,
+        } => EmailContent {
+            from: ALERT_FROM_EMAIL.to_string(),
+            subject: format!(
+                "{}: {} is {}",
+                alert_name,
+                rule.metric.label(),
+                rule.metric.format_value(*value)
+            ),
+            html: render_metric_threshold_email(
+                project_id,
+                alert_name,
+                rule,
+                *value,
+                *span_count,
+                window_start,
+                window_end,
+            ),
+        },
         NotificationKind::SignalsReport { .. } => {
             let (title, report_data) = build_report_data_from_batch(notifications, *workspace_id)
                 .expect("SignalsReport batch must contain at least one report");
@@ -122,6 +150,28 @@ pub fn format_email_batch(notifications: &[NotificationKind], workspace_id: &Uui
                 html: render_report_email(&report_data),
             }
         }
+        NotificationKind::CustomReport {
+            project_id,
+            project_name,
+            report_name,
+            period_start,
+            period_end,
+            table,
+            error,
+            ..
+        } => EmailContent {
+            from: REPORT_FROM_EMAIL.to_string(),
+            subject: format!("{} \u{2013} {}", report_name, project_name),
+            html: render_custom_report_email(
+                project_id,
+                project_name,
+                report_name,
+                period_start,
+                period_end,
+                table,
+                error.as_deref(),
+            ),
+        },
         NotificationKind::UsageWarning {
             workspace_name,
             usage_label,
@@ -442,6 +492,248 @@ fn render_new_cluster_email(
     )
 }
 
+/// Render an HTML email for a metric-threshold notification.
+fn render_metric_threshold_email(
+    project_id: &Uuid,
+    alert_name: &str,
+    rule: &MetricThresholdRule,
+    value: f64,
+    span_count: u64,
+    window_start: &str,
+    window_end: &str,
+) -> String {
+    let base = frontend_url_email();
+    let traces_link = with_utm(
+        &format!("{}/project/{}/traces", base, project_id),
+        "email",
+        "metric_alert",
+        "view_traces",
+    );
+    let alert_link = with_utm(
+        &format!("{}/project/{}/settings?tab=alerts", base, project_id),
+        "email",
+        "metric_alert",
+        "manage_alert",
+    );
+    let manage_prefs_link = with_utm(
+        &format!("{}/project/{}/settings?tab=alerts", base, project_id),
+        "email",
+        "metric_alert",
+        "manage_preferences",
+    );
+
+    let mut rows = vec![
+        ("Value", rule.metric.format_value(value)),
+        ("Condition", rule.condition()),
+    ];
+    let filters = rule.filters.describe();
+    if !filters.is_empty() {
+        rows.push(("Filters", filters));
+    }
+    rows.push(("Spans", span_count.to_string()));
+    rows.push((
+        "Window",
+        format!("{} \u{2013} {}", window_start, window_end),
+    ));
+
+    let last = rows.len() - 1;
+    let rows_html: String = rows
+        .iter()
+        .enumerate()
+        .map(|(i, (label, value))| {
+            let border = if i == last {
+                ""
+            } else {
+                "border-bottom:1px solid #f3f4f6;"
+            };
+            format!(
+                r#"    <tr>
+      <td style="padding:6px 0;font-size:13px;color:#6b7280;{border}vertical-align:top;">{label}</td>
+      <td style="padding:6px 0 6px 12px;font-size:13px;color:#111827;{border}">{value}</td>
+    </tr>
+"#,
+                border = border,
+                label = label,
+                value = html_escape(value),
+            )
+        })
+        .collect();
+
+    let details_html = format!(
+        r#"<div style="background:#f9fafb;border:1px solid #e5e7eb;border-radius:8px;padding:16px;margin-bottom:20px;">
+  <h3 style="margin:0 0 12px;font-size:14px;font-weight:600;color:#6b7280;">Details</h3>
+  <table width="100%" cellpadding="0" cellspacing="0" border="0">
+{rows_html}  </table>
+</div>"#,
+        rows_html = rows_html,
+    );
+
+    let context_html = format!(
+        r##"<div style="text-align:center;margin-top:14px;font-size:12px;color:#9ca3af;line-height:1.6;">
+  <span style="vertical-align:middle;">Alert: <a href="{alert_link}" style="color:{primary};text-decoration:none;">{alert_name}</a></span>
+</div>"##,
+        alert_link = alert_link,
+        alert_name = html_escape(alert_name),
+        primary = PRIMARY,
+    );
+
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width,initial-scale=1.0">
+<title>{alert_name}: Threshold breached</title>
+</head>
+<body style="margin:0;padding:0;background:#f3f4f6;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,'Helvetica Neue',Arial,sans-serif;">
+<div style="max-width:640px;margin:0 auto;padding:24px 16px;">
+
+  <div style="background:#0A0A0A;border-radius:10px;padding:28px 24px;margin-bottom:20px;">
+    <img src="cid:laminar-logo" alt="Laminar" width="120" height="21" style="display:block;margin-bottom:16px;" />
+    <p style="margin:0 0 6px;font-size:13px;color:#9ca3af;">Threshold breached</p>
+    <h1 style="margin:0;font-size:22px;font-weight:700;color:#ffffff;">{alert_name}</h1>
+  </div>
+
+  <div style="background:#ffffff;border-radius:10px;border:1px solid #e5e7eb;padding:24px;margin-bottom:20px;">
+    {details_html}
+    <div style="text-align:center;padding-top:8px;">
+      <a href="{traces_link}" style="display:inline-block;background:#D0754E;color:#ffffff;text-decoration:none;padding:10px 24px;border-radius:6px;font-size:14px;font-weight:600;">View Traces</a>
+    </div>
+    {context_html}
+  </div>
+
+  <div style="text-align:center;padding:16px 0;">
+    <p style="margin:0 0 4px;font-size:12px;color:#9ca3af;">This alert was generated automatically by <a href="https://www.lmnr.ai" style="color:#D0754E;text-decoration:none;">Laminar</a>.</p>
+    <p style="margin:0 0 4px;font-size:12px;color:#9ca3af;">You are receiving this because you are subscribed to alerts for this project.</p>
+    <p style="margin:0;font-size:12px;color:#9ca3af;"><a href="{manage_prefs_link}" style="color:#D0754E;text-decoration:none;">Manage alert preferences</a></p>
+  </div>
+
+</div>
+</body>
+</html>"##,
+        alert_name = html_escape(alert_name),
+        details_html = details_html,
+        traces_link = traces_link,
+        context_html = context_html,
+        manage_prefs_link = manage_prefs_link,
+    )
+}
+
+/// Render an HTML email for a custom query report.
+fn render_custom_report_email(
+    project_id: &Uuid,
+    project_name: &str,
+    report_name: &str,
+    period_start: &str,
+    period_end: &str,
+    table: &ReportTable,
+    error: Option<&str>,
+) -> String {
+    let sql_link = with_utm(
+        &format!("{}/project/{}/sql", frontend_url_email(), project_id),
+        "email",
+        "custom_report",
+        "open_sql_editor",
+    );
+
+    let body_html = if let Some(error) = error {
+        format!(
+            r#"<p style="margin:0 0 20px;font-size:14px;color:#b91c1c;">The report query failed: {}</p>"#,
+            html_escape(error)
+        )
+    } else if table.rows.is_empty() {
+        r#"<p style="margin:0 0 20px;font-size:14px;color:#6b7280;">No rows for this period.</p>"#
+            .to_string()
+    } else {
+        let header_html: String = table
+            .columns
+            .iter()
+            .map(|column| {
+                format!(
+                    r#"<th style="padding:6px 8px;font-size:12px;font-weight:600;color:#6b7280;text-align:left;border-bottom:1px solid #e5e7eb;">{}</th>"#,
+                    html_escape(column)
+                )
+            })
+            .collect();
+        let rows_html: String = table
+            .rows
+            .iter()
+            .map(|row| {
+                let cells: String = row
+                    .iter()
+                    .map(|cell| {
+                        format!(
+                            r#"<td style="padding:6px 8px;font-size:13px;color:#111827;border-bottom:1px solid #f3f4f6;">{}</td>"#,
+                            html_escape(cell)
+                        )
+                    })
+                    .collect();
+                format!("    <tr>{}</tr>\n", cells)
+            })
+            .collect();
+        let truncated_html = if table.total_rows > table.rows.len() {
+            format!(
+                r#"<p style="margin:8px 0 0;font-size:12px;color:#9ca3af;">Showing {} of {} rows</p>"#,
+                table.rows.len(),
+                table.total_rows
+            )
+        } else {
+            String::new()
+        };
+        format!(
+            r#"<div style="overflow-x:auto;margin-bottom:20px;">
+  <table width="100%" cellpadding="0" cellspacing="0" border="0">
+    <tr>{header_html}</tr>
+{rows_html}  </table>
+  {truncated_html}
+</div>"#,
+            header_html = header_html,
+            rows_html = rows_html,
+            truncated_html = truncated_html,
+        )
+    };
+
+    format!(
+        r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width,initial-scale=1.0">
+<title>{report_name}</title>
+</head>
+<body style="margin:0;padding:0;background:#f3f4f6;font-family:-apple-system,BlinkMacSystemFont,'Segoe UI',Roboto,'Helvetica Neue',Arial,sans-serif;">
+<div style="max-width:640px;margin:0 auto;padding:24px 16px;">
+
+  <div style="background:#0A0A0A;border-radius:10px;padding:28px 24px;margin-bottom:20px;">
+    <img src="cid:laminar-logo" alt="Laminar" width="120" height="21" style="display:block;margin-bottom:16px;" />
+    <p style="margin:0 0 6px;font-size:13px;color:#9ca3af;">{project_name} &middot; {period_start} &ndash; {period_end}</p>
+    <h1 style="margin:0;font-size:22px;font-weight:700;color:#ffffff;">{report_name}</h1>
+  </div>
+
+  <div style="background:#ffffff;border-radius:10px;border:1px solid #e5e7eb;padding:24px;margin-bottom:20px;">
+    {body_html}
+    <div style="text-align:center;padding-top:8px;">
+      <a href="{sql_link}" style="display:inline-block;background:#D0754E;color:#ffffff;text-decoration:none;padding:10px 24px;border-radius:6px;font-size:14px;font-weight:600;">Open SQL Editor</a>
+    </div>
+  </div>
+
+  <div style="text-align:center;padding:16px 0;">
+    <p style="margin:0 0 4px;font-size:12px;color:#9ca3af;">This report was generated automatically by <a href="https://www.lmnr.ai" style="color:#D0754E;text-decoration:none;">Laminar</a>.</p>
+    <p style="margin:0;font-size:12px;color:#9ca3af;">You are receiving this because you are subscribed to this report.</p>
+  </div>
+
+</div>
+</body>
+</html>"##,
+        report_name = html_escape(report_name),
+        project_name = html_escape(project_name),
+        period_start = html_escape(period_start),
+        period_end = html_escape(period_end),
+        body_html = body_html,
+        sql_link = sql_link,
+    )
+}
+
 /// Render an HTML email for a usage warning notification.
 #[allow(clippy::too_many_arguments)]
 fn render_usage_warning_email(
@@ -0,0 +1,568 @@
+//! Metric-threshold alerts: rules like "p95 LLM latency for model X > 8s over
+//! 15 min" evaluated against the `span_metrics` rollups on the reports
+//! scheduler's cadence.
+//!
+//! A breach is notified once when it starts and then stays quiet until the
+//! metric recovers, unless the rule asks to be reminded every
+//! `renotifyMinutes`. Snoozing (set from the frontend) suppresses notifications
+//! without resetting that state. Notifications go through the regular
+//! notifications → deliveries queues, so alert targets work unchanged.
+
+use std::sync::Arc;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
+use super::{NotificationDefinitionType, NotificationKind, NotificationMessage};
+use crate::cache::Cache;
+use crate::cache::keys::METRIC_ALERTS_SCHEDULER_LOCK_CACHE_KEY;
+use crate::ch::span_metrics::{SpanMetricsFilter, SpanMetricsWindow, get_span_metrics_window};
+use crate::db::metric_alerts::{MetricAlert, get_metric_alerts, upsert_metric_alert_state};
+use crate::db::spans::SpanType;
+use crate::mq::MessageQueue;
+use crate::reports::scheduler::run_locked_on_interval;
+
+/// Longest window a rule may look back over, in minutes (one day).
+const MAX_WINDOW_MINUTES: u32 = 24 * 60;
+
+/// The metric a rule watches. Latencies are in seconds, error rate in percent
+/// and cost in USD.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertMetric {
+    LatencyAvg,
+    LatencyP50,
+    LatencyP90,
+    LatencyP95,
+    LatencyP99,
+    ErrorRate,
+    Cost,
+    SpanCount,
+    Tokens,
+}
+
+impl AlertMetric {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::LatencyAvg => "average latency",
+            Self::LatencyP50 => "p50 latency",
+            Self::LatencyP90 => "p90 latency",
+            Self::LatencyP95 => "p95 latency",
+            Self::LatencyP99 => "p99 latency",
+            Self::ErrorRate => "error rate",
+            Self::Cost => "cost",
+            Self::SpanCount => "span count",
+            Self::Tokens => "tokens",
+        }
+    }
+
+    /// The metric's value over a window, or `None` when it is undefined
+    /// (latency and error rate without spans).
+    pub fn value(&self, window: &SpanMetricsWindow) -> Option<f64> {
+        let span_count = window.span_count as f64;
+        let quantile = |idx: usize| {
+            window
+                .duration_quantiles
+                .get(idx)
+                .copied()
+                .filter(|_| window.span_count > 0)
+                .filter(|v| v.is_finite())
+        };
+        match self {
+            Self::LatencyAvg => (window.span_count > 0).then(|| window.duration_sum / span_count),
+            Self::LatencyP50 => quantile(0),
+            Self::LatencyP90 => quantile(1),
+            Self::LatencyP95 => quantile(2),
+            Self::LatencyP99 => quantile(3),
+            Self::ErrorRate => {
+                (window.span_count > 0).then(|| window.error_count as f64 / span_count * 100.0)
+            }
+            Self::Cost => Some(window.total_cost),
+            Self::SpanCount => Some(span_count),
+            Self::Tokens => Some(window.total_tokens as f64),
+        }
+    }
+
+    /// Human-readable value in the metric's unit, e.g. `8.42s`, `5.1%`, `$50.00`.
+    pub fn format_value(&self, value: f64) -> String {
+        match self {
+            Self::LatencyAvg
+            | Self::LatencyP50
+            | Self::LatencyP90
+            | Self::LatencyP95
+            | Self::LatencyP99 => {
+                if value < 1.0 {
+                    format!("{:.0}ms", value * 1000.0)
+                } else {
+                    format!("{:.2}s", value)
+                }
+            }
+            Self::ErrorRate => format!("{:.1}%", value),
+            Self::Cost if value.abs() < 1.0 => format!("${:.4}", value),
+            Self::Cost => format!("${:.2}", value),
+            Self::SpanCount | Self::Tokens => format!("{:.0}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdOperator {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl ThresholdOperator {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Gt => ">",
+            Self::Gte => "≥",
+            Self::Lt => "<",
+            Self::Lte => "≤",
+        }
+    }
+
+    pub fn is_breached(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            Self::Gt => value > threshold,
+            Self::Gte => value >= threshold,
+            Self::Lt => value < threshold,
+            Self::Lte => value <= threshold,
+        }
+    }
+}
+
+/// Restricts a rule to spans matching every set dimension of `span_metrics`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricFilters {
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub provider: Option<String>,
+    /// Dot-joined span path, e.g. `agent.openai.chat`
+    #[serde(default)]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub span_type: Option<SpanType>,
+}
+
+impl MetricFilters {
+    /// `model = gpt-4o, path = agent.openai.chat`, or an empty string without filters.
+    pub fn describe(&self) -> String {
+        [
+            ("model", self.model.clone()),
+            ("provider", self.provider.clone()),
+            ("path", self.path.clone()),
+            ("span type", self.span_type.as_ref().map(|t| t.to_string())),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| value.map(|value| format!("{} = {}", name, value)))
+        .collect::<Vec<_>>()
+        .join(", ")
+    }
+}
+
+/// The rule of a `METRIC_THRESHOLD` alert, stored in `alerts.metadata`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricThresholdRule {
+    pub metric: AlertMetric,
+    #[serde(default)]
+    pub filters: MetricFilters,
+    pub window_minutes: u32,
+    pub operator: ThresholdOperator,
+    pub threshold: f64,
+    /// Remind every this many minutes while the breach lasts. Unset notifies
+    /// once per breach.
+    #[serde(default)]
+    pub renotify_minutes: Option<u32>,
+    /// Windows with fewer spans are not evaluated, so a single failed span
+    /// doesn't read as a 100% error rate.
+    #[serde(default)]
+    pub min_span_count: Option<u64>,
+}
+
+impl MetricThresholdRule {
+    pub fn from_metadata(metadata: &serde_json::Value) -> anyhow::Result<Self> {
+        let rule: Self = serde_json::from_value(metadata.clone())?;
+        if rule.window_minutes == 0 || rule.window_minutes > MAX_WINDOW_MINUTES {
+            anyhow::bail!(
+                "windowMinutes must be between 1 and {}, got {}",
+                MAX_WINDOW_MINUTES,
+                rule.window_minutes
+            );
+        }
+        if !rule.threshold.is_finite() {
+            anyhow::bail!("threshold must be a finite number");
+        }
+        Ok(rule)
+    }
+
+    /// `p95 latency > 8.00s over 15 min`
+    pub fn condition(&self) -> String {
+        format!(
+            "{} {} {} over {} min",
+            self.metric.label(),
+            self.operator.symbol(),
+            self.metric.format_value(self.threshold),
+            self.window_minutes
+        )
+    }
+
+    /// The metric value over `window`, or `None` when there is nothing to
+    /// compare against the threshold.
+    pub fn evaluate(&self, window: &SpanMetricsWindow) -> Option<f64> {
+        if window.span_count < self.min_span_count.unwrap_or(0) {
+            return None;
+        }
+        self.metric.value(window)
+    }
+}
+
+/// Persisted evaluation state of one alert.
+#[derive(Debug, Clone, Default)]
+struct AlertState {
+    firing: bool,
+    last_notified_at: Option<DateTime<Utc>>,
+    snoozed_until: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decision {
+    notify: bool,
+    firing: bool,
+}
+
+/// Whether to notify for this evaluation and the alert's next `firing` state.
+///
+/// `breached` is `None` when the window had no data to evaluate; the alert
+/// then keeps its previous state instead of resolving and re-firing later.
+fn decide(
+    breached: Option<bool>,
+    state: &AlertState,
+    renotify_minutes: Option<u32>,
+    now: DateTime<Utc>,
+) -> Decision {
+    let Some(breached) = breached else {
+        return Decision {
+            notify: false,
+            firing: state.firing,
+        };
+    };
+    if !breached {
+        return Decision {
+            notify: false,
+            firing: false,
+        };
+    }
+    if state.snoozed_until.is_some_and(|until| until > now) {
+        return Decision {
+            notify: false,
+            firing: state.firing,
+        };
+    }
+    if !state.firing {
+        return Decision {
+            notify: true,
+            firing: true,
+        };
+    }
+    let renotify_due = match (renotify_minutes, state.last_notified_at) {
+        (Some(minutes), Some(last)) => now - last >= chrono::Duration::minutes(minutes as i64),
+        (Some(_), None) => true,
+        (None, _) => false,
+    };
+    Decision {
+        notify: renotify_due,
+        firing: true,
+    }
+}
+
+/// The evaluated window: the last `window_minutes` full minutes before `now`.
+fn window_bounds(now: DateTime<Utc>, window_minutes: u32) -> (i64, i64) {
+    let end = now.timestamp() - now.timestamp().rem_euclid(60);
+    (end - window_minutes as i64 * 60, end)
+}
+
+pub async fn run_metric_alerts_scheduler(
+    pool: PgPool,
+    clickhouse: clickhouse::Client,
+    queue: Arc<MessageQueue>,
+    cache: Arc<Cache>,
+) {
+    log::debug!("[Metric Alerts] Starting metric alerts scheduler");
+    run_locked_on_interval(
+        "Metric Alerts",
+        METRIC_ALERTS_SCHEDULER_LOCK_CACHE_KEY,
+        cache,
+        || evaluate_metric_alerts(&pool, &clickhouse, queue.clone()),
+    )
+    .await
+}
+
+/// Evaluate every metric-threshold alert once. Failures are logged per alert
+/// so one bad rule or query doesn't hold back the rest.
+async fn evaluate_metric_alerts(
+    pool: &PgPool,
+    clickhouse: &clickhouse::Client,
+    queue: Arc<MessageQueue>,
+) -> anyhow::Result<()> {
+    let alerts = get_metric_alerts(pool).await?;
+    if alerts.is_empty() {
+        return Ok(());
+    }
+    log::debug!("[Metric Alerts] Evaluating {} alert(s)", alerts.len());
+
+    for alert in alerts {
+        if let Err(e) = evaluate_alert(pool, clickhouse, queue.clone(), &alert).await {
+            log::error!(
+                "[Metric Alerts] Failed to evaluate alert {}: {:?}",
+                alert.id,
+                e
+            );
+        }
+    }
+
+    Ok(())
+}
+
+async fn evaluate_alert(
+    pool: &PgPool,
+    clickhouse: &clickhouse::Client,
+    queue: Arc<MessageQueue>,
+    alert: &MetricAlert,
+) -> anyhow::Result<()> {
+    let rule = MetricThresholdRule::from_metadata(&alert.metadata)
+        .map_err(|e| anyhow::anyhow!("Invalid metric threshold rule: {}", e))?;
+
+    let now = Utc::now();
+    let (start_ts, end_ts) = window_bounds(now, rule.window_minutes);
+    let filter = SpanMetricsFilter {
+        model: rule.filters.model.as_deref(),
+        provider: rule.filters.provider.as_deref(),
+        path: rule.filters.path.as_deref(),
+        span_type: rule.filters.span_type.clone().map(Into::into),
+    };
+    let window =
+        get_span_metrics_window(clickhouse, &alert.project_id, &filter, start_ts, end_ts).await?;
+
+    let value = rule.evaluate(&window);
+    let breached = value.map(|v| rule.operator.is_breached(v, rule.threshold));
+    let state = AlertState {
+        firing: alert.firing.unwrap_or(false),
+        last_notified_at: alert.last_notified_at,
+        snoozed_until: alert.snoozed_until,
+    };
+    let decision = decide(breached, &state, rule.renotify_minutes, now);
+
+    let mut notified_at = None;
+    if let (true, Some(value)) = (decision.notify, value) {
+        let to_rfc3339 = |ts: i64| {
+            DateTime::from_timestamp(ts, 0)
+                .unwrap_or(now)
+                .to_rfc3339_opts(SecondsFormat::Secs, true)
+        };
+        let message = NotificationMessage {
+            definition_type: NotificationDefinitionType::Alert,
+            definition_id: alert.id,
+            workspace_id: alert.workspace_id,
+            project_id: Some(alert.project_id),
+            notifications: vec![NotificationKind::MetricThreshold {
+                project_id: alert.project_id,
+                alert_name: alert.name.clone(),
+                rule: rule.clone(),
+                value,
+                span_count: window.span_count,
+                window_start: to_rfc3339(start_ts),
+                window_end: to_rfc3339(end_ts),
+            }],
+        };
+        // Leave the alert un-notified on failure so the next tick retries.
+        super::push_to_notification_queue(message, queue).await?;
+        notified_at = Some(now);
+        log::info!(
+            "[Metric Alerts] Alert {} breached: {} is {}",
+            alert.id,
+            rule.condition(),
+            rule.metric.format_value(value)
+        );
+    }
+
+    upsert_metric_alert_state(
+        pool,
+        alert.id,
+        alert.project_id,
+        decision.firing,
+        value,
+        now,
+        notified_at,
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    use super::*;
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_700_000_000, 0).unwrap() + chrono::Duration::minutes(minutes)
+    }
+
+    fn window(span_count: u64, error_count: u64) -> SpanMetricsWindow {
+        SpanMetricsWindow {
+            span_count,
+            error_count,
+            total_tokens: 1_200,
+            total_cost: 51.5,
+            duration_sum: 20.0,
+            duration_quantiles: vec![1.0, 4.0, 9.5, 12.0],
+        }
+    }
+
+    #[test]
+    fn rule_parses_from_alert_metadata() {
+        let rule = MetricThresholdRule::from_metadata(&json!({
+            "metric": "latency_p95",
+            "filters": { "model": "gpt-4o", "spanType": "LLM" },
+            "windowMinutes": 15,
+            "operator": "gt",
+            "threshold": 8,
+        }))
+        .unwrap();
+
+        assert_eq!(rule.metric, AlertMetric::LatencyP95);
+        assert_eq!(rule.filters.span_type, Some(SpanType::LLM));
+        assert_eq!(rule.condition(), "p95 latency > 8.00s over 15 min");
+        assert_eq!(rule.filters.describe(), "model = gpt-4o, span type = LLM");
+
+        assert!(
+            MetricThresholdRule::from_metadata(&json!({
+                "metric": "cost",
+                "windowMinutes": 0,
+                "operator": "gt",
+                "threshold": 50,
+            }))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn metric_values_come_from_the_window() {
+        let w = window(4, 1);
+        assert_eq!(AlertMetric::LatencyP95.value(&w), Some(9.5));
+        assert_eq!(AlertMetric::LatencyAvg.value(&w), Some(5.0));
+        assert_eq!(AlertMetric::ErrorRate.value(&w), Some(25.0));
+        assert_eq!(AlertMetric::Cost.value(&w), Some(51.5));
+        assert_eq!(AlertMetric::Tokens.value(&w), Some(1_200.0));
+
+        let empty = SpanMetricsWindow {
+            duration_quantiles: vec![f64::NAN; 4],
+            ..Default::default()
+        };
+        assert_eq!(AlertMetric::LatencyP95.value(&empty), None);
+        assert_eq!(AlertMetric::ErrorRate.value(&empty), None);
+        assert_eq!(AlertMetric::SpanCount.value(&empty), Some(0.0));
+    }
+
+    #[test]
+    fn min_span_count_skips_thin_windows() {
+        let rule = MetricThresholdRule {
+            metric: AlertMetric::ErrorRate,
+            filters: MetricFilters::default(),
+            window_minutes: 60,
+            operator: ThresholdOperator::Gt,
+            threshold: 5.0,
+            renotify_minutes: None,
+            min_span_count: Some(10),
+        };
+        assert_eq!(rule.evaluate(&window(2, 2)), None);
+        assert_eq!(rule.evaluate(&window(10, 1)), Some(10.0));
+    }
+
+    #[test]
+    fn breach_notifies_once_until_it_recovers() {
+        let firing = decide(Some(true), &AlertState::default(), None, at(0));
+        assert_eq!(
+            firing,
+            Decision {
+                notify: true,
+                firing: true
+            }
+        );
+
+        let state = AlertState {
+            firing: true,
+            last_notified_at: Some(at(0)),
+            snoozed_until: None,
+        };
+        assert!(!decide(Some(true), &state, None, at(600)).notify);
+
+        let recovered = decide(Some(false), &state, None, at(610));
+        assert!(!recovered.notify && !recovered.firing);
+        assert!(decide(Some(true), &AlertState::default(), None, at(620)).notify);
+    }
+
+    #[test]
+    fn renotify_waits_for_the_interval() {
+        let state = AlertState {
+            firing: true,
+            last_notified_at: Some(at(0)),
+            snoozed_until: None,
+        };
+        assert!(!decide(Some(true), &state, Some(60), at(50)).notify);
+        assert!(decide(Some(true), &state, Some(60), at(60)).notify);
+    }
+
+    #[test]
+    fn snooze_suppresses_without_resetting_state() {
+        let state = AlertState {
+            firing: false,
+            last_notified_at: None,
+            snoozed_until: Some(at(30)),
+        };
+        assert_eq!(
+            decide(Some(true), &state, None, at(10)),
+            Decision {
+                notify: false,
+                firing: false
+            }
+        );
+        // Still breached once the snooze ends: notify then.
+        assert!(decide(Some(true), &state, None, at(31)).notify);
+    }
+
+    #[test]
+    fn missing_data_keeps_the_previous_state() {
+        let state = AlertState {
+            firing: true,
+            last_notified_at: Some(at(0)),
+            snoozed_until: None,
+        };
+        let no_data = decide(None, &state, Some(1), at(600));
+        assert_eq!(
+            no_data,
+            Decision {
+                notify: false,
+                firing: true
+            }
+        );
+        // Data returns still breached: no fresh "firing" notification.
+        assert!(!decide(Some(true), &state, None, at(660)).notify);
+
+        let idle = decide(None, &AlertState::default(), None, at(0));
+        assert!(!idle.notify && !idle.firing);
+    }
+
+    #[test]
+    fn window_ends_on_the_last_full_minute() {
+        let now = Utc.timestamp_opt(1_700_000_099, 0).unwrap();
+        assert_eq!(window_bounds(now, 15), (1_700_000_040 - 900, 1_700_000_040));
+    }
+}
@@ -11,15 +11,22 @@ use crate::ch::service::ClickhouseService;
 use crate::db::DB;
 use crate::mq::utils::mq_max_payload;
 use crate::mq::{MessageQueue, MessageQueueTrait};
+use crate::reports::custom::{RenderMode, ReportTable};
 use crate::reports::email_template::NoteworthyEvent;
 use crate::worker::{HandlerError, MessageHandler};
 
+mod cards;
 pub mod delivery;
+mod discord;
 mod email;
+pub mod metric_alerts;
 pub mod slack;
+mod teams;
 mod utils;
+pub mod webhook;
 
 use delivery::{DeliveryTarget, NotificationDeliveryMessage, push_to_deliveries_queue};
+use metric_alerts::MetricThresholdRule;
 
 // ── Notifications queue (producers → notifications_consumer) ──
 
@@ -34,6 +41,12 @@ pub const NOTIFICATIONS_ROUTING_KEY: &str = "notifications";
 pub enum TargetType {
     Email,
     Slack,
+    /// Signed JSON POST to a user-supplied URL.
+    Webhook,
+    /// Microsoft Teams Workflows webhook.
+    Teams,
+    /// Discord channel webhook.
+    Discord,
 }
 
 impl std::str::FromStr for TargetType {
@@ -43,6 +56,9 @@ impl std::str::FromStr for TargetType {
         match s {
             "EMAIL" => Ok(Self::Email),
             "SLACK" => Ok(Self::Slack),
+            "WEBHOOK" => Ok(Self::Webhook),
+            "TEAMS" => Ok(Self::Teams),
+            "DISCORD" => Ok(Self::Discord),
             other => Err(format!("unknown target type: {other}")),
         }
     }
@@ -53,6 +69,9 @@ impl std::fmt::Display for TargetType {
         match self {
             Self::Email => f.write_str("EMAIL"),
             Self::Slack => f.write_str("SLACK"),
+            Self::Webhook => f.write_str("WEBHOOK"),
+            Self::Teams => f.write_str("TEAMS"),
+            Self::Discord => f.write_str("DISCORD"),
         }
     }
 }
@@ -80,6 +99,8 @@ impl std::fmt::Display for NotificationDefinitionType {
 pub enum AlertType {
     SignalEvent,
     NewCluster,
+    /// Span metric crossing a threshold, evaluated on a schedule. `source_id` is the project.
+    MetricThreshold,
 }
 
 impl AlertType {
@@ -87,6 +108,7 @@ impl AlertType {
         match self {
             Self::SignalEvent => "SIGNAL_EVENT",
             Self::NewCluster => "NEW_CLUSTER",
+            Self::MetricThreshold => "METRIC_THRESHOLD",
         }
     }
 }
@@ -98,6 +120,7 @@ impl std::str::FromStr for AlertType {
         match s {
             "SIGNAL_EVENT" => Ok(Self::SignalEvent),
             "NEW_CLUSTER" => Ok(Self::NewCluster),
+            "METRIC_THRESHOLD" => Ok(Self::MetricThreshold),
             other => Err(format!("unknown alert type: {other}")),
         }
     }
@@ -139,6 +162,17 @@ pub enum NotificationKind {
         num_child_clusters: usize,
         alert_name: String,
     },
+    MetricThreshold {
+        project_id: Uuid,
+        alert_name: String,
+        rule: MetricThresholdRule,
+        /// Observed value, in the unit of `rule.metric`
+        value: f64,
+        span_count: u64,
+        /// RFC 3339 bounds of the evaluated window
+        window_start: String,
+        window_end: String,
+    },
     SignalsReport {
         workspace_name: String,
         project_id: Uuid,
@@ -151,6 +185,20 @@ pub enum NotificationKind {
         ai_summary: String,
         noteworthy_events: Vec<NoteworthyEvent>,
     },
+    CustomReport {
+        workspace_name: String,
+        project_id: Uuid,
+        project_name: String,
+        report_name: String,
+        period_start: String,
+        period_end: String,
+        mode: RenderMode,
+        table: ReportTable,
+        /// Set instead of a table when the saved query failed, so recipients
+        /// learn their report is broken rather than getting nothing.
+        #[serde(default)]
+        error: Option<String>,
+    },
     UsageWarning {
         workspace_name: String,
         usage_label: String,
@@ -298,7 +346,9 @@ impl MessageHandler for NotificationHandler {
             let project_id = match kind {
                 NotificationKind::EventIdentification { project_id, .. } => *project_id,
                 NotificationKind::NewCluster { project_id, .. } => *project_id,
+                NotificationKind::MetricThreshold { project_id, .. } => *project_id,
                 NotificationKind::SignalsReport { project_id, .. } => *project_id,
+                NotificationKind::CustomReport { project_id, .. } => *project_id,
                 NotificationKind::UsageWarning { .. } => Uuid::nil(),
             };
 
@@ -359,6 +409,8 @@ impl MessageHandler for NotificationHandler {
                 target,
                 notification_ids: notification_ids.clone(),
                 notifications: message.notifications.clone(),
+                attempt: 0,
+                delivery_id: None,
             };
 
             let serialized_size = match serde_json::to_vec(&delivery) {
@@ -442,6 +494,9 @@ impl NotificationHandler {
                             email: t.email,
                             channel_id: t.channel_id,
                             integration_id: t.integration_id,
+                            url: t.url,
+                            signing_secret: t.signing_secret,
+                            signing_secret_nonce: t.signing_secret_nonce,
                         })
                     })
                     .collect())
@@ -465,6 +520,9 @@ impl NotificationHandler {
                             email: t.email,
                             channel_id: t.channel_id,
                             integration_id: t.integration_id,
+                            url: t.url,
+                            signing_secret: t.signing_secret,
+                            signing_secret_nonce: t.signing_secret_nonce,
                         })
                     })
                     .collect())
@@ -486,6 +544,9 @@ impl NotificationHandler {
                         email: Some(email),
                         channel_id: None,
                         integration_id: None,
+                        url: None,
+                        signing_secret: None,
+                        signing_secret_nonce: None,
                     })
                     .collect())
             }
@@ -12,9 +12,11 @@ use sodiumoxide::{
 use uuid::Uuid;
 
 use super::NotificationKind;
+use super::metric_alerts::MetricThresholdRule;
 use super::utils::{
     build_report_data_from_batch, frontend_url_slack, inject_utm_into_links, with_utm,
 };
+use crate::reports::custom::ReportTable;
 use crate::reports::email_template::ReportData;
 
 const SLACK_API_BASE: &str = "https://slack.com/api";
@@ -111,11 +113,37 @@ pub fn format_message_blocks_batch(
             *num_child_clusters,
             alert_name,
         ),
+        NotificationKind::MetricThreshold {
+            project_id,
+            alert_name,
+            rule,
+            value,
+            span_count,
+            ..
+        } => format_metric_threshold_blocks(project_id, alert_name, rule, *value, *span_count),
         NotificationKind::SignalsReport { .. } => {
             let (title, report_data) = build_report_data_from_batch(notifications, workspace_id)
                 .expect("SignalsReport batch must contain at least one report");
             format_report_blocks(&title, &report_data)
         }
+        NotificationKind::CustomReport {
+            project_id,
+            project_name,
+            report_name,
+            period_start,
+            period_end,
+            table,
+            error,
+            ..
+        } => format_custom_report_blocks(
+            project_id,
+            project_name,
+            report_name,
+            period_start,
+            period_end,
+            table,
+            error.as_deref(),
+        ),
         NotificationKind::UsageWarning {
             workspace_name,
             usage_label,
@@ -374,6 +402,164 @@ fn format_new_cluster_blocks(
     ])
 }
 
+// Format Slack message blocks for a metric-threshold notification.
+fn format_metric_threshold_blocks(
+    project_id: &Uuid,
+    alert_name: &str,
+    rule: &MetricThresholdRule,
+    value: f64,
+    span_count: u64,
+) -> serde_json::Value {
+    let base = frontend_url_slack();
+    let traces_link = with_utm(
+        &format!("{}/project/{}/traces", base, project_id),
+        "slack",
+        "metric_alert",
+        "view_traces",
+    );
+    let alert_link = with_utm(
+        &format!("{}/project/{}/settings?tab=alerts", base, project_id),
+        "slack",
+        "metric_alert",
+        "manage_alert",
+    );
+
+    let mut details_text = format!(
+        "*Value:* {}\n*Condition:* {}",
+        rule.metric.format_value(value),
+        rule.condition()
+    );
+    let filters = rule.filters.describe();
+    if !filters.is_empty() {
+        details_text.push_str(&format!("\n*Filters:* {}", filters));
+    }
+    details_text.push_str(&format!("\n*Spans:* {}", span_count));
+
+    json!([
+        {
+            "type": "section",
+            "text": {
+                "type": "mrkdwn",
+                "text": format!(":warning: `{}`: Threshold Breached", alert_name)
+            }
+        },
+        {
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": details_text }
+        },
+        {
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "text": {
+                        "type": "plain_text",
+                        "text": "View Traces",
+                        "emoji": true
+                    },
+                    "url": traces_link,
+                    "action_id": "view_traces"
+                }
+            ]
+        },
+        {
+            "type": "context",
+            "elements": [
+                {
+                    "type": "mrkdwn",
+                    "text": format!("Alert: <{}|{}>", alert_link, alert_name)
+                }
+            ]
+        },
+        {"type": "divider"}
+    ])
+}
+
+/// Wraps a rendered table in a code block, dropping whole rows so the block
+/// fits in one section.
+fn table_code_block(table: &ReportTable) -> String {
+    // Room for the fences and the trailing "..." line.
+    const BUDGET: usize = 3000 - 16;
+
+    let text = table.to_text();
+    let mut kept = String::new();
+    for line in text.lines() {
+        if kept.chars().count() + line.chars().count() + 1 > BUDGET {
+            kept.push_str("...\n");
+            break;
+        }
+        kept.push_str(line);
+        kept.push('\n');
+    }
+    format!("```{}```", kept)
+}
+
+// Format Slack message blocks for a custom query report.
+fn format_custom_report_blocks(
+    project_id: &Uuid,
+    project_name: &str,
+    report_name: &str,
+    period_start: &str,
+    period_end: &str,
+    table: &ReportTable,
+    error: Option<&str>,
+) -> serde_json::Value {
+    let sql_link = with_utm(
+        &format!("{}/project/{}/sql", frontend_url_slack(), project_id),
+        "slack",
+        "custom_report",
+        "open_sql_editor",
+    );
+
+    let body = if let Some(error) = error {
+        truncate_to_slack_section_limit(&format!(":warning: The report query failed: {}", error))
+    } else if table.rows.is_empty() {
+        "No rows for this period.".to_string()
+    } else {
+        table_code_block(table)
+    };
+
+    let mut footer = format!("{} · {} – {}", project_name, period_start, period_end);
+    if error.is_none() && table.total_rows > table.rows.len() {
+        footer.push_str(&format!(
+            " · Showing {} of {} rows",
+            table.rows.len(),
+            table.total_rows
+        ));
+    }
+
+    json!([
+        {
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": format!(":bar_chart: *{}*", report_name) }
+        },
+        {
+            "type": "section",
+            "text": { "type": "mrkdwn", "text": body }
+        },
+        {
+            "type": "actions",
+            "elements": [
+                {
+                    "type": "button",
+                    "text": {
+                        "type": "plain_text",
+                        "text": "Open SQL Editor",
+                        "emoji": true
+                    },
+                    "url": sql_link,
+                    "action_id": "open_sql_editor"
+                }
+            ]
+        },
+        {
+            "type": "context",
+            "elements": [{ "type": "mrkdwn", "text": footer }]
+        },
+        {"type": "divider"}
+    ])
+}
+
 /// Format Slack message blocks for a signals report notification.
 fn format_report_blocks(title: &str, report: &ReportData) -> serde_json::Value {
     let project_count = report.projects.len();
@@ -0,0 +1,86 @@
+//! Microsoft Teams formatting. Targets are Teams Workflows ("Post to a channel
+//! when a webhook request is received") URLs, which accept a message with an
+//! Adaptive Card attachment.
+
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use super::NotificationKind;
+use super::cards::{NotificationCard, build_cards, truncate_chars};
+
+/// Teams rejects payloads above ~28 KB; keep each text block well below that.
+const MAX_TEXT_CHARS: usize = 4_000;
+
+/// Format a Teams message combining all notifications in the batch.
+pub fn format_message(notifications: &[NotificationKind], workspace_id: Uuid) -> Value {
+    let cards = build_cards(notifications, workspace_id, "teams");
+
+    let body: Vec<Value> = cards
+        .iter()
+        .enumerate()
+        .flat_map(|(i, card)| card_elements(card, i > 0))
+        .collect();
+
+    json!({
+        "type": "message",
+        "attachments": [{
+            "contentType": "application/vnd.microsoft.card.adaptive",
+            "contentUrl": null,
+            "content": {
+                "$schema": "http://adaptivecards.io/schemas/adaptive-card.json",
+                "type": "AdaptiveCard",
+                "version": "1.4",
+                "body": body,
+                "msteams": { "width": "Full" }
+            }
+        }]
+    })
+}
+
+fn card_elements(card: &NotificationCard, separator: bool) -> Vec<Value> {
+    let color = match card.severity {
+        Some(2) => "Attention",
+        Some(1) => "Warning",
+        _ => "Default",
+    };
+    let spacing = if separator { "Large" } else { "Default" };
+
+    let mut elements = vec![json!({
+        "type": "TextBlock",
+        "text": card.title,
+        "weight": "Bolder",
+        "size": "Medium",
+        "color": color,
+        "wrap": true,
+        "separator": separator,
+        "spacing": spacing,
+    })];
+
+    if let Some(body) = &card.body {
+        elements.push(json!({
+            "type": "TextBlock",
+            "text": truncate_chars(body, MAX_TEXT_CHARS),
+            "wrap": true,
+        }));
+    }
+
+    if !card.facts.is_empty() {
+        elements.push(json!({
+            "type": "FactSet",
+            "facts": card
+                .facts
+                .iter()
+                .map(|(title, value)| json!({ "title": title, "value": value }))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    if let Some((label, url)) = &card.action {
+        elements.push(json!({
+            "type": "ActionSet",
+            "actions": [{ "type": "Action.OpenUrl", "title": label, "url": url }],
+        }));
+    }
+
+    elements
+}
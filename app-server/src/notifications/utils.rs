@@ -27,6 +27,12 @@ pub(super) fn frontend_url_email() -> String {
     frontend_url_with_default("https://lmnr.ai")
 }
 
+/// Public-facing base URL for links in webhook, Teams and Discord payloads.
+/// Defaults to `https://lmnr.ai`, like email.
+pub(super) fn frontend_url_webhook() -> String {
+    frontend_url_with_default("https://lmnr.ai")
+}
+
 /// Append UTM tracking parameters to a notification URL.
 ///
 /// Used so PostHog auto-captures `$utm_source`, `$utm_medium`, `$utm_campaign`,
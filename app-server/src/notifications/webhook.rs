@@ -0,0 +1,904 @@
+//! Generic webhook targets and the delivery loop shared with Teams and Discord.
+//!
+//! Generic webhooks receive a JSON document describing the notifications (see
+//! [`format_payload`]). When the target has a signing secret, every request
+//! carries `X-Laminar-Signature: sha256=<hex>`, an HMAC-SHA256 over
+//! `"{X-Laminar-Timestamp}.{raw body}"`, so receivers can verify the sender and
+//! reject replays. `X-Laminar-Delivery` stays the same across retries of one
+//! delivery and can be used for idempotency.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde_json::{Value, json};
+use sodiumoxide::crypto::auth::hmacsha256;
+use url::{Host, Url};
+use uuid::Uuid;
+
+use super::NotificationKind;
+use super::cards::{cluster_url, severity_label, signal_url, trace_url, traces_url};
+use super::delivery::NotificationDeliveryMessage;
+use super::utils::{inject_utm_into_links, with_utm};
+use crate::env;
+
+pub const SIGNATURE_HEADER: &str = "X-Laminar-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Laminar-Timestamp";
+pub const DELIVERY_ID_HEADER: &str = "X-Laminar-Delivery";
+pub const EVENT_HEADER: &str = "X-Laminar-Event";
+
+/// Stable event name of a notification, used for the payload `event` field and
+/// the `X-Laminar-Event` header.
+pub fn event_name(kind: &NotificationKind) -> &'static str {
+    match kind {
+        NotificationKind::EventIdentification { .. } => "signal_event",
+        NotificationKind::NewCluster { .. } => "new_cluster",
+        NotificationKind::MetricThreshold { .. } => "metric_threshold",
+        NotificationKind::SignalsReport { .. } => "signals_report",
+        NotificationKind::CustomReport { .. } => "custom_report",
+        NotificationKind::UsageWarning { .. } => "usage_warning",
+    }
+}
+
+/// Build the JSON body for a generic webhook target.
+///
+/// ```json
+/// {
+///   "id": "<delivery id>",
+///   "event": "signal_event",
+///   "workspace_id": "...",
+///   "project_id": "..." | null,
+///   "sent_at": "2026-01-01T00:00:00.000Z",
+///   "notifications": [{ "id": "<notification id>", "type": "signal_event", ... }]
+/// }
+/// ```
+pub fn format_payload(
+    message: &NotificationDeliveryMessage,
+    delivery_id: Uuid,
+    sent_at: DateTime<Utc>,
+) -> Value {
+    let notifications: Vec<Value> = message
+        .notification_ids
+        .iter()
+        .zip(message.notifications.iter())
+        .map(|(notification_id, kind)| {
+            let mut value = format_notification(kind);
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("id".to_string(), json!(notification_id));
+                obj.insert("type".to_string(), json!(event_name(kind)));
+            }
+            value
+        })
+        .collect();
+
+    json!({
+        "id": delivery_id,
+        "event": message.notifications.first().map(event_name),
+        "workspace_id": message.workspace_id,
+        "project_id": message.project_id,
+        "sent_at": sent_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+        "notifications": notifications,
+    })
+}
+
+fn format_notification(kind: &NotificationKind) -> Value {
+    match kind {
+        NotificationKind::EventIdentification {
+            project_id,
+            project_name,
+            signal_id,
+            trace_id,
+            event_id,
+            event_name,
+            severity,
+            extracted_information,
+            alert_name,
+        } => {
+            let tag_links = |s: &str| {
+                json!(inject_utm_into_links(
+                    s,
+                    "webhook",
+                    "signal_alert",
+                    "event_description"
+                ))
+            };
+            let extracted_information = extracted_information.as_ref().map(|info| match info {
+                Value::String(s) => tag_links(s),
+                Value::Object(obj) => Value::Object(
+                    obj.iter()
+                        .map(|(key, value)| match value {
+                            Value::String(s) => (key.clone(), tag_links(s)),
+                            other => (key.clone(), other.clone()),
+                        })
+                        .collect(),
+                ),
+                other => other.clone(),
+            });
+            json!({
+                "project_id": project_id,
+                "project_name": project_name,
+                "signal_id": signal_id,
+                "signal_name": event_name,
+                "trace_id": trace_id,
+                "event_id": event_id,
+                "severity": severity,
+                "severity_label": severity_label(*severity).to_lowercase(),
+                "alert_name": alert_name,
+                "extracted_information": extracted_information,
+                "url": with_utm(&trace_url(project_id, trace_id), "webhook", "signal_alert", "view_trace"),
+                "signal_url": with_utm(&signal_url(project_id, signal_id), "webhook", "signal_alert", "view_signal"),
+            })
+        }
+        NotificationKind::NewCluster {
+            project_id,
+            signal_id,
+            signal_name,
+            cluster_id,
+            cluster_name,
+            num_signal_events,
+            num_child_clusters,
+            alert_name,
+        } => json!({
+            "project_id": project_id,
+            "signal_id": signal_id,
+            "signal_name": signal_name,
+            "cluster_id": cluster_id,
+            "cluster_name": cluster_name,
+            "num_signal_events": num_signal_events,
+            "num_child_clusters": num_child_clusters,
+            "alert_name": alert_name,
+            "url": with_utm(
+                &cluster_url(project_id, signal_id, cluster_id),
+                "webhook",
+                "new_cluster_alert",
+                "view_cluster",
+            ),
+        }),
+        NotificationKind::MetricThreshold {
+            project_id,
+            alert_name,
+            rule,
+            value,
+            span_count,
+            window_start,
+            window_end,
+        } => json!({
+            "project_id": project_id,
+            "alert_name": alert_name,
+            "metric": rule.metric,
+            "operator": rule.operator,
+            "threshold": rule.threshold,
+            "value": value,
+            "window_minutes": rule.window_minutes,
+            "window_start": window_start,
+            "window_end": window_end,
+            "span_count": span_count,
+            "filters": {
+                "model": rule.filters.model,
+                "provider": rule.filters.provider,
+                "path": rule.filters.path,
+                "span_type": rule.filters.span_type,
+            },
+            "condition": rule.condition(),
+            "url": with_utm(&traces_url(project_id), "webhook", "metric_alert", "view_traces"),
+        }),
+        NotificationKind::SignalsReport {
+            workspace_name,
+            project_id,
+            project_name,
+            title,
+            period_label,
+            period_start,
+            period_end,
+            signal_event_counts,
+            ai_summary,
+            noteworthy_events,
+        } => json!({
+            "workspace_name": workspace_name,
+            "project_id": project_id,
+            "project_name": project_name,
+            "title": title,
+            "period_label": period_label,
+            "period_start": period_start,
+            "period_end": period_end,
+            "signal_event_counts": signal_event_counts,
+            "ai_summary": ai_summary,
+            "noteworthy_events": noteworthy_events
+                .iter()
+                .map(|event| json!({
+                    "signal_name": event.signal_name,
+                    "summary": event.summary,
+                    "timestamp": event.timestamp,
+                    "trace_id": event.trace_id,
+                    "url": with_utm(
+                        &trace_url(project_id, &event.trace_id),
+                        "webhook",
+                        "signals_report",
+                        "view_trace",
+                    ),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        NotificationKind::CustomReport {
+            workspace_name,
+            project_id,
+            project_name,
+            report_name,
+            period_start,
+            period_end,
+            mode,
+            table,
+            error,
+        } => json!({
+            "workspace_name": workspace_name,
+            "project_id": project_id,
+            "project_name": project_name,
+            "report_name": report_name,
+            "period_start": period_start,
+            "period_end": period_end,
+            "mode": mode,
+            "columns": table.columns,
+            "rows": table.rows,
+            "total_rows": table.total_rows,
+            "error": error,
+        }),
+        NotificationKind::UsageWarning {
+            workspace_name,
+            usage_label,
+            formatted_limit,
+            usage_item,
+            at_tier_included_allowance,
+            tier_display_name,
+            overage_billable,
+        } => json!({
+            "workspace_name": workspace_name,
+            "usage_label": usage_label,
+            "usage_item": usage_item,
+            "formatted_limit": formatted_limit,
+            "at_tier_included_allowance": at_tier_included_allowance,
+            "tier_display_name": tier_display_name,
+            "overage_billable": overage_billable,
+        }),
+    }
+}
+
+/// Hex HMAC-SHA256 of `payload` under `secret`.
+fn hmac_sha256_hex(secret: &[u8], payload: &[u8]) -> String {
+    let mut state = hmacsha256::State::init(secret);
+    state.update(payload);
+    hex::encode(state.finalize().as_ref())
+}
+
+/// Value of the `X-Laminar-Signature` header for a request sent at `timestamp`
+/// (unix seconds, also sent as `X-Laminar-Timestamp`).
+pub fn sign(secret: &str, timestamp: i64, body: &[u8]) -> String {
+    let mut signed = format!("{}.", timestamp).into_bytes();
+    signed.extend_from_slice(body);
+    format!("sha256={}", hmac_sha256_hex(secret.as_bytes(), &signed))
+}
+
+/// Retry schedule for webhook-style deliveries.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub timeout: Duration,
+    /// Skip the public-address check, for self-hosted instances that deliver
+    /// to services on their own network.
+    pub allow_private_destinations: bool,
+}
+
+impl RetryPolicy {
+    pub fn from_env() -> Self {
+        Self {
+            max_attempts: env::notifications::WEBHOOK_MAX_ATTEMPTS.get().max(1),
+            base_delay: Duration::from_millis(
+                env::notifications::WEBHOOK_RETRY_BASE_DELAY_MS.get(),
+            ),
+            max_delay: Duration::from_millis(env::notifications::WEBHOOK_RETRY_MAX_DELAY_MS.get()),
+            timeout: Duration::from_secs(env::notifications::WEBHOOK_TIMEOUT_SECS.get()),
+            allow_private_destinations: env::notifications::WEBHOOK_ALLOW_PRIVATE_DESTINATIONS
+                .get(),
+        }
+    }
+
+    /// Delay before retry number `retry` (0-based): doubles from `base_delay`,
+    /// capped at `max_delay`. A `Retry-After` from the receiver wins when it is
+    /// longer, up to the same cap.
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        let exp = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_delay);
+        retry_after.map_or(exp, |after| after.min(self.max_delay).max(exp))
+    }
+}
+
+/// A delivery that was given up on, either after exhausting its attempts or on
+/// a response that retrying cannot fix (a 4xx other than 408/429).
+#[derive(Debug, Clone)]
+pub struct DeliveryFailure {
+    pub attempts: u32,
+    pub error: String,
+}
+
+/// How a single attempt ended.
+enum AttemptError {
+    Retryable {
+        error: String,
+        retry_after: Option<Duration>,
+    },
+    Permanent(String),
+}
+
+/// How one webhook-style delivery attempt ended.
+#[derive(Debug)]
+pub enum DeliveryAttempt {
+    Delivered,
+    /// Failed, but worth another attempt after `delay`.
+    Retry {
+        error: String,
+        delay: Duration,
+    },
+    /// Given up on.
+    Failed(DeliveryFailure),
+}
+
+/// Make attempt number `attempt` (1-based) to POST `body` to `url`. Network
+/// errors, timeouts, 408, 429 and 5xx are retryable until `max_attempts`. The
+/// caller schedules the retry, so a dead endpoint holds a worker for one
+/// timeout per attempt instead of the whole schedule. When `secret` is set the
+/// request is signed with the current timestamp, so receivers can enforce a
+/// freshness window.
+#[allow(clippy::too_many_arguments)]
+pub async fn attempt_delivery(
+    client: &reqwest::Client,
+    policy: &RetryPolicy,
+    url: &str,
+    body: &Value,
+    secret: Option<&str>,
+    delivery_id: Uuid,
+    event: &str,
+    attempt: u32,
+) -> DeliveryAttempt {
+    let raw_body = match serde_json::to_vec(body) {
+        Ok(raw_body) => raw_body,
+        Err(e) => {
+            return DeliveryAttempt::Failed(DeliveryFailure {
+                attempts: attempt,
+                error: format!("Failed to serialize webhook body: {}", e),
+            });
+        }
+    };
+
+    let result = match check_destination(url, policy).await {
+        Ok(()) => post_once(client, policy, url, &raw_body, secret, delivery_id, event).await,
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => DeliveryAttempt::Delivered,
+        Err(AttemptError::Permanent(error)) => DeliveryAttempt::Failed(DeliveryFailure {
+            attempts: attempt,
+            error,
+        }),
+        Err(AttemptError::Retryable { error, .. }) if attempt >= policy.max_attempts => {
+            DeliveryAttempt::Failed(DeliveryFailure {
+                attempts: attempt,
+                error,
+            })
+        }
+        Err(AttemptError::Retryable { error, retry_after }) => DeliveryAttempt::Retry {
+            error,
+            delay: policy.delay(attempt.saturating_sub(1), retry_after),
+        },
+    }
+}
+
+/// Refuse destinations inside the app server's network. Webhook URLs are user
+/// supplied, so without this a target could be pointed at internal services or
+/// the cloud metadata endpoint. Hostnames are resolved here and rejected if any
+/// address is not public; [`http_client`] filters again at connect time, so a
+/// name that re-resolves to an internal address afterwards fails to connect.
+async fn check_destination(url: &str, policy: &RetryPolicy) -> Result<(), AttemptError> {
+    let parsed = Url::parse(url)
+        .map_err(|e| AttemptError::Permanent(format!("Invalid webhook URL: {}", e)))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(AttemptError::Permanent(format!(
+            "Unsupported webhook URL scheme: {}",
+            parsed.scheme()
+        )));
+    }
+    if policy.allow_private_destinations {
+        return Ok(());
+    }
+
+    let port = parsed.port_or_known_default().unwrap_or(443);
+    let addrs: Vec<IpAddr> = match parsed.host() {
+        Some(Host::Ipv4(ip)) => vec![IpAddr::V4(ip)],
+        Some(Host::Ipv6(ip)) => vec![IpAddr::V6(ip)],
+        // Resolution failures may be transient
+        Some(Host::Domain(domain)) => tokio::net::lookup_host((domain, port))
+            .await
+            .map_err(|e| AttemptError::Retryable {
+                error: format!("Failed to resolve webhook host {}: {}", domain, e),
+                retry_after: None,
+            })?
+            .map(|addr| addr.ip())
+            .collect(),
+        None => {
+            return Err(AttemptError::Permanent(
+                "Webhook URL has no host".to_string(),
+            ));
+        }
+    };
+
+    match addrs.into_iter().find(|ip| !is_public_ip(*ip)) {
+        Some(ip) => Err(AttemptError::Permanent(format!(
+            "Webhook destination {} is not a public address",
+            ip
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Public unicast only: loopback, private, link-local (including
+/// 169.254.169.254), shared CGNAT, unspecified, broadcast, multicast,
+/// documentation, benchmarking and reserved ranges are refused, as are IPv6
+/// unique-local and link-local addresses and IPv4 addresses embedded in IPv6.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, c, _] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_unspecified()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 192 && b == 0 && c == 0)
+                || (a == 198 && (b == 18 || b == 19))
+                || a >= 240)
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4() {
+                // IPv4-mapped and -compatible forms
+                return is_public_ip(IpAddr::V4(v4));
+            }
+            let segments = ip.segments();
+            if segments[0] == 0x64 && segments[1] == 0xff9b {
+                // NAT64 reaches the embedded IPv4 address
+                let [.., hi, lo] = segments;
+                return is_public_ip(IpAddr::V4(Ipv4Addr::from(((hi as u32) << 16) | lo as u32)));
+            }
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || (segments[0] & 0xfe00) == 0xfc00
+                || (segments[0] & 0xffc0) == 0xfe80
+                || (segments[0] == 0x2001 && segments[1] == 0x0db8))
+        }
+    }
+}
+
+/// Resolver for webhook requests that drops non-public addresses.
+struct PublicOnlyResolver;
+
+impl Resolve for PublicOnlyResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((name.as_str(), 0))
+                .await?
+                .filter(|addr| is_public_ip(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} has no public address", name.as_str()).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// HTTP client for webhook-style targets. Redirects are not followed, since a
+/// redirect to an IP literal would bypass [`check_destination`], and hostnames
+/// only connect to public addresses unless the policy allows private ones.
+pub fn http_client(policy: &RetryPolicy) -> reqwest::Client {
+    let builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+    let builder = if policy.allow_private_destinations {
+        builder
+    } else {
+        builder.dns_resolver(Arc::new(PublicOnlyResolver))
+    };
+    builder
+        .build()
+        .expect("Failed to build webhook HTTP client")
+}
+
+async fn post_once(
+    client: &reqwest::Client,
+    policy: &RetryPolicy,
+    url: &str,
+    raw_body: &[u8],
+    secret: Option<&str>,
+    delivery_id: Uuid,
+    event: &str,
+) -> Result<(), AttemptError> {
+    let mut request = client
+        .post(url)
+        .timeout(policy.timeout)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(DELIVERY_ID_HEADER, delivery_id.to_string())
+        .header(EVENT_HEADER, event);
+
+    if let Some(secret) = secret {
+        let timestamp = Utc::now().timestamp();
+        request = request
+            .header(TIMESTAMP_HEADER, timestamp.to_string())
+            .header(SIGNATURE_HEADER, sign(secret, timestamp, raw_body));
+    }
+
+    let response =
+        request
+            .body(raw_body.to_vec())
+            .send()
+            .await
+            .map_err(|e| AttemptError::Retryable {
+                // Strip the URL: webhook URLs often embed credentials.
+                error: e.without_url().to_string(),
+                retry_after: None,
+            })?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(());
+    }
+
+    let retry_after = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs);
+    let text = response.text().await.unwrap_or_default();
+    let error = format!(
+        "HTTP {}: {}",
+        status.as_u16(),
+        super::cards::truncate_chars(&text, 500)
+    );
+
+    if status.is_server_error()
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+    {
+        Err(AttemptError::Retryable { error, retry_after })
+    } else {
+        Err(AttemptError::Permanent(error))
+    }
+}
+
+/// Scheme and host only, for logs. Teams and Discord webhook URLs carry their
+/// credentials in the path.
+pub fn redact_url(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => format!(
+            "{}://{}",
+            parsed.scheme(),
+            parsed.host_str().unwrap_or_default()
+        ),
+        Err(_) => "<invalid url>".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{header, header_exists, method};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    use super::*;
+    use crate::notifications::TargetType;
+    use crate::notifications::delivery::DeliveryTarget;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+            timeout: Duration::from_secs(5),
+            // Mock servers listen on loopback
+            allow_private_destinations: true,
+        }
+    }
+
+    fn cluster_message() -> NotificationDeliveryMessage {
+        NotificationDeliveryMessage {
+            workspace_id: Uuid::new_v4(),
+            project_id: Some(Uuid::new_v4()),
+            target: DeliveryTarget {
+                target_id: Uuid::new_v4(),
+                target_type: TargetType::Webhook,
+                email: None,
+                channel_id: None,
+                integration_id: None,
+                url: Some("https://example.com/hook".to_string()),
+                signing_secret: None,
+                signing_secret_nonce: None,
+            },
+            notification_ids: vec![Uuid::new_v4()],
+            notifications: vec![NotificationKind::NewCluster {
+                project_id: Uuid::new_v4(),
+                signal_id: Uuid::new_v4(),
+                signal_name: "checkout_failure".to_string(),
+                cluster_id: Uuid::new_v4(),
+                cluster_name: "Card declined".to_string(),
+                num_signal_events: 12,
+                num_child_clusters: 2,
+                alert_name: "Checkout".to_string(),
+            }],
+            attempt: 0,
+            delivery_id: None,
+        }
+    }
+
+    #[test]
+    fn hmac_matches_rfc_4231_test_vector() {
+        assert_eq!(
+            hmac_sha256_hex(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn signature_covers_timestamp_and_body() {
+        let signature = sign("secret", 1_700_000_000, b"{}");
+        assert!(signature.starts_with("sha256="));
+        assert_eq!(
+            signature,
+            format!("sha256={}", hmac_sha256_hex(b"secret", b"1700000000.{}"))
+        );
+        assert_ne!(signature, sign("secret", 1_700_000_001, b"{}"));
+    }
+
+    #[test]
+    fn payload_lists_notifications_with_ids_types_and_links() {
+        let message = cluster_message();
+        let delivery_id = Uuid::new_v4();
+        let payload = format_payload(&message, delivery_id, Utc::now());
+
+        assert_eq!(payload["id"], json!(delivery_id));
+        assert_eq!(payload["event"], "new_cluster");
+        let notification = &payload["notifications"][0];
+        assert_eq!(notification["id"], json!(message.notification_ids[0]));
+        assert_eq!(notification["type"], "new_cluster");
+        assert_eq!(notification["cluster_name"], "Card declined");
+        assert!(
+            notification["url"]
+                .as_str()
+                .unwrap()
+                .contains("utm_source=webhook")
+        );
+    }
+
+    #[tokio::test]
+    async fn signed_request_verifies_against_its_own_timestamp() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("content-type", "application/json"))
+            .and(header(EVENT_HEADER, "new_cluster"))
+            .and(header_exists(DELIVERY_ID_HEADER))
+            .and(|req: &Request| {
+                let (Some(timestamp), Some(signature)) = (
+                    req.headers.get(TIMESTAMP_HEADER),
+                    req.headers.get(SIGNATURE_HEADER),
+                ) else {
+                    return false;
+                };
+                let timestamp: i64 = timestamp.to_str().unwrap().parse().unwrap();
+                signature.to_str().unwrap() == sign("topsecret", timestamp, &req.body)
+            })
+            .respond_with(ResponseTemplate::new(204))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let body = format_payload(&cluster_message(), Uuid::new_v4(), Utc::now());
+        let outcome = attempt_delivery(
+            &reqwest::Client::new(),
+            &policy(3),
+            &server.uri(),
+            &body,
+            Some("topsecret"),
+            Uuid::new_v4(),
+            "new_cluster",
+            1,
+        )
+        .await;
+        assert!(matches!(outcome, DeliveryAttempt::Delivered));
+    }
+
+    async fn attempt(server: &MockServer, policy: &RetryPolicy, attempt: u32) -> DeliveryAttempt {
+        attempt_delivery(
+            &reqwest::Client::new(),
+            policy,
+            &server.uri(),
+            &json!({}),
+            None,
+            Uuid::new_v4(),
+            "signal_event",
+            attempt,
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_until_attempts_run_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503).set_body_string("unavailable"))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        match attempt(&server, &policy(3), 1).await {
+            DeliveryAttempt::Retry { error, delay } => {
+                assert_eq!(error, "HTTP 503: unavailable");
+                assert_eq!(delay, Duration::from_millis(1));
+            }
+            other => panic!("expected a retry, got {:?}", other),
+        }
+        match attempt(&server, &policy(3), 3).await {
+            DeliveryAttempt::Failed(failure) => {
+                assert_eq!(failure.attempts, 3);
+                assert_eq!(failure.error, "HTTP 503: unavailable");
+            }
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn client_errors_fail_without_retrying() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(410))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        match attempt(&server, &policy(5), 1).await {
+            DeliveryAttempt::Failed(failure) => assert_eq!(failure.attempts, 1),
+            other => panic!("expected a failure, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn rate_limited_delivery_is_retried_after_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "3"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy {
+            max_delay: Duration::from_secs(30),
+            ..policy(3)
+        };
+        match attempt(&server, &policy, 1).await {
+            DeliveryAttempt::Retry { delay, .. } => assert_eq!(delay, Duration::from_secs(3)),
+            other => panic!("expected a retry, got {:?}", other),
+        }
+        assert!(matches!(
+            attempt(&server, &policy, 2).await,
+            DeliveryAttempt::Delivered
+        ));
+    }
+
+    #[tokio::test]
+    async fn private_destinations_are_refused_without_a_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(0)
+            .mount(&server)
+            .await;
+
+        let policy = RetryPolicy {
+            allow_private_destinations: false,
+            ..policy(3)
+        };
+        for url in [
+            server.uri(),
+            "http://169.254.169.254/latest/meta-data/".to_string(),
+            "http://[::ffff:10.0.0.1]/hook".to_string(),
+            "http://localhost:9/hook".to_string(),
+            "file:///etc/passwd".to_string(),
+        ] {
+            let outcome = attempt_delivery(
+                &http_client(&policy),
+                &policy,
+                &url,
+                &json!({}),
+                None,
+                Uuid::new_v4(),
+                "signal_event",
+                1,
+            )
+            .await;
+            match outcome {
+                DeliveryAttempt::Failed(failure) => assert_eq!(failure.attempts, 1, "{}", url),
+                other => panic!("expected {} to be refused, got {:?}", url, other),
+            }
+        }
+    }
+
+    #[test]
+    fn only_public_addresses_are_allowed() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "224.0.0.1",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+            "64:ff9b::a9fe:a9fe",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "8.8.8.8",
+            "1.1.1.1",
+            "2606:4700:4700::1111",
+            "::ffff:8.8.8.8",
+        ] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[test]
+    fn retry_after_extends_but_never_exceeds_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            allow_private_destinations: false,
+        };
+        assert_eq!(policy.delay(0, None), Duration::from_secs(1));
+        assert_eq!(policy.delay(2, None), Duration::from_secs(4));
+        assert_eq!(policy.delay(10, None), Duration::from_secs(30));
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(7))),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            policy.delay(0, Some(Duration::from_secs(600))),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn redacted_url_keeps_only_scheme_and_host() {
+        assert_eq!(
+            redact_url("https://discord.com/api/webhooks/123/token"),
+            "https://discord.com"
+        );
+    }
+}
@@ -7,6 +7,11 @@ pub mod opentelemetry {
                     include!("opentelemetry.proto.collector.logs.v1.rs");
                 }
             }
+            pub mod metrics {
+                pub mod v1 {
+                    include!("opentelemetry.proto.collector.metrics.v1.rs");
+                }
+            }
             pub mod trace {
                 pub mod v1 {
                     include!("opentelemetry.proto.collector.trace.v1.rs");
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 use anyhow::{Result, anyhow};
+use futures_util::future::join_all;
 use tonic::transport::Channel;
 use tracing::Instrument;
 use uuid::Uuid;
@@ -9,15 +10,18 @@ use uuid::Uuid;
 use crate::cache::Cache;
 use crate::ch::deduped_content::CHDedupedContent;
 use crate::db::DB;
+use crate::db::projects::{PiiRedactionMode, PiiRedactionPolicy};
 use crate::db::spans::Span;
 use crate::utils::limits::get_workspace_info_for_project_id;
 use crate::utils::sanitize_string;
 
 #[allow(clippy::all)]
 pub mod pii_redactor;
+pub mod vault;
 
 use pii_redactor::{
-    RedactRequest, pii_redactor_service_client::PiiRedactorServiceClient,
+    Pseudonym, RedactRequest, RedactionMode, TextFindings,
+    pii_redactor_service_client::PiiRedactorServiceClient,
 };
 
 #[derive(Clone)]
@@ -33,26 +37,133 @@ impl PiiRedactorClient {
     }
 
     /// Send a list of stringified-JSON texts and get back the redacted ones,
-    /// preserving order. Empty input short-circuits without an RPC.
-    async fn redact(&self, texts: Vec<String>) -> Result<Vec<String>> {
+    /// preserving order, plus any pseudonyms the redactor returned. Empty
+    /// input short-circuits without an RPC.
+    async fn redact(
+        &self,
+        texts: Vec<String>,
+        options: RedactOptions,
+    ) -> Result<(Vec<String>, Vec<Pseudonym>)> {
         if texts.is_empty() {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), Vec::new()));
         }
         let mut client = self.client.as_ref().clone();
-        let req = RedactRequest {
-            texts,
-            placeholder_format: None,
-            skip_keys: Vec::new(),
+        let resp = client
+            .redact(tonic::Request::new(options.into_request(texts)))
+            .await
+            .map_err(|e| anyhow!("pii-redactor rpc: {}", e.message()))?
+            .into_inner();
+        Ok((resp.texts, resp.pseudonyms))
+    }
+
+    /// Dry run of [`Self::redact`] under `policy`: what would be redacted in
+    /// each text, without the matched values. One entry per text, in order.
+    pub async fn detect(
+        &self,
+        texts: Vec<String>,
+        policy: PiiRedactionPolicy,
+    ) -> Result<Vec<TextFindings>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+        let options = RedactOptions {
+            policy,
+            ..Default::default()
         };
+        let mut client = self.client.as_ref().clone();
         let resp = client
-            .redact(tonic::Request::new(req))
+            .detect(tonic::Request::new(options.into_request(texts)))
             .await
             .map_err(|e| anyhow!("pii-redactor rpc: {}", e.message()))?
             .into_inner();
-        Ok(resp.texts)
+        Ok(resp.results)
     }
 }
 
+/// Per-project knobs for one `Redact` RPC.
+#[derive(Default)]
+struct RedactOptions {
+    /// Pseudonym mode with this project's key; placeholders when `None`.
+    pseudonym_key: Option<Vec<u8>>,
+    /// Ask for the pseudonym -> original mapping to fill the vault.
+    return_pseudonyms: bool,
+    /// Label, confidence and field-path policy. `fields` is applied here
+    /// when picking targets and ignored by the RPC.
+    policy: PiiRedactionPolicy,
+}
+
+impl RedactOptions {
+    fn into_request(self, texts: Vec<String>) -> RedactRequest {
+        let (mode, pseudonym_key) = match self.pseudonym_key {
+            Some(key) => (RedactionMode::Pseudonym, key),
+            None => (RedactionMode::Placeholder, Vec::new()),
+        };
+        let policy = self.policy;
+        RedactRequest {
+            texts,
+            placeholder_format: None,
+            skip_keys: Vec::new(),
+            rule_detectors: Vec::new(),
+            disable_rule_detectors: false,
+            dictionaries: Vec::new(),
+            mode: mode as i32,
+            pseudonym_key,
+            return_pseudonyms: self.return_pseudonyms,
+            labels: policy.labels,
+            min_confidence: policy.min_confidence,
+            default_min_confidence: policy.default_min_confidence,
+            skip_paths: policy.skip_paths,
+            redact_paths: policy.redact_paths,
+        }
+    }
+}
+
+/// Redaction settings of one opted-in project.
+#[derive(Clone)]
+struct ProjectRedaction {
+    workspace_id: Uuid,
+    mode: PiiRedactionMode,
+    vault_enabled: bool,
+    policy: PiiRedactionPolicy,
+}
+
+impl ProjectRedaction {
+    /// A missing or malformed pseudonym secret degrades to placeholders
+    /// rather than skipping redaction.
+    fn options(&self, project_id: Uuid) -> RedactOptions {
+        let placeholders = RedactOptions {
+            policy: self.policy.clone(),
+            ..Default::default()
+        };
+        if self.mode != PiiRedactionMode::Pseudonym {
+            return placeholders;
+        }
+        match vault::pseudonym_key(project_id) {
+            Ok(key) => RedactOptions {
+                pseudonym_key: Some(key),
+                return_pseudonyms: self.vault_enabled,
+                ..placeholders
+            },
+            Err(e) => {
+                log::warn!(
+                    "pii-redactor: project[{project_id}] pseudonym key unavailable, using placeholders: {e:#}"
+                );
+                placeholders
+            }
+        }
+    }
+}
+
+/// Attribute keys never sent for redaction: Laminar's own `lmnr.*` keys
+/// (session / user ids, tags, span path) and the model and provider names
+/// that ClickHouse columns and pricing are derived from.
+const STRUCTURAL_ATTRIBUTE_PREFIXES: &[&str] = &[
+    "lmnr.",
+    "gen_ai.system",
+    "gen_ai.request.model",
+    "gen_ai.response.model",
+];
+
 /// What field a redacted text should be written back to.
 enum Target {
     /// Whole `span.input`. For root LLM spans this carries the
@@ -62,6 +173,9 @@ enum Target {
     Input(usize),
     /// Whole `span.output`.
     Output(usize),
+    /// The non-structural attributes of a span, sent as one JSON object and
+    /// merged back key by key.
+    Attributes(usize),
     /// One row of the `shared_content` CH buffer. Redacted content is
     /// inserted into ClickHouse on the next step; same content also lives
     /// in some span's `span_trace_new_contents` (under
@@ -84,22 +198,31 @@ enum Dir {
 
 /// Resolve `settings.remove_pii` for every unique project in `recordable_indices`,
 /// going through the cached billing-info path so repeat batches are free.
-/// Returns the set of opted-in project ids — empty set means "no work".
+/// Returns the opted-in projects with their redaction settings — empty map
+/// means "no work".
 async fn resolve_opted_in_projects(
     spans: &[Span],
     recordable_indices: &[usize],
     db: Arc<DB>,
     cache: Arc<Cache>,
-) -> HashSet<Uuid> {
+) -> HashMap<Uuid, ProjectRedaction> {
     let unique: HashSet<Uuid> = recordable_indices
         .iter()
         .map(|&i| spans[i].project_id)
         .collect();
-    let mut opted_in: HashSet<Uuid> = HashSet::with_capacity(unique.len());
+    let mut opted_in: HashMap<Uuid, ProjectRedaction> = HashMap::with_capacity(unique.len());
     for project_id in unique {
         match get_workspace_info_for_project_id(db.clone(), cache.clone(), project_id).await {
             Ok(Some(info)) if info.settings.remove_pii => {
-                opted_in.insert(project_id);
+                opted_in.insert(
+                    project_id,
+                    ProjectRedaction {
+                        workspace_id: info.workspace_id,
+                        mode: info.settings.pii_redaction_mode,
+                        vault_enabled: info.settings.pii_vault_enabled,
+                        policy: info.settings.pii_redaction_policy,
+                    },
+                );
             }
             Ok(_) => {}
             Err(e) => {
@@ -111,7 +234,8 @@ async fn resolve_opted_in_projects(
 }
 
 /// Redact `span.input` / `span.output` for every span whose project has
-/// `remove_pii=true`. Three buffer kinds are redacted in lockstep:
+/// `remove_pii=true`. Three buffer kinds are redacted in lockstep, each
+/// subject to the project's `PiiRedactionFields`:
 ///
 /// - **Whole `span.input` / `span.output`**: kept on root spans for the
 ///   trace-list preview and on non-LLM / non-array-input spans.
@@ -134,8 +258,16 @@ async fn resolve_opted_in_projects(
 /// MUST run after `build_dedup_batch` (input + output) and BEFORE the
 /// `shared_content` ClickHouse insert / Quickwit indexing.
 ///
-/// Best-effort: any RPC failure is logged and the batch is left untouched —
-/// PII redaction must never block trace ingestion.
+/// Span attributes are redacted too when the project's policy covers them,
+/// except for [`STRUCTURAL_ATTRIBUTE_PREFIXES`].
+///
+/// Texts are sent as one RPC per opted-in project, since the redaction mode,
+/// pseudonym key and label policy are per project. In pseudonym mode with the vault
+/// enabled, the returned pseudonym → original mapping is stored encrypted
+/// (see [`vault`]).
+///
+/// Best-effort: any RPC failure is logged and that project's batch is left
+/// untouched — PII redaction must never block trace ingestion.
 ///
 /// `input_trace_new_contents` / `output_trace_new_contents` are the per-span
 /// trace-new content buffers Quickwit indexes, indexed by `dedup_idx` (matching
@@ -157,50 +289,63 @@ pub async fn redact_spans_in_place(
     db: Arc<DB>,
     cache: Arc<Cache>,
 ) {
-    let opted_in = resolve_opted_in_projects(spans, recordable_indices, db, cache).await;
+    let opted_in = resolve_opted_in_projects(spans, recordable_indices, db.clone(), cache).await;
     if opted_in.is_empty() {
         return;
     }
 
-    // Map project_id → whether it's opted in, then map span_idx → opted-in
-    // bool once so the inner loops don't re-borrow.
-    let opt_in_for_span: HashMap<usize, bool> = recordable_indices
-        .iter()
-        .map(|&i| (i, opted_in.contains(&spans[i].project_id)))
-        .collect();
-
-    let mut targets: Vec<Target> = Vec::new();
-    let mut texts: Vec<String> = Vec::new();
+    // One RPC per project: the redaction mode and pseudonym key are
+    // per-project request fields. The redactor's batcher still coalesces
+    // windows across these concurrent RPCs.
+    let mut batches: HashMap<Uuid, (Vec<Target>, Vec<String>)> = HashMap::new();
+    let mut push = |project_id: Uuid, target: Target, text: String| {
+        let (targets, texts) = batches.entry(project_id).or_default();
+        targets.push(target);
+        texts.push(text);
+    };
 
     // Walk the `shared_content` rows that belong to opted-in projects.
     for (idx, msg) in shared_content.iter().enumerate() {
-        if opted_in.contains(&msg.project_id) {
-            targets.push(Target::SharedRow(idx));
-            texts.push(msg.content.clone());
+        if opted_in
+            .get(&msg.project_id)
+            .is_some_and(|r| r.policy.fields.shared_content)
+        {
+            push(msg.project_id, Target::SharedRow(idx), msg.content.clone());
         }
     }
 
     for (dedup_idx, &span_idx) in recordable_indices.iter().enumerate() {
-        if !*opt_in_for_span.get(&span_idx).unwrap_or(&false) {
-            continue;
-        }
         let span = &spans[span_idx];
+        let project_id = span.project_id;
+        let Some(fields) = opted_in.get(&project_id).map(|r| r.policy.fields) else {
+            continue;
+        };
 
         // Dedup'd LLM input: redact each trace-new content position so
         // Quickwit's per-trace indexer sees redacted content. Includes
         // storage-hit + trace-new content (cross-trace case).
-        if let Some(contents) = input_trace_new_contents.get(dedup_idx) {
+        if fields.input
+            && let Some(contents) = input_trace_new_contents.get(dedup_idx)
+        {
             for (offset, c) in contents.iter().enumerate() {
-                targets.push(Target::TraceNew(Dir::Input, dedup_idx, offset));
-                texts.push(c.clone());
+                push(
+                    project_id,
+                    Target::TraceNew(Dir::Input, dedup_idx, offset),
+                    c.clone(),
+                );
             }
         }
 
         // Dedup'd LLM output: same shape as input.
-        if let Some(contents) = output_trace_new_contents.get(dedup_idx) {
+        if fields.output
+            && let Some(contents) = output_trace_new_contents.get(dedup_idx)
+        {
             for (offset, c) in contents.iter().enumerate() {
-                targets.push(Target::TraceNew(Dir::Output, dedup_idx, offset));
-                texts.push(c.clone());
+                push(
+                    project_id,
+                    Target::TraceNew(Dir::Output, dedup_idx, offset),
+                    c.clone(),
+                );
             }
         }
 
@@ -208,71 +353,136 @@ pub async fn redact_spans_in_place(
         // nested LLM spans (those ride the wire as hashes only), so this
         // covers (a) root LLM spans whose `input` was kept for the trace
         // list preview and (b) non-LLM / non-array-input spans.
-        if let Some(input) = span.input.as_ref() {
+        if fields.input
+            && let Some(input) = span.input.as_ref()
+        {
             match serde_json::to_string(input) {
-                Ok(s) => {
-                    targets.push(Target::Input(span_idx));
-                    texts.push(s);
-                }
-                Err(e) => log::warn!(
-                    "pii-redactor: serialize span[{span_idx}].input: {e:#}"
-                ),
+                Ok(s) => push(project_id, Target::Input(span_idx), s),
+                Err(e) => log::warn!("pii-redactor: serialize span[{span_idx}].input: {e:#}"),
             }
         }
 
-        if let Some(output) = span.output.as_ref() {
+        if fields.output
+            && let Some(output) = span.output.as_ref()
+        {
             match serde_json::to_string(output) {
-                Ok(s) => {
-                    targets.push(Target::Output(span_idx));
-                    texts.push(s);
+                Ok(s) => push(project_id, Target::Output(span_idx), s),
+                Err(e) => log::warn!("pii-redactor: serialize span[{span_idx}].output: {e:#}"),
+            }
+        }
+
+        if fields.attributes {
+            let attributes: HashMap<&String, &serde_json::Value> = span
+                .attributes
+                .raw_attributes
+                .iter()
+                .filter(|(key, _)| {
+                    !STRUCTURAL_ATTRIBUTE_PREFIXES
+                        .iter()
+                        .any(|prefix| key.starts_with(prefix))
+                })
+                .collect();
+            if !attributes.is_empty() {
+                match serde_json::to_string(&attributes) {
+                    Ok(s) => push(project_id, Target::Attributes(span_idx), s),
+                    Err(e) => {
+                        log::warn!("pii-redactor: serialize span[{span_idx}].attributes: {e:#}")
+                    }
                 }
-                Err(e) => log::warn!(
-                    "pii-redactor: serialize span[{span_idx}].output: {e:#}"
-                ),
             }
         }
     }
 
-    if texts.is_empty() {
+    if batches.is_empty() {
         return;
     }
 
-    // Summary stats over per-element char counts, so the trace shows the
-    // shape of the request without growing unboundedly with batch size
-    // (OTEL collectors commonly truncate attributes above 128–256 bytes,
-    // so a full `Vec<usize>` for a 1024-element batch would be silently
-    // cut off). `total_chars` + `min/max/p50` is enough to distinguish
-    // "one giant 200k text" from "fifty 4k texts" at the same batch_size.
+    let rpcs = batches.into_iter().map(|(project_id, (targets, texts))| {
+        let options = opted_in[&project_id].options(project_id);
+        let rpc_span = rpc_span(project_id, &texts);
+        async move {
+            let result = client.redact(texts, options).instrument(rpc_span).await;
+            (project_id, targets, result)
+        }
+    });
+
+    for (project_id, targets, result) in join_all(rpcs).await {
+        let (redacted, pseudonyms) = match result {
+            Ok(r) => r,
+            Err(e) => {
+                log::error!(
+                    "pii-redactor: skipping batch of {} fields for project[{project_id}]: {e:#}",
+                    targets.len()
+                );
+                continue;
+            }
+        };
+        if redacted.len() != targets.len() {
+            log::error!(
+                "pii-redactor: response len {} != request len {} for project[{project_id}]; skipping",
+                redacted.len(),
+                targets.len()
+            );
+            continue;
+        }
+
+        if !pseudonyms.is_empty()
+            && let Err(e) = vault::store_pseudonyms(
+                &db,
+                opted_in[&project_id].workspace_id,
+                project_id,
+                pseudonyms,
+            )
+            .await
+        {
+            // The texts are redacted either way; only reveal is lost.
+            log::error!("pii-redactor: storing pseudonyms for project[{project_id}]: {e:#}");
+        }
+
+        apply_redacted(
+            targets,
+            redacted,
+            spans,
+            shared_content,
+            input_trace_new_contents,
+            output_trace_new_contents,
+        );
+    }
+}
+
+/// Summary stats over per-element char counts, so the trace shows the
+/// shape of the request without growing unboundedly with batch size
+/// (OTEL collectors commonly truncate attributes above 128–256 bytes,
+/// so a full `Vec<usize>` for a 1024-element batch would be silently
+/// cut off). `total_chars` + `min/max/p50` is enough to distinguish
+/// "one giant 200k text" from "fifty 4k texts" at the same batch_size.
+fn rpc_span(project_id: Uuid, texts: &[String]) -> tracing::Span {
     let mut char_counts: Vec<usize> = texts.iter().map(|t| t.chars().count()).collect();
     let total_chars: usize = char_counts.iter().sum();
     char_counts.sort_unstable();
     let min_chars = char_counts.first().copied().unwrap_or(0);
     let max_chars = char_counts.last().copied().unwrap_or(0);
     let p50_chars = char_counts.get(char_counts.len() / 2).copied().unwrap_or(0);
-    let rpc_span = tracing::info_span!(
+    tracing::info_span!(
         "pii_redactor.rpc",
+        project_id = %project_id,
         batch_size = texts.len(),
         total_chars,
         min_chars,
         max_chars,
         p50_chars,
-    );
-    let redacted = match client.redact(texts).instrument(rpc_span).await {
-        Ok(r) => r,
-        Err(e) => {
-            log::error!("pii-redactor: skipping batch of {} fields: {e:#}", targets.len());
-            return;
-        }
-    };
-    if redacted.len() != targets.len() {
-        log::error!(
-            "pii-redactor: response len {} != request len {}; skipping",
-            redacted.len(),
-            targets.len()
-        );
-        return;
-    }
+    )
+}
 
+/// Write redacted texts back to the fields they were taken from.
+fn apply_redacted(
+    targets: Vec<Target>,
+    redacted: Vec<String>,
+    spans: &mut [Span],
+    shared_content: &mut [CHDedupedContent],
+    input_trace_new_contents: &mut [Vec<String>],
+    output_trace_new_contents: &mut [Vec<String>],
+) {
     for (target, text) in targets.into_iter().zip(redacted.into_iter()) {
         match target {
             Target::Input(idx) => match serde_json::from_str(&text) {
@@ -283,6 +493,14 @@ pub async fn redact_spans_in_place(
                 Ok(v) => spans[idx].output = Some(v),
                 Err(e) => log::warn!("pii-redactor: parse redacted span[{idx}].output: {e:#}"),
             },
+            Target::Attributes(idx) => {
+                match serde_json::from_str::<HashMap<String, serde_json::Value>>(&text) {
+                    Ok(redacted) => spans[idx].attributes.raw_attributes.extend(redacted),
+                    Err(e) => {
+                        log::warn!("pii-redactor: parse redacted span[{idx}].attributes: {e:#}")
+                    }
+                }
+            }
             Target::SharedRow(idx) => {
                 if let Some(msg) = shared_content.get_mut(idx) {
                     // The redactor returns stringified JSON; sanitize to
@@ -1,5 +1,5 @@
 // This file is @generated by prost-build.
-#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct RedactRequest {
     /// Each entry must be a stringified JSON value (object, array, or scalar).
     /// The service walks the tree, recursively parses string leaves whose
@@ -9,7 +9,8 @@ pub struct RedactRequest {
     #[prost(string, repeated, tag = "1")]
     pub texts: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     /// Optional override for the placeholder format.
-    /// When empty, defaults to "\[REDACTED\_{LABEL}\]".
+    /// When empty, defaults to "\[REDACTED\_{LABEL}\]", or "{LABEL}\_{HASH}" in
+    /// PSEUDONYM mode (where the format must contain {HASH}).
     #[prost(string, optional, tag = "2")]
     pub placeholder_format: ::core::option::Option<::prost::alloc::string::String>,
     /// Object-key names whose VALUES should be skipped during redaction
@@ -17,11 +18,165 @@ pub struct RedactRequest {
     /// level. If empty, a built-in default list is used.
     #[prost(string, repeated, tag = "3")]
     pub skip_keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Deterministic detectors to run alongside the model, by name:
+    /// "credit_card", "iban", "jwt", "aws_access_key", "api_key". If empty,
+    /// all of them run. Unknown names are rejected with INVALID_ARGUMENT.
+    #[prost(string, repeated, tag = "4")]
+    pub rule_detectors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Turn the built-in rule detectors off; dictionaries still apply.
+    #[prost(bool, tag = "5")]
+    pub disable_rule_detectors: bool,
+    /// Caller-supplied term lists. Every whole-word occurrence of a term in a
+    /// redaction-eligible string value is redacted with the dictionary label.
+    #[prost(message, repeated, tag = "6")]
+    pub dictionaries: ::prost::alloc::vec::Vec<Dictionary>,
+    #[prost(enumeration = "RedactionMode", tag = "7")]
+    pub mode: i32,
+    /// HMAC key for PSEUDONYM mode, at least 16 bytes. Callers derive one per
+    /// project so pseudonyms are stable within a project only.
+    #[prost(bytes = "vec", tag = "8")]
+    pub pseudonym_key: ::prost::alloc::vec::Vec<u8>,
+    /// Return the pseudonym -> original mapping in the response (PSEUDONYM
+    /// mode only), for callers that keep an encrypted vault.
+    #[prost(bool, tag = "9")]
+    pub return_pseudonyms: bool,
+    /// Labels to redact: model labels ("private_email"), rule detector names
+    /// and dictionary labels, case-insensitive. If empty, every label is
+    /// redacted. Detectors whose label is excluded don't run.
+    #[prost(string, repeated, tag = "10")]
+    pub labels: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Minimum model confidence (0-1, the mean probability of the span's
+    /// tokens) per label for a model span to be redacted. Labels not listed
+    /// use default_min_confidence. Rule and dictionary matches always pass.
+    #[prost(map = "string, float", tag = "11")]
+    pub min_confidence: ::std::collections::HashMap<::prost::alloc::string::String, f32>,
+    #[prost(float, tag = "12")]
+    pub default_min_confidence: f32,
+    /// Field-path deny list, applied on top of skip_keys: "/"-separated object
+    /// keys ("metadata/user/email"), "\*" matches any one key, array indices
+    /// are not part of a path, and a path covers everything below it.
+    #[prost(string, repeated, tag = "13")]
+    pub skip_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Field-path allow list, same syntax. If non-empty, only string values
+    /// under one of these paths are redacted.
+    #[prost(string, repeated, tag = "14")]
+    pub redact_paths: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Dictionary {
+    /// Substituted into `{LABEL}`. Defaults to "custom" when empty.
+    #[prost(string, tag = "1")]
+    pub label: ::prost::alloc::string::String,
+    #[prost(string, repeated, tag = "2")]
+    pub terms: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    #[prost(bool, tag = "3")]
+    pub case_sensitive: bool,
 }
 #[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
 pub struct RedactResponse {
     #[prost(string, repeated, tag = "1")]
     pub texts: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Distinct pseudonyms issued for this request, when `return_pseudonyms`.
+    #[prost(message, repeated, tag = "2")]
+    pub pseudonyms: ::prost::alloc::vec::Vec<Pseudonym>,
+}
+#[derive(Clone, PartialEq, Eq, Hash, ::prost::Message)]
+pub struct Pseudonym {
+    #[prost(string, tag = "1")]
+    pub pseudonym: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub label: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub original: ::prost::alloc::string::String,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DetectResponse {
+    /// One entry per request text, in order.
+    #[prost(message, repeated, tag = "1")]
+    pub results: ::prost::alloc::vec::Vec<TextFindings>,
+}
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TextFindings {
+    #[prost(message, repeated, tag = "1")]
+    pub findings: ::prost::alloc::vec::Vec<Finding>,
+}
+/// One value Redact would have replaced. The matched text itself is not
+/// returned.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Finding {
+    #[prost(string, tag = "1")]
+    pub label: ::prost::alloc::string::String,
+    /// Model confidence (see min_confidence); 1.0 for rule and dictionary
+    /// matches.
+    #[prost(float, tag = "2")]
+    pub score: f32,
+    /// RFC 6901 pointer to the string value within the text. Values inside
+    /// stringified JSON are addressed as if it were parsed.
+    #[prost(string, tag = "3")]
+    pub pointer: ::prost::alloc::string::String,
+    /// Byte offsets within that string value, end exclusive.
+    #[prost(uint32, tag = "4")]
+    pub start: u32,
+    #[prost(uint32, tag = "5")]
+    pub end: u32,
+    #[prost(enumeration = "Detector", tag = "6")]
+    pub detector: i32,
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum RedactionMode {
+    /// Replace with `placeholder_format`.
+    Placeholder = 0,
+    /// Replace with a keyed hash of the value, so equal values get equal
+    /// pseudonyms: "{LABEL}\_{HASH}" -> "PRIVATE_EMAIL_7f3a09c1d24e8b5a90c3f17e6b2d4a81".
+    Pseudonym = 1,
+}
+impl RedactionMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Placeholder => "PLACEHOLDER",
+            Self::Pseudonym => "PSEUDONYM",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "PLACEHOLDER" => Some(Self::Placeholder),
+            "PSEUDONYM" => Some(Self::Pseudonym),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Detector {
+    Model = 0,
+    /// Built-in rule detector or caller dictionary.
+    Rule = 1,
+}
+impl Detector {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Self::Model => "MODEL",
+            Self::Rule => "RULE",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MODEL" => Some(Self::Model),
+            "RULE" => Some(Self::Rule),
+            _ => None,
+        }
+    }
 }
 /// Generated client implementations.
 pub mod pii_redactor_service_client {
@@ -135,5 +290,29 @@ pub mod pii_redactor_service_client {
                 .insert(GrpcMethod::new("pii_redactor.PiiRedactorService", "Redact"));
             self.inner.unary(req, path, codec).await
         }
+        /// Dry run of Redact: same request and policy, but returns what would be
+        /// redacted instead of rewriting the texts. Mode and pseudonym fields are
+        /// ignored.
+        pub async fn detect(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RedactRequest>,
+        ) -> std::result::Result<tonic::Response<super::DetectResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::unknown(
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic_prost::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/pii_redactor.PiiRedactorService/Detect",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("pii_redactor.PiiRedactorService", "Detect"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
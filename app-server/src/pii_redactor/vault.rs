@@ -0,0 +1,193 @@
+//! Pseudonym keys and the encrypted pseudonym vault.
+//!
+//! In pseudonym mode the pii-redactor replaces each detected value with a
+//! keyed hash. The key is derived per project from `PII_PSEUDONYM_SECRET`, so
+//! pseudonyms are stable within a project and meaningless across projects.
+//! Projects with the vault enabled also get the originals back from the
+//! redactor; those are stored encrypted with `data_plane::crypto` (the
+//! workspace id as associated data) and only decrypted on an explicit reveal.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, anyhow};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use uuid::Uuid;
+
+use super::pii_redactor::Pseudonym;
+use crate::data_plane::crypto;
+use crate::db::DB;
+use crate::db::pii_vault::{self, PiiVaultEntry};
+use crate::env::secrets::PII_PSEUDONYM_SECRET;
+
+/// Most pseudonyms a single reveal call may resolve.
+pub const MAX_REVEAL_PSEUDONYMS: usize = 1000;
+
+/// HMAC key the redactor hashes this project's values with. Fails if the
+/// server secret is missing or malformed; callers fall back to placeholders.
+pub fn pseudonym_key(project_id: Uuid) -> Result<Vec<u8>> {
+    let secret_hex = std::env::var(PII_PSEUDONYM_SECRET)
+        .map_err(|_| anyhow!("{PII_PSEUDONYM_SECRET} environment variable not set"))?;
+    let secret = hex::decode(secret_hex.trim())
+        .map_err(|e| anyhow!("Failed to decode {PII_PSEUDONYM_SECRET} from hex: {}", e))?;
+    if secret.len() < 32 {
+        return Err(anyhow!(
+            "{PII_PSEUDONYM_SECRET} must be at least 32 bytes, got {} bytes",
+            secret.len()
+        ));
+    }
+
+    Ok(derive_project_key(&secret, project_id))
+}
+
+fn derive_project_key(secret: &[u8], project_id: Uuid) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(b"pii-pseudonym:");
+    mac.update(project_id.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Encrypt and store the originals behind `pseudonyms`. A pseudonym already
+/// stored with a different original is an error: it means two values hashed
+/// to the same pseudonym, and a reveal would return the wrong one.
+pub async fn store_pseudonyms(
+    db: &DB,
+    workspace_id: Uuid,
+    project_id: Uuid,
+    pseudonyms: Vec<Pseudonym>,
+) -> Result<()> {
+    let mut originals = HashMap::with_capacity(pseudonyms.len());
+    let entries = pseudonyms
+        .into_iter()
+        .map(|p| {
+            let (nonce, value) = crypto::encrypt(workspace_id, &p.original)?;
+            originals.insert(p.pseudonym.clone(), (p.label.clone(), p.original));
+            Ok(PiiVaultEntry {
+                pseudonym: p.pseudonym,
+                label: p.label,
+                nonce,
+                value,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let inserted = pii_vault::insert_pii_vault_entries(&db.pool, project_id, &entries).await?;
+    if inserted.len() == entries.len() {
+        return Ok(());
+    }
+
+    let inserted: HashSet<String> = inserted.into_iter().collect();
+    let existing: Vec<String> = originals
+        .keys()
+        .filter(|pseudonym| !inserted.contains(*pseudonym))
+        .cloned()
+        .collect();
+    let stored = pii_vault::get_pii_vault_entries(&db.pool, project_id, &existing)
+        .await?
+        .into_iter()
+        .map(|entry| {
+            let original = crypto::decrypt(workspace_id, &entry.nonce, &entry.value)?;
+            Ok((entry.pseudonym, (entry.label, original)))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let collisions = colliding_pseudonyms(&originals, stored);
+    if !collisions.is_empty() {
+        return Err(anyhow!(
+            "{} pseudonym(s) already map to a different original in project {project_id}: {}",
+            collisions.len(),
+            collisions.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Pseudonyms whose stored (label, original) differs from the new one.
+fn colliding_pseudonyms(
+    originals: &HashMap<String, (String, String)>,
+    stored: Vec<(String, (String, String))>,
+) -> Vec<String> {
+    let mut collisions: Vec<String> = stored
+        .into_iter()
+        .filter(|(pseudonym, stored)| originals.get(pseudonym).is_some_and(|new| new != stored))
+        .map(|(pseudonym, _)| pseudonym)
+        .collect();
+    collisions.sort();
+    collisions
+}
+
+/// Originals of the given pseudonyms, revealed on behalf of `user_id`.
+/// Pseudonyms without a vault entry are left out of the map. Every reveal is
+/// recorded in `pii_reveal_events`, and nothing is returned if that fails.
+pub async fn reveal_pseudonyms(
+    db: &DB,
+    workspace_id: Uuid,
+    project_id: Uuid,
+    user_id: Uuid,
+    pseudonyms: &[String],
+) -> Result<HashMap<String, String>> {
+    let entries = pii_vault::get_pii_vault_entries(&db.pool, project_id, pseudonyms).await?;
+
+    let originals = entries
+        .into_iter()
+        .map(|entry| {
+            let original = crypto::decrypt(workspace_id, &entry.nonce, &entry.value)?;
+            Ok((entry.pseudonym, original))
+        })
+        .collect::<Result<HashMap<_, _>>>()?;
+
+    pii_vault::insert_pii_reveal_event(&db.pool, project_id, user_id, pseudonyms, originals.len())
+        .await?;
+    log::info!(
+        "pii vault: user[{user_id}] revealed {} of {} pseudonyms in project[{project_id}]",
+        originals.len(),
+        pseudonyms.len()
+    );
+
+    Ok(originals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_keys_are_stable_and_distinct() {
+        let secret = [7u8; 32];
+        let project = Uuid::new_v4();
+
+        assert_eq!(
+            derive_project_key(&secret, project),
+            derive_project_key(&secret, project)
+        );
+        assert_ne!(
+            derive_project_key(&secret, project),
+            derive_project_key(&secret, Uuid::new_v4())
+        );
+        assert_ne!(
+            derive_project_key(&secret, project),
+            derive_project_key(&[8u8; 32], project)
+        );
+        assert_eq!(derive_project_key(&secret, project).len(), 32);
+    }
+
+    #[test]
+    fn test_colliding_pseudonyms_only_flags_different_originals() {
+        let entry = |label: &str, original: &str| (label.to_string(), original.to_string());
+        let originals = HashMap::from([
+            ("EMAIL_a".to_string(), entry("email", "alice@example.com")),
+            ("EMAIL_b".to_string(), entry("email", "bob@example.com")),
+            ("PERSON_c".to_string(), entry("person", "Carol")),
+        ]);
+        let stored = vec![
+            ("EMAIL_a".to_string(), entry("email", "alice@example.com")),
+            ("EMAIL_b".to_string(), entry("email", "mallory@example.com")),
+            ("PERSON_c".to_string(), entry("custom", "Carol")),
+        ];
+
+        assert_eq!(
+            colliding_pseudonyms(&originals, stored),
+            vec!["EMAIL_b".to_string(), "PERSON_c".to_string()]
+        );
+    }
+}
@@ -1,5 +1,7 @@
 use sha3::{Digest, Sha3_256};
 
+pub mod restrictions;
+
 pub fn hash_api_key(api_key: &str) -> String {
     let mut hasher = Sha3_256::new();
     hasher.update(api_key.as_bytes());
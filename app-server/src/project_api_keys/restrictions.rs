@@ -0,0 +1,363 @@
+//! Least-privilege restrictions on project API keys: scopes, source-IP allow
+//! lists and per-key rate limits. Checked after the key itself is validated,
+//! by both the HTTP validators and gRPC authentication.
+
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, LazyLock};
+
+use chrono::Utc;
+
+use crate::cache::{Cache, CacheTrait, keys::PROJECT_API_KEY_RATE_LIMIT_CACHE_KEY};
+use crate::db::project_api_keys::ProjectApiKey;
+use crate::env::server::TRUSTED_PROXY_CIDRS;
+
+static TRUSTED_PROXIES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    TRUSTED_PROXY_CIDRS
+        .get()
+        .split(',')
+        .map(str::trim)
+        .filter(|cidr| !cidr.is_empty())
+        .map(str::to_string)
+        .collect()
+});
+
+/// What a scoped key may do. Must stay in sync with `API_KEY_SCOPES` in
+/// `frontend/lib/api-keys/types.ts`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyScope {
+    /// OTLP traces and metrics, `/v1/spans`, browser sessions, trace
+    /// metadata and tags.
+    IngestTraces,
+    IngestLogs,
+    ReadSql,
+    /// Dataset reads and writes, including pushing labeling queue items.
+    WriteDatasets,
+    /// Evaluations and evaluators.
+    RunEvals,
+    Mcp,
+    /// Debugger sessions and their cache.
+    Rollouts,
+}
+
+impl ApiKeyScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyScope::IngestTraces => "ingest_traces",
+            ApiKeyScope::IngestLogs => "ingest_logs",
+            ApiKeyScope::ReadSql => "read_sql",
+            ApiKeyScope::WriteDatasets => "write_datasets",
+            ApiKeyScope::RunEvals => "run_evals",
+            ApiKeyScope::Mcp => "mcp",
+            ApiKeyScope::Rollouts => "rollouts",
+        }
+    }
+}
+
+/// Scope a public API path requires. Paths no scope grants (project info,
+/// reports) return `None` and are open to unscoped keys only.
+pub fn required_scope(path: &str) -> Option<ApiKeyScope> {
+    let mut segments = path.trim_start_matches('/').split('/');
+    if segments.next() != Some("v1") {
+        return None;
+    }
+    match segments.next()? {
+        "traces" | "spans" | "metrics" | "browser-sessions" | "tag" => {
+            Some(ApiKeyScope::IngestTraces)
+        }
+        "logs" => Some(ApiKeyScope::IngestLogs),
+        "sql" => Some(ApiKeyScope::ReadSql),
+        "datasets" | "labeling_queues" => Some(ApiKeyScope::WriteDatasets),
+        "evals" | "evaluators" => Some(ApiKeyScope::RunEvals),
+        "mcp" => Some(ApiKeyScope::Mcp),
+        "rollouts" => Some(ApiKeyScope::Rollouts),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rejection {
+    MissingScope,
+    IpNotAllowed,
+    RateLimited,
+}
+
+impl Rejection {
+    pub fn message(&self) -> &'static str {
+        match self {
+            Rejection::MissingScope => "API key is not allowed to access this endpoint",
+            Rejection::IpNotAllowed => "API key is not allowed from this IP address",
+            Rejection::RateLimited => "API key rate limit exceeded",
+        }
+    }
+}
+
+/// Check a validated key against its restrictions. `ip` is the client address
+/// from [`client_ip`]; a key with an IP allow list rejects requests without
+/// one.
+pub async fn check_restrictions(
+    api_key: &ProjectApiKey,
+    scope: Option<ApiKeyScope>,
+    ip: Option<IpAddr>,
+    cache: Arc<Cache>,
+) -> Result<(), Rejection> {
+    if !api_key.scopes.is_empty()
+        && !scope.is_some_and(|scope| api_key.scopes.iter().any(|s| s == scope.as_str()))
+    {
+        return Err(Rejection::MissingScope);
+    }
+    if !api_key.allowed_cidrs.is_empty()
+        && !ip.is_some_and(|ip| {
+            api_key
+                .allowed_cidrs
+                .iter()
+                .any(|cidr| cidr_contains(cidr, ip))
+        })
+    {
+        return Err(Rejection::IpNotAllowed);
+    }
+    if let Some(limit) = api_key.rate_limit_per_minute
+        && is_rate_limited(api_key, limit, cache).await
+    {
+        return Err(Rejection::RateLimited);
+    }
+    Ok(())
+}
+
+/// Fixed one-minute window per key. Fails open on cache errors, same posture
+/// as the project rate limiters.
+async fn is_rate_limited(api_key: &ProjectApiKey, limit: i32, cache: Arc<Cache>) -> bool {
+    let minute = Utc::now().timestamp() / 60;
+    let key = format!(
+        "{PROJECT_API_KEY_RATE_LIMIT_CACHE_KEY}:{}:{minute}",
+        api_key.hash
+    );
+    match cache.increment(&key, 1).await {
+        Ok(count) => {
+            if count == 1 {
+                let _ = cache.set_ttl(&key, 60).await;
+            }
+            count > limit as i64
+        }
+        Err(e) => {
+            log::error!("API key rate limiter error, allowing request: {:?}", e);
+            false
+        }
+    }
+}
+
+/// The client address of a request that arrived from `peer` with the given
+/// `X-Forwarded-For` header values, trusting the proxies in
+/// `TRUSTED_PROXY_CIDRS`.
+pub fn client_ip<'a>(
+    peer: Option<IpAddr>,
+    forwarded_for: impl IntoIterator<Item = &'a str>,
+) -> Option<IpAddr> {
+    resolve_client_ip(peer, forwarded_for, &TRUSTED_PROXIES)
+}
+
+/// `X-Forwarded-For` is client-controlled except for the hops our own proxies
+/// append on the right. So the header is ignored unless the peer is a trusted
+/// proxy, and then read right to left: the first hop that isn't a trusted
+/// proxy is the client. An unparsable hop stops the walk with no address.
+fn resolve_client_ip<'a>(
+    peer: Option<IpAddr>,
+    forwarded_for: impl IntoIterator<Item = &'a str>,
+    trusted_proxies: &[String],
+) -> Option<IpAddr> {
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|cidr| cidr_contains(cidr, ip));
+    let mut client = peer?;
+    if !is_trusted(client) {
+        return Some(client);
+    }
+    let hops: Vec<&str> = forwarded_for
+        .into_iter()
+        .flat_map(|value| value.split(','))
+        .collect();
+    for hop in hops.into_iter().rev() {
+        client = parse_client_ip(hop)?;
+        if !is_trusted(client) {
+            break;
+        }
+    }
+    Some(client)
+}
+
+/// Parse a client address given either as `ip` or `ip:port`.
+pub fn parse_client_ip(addr: &str) -> Option<IpAddr> {
+    let addr = addr.trim();
+    addr.parse::<IpAddr>()
+        .ok()
+        .or_else(|| addr.parse::<SocketAddr>().ok().map(|a| a.ip()))
+}
+
+/// Whether `cidr` (`10.0.0.0/8`, `2001:db8::/32`, or a bare address) contains
+/// `ip`. IPv4-mapped IPv6 addresses match IPv4 ranges. Malformed entries match
+/// nothing.
+pub fn cidr_contains(cidr: &str, ip: IpAddr) -> bool {
+    let (addr, prefix) = match cidr.trim().split_once('/') {
+        Some((addr, prefix)) => (addr, Some(prefix)),
+        None => (cidr.trim(), None),
+    };
+    let Ok(network) = addr.parse::<IpAddr>() else {
+        return false;
+    };
+    let ip = ip.to_canonical();
+    let (network_bits, width) = to_bits(network);
+    let (ip_bits, ip_width) = to_bits(ip);
+    if width != ip_width {
+        return false;
+    }
+    let prefix = match prefix.map(str::parse::<u32>) {
+        None => width,
+        Some(Ok(prefix)) if prefix <= width => prefix,
+        Some(_) => return false,
+    };
+    if prefix == 0 {
+        return true;
+    }
+    let shift = width - prefix;
+    network_bits >> shift == ip_bits >> shift
+}
+
+fn to_bits(ip: IpAddr) -> (u128, u32) {
+    match ip {
+        IpAddr::V4(ip) => (u32::from(ip) as u128, 32),
+        IpAddr::V6(ip) => (u128::from(ip), 128),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cache::in_memory::InMemoryCache;
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    fn test_key() -> ProjectApiKey {
+        ProjectApiKey {
+            project_id: uuid::Uuid::nil(),
+            name: None,
+            hash: "hash".to_string(),
+            shorthand: "lmnr...hash".to_string(),
+            is_ingest_only: false,
+            expires_at: None,
+            scopes: Vec::new(),
+            allowed_cidrs: Vec::new(),
+            rate_limit_per_minute: None,
+        }
+    }
+
+    #[test]
+    fn cidr_contains_matches_prefixes() {
+        assert!(cidr_contains("10.0.0.0/8", ip("10.1.2.3")));
+        assert!(!cidr_contains("10.0.0.0/8", ip("11.0.0.1")));
+        assert!(cidr_contains("192.168.1.7", ip("192.168.1.7")));
+        assert!(!cidr_contains("192.168.1.7", ip("192.168.1.8")));
+        assert!(cidr_contains("0.0.0.0/0", ip("8.8.8.8")));
+        assert!(cidr_contains("2001:db8::/32", ip("2001:db8:1::1")));
+        assert!(!cidr_contains("2001:db8::/32", ip("2001:db9::1")));
+        assert!(cidr_contains("10.0.0.0/8", ip("::ffff:10.0.0.1")));
+        assert!(!cidr_contains("10.0.0.0/8", ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn malformed_cidrs_match_nothing() {
+        assert!(!cidr_contains("10.0.0.0/33", ip("10.0.0.1")));
+        assert!(!cidr_contains("10.0.0/8", ip("10.0.0.1")));
+        assert!(!cidr_contains("", ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn parses_client_ip_with_or_without_port() {
+        assert_eq!(parse_client_ip("1.2.3.4"), Some(ip("1.2.3.4")));
+        assert_eq!(parse_client_ip("1.2.3.4:5678"), Some(ip("1.2.3.4")));
+        assert_eq!(parse_client_ip("[::1]:80"), Some(ip("::1")));
+        assert_eq!(parse_client_ip("unknown"), None);
+    }
+
+    #[test]
+    fn forwarded_for_is_ignored_from_untrusted_peers() {
+        let trusted = vec!["10.0.0.0/8".to_string()];
+        // A client connecting directly can't claim an allow-listed address.
+        assert_eq!(
+            resolve_client_ip(Some(ip("203.0.113.9")), ["198.51.100.1"], &trusted),
+            Some(ip("203.0.113.9"))
+        );
+        // Nothing is trusted by default.
+        assert_eq!(
+            resolve_client_ip(Some(ip("10.0.0.2")), ["198.51.100.1"], &[]),
+            Some(ip("10.0.0.2"))
+        );
+    }
+
+    #[test]
+    fn forwarded_for_is_read_from_the_right_through_trusted_proxies() {
+        let trusted = vec!["10.0.0.0/8".to_string()];
+        // The client prepended a spoofed hop; the load balancer appended the
+        // real address it received the connection from.
+        assert_eq!(
+            resolve_client_ip(
+                Some(ip("10.0.0.2")),
+                ["198.51.100.1, 203.0.113.9"],
+                &trusted
+            ),
+            Some(ip("203.0.113.9"))
+        );
+        // Two proxies, and the hops split across two headers.
+        assert_eq!(
+            resolve_client_ip(
+                Some(ip("10.0.0.2")),
+                ["198.51.100.1, 203.0.113.9", "10.0.0.3"],
+                &trusted
+            ),
+            Some(ip("203.0.113.9"))
+        );
+        assert_eq!(
+            resolve_client_ip(Some(ip("10.0.0.2")), ["garbage"], &trusted),
+            None
+        );
+        assert_eq!(
+            resolve_client_ip(Some(ip("10.0.0.2")), [], &trusted),
+            Some(ip("10.0.0.2"))
+        );
+        assert_eq!(resolve_client_ip(None, ["203.0.113.9"], &trusted), None);
+    }
+
+    #[tokio::test]
+    async fn spoofed_forwarded_for_does_not_pass_an_allow_list() {
+        let key = ProjectApiKey {
+            allowed_cidrs: vec!["198.51.100.0/24".to_string()],
+            ..test_key()
+        };
+        let cache = Arc::new(Cache::InMemory(InMemoryCache::new(None)));
+        let trusted = vec!["10.0.0.0/8".to_string()];
+
+        let spoofed = resolve_client_ip(Some(ip("203.0.113.9")), ["198.51.100.1"], &trusted);
+        assert_eq!(
+            check_restrictions(&key, None, spoofed, cache.clone()).await,
+            Err(Rejection::IpNotAllowed)
+        );
+        let via_proxy = resolve_client_ip(Some(ip("10.0.0.2")), ["198.51.100.1"], &trusted);
+        assert_eq!(
+            check_restrictions(&key, None, via_proxy, cache).await,
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn required_scope_by_path() {
+        assert_eq!(
+            required_scope("/v1/traces"),
+            Some(ApiKeyScope::IngestTraces)
+        );
+        assert_eq!(
+            required_scope("/v1/datasets/versions/diff"),
+            Some(ApiKeyScope::WriteDatasets)
+        );
+        assert_eq!(required_scope("/v1/sql/query"), Some(ApiKeyScope::ReadSql));
+        assert_eq!(required_scope("/v1/reports"), None);
+        assert_eq!(required_scope("/v1/project"), None);
+    }
+}
@@ -44,6 +44,17 @@ const ENUMS: &[(&str, &[&str])] = &[
         &["PENDING", "COMPLETED", "FAILED", "UNKNOWN"],
     ),
     ("signal_run_mode", &["BATCH", "REALTIME", "UNKNOWN"]),
+    (
+        "metric_type",
+        &[
+            "GAUGE",
+            "SUM",
+            "HISTOGRAM",
+            "EXPONENTIAL_HISTOGRAM",
+            "SUMMARY",
+            "UNKNOWN",
+        ],
+    ),
 ];
 
 const TABLES: &[Table] = &[
@@ -92,6 +103,11 @@ const TABLES: &[Table] = &[
                 "String",
                 "Tool definitions exposed to the LLM span as stringified JSON",
             ),
+            col(
+                "links",
+                "Array(Tuple(trace_id UUID, span_id UUID, attributes String))",
+                "OpenTelemetry span links to spans in this or other traces",
+            ),
         ],
     },
     Table {
@@ -260,6 +276,144 @@ const TABLES: &[Table] = &[
             col("event_name", "String", "Event name"),
         ],
     },
+    Table {
+        name: "span_log_counts",
+        desc: "Number of logs emitted in each span that logged anything, by severity. \
+            Join to spans on (trace_id, span_id); spans without logs have no row.",
+        columns: &[
+            col("trace_id", "UUID", "Id of the trace"),
+            col("span_id", "UUID", "Id of the span"),
+            col("log_count", "UInt64", "Logs emitted in the span"),
+            col(
+                "warning_log_count",
+                "UInt64",
+                "Logs emitted in the span with WARN severity",
+            ),
+            col(
+                "error_log_count",
+                "UInt64",
+                "Logs emitted in the span with ERROR or FATAL severity",
+            ),
+        ],
+    },
+    Table {
+        name: "metrics",
+        desc: "OTel metric data points (gauges, sums, histograms, summaries), one row per point.",
+        columns: &[
+            col("id", "UUID", "Unique id of the data point"),
+            col("time", "DateTime64(9,'UTC')", "When the point was recorded"),
+            col(
+                "start_time",
+                "DateTime64(9,'UTC')",
+                "Start of the aggregation window",
+            ),
+            col("name", "String", "Metric name"),
+            col("description", "String", "Metric description"),
+            col("unit", "String", "Metric unit, e.g. 's', '{token}'"),
+            col(
+                "metric_type",
+                "String (enum metric_type)",
+                "Metric kind, e.g. 'GAUGE', 'SUM', 'HISTOGRAM'",
+            ),
+            col(
+                "aggregation_temporality",
+                "String",
+                "'DELTA', 'CUMULATIVE', or 'UNSPECIFIED'",
+            ),
+            col("is_monotonic", "Bool", "Whether a SUM is monotonic"),
+            col("value", "Float64", "Value of a GAUGE or SUM point"),
+            col("count", "UInt64", "Population count of a histogram/summary"),
+            col("sum", "Float64", "Population sum of a histogram/summary"),
+            col("min", "Nullable(Float64)", "Minimum recorded value"),
+            col("max", "Nullable(Float64)", "Maximum recorded value"),
+            col(
+                "bucket_counts",
+                "Array(UInt64)",
+                "HISTOGRAM bucket counts (one more than explicit_bounds)",
+            ),
+            col(
+                "explicit_bounds",
+                "Array(Float64)",
+                "HISTOGRAM bucket upper bounds",
+            ),
+            col("quantiles", "Array(Float64)", "SUMMARY quantiles"),
+            col(
+                "quantile_values",
+                "Array(Float64)",
+                "SUMMARY values at each quantile",
+            ),
+            col("attributes", "String", "Attributes as stringified JSON"),
+            col(
+                "resource_attributes",
+                "String",
+                "Resource attributes as stringified JSON",
+            ),
+            col("scope_name", "String", "Instrumentation scope name"),
+            col("trace_id", "UUID", "Id of an exemplar trace, if any"),
+            col("span_id", "UUID", "Id of an exemplar span, if any"),
+        ],
+    },
+    Table {
+        name: "span_metrics",
+        desc: "Per-minute span rollups grouped by model, provider, path, span_type, status and tags. \
+               Prefer over `spans` for latency/cost/token trends over long time ranges; \
+               re-aggregate with sum()/min()/max() when grouping coarser than a minute. \
+               Average duration is sum(duration_sum) / sum(span_count). Percentiles over any range \
+               come from merging the quantile states: \
+               quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)(duration_quantiles) returns [p50, p90, p95, p99].",
+        columns: &[
+            col("minute", "DateTime('UTC')", "Start of the minute bucket"),
+            col(
+                "model",
+                "String",
+                "LLM model (response model, else request model)",
+            ),
+            col(
+                "provider",
+                "String",
+                "LLM provider, e.g. 'openai', 'anthropic'",
+            ),
+            col(
+                "path",
+                "String",
+                "Hierarchical span path, e.g. 'outer.inner'",
+            ),
+            col(
+                "span_type",
+                "String (enum span_type)",
+                "Span type, e.g. 'LLM', 'TOOL', 'DEFAULT'",
+            ),
+            col("status", "String (enum status)", "'success' or 'error'"),
+            col("tags", "Array(String)", "Span-level tags, sorted"),
+            col("span_count", "UInt64", "Number of spans in the bucket"),
+            col("input_tokens", "Int64", "Sum of input tokens"),
+            col("output_tokens", "Int64", "Sum of output tokens"),
+            col("total_tokens", "Int64", "Sum of total tokens"),
+            col("input_cost", "Float64", "Sum of input cost"),
+            col("output_cost", "Float64", "Sum of output cost"),
+            col("total_cost", "Float64", "Sum of total cost"),
+            col(
+                "duration_sum",
+                "Float64",
+                "Sum of span durations in seconds",
+            ),
+            col(
+                "min_duration",
+                "Float64",
+                "Minimum span duration in seconds",
+            ),
+            col(
+                "max_duration",
+                "Float64",
+                "Maximum span duration in seconds",
+            ),
+            col(
+                "duration_quantiles",
+                "AggregateFunction(quantilesTDigestArray(0.5, 0.9, 0.95, 0.99), Array(Float64))",
+                "Mergeable duration quantile state; read with quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)",
+            ),
+        ],
+    },
     Table {
         name: "signal_runs",
         desc: "Execution records for signals with status and error info.",
@@ -419,6 +573,9 @@ mod tests {
             "clusters",
             "signal_runs",
             "logs",
+            "metrics",
+            "span_metrics",
+            "span_log_counts",
         ] {
             assert!(
                 p.contains(&format!("TABLE {table} ")),
@@ -366,6 +366,7 @@ impl TableRegistry {
             "trace_id",
             "tags",
             "tool_definitions",
+            "links",
         ];
 
         let traces_columns = [
@@ -406,6 +407,16 @@ impl TableRegistry {
             "metadata",
         ];
 
+        let dataset_snapshot_datapoints_columns = [
+            "snapshot_id",
+            "id",
+            "created_at",
+            "dataset_id",
+            "data",
+            "target",
+            "metadata",
+        ];
+
         let signal_runs_columns = [
             "project_id",
             "signal_id",
@@ -452,6 +463,63 @@ impl TableRegistry {
             "event_name",
         ];
 
+        let span_log_counts_columns = [
+            "trace_id",
+            "span_id",
+            "log_count",
+            "warning_log_count",
+            "error_log_count",
+        ];
+
+        let metrics_columns = [
+            "id",
+            "project_id",
+            "time",
+            "start_time",
+            "name",
+            "description",
+            "unit",
+            "metric_type",
+            "aggregation_temporality",
+            "is_monotonic",
+            "value",
+            "count",
+            "sum",
+            "min",
+            "max",
+            "bucket_counts",
+            "explicit_bounds",
+            "quantiles",
+            "quantile_values",
+            "attributes",
+            "resource_attributes",
+            "scope_name",
+            "trace_id",
+            "span_id",
+        ];
+
+        let span_metrics_columns = [
+            "project_id",
+            "minute",
+            "model",
+            "provider",
+            "path",
+            "span_type",
+            "status",
+            "tags",
+            "span_count",
+            "input_tokens",
+            "output_tokens",
+            "total_tokens",
+            "input_cost",
+            "output_cost",
+            "total_cost",
+            "duration_sum",
+            "min_duration",
+            "max_duration",
+            "duration_quantiles",
+        ];
+
         let labeling_queue_items_columns = [
             "id",
             "queue_id",
@@ -529,6 +597,11 @@ impl TableRegistry {
             "dataset_datapoint_versions",
             schema(&dataset_datapoints_columns),
         );
+        // immutable copies of dataset versions, one set of rows per snapshot_id
+        tables.insert(
+            "dataset_snapshot_datapoints",
+            schema(&dataset_snapshot_datapoints_columns),
+        );
         tables.insert(
             "evaluation_datapoints",
             schema(&evaluation_datapoints_columns),
@@ -536,6 +609,9 @@ impl TableRegistry {
         tables.insert("signal_runs", schema(&signal_runs_columns));
         tables.insert("signal_events", schema(&signal_events_columns));
         tables.insert("logs", schema(&logs_columns));
+        tables.insert("span_log_counts", schema(&span_log_counts_columns));
+        tables.insert("metrics", schema(&metrics_columns));
+        tables.insert("span_metrics", schema(&span_metrics_columns));
         tables.insert(
             "labeling_queue_items",
             schema(&labeling_queue_items_columns),
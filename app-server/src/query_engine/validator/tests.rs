@@ -115,6 +115,46 @@ fn test_validate_evaluation_datapoints_select() {
     );
 }
 
+#[test]
+fn test_validate_span_log_counts_select() {
+    let result = validate_ok(
+        "SELECT span_id, error_log_count FROM span_log_counts WHERE trace_id = {trace_id:UUID}",
+    );
+    assert!(
+        contains_ws(
+            &result,
+            &format!("FROM span_log_counts_v0(project_id = '{SAMPLE_PROJECT_ID}')")
+        ),
+        "got: {result}"
+    );
+}
+
+#[test]
+fn test_reject_log_counts_on_spans() {
+    let err = validate("SELECT spans.log_count FROM spans")
+        .expect_err("log counts live in span_log_counts");
+    assert!(
+        err.contains("Column 'log_count' does not exist"),
+        "got: {err}"
+    );
+}
+
+#[test]
+fn test_validate_span_metrics_quantile_merge() {
+    let result = validate_ok(
+        "SELECT toStartOfDay(minute) AS day, sum(duration_sum) / sum(span_count) AS avg_duration, \
+         quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)(duration_quantiles) AS quantiles \
+         FROM span_metrics GROUP BY day",
+    );
+    assert!(
+        contains_ws(
+            &result,
+            &format!("FROM span_metrics_v0(project_id = '{SAMPLE_PROJECT_ID}')")
+        ),
+        "got: {result}"
+    );
+}
+
 #[test]
 fn test_reject_write_operations() {
     // Write operations sqlparser parses as a non-Query statement: rejected by
@@ -0,0 +1,521 @@
+//! Custom reports: a saved SQL query, run on the report's schedule and rendered
+//! as a table, a top-N list or a delta against the previous period of the
+//! same length. The query runs through the same validation and read-only
+//! execution path as `/v1/sql/query`, scoped to the report's project.
+//!
+//! Queries can reference `{start_time:DateTime64}` and `{end_time:DateTime64}`,
+//! which are bound to the bounds of the period being reported on.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::cache::Cache;
+use crate::db::DB;
+use crate::query_engine::QueryEngine;
+use crate::sql::{self, ClickhouseReadonlyClient, SqlQueryError, SqlQuerySource};
+
+/// Report type identifier for custom SQL query reports.
+pub const REPORT_TYPE_CUSTOM_QUERY: &str = "CUSTOM_QUERY";
+
+const DEFAULT_TABLE_LIMIT: usize = 20;
+const DEFAULT_TOP_N_LIMIT: usize = 10;
+/// Most rows a report may render, regardless of the configured limit.
+pub const MAX_RENDERED_ROWS: usize = 50;
+/// Longest rendered cell, in characters.
+const MAX_CELL_CHARS: usize = 80;
+
+const PARAM_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// The first rows of the result, as returned.
+    Table,
+    /// Rows ranked by `valueColumn`, highest first.
+    TopN,
+    /// `valueColumn` per `labelColumn` compared with the previous period.
+    Delta,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderOptions {
+    pub mode: RenderMode,
+    #[serde(default)]
+    pub label_column: Option<String>,
+    #[serde(default)]
+    pub value_column: Option<String>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// The definition of a `CUSTOM_QUERY` report, stored in `reports.metadata`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomReportDefinition {
+    pub query: String,
+    pub render: RenderOptions,
+}
+
+impl CustomReportDefinition {
+    pub fn from_metadata(metadata: &Value) -> anyhow::Result<Self> {
+        let definition: Self = serde_json::from_value(metadata.clone())?;
+        definition.validate()?;
+        Ok(definition)
+    }
+
+    /// Checks everything but the query itself, which is validated by the
+    /// query engine.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.query.trim().is_empty() {
+            anyhow::bail!("query must not be empty");
+        }
+        if matches!(self.render.mode, RenderMode::TopN | RenderMode::Delta) {
+            if self.render.label_column.as_deref().unwrap_or("").is_empty()
+                || self.render.value_column.as_deref().unwrap_or("").is_empty()
+            {
+                anyhow::bail!(
+                    "labelColumn and valueColumn are required for top_n and delta reports"
+                );
+            }
+        }
+        if let Some(limit) = self.render.limit {
+            if limit == 0 || limit > MAX_RENDERED_ROWS {
+                anyhow::bail!(
+                    "limit must be between 1 and {}, got {}",
+                    MAX_RENDERED_ROWS,
+                    limit
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn limit(&self) -> usize {
+        self.render.limit.unwrap_or(match self.render.mode {
+            RenderMode::Table => DEFAULT_TABLE_LIMIT,
+            RenderMode::TopN | RenderMode::Delta => DEFAULT_TOP_N_LIMIT,
+        })
+    }
+}
+
+/// A rendered result, ready for the notification formatters. All cells are
+/// pre-formatted strings so every channel shows the same values.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReportTable {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    /// Rows in the query result, before the limit was applied
+    pub total_rows: usize,
+}
+
+impl ReportTable {
+    /// Renders the table as aligned plain text, for monospace channels.
+    pub fn to_text(&self) -> String {
+        let mut widths: Vec<usize> = self.columns.iter().map(|c| c.chars().count()).collect();
+        for row in &self.rows {
+            for (i, cell) in row.iter().enumerate() {
+                if let Some(width) = widths.get_mut(i) {
+                    *width = (*width).max(cell.chars().count());
+                }
+            }
+        }
+
+        let format_row = |cells: &[String]| {
+            cells
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ")
+                .trim_end()
+                .to_string()
+        };
+
+        let mut lines = Vec::with_capacity(self.rows.len() + 2);
+        lines.push(format_row(&self.columns));
+        lines.push(
+            widths
+                .iter()
+                .map(|w| "-".repeat(*w))
+                .collect::<Vec<_>>()
+                .join("  "),
+        );
+        lines.extend(self.rows.iter().map(|row| format_row(row)));
+        lines.join("\n")
+    }
+}
+
+/// Everything needed to run report queries against the read-only ClickHouse user.
+#[derive(Clone)]
+pub struct CustomQueryRunner {
+    pub clickhouse_ro: Option<Arc<ClickhouseReadonlyClient>>,
+    pub query_engine: Arc<QueryEngine>,
+    pub http_client: Arc<reqwest::Client>,
+    pub db: Arc<DB>,
+    pub cache: Arc<Cache>,
+}
+
+impl CustomQueryRunner {
+    async fn run(
+        &self,
+        query: &str,
+        project_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<Vec<Value>, SqlQueryError> {
+        let Some(clickhouse_ro) = self.clickhouse_ro.clone() else {
+            return Err(SqlQueryError::InternalError(
+                "Read-only ClickHouse client is not configured".to_string(),
+            ));
+        };
+
+        let parameters = HashMap::from([
+            (
+                "start_time".to_string(),
+                Value::String(start.format(PARAM_TIME_FORMAT).to_string()),
+            ),
+            (
+                "end_time".to_string(),
+                Value::String(end.format(PARAM_TIME_FORMAT).to_string()),
+            ),
+        ]);
+
+        sql::execute_sql_query(
+            query.to_string(),
+            project_id,
+            parameters,
+            SqlQuerySource::Public,
+            clickhouse_ro,
+            self.query_engine.clone(),
+            self.http_client.clone(),
+            self.db.clone(),
+            self.cache.clone(),
+        )
+        .await
+    }
+
+    /// Runs the report for `[start, end)` and renders it. Delta reports also
+    /// run the query over the preceding period of the same length.
+    pub async fn render_report(
+        &self,
+        definition: &CustomReportDefinition,
+        project_id: Uuid,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<ReportTable, SqlQueryError> {
+        let current = self.run(&definition.query, project_id, start, end).await?;
+        match definition.render.mode {
+            RenderMode::Table => Ok(render_table(&current, definition.limit())),
+            RenderMode::TopN => Ok(render_top_n(definition, &current)),
+            RenderMode::Delta => {
+                let previous_start = start - (end - start);
+                let previous = self
+                    .run(&definition.query, project_id, previous_start, start)
+                    .await?;
+                Ok(render_delta(definition, &current, &previous))
+            }
+        }
+    }
+}
+
+fn render_table(rows: &[Value], limit: usize) -> ReportTable {
+    let columns: Vec<String> = rows
+        .first()
+        .and_then(Value::as_object)
+        .map(|row| row.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let rendered = rows
+        .iter()
+        .take(limit)
+        .map(|row| {
+            columns
+                .iter()
+                .map(|column| format_cell(row.get(column).unwrap_or(&Value::Null)))
+                .collect()
+        })
+        .collect();
+
+    ReportTable {
+        columns,
+        rows: rendered,
+        total_rows: rows.len(),
+    }
+}
+
+fn render_top_n(definition: &CustomReportDefinition, rows: &[Value]) -> ReportTable {
+    let label_column = definition.render.label_column.clone().unwrap_or_default();
+    let value_column = definition.render.value_column.clone().unwrap_or_default();
+
+    let mut ranked: Vec<(String, f64)> = rows
+        .iter()
+        .filter_map(|row| {
+            let value = numeric(row.get(&value_column)?)?;
+            Some((label(row.get(&label_column)), value))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ReportTable {
+        columns: vec![label_column, value_column],
+        rows: ranked
+            .iter()
+            .take(definition.limit())
+            .map(|(label, value)| vec![label.clone(), format_number(*value)])
+            .collect(),
+        total_rows: ranked.len(),
+    }
+}
+
+fn render_delta(
+    definition: &CustomReportDefinition,
+    current: &[Value],
+    previous: &[Value],
+) -> ReportTable {
+    let label_column = definition.render.label_column.clone().unwrap_or_default();
+    let value_column = definition.render.value_column.clone().unwrap_or_default();
+
+    let values_by_label = |rows: &[Value]| -> Vec<(String, f64)> {
+        rows.iter()
+            .filter_map(|row| {
+                let value = numeric(row.get(&value_column)?)?;
+                Some((label(row.get(&label_column)), value))
+            })
+            .collect()
+    };
+    let current = values_by_label(current);
+    let previous: HashMap<String, f64> = values_by_label(previous).into_iter().collect();
+
+    let mut seen = HashSet::new();
+    let mut deltas: Vec<(String, Option<f64>, Option<f64>)> = current
+        .into_iter()
+        .map(|(label, value)| {
+            seen.insert(label.clone());
+            let before = previous.get(&label).copied();
+            (label, Some(value), before)
+        })
+        .collect();
+    deltas.extend(
+        previous
+            .iter()
+            .filter(|(label, _)| !seen.contains(*label))
+            .map(|(label, value)| (label.clone(), None, Some(*value))),
+    );
+    // Largest absolute change first, so the biggest movers make the cut.
+    deltas.sort_by(|a, b| {
+        let change = |d: &(String, Option<f64>, Option<f64>)| {
+            (d.1.unwrap_or(0.0) - d.2.unwrap_or(0.0)).abs()
+        };
+        change(b).total_cmp(&change(a)).then_with(|| a.0.cmp(&b.0))
+    });
+
+    let total_rows = deltas.len();
+    let rows = deltas
+        .into_iter()
+        .take(definition.limit())
+        .map(|(label, current, previous)| {
+            vec![
+                label,
+                current
+                    .map(format_number)
+                    .unwrap_or_else(|| "-".to_string()),
+                previous
+                    .map(format_number)
+                    .unwrap_or_else(|| "-".to_string()),
+                format_change(current, previous),
+            ]
+        })
+        .collect();
+
+    ReportTable {
+        columns: vec![
+            label_column,
+            value_column,
+            "previous".to_string(),
+            "change".to_string(),
+        ],
+        rows,
+        total_rows,
+    }
+}
+
+fn format_change(current: Option<f64>, previous: Option<f64>) -> String {
+    match (current, previous) {
+        (Some(_), None) => "new".to_string(),
+        (None, Some(_)) => "removed".to_string(),
+        (Some(current), Some(previous)) => {
+            let diff = current - previous;
+            let sign = if diff > 0.0 { "+" } else { "" };
+            if previous == 0.0 {
+                format!("{}{}", sign, format_number(diff))
+            } else {
+                format!(
+                    "{}{} ({}{:.1}%)",
+                    sign,
+                    format_number(diff),
+                    sign,
+                    diff / previous.abs() * 100.0
+                )
+            }
+        }
+        (None, None) => "-".to_string(),
+    }
+}
+
+/// ClickHouse returns 64-bit integers and decimals as JSON strings, so numeric
+/// strings count as numbers.
+fn numeric(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse::<f64>().ok().filter(|v| v.is_finite()),
+        _ => None,
+    }
+}
+
+fn label(value: Option<&Value>) -> String {
+    match value {
+        None | Some(Value::Null) => "(empty)".to_string(),
+        Some(Value::String(s)) if s.is_empty() => "(empty)".to_string(),
+        Some(value) => format_cell(value),
+    }
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{:.0}", value);
+    }
+    let formatted = format!("{:.4}", value);
+    formatted
+        .trim_end_matches('0')
+        .trim_end_matches('.')
+        .to_string()
+}
+
+fn format_cell(value: &Value) -> String {
+    let formatted = match value {
+        Value::Null => "-".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.to_string(),
+            None => n
+                .as_f64()
+                .map(format_number)
+                .unwrap_or_else(|| n.to_string()),
+        },
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    truncate(&formatted)
+}
+
+fn truncate(s: &str) -> String {
+    if s.chars().count() <= MAX_CELL_CHARS {
+        return s.to_string();
+    }
+    let mut truncated: String = s.chars().take(MAX_CELL_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn definition(mode: RenderMode, limit: Option<usize>) -> CustomReportDefinition {
+        CustomReportDefinition {
+            query: "SELECT model, count() AS spans FROM spans GROUP BY model".to_string(),
+            render: RenderOptions {
+                mode,
+                label_column: Some("model".to_string()),
+                value_column: Some("spans".to_string()),
+                limit,
+            },
+        }
+    }
+
+    #[test]
+    fn definition_parses_from_report_metadata() {
+        let metadata = json!({
+            "query": "SELECT 1",
+            "render": { "mode": "top_n", "labelColumn": "model", "valueColumn": "cost", "limit": 5 }
+        });
+        let definition = CustomReportDefinition::from_metadata(&metadata).unwrap();
+        assert_eq!(definition.render.mode, RenderMode::TopN);
+        assert_eq!(definition.limit(), 5);
+
+        let missing_columns = json!({ "query": "SELECT 1", "render": { "mode": "delta" } });
+        assert!(CustomReportDefinition::from_metadata(&missing_columns).is_err());
+
+        let too_many = json!({ "query": "SELECT 1", "render": { "mode": "table", "limit": 500 } });
+        assert!(CustomReportDefinition::from_metadata(&too_many).is_err());
+    }
+
+    #[test]
+    fn table_keeps_column_order_and_formats_cells() {
+        let rows = vec![
+            json!({ "name": "chat", "spans": 12, "cost": 0.123456, "error": null }),
+            json!({ "name": "embed", "spans": "18446744073709551615", "cost": 2.0, "error": "x" }),
+        ];
+        let table = render_table(&rows, 1);
+        assert_eq!(table.columns, vec!["name", "spans", "cost", "error"]);
+        assert_eq!(table.rows, vec![vec!["chat", "12", "0.1235", "-"]]);
+        assert_eq!(table.total_rows, 2);
+        assert!(render_table(&[], 10).columns.is_empty());
+    }
+
+    #[test]
+    fn top_n_ranks_by_value_column() {
+        let rows = vec![
+            json!({ "model": "a", "spans": "3" }),
+            json!({ "model": "b", "spans": 10 }),
+            json!({ "model": null, "spans": 5 }),
+            json!({ "model": "c", "spans": "n/a" }),
+        ];
+        let table = render_top_n(&definition(RenderMode::TopN, Some(2)), &rows);
+        assert_eq!(table.rows, vec![vec!["b", "10"], vec!["(empty)", "5"]]);
+        assert_eq!(table.total_rows, 3);
+    }
+
+    #[test]
+    fn delta_compares_with_previous_period() {
+        let current = vec![
+            json!({ "model": "a", "spans": 150 }),
+            json!({ "model": "b", "spans": 10 }),
+            json!({ "model": "new", "spans": 7 }),
+        ];
+        let previous = vec![
+            json!({ "model": "a", "spans": 100 }),
+            json!({ "model": "b", "spans": 10 }),
+            json!({ "model": "gone", "spans": 3 }),
+        ];
+        let table = render_delta(&definition(RenderMode::Delta, None), &current, &previous);
+        assert_eq!(table.columns, vec!["model", "spans", "previous", "change"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["a", "150", "100", "+50 (+50.0%)"],
+                vec!["new", "7", "-", "new"],
+                vec!["gone", "-", "3", "removed"],
+                vec!["b", "10", "10", "0 (0.0%)"],
+            ]
+        );
+    }
+
+    #[test]
+    fn text_table_is_aligned() {
+        let table = ReportTable {
+            columns: vec!["model".to_string(), "spans".to_string()],
+            rows: vec![vec!["gpt-4o".to_string(), "12".to_string()]],
+            total_rows: 1,
+        };
+        assert_eq!(table.to_text(), "model   spans\n------  -----\ngpt-4o  12");
+    }
+}
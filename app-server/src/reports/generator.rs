@@ -3,6 +3,9 @@
 //! calling, builds a ReportData struct, and pushes a single notification message to the
 //! notification queue. Target fetching and rendering happen downstream in the notification
 //! consumer pipeline.
+//!
+//! `CUSTOM_QUERY` reports instead run their saved SQL query for the period and push a single
+//! rendered table (see [`super::custom`]).
 
 use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
@@ -13,11 +16,14 @@ use tracing::instrument;
 use uuid::Uuid;
 
 use super::ReportTriggerMessage;
+use super::custom::{
+    CustomQueryRunner, CustomReportDefinition, REPORT_TYPE_CUSTOM_QUERY, ReportTable,
+};
 use super::email_template::{NoteworthyEvent, ProjectReportData};
 use crate::ch::signal_events::{get_signal_event_counts, get_signal_events_for_summary};
 use crate::db::DB;
 use crate::db::projects::get_projects_for_workspace;
-use crate::db::reports::get_signals_for_workspace;
+use crate::db::reports::{get_custom_report, get_signals_for_workspace};
 use crate::db::workspaces::get_workspace;
 use crate::mq::MessageQueue;
 use crate::mq::utils::mq_max_payload;
@@ -31,6 +37,7 @@ use crate::llm::{
     LlmClient, ProviderContent, ProviderPart, ProviderRequest, ProviderThinkingConfig,
     ProviderThinkingLevel,
 };
+use crate::sql::SqlQueryError;
 use crate::worker::{HandlerError, MessageHandler};
 
 const MAX_EVENTS_FOR_SUMMARY: u64 = 128;
@@ -48,6 +55,7 @@ pub struct ReportsGenerator {
     pub clickhouse: clickhouse::Client,
     pub queue: Arc<MessageQueue>,
     pub llm_client: Option<Arc<LlmClient>>,
+    pub custom_queries: CustomQueryRunner,
 }
 
 #[async_trait]
@@ -61,18 +69,20 @@ impl MessageHandler for ReportsGenerator {
             self.clickhouse.clone(),
             self.queue.clone(),
             self.llm_client.clone(),
+            &self.custom_queries,
         )
         .await
     }
 }
 
-#[instrument(skip(message, db, clickhouse, queue, llm_client))]
+#[instrument(skip(message, db, clickhouse, queue, llm_client, custom_queries))]
 async fn process_report_trigger(
     message: ReportTriggerMessage,
     db: Arc<DB>,
     clickhouse: clickhouse::Client,
     queue: Arc<MessageQueue>,
     llm_client: Option<Arc<LlmClient>>,
+    custom_queries: &CustomQueryRunner,
 ) -> Result<(), HandlerError> {
     let workspace_id = message.workspace_id;
     let report_id = message.id;
@@ -89,6 +99,19 @@ async fn process_report_trigger(
     let triggered_at = DateTime::from_timestamp(message.triggered_at, 0).unwrap_or_else(Utc::now);
     let (period_start, period_end) = report_period(triggered_at, &message.weekdays, message.hour);
 
+    if report_type == REPORT_TYPE_CUSTOM_QUERY {
+        return process_custom_report(
+            report_id,
+            workspace_id,
+            period_start,
+            period_end,
+            db,
+            queue,
+            custom_queries,
+        )
+        .await;
+    }
+
     let report_name = match report_type.as_str() {
         REPORT_TYPE_SIGNAL_EVENTS_SUMMARY => REPORT_NAME_SIGNAL_EVENTS_SUMMARY.to_string(),
         _ => "Report".to_string(),
@@ -294,6 +317,106 @@ async fn process_report_trigger(
     Ok(())
 }
 
+/// Run a `CUSTOM_QUERY` report's saved query for the period and push the rendered
+/// result as a single notification.
+async fn process_custom_report(
+    report_id: Uuid,
+    workspace_id: Uuid,
+    period_start: DateTime<Utc>,
+    period_end: DateTime<Utc>,
+    db: Arc<DB>,
+    queue: Arc<MessageQueue>,
+    custom_queries: &CustomQueryRunner,
+) -> Result<(), HandlerError> {
+    let Some(report) = get_custom_report(&db.pool, &report_id, REPORT_TYPE_CUSTOM_QUERY)
+        .await
+        .map_err(|e| HandlerError::transient(e))?
+    else {
+        log::info!(
+            "[Reports Generator] Custom report {} no longer exists, skipping",
+            report_id
+        );
+        return Ok(());
+    };
+
+    if custom_queries.clickhouse_ro.is_none() {
+        return Err(HandlerError::permanent(anyhow::anyhow!(
+            "[Reports Generator] Read-only ClickHouse client is not configured, cannot run custom report {}",
+            report_id
+        )));
+    }
+
+    let definition = CustomReportDefinition::from_metadata(&report.metadata).map_err(|e| {
+        HandlerError::permanent(anyhow::anyhow!(
+            "[Reports Generator] Invalid definition for custom report {}: {:?}",
+            report_id,
+            e
+        ))
+    })?;
+
+    // A broken query is reported to the recipients; only infrastructure errors are retried.
+    let (table, error) = match custom_queries
+        .render_report(&definition, report.project_id, period_start, period_end)
+        .await
+    {
+        Ok(table) => (table, None),
+        Err(SqlQueryError::InternalError(e)) => {
+            return Err(HandlerError::transient(anyhow::anyhow!(
+                "[Reports Generator] Failed to run custom report {}: {}",
+                report_id,
+                e
+            )));
+        }
+        Err(SqlQueryError::ValidationError(e) | SqlQueryError::BadResponseError(e)) => {
+            log::warn!(
+                "[Reports Generator] Query of custom report {} failed: {}",
+                report_id,
+                e
+            );
+            (ReportTable::default(), Some(e))
+        }
+    };
+
+    let workspace_name = get_workspace(&db.pool, &workspace_id)
+        .await
+        .map_err(|e| HandlerError::transient(e))?
+        .map(|w| w.name)
+        .unwrap_or_else(|| "Unknown Workspace".to_string());
+
+    let notification_message = NotificationMessage {
+        definition_type: NotificationDefinitionType::Report,
+        definition_id: report_id,
+        workspace_id,
+        project_id: Some(report.project_id),
+        notifications: vec![NotificationKind::CustomReport {
+            workspace_name,
+            project_id: report.project_id,
+            project_name: report.project_name,
+            report_name: report.name,
+            period_start: period_start.format("%b %d, %Y %H:%M UTC").to_string(),
+            period_end: period_end.format("%b %d, %Y %H:%M UTC").to_string(),
+            mode: definition.render.mode,
+            table,
+            error,
+        }],
+    };
+
+    if let Err(e) = push_to_notification_queue(notification_message, queue).await {
+        return Err(HandlerError::transient(anyhow::anyhow!(
+            "[Reports Generator] Failed to push custom report {} notification: {:?}",
+            report_id,
+            e
+        )));
+    }
+
+    log::info!(
+        "[Reports Generator] Custom report {} notification pushed to queue",
+        report_id
+    );
+
+    Ok(())
+}
+
 /// Build the tool definition for the report summary LLM call.
 /// The LLM must always call this tool to produce its output.
 fn build_summary_tool() -> ProviderTool {
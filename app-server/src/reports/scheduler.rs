@@ -18,31 +18,49 @@ const TICK_INTERVAL_SECONDS: u64 = 600;
 
 pub async fn run_reports_scheduler(pool: PgPool, queue: Arc<MessageQueue>, cache: Arc<Cache>) {
     log::debug!("[Reports Scheduler] Starting reports scheduler");
+    run_locked_on_interval(
+        "Reports Scheduler",
+        REPORT_SCHEDULER_LOCK_CACHE_KEY,
+        cache.clone(),
+        || check_and_enqueue(&pool, queue.clone(), &cache),
+    )
+    .await
+}
+
+/// Run `task` every scheduler tick on whichever replica holds `lock_key`.
+/// Shared by the reports scheduler and the metric-alert evaluator so both run
+/// on the same cadence and never concurrently across replicas.
+pub(crate) async fn run_locked_on_interval<F, Fut>(
+    name: &str,
+    lock_key: &str,
+    cache: Arc<Cache>,
+    mut task: F,
+) where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = anyhow::Result<()>>,
+{
     let mut interval = time::interval(Duration::from_secs(TICK_INTERVAL_SECONDS));
     interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
 
     loop {
         interval.tick().await;
 
-        match cache
-            .try_acquire_lock(REPORT_SCHEDULER_LOCK_CACHE_KEY, LOCK_TTL_SECONDS)
-            .await
-        {
+        match cache.try_acquire_lock(lock_key, LOCK_TTL_SECONDS).await {
             Ok(true) => {
-                log::debug!("[Reports Scheduler] Acquired lock, checking and enqueuing reports");
-                if let Err(e) = check_and_enqueue(&pool, queue.clone(), &cache).await {
-                    log::error!("[Reports Scheduler] Error: {:?}", e);
+                log::debug!("[{}] Acquired lock, running", name);
+                if let Err(e) = task().await {
+                    log::error!("[{}] Error: {:?}", name, e);
                 }
 
-                if let Err(e) = cache.release_lock(REPORT_SCHEDULER_LOCK_CACHE_KEY).await {
-                    log::warn!("[Reports Scheduler] Failed to release lock: {:?}", e);
+                if let Err(e) = cache.release_lock(lock_key).await {
+                    log::warn!("[{}] Failed to release lock: {:?}", name, e);
                 }
             }
             Ok(false) => {
-                log::debug!("[Reports Scheduler] Another replica holds the lock, skipping");
+                log::debug!("[{}] Another replica holds the lock, skipping", name);
             }
             Err(e) => {
-                log::warn!("[Reports Scheduler] Failed to acquire lock: {:?}", e);
+                log::warn!("[{}] Failed to acquire lock: {:?}", name, e);
             }
         }
     }
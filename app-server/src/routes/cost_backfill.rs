@@ -0,0 +1,112 @@
+use std::sync::Arc;
+
+use actix_web::{HttpResponse, post, web};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    cache::Cache,
+    cost_backfill::{CostBackfillMessage, push_to_cost_backfill_queue},
+    data_plane::get_workspace_deployment,
+    db::{
+        DB,
+        cost_backfill_jobs::{
+            create_cost_backfill_job, get_cost_backfill_job, resume_cost_backfill_job,
+        },
+        workspaces::DeploymentMode,
+    },
+    mq::MessageQueue,
+    routes::types::ResponseResult,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartCostBackfillRequest {
+    pub start_time: DateTime<Utc>,
+    pub end_time: DateTime<Utc>,
+    /// Restrict the backfill to one model; all models if absent.
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+/// `POST /api/v1/projects/{project_id}/cost-backfill-jobs` — start recomputing
+/// the cost of zero-cost LLM spans in a time range (auth and the admin role
+/// check are handled by Next.js). Returns the created job; progress is read
+/// from the `cost_backfill_jobs` row.
+#[post("cost-backfill-jobs")]
+pub async fn start_cost_backfill(
+    path: web::Path<Uuid>,
+    body: web::Json<StartCostBackfillRequest>,
+    db: web::Data<DB>,
+    cache: web::Data<Cache>,
+    queue: web::Data<Arc<MessageQueue>>,
+) -> ResponseResult {
+    let project_id = path.into_inner();
+    let request = body.into_inner();
+
+    if request.start_time >= request.end_time {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "startTime must be before endTime",
+        })));
+    }
+
+    // Spans of hybrid deployments live in the customer's data plane, which
+    // does not accept mutations.
+    let deployment = get_workspace_deployment(&db.pool, cache.into_inner(), project_id).await?;
+    if deployment.mode != DeploymentMode::CLOUD {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": "Cost backfill is not available for hybrid deployments",
+        })));
+    }
+
+    let model = request
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|model| !model.is_empty());
+    let job = create_cost_backfill_job(
+        &db.pool,
+        project_id,
+        request.start_time,
+        request.end_time,
+        model,
+    )
+    .await?;
+
+    let message = CostBackfillMessage {
+        project_id,
+        job_id: job.id,
+    };
+    push_to_cost_backfill_queue(&message, queue.get_ref().clone()).await?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
+
+/// `POST /api/v1/projects/{project_id}/cost-backfill-jobs/{job_id}/resume` —
+/// continue a failed or stalled job from its last committed batch.
+#[post("cost-backfill-jobs/{job_id}/resume")]
+pub async fn resume_cost_backfill(
+    path: web::Path<(Uuid, Uuid)>,
+    db: web::Data<DB>,
+    queue: web::Data<Arc<MessageQueue>>,
+) -> ResponseResult {
+    let (project_id, job_id) = path.into_inner();
+
+    let Some(job) = resume_cost_backfill_job(&db.pool, project_id, job_id).await? else {
+        return Ok(
+            match get_cost_backfill_job(&db.pool, project_id, job_id).await? {
+                Some(_) => HttpResponse::Conflict().json(json!({
+                    "error": "Only failed or stalled jobs can be resumed",
+                })),
+                None => HttpResponse::NotFound().json("Job not found"),
+            },
+        );
+    };
+
+    let message = CostBackfillMessage { project_id, job_id };
+    push_to_cost_backfill_queue(&message, queue.get_ref().clone()).await?;
+
+    Ok(HttpResponse::Ok().json(job))
+}
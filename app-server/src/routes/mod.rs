@@ -1,10 +1,13 @@
+pub mod cost_backfill;
 pub mod error;
+pub mod pii;
 pub mod probes;
 pub mod realtime;
 pub mod rollouts;
 pub mod signal_events;
 pub mod spans;
 pub mod sql;
+pub mod traces;
 pub mod types;
 use serde::Serialize;
 use types::*;
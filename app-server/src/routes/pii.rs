@@ -0,0 +1,146 @@
+use actix_web::{HttpResponse, post, web};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use uuid::Uuid;
+
+use crate::{
+    cache::Cache,
+    db::{
+        DB,
+        projects::{PiiRedactionPolicy, project_has_admin},
+    },
+    pii_redactor::{
+        PiiRedactorClient,
+        pii_redactor::Detector,
+        vault::{self, MAX_REVEAL_PSEUDONYMS},
+    },
+    routes::types::ResponseResult,
+    utils::limits::get_workspace_info_for_project_id,
+};
+
+/// Cap on values per detection request; each is sent as one text.
+const MAX_DETECT_VALUES: usize = 100;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevealPseudonymsRequest {
+    pub pseudonyms: Vec<String>,
+    /// The signed-in user the reveal is made for, recorded in the audit log.
+    pub user_id: Uuid,
+}
+
+/// `POST /api/v1/projects/{project_id}/pii/reveal` — resolve pseudonyms back
+/// to their originals from the project's encrypted vault (auth and the admin
+/// role check are handled by Next.js; the role is checked again here since
+/// this undoes redaction). Pseudonyms with no vault entry, e.g. recorded
+/// while the vault was disabled, are absent from `originals`. Every reveal is
+/// audit-logged.
+#[post("pii/reveal")]
+pub async fn reveal_pseudonyms(
+    path: web::Path<Uuid>,
+    body: web::Json<RevealPseudonymsRequest>,
+    db: web::Data<DB>,
+    cache: web::Data<Cache>,
+) -> ResponseResult {
+    let project_id = path.into_inner();
+    let RevealPseudonymsRequest {
+        pseudonyms,
+        user_id,
+    } = body.into_inner();
+    if pseudonyms.len() > MAX_REVEAL_PSEUDONYMS {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": format!("At most {MAX_REVEAL_PSEUDONYMS} pseudonyms can be revealed at once"),
+        })));
+    }
+
+    let db = db.into_inner();
+    let Some(info) =
+        get_workspace_info_for_project_id(db.clone(), cache.into_inner(), project_id).await?
+    else {
+        return Ok(HttpResponse::NotFound().json("Project not found"));
+    };
+    if !project_has_admin(&db.pool, &user_id, &project_id).await? {
+        return Ok(HttpResponse::Forbidden().json(json!({
+            "error": "Only workspace owners and admins can reveal pseudonyms",
+        })));
+    }
+
+    let originals =
+        vault::reveal_pseudonyms(&db, info.workspace_id, project_id, user_id, &pseudonyms).await?;
+
+    Ok(HttpResponse::Ok().json(json!({ "originals": originals })))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectPiiRequest {
+    pub values: Vec<Value>,
+    /// Policy to audit instead of the project's saved one.
+    #[serde(default)]
+    pub policy: Option<PiiRedactionPolicy>,
+}
+
+/// `POST /api/v1/projects/{project_id}/pii/detect` — dry run of redaction:
+/// what the pii-redactor would redact in each value under the project's
+/// policy (or the one in the body), without changing anything. Works whether
+/// or not the project has redaction turned on, so the policy can be audited
+/// first. Findings carry the label, score, JSON Pointer and byte offsets,
+/// never the matched text.
+#[post("pii/detect")]
+pub async fn detect_pii(
+    path: web::Path<Uuid>,
+    body: web::Json<DetectPiiRequest>,
+    db: web::Data<DB>,
+    cache: web::Data<Cache>,
+    pii_redactor: web::Data<Option<PiiRedactorClient>>,
+) -> ResponseResult {
+    let project_id = path.into_inner();
+    let DetectPiiRequest { values, policy } = body.into_inner();
+    if values.len() > MAX_DETECT_VALUES {
+        return Ok(HttpResponse::BadRequest().json(json!({
+            "error": format!("At most {MAX_DETECT_VALUES} values can be checked at once"),
+        })));
+    }
+    let Some(client) = pii_redactor.as_ref() else {
+        return Ok(HttpResponse::ServiceUnavailable().json(json!({
+            "error": "PII redactor is not configured",
+        })));
+    };
+
+    let Some(info) =
+        get_workspace_info_for_project_id(db.into_inner(), cache.into_inner(), project_id).await?
+    else {
+        return Ok(HttpResponse::NotFound().json("Project not found"));
+    };
+    let policy = policy.unwrap_or(info.settings.pii_redaction_policy);
+
+    let texts = values.iter().map(Value::to_string).collect();
+    let results = client.detect(texts, policy).await?;
+
+    let results: Vec<Value> = results
+        .into_iter()
+        .map(|text| {
+            let findings: Vec<Value> = text
+                .findings
+                .into_iter()
+                .map(|finding| {
+                    let detector = Detector::try_from(finding.detector)
+                        .unwrap_or(Detector::Model)
+                        .as_str_name()
+                        .to_lowercase();
+                    json!({
+                        "label": finding.label,
+                        "score": finding.score,
+                        "pointer": finding.pointer,
+                        "start": finding.start,
+                        "end": finding.end,
+                        "detector": detector,
+                    })
+                })
+                .collect();
+            json!({ "findings": findings })
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(json!({ "results": results })))
+}
@@ -61,6 +61,7 @@ pub async fn create_span(
         end_time: request.end_time,
         status: None,
         events: vec![],
+        links: vec![],
         tags: None,
         input_url: None,
         output_url: None,
@@ -0,0 +1,164 @@
+use std::collections::{HashMap, HashSet};
+
+use actix_web::{HttpResponse, get, web};
+use chrono::{DateTime, TimeZone, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    ch::{spans::get_span_links_for_traces, traces::get_trace_summaries},
+    routes::ResponseResult,
+};
+
+/// How many link hops to follow from the requested trace.
+const MAX_GRAPH_DEPTH: usize = 5;
+/// Traces are added in BFS order until this many are in the graph.
+const MAX_GRAPH_TRACES: usize = 50;
+/// Links are searched on spans that started within this margin of the
+/// traces found so far, since a consumer can pick a message up long after
+/// its producer ran. Bounds each hop's scan to a few partitions.
+const LINK_SEARCH_MARGIN_NS: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceGraphNode {
+    id: Uuid,
+    /// Link hops from the requested trace.
+    depth: usize,
+    /// `None` for traces that are linked to but were never ingested.
+    top_span_name: Option<String>,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+    status: Option<String>,
+    total_cost: Option<f64>,
+    num_spans: Option<u64>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceGraphLink {
+    trace_id: Uuid,
+    span_id: Uuid,
+    linked_trace_id: Uuid,
+    linked_span_id: Uuid,
+    attributes: Value,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TraceGraphResponse {
+    traces: Vec<TraceGraphNode>,
+    links: Vec<TraceGraphLink>,
+    /// Whether the depth or size limit was hit, so more linked traces may
+    /// exist than are returned.
+    truncated: bool,
+}
+
+/// Traces reachable from `trace_id` through span links in either direction,
+/// e.g. a producer trace and the consumer traces that link back to it.
+#[get("traces/{trace_id}/graph")]
+pub async fn get_trace_graph(
+    path: web::Path<(Uuid, Uuid)>,
+    clickhouse: web::Data<clickhouse::Client>,
+) -> ResponseResult {
+    let (project_id, trace_id) = path.into_inner();
+    let clickhouse = clickhouse.as_ref().clone();
+
+    let mut depths = HashMap::from([(trace_id, 0)]);
+    let mut seen_links = HashSet::new();
+    let mut links = Vec::new();
+    let mut frontier = vec![trace_id];
+    let mut truncated = false;
+
+    // Time range covered by the graph so far, in nanoseconds. A trace that
+    // was never ingested can still be linked to, so fall back to "now".
+    let mut window = match get_trace_summaries(clickhouse.clone(), project_id, vec![trace_id])
+        .await?
+        .first()
+    {
+        Some(summary) => (summary.start_time, summary.end_time.max(summary.start_time)),
+        None => {
+            let now = Utc::now().timestamp_nanos_opt().unwrap_or(i64::MAX);
+            (now, now)
+        }
+    };
+
+    for depth in 1..=MAX_GRAPH_DEPTH {
+        if frontier.is_empty() {
+            break;
+        }
+        let rows = get_span_links_for_traces(
+            clickhouse.clone(),
+            project_id,
+            frontier,
+            window.0.saturating_sub(LINK_SEARCH_MARGIN_NS),
+            window.1.saturating_add(LINK_SEARCH_MARGIN_NS),
+        )
+        .await?;
+        frontier = Vec::new();
+        for row in &rows {
+            window = (
+                window.0.min(row.span_start_time),
+                window.1.max(row.span_start_time),
+            );
+        }
+        for row in rows {
+            for next in [row.trace_id, row.linked_trace_id] {
+                if depths.contains_key(&next) {
+                    continue;
+                }
+                if depths.len() >= MAX_GRAPH_TRACES {
+                    truncated = true;
+                    continue;
+                }
+                depths.insert(next, depth);
+                frontier.push(next);
+            }
+            // Only keep links whose both ends made it into the graph
+            if !depths.contains_key(&row.trace_id) || !depths.contains_key(&row.linked_trace_id) {
+                continue;
+            }
+            if seen_links.insert((row.span_id, row.linked_span_id)) {
+                links.push(TraceGraphLink {
+                    trace_id: row.trace_id,
+                    span_id: row.span_id,
+                    linked_trace_id: row.linked_trace_id,
+                    linked_span_id: row.linked_span_id,
+                    attributes: serde_json::from_str(&row.attributes).unwrap_or(Value::Null),
+                });
+            }
+        }
+    }
+    truncated |= !frontier.is_empty();
+
+    let summaries = get_trace_summaries(clickhouse, project_id, depths.keys().copied().collect())
+        .await?
+        .into_iter()
+        .map(|summary| (summary.id, summary))
+        .collect::<HashMap<_, _>>();
+
+    let mut traces = depths
+        .into_iter()
+        .map(|(id, depth)| {
+            let summary = summaries.get(&id);
+            TraceGraphNode {
+                id,
+                depth,
+                top_span_name: summary.map(|s| s.top_span_name.clone()),
+                start_time: summary.map(|s| Utc.timestamp_nanos(s.start_time)),
+                end_time: summary.map(|s| Utc.timestamp_nanos(s.end_time)),
+                status: summary.map(|s| s.status.clone()),
+                total_cost: summary.map(|s| s.total_cost),
+                num_spans: summary.map(|s| s.num_spans),
+            }
+        })
+        .collect::<Vec<_>>();
+    traces.sort_by(|a, b| a.depth.cmp(&b.depth).then(a.start_time.cmp(&b.start_time)));
+
+    Ok(HttpResponse::Ok().json(TraceGraphResponse {
+        traces,
+        links,
+        truncated,
+    }))
+}
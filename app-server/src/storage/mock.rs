@@ -2,6 +2,9 @@ use std::pin::Pin;
 
 use anyhow::Result;
 use async_trait::async_trait;
+use futures_util::StreamExt;
+
+use super::UploadStream;
 
 pub struct MockStorage;
 
@@ -19,4 +22,12 @@ impl super::StorageTrait for MockStorage {
     async fn get_size(&self, _bucket: &str, _key: &str) -> Result<u64> {
         Ok(0)
     }
+
+    async fn put_stream(&self, _bucket: &str, _key: &str, mut body: UploadStream) -> Result<u64> {
+        let mut size = 0;
+        while let Some(chunk) = body.next().await {
+            size += chunk?.len() as u64;
+        }
+        Ok(size)
+    }
 }
@@ -1,3 +1,5 @@
+use std::pin::Pin;
+
 use anyhow::Result;
 use async_trait::async_trait;
 use enum_delegate;
@@ -8,6 +10,10 @@ pub mod s3;
 use mock::MockStorage;
 use s3::S3Storage;
 
+/// A fallible byte stream written to storage.
+pub type UploadStream =
+    Pin<Box<dyn futures_util::stream::Stream<Item = Result<bytes::Bytes>> + Send + 'static>>;
+
 #[enum_delegate::implement(StorageTrait)]
 pub enum Storage {
     Mock(MockStorage),
@@ -20,4 +26,6 @@ pub trait StorageTrait {
     type StorageBytesStream: futures_util::stream::Stream<Item = bytes::Bytes>;
     async fn get_stream(&self, bucket: &str, key: &str) -> Result<Self::StorageBytesStream>;
     async fn get_size(&self, bucket: &str, key: &str) -> Result<u64>;
+    /// Writes the stream to `key` and returns the number of bytes written.
+    async fn put_stream(&self, bucket: &str, key: &str, body: UploadStream) -> Result<u64>;
 }
@@ -1,8 +1,19 @@
 use anyhow::Result;
 use async_trait::async_trait;
-use aws_sdk_s3::Client;
+use aws_sdk_s3::{
+    Client,
+    primitives::ByteStream,
+    types::{CompletedMultipartUpload, CompletedPart},
+};
+use bytes::BytesMut;
+use futures_util::StreamExt;
 use std::pin::Pin;
 
+use super::UploadStream;
+
+/// S3 rejects multipart parts smaller than 5 MiB, except for the last one.
+const MIN_PART_SIZE: usize = 8 * 1024 * 1024;
+
 #[derive(Clone)]
 pub struct S3Storage {
     client: Client,
@@ -12,6 +23,52 @@ impl S3Storage {
     pub fn new(client: Client) -> Self {
         Self { client }
     }
+
+    async fn upload_parts(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        mut body: UploadStream,
+    ) -> Result<(Vec<CompletedPart>, u64)> {
+        let mut parts = Vec::new();
+        let mut buffer = BytesMut::new();
+        let mut size = 0;
+
+        loop {
+            let chunk = body.next().await.transpose()?;
+            let done = chunk.is_none();
+            if let Some(chunk) = chunk {
+                size += chunk.len() as u64;
+                buffer.extend_from_slice(&chunk);
+            }
+
+            // An empty body still needs one (empty) part to complete the upload.
+            if buffer.len() >= MIN_PART_SIZE || (done && (!buffer.is_empty() || parts.is_empty())) {
+                let part_number = parts.len() as i32 + 1;
+                let response = self
+                    .client
+                    .upload_part()
+                    .bucket(bucket)
+                    .key(key)
+                    .upload_id(upload_id)
+                    .part_number(part_number)
+                    .body(ByteStream::from(buffer.split().freeze()))
+                    .send()
+                    .await?;
+                parts.push(
+                    CompletedPart::builder()
+                        .part_number(part_number)
+                        .set_e_tag(response.e_tag)
+                        .build(),
+                );
+            }
+
+            if done {
+                return Ok((parts, size));
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -51,4 +108,54 @@ impl super::StorageTrait for S3Storage {
             .ok_or(anyhow::anyhow!("Content length not found"))
             .map(|l| l as u64)
     }
+
+    async fn put_stream(&self, bucket: &str, key: &str, body: UploadStream) -> Result<u64> {
+        let upload = self
+            .client
+            .create_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .send()
+            .await?;
+        let upload_id = upload
+            .upload_id
+            .ok_or(anyhow::anyhow!("Multipart upload id not found"))?;
+
+        let (parts, size) = match self.upload_parts(bucket, key, &upload_id, body).await {
+            Ok(uploaded) => uploaded,
+            Err(e) => {
+                if let Err(abort_error) = self
+                    .client
+                    .abort_multipart_upload()
+                    .bucket(bucket)
+                    .key(key)
+                    .upload_id(&upload_id)
+                    .send()
+                    .await
+                {
+                    log::warn!(
+                        "Failed to abort multipart upload of {}: {:?}",
+                        key,
+                        abort_error
+                    );
+                }
+                return Err(e);
+            }
+        };
+
+        self.client
+            .complete_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .upload_id(&upload_id)
+            .multipart_upload(
+                CompletedMultipartUpload::builder()
+                    .set_parts(Some(parts))
+                    .build(),
+            )
+            .send()
+            .await?;
+
+        Ok(size)
+    }
 }
@@ -12,6 +12,7 @@ use crate::{
     opentelemetry_proto::opentelemetry::proto::collector::trace::v1::{
         ExportTraceServiceRequest, ExportTraceServiceResponse, trace_service_server::TraceService,
     },
+    project_api_keys::restrictions::ApiKeyScope,
     utils::limits::get_workspace_bytes_limit_exceeded,
 };
 use tonic::{Request, Response, Status};
@@ -50,9 +51,13 @@ impl TraceService for ProcessTracesService {
         &self,
         request: Request<ExportTraceServiceRequest>,
     ) -> Result<Response<ExportTraceServiceResponse>, Status> {
-        let api_key = authenticate_request(request.metadata(), &self.db.pool, self.cache.clone())
-            .await
-            .map_err(|_| Status::unauthenticated("Failed to authenticate request"))?;
+        let api_key = authenticate_request(
+            &request,
+            ApiKeyScope::IngestTraces,
+            &self.db.pool,
+            self.cache.clone(),
+        )
+        .await?;
         let project_id = api_key.project_id;
         let request = request.into_inner();
 
@@ -60,6 +60,7 @@ pub async fn publish_trace_metadata_patch(
         end_time: now,
         status: None,
         events: vec![],
+        links: vec![],
         tags: None,
         input_url: None,
         output_url: None,
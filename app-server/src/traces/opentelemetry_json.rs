@@ -49,7 +49,7 @@ struct ResourceSpansJson {
 
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase", default)]
-struct ResourceJson {
+pub(crate) struct ResourceJson {
     attributes: Vec<KeyValueJson>,
     dropped_attributes_count: u32,
 }
@@ -64,7 +64,7 @@ struct ScopeSpansJson {
 
 #[derive(Deserialize, Default)]
 #[serde(rename_all = "camelCase", default)]
-struct InstrumentationScopeJson {
+pub(crate) struct InstrumentationScopeJson {
     name: String,
     version: String,
     attributes: Vec<KeyValueJson>,
@@ -126,7 +126,7 @@ struct StatusJson {
 }
 
 #[derive(Deserialize)]
-struct KeyValueJson {
+pub(crate) struct KeyValueJson {
     key: String,
     #[serde(default)]
     value: Option<AnyValueJson>,
@@ -134,7 +134,7 @@ struct KeyValueJson {
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
-enum AnyValueJson {
+pub(crate) enum AnyValueJson {
     #[serde(rename = "stringValue")]
     String(String),
     #[serde(rename = "boolValue")]
@@ -155,13 +155,13 @@ enum AnyValueJson {
 }
 
 #[derive(Deserialize, Default)]
-struct ArrayValueJson {
+pub(crate) struct ArrayValueJson {
     #[serde(default)]
     values: Vec<AnyValueJson>,
 }
 
 #[derive(Deserialize, Default)]
-struct KeyValueListJson {
+pub(crate) struct KeyValueListJson {
     #[serde(default)]
     values: Vec<KeyValueJson>,
 }
@@ -365,7 +365,7 @@ fn decode_bytes_value_lenient(s: &str) -> Vec<u8> {
 /// clean 400. `allow_empty` is true only for `parent_span_id`, which is the sole
 /// legitimately-absent id (root spans); the consumer guards that one with an
 /// `is_empty()` check before converting to a `Uuid`.
-fn decode_id_field(
+pub(crate) fn decode_id_field(
     field: &'static str,
     s: &str,
     expected_len: usize,
@@ -403,7 +403,9 @@ fn decode_id_field(
 
 /// `fixed64` is JSON-encoded as a decimal string per OTLP/JSON spec; some
 /// senders emit raw numbers. Accept both.
-fn deserialize_u64_or_string<'de, D: serde::Deserializer<'de>>(d: D) -> Result<u64, D::Error> {
+pub(crate) fn deserialize_u64_or_string<'de, D: serde::Deserializer<'de>>(
+    d: D,
+) -> Result<u64, D::Error> {
     use serde::de::Error;
     #[derive(Deserialize)]
     #[serde(untagged)]
@@ -419,7 +421,7 @@ fn deserialize_u64_or_string<'de, D: serde::Deserializer<'de>>(d: D) -> Result<u
 }
 
 /// `int64` attribute values are spec-encoded as strings; accept numbers too.
-fn deserialize_i64_or_string_field<'de, D: serde::Deserializer<'de>>(
+pub(crate) fn deserialize_i64_or_string_field<'de, D: serde::Deserializer<'de>>(
     d: D,
 ) -> Result<i64, D::Error> {
     use serde::de::Error;
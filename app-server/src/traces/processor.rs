@@ -13,6 +13,7 @@ use crate::{
     ch::{
         ClickhouseTrait,
         deduped_content::CHDedupedContent,
+        span_metrics::CHSpanMetricsRollup,
         spans::CHSpan,
         traces::{CHTrace, TraceAggregation},
     },
@@ -39,6 +40,8 @@ use crate::{
             RealtimeDebuggerTrace, RealtimeTrace, TraceChannel, channels_for_trace,
             send_span_updates, send_trace_updates,
         },
+        sampling::{TailSamplingResult, apply_tail_sampling},
+        spans::SpanUsage,
         tool_dedup::{ToolDedup, resolve_tool_dedup},
         utils::{get_llm_usage_for_span, prepare_span_for_recording},
     },
@@ -129,11 +132,14 @@ pub async fn process_span_messages(
     // and `convert_span_to_provider_format` for `pre_processed` messages.
     // Re-running on the consumer would double-apply the LangChain rewrite
     // and double-copy attributes into `span.input`, breaking dedup identity.
+    // Conversion runs before usage extraction, same as on the producer, because
+    // framework converters normalize model and token attributes into `gen_ai.*`.
     let mut messages: Vec<RabbitMqSpanMessage> = messages
         .into_par_iter()
         .map(|mut message| {
             if !message.pre_processed {
                 message.span.parse_and_enrich_attributes();
+                convert_span_to_provider_format(&mut message.span);
             }
             message
         })
@@ -185,13 +191,11 @@ pub async fn process_span_messages(
             cache.clone(),
             &m.span.name,
             &m.span.project_id,
+            m.span.start_time,
         )
         .await;
 
         prepare_span_for_recording(&mut m.span, &span_usage);
-        if !m.pre_processed {
-            convert_span_to_provider_format(&mut m.span);
-        }
         // `estimate_size_bytes_no_payload` is deferred until AFTER PII redaction
         // (post-dedup loop below) so the recorded size reflects the
         // redacted output.
@@ -199,6 +203,16 @@ pub async fn process_span_messages(
         span_usage_vec.push(span_usage);
     }
 
+    // Tail-based sampling for projects with `traceSamplingRules`. Spans of
+    // undecided traces are held in the cache and spans of dropped traces are
+    // discarded here, so neither reaches storage or the bytes-ingested billing
+    // below. Released buffered spans are spliced back in.
+    let TailSamplingResult {
+        messages,
+        span_usage_vec,
+        decisions: sampling_decisions,
+    } = apply_tail_sampling(messages, span_usage_vec, db.clone(), cache.clone()).await;
+
     // Split into parallel `Vec`s — downstream code reads `spans`, `dedups`
     // (input messages), `output_dedups`, and `tool_dedups` as separate slices
     // keyed by index. All three dedup paths share the project-scoped
@@ -230,7 +244,12 @@ pub async fn process_span_messages(
         (a, b, c)
     };
 
-    let trace_aggregations = TraceAggregation::from_spans(&spans, &span_usage_vec);
+    let mut trace_aggregations = TraceAggregation::from_spans(spans.iter().zip(&span_usage_vec));
+    for agg in trace_aggregations.iter_mut() {
+        agg.sampling_decision = sampling_decisions
+            .get(&(agg.project_id, agg.trace_id))
+            .and_then(|decision| serde_json::to_value(decision).ok());
+    }
 
     // Build the unified dedup batch up front so the size-bytes loop and
     // CHSpans build can run before we kick off the parallel inserts. Input,
@@ -412,6 +431,17 @@ pub async fn process_span_messages(
             .collect()
     };
 
+    // Per-minute rollups for the `span_metrics` table, over the same spans
+    // that are recorded to the `spans` table.
+    let span_metrics_rollups: Vec<CHSpanMetricsRollup> = {
+        let recordable_spans: Vec<&Span> = recordable_indices.iter().map(|&i| &spans[i]).collect();
+        let recordable_usages: Vec<&SpanUsage> = recordable_indices
+            .iter()
+            .map(|&i| &span_usage_vec[i])
+            .collect();
+        CHSpanMetricsRollup::from_spans(&recordable_spans, &recordable_usages)
+    };
+
     // Parallelize trace upsert against the span path. Within the span path
     // the strict order llm_messages -> mark_seen -> spans must be preserved
     // (`spans` is plain MergeTree, so a retry after a successful spans
@@ -593,6 +623,18 @@ pub async fn process_span_messages(
             )));
         }
 
+        // Rollups are derived data: a failed insert is logged, not retried,
+        // because retrying the batch would duplicate the spans just written.
+        if !span_metrics_rollups.is_empty() {
+            if let Err(e) = ch.insert_batch(&span_metrics_rollups, config).await {
+                log::error!(
+                    "Failed to record {} span metrics rollups to clickhouse: {:?}",
+                    span_metrics_rollups.len(),
+                    e
+                );
+            }
+        }
+
         if !storage_keys.is_empty() || !trace_new_keys.is_empty() {
             mark_seen(&storage_keys, &trace_new_keys, cache.clone()).await;
         }
@@ -737,7 +779,8 @@ pub async fn process_span_messages(
         .await;
     }
 
-    // Update usage limits per project
+    // Update usage limits per project. `spans` only holds spans of kept or
+    // unsampled traces, so held and dropped spans are never billed.
     if is_feature_enabled(Feature::UsageLimit) {
         let mut bytes_per_project: HashMap<Uuid, usize> = HashMap::new();
         for span in &spans {
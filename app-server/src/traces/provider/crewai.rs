@@ -0,0 +1,175 @@
+//! Convert CrewAI LLM spans emitted by the OpenLLMetry CrewAI instrumentation.
+//!
+//! `LLM.call` is wrapped in a `{model}.llm` span that describes the LLM object as
+//! `crewai.llm.*` attributes (`crewai.llm.model`, `crewai.llm.temperature`, ...).
+//! The call arguments and result end up in `traceloop.entity.input` /
+//! `traceloop.entity.output`, which become the span input and output before
+//! conversion. The input is a list of `{role, content}` messages or a bare prompt
+//! string, and the output is the completion text.
+//!
+//! CrewAI routes calls through LiteLLM, so the model is usually `provider/model`;
+//! the prefix is split off into `gen_ai.system`.
+
+use serde_json::Value;
+
+use super::{SpanConverter, openinference::parse_json_string, set_provider_prefixed_model};
+use crate::{
+    db::spans::{Span, SpanType},
+    language_model::{ChatMessage, ChatMessageContent},
+    traces::spans::input_chat_messages_from_json,
+    utils::json_value_to_string,
+};
+
+const CREWAI_LLM_ATTRIBUTE_PREFIX: &str = "crewai.llm.";
+const CREWAI_LLM_MODEL: &str = "crewai.llm.model";
+
+pub struct CrewAIConverter;
+
+impl SpanConverter for CrewAIConverter {
+    fn detect(&self, span: &Span) -> bool {
+        span.name.ends_with(".llm")
+            && span
+                .attributes
+                .raw_attributes
+                .keys()
+                .any(|key| key.starts_with(CREWAI_LLM_ATTRIBUTE_PREFIX))
+    }
+
+    fn convert(&self, span: &mut Span) {
+        if let Some(Value::String(model)) = span.attributes.raw_attributes.get(CREWAI_LLM_MODEL) {
+            let model = model.clone();
+            set_provider_prefixed_model(&mut span.attributes.raw_attributes, &model);
+        }
+
+        if let Some(input) = span.input.take().map(parse_json_string) {
+            span.input = Some(match input_messages(&input) {
+                Some(messages) => serde_json::to_value(messages).unwrap_or_default(),
+                None => input,
+            });
+        }
+        if let Some(output) = span.output.take().map(parse_json_string) {
+            span.output = Some(match output {
+                Value::Null => Value::Null,
+                output => serde_json::to_value(vec![ChatMessage {
+                    role: "assistant".to_string(),
+                    content: ChatMessageContent::Text(json_value_to_string(&output)),
+                    tool_call_id: None,
+                }])
+                .unwrap_or_default(),
+            });
+        }
+
+        span.span_type = SpanType::LLM;
+    }
+}
+
+fn input_messages(input: &Value) -> Option<Vec<ChatMessage>> {
+    let messages = input.get("messages").unwrap_or(input);
+    match messages {
+        Value::Array(_) => input_chat_messages_from_json(messages)
+            .map_err(|e| log::warn!("Failed to parse CrewAI LLM messages: {}", e))
+            .ok(),
+        Value::String(prompt) => Some(vec![ChatMessage {
+            role: "user".to_string(),
+            content: ChatMessageContent::Text(prompt.clone()),
+            tool_call_id: None,
+        }]),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::traces::{provider::convert_span_to_provider_format, spans::SpanAttributes};
+
+    fn make_span(name: &str, attributes: HashMap<String, Value>) -> Span {
+        let mut span = Span {
+            name: name.to_string(),
+            attributes: SpanAttributes::new(attributes),
+            ..Default::default()
+        };
+        span.span_type = span.attributes.span_type();
+        span.parse_and_enrich_attributes();
+        span
+    }
+
+    #[test]
+    fn test_convert_crewai_llm_span() {
+        let input = json!([
+            {"role": "system", "content": "You are Researcher. Find facts."},
+            {"role": "user", "content": "Current Task: list three facts about otters"}
+        ]);
+        let mut span = make_span(
+            "anthropic/claude-3-5-sonnet-20241022.llm",
+            HashMap::from([
+                (
+                    "crewai.llm.model".to_string(),
+                    json!("anthropic/claude-3-5-sonnet-20241022"),
+                ),
+                ("crewai.llm.temperature".to_string(), json!(0.7)),
+                (
+                    "traceloop.entity.input".to_string(),
+                    json!(input.to_string()),
+                ),
+                (
+                    "traceloop.entity.output".to_string(),
+                    json!("Thought: I now know the final answer\nFinal Answer: ..."),
+                ),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(span.span_type, SpanType::LLM);
+        assert_eq!(span.input, Some(input));
+        assert_eq!(
+            span.output,
+            Some(json!([{
+                "role": "assistant",
+                "content": "Thought: I now know the final answer\nFinal Answer: ..."
+            }]))
+        );
+        assert_eq!(
+            span.attributes.request_model(),
+            Some("claude-3-5-sonnet-20241022".to_string())
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("anthropic".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_crewai_prompt_string_input() {
+        let mut span = make_span(
+            "gpt-4o.llm",
+            HashMap::from([
+                ("crewai.llm.model".to_string(), json!("gpt-4o")),
+                ("traceloop.entity.input".to_string(), json!("Say hi")),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(
+            span.input,
+            Some(json!([{"role": "user", "content": "Say hi"}]))
+        );
+        assert_eq!(span.attributes.request_model(), Some("gpt-4o".to_string()));
+        assert_eq!(span.output, None);
+    }
+
+    #[test]
+    fn test_does_not_detect_crewai_task_span() {
+        let span = make_span(
+            "Research otters.task",
+            HashMap::from([("crewai.task.id".to_string(), json!("1"))]),
+        );
+        assert!(!CrewAIConverter.detect(&span));
+    }
+}
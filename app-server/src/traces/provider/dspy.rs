@@ -0,0 +1,215 @@
+//! Convert DSPy LM spans emitted by the OpenInference DSPy instrumentation.
+//!
+//! DSPy wraps every language model call in `LM.__call__` (or `<Subclass>.__call__`
+//! for custom clients). Instead of flattened messages, the span carries the call
+//! arguments as JSON in `input.value`, either `{"messages": [...]}` for chat calls
+//! or `{"prompt": "..."}` for completion calls, and the list of completions
+//! in `output.value`. Completions are strings, or objects with a `text` field
+//! when the LM returns logprobs or tool calls.
+//!
+//! DSPy routes calls through LiteLLM, so `llm.model_name` is usually
+//! `provider/model`; the prefix is split off into `gen_ai.system`.
+
+use serde_json::Value;
+
+use super::{
+    SpanConverter,
+    openinference::{
+        INPUT_VALUE, LLM_MODEL_NAME, OUTPUT_VALUE, convert_llm_span, is_openinference_llm_span,
+        parse_json_string,
+    },
+    set_provider_prefixed_model,
+};
+use crate::{
+    db::spans::Span,
+    language_model::{ChatMessage, ChatMessageContent},
+    traces::spans::input_chat_messages_from_json,
+    utils::json_value_to_string,
+};
+
+pub struct DSPyConverter;
+
+impl SpanConverter for DSPyConverter {
+    fn detect(&self, span: &Span) -> bool {
+        is_openinference_llm_span(span)
+            && span.name.ends_with(".__call__")
+            && span.attributes.raw_attributes.contains_key(INPUT_VALUE)
+    }
+
+    fn convert(&self, span: &mut Span) {
+        if let Some(Value::String(model)) = span.attributes.raw_attributes.get(LLM_MODEL_NAME) {
+            let model = model.clone();
+            set_provider_prefixed_model(&mut span.attributes.raw_attributes, &model);
+        }
+        convert_llm_span(span);
+
+        let attributes = &mut span.attributes.raw_attributes;
+        if span.input.is_none()
+            && let Some(input) = attributes.remove(INPUT_VALUE).map(parse_json_string)
+        {
+            span.input =
+                input_messages(&input).map(|m| serde_json::to_value(m).unwrap_or_default());
+        }
+        if span.output.is_none()
+            && let Some(output) = attributes.remove(OUTPUT_VALUE).map(parse_json_string)
+        {
+            span.output = Some(serde_json::to_value(output_messages(output)).unwrap_or_default());
+        }
+    }
+}
+
+fn input_messages(input: &Value) -> Option<Vec<ChatMessage>> {
+    if let Some(messages) = input.get("messages").filter(|m| m.is_array()) {
+        return input_chat_messages_from_json(messages)
+            .map_err(|e| log::warn!("Failed to parse DSPy LM messages: {}", e))
+            .ok();
+    }
+    input.get("prompt").filter(|p| !p.is_null()).map(|prompt| {
+        vec![ChatMessage {
+            role: "user".to_string(),
+            content: ChatMessageContent::Text(json_value_to_string(prompt)),
+            tool_call_id: None,
+        }]
+    })
+}
+
+fn output_messages(output: Value) -> Vec<ChatMessage> {
+    let completions = match output {
+        Value::Array(completions) => completions,
+        other => vec![other],
+    };
+    completions
+        .into_iter()
+        .map(|completion| {
+            let text = match completion.get("text") {
+                Some(text) => json_value_to_string(text),
+                None => json_value_to_string(&completion),
+            };
+            ChatMessage {
+                role: "assistant".to_string(),
+                content: ChatMessageContent::Text(text),
+                tool_call_id: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        db::spans::SpanType,
+        traces::{provider::convert_span_to_provider_format, spans::SpanAttributes},
+    };
+
+    fn make_span(name: &str, attributes: HashMap<String, Value>) -> Span {
+        let mut span = Span {
+            name: name.to_string(),
+            attributes: SpanAttributes::new(attributes),
+            ..Default::default()
+        };
+        span.span_type = span.attributes.span_type();
+        span.parse_and_enrich_attributes();
+        span
+    }
+
+    #[test]
+    fn test_convert_dspy_chat_span() {
+        let input = json!({
+            "prompt": null,
+            "messages": [
+                {"role": "system", "content": "Your input fields are:\n1. `question` (str)"},
+                {"role": "user", "content": "[[ ## question ## ]]\nWhat is 2 + 2?"}
+            ],
+            "kwargs": {}
+        });
+        let mut span = make_span(
+            "LM.__call__",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                ("llm.model_name".to_string(), json!("openai/gpt-4o-mini")),
+                ("input.value".to_string(), json!(input.to_string())),
+                ("input.mime_type".to_string(), json!("application/json")),
+                (
+                    "output.value".to_string(),
+                    json!("[\"[[ ## answer ## ]]\\n4\\n\\n[[ ## completed ## ]]\"]"),
+                ),
+                ("output.mime_type".to_string(), json!("application/json")),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(span.span_type, SpanType::LLM);
+        assert_eq!(
+            span.input,
+            Some(json!([
+                {"role": "system", "content": "Your input fields are:\n1. `question` (str)"},
+                {"role": "user", "content": "[[ ## question ## ]]\nWhat is 2 + 2?"}
+            ]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([
+                {"role": "assistant", "content": "[[ ## answer ## ]]\n4\n\n[[ ## completed ## ]]"}
+            ]))
+        );
+        assert_eq!(
+            span.attributes.request_model(),
+            Some("gpt-4o-mini".to_string())
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("openai".to_string())
+        );
+        assert!(!span.attributes.raw_attributes.contains_key("input.value"));
+    }
+
+    #[test]
+    fn test_convert_dspy_prompt_span_with_object_completions() {
+        let mut span = make_span(
+            "LM.__call__",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                (
+                    "input.value".to_string(),
+                    json!("{\"prompt\": \"Translate to French: cat\"}"),
+                ),
+                (
+                    "output.value".to_string(),
+                    json!("[{\"text\": \"chat\", \"logprobs\": null}]"),
+                ),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(
+            span.input,
+            Some(json!([{"role": "user", "content": "Translate to French: cat"}]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([{"role": "assistant", "content": "chat"}]))
+        );
+    }
+
+    #[test]
+    fn test_does_not_detect_dspy_module_span() {
+        let span = make_span(
+            "ChainOfThought.forward",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("CHAIN")),
+                (
+                    "input.value".to_string(),
+                    json!("{\"question\": \"2 + 2\"}"),
+                ),
+            ]),
+        );
+        assert!(!DSPyConverter.detect(&span));
+    }
+}
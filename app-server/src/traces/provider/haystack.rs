@@ -0,0 +1,382 @@
+//! Convert Haystack generator spans emitted by Haystack's built-in OpenTelemetry tracer.
+//!
+//! Every pipeline component run becomes a span with `haystack.component.type`
+//! (e.g. `OpenAIChatGenerator`). With content tracing enabled, the run inputs and
+//! outputs are attached as JSON in `haystack.component.input` and
+//! `haystack.component.output`:
+//! - chat generators take `{"messages": [ChatMessage]}` and return
+//!   `{"replies": [ChatMessage]}`, with the model and usage in each reply's `meta`
+//! - plain generators take `{"prompt": "...", "system_prompt": "..."}` and return
+//!   `{"replies": ["..."], "meta": [{...}]}`
+//!
+//! Serialized Haystack `ChatMessage`s come in two shapes depending on the version:
+//! `{"role", "content": [{"text"}, {"tool_call"}, {"tool_call_result"}]}` and the
+//! older `{"_role", "_content": [...]}`. Plain string content is accepted too.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{
+    SpanConverter,
+    openinference::{as_i64, insert_if_absent, parse_json_string},
+};
+use crate::{
+    db::spans::{Span, SpanType},
+    language_model::{
+        ChatMessage, ChatMessageContent, ChatMessageContentPart, ChatMessageText,
+        ChatMessageToolCall,
+    },
+    traces::span_attributes::{
+        GEN_AI_INPUT_TOKENS, GEN_AI_OUTPUT_TOKENS, GEN_AI_RESPONSE_MODEL, GEN_AI_SYSTEM,
+    },
+    utils::json_value_to_string,
+};
+
+const HAYSTACK_COMPONENT_TYPE: &str = "haystack.component.type";
+const HAYSTACK_COMPONENT_INPUT: &str = "haystack.component.input";
+const HAYSTACK_COMPONENT_OUTPUT: &str = "haystack.component.output";
+
+pub struct HaystackConverter;
+
+impl SpanConverter for HaystackConverter {
+    fn detect(&self, span: &Span) -> bool {
+        component_type(span).is_some_and(|t| t.ends_with("Generator"))
+    }
+
+    fn convert(&self, span: &mut Span) {
+        let provider = component_type(span).and_then(provider_from_component_type);
+        let attributes = &mut span.attributes.raw_attributes;
+
+        if let Some(input) = attributes
+            .remove(HAYSTACK_COMPONENT_INPUT)
+            .map(parse_json_string)
+        {
+            let messages = input_messages(&input);
+            if !messages.is_empty() {
+                span.input = Some(serde_json::to_value(messages).unwrap_or_default());
+            }
+        }
+
+        if let Some(output) = attributes
+            .remove(HAYSTACK_COMPONENT_OUTPUT)
+            .map(parse_json_string)
+        {
+            let replies = output
+                .get("replies")
+                .and_then(|r| r.as_array())
+                .cloned()
+                .unwrap_or_default();
+            let messages: Vec<ChatMessage> = replies
+                .iter()
+                .map(|reply| chat_message(reply, "assistant"))
+                .collect();
+            if !messages.is_empty() {
+                span.output = Some(serde_json::to_value(messages).unwrap_or_default());
+            }
+
+            // Chat replies carry their own `meta`; plain generators return a parallel `meta` list.
+            let meta = replies
+                .iter()
+                .find_map(|reply| reply.get("meta").or(reply.get("_meta")))
+                .or(output.get("meta").and_then(|m| m.get(0)))
+                .cloned();
+            if let Some(meta) = meta {
+                set_usage_attributes(attributes, &meta);
+            }
+        }
+
+        if let Some(provider) = provider {
+            insert_if_absent(attributes, GEN_AI_SYSTEM, Value::String(provider));
+        }
+        span.span_type = SpanType::LLM;
+    }
+}
+
+fn component_type(span: &Span) -> Option<&str> {
+    span.attributes
+        .raw_attributes
+        .get(HAYSTACK_COMPONENT_TYPE)
+        .and_then(|v| v.as_str())
+}
+
+/// `OpenAIChatGenerator` -> `openai`, `AnthropicGenerator` -> `anthropic`.
+fn provider_from_component_type(component_type: &str) -> Option<String> {
+    let provider = component_type
+        .strip_suffix("ChatGenerator")
+        .or(component_type.strip_suffix("Generator"))?;
+    if provider.is_empty() {
+        return None;
+    }
+    Some(provider.to_lowercase())
+}
+
+fn input_messages(input: &Value) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+    if let Some(system_prompt) = input.get("system_prompt").filter(|p| !p.is_null()) {
+        messages.push(text_message("system", json_value_to_string(system_prompt)));
+    }
+    if let Some(chat_messages) = input.get("messages").and_then(|m| m.as_array()) {
+        messages.extend(chat_messages.iter().map(|m| chat_message(m, "user")));
+    } else if let Some(prompt) = input.get("prompt").filter(|p| !p.is_null()) {
+        messages.push(text_message("user", json_value_to_string(prompt)));
+    }
+    messages
+}
+
+fn text_message(role: &str, text: String) -> ChatMessage {
+    ChatMessage {
+        role: role.to_string(),
+        content: ChatMessageContent::Text(text),
+        tool_call_id: None,
+    }
+}
+
+fn chat_message(message: &Value, default_role: &str) -> ChatMessage {
+    if let Value::String(text) = message {
+        return text_message(default_role, text.clone());
+    }
+
+    let role = message
+        .get("role")
+        .or(message.get("_role"))
+        .map(json_value_to_string)
+        .unwrap_or(default_role.to_string());
+    let content = message.get("content").or(message.get("_content"));
+
+    let parts = match content {
+        Some(Value::Array(parts)) => parts,
+        Some(other) => return text_message(&role, json_value_to_string(other)),
+        None => return text_message(&role, String::new()),
+    };
+
+    let mut tool_call_id = None;
+    let mut content_parts = Vec::new();
+    for part in parts {
+        if let Some(text) = part.get("text") {
+            content_parts.push(ChatMessageContentPart::Text(ChatMessageText {
+                text: json_value_to_string(text),
+            }));
+        } else if let Some(tool_call) = part.get("tool_call") {
+            content_parts.push(ChatMessageContentPart::ToolCall(ChatMessageToolCall {
+                name: tool_call
+                    .get("tool_name")
+                    .map(json_value_to_string)
+                    .unwrap_or_default(),
+                id: tool_call
+                    .get("id")
+                    .filter(|id| !id.is_null())
+                    .map(json_value_to_string),
+                arguments: tool_call.get("arguments").cloned(),
+            }));
+        } else if let Some(result) = part.get("tool_call_result") {
+            tool_call_id = result
+                .get("origin")
+                .and_then(|origin| origin.get("id"))
+                .filter(|id| !id.is_null())
+                .map(json_value_to_string);
+            content_parts.push(ChatMessageContentPart::Text(ChatMessageText {
+                text: result
+                    .get("result")
+                    .map(json_value_to_string)
+                    .unwrap_or_default(),
+            }));
+        }
+    }
+
+    let content = match content_parts.as_slice() {
+        [ChatMessageContentPart::Text(text)] => ChatMessageContent::Text(text.text.clone()),
+        _ => ChatMessageContent::ContentPartList(content_parts),
+    };
+    ChatMessage {
+        role,
+        content,
+        tool_call_id,
+    }
+}
+
+fn set_usage_attributes(attributes: &mut HashMap<String, Value>, meta: &Value) {
+    if let Some(model) = meta.get("model").filter(|m| m.is_string()) {
+        insert_if_absent(attributes, GEN_AI_RESPONSE_MODEL, model.clone());
+    }
+    let Some(usage) = meta.get("usage") else {
+        return;
+    };
+    if let Some(tokens) = usage
+        .get("prompt_tokens")
+        .or(usage.get("input_tokens"))
+        .and_then(as_i64)
+    {
+        insert_if_absent(attributes, GEN_AI_INPUT_TOKENS, Value::from(tokens));
+    }
+    if let Some(tokens) = usage
+        .get("completion_tokens")
+        .or(usage.get("output_tokens"))
+        .and_then(as_i64)
+    {
+        insert_if_absent(attributes, GEN_AI_OUTPUT_TOKENS, Value::from(tokens));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::traces::{provider::convert_span_to_provider_format, spans::SpanAttributes};
+
+    fn make_span(name: &str, attributes: HashMap<String, Value>) -> Span {
+        let mut span = Span {
+            name: name.to_string(),
+            attributes: SpanAttributes::new(attributes),
+            ..Default::default()
+        };
+        span.span_type = span.attributes.span_type();
+        span.parse_and_enrich_attributes();
+        span
+    }
+
+    #[test]
+    fn test_convert_haystack_chat_generator_span() {
+        let input = json!({
+            "messages": [
+                {"role": "system", "content": [{"text": "You are a weather bot."}], "meta": {}, "name": null},
+                {"role": "user", "content": [{"text": "Weather in Berlin?"}], "meta": {}, "name": null},
+                {"role": "assistant", "content": [
+                    {"tool_call": {"tool_name": "weather", "arguments": {"city": "Berlin"}, "id": "call_9"}}
+                ], "meta": {}, "name": null},
+                {"role": "tool", "content": [
+                    {"tool_call_result": {
+                        "result": "12°C, cloudy",
+                        "origin": {"tool_name": "weather", "arguments": {"city": "Berlin"}, "id": "call_9"},
+                        "error": false
+                    }}
+                ], "meta": {}, "name": null}
+            ],
+            "generation_kwargs": null
+        });
+        let output = json!({
+            "replies": [{
+                "role": "assistant",
+                "content": [{"text": "It is 12°C and cloudy in Berlin."}],
+                "name": null,
+                "meta": {
+                    "model": "gpt-4o-mini-2024-07-18",
+                    "index": 0,
+                    "finish_reason": "stop",
+                    "usage": {"completion_tokens": 11, "prompt_tokens": 87, "total_tokens": 98}
+                }
+            }]
+        });
+        let mut span = make_span(
+            "haystack.component.run",
+            HashMap::from([
+                ("haystack.component.name".to_string(), json!("llm")),
+                (
+                    "haystack.component.type".to_string(),
+                    json!("OpenAIChatGenerator"),
+                ),
+                (
+                    "haystack.component.input".to_string(),
+                    json!(input.to_string()),
+                ),
+                (
+                    "haystack.component.output".to_string(),
+                    json!(output.to_string()),
+                ),
+            ]),
+        );
+        assert_eq!(span.span_type, SpanType::Default);
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(span.span_type, SpanType::LLM);
+        assert_eq!(
+            span.input,
+            Some(json!([
+                {"role": "system", "content": "You are a weather bot."},
+                {"role": "user", "content": "Weather in Berlin?"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_call", "name": "weather", "id": "call_9", "arguments": {"city": "Berlin"}}
+                ]},
+                {"role": "tool", "content": "12°C, cloudy", "tool_call_id": "call_9"}
+            ]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([
+                {"role": "assistant", "content": "It is 12°C and cloudy in Berlin."}
+            ]))
+        );
+        assert_eq!(
+            span.attributes.response_model(),
+            Some("gpt-4o-mini-2024-07-18".to_string())
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("openai".to_string())
+        );
+        assert_eq!(span.attributes.input_tokens().total(), 87);
+        assert_eq!(span.attributes.output_tokens(), 11);
+    }
+
+    #[test]
+    fn test_convert_haystack_legacy_generator_span() {
+        let input = json!({"prompt": "Summarize: ...", "system_prompt": "Be brief."});
+        let output = json!({
+            "replies": ["A summary."],
+            "meta": [{"model": "claude-3-5-haiku-20241022", "usage": {"input_tokens": 20, "output_tokens": 4}}]
+        });
+        let mut span = make_span(
+            "haystack.component.run",
+            HashMap::from([
+                (
+                    "haystack.component.type".to_string(),
+                    json!("AnthropicGenerator"),
+                ),
+                ("haystack.component.input".to_string(), input),
+                ("haystack.component.output".to_string(), output),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(
+            span.input,
+            Some(json!([
+                {"role": "system", "content": "Be brief."},
+                {"role": "user", "content": "Summarize: ..."}
+            ]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([{"role": "assistant", "content": "A summary."}]))
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("anthropic".to_string())
+        );
+        assert_eq!(span.attributes.input_tokens().total(), 20);
+    }
+
+    #[test]
+    fn test_convert_haystack_underscore_chat_message() {
+        let message = json!({"_role": "user", "_content": [{"text": "hi"}], "_meta": {}});
+        assert_eq!(
+            serde_json::to_value(chat_message(&message, "assistant")).unwrap(),
+            json!({"role": "user", "content": "hi"})
+        );
+    }
+
+    #[test]
+    fn test_does_not_detect_haystack_retriever_span() {
+        let span = make_span(
+            "haystack.component.run",
+            HashMap::from([(
+                "haystack.component.type".to_string(),
+                json!("InMemoryBM25Retriever"),
+            )]),
+        );
+        assert!(!HaystackConverter.detect(&span));
+    }
+}
@@ -18,8 +18,9 @@ use indexmap::IndexMap;
 use serde::Serialize;
 use serde_json::Value;
 
+use super::SpanConverter;
 use crate::{
-    db::spans::Span,
+    db::spans::{Span, SpanType},
     language_model::{ChatMessage, ChatMessageContent, ChatMessageContentPart},
     utils::json_value_to_string,
 };
@@ -151,6 +152,18 @@ enum LangChainChatMessage {
     Tool(LangChainToolChatMessage),
 }
 
+pub struct LangChainConverter;
+
+impl SpanConverter for LangChainConverter {
+    fn detect(&self, span: &Span) -> bool {
+        span.span_type == SpanType::LLM && is_langchain_span(span)
+    }
+
+    fn convert(&self, span: &mut Span) {
+        convert_span_to_langchain(span);
+    }
+}
+
 pub fn is_langchain_span(span: &Span) -> bool {
     span.is_llm_span()
         && (span
@@ -0,0 +1,228 @@
+//! Convert LlamaIndex LLM spans emitted by the OpenInference LlamaIndex instrumentation.
+//!
+//! LlamaIndex names LLM spans after the model class and the called method, e.g.
+//! `OpenAI.chat` or `Anthropic.astream_complete`. Chat calls carry
+//! `llm.input_messages.*`; completion calls carry the prompt in `llm.prompts`
+//! and the completion text in `output.value` instead.
+
+use serde_json::Value;
+
+use super::{
+    SpanConverter,
+    openinference::{OUTPUT_VALUE, convert_llm_span, is_openinference_llm_span},
+};
+use crate::{
+    db::spans::Span,
+    language_model::{ChatMessage, ChatMessageContent},
+    utils::json_value_to_string,
+};
+
+const LLM_PROMPTS: &str = "llm.prompts";
+
+const LLM_METHODS: &[&str] = &[
+    "chat",
+    "achat",
+    "stream_chat",
+    "astream_chat",
+    "complete",
+    "acomplete",
+    "stream_complete",
+    "astream_complete",
+];
+
+pub struct LlamaIndexConverter;
+
+impl SpanConverter for LlamaIndexConverter {
+    fn detect(&self, span: &Span) -> bool {
+        is_openinference_llm_span(span)
+            && span
+                .name
+                .rsplit_once('.')
+                .is_some_and(|(_, method)| LLM_METHODS.contains(&method))
+    }
+
+    fn convert(&self, span: &mut Span) {
+        convert_llm_span(span);
+
+        if span.input.is_none()
+            && let Some(prompts) = completion_prompts(&mut span.attributes.raw_attributes)
+        {
+            span.input = Some(serde_json::to_value(prompts).unwrap_or_default());
+        }
+
+        if span.output.is_none()
+            && let Some(text) = span.attributes.raw_attributes.remove(OUTPUT_VALUE)
+        {
+            let message = ChatMessage {
+                role: "assistant".to_string(),
+                content: ChatMessageContent::Text(json_value_to_string(&text)),
+                tool_call_id: None,
+            };
+            span.output = Some(serde_json::to_value(vec![message]).unwrap_or_default());
+        }
+    }
+}
+
+/// `llm.prompts` is either an array attribute or flattened as `llm.prompts.{i}`.
+fn completion_prompts(
+    attributes: &mut std::collections::HashMap<String, Value>,
+) -> Option<Vec<ChatMessage>> {
+    let prompts: Vec<String> = match attributes.remove(LLM_PROMPTS) {
+        Some(Value::Array(prompts)) => prompts.iter().map(json_value_to_string).collect(),
+        Some(other) => vec![json_value_to_string(&other)],
+        None => {
+            let mut prompts = Vec::new();
+            while let Some(prompt) = attributes.remove(&format!("{LLM_PROMPTS}.{}", prompts.len()))
+            {
+                prompts.push(json_value_to_string(&prompt));
+            }
+            prompts
+        }
+    };
+
+    if prompts.is_empty() {
+        return None;
+    }
+
+    Some(
+        prompts
+            .into_iter()
+            .map(|prompt| ChatMessage {
+                role: "user".to_string(),
+                content: ChatMessageContent::Text(prompt),
+                tool_call_id: None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        db::spans::SpanType,
+        traces::{provider::convert_span_to_provider_format, spans::SpanAttributes},
+    };
+
+    fn make_span(name: &str, attributes: HashMap<String, Value>) -> Span {
+        let mut span = Span {
+            name: name.to_string(),
+            attributes: SpanAttributes::new(attributes),
+            ..Default::default()
+        };
+        span.span_type = span.attributes.span_type();
+        span.parse_and_enrich_attributes();
+        span
+    }
+
+    #[test]
+    fn test_convert_llamaindex_chat_span() {
+        let mut span = make_span(
+            "OpenAI.chat",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                ("llm.model_name".to_string(), json!("gpt-4o-mini")),
+                ("llm.provider".to_string(), json!("openai")),
+                (
+                    "llm.input_messages.0.message.role".to_string(),
+                    json!("system"),
+                ),
+                (
+                    "llm.input_messages.0.message.content".to_string(),
+                    json!("Answer using the context."),
+                ),
+                (
+                    "llm.input_messages.1.message.role".to_string(),
+                    json!("user"),
+                ),
+                (
+                    "llm.input_messages.1.message.content".to_string(),
+                    json!("What did the author do growing up?"),
+                ),
+                (
+                    "llm.output_messages.0.message.role".to_string(),
+                    json!("assistant"),
+                ),
+                (
+                    "llm.output_messages.0.message.content".to_string(),
+                    json!("He wrote short stories."),
+                ),
+                ("llm.token_count.prompt".to_string(), json!(52)),
+                ("llm.token_count.completion".to_string(), json!(6)),
+                ("llm.token_count.total".to_string(), json!(58)),
+            ]),
+        );
+        assert_eq!(span.span_type, SpanType::Default);
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(span.span_type, SpanType::LLM);
+        assert_eq!(
+            span.input,
+            Some(json!([
+                {"role": "system", "content": "Answer using the context."},
+                {"role": "user", "content": "What did the author do growing up?"}
+            ]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([{"role": "assistant", "content": "He wrote short stories."}]))
+        );
+        assert_eq!(
+            span.attributes.request_model(),
+            Some("gpt-4o-mini".to_string())
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("openai".to_string())
+        );
+        assert_eq!(span.attributes.input_tokens().total(), 52);
+        assert_eq!(span.attributes.output_tokens(), 6);
+    }
+
+    #[test]
+    fn test_convert_llamaindex_completion_span() {
+        let mut span = make_span(
+            "OpenAI.complete",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                (
+                    "llm.model_name".to_string(),
+                    json!("gpt-3.5-turbo-instruct"),
+                ),
+                ("llm.prompts".to_string(), json!(["Say hello"])),
+                ("output.value".to_string(), json!("Hello!")),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(
+            span.input,
+            Some(json!([{"role": "user", "content": "Say hello"}]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([{"role": "assistant", "content": "Hello!"}]))
+        );
+    }
+
+    #[test]
+    fn test_does_not_detect_non_llm_llamaindex_span() {
+        let span = make_span(
+            "VectorIndexRetriever.retrieve",
+            HashMap::from([("openinference.span.kind".to_string(), json!("RETRIEVER"))]),
+        );
+        assert!(!LlamaIndexConverter.detect(&span));
+
+        let span = make_span(
+            "RetrieverQueryEngine.query",
+            HashMap::from([("openinference.span.kind".to_string(), json!("LLM"))]),
+        );
+        assert!(!LlamaIndexConverter.detect(&span));
+    }
+}
@@ -1,20 +1,52 @@
+//! Convert spans from framework-specific shapes into the chat message format we
+//! render and the `gen_ai.*` attributes usage and cost calculation read.
+//!
+//! Each framework gets a [`SpanConverter`]; converters are tried in the order of
+//! [`CONVERTERS`] and the first one that detects the span converts it.
+
+mod crewai;
+mod dspy;
+mod haystack;
 mod langchain;
+mod llamaindex;
+mod openai_agents;
+mod openinference;
+
+use std::collections::HashMap;
+
+use serde_json::Value;
 
 use crate::{
-    db::spans::{Span, SpanType},
-    traces::provider::langchain::is_langchain_span,
+    db::spans::Span,
+    traces::span_attributes::{GEN_AI_REQUEST_MODEL, GEN_AI_RESPONSE_MODEL, GEN_AI_SYSTEM},
 };
 
+pub trait SpanConverter: Sync {
+    /// Whether this span was produced by the framework this converter handles.
+    fn detect(&self, span: &Span) -> bool;
+
+    /// Rewrite the span in place. Only called if `detect` returned true.
+    fn convert(&self, span: &mut Span);
+}
+
+/// More specific detectors go first. OpenInference-based converters
+/// (DSPy, OpenAI Agents, LlamaIndex) share the `openinference.span.kind`
+/// attribute and are told apart by span names only.
+static CONVERTERS: &[&dyn SpanConverter] = &[
+    &langchain::LangChainConverter,
+    &haystack::HaystackConverter,
+    &crewai::CrewAIConverter,
+    &dspy::DSPyConverter,
+    &openai_agents::OpenAIAgentsConverter,
+    &llamaindex::LlamaIndexConverter,
+];
+
 pub fn convert_span_to_provider_format(span: &mut Span) {
-    if span.span_type != SpanType::LLM {
-        return;
-    }
     if is_ai_sdk_llm_span(span) {
         return;
     }
-    if is_langchain_span(span) {
-        langchain::convert_span_to_langchain(span);
-        return;
+    if let Some(converter) = CONVERTERS.iter().find(|c| c.detect(span)) {
+        converter.convert(span);
     }
 }
 
@@ -36,3 +68,30 @@ fn is_ai_sdk_llm_span(span: &Span) -> bool {
                 .get("aisdk.model.provider")
                 .is_some())
 }
+
+/// Split a LiteLLM-style `provider/model` name into `gen_ai.system` and the
+/// request/response model. Names without a provider prefix only set the model.
+/// Existing `gen_ai.*` values win.
+fn set_provider_prefixed_model(attributes: &mut HashMap<String, Value>, model: &str) {
+    let model = match model.split_once('/') {
+        Some((provider, model)) if !provider.is_empty() && !model.is_empty() => {
+            openinference::insert_if_absent(
+                attributes,
+                GEN_AI_SYSTEM,
+                Value::String(provider.to_string()),
+            );
+            model
+        }
+        _ => model,
+    };
+    openinference::insert_if_absent(
+        attributes,
+        GEN_AI_REQUEST_MODEL,
+        Value::String(model.to_string()),
+    );
+    openinference::insert_if_absent(
+        attributes,
+        GEN_AI_RESPONSE_MODEL,
+        Value::String(model.to_string()),
+    );
+}
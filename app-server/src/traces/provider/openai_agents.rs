@@ -0,0 +1,321 @@
+//! Convert model call spans emitted by the OpenInference OpenAI Agents SDK instrumentation.
+//!
+//! The Agents SDK reports each model call as a `response` span (Responses API) or
+//! a `generation` span (Chat Completions). Flattened `llm.input_messages.*` /
+//! `llm.output_messages.*` are used when present. Otherwise the Responses API
+//! items are read from `input.value` and the serialized `Response` from
+//! `output.value`:
+//! - `{"role", "content"}` messages, with `input_text` / `output_text` parts
+//! - `{"type": "function_call", "call_id", "name", "arguments"}` tool calls
+//! - `{"type": "function_call_output", "call_id", "output"}` tool results
+//!
+//! The `Response` also carries the model and usage, which fill in `gen_ai.*`
+//! attributes that were not set from `llm.*`.
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::{
+    SpanConverter,
+    openinference::{
+        INPUT_VALUE, OUTPUT_VALUE, as_i64, convert_llm_span, insert_if_absent,
+        is_openinference_llm_span, parse_json_string,
+    },
+};
+use crate::{
+    db::spans::Span,
+    language_model::{
+        ChatMessage, ChatMessageContent, ChatMessageContentPart, ChatMessageText,
+        ChatMessageToolCall,
+    },
+    traces::span_attributes::{
+        GEN_AI_CACHE_READ_INPUT_TOKENS, GEN_AI_INPUT_TOKENS, GEN_AI_OUTPUT_TOKENS,
+        GEN_AI_RESPONSE_MODEL, GEN_AI_SYSTEM, GEN_AI_USAGE_REASONING_TOKENS,
+    },
+    utils::json_value_to_string,
+};
+
+const AGENTS_MODEL_SPAN_NAMES: &[&str] = &["response", "generation"];
+
+pub struct OpenAIAgentsConverter;
+
+impl SpanConverter for OpenAIAgentsConverter {
+    fn detect(&self, span: &Span) -> bool {
+        is_openinference_llm_span(span) && AGENTS_MODEL_SPAN_NAMES.contains(&span.name.as_str())
+    }
+
+    fn convert(&self, span: &mut Span) {
+        convert_llm_span(span);
+
+        let attributes = &mut span.attributes.raw_attributes;
+        if span.input.is_none()
+            && let Some(input) = attributes.remove(INPUT_VALUE).map(parse_json_string)
+        {
+            span.input = Some(serde_json::to_value(input_messages(input)).unwrap_or_default());
+        }
+        if let Some(response) = attributes.get(OUTPUT_VALUE).cloned().map(parse_json_string) {
+            set_usage_attributes(attributes, &response);
+            if span.output.is_none()
+                && let Some(items) = response.get("output").and_then(|o| o.as_array())
+            {
+                span.output = Some(
+                    serde_json::to_value(response_items_to_messages(items)).unwrap_or_default(),
+                );
+                attributes.remove(OUTPUT_VALUE);
+            }
+        }
+        insert_if_absent(
+            attributes,
+            GEN_AI_SYSTEM,
+            Value::String("openai".to_string()),
+        );
+    }
+}
+
+/// `input.value` is a list of Responses API items or a bare string prompt.
+fn input_messages(input: Value) -> Vec<ChatMessage> {
+    match input {
+        Value::Array(items) => response_items_to_messages(&items),
+        other => vec![ChatMessage {
+            role: "user".to_string(),
+            content: ChatMessageContent::Text(json_value_to_string(&other)),
+            tool_call_id: None,
+        }],
+    }
+}
+
+fn response_items_to_messages(items: &[Value]) -> Vec<ChatMessage> {
+    let mut messages: Vec<ChatMessage> = Vec::new();
+    for item in items {
+        match item.get("type").and_then(|t| t.as_str()) {
+            Some("function_call") => {
+                let tool_call = ChatMessageContentPart::ToolCall(ChatMessageToolCall {
+                    name: item
+                        .get("name")
+                        .map(json_value_to_string)
+                        .unwrap_or_default(),
+                    id: item.get("call_id").map(json_value_to_string),
+                    arguments: item.get("arguments").cloned().map(parse_json_string),
+                });
+                // Parallel tool calls come as consecutive items; keep them in one message
+                if let Some(ChatMessage {
+                    role,
+                    content: ChatMessageContent::ContentPartList(parts),
+                    ..
+                }) = messages.last_mut()
+                    && role == "assistant"
+                {
+                    parts.push(tool_call);
+                } else {
+                    messages.push(ChatMessage {
+                        role: "assistant".to_string(),
+                        content: ChatMessageContent::ContentPartList(vec![tool_call]),
+                        tool_call_id: None,
+                    });
+                }
+            }
+            Some("function_call_output") => messages.push(ChatMessage {
+                role: "tool".to_string(),
+                content: ChatMessageContent::Text(
+                    item.get("output")
+                        .map(json_value_to_string)
+                        .unwrap_or_default(),
+                ),
+                tool_call_id: item.get("call_id").map(json_value_to_string),
+            }),
+            // Reasoning summaries and other hosted tool items are not rendered
+            Some("message") | None => {
+                let Some(role) = item.get("role").and_then(|r| r.as_str()) else {
+                    continue;
+                };
+                messages.push(ChatMessage {
+                    role: role.to_string(),
+                    content: message_content(item.get("content")),
+                    tool_call_id: None,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    messages
+}
+
+fn message_content(content: Option<&Value>) -> ChatMessageContent {
+    match content {
+        Some(Value::Array(parts)) => {
+            let parts: Vec<ChatMessageContentPart> = parts
+                .iter()
+                .filter_map(|part| {
+                    part.get("text").or(part.get("refusal")).map(|text| {
+                        ChatMessageContentPart::Text(ChatMessageText {
+                            text: json_value_to_string(text),
+                        })
+                    })
+                })
+                .collect();
+            match parts.as_slice() {
+                [ChatMessageContentPart::Text(text)] => ChatMessageContent::Text(text.text.clone()),
+                _ => ChatMessageContent::ContentPartList(parts),
+            }
+        }
+        Some(other) => ChatMessageContent::Text(json_value_to_string(other)),
+        None => ChatMessageContent::Text(String::new()),
+    }
+}
+
+fn set_usage_attributes(attributes: &mut HashMap<String, Value>, response: &Value) {
+    if let Some(model) = response.get("model").filter(|m| m.is_string()) {
+        insert_if_absent(attributes, GEN_AI_RESPONSE_MODEL, model.clone());
+    }
+    let Some(usage) = response.get("usage") else {
+        return;
+    };
+    for (path, target) in [
+        (&["input_tokens"][..], GEN_AI_INPUT_TOKENS),
+        (&["output_tokens"][..], GEN_AI_OUTPUT_TOKENS),
+        (
+            &["input_tokens_details", "cached_tokens"][..],
+            GEN_AI_CACHE_READ_INPUT_TOKENS,
+        ),
+        (
+            &["output_tokens_details", "reasoning_tokens"][..],
+            GEN_AI_USAGE_REASONING_TOKENS,
+        ),
+    ] {
+        let value = path
+            .iter()
+            .try_fold(usage, |value, key| value.get(key))
+            .and_then(as_i64);
+        if let Some(value) = value {
+            insert_if_absent(attributes, target, Value::from(value));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        db::spans::SpanType,
+        traces::{provider::convert_span_to_provider_format, spans::SpanAttributes},
+    };
+
+    fn make_span(name: &str, attributes: HashMap<String, Value>) -> Span {
+        let mut span = Span {
+            name: name.to_string(),
+            attributes: SpanAttributes::new(attributes),
+            ..Default::default()
+        };
+        span.span_type = span.attributes.span_type();
+        span.parse_and_enrich_attributes();
+        span
+    }
+
+    #[test]
+    fn test_convert_openai_agents_response_span_from_values() {
+        let input = json!([
+            {"role": "user", "content": "What's the weather in Tokyo?"},
+            {"type": "function_call", "call_id": "call_a", "name": "get_weather", "arguments": "{\"city\":\"Tokyo\"}", "id": "fc_1", "status": "completed"},
+            {"type": "function_call_output", "call_id": "call_a", "output": "Sunny, 22°C"}
+        ]);
+        let output = json!({
+            "id": "resp_1",
+            "model": "gpt-4.1-2025-04-14",
+            "output": [{
+                "type": "message",
+                "id": "msg_1",
+                "role": "assistant",
+                "status": "completed",
+                "content": [{"type": "output_text", "text": "It's sunny and 22°C in Tokyo.", "annotations": []}]
+            }],
+            "usage": {
+                "input_tokens": 80,
+                "input_tokens_details": {"cached_tokens": 64},
+                "output_tokens": 12,
+                "output_tokens_details": {"reasoning_tokens": 0},
+                "total_tokens": 92
+            }
+        });
+        let mut span = make_span(
+            "response",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                ("input.value".to_string(), json!(input.to_string())),
+                ("output.value".to_string(), json!(output.to_string())),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(span.span_type, SpanType::LLM);
+        assert_eq!(
+            span.input,
+            Some(json!([
+                {"role": "user", "content": "What's the weather in Tokyo?"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_call", "name": "get_weather", "id": "call_a", "arguments": {"city": "Tokyo"}}
+                ]},
+                {"role": "tool", "content": "Sunny, 22°C", "tool_call_id": "call_a"}
+            ]))
+        );
+        assert_eq!(
+            span.output,
+            Some(json!([{"role": "assistant", "content": "It's sunny and 22°C in Tokyo."}]))
+        );
+        assert_eq!(
+            span.attributes.response_model(),
+            Some("gpt-4.1-2025-04-14".to_string())
+        );
+        assert_eq!(
+            span.attributes.provider_name(&span.name),
+            Some("openai".to_string())
+        );
+        assert_eq!(span.attributes.output_tokens(), 12);
+        assert_eq!(
+            span.attributes
+                .raw_attributes
+                .get(GEN_AI_CACHE_READ_INPUT_TOKENS),
+            Some(&json!(64))
+        );
+    }
+
+    #[test]
+    fn test_convert_openai_agents_prefers_flattened_messages() {
+        let mut span = make_span(
+            "generation",
+            HashMap::from([
+                ("openinference.span.kind".to_string(), json!("LLM")),
+                ("llm.model_name".to_string(), json!("gpt-4o")),
+                (
+                    "llm.input_messages.0.message.role".to_string(),
+                    json!("user"),
+                ),
+                (
+                    "llm.input_messages.0.message.content".to_string(),
+                    json!("hello"),
+                ),
+                ("input.value".to_string(), json!("[{\"role\": \"user\"}]")),
+            ]),
+        );
+
+        convert_span_to_provider_format(&mut span);
+
+        assert_eq!(
+            span.input,
+            Some(json!([{"role": "user", "content": "hello"}]))
+        );
+        assert_eq!(span.attributes.request_model(), Some("gpt-4o".to_string()));
+    }
+
+    #[test]
+    fn test_does_not_detect_openai_agents_agent_span() {
+        let span = make_span(
+            "Weather agent",
+            HashMap::from([("openinference.span.kind".to_string(), json!("AGENT"))]),
+        );
+        assert!(!OpenAIAgentsConverter.detect(&span));
+    }
+}
@@ -0,0 +1,434 @@
+//! Shared parsing for spans emitted by OpenInference instrumentations.
+//!
+//! The LlamaIndex, DSPy and OpenAI Agents SDK instrumentations all follow the
+//! OpenInference semantic conventions, so their converters only differ in how
+//! they detect spans and in the framework-specific fallbacks on top of this.
+//!
+//! Attributes we read:
+//! - `openinference.span.kind` — `LLM` for model calls
+//! - `llm.input_messages.{i}.message.*` / `llm.output_messages.{i}.message.*` —
+//!   flattened chat messages, with `role`, `content`, `contents.{j}.message_content.*`,
+//!   `tool_calls.{k}.tool_call.*` and `tool_call_id`
+//! - `llm.model_name`, `llm.provider`, `llm.system`
+//! - `llm.token_count.*` — prompt/completion tokens and their cache/reasoning details
+//!
+//! Message attributes are removed once parsed so they are not stored twice.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde_json::Value;
+
+use crate::{
+    db::spans::{Span, SpanType},
+    language_model::{
+        ChatMessage, ChatMessageContent, ChatMessageContentPart, ChatMessageImageUrl,
+        ChatMessageText, ChatMessageToolCall,
+    },
+    traces::span_attributes::{
+        GEN_AI_CACHE_READ_INPUT_TOKENS, GEN_AI_CACHE_WRITE_INPUT_TOKENS, GEN_AI_INPUT_TOKENS,
+        GEN_AI_OUTPUT_TOKENS, GEN_AI_REQUEST_MODEL, GEN_AI_RESPONSE_MODEL, GEN_AI_SYSTEM,
+        GEN_AI_USAGE_REASONING_TOKENS,
+    },
+    utils::json_value_to_string,
+};
+
+pub const OPENINFERENCE_SPAN_KIND: &str = "openinference.span.kind";
+pub const LLM_INPUT_MESSAGES: &str = "llm.input_messages";
+pub const LLM_OUTPUT_MESSAGES: &str = "llm.output_messages";
+pub const LLM_MODEL_NAME: &str = "llm.model_name";
+pub const LLM_PROVIDER: &str = "llm.provider";
+pub const LLM_SYSTEM: &str = "llm.system";
+pub const LLM_TOKEN_COUNT_PROMPT: &str = "llm.token_count.prompt";
+pub const LLM_TOKEN_COUNT_COMPLETION: &str = "llm.token_count.completion";
+pub const LLM_TOKEN_COUNT_CACHE_READ: &str = "llm.token_count.prompt_details.cache_read";
+pub const LLM_TOKEN_COUNT_CACHE_WRITE: &str = "llm.token_count.prompt_details.cache_write";
+pub const LLM_TOKEN_COUNT_REASONING: &str = "llm.token_count.completion_details.reasoning";
+pub const INPUT_VALUE: &str = "input.value";
+pub const OUTPUT_VALUE: &str = "output.value";
+
+/// Upper bound on the messages, content parts or tool calls read under one
+/// prefix. Indices come from the client, so they must not size allocations.
+const MAX_INDICES: usize = 1024;
+
+pub fn is_openinference_llm_span(span: &Span) -> bool {
+    span.attributes
+        .raw_attributes
+        .get(OPENINFERENCE_SPAN_KIND)
+        .and_then(|v| v.as_str())
+        .is_some_and(|kind| kind.eq_ignore_ascii_case("LLM"))
+}
+
+/// Parse flattened `{prefix}.{i}.message.*` attributes into chat messages.
+/// Returns `None` when there are no message attributes under `prefix`.
+pub fn messages_from_attributes(
+    attributes: &mut HashMap<String, Value>,
+    prefix: &str,
+) -> Option<Vec<ChatMessage>> {
+    let indices = present_indices(attributes, prefix);
+    if indices.is_empty() {
+        return None;
+    }
+
+    let mut messages = Vec::with_capacity(indices.len());
+    for i in indices {
+        let message_prefix = format!("{prefix}.{i}.message");
+        let role = attributes
+            .remove(&format!("{message_prefix}.role"))
+            .map(|v| json_value_to_string(&v));
+        let content = attributes
+            .remove(&format!("{message_prefix}.content"))
+            .map(|v| json_value_to_string(&v));
+        let tool_call_id = attributes
+            .remove(&format!("{message_prefix}.tool_call_id"))
+            .map(|v| json_value_to_string(&v));
+
+        let mut parts = content_parts(attributes, &format!("{message_prefix}.contents"));
+        let tool_calls = tool_calls(attributes, &format!("{message_prefix}.tool_calls"));
+        let has_tool_calls = !tool_calls.is_empty();
+        parts.extend(tool_calls.into_iter().map(ChatMessageContentPart::ToolCall));
+
+        let content = match content {
+            Some(text) if parts.is_empty() => ChatMessageContent::Text(text),
+            Some(text) => {
+                if !text.is_empty() {
+                    parts.insert(0, ChatMessageContentPart::Text(ChatMessageText { text }));
+                }
+                ChatMessageContent::ContentPartList(parts)
+            }
+            None if parts.is_empty() => ChatMessageContent::Text(String::new()),
+            None => ChatMessageContent::ContentPartList(parts),
+        };
+
+        messages.push(ChatMessage {
+            role: role.unwrap_or_else(|| {
+                if has_tool_calls {
+                    "assistant".to_string()
+                } else {
+                    "user".to_string()
+                }
+            }),
+            content,
+            tool_call_id,
+        });
+    }
+
+    Some(messages)
+}
+
+fn content_parts(
+    attributes: &mut HashMap<String, Value>,
+    prefix: &str,
+) -> Vec<ChatMessageContentPart> {
+    let mut parts = Vec::new();
+    for j in present_indices(attributes, prefix) {
+        let part_prefix = format!("{prefix}.{j}.message_content");
+        let part_type = attributes
+            .remove(&format!("{part_prefix}.type"))
+            .map(|v| json_value_to_string(&v));
+        let text = attributes.remove(&format!("{part_prefix}.text"));
+        let image_url = attributes.remove(&format!("{part_prefix}.image.image.url"));
+
+        match (part_type.as_deref(), text, image_url) {
+            (Some("image"), _, Some(url)) | (None, None, Some(url)) => {
+                parts.push(ChatMessageContentPart::ImageUrl(ChatMessageImageUrl {
+                    url: json_value_to_string(&url),
+                    detail: None,
+                }));
+            }
+            (_, Some(text), _) => {
+                parts.push(ChatMessageContentPart::Text(ChatMessageText {
+                    text: json_value_to_string(&text),
+                }));
+            }
+            _ => {}
+        }
+    }
+    parts
+}
+
+fn tool_calls(attributes: &mut HashMap<String, Value>, prefix: &str) -> Vec<ChatMessageToolCall> {
+    let mut tool_calls = Vec::new();
+    for k in present_indices(attributes, prefix) {
+        let call_prefix = format!("{prefix}.{k}.tool_call");
+        let id = attributes
+            .remove(&format!("{call_prefix}.id"))
+            .map(|v| json_value_to_string(&v));
+        let name = attributes
+            .remove(&format!("{call_prefix}.function.name"))
+            .map(|v| json_value_to_string(&v));
+        let arguments = attributes
+            .remove(&format!("{call_prefix}.function.arguments"))
+            .map(parse_json_string);
+
+        if let Some(name) = name {
+            tool_calls.push(ChatMessageToolCall {
+                name,
+                id,
+                arguments,
+            });
+        }
+    }
+    tool_calls
+}
+
+/// The `{i}`s of keys shaped like `{prefix}.{i}.…`, ascending, at most
+/// `MAX_INDICES` of them. Gaps are skipped rather than filled.
+fn present_indices(attributes: &HashMap<String, Value>, prefix: &str) -> Vec<usize> {
+    let prefix = format!("{prefix}.");
+    let indices: BTreeSet<usize> = attributes
+        .keys()
+        .filter_map(|key| {
+            key.strip_prefix(&prefix)?
+                .split('.')
+                .next()?
+                .parse::<usize>()
+                .ok()
+        })
+        .collect();
+    indices.into_iter().take(MAX_INDICES).collect()
+}
+
+/// Attribute values that carry JSON are usually serialized into a string.
+pub fn parse_json_string(value: Value) -> Value {
+    match value {
+        Value::String(s) => serde_json::from_str::<Value>(&s).unwrap_or(Value::String(s)),
+        other => other,
+    }
+}
+
+/// Copy OpenInference model, provider and token counts into the `gen_ai.*`
+/// attributes that usage and cost calculation read. Existing `gen_ai.*` values win.
+pub fn normalize_usage_attributes(attributes: &mut HashMap<String, Value>) {
+    if let Some(model) = attributes.get(LLM_MODEL_NAME).cloned() {
+        insert_if_absent(attributes, GEN_AI_REQUEST_MODEL, model.clone());
+        insert_if_absent(attributes, GEN_AI_RESPONSE_MODEL, model);
+    }
+    if let Some(provider) = attributes
+        .get(LLM_PROVIDER)
+        .or(attributes.get(LLM_SYSTEM))
+        .cloned()
+    {
+        insert_if_absent(attributes, GEN_AI_SYSTEM, provider);
+    }
+    for (source, target) in [
+        (LLM_TOKEN_COUNT_PROMPT, GEN_AI_INPUT_TOKENS),
+        (LLM_TOKEN_COUNT_COMPLETION, GEN_AI_OUTPUT_TOKENS),
+        (LLM_TOKEN_COUNT_CACHE_READ, GEN_AI_CACHE_READ_INPUT_TOKENS),
+        (LLM_TOKEN_COUNT_CACHE_WRITE, GEN_AI_CACHE_WRITE_INPUT_TOKENS),
+        (LLM_TOKEN_COUNT_REASONING, GEN_AI_USAGE_REASONING_TOKENS),
+    ] {
+        if let Some(value) = attributes.get(source).and_then(as_i64) {
+            insert_if_absent(attributes, target, Value::from(value));
+        }
+    }
+}
+
+pub fn insert_if_absent(attributes: &mut HashMap<String, Value>, key: &str, value: Value) {
+    if !attributes.contains_key(key) {
+        attributes.insert(key.to_string(), value);
+    }
+}
+
+/// Token counts sometimes arrive as strings or floats depending on the exporter.
+pub fn as_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or(n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.parse::<i64>().ok(),
+        _ => None,
+    }
+}
+
+/// Convert an OpenInference LLM span: messages into `span.input` / `span.output`,
+/// model and usage into `gen_ai.*`. Input and output are only replaced when the
+/// corresponding message attributes are present.
+pub fn convert_llm_span(span: &mut Span) {
+    let attributes = &mut span.attributes.raw_attributes;
+    if let Some(input) = messages_from_attributes(attributes, LLM_INPUT_MESSAGES) {
+        span.input = Some(serde_json::to_value(input).unwrap_or_default());
+    }
+    if let Some(output) = messages_from_attributes(attributes, LLM_OUTPUT_MESSAGES) {
+        span.output = Some(serde_json::to_value(output).unwrap_or_default());
+    }
+    normalize_usage_attributes(attributes);
+    span.span_type = SpanType::LLM;
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_messages_from_attributes() {
+        let mut attributes: HashMap<String, Value> = HashMap::from([
+            (
+                "llm.input_messages.0.message.role".to_string(),
+                json!("system"),
+            ),
+            (
+                "llm.input_messages.0.message.content".to_string(),
+                json!("You are helpful."),
+            ),
+            (
+                "llm.input_messages.1.message.role".to_string(),
+                json!("user"),
+            ),
+            (
+                "llm.input_messages.1.message.contents.0.message_content.type".to_string(),
+                json!("text"),
+            ),
+            (
+                "llm.input_messages.1.message.contents.0.message_content.text".to_string(),
+                json!("What is in this image?"),
+            ),
+            (
+                "llm.input_messages.1.message.contents.1.message_content.type".to_string(),
+                json!("image"),
+            ),
+            (
+                "llm.input_messages.1.message.contents.1.message_content.image.image.url"
+                    .to_string(),
+                json!("https://example.com/cat.png"),
+            ),
+            (
+                "llm.input_messages.2.message.role".to_string(),
+                json!("assistant"),
+            ),
+            (
+                "llm.input_messages.2.message.tool_calls.0.tool_call.id".to_string(),
+                json!("call_1"),
+            ),
+            (
+                "llm.input_messages.2.message.tool_calls.0.tool_call.function.name".to_string(),
+                json!("get_weather"),
+            ),
+            (
+                "llm.input_messages.2.message.tool_calls.0.tool_call.function.arguments"
+                    .to_string(),
+                json!("{\"city\": \"Paris\"}"),
+            ),
+            (
+                "llm.input_messages.3.message.role".to_string(),
+                json!("tool"),
+            ),
+            (
+                "llm.input_messages.3.message.tool_call_id".to_string(),
+                json!("call_1"),
+            ),
+            (
+                "llm.input_messages.3.message.content".to_string(),
+                json!("sunny"),
+            ),
+            ("llm.model_name".to_string(), json!("gpt-4o")),
+        ]);
+
+        let messages = messages_from_attributes(&mut attributes, LLM_INPUT_MESSAGES).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            json!([
+                {"role": "system", "content": "You are helpful."},
+                {"role": "user", "content": [
+                    {"type": "text", "text": "What is in this image?"},
+                    {"type": "image_url", "url": "https://example.com/cat.png", "detail": null}
+                ]},
+                {"role": "assistant", "content": [
+                    {"type": "tool_call", "name": "get_weather", "id": "call_1", "arguments": {"city": "Paris"}}
+                ]},
+                {"role": "tool", "content": "sunny", "tool_call_id": "call_1"}
+            ])
+        );
+        // Parsed message attributes are consumed, everything else is kept
+        assert_eq!(attributes.len(), 1);
+        assert!(attributes.contains_key("llm.model_name"));
+    }
+
+    #[test]
+    fn test_messages_from_attributes_absent() {
+        let mut attributes: HashMap<String, Value> =
+            HashMap::from([("llm.model_name".to_string(), json!("gpt-4o"))]);
+        assert!(messages_from_attributes(&mut attributes, LLM_INPUT_MESSAGES).is_none());
+    }
+
+    #[test]
+    fn test_messages_from_attributes_huge_sparse_index() {
+        let mut attributes: HashMap<String, Value> = HashMap::from([
+            (
+                "llm.input_messages.0.message.content".to_string(),
+                json!("hi"),
+            ),
+            (
+                "llm.input_messages.4000000000.message.role".to_string(),
+                json!("assistant"),
+            ),
+            (
+                "llm.input_messages.4000000000.message.tool_calls.18446744073709551615.tool_call.function.name"
+                    .to_string(),
+                json!("lookup"),
+            ),
+        ]);
+
+        let messages = messages_from_attributes(&mut attributes, LLM_INPUT_MESSAGES).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&messages).unwrap(),
+            json!([
+                {"role": "user", "content": "hi"},
+                {"role": "assistant", "content": [
+                    {"type": "tool_call", "name": "lookup", "id": null, "arguments": null}
+                ]}
+            ])
+        );
+        assert!(attributes.is_empty());
+    }
+
+    #[test]
+    fn test_messages_from_attributes_caps_index_count() {
+        let mut attributes: HashMap<String, Value> = (0..MAX_INDICES + 10)
+            .map(|i| {
+                (
+                    format!("llm.input_messages.{}.message.content", i * 1000),
+                    json!("x"),
+                )
+            })
+            .collect();
+
+        let messages = messages_from_attributes(&mut attributes, LLM_INPUT_MESSAGES).unwrap();
+
+        assert_eq!(messages.len(), MAX_INDICES);
+    }
+
+    #[test]
+    fn test_normalize_usage_attributes_does_not_overwrite() {
+        let mut attributes: HashMap<String, Value> = HashMap::from([
+            ("llm.model_name".to_string(), json!("gpt-4o")),
+            ("llm.provider".to_string(), json!("openai")),
+            ("llm.token_count.prompt".to_string(), json!(120)),
+            ("llm.token_count.completion".to_string(), json!("30")),
+            (
+                "llm.token_count.prompt_details.cache_read".to_string(),
+                json!(100),
+            ),
+            (
+                "gen_ai.response.model".to_string(),
+                json!("gpt-4o-2024-08-06"),
+            ),
+        ]);
+
+        normalize_usage_attributes(&mut attributes);
+
+        assert_eq!(attributes.get(GEN_AI_REQUEST_MODEL), Some(&json!("gpt-4o")));
+        assert_eq!(
+            attributes.get(GEN_AI_RESPONSE_MODEL),
+            Some(&json!("gpt-4o-2024-08-06"))
+        );
+        assert_eq!(attributes.get(GEN_AI_SYSTEM), Some(&json!("openai")));
+        assert_eq!(attributes.get(GEN_AI_INPUT_TOKENS), Some(&json!(120)));
+        assert_eq!(attributes.get(GEN_AI_OUTPUT_TOKENS), Some(&json!(30)));
+        assert_eq!(
+            attributes.get(GEN_AI_CACHE_READ_INPUT_TOKENS),
+            Some(&json!(100))
+        );
+    }
+}
@@ -5,9 +5,30 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use chrono::Utc;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::cache::{Cache, CacheTrait, keys::SAMPLING_FACTORS_CACHE_KEY};
+use crate::{
+    api::v1::traces::RabbitMqSpanMessage,
+    cache::{
+        Cache, CacheError, CacheTrait,
+        keys::{
+            SAMPLING_FACTORS_CACHE_KEY, TRACE_SAMPLING_BUFFER_CACHE_KEY,
+            TRACE_SAMPLING_DECISION_CACHE_KEY, TRACE_SAMPLING_PENDING_CACHE_KEY,
+        },
+    },
+    ch::traces::TraceAggregation,
+    db::{
+        DB,
+        utils::{
+            Filter, FilterOperator, evaluate_array_contains_filter, evaluate_number_filter,
+            evaluate_string_filter,
+        },
+    },
+    traces::spans::SpanUsage,
+    utils::limits::get_workspace_info_for_project_id,
+};
 
 /// Pre-computed per-user sampling base factors for a project.
 /// Maps user_id -> base_factor.
@@ -157,3 +178,882 @@ pub fn should_sample_trace(
 
     rand::RngExt::random::<f64>(&mut rand::rng()) < p
 }
+
+// Tail-based sampling
+//
+// Projects opt in by configuring `projects.settings.traceSamplingRules`.
+// Spans of those projects are held back in the cache until their trace is
+// decided, then either released into the normal ingest pipeline or dropped.
+// A trace is decided when its root span arrives, or, for traces whose root
+// never shows up, once the decision wait has passed. Decisions are cached so
+// spans arriving after the decision (e.g. async children that end after the
+// root) follow it without being buffered.
+//
+// Dropped spans never reach storage, realtime, indexing or the bytes-ingested
+// billing in `process_span_messages`; buffered spans are billed only once
+// released.
+
+/// How long undecided spans wait for their trace's root span before the trace
+/// is decided on whatever has been buffered.
+const TAIL_SAMPLING_DECISION_WAIT_SECONDS: i64 = 60;
+/// Buffers outlive the decision wait by a wide margin so a buffer is still
+/// there when a consumer gets around to sweeping it.
+const TAIL_SAMPLING_BUFFER_TTL_SECONDS: u64 = 3600;
+const TAIL_SAMPLING_DECISION_TTL_SECONDS: u64 = 86400; // 24 hours
+/// Timed-out traces released per batch, so a backlog can't balloon one batch.
+const TAIL_SAMPLING_SWEEP_LIMIT: usize = 50;
+
+/// A tail-based sampling rule from `projects.settings.traceSamplingRules`.
+/// Rules are evaluated in order against the whole trace and the first match
+/// decides. Traces matching no rule are kept.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSamplingRule {
+    pub name: String,
+    /// All filters must match. An empty list matches every trace, which
+    /// makes a catch-all last rule possible.
+    #[serde(default)]
+    pub filters: Vec<Filter>,
+    /// Percentage of matching traces to keep, 0-100.
+    pub sample_rate: f64,
+}
+
+impl TraceSamplingRule {
+    fn matches(&self, trace: &TraceAggregation) -> bool {
+        self.filters
+            .iter()
+            .all(|filter| trace_matches_filter(trace, filter))
+    }
+}
+
+/// Recorded on the trace row as `traces.sampling_decision`.
+#[derive(Deserialize, Serialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSamplingDecision {
+    pub keep: bool,
+    /// Name of the rule that decided, `None` when no rule matched.
+    pub rule: Option<String>,
+    pub sample_rate: f64,
+}
+
+impl TraceSamplingDecision {
+    pub fn evaluate(rules: &[TraceSamplingRule], trace: &TraceAggregation) -> Self {
+        match rules.iter().find(|rule| rule.matches(trace)) {
+            Some(rule) => Self {
+                keep: is_trace_in_sample(trace.trace_id, rule.sample_rate),
+                rule: Some(rule.name.clone()),
+                sample_rate: rule.sample_rate,
+            },
+            None => Self::keep_all(),
+        }
+    }
+
+    fn keep_all() -> Self {
+        Self {
+            keep: true,
+            rule: None,
+            sample_rate: 100.0,
+        }
+    }
+}
+
+/// Deterministic per trace id, so every consumer that decides the same trace
+/// concurrently comes to the same answer.
+fn is_trace_in_sample(trace_id: Uuid, sample_rate: f64) -> bool {
+    ((trace_id.as_u128() % 10_000) as f64) < sample_rate * 100.0
+}
+
+/// Filter columns mirror `Trace::matches_filters`, evaluated over the
+/// aggregation of every span seen for the trace.
+fn trace_matches_filter(trace: &TraceAggregation, filter: &Filter) -> bool {
+    match filter.column.as_str() {
+        "status" => {
+            // Normalized the same way as `spans_v0`: anything but error is success
+            let status = if trace.status.as_deref() == Some("error") {
+                "error"
+            } else {
+                "success"
+            };
+            evaluate_string_filter(status, &filter.operator, &filter.value)
+        }
+        "cost" => evaluate_number_filter(trace.total_cost, &filter.operator, &filter.value),
+        "input_cost" => evaluate_number_filter(trace.input_cost, &filter.operator, &filter.value),
+        "output_cost" => evaluate_number_filter(trace.output_cost, &filter.operator, &filter.value),
+        "total_token_count" => {
+            evaluate_number_filter(trace.total_tokens as f64, &filter.operator, &filter.value)
+        }
+        "num_spans" => {
+            evaluate_number_filter(trace.num_spans as f64, &filter.operator, &filter.value)
+        }
+        "session_id" => evaluate_string_filter(
+            trace.session_id.as_deref().unwrap_or_default(),
+            &filter.operator,
+            &filter.value,
+        ),
+        "user_id" => evaluate_string_filter(
+            trace.user_id.as_deref().unwrap_or_default(),
+            &filter.operator,
+            &filter.value,
+        ),
+        "tags" => {
+            let tags: Vec<String> = trace.tags.iter().cloned().collect();
+            evaluate_array_contains_filter(&tags, &filter.operator, &filter.value)
+        }
+        "trace_type" => {
+            let trace_type = match trace.trace_type {
+                1 => "EVALUATION",
+                2 => "EVENT",
+                3 => "PLAYGROUND",
+                _ => "DEFAULT",
+            };
+            evaluate_string_filter(trace_type, &filter.operator, &filter.value)
+        }
+        "top_span_name" => evaluate_string_filter(
+            trace.top_span_name.as_deref().unwrap_or_default(),
+            &filter.operator,
+            &filter.value,
+        ),
+        "span_name" => {
+            let has_span = trace
+                .span_names
+                .contains(filter.value.as_str().unwrap_or_default());
+            match filter.operator {
+                FilterOperator::Eq => has_span,
+                FilterOperator::Ne => !has_span,
+                _ => {
+                    log::warn!(
+                        "Invalid operator {:?} for span_name sampling filter, only eq/ne supported",
+                        filter.operator
+                    );
+                    false
+                }
+            }
+        }
+        _ => {
+            log::warn!("Unknown sampling filter column: {}", filter.column);
+            false
+        }
+    }
+}
+
+/// A span held back until its trace is decided. Stored after usage
+/// extraction and `prepare_span_for_recording`, so releasing it splices it
+/// straight back into the batch without re-running the consumer preamble.
+#[derive(Serialize, Deserialize)]
+struct BufferedSpan {
+    message: RabbitMqSpanMessage,
+    usage: SpanUsage,
+}
+
+pub struct TailSamplingResult {
+    /// Spans to ingest: this batch's spans of unsampled projects and of kept
+    /// traces, plus buffered spans released by this batch.
+    pub messages: Vec<RabbitMqSpanMessage>,
+    pub span_usage_vec: Vec<SpanUsage>,
+    /// Decision for every trace with spans in `messages` that was sampled.
+    pub decisions: HashMap<(Uuid, Uuid), TraceSamplingDecision>,
+}
+
+impl TailSamplingResult {
+    fn keep(&mut self, spans: Vec<BufferedSpan>) {
+        for span in spans {
+            self.messages.push(span.message);
+            self.span_usage_vec.push(span.usage);
+        }
+    }
+
+    fn apply(
+        &mut self,
+        project_id: Uuid,
+        trace_id: Uuid,
+        decision: TraceSamplingDecision,
+        spans: Vec<BufferedSpan>,
+    ) {
+        if decision.keep {
+            self.keep(spans);
+            self.decisions.insert((project_id, trace_id), decision);
+        } else {
+            log::debug!(
+                "Dropping {} spans of trace {} (project {}) by sampling rule {:?}",
+                spans.len(),
+                trace_id,
+                project_id,
+                decision.rule
+            );
+        }
+    }
+}
+
+/// Apply per-project tail-based sampling to a batch after usage extraction.
+/// Every cache failure fails open: the affected spans are kept undecided
+/// rather than lost.
+pub async fn apply_tail_sampling(
+    messages: Vec<RabbitMqSpanMessage>,
+    span_usage_vec: Vec<SpanUsage>,
+    db: Arc<DB>,
+    cache: Arc<Cache>,
+) -> TailSamplingResult {
+    let load_rules =
+        async |project_id: Uuid| get_sampling_rules(db.clone(), cache.clone(), project_id).await;
+    tail_sample(messages, span_usage_vec, &cache, &load_rules).await
+}
+
+/// The buffer / decide / release state machine behind `apply_tail_sampling`,
+/// with the project settings lookup passed in.
+async fn tail_sample(
+    messages: Vec<RabbitMqSpanMessage>,
+    span_usage_vec: Vec<SpanUsage>,
+    cache: &Cache,
+    load_rules: &impl AsyncFn(Uuid) -> Vec<TraceSamplingRule>,
+) -> TailSamplingResult {
+    let mut result = TailSamplingResult {
+        messages: Vec::with_capacity(messages.len()),
+        span_usage_vec: Vec::with_capacity(span_usage_vec.len()),
+        decisions: HashMap::new(),
+    };
+
+    let mut rules_by_project: HashMap<Uuid, Vec<TraceSamplingRule>> = HashMap::new();
+    for project_id in messages.iter().map(|m| m.span.project_id).unique() {
+        rules_by_project.insert(project_id, load_rules(project_id).await);
+    }
+
+    let mut spans_by_trace: HashMap<(Uuid, Uuid), Vec<BufferedSpan>> = HashMap::new();
+    for (message, usage) in messages.into_iter().zip(span_usage_vec) {
+        let project_id = message.span.project_id;
+        let span = BufferedSpan { message, usage };
+        if rules_by_project
+            .get(&project_id)
+            .is_some_and(|rules| !rules.is_empty())
+        {
+            spans_by_trace
+                .entry((project_id, span.message.span.trace_id))
+                .or_default()
+                .push(span);
+        } else {
+            result.keep(vec![span]);
+        }
+    }
+
+    for ((project_id, trace_id), mut spans) in spans_by_trace {
+        if let Some(decision) = get_decision(cache, project_id, trace_id).await {
+            result.apply(project_id, trace_id, decision, spans);
+            continue;
+        }
+
+        // Root spans end last, so the root arriving means the trace is complete
+        if spans
+            .iter()
+            .any(|s| s.message.span.parent_span_id.is_none())
+        {
+            let Some(mut buffered) = take_buffer(cache, project_id, trace_id).await else {
+                result.keep(spans);
+                continue;
+            };
+            buffered.append(&mut spans);
+            let _ = cache
+                .zrem(
+                    TRACE_SAMPLING_PENDING_CACHE_KEY,
+                    &pending_member(project_id, trace_id),
+                )
+                .await;
+            let decision = decide(cache, &rules_by_project[&project_id], &buffered).await;
+            result.apply(project_id, trace_id, decision, buffered);
+            continue;
+        }
+
+        if let Err(e) = buffer_spans(cache, project_id, trace_id, &spans).await {
+            log::warn!(
+                "Failed to buffer {} spans of trace {} for sampling, ingesting undecided: {:?}",
+                spans.len(),
+                trace_id,
+                e
+            );
+            result.keep(spans);
+            continue;
+        }
+
+        // Another consumer may have decided the trace between our decision
+        // lookup and the buffer push; release what we just buffered.
+        if let Some(decision) = get_decision(cache, project_id, trace_id).await
+            && let Some(buffered) = take_buffer(cache, project_id, trace_id).await
+        {
+            result.apply(project_id, trace_id, decision, buffered);
+        }
+    }
+
+    sweep_timed_out_traces(&mut result, &mut rules_by_project, cache, load_rules).await;
+
+    result
+}
+
+/// Decide traces that have waited past their deadline without a root span.
+/// Piggybacks on regular batches instead of a dedicated background task, so
+/// it runs at the ingest cadence.
+async fn sweep_timed_out_traces(
+    result: &mut TailSamplingResult,
+    rules_by_project: &mut HashMap<Uuid, Vec<TraceSamplingRule>>,
+    cache: &Cache,
+    load_rules: &impl AsyncFn(Uuid) -> Vec<TraceSamplingRule>,
+) {
+    let members = match cache
+        .zpop_by_score(
+            TRACE_SAMPLING_PENDING_CACHE_KEY,
+            Utc::now().timestamp() as f64,
+            TAIL_SAMPLING_SWEEP_LIMIT,
+        )
+        .await
+    {
+        Ok(members) => members,
+        Err(e) => {
+            log::warn!("Failed to read timed-out sampling traces: {:?}", e);
+            return;
+        }
+    };
+
+    for member in members {
+        let Some((project_id, trace_id)) = parse_pending_member(&member) else {
+            log::warn!("Malformed sampling pending member: {}", member);
+            continue;
+        };
+        let Some(buffered) = take_buffer(cache, project_id, trace_id).await else {
+            continue;
+        };
+        if buffered.is_empty() {
+            continue;
+        }
+
+        let decision = match get_decision(cache, project_id, trace_id).await {
+            Some(decision) => decision,
+            None => {
+                if !rules_by_project.contains_key(&project_id) {
+                    rules_by_project.insert(project_id, load_rules(project_id).await);
+                }
+                decide(cache, &rules_by_project[&project_id], &buffered).await
+            }
+        };
+        result.apply(project_id, trace_id, decision, buffered);
+    }
+}
+
+async fn get_sampling_rules(
+    db: Arc<DB>,
+    cache: Arc<Cache>,
+    project_id: Uuid,
+) -> Vec<TraceSamplingRule> {
+    match get_workspace_info_for_project_id(db, cache, project_id).await {
+        Ok(Some(info)) => info.settings.trace_sampling_rules,
+        Ok(None) => Vec::new(),
+        Err(e) => {
+            log::warn!(
+                "Failed to get sampling rules for project {}: {:?}",
+                project_id,
+                e
+            );
+            Vec::new()
+        }
+    }
+}
+
+async fn decide(
+    cache: &Cache,
+    rules: &[TraceSamplingRule],
+    spans: &[BufferedSpan],
+) -> TraceSamplingDecision {
+    let Some(trace) =
+        TraceAggregation::from_spans(spans.iter().map(|s| (&s.message.span, &s.usage))).pop()
+    else {
+        return TraceSamplingDecision::keep_all();
+    };
+
+    let decision = TraceSamplingDecision::evaluate(rules, &trace);
+    let key = decision_key(trace.project_id, trace.trace_id);
+    if let Err(e) = cache
+        .insert_with_ttl(&key, decision.clone(), TAIL_SAMPLING_DECISION_TTL_SECONDS)
+        .await
+    {
+        log::warn!(
+            "Failed to cache sampling decision for trace {}: {:?}",
+            trace.trace_id,
+            e
+        );
+    }
+    decision
+}
+
+async fn get_decision(
+    cache: &Cache,
+    project_id: Uuid,
+    trace_id: Uuid,
+) -> Option<TraceSamplingDecision> {
+    match cache
+        .get::<TraceSamplingDecision>(&decision_key(project_id, trace_id))
+        .await
+    {
+        Ok(decision) => decision,
+        Err(e) => {
+            log::warn!(
+                "Failed to read sampling decision for trace {}: {:?}",
+                trace_id,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// `None` if the buffer could not be read; the caller then keeps its spans
+/// undecided, and whatever was buffered is picked up by the sweep.
+async fn take_buffer(cache: &Cache, project_id: Uuid, trace_id: Uuid) -> Option<Vec<BufferedSpan>> {
+    match cache
+        .take_list::<BufferedSpan>(&buffer_key(project_id, trace_id))
+        .await
+    {
+        Ok(spans) => Some(spans),
+        Err(e) => {
+            log::warn!(
+                "Failed to read sampling buffer for trace {}: {:?}",
+                trace_id,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Push spans to the trace's buffer and register the trace's decision
+/// deadline. On error nothing was buffered and the caller still owns the spans.
+async fn buffer_spans(
+    cache: &Cache,
+    project_id: Uuid,
+    trace_id: Uuid,
+    spans: &[BufferedSpan],
+) -> Result<(), CacheError> {
+    cache
+        .push_to_list(
+            &buffer_key(project_id, trace_id),
+            spans.iter().collect::<Vec<_>>(),
+            TAIL_SAMPLING_BUFFER_TTL_SECONDS,
+        )
+        .await?;
+
+    // NX: the deadline is set by the first buffered span and never pushed back
+    let deadline = Utc::now().timestamp() + TAIL_SAMPLING_DECISION_WAIT_SECONDS;
+    if let Err(e) = cache
+        .zadd(
+            TRACE_SAMPLING_PENDING_CACHE_KEY,
+            deadline as f64,
+            &pending_member(project_id, trace_id),
+        )
+        .await
+    {
+        // The spans are buffered; without a deadline they are only released
+        // by the root span or lost with the buffer TTL.
+        log::error!(
+            "Failed to register sampling deadline for trace {}: {:?}",
+            trace_id,
+            e
+        );
+    }
+    Ok(())
+}
+
+fn buffer_key(project_id: Uuid, trace_id: Uuid) -> String {
+    format!("{TRACE_SAMPLING_BUFFER_CACHE_KEY}:{project_id}:{trace_id}")
+}
+
+fn decision_key(project_id: Uuid, trace_id: Uuid) -> String {
+    format!("{TRACE_SAMPLING_DECISION_CACHE_KEY}:{project_id}:{trace_id}")
+}
+
+fn pending_member(project_id: Uuid, trace_id: Uuid) -> String {
+    format!("{project_id}:{trace_id}")
+}
+
+fn parse_pending_member(member: &str) -> Option<(Uuid, Uuid)> {
+    let (project_id, trace_id) = member.split_once(':')?;
+    Some((project_id.parse().ok()?, trace_id.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{db::spans::Span, traces::spans::SpanAttributes};
+    use serde_json::json;
+
+    fn make_usage(total_cost: f64) -> SpanUsage {
+        SpanUsage {
+            input_tokens: 0,
+            output_tokens: 0,
+            total_tokens: 0,
+            cache_read_input_tokens: 0,
+            cache_creation_input_tokens: 0,
+            reasoning_tokens: 0,
+            input_cost: 0.0,
+            output_cost: 0.0,
+            total_cost,
+            request_model: None,
+            response_model: None,
+            provider_name: None,
+        }
+    }
+
+    fn make_trace(trace_id: Uuid, status: Option<&str>, total_cost: f64) -> TraceAggregation {
+        let span = Span {
+            span_id: Uuid::new_v4(),
+            trace_id,
+            project_id: Uuid::new_v4(),
+            name: "root".to_string(),
+            status: status.map(String::from),
+            ..Default::default()
+        };
+        let usage = make_usage(total_cost);
+        TraceAggregation::from_spans([(&span, &usage)])
+            .pop()
+            .unwrap()
+    }
+
+    fn rule(name: &str, filters: serde_json::Value, sample_rate: f64) -> TraceSamplingRule {
+        TraceSamplingRule {
+            name: name.to_string(),
+            filters: serde_json::from_value(filters).unwrap(),
+            sample_rate,
+        }
+    }
+
+    fn rules() -> Vec<TraceSamplingRule> {
+        vec![
+            rule(
+                "errors",
+                json!([{"column": "status", "operator": "eq", "value": "error"}]),
+                100.0,
+            ),
+            rule(
+                "expensive",
+                json!([{"column": "cost", "operator": "gt", "value": 1.5}]),
+                100.0,
+            ),
+            rule(
+                "default",
+                json!([
+                    {"column": "status", "operator": "eq", "value": "success"},
+                    {"column": "trace_type", "operator": "eq", "value": "DEFAULT"}
+                ]),
+                5.0,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_first_matching_rule_decides() {
+        let decision = TraceSamplingDecision::evaluate(
+            &rules(),
+            &make_trace(Uuid::new_v4(), Some("error"), 10.0),
+        );
+        assert_eq!(
+            decision,
+            TraceSamplingDecision {
+                keep: true,
+                rule: Some("errors".to_string()),
+                sample_rate: 100.0,
+            }
+        );
+
+        let decision =
+            TraceSamplingDecision::evaluate(&rules(), &make_trace(Uuid::new_v4(), None, 2.0));
+        assert!(decision.keep);
+        assert_eq!(decision.rule, Some("expensive".to_string()));
+    }
+
+    #[test]
+    fn test_downsampling_is_deterministic_per_trace() {
+        // as_u128() % 10_000 == 0 and 9_999 respectively
+        let sampled_in = Uuid::from_u128(20_000);
+        let sampled_out = Uuid::from_u128(29_999);
+
+        let decision =
+            TraceSamplingDecision::evaluate(&rules(), &make_trace(sampled_in, None, 0.0));
+        assert!(decision.keep);
+        assert_eq!(decision.rule, Some("default".to_string()));
+        assert_eq!(decision.sample_rate, 5.0);
+
+        let decision =
+            TraceSamplingDecision::evaluate(&rules(), &make_trace(sampled_out, None, 0.0));
+        assert!(!decision.keep);
+        assert_eq!(decision.rule, Some("default".to_string()));
+    }
+
+    #[test]
+    fn test_no_matching_rule_keeps_trace() {
+        let mut trace = make_trace(Uuid::from_u128(29_999), None, 0.0);
+        trace.trace_type = 1;
+        let decision = TraceSamplingDecision::evaluate(&rules(), &trace);
+        assert_eq!(decision, TraceSamplingDecision::keep_all());
+    }
+
+    #[test]
+    fn test_session_and_tag_rules() {
+        let span = Span {
+            trace_id: Uuid::from_u128(29_999),
+            attributes: SpanAttributes::new(
+                [
+                    (
+                        "lmnr.association.properties.session_id".to_string(),
+                        json!("s-1"),
+                    ),
+                    (
+                        "lmnr.association.properties.tags".to_string(),
+                        json!(["vip"]),
+                    ),
+                ]
+                .into(),
+            ),
+            ..Default::default()
+        };
+        let usage = make_usage(0.0);
+        let trace = TraceAggregation::from_spans([(&span, &usage)])
+            .pop()
+            .unwrap();
+
+        let session_rules = vec![
+            rule(
+                "session",
+                json!([{"column": "session_id", "operator": "eq", "value": "s-1"}]),
+                100.0,
+            ),
+            rule("rest", json!([]), 0.0),
+        ];
+        assert!(TraceSamplingDecision::evaluate(&session_rules, &trace).keep);
+
+        let tag_rules = vec![
+            rule(
+                "vip",
+                json!([{"column": "tags", "operator": "eq", "value": "vip"}]),
+                100.0,
+            ),
+            rule("rest", json!([]), 0.0),
+        ];
+        assert!(TraceSamplingDecision::evaluate(&tag_rules, &trace).keep);
+
+        let other_rules = vec![
+            rule(
+                "vip",
+                json!([{"column": "tags", "operator": "eq", "value": "other"}]),
+                100.0,
+            ),
+            rule("rest", json!([]), 0.0),
+        ];
+        assert!(!TraceSamplingDecision::evaluate(&other_rules, &trace).keep);
+    }
+
+    fn make_message(project_id: Uuid, trace_id: Uuid, root: bool) -> RabbitMqSpanMessage {
+        RabbitMqSpanMessage {
+            span: Span {
+                span_id: Uuid::new_v4(),
+                project_id,
+                trace_id,
+                parent_span_id: (!root).then(Uuid::new_v4),
+                ..Default::default()
+            },
+            pre_processed: true,
+            input_dedup: None,
+            output_dedup: None,
+            tool_dedup: None,
+        }
+    }
+
+    async fn run_batch(
+        cache: &Cache,
+        rules: &[TraceSamplingRule],
+        messages: Vec<RabbitMqSpanMessage>,
+    ) -> TailSamplingResult {
+        let usage = messages.iter().map(|_| make_usage(0.0)).collect();
+        let load_rules = async |_: Uuid| rules.to_vec();
+        tail_sample(messages, usage, cache, &load_rules).await
+    }
+
+    fn make_cache() -> Cache {
+        Cache::InMemory(crate::cache::in_memory::InMemoryCache::new(None))
+    }
+
+    fn keep_all_rules() -> Vec<TraceSamplingRule> {
+        vec![rule("all", json!([]), 100.0)]
+    }
+
+    fn drop_all_rules() -> Vec<TraceSamplingRule> {
+        vec![rule("none", json!([]), 0.0)]
+    }
+
+    #[tokio::test]
+    async fn test_children_are_buffered_until_root_releases_them() {
+        let cache = make_cache();
+        let (project_id, trace_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let result = run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![
+                make_message(project_id, trace_id, false),
+                make_message(project_id, trace_id, false),
+            ],
+        )
+        .await;
+        assert!(result.messages.is_empty());
+        assert!(result.decisions.is_empty());
+
+        let result = run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![make_message(project_id, trace_id, true)],
+        )
+        .await;
+        assert_eq!(result.messages.len(), 3);
+        assert_eq!(result.span_usage_vec.len(), 3);
+        assert_eq!(
+            result.decisions[&(project_id, trace_id)].rule,
+            Some("all".to_string())
+        );
+        // The trace is decided, so nothing is left pending for the sweep
+        assert!(
+            cache
+                .zpop_by_score(TRACE_SAMPLING_PENDING_CACHE_KEY, f64::MAX, 10)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dropped_trace_drops_late_spans_without_buffering() {
+        let cache = make_cache();
+        let (project_id, trace_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let result = run_batch(
+            &cache,
+            &drop_all_rules(),
+            vec![
+                make_message(project_id, trace_id, false),
+                make_message(project_id, trace_id, true),
+            ],
+        )
+        .await;
+        assert!(result.messages.is_empty());
+        assert!(result.decisions.is_empty());
+
+        let result = run_batch(
+            &cache,
+            &drop_all_rules(),
+            vec![make_message(project_id, trace_id, false)],
+        )
+        .await;
+        assert!(result.messages.is_empty());
+        let buffered = cache
+            .take_list::<BufferedSpan>(&buffer_key(project_id, trace_id))
+            .await
+            .unwrap();
+        assert!(buffered.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_late_spans_follow_kept_decision() {
+        let cache = make_cache();
+        let (project_id, trace_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![make_message(project_id, trace_id, true)],
+        )
+        .await;
+        let result = run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![make_message(project_id, trace_id, false)],
+        )
+        .await;
+        assert_eq!(result.messages.len(), 1);
+        assert!(result.decisions.contains_key(&(project_id, trace_id)));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_decides_traces_without_root_after_deadline() {
+        let cache = make_cache();
+        let (project_id, trace_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![make_message(project_id, trace_id, false)],
+        )
+        .await;
+
+        // Not due yet
+        let result = run_batch(&cache, &keep_all_rules(), Vec::new()).await;
+        assert!(result.messages.is_empty());
+
+        // Move the deadline into the past
+        let member = pending_member(project_id, trace_id);
+        cache
+            .zrem(TRACE_SAMPLING_PENDING_CACHE_KEY, &member)
+            .await
+            .unwrap();
+        cache
+            .zadd(TRACE_SAMPLING_PENDING_CACHE_KEY, 0.0, &member)
+            .await
+            .unwrap();
+
+        let result = run_batch(&cache, &keep_all_rules(), Vec::new()).await;
+        assert_eq!(result.messages.len(), 1);
+        assert!(result.decisions.contains_key(&(project_id, trace_id)));
+
+        // Released once only
+        let result = run_batch(&cache, &keep_all_rules(), Vec::new()).await;
+        assert!(result.messages.is_empty());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_undecided_buffer_expires() {
+        let cache = make_cache();
+        let (project_id, trace_id) = (Uuid::new_v4(), Uuid::new_v4());
+
+        run_batch(
+            &cache,
+            &keep_all_rules(),
+            vec![make_message(project_id, trace_id, false)],
+        )
+        .await;
+        tokio::time::advance(std::time::Duration::from_secs(
+            TAIL_SAMPLING_BUFFER_TTL_SECONDS + 1,
+        ))
+        .await;
+
+        let buffered = cache
+            .take_list::<BufferedSpan>(&buffer_key(project_id, trace_id))
+            .await
+            .unwrap();
+        assert!(buffered.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_projects_without_rules_pass_through() {
+        let cache = make_cache();
+        let result = run_batch(
+            &cache,
+            &[],
+            vec![make_message(Uuid::new_v4(), Uuid::new_v4(), false)],
+        )
+        .await;
+        assert_eq!(result.messages.len(), 1);
+        assert!(result.decisions.is_empty());
+    }
+
+    #[test]
+    fn test_parse_pending_member() {
+        let project_id = Uuid::new_v4();
+        let trace_id = Uuid::new_v4();
+        assert_eq!(
+            parse_pending_member(&pending_member(project_id, trace_id)),
+            Some((project_id, trace_id))
+        );
+        assert_eq!(parse_pending_member("garbage"), None);
+    }
+}
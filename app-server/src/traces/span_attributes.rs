@@ -63,6 +63,8 @@ pub const GEN_AI_USAGE_CACHE_CREATION_EPHEMERAL_5M_TOKENS: &str =
 pub const GEN_AI_USAGE_CACHE_CREATION_EPHEMERAL_1H_TOKENS: &str =
     "gen_ai.usage.cache_creation.input_tokens.ephemeral_1h";
 pub const GEN_AI_REQUEST_BATCH: &str = "gen_ai.request.batch";
+pub const GEN_AI_USAGE_WEB_SEARCH_CALLS: &str = "gen_ai.usage.web_search_calls";
+pub const GEN_AI_USAGE_IMAGE_GENERATION_UNITS: &str = "gen_ai.usage.image_generation_units";
 
 // Service tier
 pub const GEN_AI_RESPONSE_SERVICE_TIER: &str = "gen_ai.response.service_tier";
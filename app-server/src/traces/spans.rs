@@ -14,7 +14,7 @@ use uuid::Uuid;
 use crate::{
     db::{
         events::Event,
-        spans::{Span, SpanType},
+        spans::{Span, SpanLink, SpanType},
         trace::TraceType,
         utils::span_id_to_uuid,
     },
@@ -725,6 +725,12 @@ impl Span {
             .map(|event| Event::from_otel(event, span_id, project_id, trace_id))
             .collect();
 
+        let links = otel_span
+            .links
+            .into_iter()
+            .filter_map(SpanLink::from_otel)
+            .collect();
+
         let attributes = otel_span
             .attributes
             .into_iter()
@@ -743,6 +749,7 @@ impl Span {
             start_time: Utc.timestamp_nanos(otel_span.start_time_unix_nano as i64),
             end_time: Utc.timestamp_nanos(otel_span.end_time_unix_nano as i64),
             events,
+            links,
             ..Default::default()
         };
 
@@ -1094,6 +1101,11 @@ impl Span {
                 .events
                 .iter()
                 .map(|event| event.estimate_size_bytes())
+                .sum::<usize>()
+            + self
+                .links
+                .iter()
+                .map(|link| link.estimate_size_bytes())
                 .sum::<usize>();
         self.size_bytes = size_bytes;
     }
@@ -1272,6 +1284,7 @@ pub fn should_keep_attribute(attribute: &str) -> bool {
     true
 }
 
+#[derive(Serialize, Deserialize)]
 pub struct SpanUsage {
     pub input_tokens: i64,
     pub output_tokens: i64,
@@ -1375,7 +1388,7 @@ fn input_chat_messages_from_genai_attributes(
     input_messages
 }
 
-fn input_chat_messages_from_json(input: &serde_json::Value) -> Result<Vec<ChatMessage>> {
+pub(crate) fn input_chat_messages_from_json(input: &serde_json::Value) -> Result<Vec<ChatMessage>> {
     if let Some(messages) = input.as_array() {
         messages
             .iter()
@@ -1764,6 +1777,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -2090,6 +2104,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -2436,6 +2451,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -2583,6 +2599,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -3056,6 +3073,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -3210,6 +3228,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -3471,6 +3490,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -3647,6 +3667,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -3707,6 +3728,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4113,6 +4135,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4221,6 +4244,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4267,6 +4291,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4301,6 +4326,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4328,6 +4354,7 @@ mod tests {
             input: None,
             output: None,
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
@@ -4507,6 +4534,7 @@ mod tests {
             start_time: Utc::now(),
             end_time: Utc::now(),
             events: vec![],
+            links: vec![],
             status: None,
             tags: None,
             input_url: None,
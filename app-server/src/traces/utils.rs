@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::{Arc, LazyLock};
 
+use chrono::{DateTime, Utc};
 use indexmap::IndexMap;
 use regex::Regex;
 use serde_json::{Value, json};
@@ -22,15 +23,17 @@ use super::span_attributes::{
     GEN_AI_REQUEST_SERVICE_TIER, GEN_AI_RESPONSE_SERVICE_TIER, GEN_AI_SYSTEM,
     GEN_AI_USAGE_AUDIO_INPUT_TOKENS, GEN_AI_USAGE_AUDIO_OUTPUT_TOKENS,
     GEN_AI_USAGE_CACHE_CREATION_EPHEMERAL_1H_TOKENS,
-    GEN_AI_USAGE_CACHE_CREATION_EPHEMERAL_5M_TOKENS, GEN_AI_USAGE_REASONING_TOKENS,
-    OPENAI_REQUEST_SERVICE_TIER, OPENAI_RESPONSE_SERVICE_TIER, SPAN_PROMPT_HASH,
+    GEN_AI_USAGE_CACHE_CREATION_EPHEMERAL_5M_TOKENS, GEN_AI_USAGE_IMAGE_GENERATION_UNITS,
+    GEN_AI_USAGE_REASONING_TOKENS, GEN_AI_USAGE_WEB_SEARCH_CALLS, OPENAI_REQUEST_SERVICE_TIER,
+    OPENAI_RESPONSE_SERVICE_TIER, SPAN_PROMPT_HASH,
 };
 use super::spans::{SpanAttributes, SpanUsage};
 
 static SKIP_SPAN_NAME_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^Runnable[A-Z][A-Za-z]*(?:<[A-Za-z_,]+>)*\.task$").unwrap());
 
-/// Calculate usage for both default and LLM spans
+/// Calculate usage for both default and LLM spans.
+/// Costs are priced with the model price version in effect at `span_time`.
 pub async fn get_llm_usage_for_span(
     // mut because input and output tokens are updated to new convention
     attributes: &mut SpanAttributes,
@@ -38,6 +41,7 @@ pub async fn get_llm_usage_for_span(
     cache: Arc<Cache>,
     span_name: &str,
     project_id: &Uuid,
+    span_time: DateTime<Utc>,
 ) -> SpanUsage {
     let input_tokens = attributes.input_tokens();
     let output_tokens = attributes.output_tokens();
@@ -97,15 +101,18 @@ pub async fn get_llm_usage_for_span(
             project_id,
             orig_provider_name.as_deref().unwrap_or(""),
             orig_model_name.as_deref().unwrap_or(""),
+            span_time,
         )
         .await
         {
             let span_cost_input: SpanCostInput =
                 build_span_cost_input(attributes, &input_tokens, output_tokens);
-            let cost_entry = calculate_span_cost(&model_costs, &span_cost_input);
+            let cost_entry = calculate_span_cost(&model_costs.costs, &span_cost_input)
+                .scaled(model_costs.multiplier);
             input_cost = cost_entry.input_cost;
             output_cost = cost_entry.output_cost;
-            total_cost = input_cost + output_cost;
+            // Per-request fees only show up in the total
+            total_cost = cost_entry.total_cost();
         }
     } else if let Some(provider) = attributes
         .raw_attributes
@@ -169,6 +176,13 @@ fn build_span_cost_input(
 
     let is_batch = attributes.bool_attr(GEN_AI_REQUEST_BATCH).unwrap_or(false);
 
+    let web_search_calls = attributes
+        .int_attr(GEN_AI_USAGE_WEB_SEARCH_CALLS)
+        .unwrap_or(0);
+    let image_generation_units = attributes
+        .int_attr(GEN_AI_USAGE_IMAGE_GENERATION_UNITS)
+        .unwrap_or(0);
+
     SpanCostInput {
         prompt_tokens: input_tokens.regular_input_tokens,
         completion_tokens: output_tokens,
@@ -181,6 +195,8 @@ fn build_span_cost_input(
         reasoning_tokens,
         service_tier,
         is_batch,
+        web_search_calls,
+        image_generation_units,
     }
 }
 
@@ -121,8 +121,11 @@ pub enum WorkerType {
     #[cfg_attr(not(feature = "signals"), allow(dead_code))]
     SignalJobRealtime,
     Logs,
+    Metrics,
     Reports,
     Checkpoints,
+    EvaluationJudges,
+    CostBackfill,
 }
 
 impl std::fmt::Display for WorkerType {
@@ -137,8 +140,11 @@ impl std::fmt::Display for WorkerType {
             WorkerType::SignalJobPendingBatch => write!(f, "signal_job_pending_batch"),
             WorkerType::SignalJobRealtime => write!(f, "signal_job_realtime"),
             WorkerType::Logs => write!(f, "logs"),
+            WorkerType::Metrics => write!(f, "metrics"),
             WorkerType::Reports => write!(f, "reports"),
             WorkerType::Checkpoints => write!(f, "checkpoints"),
+            WorkerType::EvaluationJudges => write!(f, "evaluation_judges"),
+            WorkerType::CostBackfill => write!(f, "cost_backfill"),
         }
     }
 }
@@ -0,0 +1,28 @@
+import { type NextRequest, NextResponse } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import { setAlertWebhookTargets } from "@/lib/actions/alerts";
+
+export async function POST(request: NextRequest, props: { params: Promise<{ projectId: string; alertId: string }> }) {
+  const { projectId, alertId } = await props.params;
+
+  try {
+    const body = await request.json();
+    const result = await setAlertWebhookTargets({ ...body, projectId, alertId });
+
+    if (!result) {
+      return NextResponse.json({ error: "Alert not found" }, { status: 404 });
+    }
+
+    return NextResponse.json(result);
+  } catch (error) {
+    console.error(error);
+    if (error instanceof ZodError) {
+      return NextResponse.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return NextResponse.json(
+      { error: error instanceof Error ? error.message : "Failed to set alert webhook targets." },
+      { status: 500 }
+    );
+  }
+}
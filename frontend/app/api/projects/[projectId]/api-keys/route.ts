@@ -22,6 +22,9 @@ export async function POST(req: NextRequest, props: { params: Promise<{ projectI
       isIngestOnly: body.isIngestOnly,
       userId: session?.user.id ?? null,
       expiresAt,
+      scopes: body.scopes,
+      allowedCidrs: body.allowedCidrs,
+      rateLimitPerMinute: body.rateLimitPerMinute,
     });
 
     return new Response(JSON.stringify(result), {
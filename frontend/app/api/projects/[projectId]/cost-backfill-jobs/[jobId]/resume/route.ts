@@ -0,0 +1,23 @@
+import { type NextRequest } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import { resumeCostBackfill } from "@/lib/actions/cost-backfill";
+
+export async function POST(
+  _req: NextRequest,
+  props: { params: Promise<{ projectId: string; jobId: string }> }
+): Promise<Response> {
+  try {
+    const params = await props.params;
+    const job = await resumeCostBackfill({ projectId: params.projectId, jobId: params.jobId });
+    return Response.json(job);
+  } catch (error) {
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to resume cost backfill." },
+      { status: 500 }
+    );
+  }
+}
@@ -0,0 +1,42 @@
+import { type NextRequest } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import { getCostBackfillJobs, startCostBackfill } from "@/lib/actions/cost-backfill";
+
+export async function GET(_req: NextRequest, props: { params: Promise<{ projectId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const jobs = await getCostBackfillJobs({ projectId: params.projectId });
+    return Response.json(jobs);
+  } catch (error) {
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to get cost backfill jobs." },
+      { status: 500 }
+    );
+  }
+}
+
+export async function POST(req: NextRequest, props: { params: Promise<{ projectId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const body = await req.json();
+    const job = await startCostBackfill({
+      projectId: params.projectId,
+      startTime: body.startTime,
+      endTime: body.endTime,
+      model: body.model,
+    });
+    return Response.json(job);
+  } catch (error) {
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to start cost backfill." },
+      { status: 500 }
+    );
+  }
+}
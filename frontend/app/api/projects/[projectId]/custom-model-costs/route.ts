@@ -44,6 +44,7 @@ export async function POST(req: NextRequest, props: { params: Promise<{ projectI
       provider: body.provider,
       model: body.model,
       costs: body.costs,
+      effectiveFrom: body.effectiveFrom,
       previousModel: body.previousModel,
       previousProvider: body.previousProvider,
     });
@@ -0,0 +1,24 @@
+import { type NextRequest } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import { PiiRevealForbiddenError, revealPseudonyms } from "@/lib/actions/pii";
+
+export async function POST(req: NextRequest, props: { params: Promise<{ projectId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const body = await req.json();
+    const result = await revealPseudonyms({ projectId: params.projectId, pseudonyms: body.pseudonyms });
+    return Response.json(result);
+  } catch (error) {
+    if (error instanceof PiiRevealForbiddenError) {
+      return Response.json({ error: error.message }, { status: 403 });
+    }
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to reveal pseudonyms." },
+      { status: 500 }
+    );
+  }
+}
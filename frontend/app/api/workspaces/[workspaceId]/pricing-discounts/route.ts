@@ -0,0 +1,69 @@
+import { type NextRequest } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import {
+  deletePricingDiscount,
+  DuplicatePricingDiscountError,
+  getPricingDiscounts,
+  upsertPricingDiscount,
+} from "@/lib/actions/pricing-discounts";
+
+export async function GET(_req: NextRequest, props: { params: Promise<{ workspaceId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const discounts = await getPricingDiscounts({ workspaceId: params.workspaceId });
+    return Response.json(discounts);
+  } catch (error) {
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to get pricing discounts." },
+      { status: 500 }
+    );
+  }
+}
+
+export async function POST(req: NextRequest, props: { params: Promise<{ workspaceId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const body = await req.json();
+    const result = await upsertPricingDiscount({
+      id: body.id,
+      workspaceId: params.workspaceId,
+      provider: body.provider,
+      multiplier: body.multiplier,
+      effectiveFrom: body.effectiveFrom,
+    });
+    return Response.json(result);
+  } catch (error) {
+    if (error instanceof DuplicatePricingDiscountError) {
+      return Response.json({ error: error.message }, { status: 409 });
+    }
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return Response.json(
+      { error: error instanceof Error ? error.message : "Failed to save pricing discount." },
+      { status: 500 }
+    );
+  }
+}
+
+export async function DELETE(req: NextRequest, props: { params: Promise<{ workspaceId: string }> }): Promise<Response> {
+  try {
+    const params = await props.params;
+    const id = req.nextUrl.searchParams.get("id") ?? "";
+    await deletePricingDiscount({ workspaceId: params.workspaceId, id });
+    return Response.json({ success: true });
+  } catch (error) {
+    if (error instanceof ZodError) {
+      return Response.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    if (error instanceof Error) {
+      const status = error.message === "Pricing discount not found" ? 404 : 500;
+      return Response.json({ error: error.message }, { status });
+    }
+    return Response.json({ error: "Failed to delete pricing discount." }, { status: 500 });
+  }
+}
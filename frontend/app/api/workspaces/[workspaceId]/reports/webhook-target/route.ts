@@ -0,0 +1,28 @@
+import { type NextRequest, NextResponse } from "next/server";
+import { prettifyError, ZodError } from "zod/v4";
+
+import { setWebhookTargets } from "@/lib/actions/reports";
+import { getServerSession } from "@/lib/auth-session";
+
+export async function POST(request: NextRequest, props: { params: Promise<{ workspaceId: string }> }) {
+  const { workspaceId } = await props.params;
+
+  try {
+    const session = await getServerSession();
+    if (!session?.user?.email) {
+      return NextResponse.json({ error: "Unauthorized" }, { status: 401 });
+    }
+    const body = await request.json();
+    const result = await setWebhookTargets({ ...body, workspaceId });
+    return NextResponse.json(result);
+  } catch (error) {
+    console.error(error);
+    if (error instanceof ZodError) {
+      return NextResponse.json({ error: prettifyError(error) }, { status: 400 });
+    }
+    return NextResponse.json(
+      { error: error instanceof Error ? error.message : "Failed to set webhook targets." },
+      { status: 500 }
+    );
+  }
+}
@@ -32,4 +32,5 @@ export const DEFAULT_VALUES: AlertFormValues = {
 export const ALERT_TYPE_DESCRIPTIONS: Record<AlertType, string> = {
   [ALERT_TYPE.SIGNAL_EVENT]: "Notify when a new signal event is detected.",
   [ALERT_TYPE.NEW_CLUSTER]: "Notify when a new cluster is created.",
+  [ALERT_TYPE.METRIC_THRESHOLD]: "Notify when a span metric crosses a threshold.",
 };
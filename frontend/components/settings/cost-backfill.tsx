@@ -0,0 +1,188 @@
+"use client";
+
+import { isEmpty } from "lodash";
+import { useParams } from "next/navigation";
+import { useState } from "react";
+import useSWR from "swr";
+
+import { type CostBackfillJob } from "@/lib/actions/cost-backfill";
+import { useToast } from "@/lib/hooks/use-toast";
+import { swrFetcher } from "@/lib/utils";
+
+import { Badge } from "../ui/badge";
+import { Button } from "../ui/button";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+import { SettingsSection, SettingsSectionHeader, SettingsTable, SettingsTableRow } from "./settings-section";
+
+const POLL_INTERVAL_MS = 3000;
+
+/** Must match `COST_BACKFILL_STALE_AFTER_MINUTES` in `app-server/src/db/cost_backfill_jobs.rs`. */
+const STALE_AFTER_MS = 30 * 60 * 1000;
+
+/** A running job that has not made progress for a while has lost its worker and can be resumed. */
+function isStalled(job: CostBackfillJob): boolean {
+  return job.status === "running" && Date.now() - new Date(job.updatedAt).getTime() > STALE_AFTER_MS;
+}
+
+/** Start of the given UTC day, or of the day after it, as an ISO timestamp. */
+function dayStart(date: string, nextDay = false): string {
+  const start = new Date(`${date}T00:00:00.000Z`);
+  if (nextDay) {
+    start.setUTCDate(start.getUTCDate() + 1);
+  }
+  return start.toISOString();
+}
+
+/** The stored end is exclusive, so the last day shown is the one before it. */
+function formatRange(job: CostBackfillJob): string {
+  const [year, month, day] = job.endTime.slice(0, 10).split("-").map(Number);
+  const end = new Date(Date.UTC(year, month - 1, day - 1));
+  return `${job.startTime.slice(0, 10)} – ${end.toISOString().slice(0, 10)}`;
+}
+
+function statusVariant(status: CostBackfillJob["status"]) {
+  switch (status) {
+    case "completed":
+      return "outlinePrimary";
+    case "failed":
+      return "destructive";
+    default:
+      return "secondary";
+  }
+}
+
+export default function CostBackfill() {
+  const { projectId } = useParams();
+  const { toast } = useToast();
+  const {
+    data: jobs,
+    mutate,
+    isLoading,
+  } = useSWR<CostBackfillJob[]>(`/api/projects/${projectId}/cost-backfill-jobs`, swrFetcher, {
+    refreshInterval: (latest) => (latest?.some((job) => job.status === "running") ? POLL_INTERVAL_MS : 0),
+  });
+
+  const [startDate, setStartDate] = useState("");
+  const [endDate, setEndDate] = useState("");
+  const [model, setModel] = useState("");
+  const [isStarting, setIsStarting] = useState(false);
+
+  const isValid = startDate !== "" && endDate !== "" && startDate <= endDate;
+
+  const readError = (res: Response) =>
+    res
+      .json()
+      .then((d) => d?.error)
+      .catch(() => null);
+
+  const startBackfill = async () => {
+    setIsStarting(true);
+    try {
+      const res = await fetch(`/api/projects/${projectId}/cost-backfill-jobs`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+          startTime: dayStart(startDate),
+          endTime: dayStart(endDate, true),
+          model: model.trim() || undefined,
+        }),
+      });
+      if (res.ok) {
+        mutate();
+        toast({ title: "Cost recalculation started" });
+        return;
+      }
+      toast({ variant: "destructive", title: (await readError(res)) ?? "Failed to start cost recalculation" });
+    } catch {
+      toast({ variant: "destructive", title: "Failed to start cost recalculation" });
+    } finally {
+      setIsStarting(false);
+    }
+  };
+
+  const resumeBackfill = async (jobId: string) => {
+    try {
+      const res = await fetch(`/api/projects/${projectId}/cost-backfill-jobs/${jobId}/resume`, {
+        method: "POST",
+      });
+      if (res.ok) {
+        mutate();
+        toast({ title: "Cost recalculation resumed" });
+        return;
+      }
+      toast({ variant: "destructive", title: (await readError(res)) ?? "Failed to resume cost recalculation" });
+    } catch {
+      toast({ variant: "destructive", title: "Failed to resume cost recalculation" });
+    }
+  };
+
+  return (
+    <SettingsSection>
+      <SettingsSectionHeader
+        size="sm"
+        title="Recalculate historical costs"
+        description="Price LLM spans that were recorded with zero cost, e.g. before a custom model cost was added, using the prices above. Trace totals are updated as well. Only workspace owners and admins can start a recalculation."
+      />
+      <div className="flex flex-wrap items-end gap-2">
+        <div className="flex flex-col gap-1">
+          <Label className="text-xs">From (UTC)</Label>
+          <Input type="date" value={startDate} onChange={(e) => setStartDate(e.target.value)} className="w-40" />
+        </div>
+        <div className="flex flex-col gap-1">
+          <Label className="text-xs">To (UTC, inclusive)</Label>
+          <Input type="date" value={endDate} onChange={(e) => setEndDate(e.target.value)} className="w-40" />
+        </div>
+        <div className="flex flex-col gap-1">
+          <Label className="text-xs">Model (optional)</Label>
+          <Input placeholder="All models" value={model} onChange={(e) => setModel(e.target.value)} className="w-56" />
+        </div>
+        <Button variant="outline" disabled={!isValid || isStarting} onClick={startBackfill}>
+          {isStarting ? "Starting..." : "Recalculate"}
+        </Button>
+      </div>
+      <SettingsTable
+        headers={["Range", "Model", "Status", "Spans priced", "Started", ""]}
+        isLoading={isLoading}
+        isEmpty={isEmpty(jobs)}
+        emptyMessage="No cost recalculations yet."
+        colSpan={6}
+      >
+        {jobs?.map((job) => (
+          <SettingsTableRow key={job.id}>
+            <td className="px-4">
+              <span className="text-xs whitespace-nowrap">{formatRange(job)}</span>
+            </td>
+            <td className="px-4">
+              <span className="text-xs text-muted-foreground">{job.model ?? "All models"}</span>
+            </td>
+            <td className="px-4">
+              <Badge variant={statusVariant(job.status)} title={job.error ?? undefined}>
+                {isStalled(job) ? "stalled" : job.status}
+              </Badge>
+            </td>
+            <td className="px-4">
+              <span className="text-xs text-muted-foreground whitespace-nowrap">
+                {job.updatedSpans.toLocaleString()} of {job.processedSpans.toLocaleString()} scanned
+              </span>
+            </td>
+            <td className="px-4">
+              <span className="text-xs text-muted-foreground whitespace-nowrap">
+                {new Date(job.createdAt).toLocaleString()}
+              </span>
+            </td>
+            <td className="px-4">
+              <div className="flex justify-end">
+                {(job.status === "failed" || isStalled(job)) && (
+                  <Button variant="ghost" size="sm" onClick={() => resumeBackfill(job.id)}>
+                    Resume
+                  </Button>
+                )}
+              </div>
+            </td>
+          </SettingsTableRow>
+        ))}
+      </SettingsTable>
+    </SettingsSection>
+  );
+}
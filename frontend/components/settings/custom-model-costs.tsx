@@ -35,9 +35,19 @@ const COST_FIELDS: readonly { key: string; label: string; required?: boolean }[]
   { key: "output_cost_per_token", label: "Output" },
 ];
 
+/** Flat fees in dollars per request or per unit, charged on top of token costs. */
+const FEE_FIELDS: readonly { key: string; label: string }[] = [
+  { key: "cost_per_request", label: "Per request" },
+  { key: "cost_per_web_search_call", label: "Per web search call" },
+  { key: "cost_per_image_generation_unit", label: "Per image" },
+];
+
 const PER_MILLION = 1_000_000;
 
-/** Convert per-token DB values to per-million for display */
+/** Date of the default `effective_from`, for versions that apply to all spans */
+const ALWAYS_EFFECTIVE_DATE = "1970-01-01";
+
+/** Convert per-token DB values to per-million for display. Fees are shown as-is. */
 function toPerMillion(costs: Record<string, number>): Record<string, string> {
   const result: Record<string, string> = {};
   for (const { key } of COST_FIELDS) {
@@ -45,18 +55,26 @@ function toPerMillion(costs: Record<string, number>): Record<string, string> {
       result[key] = String(parseFloat((costs[key] * PER_MILLION).toPrecision(12)));
     }
   }
+  for (const { key } of FEE_FIELDS) {
+    if (key in costs && costs[key] !== undefined) {
+      result[key] = String(costs[key]);
+    }
+  }
   return result;
 }
 
 /** Convert per-million input values to per-token for DB storage. Only includes non-empty fields. */
 function toPerToken(values: Record<string, string>): Record<string, number> {
   const result: Record<string, number> = {};
-  for (const { key } of COST_FIELDS) {
+  for (const { key, scale } of [
+    ...COST_FIELDS.map(({ key }) => ({ key, scale: PER_MILLION })),
+    ...FEE_FIELDS.map(({ key }) => ({ key, scale: 1 })),
+  ]) {
     const v = values[key];
     if (v !== undefined && v !== "") {
       const num = parseFloat(v);
       if (!isNaN(num)) {
-        result[key] = num / PER_MILLION;
+        result[key] = num / scale;
       }
     }
   }
@@ -71,15 +89,27 @@ function formatCostDisplay(costs: Record<string, number>): string {
       parts.push(`${label}: $${(costs[key] * PER_MILLION).toFixed(2)}`);
     }
   }
+  for (const { key, label } of FEE_FIELDS) {
+    if (key in costs) {
+      parts.push(`${label}: $${costs[key]}`);
+    }
+  }
   return parts.join(", ");
 }
 
+/** `YYYY-MM-DD` for a date input, or empty for versions that apply to all spans */
+function toDateInput(effectiveFrom?: string): string {
+  const date = effectiveFrom?.slice(0, 10) ?? "";
+  return date === ALWAYS_EFFECTIVE_DATE ? "" : date;
+}
+
 function ModelCostDialog({
   mode,
   id,
   initialProvider,
   initialModel,
   initialCosts,
+  initialEffectiveFrom,
   onSave,
   trigger,
 }: {
@@ -88,11 +118,13 @@ function ModelCostDialog({
   initialProvider?: string;
   initialModel?: string;
   initialCosts?: Record<string, number>;
+  initialEffectiveFrom?: string;
   onSave: (params: {
     id?: string;
     provider: string | undefined;
     model: string;
     costs: Record<string, number>;
+    effectiveFrom?: string;
     previousModel?: string;
     previousProvider?: string;
   }) => Promise<boolean>;
@@ -105,6 +137,7 @@ function ModelCostDialog({
   const [costValues, setCostValues] = useState<Record<string, string>>(
     mode === "edit" && initialCosts ? toPerMillion(initialCosts) : emptyFields()
   );
+  const [effectiveFrom, setEffectiveFrom] = useState(toDateInput(initialEffectiveFrom));
   const [validationError, setValidationError] = useState<string | undefined>();
   const [open, setOpen] = useState(false);
 
@@ -113,8 +146,8 @@ function ModelCostDialog({
   const handleSave = async () => {
     const costs = toPerToken(costValues);
 
-    if (!("input_cost_per_token" in costs) && !("output_cost_per_token" in costs)) {
-      setValidationError("At least input or output cost is required");
+    if (isEmpty(costs)) {
+      setValidationError("At least one cost or fee is required");
       return;
     }
 
@@ -129,6 +162,7 @@ function ModelCostDialog({
         provider: provider || undefined,
         model,
         costs,
+        effectiveFrom: effectiveFrom ? new Date(`${effectiveFrom}T00:00:00Z`).toISOString() : undefined,
         previousModel: isRekey ? initialModel : undefined,
         previousProvider: isRekey ? initialProvider : undefined,
       });
@@ -140,6 +174,7 @@ function ModelCostDialog({
       setProvider("");
       setModel("");
       setCostValues(emptyFields());
+      setEffectiveFrom("");
     }
     setValidationError(undefined);
     setOpen(false);
@@ -160,6 +195,7 @@ function ModelCostDialog({
             setProvider(initialProvider ?? "");
             setModel(initialModel ?? "");
             setCostValues(initialCosts ? toPerMillion(initialCosts) : emptyFields());
+            setEffectiveFrom(toDateInput(initialEffectiveFrom));
           }
           setValidationError(undefined);
         } else {
@@ -167,6 +203,7 @@ function ModelCostDialog({
             setProvider("");
             setModel("");
             setCostValues(emptyFields());
+            setEffectiveFrom("");
           }
           setValidationError(undefined);
         }
@@ -179,7 +216,7 @@ function ModelCostDialog({
           <DialogDescription>
             {mode === "edit"
               ? "Update pricing for this model. Changes override global model prices."
-              : "Define pricing for a specific model in this project. These override global model prices."}
+              : "Define pricing for a specific model in this project. These override global model prices. Add another entry with a later effective date to record a price change."}
           </DialogDescription>
         </DialogHeader>
         <div className="flex flex-col gap-4 overflow-y-auto pr-2">
@@ -223,6 +260,36 @@ function ModelCostDialog({
               ))}
             </div>
           </div>
+          <div className="flex flex-col gap-3">
+            <div>
+              <Label>Fees ($)</Label>
+              <p className="text-xs text-muted-foreground mt-1">
+                Flat fees charged per request or per unit, on top of token costs.
+              </p>
+            </div>
+            <div className="grid grid-cols-2 gap-3">
+              {FEE_FIELDS.map(({ key, label }) => (
+                <div key={key} className="flex flex-col gap-1">
+                  <Label className="text-xs text-muted-foreground">{label}</Label>
+                  <Input
+                    type="number"
+                    min="0"
+                    step="any"
+                    placeholder="—"
+                    value={costValues[key] ?? ""}
+                    onChange={(e) => updateField(key, e.target.value)}
+                  />
+                </div>
+              ))}
+            </div>
+          </div>
+          <div className="flex flex-col gap-2">
+            <Label>Effective from (optional)</Label>
+            <Input type="date" value={effectiveFrom} onChange={(e) => setEffectiveFrom(e.target.value)} />
+            <p className="text-xs text-muted-foreground">
+              Spans that started on or after this date (UTC) use these prices. Leave empty to apply to all spans.
+            </p>
+          </div>
           {validationError && (
             <p className="text-xs text-destructive flex items-center gap-1">
               <AlertTriangle size={12} />
@@ -340,15 +407,16 @@ export default function CustomModelCosts() {
     provider: string | undefined;
     model: string;
     costs: Record<string, number>;
+    effectiveFrom?: string;
     previousModel?: string;
     previousProvider?: string;
   }): Promise<boolean> => {
-    const { id, provider, model, costs, previousModel, previousProvider } = params;
+    const { id, provider, model, costs, effectiveFrom, previousModel, previousProvider } = params;
     try {
       const res = await fetch(`/api/projects/${projectId}/custom-model-costs`, {
         method: "POST",
         headers: { "Content-Type": "application/json" },
-        body: JSON.stringify({ id, provider, model, costs, previousModel, previousProvider }),
+        body: JSON.stringify({ id, provider, model, costs, effectiveFrom, previousModel, previousProvider }),
       });
       if (res.ok) {
         mutate();
@@ -363,7 +431,7 @@ export default function CustomModelCosts() {
       if (res.status === 409) {
         toast({
           variant: "destructive",
-          title: errMessage ?? "A cost entry for this provider and model already exists",
+          title: errMessage ?? "A cost entry for this provider, model and effective date already exists",
         });
         return false;
       }
@@ -438,11 +506,11 @@ export default function CustomModelCosts() {
         {!isEmpty(customModelCosts) && <CopyModelCostsDialog onCopy={copyCosts} />}
       </div>
       <SettingsTable
-        headers={["Model", "Costs", "Created", "Updated", ""]}
+        headers={["Model", "Costs", "Effective from", "Created", "Updated", ""]}
         isLoading={isLoading}
         isEmpty={isEmpty(customModelCosts)}
         emptyMessage="No custom model costs defined."
-        colSpan={5}
+        colSpan={6}
       >
         {customModelCosts?.map((cost) => {
           const costObj = cost.costs as Record<string, number>;
@@ -470,11 +538,21 @@ export default function CustomModelCosts() {
                             {label}: ${(costObj[key] * PER_MILLION).toFixed(2)} / 1M tokens
                           </div>
                         ))}
+                        {FEE_FIELDS.filter(({ key }) => key in costObj).map(({ key, label }) => (
+                          <div key={key}>
+                            {label}: ${costObj[key]}
+                          </div>
+                        ))}
                       </div>
                     </TooltipContent>
                   </Tooltip>
                 </TooltipProvider>
               </td>
+              <td className="px-4">
+                <span className="text-xs text-muted-foreground whitespace-nowrap">
+                  {toDateInput(cost.effectiveFrom) || "Always"}
+                </span>
+              </td>
               <td className="px-4">
                 <span className="text-xs text-muted-foreground whitespace-nowrap">
                   {new Date(cost.createdAt).toLocaleString()}
@@ -493,6 +571,7 @@ export default function CustomModelCosts() {
                     initialProvider={cost.provider || undefined}
                     initialModel={cost.model}
                     initialCosts={costObj}
+                    initialEffectiveFrom={cost.effectiveFrom}
                     onSave={upsertCost}
                     trigger={
                       <Button variant="ghost" size="sm" className="h-8 w-8 p-0">
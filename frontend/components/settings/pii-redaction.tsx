@@ -7,6 +7,7 @@ import { useState } from "react";
 
 import { useFeatureFlags } from "@/contexts/feature-flags-context";
 import { useProjectContext } from "@/contexts/project-context";
+import type { ProjectSettings } from "@/lib/actions/project/settings";
 import { Feature } from "@/lib/features/features";
 import { useToast } from "@/lib/hooks/use-toast";
 import { WorkspaceTier } from "@/lib/workspaces/types";
@@ -25,6 +26,10 @@ export default function PiiRedaction() {
   const flags = useFeatureFlags();
 
   const [enabled, setEnabled] = useState<boolean>(project?.settings.removePii ?? false);
+  const [pseudonymize, setPseudonymize] = useState<boolean>(
+    project?.settings.piiRedactionMode === "pseudonym"
+  );
+  const [vaultEnabled, setVaultEnabled] = useState<boolean>(project?.settings.piiVaultEnabled ?? false);
   const [isLoading, setIsLoading] = useState(false);
 
   // Self-hosted installs aren't on tiered billing, so the Pro gate only
@@ -33,18 +38,17 @@ export default function PiiRedaction() {
   const isCloud = flags[Feature.LAMINAR_CLOUD];
   const isProTier = !isCloud || (workspace ? PRO_TIERS.includes(workspace.tierName) : false);
 
-  const onToggle = async (next: boolean) => {
+  // Optimistic — `apply` flips local state, `revert` undoes it on error.
+  const updateSettings = async (settings: Partial<ProjectSettings>, apply: () => void, revert: () => void) => {
     if (!isProTier) return;
-    // Optimistic — revert on error.
-    const previous = enabled;
-    setEnabled(next);
+    apply();
     setIsLoading(true);
 
     try {
       const res = await fetch(`/api/projects/${projectId}/settings`, {
         method: "PATCH",
         headers: { "Content-Type": "application/json" },
-        body: JSON.stringify({ settings: { removePii: next } }),
+        body: JSON.stringify({ settings }),
       });
 
       if (!res.ok) {
@@ -56,24 +60,51 @@ export default function PiiRedaction() {
           variant: "destructive",
           title: errMessage ?? "Failed to update PII redaction setting",
         });
-        setEnabled(previous);
+        revert();
         return;
       }
       router.refresh();
     } catch {
       toast({ variant: "destructive", title: "Failed to update PII redaction setting" });
-      setEnabled(previous);
+      revert();
     } finally {
       setIsLoading(false);
     }
   };
 
+  const onToggle = (next: boolean) => {
+    const previous = enabled;
+    return updateSettings(
+      { removePii: next },
+      () => setEnabled(next),
+      () => setEnabled(previous)
+    );
+  };
+
+  const onTogglePseudonymize = (next: boolean) => {
+    const previous = pseudonymize;
+    return updateSettings(
+      { piiRedactionMode: next ? "pseudonym" : "placeholder" },
+      () => setPseudonymize(next),
+      () => setPseudonymize(previous)
+    );
+  };
+
+  const onToggleVault = (next: boolean) => {
+    const previous = vaultEnabled;
+    return updateSettings(
+      { piiVaultEnabled: next },
+      () => setVaultEnabled(next),
+      () => setVaultEnabled(previous)
+    );
+  };
+
   return (
     <SettingsSection>
       <SettingsSectionHeader
         size="sm"
         title="Redact PII from spans"
-        description="When enabled, every span ingested for this project is run through the PII redactor before storage. Names, emails, phone numbers, and other detected PII are replaced with placeholders in inputs and outputs."
+        description="When enabled, every span ingested for this project is run through the PII redactor before storage. Names, emails, phone numbers, and other detected PII are replaced with placeholders or consistent pseudonyms in inputs and outputs."
       />
       <div className="flex items-center gap-3">
         <Switch checked={enabled} onCheckedChange={onToggle} disabled={!isProTier || isLoading} />
@@ -95,6 +126,28 @@ export default function PiiRedaction() {
           </>
         )}
       </div>
+      {enabled && (
+        <>
+          <div className="flex items-center gap-3">
+            <Switch
+              checked={pseudonymize}
+              onCheckedChange={onTogglePseudonymize}
+              disabled={!isProTier || isLoading}
+            />
+            <span className="text-sm text-muted-foreground">
+              Use consistent pseudonyms (e.g. EMAIL_1a2b3c4d) instead of placeholders
+            </span>
+          </div>
+          {pseudonymize && (
+            <div className="flex items-center gap-3">
+              <Switch checked={vaultEnabled} onCheckedChange={onToggleVault} disabled={!isProTier || isLoading} />
+              <span className="text-sm text-muted-foreground">
+                Store originals encrypted so project members can reveal pseudonyms
+              </span>
+            </div>
+          )}
+        </>
+      )}
     </SettingsSection>
   );
 }
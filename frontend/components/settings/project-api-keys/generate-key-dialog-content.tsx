@@ -1,10 +1,18 @@
 import { InfoCircledIcon } from "@radix-ui/react-icons";
 import { Loader2 } from "lucide-react";
 
-import { KEY_EXPIRATION_OPTIONS, type KeyExpiration } from "@/lib/api-keys/types";
+import {
+  API_KEY_SCOPE_LABELS,
+  API_KEY_SCOPES,
+  type ApiKeyScope,
+  type KeyType,
+  KEY_EXPIRATION_OPTIONS,
+  type KeyExpiration,
+} from "@/lib/api-keys/types";
 import { cn } from "@/lib/utils";
 
 import { Button } from "../../ui/button";
+import { Checkbox } from "../../ui/checkbox";
 import { DialogFooter } from "../../ui/dialog";
 import { Input } from "../../ui/input";
 import { Label } from "../../ui/label";
@@ -14,8 +22,14 @@ import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from "../../
 interface GenerateKeyDialogContentProps {
   onClick: () => void;
   onNameChange: (name: string) => void;
-  keyType: "default" | "ingest_only";
-  onKeyTypeChange: (type: "default" | "ingest_only") => void;
+  keyType: KeyType;
+  onKeyTypeChange: (type: KeyType) => void;
+  scopes: ApiKeyScope[];
+  onScopesChange: (scopes: ApiKeyScope[]) => void;
+  allowedCidrs: string;
+  onAllowedCidrsChange: (value: string) => void;
+  rateLimitPerMinute: string;
+  onRateLimitPerMinuteChange: (value: string) => void;
   expiration: KeyExpiration;
   onExpirationChange: (value: KeyExpiration) => void;
   isLoading: boolean;
@@ -27,6 +41,12 @@ export function GenerateKeyDialogContent({
   onNameChange,
   keyType,
   onKeyTypeChange,
+  scopes,
+  onScopesChange,
+  allowedCidrs,
+  onAllowedCidrsChange,
+  rateLimitPerMinute,
+  onRateLimitPerMinuteChange,
   expiration,
   onExpirationChange,
 }: GenerateKeyDialogContentProps) {
@@ -48,22 +68,60 @@ export function GenerateKeyDialogContent({
                 <TooltipContent side="bottom">
                   <p className="max-w-xs">
                     Ingest-only keys can write trace and browser session recording data but cannot access any other
-                    project data
+                    project data. Scoped keys can only use the APIs you select.
                   </p>
                 </TooltipContent>
               </Tooltip>
             </TooltipProvider>
           </div>
-          <Select value={keyType} onValueChange={(value) => onKeyTypeChange(value as "default" | "ingest_only")}>
+          <Select value={keyType} onValueChange={(value) => onKeyTypeChange(value as KeyType)}>
             <SelectTrigger>
               <SelectValue />
             </SelectTrigger>
             <SelectContent>
               <SelectItem value="default">Default</SelectItem>
               <SelectItem value="ingest_only">Ingest Only</SelectItem>
+              <SelectItem value="scoped">Scoped</SelectItem>
             </SelectContent>
           </Select>
         </div>
+        {keyType === "scoped" && (
+          <div className="flex flex-col gap-2">
+            <Label className="text-sm">Scopes</Label>
+            {API_KEY_SCOPES.map((scope) => (
+              <div key={scope} className="flex items-center gap-2">
+                <Checkbox
+                  id={`scope-${scope}`}
+                  checked={scopes.includes(scope)}
+                  onCheckedChange={(checked) =>
+                    onScopesChange(checked ? [...scopes, scope] : scopes.filter((s) => s !== scope))
+                  }
+                />
+                <Label htmlFor={`scope-${scope}`} className="text-sm font-normal">
+                  {API_KEY_SCOPE_LABELS[scope]}
+                </Label>
+              </div>
+            ))}
+          </div>
+        )}
+        <div className="flex flex-col gap-2">
+          <Label className="text-sm">Allowed IP ranges</Label>
+          <Input
+            placeholder="Any, or e.g. 10.0.0.0/8, 203.0.113.7"
+            value={allowedCidrs}
+            onChange={(e) => onAllowedCidrsChange(e.target.value)}
+          />
+        </div>
+        <div className="flex flex-col gap-2">
+          <Label className="text-sm">Rate limit (requests per minute)</Label>
+          <Input
+            type="number"
+            min={1}
+            placeholder="No limit"
+            value={rateLimitPerMinute}
+            onChange={(e) => onRateLimitPerMinuteChange(e.target.value)}
+          />
+        </div>
         <div className="flex flex-col gap-2">
           <Label className="text-sm">Expiration</Label>
           <Select value={expiration} onValueChange={(value) => onExpirationChange(value as KeyExpiration)}>
@@ -81,7 +139,7 @@ export function GenerateKeyDialogContent({
         </div>
       </div>
       <DialogFooter>
-        <Button onClick={onClick} handleEnter disabled={isLoading}>
+        <Button onClick={onClick} handleEnter disabled={isLoading || (keyType === "scoped" && scopes.length === 0)}>
           <Loader2 className={cn("mr-2 hidden", isLoading ? "animate-spin block" : "")} size={16} />
           Create
         </Button>
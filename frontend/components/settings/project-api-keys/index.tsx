@@ -3,7 +3,14 @@ import { useParams } from "next/navigation";
 import { useCallback, useState } from "react";
 
 import { Badge } from "@/components/ui/badge";
-import { type GenerateProjectApiKeyResponse, type KeyExpiration, type ProjectApiKey } from "@/lib/api-keys/types";
+import {
+  API_KEY_SCOPE_LABELS,
+  type ApiKeyScope,
+  type GenerateProjectApiKeyResponse,
+  type KeyExpiration,
+  type KeyType,
+  type ProjectApiKey,
+} from "@/lib/api-keys/types";
 import { useToast } from "@/lib/hooks/use-toast";
 import { track } from "@/lib/posthog";
 
@@ -22,7 +29,10 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
   const [isGenerateKeyDialogOpen, setIsGenerateKeyDialogOpen] = useState(false);
   const [projectApiKeys, setProjectApiKeys] = useState<ProjectApiKey[]>(apiKeys);
   const [newApiKeyName, setNewApiKeyName] = useState<string>("");
-  const [keyType, setKeyType] = useState<"default" | "ingest_only">("default");
+  const [keyType, setKeyType] = useState<KeyType>("default");
+  const [scopes, setScopes] = useState<ApiKeyScope[]>([]);
+  const [allowedCidrs, setAllowedCidrs] = useState<string>("");
+  const [rateLimitPerMinute, setRateLimitPerMinute] = useState<string>("");
   const [expiration, setExpiration] = useState<KeyExpiration>("30");
   const [newApiKey, setNewApiKey] = useState<GenerateProjectApiKeyResponse | null>(null);
   const [isGenerated, setIsGenerated] = useState(false);
@@ -31,11 +41,21 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
   const { projectId } = useParams();
 
   const generateNewAPIKey = useCallback(
-    async (newName: string, isIngestOnly: boolean, expiresDays: number | null) => {
+    async (body: {
+      name: string;
+      isIngestOnly: boolean;
+      expiresDays: number | null;
+      scopes: ApiKeyScope[];
+      allowedCidrs: string[];
+      rateLimitPerMinute: number | null;
+    }) => {
       const res = await fetch(`/api/projects/${projectId}/api-keys`, {
         method: "POST",
-        body: JSON.stringify({ name: newName, isIngestOnly, expiresDays }),
+        body: JSON.stringify(body),
       });
+      if (!res.ok) {
+        throw new Error(await res.text());
+      }
       const newKey = (await res.json()) as GenerateProjectApiKeyResponse;
 
       setNewApiKey(newKey);
@@ -67,27 +87,41 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
     [projectId, getProjectApiKeys]
   );
 
-  const handleKeyTypeChange = useCallback((type: "default" | "ingest_only") => {
+  const handleKeyTypeChange = useCallback((type: KeyType) => {
     setKeyType(type);
-    // Ingest-only keys are long-lived trace senders, but default to a 1-day expiry
-    // to nudge toward short-lived credentials; default keys default to 30 days. The
-    // user can still pick "Never".
-    setExpiration(type === "ingest_only" ? "1" : "30");
+    // Ingest-only and scoped keys are usually handed to CI or other services, so
+    // default to a 1-day expiry to nudge toward short-lived credentials; default
+    // keys default to 30 days. The user can still pick "Never".
+    setExpiration(type === "default" ? "30" : "1");
   }, []);
 
   const handleGenerateKey = useCallback(async () => {
     try {
       setIsLoading(true);
       const expiresDays = expiration === "never" ? null : Number(expiration);
-      await generateNewAPIKey(newApiKeyName, keyType === "ingest_only", expiresDays);
+      await generateNewAPIKey({
+        name: newApiKeyName,
+        isIngestOnly: keyType === "ingest_only",
+        expiresDays,
+        scopes: keyType === "scoped" ? scopes : [],
+        allowedCidrs: allowedCidrs
+          .split(",")
+          .map((cidr) => cidr.trim())
+          .filter(Boolean),
+        rateLimitPerMinute: rateLimitPerMinute ? Number(rateLimitPerMinute) : null,
+      });
       setIsGenerated(true);
       track("api_keys", "generated", { key_type: keyType, expiration });
     } catch (error) {
-      toast({ variant: "destructive", title: "Error", description: "Failed to generate API key" });
+      toast({
+        variant: "destructive",
+        title: "Failed to generate API key",
+        description: error instanceof Error ? error.message : undefined,
+      });
     } finally {
       setIsLoading(false);
     }
-  }, [newApiKeyName, keyType, expiration, generateNewAPIKey, toast]);
+  }, [newApiKeyName, keyType, scopes, allowedCidrs, rateLimitPerMinute, expiration, generateNewAPIKey, toast]);
 
   return (
     <SettingsSection>
@@ -101,6 +135,9 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
           setIsGenerateKeyDialogOpen(!isGenerateKeyDialogOpen);
           setNewApiKeyName("");
           setKeyType("default");
+          setScopes([]);
+          setAllowedCidrs("");
+          setRateLimitPerMinute("");
           setExpiration("30");
           setNewApiKey(null);
           setIsGenerated(false);
@@ -134,6 +171,12 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
               onNameChange={(name) => setNewApiKeyName(name)}
               keyType={keyType}
               onKeyTypeChange={handleKeyTypeChange}
+              scopes={scopes}
+              onScopesChange={setScopes}
+              allowedCidrs={allowedCidrs}
+              onAllowedCidrsChange={setAllowedCidrs}
+              rateLimitPerMinute={rateLimitPerMinute}
+              onRateLimitPerMinuteChange={setRateLimitPerMinute}
               expiration={expiration}
               onExpirationChange={setExpiration}
             />
@@ -151,8 +194,12 @@ export default function ProjectApiKeys({ apiKeys }: ApiKeysProps) {
             <td className="px-4 text-sm font-medium">{apiKey.name}</td>
             <td className="px-4 text-sm font-mono text-muted-foreground">{apiKey.shorthand}</td>
             <td className="px-4">
-              <Badge variant="outline" className="font-normal whitespace-nowrap">
-                {apiKey.isIngestOnly ? "Ingest Only" : "Default"}
+              <Badge
+                variant="outline"
+                className="font-normal whitespace-nowrap"
+                title={apiKey.scopes.map((scope) => API_KEY_SCOPE_LABELS[scope]).join(", ") || undefined}
+              >
+                {apiKey.isIngestOnly ? "Ingest Only" : apiKey.scopes.length > 0 ? "Scoped" : "Default"}
               </Badge>
             </td>
             <td className="px-4 text-sm text-muted-foreground whitespace-nowrap">
@@ -23,6 +23,7 @@ import { useMemo } from "react";
 
 import AgentVersions from "@/components/settings/agent-versions";
 import AlertsSettings from "@/components/settings/alerts";
+import CostBackfill from "@/components/settings/cost-backfill";
 import CustomModelCosts from "@/components/settings/custom-model-costs";
 import DeleteProject from "@/components/settings/delete-project";
 import PiiRedaction from "@/components/settings/pii-redaction";
@@ -230,7 +231,12 @@ const SharedSettings = ({
       case "provider-api-keys":
         return <ProviderApiKeys />;
       case "model-costs":
-        return <CustomModelCosts />;
+        return (
+          <div className="flex flex-col gap-8">
+            <CustomModelCosts />
+            <CostBackfill />
+          </div>
+        );
       case "render-templates":
         return <RenderTemplates />;
       case "agent-versions":
@@ -34,6 +34,7 @@ export const enumValues = {
   status: ["success", "error"],
   signal_run_status: ["PENDING", "COMPLETED", "FAILED", "UNKNOWN"],
   signal_run_mode: ["BATCH", "REALTIME", "UNKNOWN"],
+  metric_type: ["GAUGE", "SUM", "HISTOGRAM", "EXPONENTIAL_HISTOGRAM", "SUMMARY", "UNKNOWN"],
 } as const satisfies Record<string, readonly string[]>;
 
 export type EnumType = keyof typeof enumValues;
@@ -113,6 +114,11 @@ export const tableSchemas: Record<string, TableSchema> = {
         type: "String",
         description: "Tool definitions available to the LLM span as stringified JSON",
       },
+      {
+        name: "links",
+        type: "Array(Tuple(trace_id UUID, span_id UUID, attributes String))",
+        description: "Links from the span to spans in the same or other traces",
+      },
     ],
   },
   traces: {
@@ -360,6 +366,85 @@ export const tableSchemas: Record<string, TableSchema> = {
       { name: "event_name", type: "String", description: "Event name of the log" },
     ],
   },
+  span_log_counts: {
+    description:
+      "Number of logs emitted in each span that logged anything, by severity. Join to spans on (trace_id, span_id)",
+    columns: [
+      { name: "trace_id", type: "UUID", description: "Unique identifier for the trace" },
+      { name: "span_id", type: "UUID", description: "Unique identifier for the span" },
+      { name: "log_count", type: "UInt64", description: "Number of logs emitted in the span" },
+      {
+        name: "warning_log_count",
+        type: "UInt64",
+        description: "Number of logs emitted in the span with WARN severity",
+      },
+      {
+        name: "error_log_count",
+        type: "UInt64",
+        description: "Number of logs emitted in the span with ERROR or FATAL severity",
+      },
+    ],
+  },
+  metrics: {
+    description: "OpenTelemetry metric data points (gauges, sums, histograms, summaries), one row per point",
+    columns: [
+      { name: "id", type: "UUID", description: "Unique identifier for the data point" },
+      { name: "time", type: "DateTime64(9, 'UTC')", description: "When the data point was recorded" },
+      { name: "start_time", type: "DateTime64(9, 'UTC')", description: "Start of the aggregation window" },
+      { name: "name", type: "String", description: "Name of the metric" },
+      { name: "description", type: "String", description: "Description of the metric" },
+      { name: "unit", type: "String", description: "Unit of the metric" },
+      { name: "metric_type", type: "String", description: "Kind of the metric", enumType: "metric_type" },
+      {
+        name: "aggregation_temporality",
+        type: "String",
+        description: "Aggregation temporality: DELTA, CUMULATIVE or UNSPECIFIED",
+      },
+      { name: "is_monotonic", type: "Bool", description: "Whether a SUM metric is monotonic" },
+      { name: "value", type: "Float64", description: "Value of a GAUGE or SUM data point" },
+      { name: "count", type: "UInt64", description: "Population count of a histogram or summary" },
+      { name: "sum", type: "Float64", description: "Population sum of a histogram or summary" },
+      { name: "min", type: "Nullable(Float64)", description: "Minimum recorded value" },
+      { name: "max", type: "Nullable(Float64)", description: "Maximum recorded value" },
+      { name: "bucket_counts", type: "Array(UInt64)", description: "Histogram bucket counts" },
+      { name: "explicit_bounds", type: "Array(Float64)", description: "Histogram bucket upper bounds" },
+      { name: "quantiles", type: "Array(Float64)", description: "Summary quantiles" },
+      { name: "quantile_values", type: "Array(Float64)", description: "Summary values at each quantile" },
+      { name: "attributes", type: "String", description: "Attributes of the data point as stringified JSON" },
+      { name: "resource_attributes", type: "String", description: "Resource attributes as stringified JSON" },
+      { name: "scope_name", type: "String", description: "Instrumentation scope name" },
+      { name: "trace_id", type: "UUID", description: "Unique identifier for an exemplar trace" },
+      { name: "span_id", type: "UUID", description: "Unique identifier for an exemplar span" },
+    ],
+  },
+  span_metrics: {
+    description:
+      "Per-minute span rollups by model, provider, path, span type, status and tags. Faster than spans for long-range trends. Average duration is sum(duration_sum) / sum(span_count); percentiles over any range come from quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)(duration_quantiles), which returns [p50, p90, p95, p99]",
+    columns: [
+      { name: "minute", type: "DateTime('UTC')", description: "Start of the minute bucket" },
+      { name: "model", type: "String", description: "LLM model (response model, else request model)" },
+      { name: "provider", type: "String", description: "LLM provider, e.g. openai, anthropic" },
+      { name: "path", type: "String", description: "Hierarchical path of the span, e.g. outer.inner" },
+      { name: "span_type", type: "String", description: "Type of the span", enumType: "span_type" },
+      { name: "status", type: "String", description: "Status of the spans in the bucket", enumType: "status" },
+      { name: "tags", type: "Array(String)", description: "Span-level tags, sorted" },
+      { name: "span_count", type: "UInt64", description: "Number of spans in the bucket" },
+      { name: "input_tokens", type: "Int64", description: "Sum of input tokens" },
+      { name: "output_tokens", type: "Int64", description: "Sum of output tokens" },
+      { name: "total_tokens", type: "Int64", description: "Sum of total tokens" },
+      { name: "input_cost", type: "Float64", description: "Sum of input cost" },
+      { name: "output_cost", type: "Float64", description: "Sum of output cost" },
+      { name: "total_cost", type: "Float64", description: "Sum of total cost" },
+      { name: "duration_sum", type: "Float64", description: "Sum of span durations in seconds" },
+      { name: "min_duration", type: "Float64", description: "Minimum span duration in seconds" },
+      { name: "max_duration", type: "Float64", description: "Maximum span duration in seconds" },
+      {
+        name: "duration_quantiles",
+        type: "AggregateFunction(quantilesTDigestArray(0.5, 0.9, 0.95, 0.99), Array(Float64))",
+        description: "Mergeable duration quantile state, read with quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)",
+      },
+    ],
+  },
 };
 
 // --- String / option helpers -------------------------------------------------
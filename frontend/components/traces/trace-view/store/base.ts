@@ -35,6 +35,8 @@ export type TraceViewSpan = {
   path: string;
   events: SpanEvent[];
   status?: string;
+  warningLogCount?: number;
+  errorLogCount?: number;
   model?: string;
   pending?: boolean;
   collapsed: boolean;
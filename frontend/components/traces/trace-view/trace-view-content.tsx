@@ -6,7 +6,12 @@ import { shallow } from "zustand/shallow";
 import Chat from "@/components/traces/trace-view/chat";
 import { HumanEvaluatorSpanView } from "@/components/traces/trace-view/human-evaluator-span-view";
 import { type TraceViewSpan, type TraceViewTrace, useTraceViewStore } from "@/components/traces/trace-view/store";
-import { enrichSpansWithPending, findSpanToSelect, onRealtimeUpdateSpans } from "@/components/traces/trace-view/utils";
+import {
+  enrichSpansWithPending,
+  findSpanToSelect,
+  onRealtimeUpdateLogs,
+  onRealtimeUpdateSpans,
+} from "@/components/traces/trace-view/utils";
 import { useFeatureFlags } from "@/contexts/feature-flags-context";
 import { type Filter } from "@/lib/actions/common/filters";
 import { Feature } from "@/lib/features/features";
@@ -277,6 +282,12 @@ export default function TraceViewContent({
           }
         }
       },
+      log_update: (event: MessageEvent) => {
+        const payload = JSON.parse(event.data);
+        if (payload.logs && Array.isArray(payload.logs)) {
+          onRealtimeUpdateLogs(setSpans)(payload.logs);
+        }
+      },
     }),
     [setBrowserSession, setSpans, setTrace]
   );
@@ -1,5 +1,5 @@
 import { isNil } from "lodash";
-import { ChevronDown, ChevronRight, X } from "lucide-react";
+import { ChevronDown, ChevronRight, TriangleAlert, X } from "lucide-react";
 import { useRef } from "react";
 
 import { SnippetPreview } from "@/components/traces/snippet-preview";
@@ -7,6 +7,7 @@ import { ContentPreview } from "@/components/traces/trace-view/content-preview";
 import { type TraceViewSpan } from "@/components/traces/trace-view/store/base";
 import { getLLMMetrics, getSpanDisplayName } from "@/components/traces/trace-view/utils";
 import { Skeleton } from "@/components/ui/skeleton.tsx";
+import { Tooltip, TooltipContent, TooltipProvider, TooltipTrigger } from "@/components/ui/tooltip";
 import { isStringDateOld } from "@/lib/traces/utils";
 import { cn } from "@/lib/utils";
 
@@ -54,6 +55,9 @@ export function SpanCard({
 
   const hasSnippet = !!(span.inputSnippet || span.outputSnippet || span.attributesSnippet);
 
+  const errorLogCount = Number(span.errorLogCount ?? 0);
+  const warningLogCount = Number(span.warningLogCount ?? 0);
+
   const isExpandable =
     hasChildren || ((span.spanType === "LLM" || span.spanType === "CACHED") && showTreeContent) || hasSnippet;
 
@@ -133,6 +137,25 @@ export function SpanCard({
                 cacheReadInputTokens={llmMetrics?.cacheReadInputTokens}
               />
             )}
+            {(errorLogCount > 0 || warningLogCount > 0) && (
+              <TooltipProvider>
+                <Tooltip>
+                  <TooltipTrigger asChild>
+                    <TriangleAlert
+                      className={cn("h-3.5 w-3.5 shrink-0", errorLogCount > 0 ? "text-destructive" : "text-yellow-500")}
+                    />
+                  </TooltipTrigger>
+                  <TooltipContent side="bottom">
+                    {[
+                      errorLogCount > 0 && `${errorLogCount} error log${errorLogCount === 1 ? "" : "s"}`,
+                      warningLogCount > 0 && `${warningLogCount} warning log${warningLogCount === 1 ? "" : "s"}`,
+                    ]
+                      .filter(Boolean)
+                      .join(", ")}
+                  </TooltipContent>
+                </Tooltip>
+              </TooltipProvider>
+            )}
             {isExpandable && (
               <button
                 className="p-1 hover:bg-muted transition-all text-muted-foreground rounded-sm"
@@ -4,7 +4,7 @@ import { createSpanTypeIcon } from "@/components/traces/span-type-icon";
 import { type TraceViewListSpan, type TraceViewSpan, type TraceViewTrace } from "@/components/traces/trace-view/store";
 import { type ColumnFilter } from "@/components/ui/infinite-datatable/ui/datatable-filter/utils";
 import { aggregateSpanMetrics } from "@/lib/actions/spans/utils.ts";
-import { type RealtimeSpan, SpanType } from "@/lib/traces/types";
+import { type RealtimeLog, type RealtimeSpan, SpanType } from "@/lib/traces/types";
 
 export const enrichSpansWithPending = (existingSpans: TraceViewSpan[]): TraceViewSpan[] => {
   const existingSpanIds = new Set(existingSpans.map((span) => span.spanId));
@@ -216,6 +216,8 @@ export const onRealtimeUpdateSpans =
           totalCost,
           model,
           collapsed: newSpans[index].collapsed || false,
+          warningLogCount: newSpans[index].warningLogCount,
+          errorLogCount: newSpans[index].errorLogCount,
           events: [],
           path: "",
         };
@@ -243,6 +245,39 @@ export const onRealtimeUpdateSpans =
     });
   };
 
+// OTel severity numbers: 13-16 WARN, 17-24 ERROR and FATAL.
+const WARN_SEVERITY_NUMBER = 13;
+const ERROR_SEVERITY_NUMBER = 17;
+
+export const onRealtimeUpdateLogs =
+  (setSpans: (spans: TraceViewSpan[] | ((prevSpans: TraceViewSpan[]) => TraceViewSpan[])) => void) =>
+  (logs: RealtimeLog[]) => {
+    const counts = new Map<string, { warnings: number; errors: number }>();
+    for (const log of logs) {
+      if (!log.spanId || log.severityNumber < WARN_SEVERITY_NUMBER) continue;
+      const count = counts.get(log.spanId) ?? { warnings: 0, errors: 0 };
+      if (log.severityNumber >= ERROR_SEVERITY_NUMBER) {
+        count.errors += 1;
+      } else {
+        count.warnings += 1;
+      }
+      counts.set(log.spanId, count);
+    }
+    if (counts.size === 0) return;
+
+    setSpans((spans) =>
+      spans.map((span) => {
+        const count = counts.get(span.spanId);
+        if (!count) return span;
+        return {
+          ...span,
+          warningLogCount: Number(span.warningLogCount ?? 0) + count.warnings,
+          errorLogCount: Number(span.errorLogCount ?? 0) + count.errors,
+        };
+      })
+    );
+  };
+
 export const findSpanToSelect = (
   spans: TraceViewSpan[],
   spanId: string | undefined,
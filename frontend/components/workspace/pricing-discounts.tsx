@@ -0,0 +1,280 @@
+"use client";
+
+import { isEmpty } from "lodash";
+import { Pencil, Trash2 } from "lucide-react";
+import { useState } from "react";
+import useSWR from "swr";
+
+import {
+  SettingsSection,
+  SettingsSectionHeader,
+  SettingsTable,
+  SettingsTableRow,
+} from "@/components/settings/settings-section";
+import { type PricingDiscount } from "@/lib/actions/pricing-discounts";
+import { useToast } from "@/lib/hooks/use-toast";
+import { swrFetcher } from "@/lib/utils";
+
+import { Button } from "../ui/button";
+import { ConfirmDialog } from "../ui/confirm-dialog";
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+  DialogTrigger,
+} from "../ui/dialog";
+import { Input } from "../ui/input";
+import { Label } from "../ui/label";
+
+/** Date of the default `effective_from`, for discounts that apply to all spans */
+const ALWAYS_EFFECTIVE_DATE = "1970-01-01";
+
+/** `YYYY-MM-DD` for a date input, or empty for discounts that apply to all spans */
+function toDateInput(effectiveFrom?: string): string {
+  const date = effectiveFrom?.slice(0, 10) ?? "";
+  return date === ALWAYS_EFFECTIVE_DATE ? "" : date;
+}
+
+/** Percent off list price, e.g. a `0.8` multiplier is a 20% discount. */
+function toPercentOff(multiplier: number): string {
+  return String(parseFloat(((1 - multiplier) * 100).toPrecision(12)));
+}
+
+function PricingDiscountDialog({
+  mode,
+  discount,
+  onSave,
+  trigger,
+}: {
+  mode: "add" | "edit";
+  discount?: PricingDiscount;
+  onSave: (params: { id?: string; provider: string; multiplier: number; effectiveFrom?: string }) => Promise<boolean>;
+  trigger: React.ReactNode;
+}) {
+  const [open, setOpen] = useState(false);
+  const [provider, setProvider] = useState("");
+  const [percentOff, setPercentOff] = useState("");
+  const [effectiveFrom, setEffectiveFrom] = useState("");
+  const [validationError, setValidationError] = useState<string | undefined>();
+  const [isSaving, setIsSaving] = useState(false);
+
+  const reset = () => {
+    setProvider(discount?.provider ?? "");
+    setPercentOff(discount ? toPercentOff(discount.multiplier) : "");
+    setEffectiveFrom(toDateInput(discount?.effectiveFrom));
+    setValidationError(undefined);
+  };
+
+  const handleSave = async () => {
+    const percent = parseFloat(percentOff);
+    if (isNaN(percent) || percent < 0 || percent > 100) {
+      setValidationError("Discount must be between 0 and 100%");
+      return;
+    }
+
+    setIsSaving(true);
+    let ok: boolean;
+    try {
+      ok = await onSave({
+        id: discount?.id,
+        provider,
+        multiplier: 1 - percent / 100,
+        effectiveFrom: effectiveFrom ? new Date(`${effectiveFrom}T00:00:00Z`).toISOString() : undefined,
+      });
+    } finally {
+      setIsSaving(false);
+    }
+    if (!ok) return;
+    setOpen(false);
+  };
+
+  return (
+    <Dialog
+      open={open}
+      onOpenChange={(isOpen) => {
+        setOpen(isOpen);
+        if (isOpen) reset();
+      }}
+    >
+      <DialogTrigger asChild>{trigger}</DialogTrigger>
+      <DialogContent className="sm:max-w-[425px]">
+        <DialogHeader>
+          <DialogTitle>{mode === "edit" ? "Edit pricing discount" : "Add pricing discount"}</DialogTitle>
+          <DialogDescription>
+            Discounts apply to global model prices in every project of this workspace. Custom model costs are not
+            discounted.
+          </DialogDescription>
+        </DialogHeader>
+        <div className="flex flex-col gap-4">
+          <div className="flex flex-col gap-2">
+            <Label>Provider (optional)</Label>
+            <Input placeholder="All providers" value={provider} onChange={(e) => setProvider(e.target.value)} />
+          </div>
+          <div className="flex flex-col gap-2">
+            <Label>Discount (%) *</Label>
+            <Input
+              type="number"
+              step="any"
+              min="0"
+              max="100"
+              placeholder="e.g. 20"
+              value={percentOff}
+              onChange={(e) => {
+                setPercentOff(e.target.value);
+                setValidationError(undefined);
+              }}
+            />
+          </div>
+          <div className="flex flex-col gap-2">
+            <Label>Effective from (optional)</Label>
+            <Input type="date" value={effectiveFrom} onChange={(e) => setEffectiveFrom(e.target.value)} />
+            <p className="text-xs text-muted-foreground">Leave empty to apply to all spans.</p>
+          </div>
+          {validationError && <p className="text-xs text-destructive">{validationError}</p>}
+        </div>
+        <DialogFooter>
+          <Button disabled={isSaving || percentOff === ""} onClick={handleSave}>
+            {isSaving ? "Saving..." : "Save"}
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  );
+}
+
+export default function PricingDiscounts({ workspaceId }: { workspaceId: string }) {
+  const { toast } = useToast();
+  const {
+    data: discounts,
+    mutate,
+    isLoading,
+  } = useSWR<PricingDiscount[]>(`/api/workspaces/${workspaceId}/pricing-discounts`, swrFetcher);
+
+  const [deleteTarget, setDeleteTarget] = useState<PricingDiscount | null>(null);
+
+  const upsertDiscount = async (params: {
+    id?: string;
+    provider: string;
+    multiplier: number;
+    effectiveFrom?: string;
+  }): Promise<boolean> => {
+    try {
+      const res = await fetch(`/api/workspaces/${workspaceId}/pricing-discounts`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(params),
+      });
+      if (res.ok) {
+        mutate();
+        toast({ title: params.id ? "Pricing discount updated" : "Pricing discount saved" });
+        return true;
+      }
+      const errMessage = await res
+        .json()
+        .then((d) => d?.error)
+        .catch(() => null);
+      toast({ variant: "destructive", title: errMessage ?? "Failed to save pricing discount" });
+      return false;
+    } catch {
+      toast({ variant: "destructive", title: "Failed to save pricing discount" });
+      return false;
+    }
+  };
+
+  const deleteDiscount = async (id: string) => {
+    try {
+      const res = await fetch(`/api/workspaces/${workspaceId}/pricing-discounts?id=${id}`, {
+        method: "DELETE",
+      });
+      if (res.ok) {
+        mutate();
+        toast({ title: "Pricing discount deleted" });
+      } else {
+        const errMessage = await res
+          .json()
+          .then((d) => d?.error)
+          .catch(() => null);
+        toast({ variant: "destructive", title: errMessage ?? "Failed to delete pricing discount" });
+      }
+    } catch {
+      toast({ variant: "destructive", title: "Failed to delete pricing discount" });
+    }
+  };
+
+  return (
+    <SettingsSection>
+      <SettingsSectionHeader
+        size="sm"
+        title="Pricing discounts"
+        description="Negotiated discounts on list model prices. A discount for a specific provider takes priority over one for all providers. Add another entry with a later effective date to record a change."
+      />
+      <PricingDiscountDialog
+        mode="add"
+        onSave={upsertDiscount}
+        trigger={
+          <Button icon="plus" variant="outline" className="w-fit">
+            Discount
+          </Button>
+        }
+      />
+      <SettingsTable
+        headers={["Provider", "Discount", "Effective from", ""]}
+        isLoading={isLoading}
+        isEmpty={isEmpty(discounts)}
+        emptyMessage="No pricing discounts defined."
+        colSpan={4}
+      >
+        {discounts?.map((discount) => (
+          <SettingsTableRow key={discount.id}>
+            <td className="px-4">
+              <span className="text-sm font-medium">{discount.provider || "All providers"}</span>
+            </td>
+            <td className="px-4">
+              <span className="text-xs text-muted-foreground">{toPercentOff(discount.multiplier)}%</span>
+            </td>
+            <td className="px-4">
+              <span className="text-xs text-muted-foreground whitespace-nowrap">
+                {toDateInput(discount.effectiveFrom) || "Always"}
+              </span>
+            </td>
+            <td className="px-4">
+              <div className="flex justify-end gap-1">
+                <PricingDiscountDialog
+                  mode="edit"
+                  discount={discount}
+                  onSave={upsertDiscount}
+                  trigger={
+                    <Button variant="ghost" size="sm" className="h-8 w-8 p-0">
+                      <Pencil size={14} />
+                    </Button>
+                  }
+                />
+                <Button variant="ghost" size="sm" className="h-8 w-8 p-0" onClick={() => setDeleteTarget(discount)}>
+                  <Trash2 size={14} />
+                </Button>
+              </div>
+            </td>
+          </SettingsTableRow>
+        ))}
+      </SettingsTable>
+      <ConfirmDialog
+        open={deleteTarget !== null}
+        onOpenChange={(open) => {
+          if (!open) setDeleteTarget(null);
+        }}
+        title="Delete pricing discount"
+        description={`Are you sure you want to delete the discount for ${deleteTarget?.provider || "all providers"}? Costs of new spans will use list prices.`}
+        confirmText="Delete"
+        onConfirm={() => {
+          if (deleteTarget) {
+            deleteDiscount(deleteTarget.id);
+            setDeleteTarget(null);
+          }
+        }}
+      />
+    </SettingsSection>
+  );
+}
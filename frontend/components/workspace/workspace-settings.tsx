@@ -16,6 +16,7 @@ import { type Workspace, type WorkspaceWithProjects } from "@/lib/workspaces/typ
 import { Button } from "../ui/button";
 import { Input } from "../ui/input";
 import { Label } from "../ui/label";
+import PricingDiscounts from "./pricing-discounts";
 
 interface RenameWorkspaceForm {
   name: string;
@@ -221,6 +222,8 @@ export default function WorkspaceSettings({ workspace, isOwner }: WorkspaceSetti
         </Dialog>
       </SettingsSection>
 
+      <PricingDiscounts workspaceId={workspace.id} />
+
       <SettingsSection>
         <SettingsSectionHeader
           size="sm"
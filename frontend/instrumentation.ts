@@ -79,18 +79,28 @@ export async function register() {
             costs,
           }));
 
-          await db
-            .insert(modelCosts)
-            .values(allRows)
-            .onConflictDoUpdate({
-              target: modelCosts.model,
-              set: {
-                costs: sql.raw(`excluded.costs`) as any,
-                updatedAt: sql.raw(`now()`) as any,
-              },
-            });
-
-          console.log(`Upserted ${allRows.length} rows into model_costs`);
+          // Prices are versioned by `effective_from`, so spans keep the price that was in
+          // effect when they ran. A model seen for the first time applies to all history;
+          // a changed price starts a new version from now.
+          await db.execute(sql`
+            INSERT INTO ${modelCosts} (model, costs, effective_from)
+            SELECT
+              r.model,
+              r.costs,
+              CASE WHEN latest.costs IS NULL THEN '1970-01-01 00:00:00+00'::timestamptz ELSE now() END
+            FROM jsonb_to_recordset(${JSON.stringify(allRows)}::jsonb) AS r(model text, costs jsonb)
+            LEFT JOIN LATERAL (
+              SELECT ${modelCosts.costs} AS costs
+              FROM ${modelCosts}
+              WHERE ${modelCosts.model} = r.model
+              ORDER BY ${modelCosts.effectiveFrom} DESC
+              LIMIT 1
+            ) AS latest ON true
+            WHERE latest.costs IS DISTINCT FROM r.costs
+            ON CONFLICT (model, effective_from) DO UPDATE SET costs = excluded.costs, updated_at = now()
+          `);
+
+          console.log(`Synced ${allRows.length} models into model_costs`);
           return true;
         } catch (error) {
           console.error("Failed to initialize model costs:", error);
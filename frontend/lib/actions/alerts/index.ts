@@ -2,10 +2,19 @@ import { and, eq, inArray } from "drizzle-orm";
 import { z } from "zod/v4";
 
 import { ALERT_FILTERS_CACHE_KEY, cache } from "@/lib/cache.ts";
+import { encryptValue } from "@/lib/crypto";
 import { db } from "@/lib/db/drizzle";
-import { alerts, alertTargets, projects, signals } from "@/lib/db/migrations/schema";
-
-import { type AlertMetadata, type AlertTarget, type AlertType, type AlertWithDetails } from "./types";
+import { alerts, alertTargets, metricAlertStates, projects, signals } from "@/lib/db/migrations/schema";
+
+import {
+  ALERT_METRIC,
+  type AlertMetadata,
+  type AlertTarget,
+  type AlertType,
+  type AlertWithDetails,
+  THRESHOLD_OPERATOR,
+  WEBHOOK_TARGET_TYPES,
+} from "./types";
 
 const TargetSchema = z.object({
   type: z.string(),
@@ -22,21 +31,70 @@ const SignalEventMetadataSchema = z.object({
 
 const NewClusterMetadataSchema = z.object({}).strict();
 
-// NEW_CLUSTER alerts ignore metadata; SIGNAL_EVENT alerts require severities + optional skipSimilar.
-const buildMetadataValidator = (type: "SIGNAL_EVENT" | "NEW_CLUSTER", metadata: unknown) => {
+// Span types as stored in span_metrics (app-server's SpanType).
+const METRIC_SPAN_TYPES = [
+  "DEFAULT",
+  "LLM",
+  "PIPELINE",
+  "EXECUTOR",
+  "EVALUATOR",
+  "HUMAN_EVALUATOR",
+  "EVALUATION",
+  "TOOL",
+  "CACHED",
+] as const;
+
+// Mirrors MetricThresholdRule in app-server/src/notifications/metric_alerts.rs.
+const MetricThresholdMetadataSchema = z
+  .object({
+    metric: z.enum(ALERT_METRIC),
+    filters: z
+      .object({
+        model: z.string().min(1).optional(),
+        provider: z.string().min(1).optional(),
+        path: z.string().min(1).optional(),
+        spanType: z.enum(METRIC_SPAN_TYPES).optional(),
+      })
+      .strict()
+      .optional(),
+    windowMinutes: z.number().int().min(1).max(1440),
+    operator: z.enum(THRESHOLD_OPERATOR),
+    threshold: z.number(),
+    renotifyMinutes: z.number().int().min(1).optional(),
+    minSpanCount: z.number().int().min(1).optional(),
+  })
+  .strict();
+
+const AlertTypeSchema = z.enum(["SIGNAL_EVENT", "NEW_CLUSTER", "METRIC_THRESHOLD"]);
+
+// NEW_CLUSTER alerts ignore metadata; SIGNAL_EVENT alerts require severities + optional skipSimilar;
+// METRIC_THRESHOLD alerts store their threshold rule.
+const buildMetadataValidator = (type: z.infer<typeof AlertTypeSchema>, metadata: unknown) => {
   if (type === "SIGNAL_EVENT") {
     const parsed = SignalEventMetadataSchema.parse(metadata ?? {});
     const dedupedSeverities = Array.from(new Set(parsed.severities)).sort((a, b) => a - b);
     return { ...parsed, severities: dedupedSeverities };
   }
+  if (type === "METRIC_THRESHOLD") {
+    return MetricThresholdMetadataSchema.parse(metadata ?? {});
+  }
   return NewClusterMetadataSchema.parse(metadata ?? {});
 };
 
+// Metric-threshold alerts watch the whole project, so their source is the project itself.
+const resolveSourceId = (type: z.infer<typeof AlertTypeSchema>, projectId: string, sourceId?: string) => {
+  if (type === "METRIC_THRESHOLD") return projectId;
+  if (!sourceId) {
+    throw new Error("sourceId is required for signal alerts.");
+  }
+  return sourceId;
+};
+
 const CreateAlertSchema = z.object({
   projectId: z.guid(),
   name: z.string().min(1),
-  type: z.enum(["SIGNAL_EVENT", "NEW_CLUSTER"]),
-  sourceId: z.guid(),
+  type: AlertTypeSchema,
+  sourceId: z.guid().optional(),
   targets: z.array(TargetSchema),
   metadata: z.unknown().optional(),
 });
@@ -45,13 +103,33 @@ const UpdateAlertSchema = z.object({
   alertId: z.guid(),
   projectId: z.guid(),
   name: z.string().min(1),
-  type: z.enum(["SIGNAL_EVENT", "NEW_CLUSTER"]),
-  sourceId: z.guid(),
+  type: AlertTypeSchema,
+  sourceId: z.guid().optional(),
   targets: z.array(TargetSchema),
   userEmail: z.string().optional(),
   metadata: z.unknown().optional(),
 });
 
+const SetWebhookTargetsSchema = z.object({
+  alertId: z.guid(),
+  projectId: z.guid(),
+  targets: z.array(
+    z.object({
+      type: z.enum(["WEBHOOK", "TEAMS", "DISCORD"]),
+      url: z.url({ protocol: /^https?$/ }),
+      // Only used by WEBHOOK targets. Omit to keep the secret already stored for the same URL.
+      signingSecret: z.string().min(16).optional(),
+    })
+  ),
+});
+
+const SnoozeAlertSchema = z.object({
+  alertId: z.guid(),
+  projectId: z.guid(),
+  // 0 lifts an active snooze.
+  minutes: z.number().int().min(0).max(7 * 24 * 60),
+});
+
 const DeleteAlertSchema = z.object({
   alertId: z.guid(),
   projectId: z.guid(),
@@ -76,9 +154,12 @@ export async function getAlerts(projectId: string, userEmail?: string): Promise<
       projectId: alerts.projectId,
       createdAt: alerts.createdAt,
       metadata: alerts.metadata,
+      firing: metricAlertStates.firing,
+      snoozedUntil: metricAlertStates.snoozedUntil,
     })
     .from(alerts)
     .leftJoin(signals, eq(alerts.sourceId, signals.id))
+    .leftJoin(metricAlertStates, eq(metricAlertStates.alertId, alerts.id))
     .where(eq(alerts.projectId, projectId))
     .orderBy(alerts.createdAt);
 
@@ -95,6 +176,7 @@ export async function getAlerts(projectId: string, userEmail?: string): Promise<
       channelId: alertTargets.channelId,
       channelName: alertTargets.channelName,
       email: alertTargets.email,
+      url: alertTargets.url,
     })
     .from(alertTargets)
     .where(inArray(alertTargets.alertId, alertIds));
@@ -112,6 +194,7 @@ export async function getAlerts(projectId: string, userEmail?: string): Promise<
       channelId: t.channelId,
       channelName: t.channelName,
       email: t.email,
+      url: t.url,
     });
     targetsByAlert.set(t.alertId, list);
   }
@@ -127,8 +210,9 @@ export async function getAlerts(projectId: string, userEmail?: string): Promise<
 }
 
 export async function createAlert(input: z.infer<typeof CreateAlertSchema>) {
-  const { projectId, name, type, sourceId, targets, metadata } = CreateAlertSchema.parse(input);
+  const { projectId, name, type, sourceId: inputSourceId, targets, metadata } = CreateAlertSchema.parse(input);
   const validatedMetadata = buildMetadataValidator(type, metadata);
+  const sourceId = resolveSourceId(type, projectId, inputSourceId);
 
   return await db.transaction(async (tx) => {
     const [alert] = await tx
@@ -155,8 +239,18 @@ export async function createAlert(input: z.infer<typeof CreateAlertSchema>) {
 }
 
 export async function updateAlert(input: z.infer<typeof UpdateAlertSchema>) {
-  const { alertId, projectId, name, type, sourceId, targets, userEmail, metadata } = UpdateAlertSchema.parse(input);
+  const {
+    alertId,
+    projectId,
+    name,
+    type,
+    sourceId: inputSourceId,
+    targets,
+    userEmail,
+    metadata,
+  } = UpdateAlertSchema.parse(input);
   const validatedMetadata = buildMetadataValidator(type, metadata);
+  const sourceId = resolveSourceId(type, projectId, inputSourceId);
 
   const result = await db.transaction(async (tx) => {
     await tx
@@ -174,6 +268,9 @@ export async function updateAlert(input: z.infer<typeof UpdateAlertSchema>) {
         channelId: alertTargets.channelId,
         channelName: alertTargets.channelName,
         email: alertTargets.email,
+        url: alertTargets.url,
+        signingSecret: alertTargets.signingSecret,
+        signingSecretNonce: alertTargets.signingSecretNonce,
       })
       .from(alertTargets)
       .where(and(eq(alertTargets.alertId, alertId), eq(alertTargets.projectId, projectId)));
@@ -183,6 +280,8 @@ export async function updateAlert(input: z.infer<typeof UpdateAlertSchema>) {
     const preservedEmailTargets = existingTargets.filter(
       (t) => t.type === "EMAIL" && (!userEmail || t.email !== userEmail)
     );
+    // Webhook targets are managed through setAlertWebhookTargets, so the alert form never drops them.
+    const preservedWebhookTargets = existingTargets.filter((t) => WEBHOOK_TARGET_TYPES.includes(t.type));
 
     await tx.delete(alertTargets).where(and(eq(alertTargets.alertId, alertId), eq(alertTargets.projectId, projectId)));
 
@@ -205,6 +304,14 @@ export async function updateAlert(input: z.infer<typeof UpdateAlertSchema>) {
         channelName: t.channelName ?? null,
         email: t.email,
       })),
+      ...preservedWebhookTargets.map((t) => ({
+        alertId,
+        projectId,
+        type: t.type,
+        url: t.url,
+        signingSecret: t.signingSecret,
+        signingSecretNonce: t.signingSecretNonce,
+      })),
     ];
 
     if (allTargets.length > 0) {
@@ -219,6 +326,102 @@ export async function updateAlert(input: z.infer<typeof UpdateAlertSchema>) {
   return result;
 }
 
+// Replace ALL webhook, Teams and Discord targets of an alert with the provided set.
+// Pass an empty `targets` array to remove every webhook target.
+// Returns null if the alert does not belong to the project.
+export async function setAlertWebhookTargets(input: z.infer<typeof SetWebhookTargetsSchema>) {
+  const { alertId, projectId, targets } = SetWebhookTargetsSchema.parse(input);
+
+  const [project] = await db
+    .select({ workspaceId: projects.workspaceId })
+    .from(projects)
+    .where(eq(projects.id, projectId))
+    .limit(1);
+  if (!project) {
+    throw new Error("Project not found.");
+  }
+
+  const [alert] = await db
+    .select({ id: alerts.id })
+    .from(alerts)
+    .where(and(eq(alerts.id, alertId), eq(alerts.projectId, projectId)))
+    .limit(1);
+  if (!alert) {
+    return null;
+  }
+
+  return await db.transaction(async (tx) => {
+    const whereWebhookTargets = and(
+      eq(alertTargets.alertId, alertId),
+      eq(alertTargets.projectId, projectId),
+      inArray(alertTargets.type, WEBHOOK_TARGET_TYPES)
+    );
+
+    const existing = await tx
+      .select({
+        url: alertTargets.url,
+        signingSecret: alertTargets.signingSecret,
+        signingSecretNonce: alertTargets.signingSecretNonce,
+      })
+      .from(alertTargets)
+      .where(and(whereWebhookTargets, eq(alertTargets.type, "WEBHOOK")));
+
+    const rows = await Promise.all(
+      targets.map(async (t) => {
+        let signingSecret: string | null = null;
+        let signingSecretNonce: string | null = null;
+        if (t.type === "WEBHOOK") {
+          if (t.signingSecret) {
+            // The workspace id is the associated data, matching app-server's data_plane::crypto.
+            const encrypted = await encryptValue(project.workspaceId, t.signingSecret);
+            signingSecret = encrypted.value;
+            signingSecretNonce = encrypted.nonce;
+          } else {
+            const previous = existing.find((e) => e.url === t.url);
+            signingSecret = previous?.signingSecret ?? null;
+            signingSecretNonce = previous?.signingSecretNonce ?? null;
+          }
+        }
+        return { alertId, projectId, type: t.type, url: t.url, signingSecret, signingSecretNonce };
+      })
+    );
+
+    await tx.delete(alertTargets).where(whereWebhookTargets);
+    if (rows.length > 0) {
+      await tx.insert(alertTargets).values(rows);
+    }
+
+    return { success: true };
+  });
+}
+
+// Suppress notifications of a metric-threshold alert for `minutes`. Evaluation keeps running,
+// so a breach that is still ongoing when the snooze ends is notified then.
+export async function snoozeAlert(input: z.infer<typeof SnoozeAlertSchema>) {
+  const { alertId, projectId, minutes } = SnoozeAlertSchema.parse(input);
+
+  const [alert] = await db
+    .select({ id: alerts.id, type: alerts.type })
+    .from(alerts)
+    .where(and(eq(alerts.id, alertId), eq(alerts.projectId, projectId)))
+    .limit(1);
+  if (!alert) {
+    throw new Error("Alert not found.");
+  }
+  if (alert.type !== "METRIC_THRESHOLD") {
+    throw new Error("Only metric threshold alerts can be snoozed.");
+  }
+
+  const snoozedUntil = minutes > 0 ? new Date(Date.now() + minutes * 60 * 1000).toISOString() : null;
+
+  await db
+    .insert(metricAlertStates)
+    .values({ alertId, projectId, snoozedUntil })
+    .onConflictDoUpdate({ target: metricAlertStates.alertId, set: { snoozedUntil } });
+
+  return { snoozedUntil };
+}
+
 export async function deleteAlert(input: z.infer<typeof DeleteAlertSchema>) {
   const { alertId, projectId } = DeleteAlertSchema.parse(input);
   await db.delete(alerts).where(and(eq(alerts.id, alertId), eq(alerts.projectId, projectId)));
@@ -1,6 +1,7 @@
 export const ALERT_TYPE = {
   SIGNAL_EVENT: "SIGNAL_EVENT",
   NEW_CLUSTER: "NEW_CLUSTER",
+  METRIC_THRESHOLD: "METRIC_THRESHOLD",
 } as const;
 
 export type AlertType = (typeof ALERT_TYPE)[keyof typeof ALERT_TYPE];
@@ -8,13 +9,24 @@ export type AlertType = (typeof ALERT_TYPE)[keyof typeof ALERT_TYPE];
 export const ALERT_TYPE_LABELS: Record<AlertType, string> = {
   [ALERT_TYPE.SIGNAL_EVENT]: "New event",
   [ALERT_TYPE.NEW_CLUSTER]: "New cluster",
+  [ALERT_TYPE.METRIC_THRESHOLD]: "Metric threshold",
 };
 
 export const ALERT_TARGET_TYPE = {
   SLACK: "SLACK",
   EMAIL: "EMAIL",
+  WEBHOOK: "WEBHOOK",
+  TEAMS: "TEAMS",
+  DISCORD: "DISCORD",
 } as const;
 
+// Target types that post to a user-supplied URL and are managed separately from Slack/email targets.
+export const WEBHOOK_TARGET_TYPES: string[] = [
+  ALERT_TARGET_TYPE.WEBHOOK,
+  ALERT_TARGET_TYPE.TEAMS,
+  ALERT_TARGET_TYPE.DISCORD,
+];
+
 export const SEVERITY_LEVEL = {
   INFO: 0,
   WARNING: 1,
@@ -34,6 +46,40 @@ export interface SignalEventAlertMetadata {
   skipSimilar?: boolean;
 }
 
+export const ALERT_METRIC = [
+  "latency_avg",
+  "latency_p50",
+  "latency_p90",
+  "latency_p95",
+  "latency_p99",
+  "error_rate",
+  "cost",
+  "span_count",
+  "tokens",
+] as const;
+
+export type AlertMetric = (typeof ALERT_METRIC)[number];
+
+export const THRESHOLD_OPERATOR = ["gt", "gte", "lt", "lte"] as const;
+
+export type ThresholdOperator = (typeof THRESHOLD_OPERATOR)[number];
+
+// Latencies are in seconds, error rate in percent and cost in USD.
+export interface MetricThresholdAlertMetadata {
+  metric: AlertMetric;
+  filters?: {
+    model?: string;
+    provider?: string;
+    path?: string;
+    spanType?: string;
+  };
+  windowMinutes: number;
+  operator: ThresholdOperator;
+  threshold: number;
+  renotifyMinutes?: number;
+  minSpanCount?: number;
+}
+
 export type AlertMetadata = Record<string, any>;
 
 export interface AlertTarget {
@@ -43,6 +89,7 @@ export interface AlertTarget {
   channelId: string | null;
   channelName: string | null;
   email: string | null;
+  url: string | null;
 }
 
 export interface AlertWithDetails {
@@ -56,4 +103,7 @@ export interface AlertWithDetails {
   createdAt: string;
   targets: AlertTarget[];
   metadata: AlertMetadata;
+  // Evaluation state of METRIC_THRESHOLD alerts; null for other types or before the first evaluation.
+  firing: boolean | null;
+  snoozedUntil: string | null;
 }
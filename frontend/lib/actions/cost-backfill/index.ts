@@ -0,0 +1,110 @@
+import { desc, eq } from "drizzle-orm";
+import { z } from "zod/v4";
+
+import { checkUserWorkspaceRole } from "@/lib/actions/workspace/utils";
+import { db } from "@/lib/db/drizzle";
+import { costBackfillJobs, projects } from "@/lib/db/migrations/schema";
+import { fetcherJSON } from "@/lib/utils";
+
+const GetCostBackfillJobsSchema = z.object({
+  projectId: z.guid(),
+});
+
+const StartCostBackfillSchema = z
+  .object({
+    projectId: z.guid(),
+    startTime: z.iso.datetime({ offset: true }),
+    endTime: z.iso.datetime({ offset: true }),
+    model: z.string().trim().optional(),
+  })
+  .refine((input) => new Date(input.startTime) < new Date(input.endTime), {
+    message: "Start time must be before end time",
+    path: ["endTime"],
+  });
+
+const ResumeCostBackfillSchema = z.object({
+  projectId: z.guid(),
+  jobId: z.guid(),
+});
+
+export type CostBackfillJob = {
+  id: string;
+  projectId: string;
+  startTime: string;
+  endTime: string;
+  model: string | null;
+  status: "running" | "completed" | "failed";
+  processedSpans: number;
+  updatedSpans: number;
+  error: string | null;
+  createdAt: string;
+  updatedAt: string;
+};
+
+/** Backfills rewrite stored costs across the project, so only workspace admins may run them. */
+async function checkCanRunCostBackfill(projectId: string) {
+  const [project] = await db
+    .select({ workspaceId: projects.workspaceId })
+    .from(projects)
+    .where(eq(projects.id, projectId))
+    .limit(1);
+  if (!project) {
+    throw new Error("Project not found.");
+  }
+
+  await checkUserWorkspaceRole({ workspaceId: project.workspaceId, roles: ["owner", "admin"] });
+}
+
+export async function getCostBackfillJobs(
+  input: z.infer<typeof GetCostBackfillJobsSchema>
+): Promise<CostBackfillJob[]> {
+  const { projectId } = GetCostBackfillJobsSchema.parse(input);
+
+  const rows = await db
+    .select({
+      id: costBackfillJobs.id,
+      projectId: costBackfillJobs.projectId,
+      startTime: costBackfillJobs.startTime,
+      endTime: costBackfillJobs.endTime,
+      model: costBackfillJobs.model,
+      status: costBackfillJobs.status,
+      processedSpans: costBackfillJobs.processedSpans,
+      updatedSpans: costBackfillJobs.updatedSpans,
+      error: costBackfillJobs.error,
+      createdAt: costBackfillJobs.createdAt,
+      updatedAt: costBackfillJobs.updatedAt,
+    })
+    .from(costBackfillJobs)
+    .where(eq(costBackfillJobs.projectId, projectId))
+    .orderBy(desc(costBackfillJobs.createdAt));
+
+  return rows as CostBackfillJob[];
+}
+
+export async function startCostBackfill(input: z.infer<typeof StartCostBackfillSchema>): Promise<CostBackfillJob> {
+  const { projectId, startTime, endTime, model } = StartCostBackfillSchema.parse(input);
+
+  await checkCanRunCostBackfill(projectId);
+
+  return await fetcherJSON<CostBackfillJob>(`/projects/${projectId}/cost-backfill-jobs`, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify({
+      startTime,
+      endTime,
+      model: model || undefined,
+    }),
+  });
+}
+
+export async function resumeCostBackfill(input: z.infer<typeof ResumeCostBackfillSchema>): Promise<CostBackfillJob> {
+  const { projectId, jobId } = ResumeCostBackfillSchema.parse(input);
+
+  await checkCanRunCostBackfill(projectId);
+
+  return await fetcherJSON<CostBackfillJob>(`/projects/${projectId}/cost-backfill-jobs/${jobId}/resume`, {
+    method: "POST",
+  });
+}
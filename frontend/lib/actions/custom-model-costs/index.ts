@@ -6,6 +6,9 @@ import { customModelCosts } from "@/lib/db/migrations/schema";
 
 import { invalidateCustomModelCostsCache } from "./invalidate-cache";
 
+/** Versions without an explicit start date apply to all spans. */
+const ALWAYS_EFFECTIVE_FROM = "1970-01-01T00:00:00.000Z";
+
 const GetCustomModelCostsSchema = z.object({
   projectId: z.guid(),
 });
@@ -16,6 +19,7 @@ const UpsertCustomModelCostSchema = z.object({
   provider: z.string().optional(),
   model: z.string().min(1, "Model name is required"),
   costs: z.record(z.string(), z.number().nonnegative("Cost values must not be negative")),
+  effectiveFrom: z.iso.datetime({ offset: true }).optional(),
   previousModel: z.string().optional(),
   previousProvider: z.string().optional(),
 });
@@ -36,6 +40,7 @@ export type CustomModelCost = {
   provider: string;
   model: string;
   costs: Record<string, number>;
+  effectiveFrom: string;
   createdAt: string;
   updatedAt: string;
 };
@@ -52,6 +57,7 @@ export async function getCustomModelCosts(
       provider: customModelCosts.provider,
       model: customModelCosts.model,
       costs: customModelCosts.costs,
+      effectiveFrom: customModelCosts.effectiveFrom,
       createdAt: customModelCosts.createdAt,
       updatedAt: customModelCosts.updatedAt,
     })
@@ -64,12 +70,18 @@ export async function getCustomModelCosts(
 
 export class DuplicateModelCostError extends Error {
   constructor() {
-    super("A cost entry for this provider and model already exists");
+    super("A cost entry for this provider, model and effective date already exists");
     this.name = "DuplicateModelCostError";
   }
 }
 
-async function checkDuplicate(projectId: string, provider: string, model: string, excludeId?: string) {
+async function checkDuplicate(
+  projectId: string,
+  provider: string,
+  model: string,
+  effectiveFrom: string,
+  excludeId?: string
+) {
   const existing = await db
     .select({ id: customModelCosts.id })
     .from(customModelCosts)
@@ -77,7 +89,8 @@ async function checkDuplicate(projectId: string, provider: string, model: string
       and(
         eq(customModelCosts.projectId, projectId),
         eq(customModelCosts.provider, provider),
-        eq(customModelCosts.model, model)
+        eq(customModelCosts.model, model),
+        eq(customModelCosts.effectiveFrom, effectiveFrom)
       )
     )
     .limit(1);
@@ -95,15 +108,16 @@ export async function upsertCustomModelCost(
   const provider = (parsed.provider ?? "").toLowerCase();
   const model = parsed.model.toLowerCase();
   const costs = parsed.costs;
+  const effectiveFrom = parsed.effectiveFrom ?? ALWAYS_EFFECTIVE_FROM;
 
-  await checkDuplicate(projectId, provider, model, id);
+  await checkDuplicate(projectId, provider, model, effectiveFrom, id);
 
   let result: CustomModelCost;
 
   if (id) {
     const [row] = await db
       .update(customModelCosts)
-      .set({ provider, model, costs, updatedAt: new Date().toISOString() })
+      .set({ provider, model, costs, effectiveFrom, updatedAt: new Date().toISOString() })
       .where(and(eq(customModelCosts.id, id), eq(customModelCosts.projectId, projectId)))
       .returning();
 
@@ -113,7 +127,10 @@ export async function upsertCustomModelCost(
 
     result = row as CustomModelCost;
   } else {
-    const [row] = await db.insert(customModelCosts).values({ projectId, provider, model, costs }).returning();
+    const [row] = await db
+      .insert(customModelCosts)
+      .values({ projectId, provider, model, costs, effectiveFrom })
+      .returning();
     result = row as CustomModelCost;
   }
 
@@ -179,6 +196,7 @@ export async function copyCustomModelCosts(
           provider: cost.provider,
           model: cost.model,
           costs: cost.costs,
+          effectiveFrom: cost.effectiveFrom,
         }))
       )
       .returning();
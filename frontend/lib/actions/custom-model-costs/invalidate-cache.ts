@@ -1,11 +1,13 @@
 import { cache } from "@/lib/cache";
 
-const CUSTOM_MODEL_COSTS_CACHE_KEY = "custom_model_costs";
+// Must stay in sync with `CUSTOM_MODEL_COSTS_CACHE_KEY` in `app-server/src/cache/keys.rs`.
+// The `_v2` suffix marks entries holding every price version of a model.
+const CUSTOM_MODEL_COSTS_CACHE_KEY = "custom_model_costs_v2";
 
 /**
  * Invalidate the custom model cost cache entry for a project + provider + model.
  *
- * Cache keys have the format `custom_model_costs:{project_id}:{provider}:{model}`,
+ * Cache keys have the format `custom_model_costs_v2:{project_id}:{provider}:{model}`,
  * matching the Rust backend's key construction. Provider may be empty string.
  *
  * Best-effort: logs errors but does not throw.
@@ -0,0 +1,61 @@
+import { and, eq } from "drizzle-orm";
+import { z } from "zod/v4";
+
+import { getServerSession } from "@/lib/auth-session";
+import { db } from "@/lib/db/drizzle";
+import { membersOfWorkspaces, projects } from "@/lib/db/migrations/schema";
+import { fetcherJSON } from "@/lib/utils";
+
+const RevealPseudonymsSchema = z.object({
+  projectId: z.guid(),
+  pseudonyms: z.array(z.string().min(1)).min(1).max(1000),
+});
+
+export class PiiRevealForbiddenError extends Error {
+  constructor() {
+    super("Forbidden: Only owner or admin roles can reveal pseudonyms");
+    this.name = "PiiRevealForbiddenError";
+  }
+}
+
+/** Reveal undoes redaction, so only workspace admins may do it; the app-server audit-logs every call. */
+export async function revealPseudonyms(
+  input: z.infer<typeof RevealPseudonymsSchema>
+): Promise<{ originals: Record<string, string> }> {
+  const { projectId, pseudonyms } = RevealPseudonymsSchema.parse(input);
+
+  const session = await getServerSession();
+  if (!session?.user) {
+    throw new Error("Unauthorized: User not authenticated");
+  }
+
+  const [project] = await db
+    .select({ workspaceId: projects.workspaceId })
+    .from(projects)
+    .where(eq(projects.id, projectId))
+    .limit(1);
+  if (!project) {
+    throw new Error("Project not found.");
+  }
+
+  const membership = await db.query.membersOfWorkspaces.findFirst({
+    where: and(
+      eq(membersOfWorkspaces.workspaceId, project.workspaceId),
+      eq(membersOfWorkspaces.userId, session.user.id)
+    ),
+  });
+  if (membership?.memberRole !== "owner" && membership?.memberRole !== "admin") {
+    throw new PiiRevealForbiddenError();
+  }
+
+  return await fetcherJSON<{ originals: Record<string, string> }>(`/projects/${projectId}/pii/reveal`, {
+    method: "POST",
+    headers: {
+      "Content-Type": "application/json",
+    },
+    body: JSON.stringify({
+      pseudonyms,
+      userId: session.user.id,
+    }),
+  });
+}
@@ -0,0 +1,142 @@
+import { and, eq } from "drizzle-orm";
+import { z } from "zod/v4";
+
+import { checkUserWorkspaceRole } from "@/lib/actions/workspace/utils";
+import { db } from "@/lib/db/drizzle";
+import { workspacePricingDiscounts } from "@/lib/db/migrations/schema";
+
+import { invalidatePricingDiscountsCache } from "./invalidate-cache";
+
+/** Discounts without an explicit start date apply to all spans. */
+const ALWAYS_EFFECTIVE_FROM = "1970-01-01T00:00:00.000Z";
+
+const GetPricingDiscountsSchema = z.object({
+  workspaceId: z.guid(),
+});
+
+const UpsertPricingDiscountSchema = z.object({
+  id: z.guid().optional(),
+  workspaceId: z.guid(),
+  provider: z.string().optional(),
+  multiplier: z.number().min(0, "Multiplier must not be negative").max(1, "Multiplier must not exceed 1"),
+  effectiveFrom: z.iso.datetime({ offset: true }).optional(),
+});
+
+const DeletePricingDiscountSchema = z.object({
+  workspaceId: z.guid(),
+  id: z.guid(),
+});
+
+export type PricingDiscount = {
+  id: string;
+  workspaceId: string;
+  provider: string;
+  multiplier: number;
+  effectiveFrom: string;
+  createdAt: string;
+};
+
+export class DuplicatePricingDiscountError extends Error {
+  constructor() {
+    super("A discount for this provider and effective date already exists");
+    this.name = "DuplicatePricingDiscountError";
+  }
+}
+
+export async function getPricingDiscounts(
+  input: z.infer<typeof GetPricingDiscountsSchema>
+): Promise<PricingDiscount[]> {
+  const { workspaceId } = GetPricingDiscountsSchema.parse(input);
+
+  await checkUserWorkspaceRole({ workspaceId, roles: ["owner", "admin", "member"] });
+
+  const rows = await db
+    .select({
+      id: workspacePricingDiscounts.id,
+      workspaceId: workspacePricingDiscounts.workspaceId,
+      provider: workspacePricingDiscounts.provider,
+      multiplier: workspacePricingDiscounts.multiplier,
+      effectiveFrom: workspacePricingDiscounts.effectiveFrom,
+      createdAt: workspacePricingDiscounts.createdAt,
+    })
+    .from(workspacePricingDiscounts)
+    .where(eq(workspacePricingDiscounts.workspaceId, workspaceId))
+    .orderBy(workspacePricingDiscounts.provider, workspacePricingDiscounts.effectiveFrom);
+
+  return rows as PricingDiscount[];
+}
+
+async function checkDuplicate(workspaceId: string, provider: string, effectiveFrom: string, excludeId?: string) {
+  const existing = await db
+    .select({ id: workspacePricingDiscounts.id })
+    .from(workspacePricingDiscounts)
+    .where(
+      and(
+        eq(workspacePricingDiscounts.workspaceId, workspaceId),
+        eq(workspacePricingDiscounts.provider, provider),
+        eq(workspacePricingDiscounts.effectiveFrom, effectiveFrom)
+      )
+    )
+    .limit(1);
+
+  if (existing.length > 0 && existing[0].id !== excludeId) {
+    throw new DuplicatePricingDiscountError();
+  }
+}
+
+export async function upsertPricingDiscount(
+  input: z.infer<typeof UpsertPricingDiscountSchema>
+): Promise<PricingDiscount> {
+  const parsed = UpsertPricingDiscountSchema.parse(input);
+  const { id, workspaceId, multiplier } = parsed;
+  // Lowercase to match the Rust backend, which compares providers case-insensitively
+  const provider = (parsed.provider ?? "").trim().toLowerCase();
+  const effectiveFrom = parsed.effectiveFrom ?? ALWAYS_EFFECTIVE_FROM;
+
+  await checkUserWorkspaceRole({ workspaceId, roles: ["owner", "admin"] });
+
+  await checkDuplicate(workspaceId, provider, effectiveFrom, id);
+
+  let result: PricingDiscount;
+
+  if (id) {
+    const [row] = await db
+      .update(workspacePricingDiscounts)
+      .set({ provider, multiplier, effectiveFrom })
+      .where(and(eq(workspacePricingDiscounts.id, id), eq(workspacePricingDiscounts.workspaceId, workspaceId)))
+      .returning();
+
+    if (!row) {
+      throw new Error("Pricing discount not found");
+    }
+
+    result = row as PricingDiscount;
+  } else {
+    const [row] = await db
+      .insert(workspacePricingDiscounts)
+      .values({ workspaceId, provider, multiplier, effectiveFrom })
+      .returning();
+    result = row as PricingDiscount;
+  }
+
+  await invalidatePricingDiscountsCache(workspaceId);
+
+  return result;
+}
+
+export async function deletePricingDiscount(input: z.infer<typeof DeletePricingDiscountSchema>): Promise<void> {
+  const { workspaceId, id } = DeletePricingDiscountSchema.parse(input);
+
+  await checkUserWorkspaceRole({ workspaceId, roles: ["owner", "admin"] });
+
+  const result = await db
+    .delete(workspacePricingDiscounts)
+    .where(and(eq(workspacePricingDiscounts.id, id), eq(workspacePricingDiscounts.workspaceId, workspaceId)))
+    .returning({ id: workspacePricingDiscounts.id });
+
+  if (result.length === 0) {
+    throw new Error("Pricing discount not found");
+  }
+
+  await invalidatePricingDiscountsCache(workspaceId);
+}
@@ -0,0 +1,31 @@
+import { eq } from "drizzle-orm";
+
+import { cache, PRICING_DISCOUNTS_CACHE_KEY } from "@/lib/cache";
+import { db } from "@/lib/db/drizzle";
+import { projects } from "@/lib/db/migrations/schema";
+
+/**
+ * Invalidate the pricing discount cache entries for every project in a workspace.
+ *
+ * Cache keys have the format `pricing_discounts:{project_id}`, matching the Rust
+ * backend's key construction. Discounts are workspace-wide, so one change affects
+ * all of the workspace's projects.
+ *
+ * Best-effort: logs errors but does not throw.
+ */
+export async function invalidatePricingDiscountsCache(workspaceId: string): Promise<void> {
+  try {
+    const workspaceProjects = await db.query.projects.findMany({
+      where: eq(projects.workspaceId, workspaceId),
+      columns: { id: true },
+    });
+
+    await Promise.all(
+      workspaceProjects.map((project) => cache.remove(`${PRICING_DISCOUNTS_CACHE_KEY}:${project.id}`))
+    );
+  } catch (error) {
+    // Best-effort: don't block the CRUD response if cache invalidation fails.
+    // The cache entry will expire naturally via TTL.
+    console.error("Failed to invalidate pricing discounts cache:", error);
+  }
+}
@@ -1,11 +1,29 @@
 import { and, eq } from "drizzle-orm";
+import { isIP } from "net";
 import { z } from "zod/v4";
 
 import { createProjectApiKey } from "@/lib/api-keys";
+import { API_KEY_SCOPES, type ApiKeyScope } from "@/lib/api-keys/types";
 import { cache, PROJECT_API_KEY_CACHE_KEY } from "@/lib/cache";
 import { db } from "@/lib/db/drizzle";
 import { projectApiKeys } from "@/lib/db/migrations/schema";
 
+const MAX_ALLOWED_CIDRS = 50;
+
+// `10.0.0.0/8`, `2001:db8::/32` or a bare address.
+function isValidCidr(value: string): boolean {
+  const [address, prefix, ...rest] = value.split("/");
+  const version = isIP(address);
+  if (version === 0 || rest.length > 0) {
+    return false;
+  }
+  if (prefix === undefined) {
+    return true;
+  }
+  const bits = Number(prefix);
+  return /^\d+$/.test(prefix) && bits <= (version === 4 ? 32 : 128);
+}
+
 const CreateProjectApiKeySchema = z.object({
   projectId: z.guid(),
   name: z.string().optional().nullable(),
@@ -14,6 +32,14 @@ const CreateProjectApiKeySchema = z.object({
   userId: z.guid().optional().nullable(),
   // Absolute expiry as an ISO timestamp; null = never expires.
   expiresAt: z.string().optional().nullable(),
+  // Least-privilege restrictions, enforced by the app-server. Empty = unrestricted.
+  scopes: z.array(z.enum(API_KEY_SCOPES)).optional().default([]),
+  allowedCidrs: z
+    .array(z.string().trim().refine(isValidCidr, { message: "Invalid IP address or CIDR range" }))
+    .max(MAX_ALLOWED_CIDRS)
+    .optional()
+    .default([]),
+  rateLimitPerMinute: z.number().int().positive().optional().nullable(),
 });
 
 const GetProjectApiKeysSchema = z.object({
@@ -33,10 +59,14 @@ export interface ProjectApiKeyResponse {
   shorthand: string;
   isIngestOnly: boolean;
   expiresAt: string | null;
+  scopes: ApiKeyScope[];
+  allowedCidrs: string[];
+  rateLimitPerMinute: number | null;
 }
 
-export async function createApiKey(input: z.infer<typeof CreateProjectApiKeySchema>): Promise<ProjectApiKeyResponse> {
-  const { projectId, name, isIngestOnly, userId, expiresAt } = CreateProjectApiKeySchema.parse(input);
+export async function createApiKey(input: z.input<typeof CreateProjectApiKeySchema>): Promise<ProjectApiKeyResponse> {
+  const { projectId, name, isIngestOnly, userId, expiresAt, scopes, allowedCidrs, rateLimitPerMinute } =
+    CreateProjectApiKeySchema.parse(input);
 
   const { value, hash, shorthand } = createProjectApiKey();
 
@@ -50,6 +80,9 @@ export async function createApiKey(input: z.infer<typeof CreateProjectApiKeySche
       isIngestOnly: isIngestOnly ?? false,
       userId: userId || null,
       expiresAt: expiresAt || null,
+      scopes,
+      allowedCidrs,
+      rateLimitPerMinute: rateLimitPerMinute ?? null,
     })
     .returning();
 
@@ -65,6 +98,9 @@ export async function createApiKey(input: z.infer<typeof CreateProjectApiKeySche
       shorthand: key.shorthand,
       isIngestOnly: key.isIngestOnly,
       expiresAt: key.expiresAt,
+      scopes: key.scopes,
+      allowedCidrs: key.allowedCidrs,
+      rateLimitPerMinute: key.rateLimitPerMinute,
     },
     // Match the app-server's 1-day cache TTL so an expired key can't outlive its
     // window in cache; without expiry the app-server re-reads from the DB anyway.
@@ -79,6 +115,9 @@ export async function createApiKey(input: z.infer<typeof CreateProjectApiKeySche
     shorthand,
     isIngestOnly,
     expiresAt: key.expiresAt,
+    scopes,
+    allowedCidrs,
+    rateLimitPerMinute: key.rateLimitPerMinute,
   };
 }
 
@@ -90,6 +129,9 @@ export async function getApiKeys(input: z.infer<typeof GetProjectApiKeysSchema>)
     shorthand: string;
     isIngestOnly: boolean;
     expiresAt: string | null;
+    scopes: ApiKeyScope[];
+    allowedCidrs: string[];
+    rateLimitPerMinute: number | null;
   }>
 > {
   const { projectId } = GetProjectApiKeysSchema.parse(input);
@@ -102,6 +144,9 @@ export async function getApiKeys(input: z.infer<typeof GetProjectApiKeysSchema>)
       shorthand: projectApiKeys.shorthand,
       isIngestOnly: projectApiKeys.isIngestOnly,
       expiresAt: projectApiKeys.expiresAt,
+      scopes: projectApiKeys.scopes,
+      allowedCidrs: projectApiKeys.allowedCidrs,
+      rateLimitPerMinute: projectApiKeys.rateLimitPerMinute,
     })
     .from(projectApiKeys)
     .where(eq(projectApiKeys.projectId, projectId));
@@ -111,6 +156,7 @@ export async function getApiKeys(input: z.infer<typeof GetProjectApiKeysSchema>)
     ...key,
     name: key.name ?? undefined,
     shorthand: key.shorthand ?? "",
+    scopes: key.scopes as ApiKeyScope[],
   }));
 }
 
@@ -104,6 +104,7 @@ async function deleteProjectDataFromClickHouse(
     "default.evaluation_datapoints",
     "default.evaluation_datapoint_executor_outputs",
     "default.dataset_datapoints",
+    "default.dataset_snapshot_datapoints",
     "default.labeling_queue_items",
     "default.notifications",
     "default.notification_deliveries",
@@ -18,11 +18,96 @@ import { db } from "@/lib/db/drizzle";
 import { projects, subscriptionTiers, workspaces } from "@/lib/db/migrations/schema";
 import { Feature, isFeatureEnabled } from "@/lib/features/features";
 
+/// Tail-based sampling rule, evaluated by the app-server once a trace's spans
+/// are buffered. Filters use the same `{ column, operator, value }` shape as
+/// signal triggers; an empty list matches every trace.
+export const TraceSamplingRuleSchema = z
+  .object({
+    name: z.string().min(1),
+    filters: z.array(
+      z.object({
+        column: z.enum([
+          "status",
+          "cost",
+          "input_cost",
+          "output_cost",
+          "total_token_count",
+          "num_spans",
+          "session_id",
+          "user_id",
+          "tags",
+          "trace_type",
+          "top_span_name",
+          "span_name",
+        ]),
+        operator: z.enum(["eq", "ne", "gt", "gte", "lt", "lte"]),
+        value: z.union([z.string(), z.number(), z.boolean()]),
+      })
+    ),
+    /// Percentage of matching traces to keep, 0-100.
+    sampleRate: z.number().min(0).max(100),
+  })
+  .strict();
+
+export type TraceSamplingRule = z.infer<typeof TraceSamplingRuleSchema>;
+
+/// A pii-redactor field path: `/`-separated object keys with `*` matching any
+/// one key (e.g. `metadata/*/email`). Same grammar as `FieldPath::parse` in
+/// pii-redactor, so a path the redactor would reject never gets saved.
+const PiiFieldPathSchema = z
+  .string()
+  .min(1)
+  .refine(
+    (path) =>
+      path
+        .trim()
+        .replace(/^\/+/, "")
+        .split("/")
+        .every((segment) => segment.length > 0),
+    { message: "Field paths are non-empty keys separated by single slashes, e.g. metadata/*/email" }
+  );
+
+/// Narrows what the pii-redactor redacts. Mirror of the Rust
+/// `PiiRedactionPolicy`.
+export const PiiRedactionPolicySchema = z
+  .object({
+    /// Labels to redact (`private_email`, `credit_card`, ...). Empty = all.
+    labels: z.array(z.string().min(1)).max(100),
+    /// Per-label minimum model confidence.
+    minConfidence: z.record(z.string().min(1), z.number().min(0).max(1)),
+    defaultMinConfidence: z.number().min(0).max(1),
+    skipPaths: z.array(PiiFieldPathSchema).max(128),
+    redactPaths: z.array(PiiFieldPathSchema).max(128),
+    fields: z
+      .object({
+        input: z.boolean(),
+        output: z.boolean(),
+        attributes: z.boolean(),
+        sharedContent: z.boolean(),
+      })
+      .strict(),
+  })
+  .strict();
+
+export type PiiRedactionPolicy = z.infer<typeof PiiRedactionPolicySchema>;
+
 export const ProjectSettingsSchema = z
   .object({
     /// Route every span on this project through the pii-redactor before
     /// storage. Pro-tier gated server-side.
     removePii: z.boolean(),
+    /// How detected PII is replaced: a fixed `[REDACTED_{LABEL}]` placeholder,
+    /// or a per-project keyed `{LABEL}_{HASH}` pseudonym that is stable across
+    /// spans and traces.
+    piiRedactionMode: z.enum(["placeholder", "pseudonym"]),
+    /// Store pseudonym originals encrypted so authorized users can reveal
+    /// them. Only applies in pseudonym mode. Pro-tier gated server-side.
+    piiVaultEnabled: z.boolean(),
+    /// Which labels, confidences and span fields get redacted.
+    piiRedactionPolicy: PiiRedactionPolicySchema,
+    /// Ordered tail-based sampling rules; the first matching rule decides
+    /// whether a trace is kept. Traces that match no rule are kept.
+    traceSamplingRules: z.array(TraceSamplingRuleSchema),
   })
   // `.strict()` rejects unknown keys — a typo in the UI surfaces as 400
   // rather than silently dropping into the JSONB row.
@@ -34,6 +119,17 @@ export type ProjectSettings = z.infer<typeof ProjectSettingsSchema>;
 /// Rust `Default for ProjectSettings`.
 export const DEFAULT_PROJECT_SETTINGS: ProjectSettings = {
   removePii: false,
+  piiRedactionMode: "placeholder",
+  piiVaultEnabled: false,
+  piiRedactionPolicy: {
+    labels: [],
+    minConfidence: {},
+    defaultMinConfidence: 0,
+    skipPaths: [],
+    redactPaths: [],
+    fields: { input: true, output: true, attributes: false, sharedContent: true },
+  },
+  traceSamplingRules: [],
 };
 
 export const UpdateProjectSettingsSchema = z.object({
@@ -44,7 +140,7 @@ export const UpdateProjectSettingsSchema = z.object({
 
 /// Per-key tier gate. A setting whose key is absent here is allowed on every
 /// tier; a present key requires the workspace tier to match the predicate.
-const PRO_TIER_KEYS = new Set<keyof ProjectSettings>(["removePii"]);
+const PRO_TIER_KEYS = new Set<keyof ProjectSettings>(["removePii", "piiVaultEnabled"]);
 
 const PRO_LIKE_TIERS = new Set(["pro", "enterprise"]);
 
@@ -1,15 +1,18 @@
-import { and, eq } from "drizzle-orm";
+import { and, eq, inArray } from "drizzle-orm";
 import { z } from "zod/v4";
 
+import { encryptValue } from "@/lib/crypto";
 import { db } from "@/lib/db/drizzle";
 import { reports, reportTargets } from "@/lib/db/migrations/schema";
 
 import {
   getReportLabel,
   REPORT_TARGET_TYPE,
+  REPORT_TYPE,
   type ReportTargetRow,
   type ReportType,
   type ReportWithDetails,
+  WEBHOOK_TARGET_TYPES,
 } from "./types";
 
 const OptInSchema = z.object({
@@ -37,11 +40,25 @@ const SetSlackTargetsSchema = z.object({
   channels: z.array(z.object({ id: z.string().min(1), name: z.string() })),
 });
 
+const SetWebhookTargetsSchema = z.object({
+  reportId: z.uuid(),
+  workspaceId: z.uuid(),
+  targets: z.array(
+    z.object({
+      type: z.enum(["WEBHOOK", "TEAMS", "DISCORD"]),
+      url: z.url({ protocol: /^https?$/ }),
+      // Only used by WEBHOOK targets. Omit to keep the secret already stored for the same URL.
+      signingSecret: z.string().min(16).optional(),
+    })
+  ),
+});
+
 export async function getReports(workspaceId: string, userEmail?: string): Promise<ReportWithDetails[]> {
   const reportRows = await db
     .select({
       id: reports.id,
       type: reports.type,
+      name: reports.name,
       workspaceId: reports.workspaceId,
       createdAt: reports.createdAt,
       weekdays: reports.weekdays,
@@ -61,6 +78,7 @@ export async function getReports(workspaceId: string, userEmail?: string): Promi
       email: reportTargets.email,
       channelId: reportTargets.channelId,
       channelName: reportTargets.channelName,
+      url: reportTargets.url,
     })
     .from(reportTargets)
     .where(eq(reportTargets.workspaceId, workspaceId));
@@ -77,6 +95,7 @@ export async function getReports(workspaceId: string, userEmail?: string): Promi
       email: t.email,
       channelId: t.channelId,
       channelName: t.channelName,
+      url: t.url,
     });
     targetsByReport.set(t.reportId, list);
   }
@@ -86,7 +105,10 @@ export async function getReports(workspaceId: string, userEmail?: string): Promi
     return {
       id: r.id,
       reportType,
-      label: getReportLabel({ weekdays: r.weekdays, hour: r.hour }),
+      label:
+        reportType === REPORT_TYPE.CUSTOM_QUERY && r.name
+          ? r.name
+          : getReportLabel({ weekdays: r.weekdays, hour: r.hour }),
       workspaceId: r.workspaceId,
       createdAt: r.createdAt,
       schedule: { weekdays: r.weekdays, hour: r.hour },
@@ -210,3 +232,53 @@ export async function setSlackTargets(input: z.infer<typeof SetSlackTargetsSchem
     return { success: true };
   });
 }
+
+// Replace ALL webhook, Teams and Discord targets for a report with the provided set.
+// Pass an empty `targets` array to remove every webhook target.
+export async function setWebhookTargets(input: z.infer<typeof SetWebhookTargetsSchema>) {
+  const { reportId, workspaceId, targets } = SetWebhookTargetsSchema.parse(input);
+
+  return await db.transaction(async (tx) => {
+    const whereWebhookTargets = and(
+      eq(reportTargets.reportId, reportId),
+      eq(reportTargets.workspaceId, workspaceId),
+      inArray(reportTargets.type, WEBHOOK_TARGET_TYPES)
+    );
+
+    const existing = await tx
+      .select({
+        url: reportTargets.url,
+        signingSecret: reportTargets.signingSecret,
+        signingSecretNonce: reportTargets.signingSecretNonce,
+      })
+      .from(reportTargets)
+      .where(and(whereWebhookTargets, eq(reportTargets.type, REPORT_TARGET_TYPE.WEBHOOK)));
+
+    const rows = await Promise.all(
+      targets.map(async (t) => {
+        let signingSecret: string | null = null;
+        let signingSecretNonce: string | null = null;
+        if (t.type === REPORT_TARGET_TYPE.WEBHOOK) {
+          if (t.signingSecret) {
+            // The workspace id is the associated data, matching app-server's data_plane::crypto.
+            const encrypted = await encryptValue(workspaceId, t.signingSecret);
+            signingSecret = encrypted.value;
+            signingSecretNonce = encrypted.nonce;
+          } else {
+            const previous = existing.find((e) => e.url === t.url);
+            signingSecret = previous?.signingSecret ?? null;
+            signingSecretNonce = previous?.signingSecretNonce ?? null;
+          }
+        }
+        return { workspaceId, reportId, type: t.type, url: t.url, signingSecret, signingSecretNonce };
+      })
+    );
+
+    await tx.delete(reportTargets).where(whereWebhookTargets);
+    if (rows.length > 0) {
+      await tx.insert(reportTargets).values(rows);
+    }
+
+    return { success: true };
+  });
+}
@@ -1,5 +1,7 @@
 export const REPORT_TYPE = {
   SIGNAL_EVENTS_SUMMARY: "SIGNAL_EVENTS_SUMMARY",
+  // A saved SQL query scoped to one project, created through the public API.
+  CUSTOM_QUERY: "CUSTOM_QUERY",
 } as const;
 
 export type ReportType = (typeof REPORT_TYPE)[keyof typeof REPORT_TYPE];
@@ -7,8 +9,18 @@ export type ReportType = (typeof REPORT_TYPE)[keyof typeof REPORT_TYPE];
 export const REPORT_TARGET_TYPE = {
   SLACK: "SLACK",
   EMAIL: "EMAIL",
+  WEBHOOK: "WEBHOOK",
+  TEAMS: "TEAMS",
+  DISCORD: "DISCORD",
 } as const;
 
+// Target types that post to a user-supplied URL and are managed through setWebhookTargets.
+export const WEBHOOK_TARGET_TYPES: string[] = [
+  REPORT_TARGET_TYPE.WEBHOOK,
+  REPORT_TARGET_TYPE.TEAMS,
+  REPORT_TARGET_TYPE.DISCORD,
+];
+
 export type ReportTargetType = (typeof REPORT_TARGET_TYPE)[keyof typeof REPORT_TARGET_TYPE];
 
 export interface ReportSchedule {
@@ -22,6 +34,7 @@ export interface ReportTargetRow {
   email: string | null;
   channelId: string | null;
   channelName: string | null;
+  url: string | null;
 }
 
 export interface ReportWithDetails {
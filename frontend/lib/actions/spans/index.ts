@@ -167,6 +167,21 @@ const getTraceTreeStructure = async ({
   });
 };
 
+const getTraceSpanLogCounts = async ({
+  projectId,
+  traceId,
+}: {
+  projectId: string;
+  traceId: string;
+}): Promise<{ spanId: string; warningLogCount: number; errorLogCount: number }[]> =>
+  executeQuery<{ spanId: string; warningLogCount: number; errorLogCount: number }>({
+    query: `SELECT span_id as spanId, warning_log_count as warningLogCount, error_log_count as errorLogCount
+      FROM span_log_counts
+      WHERE trace_id = {traceId: UUID}`,
+    parameters: { traceId },
+    projectId,
+  });
+
 const fetchTraceSpans = async ({
   projectId,
   traceId,
@@ -254,7 +269,7 @@ export async function getTraceSpans(input: z.infer<typeof GetTraceSpansSchema>):
 
   const shouldApplyRewiring = search || filters.length > 0;
 
-  const [spans, treeStructure] = await Promise.all([
+  const [spans, treeStructure, logCounts] = await Promise.all([
     fetchTraceSpans({
       projectId,
       traceId,
@@ -266,6 +281,7 @@ export async function getTraceSpans(input: z.infer<typeof GetTraceSpansSchema>):
       orderBy: [{ column: "start_time", direction: "ASC" }],
     }),
     shouldApplyRewiring ? getTraceTreeStructure({ projectId, traceId }) : Promise.resolve([]),
+    getTraceSpanLogCounts({ projectId, traceId }),
   ]);
 
   if (spans.length === 0) {
@@ -280,7 +296,18 @@ export async function getTraceSpans(input: z.infer<typeof GetTraceSpansSchema>):
         )
       : new Map<string, string | undefined>();
 
-  const transformedSpans = spans.map((span) => transformSpanWithEvents(span as any, parentRewiring));
+  const logCountsBySpan = new Map(logCounts.map((count) => [count.spanId, count]));
+
+  const transformedSpans = spans.map((span) =>
+    transformSpanWithEvents(
+      {
+        ...span,
+        warningLogCount: logCountsBySpan.get(span.spanId)?.warningLogCount ?? 0,
+        errorLogCount: logCountsBySpan.get(span.spanId)?.errorLogCount ?? 0,
+      } as any,
+      parentRewiring
+    )
+  );
 
   const result = aggregateSpanMetrics(transformedSpans);
 
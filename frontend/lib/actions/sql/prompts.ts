@@ -54,7 +54,8 @@ LIMIT n
 
 Additional rules:
 - Only generate SELECT queries
-- Join relationships: spans.trace_id = traces.id, signal_events.trace_id = traces.id
+- Join relationships: spans.trace_id = traces.id, signal_events.trace_id = traces.id, logs.span_id = spans.span_id,
+  span_log_counts.trace_id = spans.trace_id AND span_log_counts.span_id = spans.span_id
 </task>
 
 <examples>
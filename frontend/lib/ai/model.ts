@@ -1,4 +1,5 @@
 import { createAmazonBedrock } from "@ai-sdk/amazon-bedrock";
+import { createAnthropic } from "@ai-sdk/anthropic";
 import { createGoogleGenerativeAI } from "@ai-sdk/google";
 import { createOpenAI } from "@ai-sdk/openai";
 import type { LanguageModel } from "ai";
@@ -9,7 +10,7 @@ import type { LanguageModel } from "ai";
  */
 type ModelTier = "small" | "medium" | "large";
 
-type LLMProvider = "openai" | "gemini" | "bedrock";
+type LLMProvider = "openai" | "gemini" | "bedrock" | "anthropic";
 type LlmDefaultHeaders = Record<string, string>;
 
 // Per-provider defaults. Used when LLM_MODEL_<TIER> is not set.
@@ -29,6 +30,11 @@ const DEFAULT_MODELS: Record<LLMProvider, Record<ModelTier, string>> = {
     medium: "gpt-5.4",
     large: "gpt-5.5",
   },
+  anthropic: {
+    small: "claude-haiku-4-5-20251001",
+    medium: "claude-sonnet-4-6",
+    large: "claude-opus-4-7",
+  },
 };
 
 function hasBedrockCreds(): boolean {
@@ -40,7 +46,7 @@ function getConfiguredLLMProvider(): LLMProvider | null {
   if (provider === "bedrock") {
     return hasBedrockCreds() ? "bedrock" : null;
   }
-  if (provider === "openai" || provider === "gemini") {
+  if (provider === "openai" || provider === "gemini" || provider === "anthropic") {
     return process.env.LLM_API_KEY ? provider : null;
   }
   return null;
@@ -114,8 +120,8 @@ export function getLanguageModel(tier: ModelTier = "large"): LanguageModel {
   const provider = getConfiguredLLMProvider();
   if (!provider) {
     throw new Error(
-      "No AI provider configured. Set LLM_PROVIDER to openai, gemini, or bedrock. " +
-        "openai/gemini require LLM_API_KEY (with optional LLM_BASE_URL); " +
+      "No AI provider configured. Set LLM_PROVIDER to openai, gemini, anthropic, or bedrock. " +
+        "openai/gemini/anthropic require LLM_API_KEY (with optional LLM_BASE_URL); " +
         "bedrock requires AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY, and AWS_REGION."
     );
   }
@@ -136,6 +142,11 @@ export function getLanguageModel(tier: ModelTier = "large"): LanguageModel {
     return openai(modelName);
   }
 
+  if (provider === "anthropic") {
+    const anthropic = createAnthropic({ apiKey, ...(baseURL ? { baseURL } : {}), ...(headers ? { headers } : {}) });
+    return anthropic(modelName);
+  }
+
   const google = createGoogleGenerativeAI({ apiKey, ...(baseURL ? { baseURL } : {}), ...(headers ? { headers } : {}) });
   return google(modelName);
 }
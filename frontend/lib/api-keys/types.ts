@@ -1,3 +1,26 @@
+// Must stay in sync with `ApiKeyScope` in app-server/src/project_api_keys/restrictions.rs.
+export const API_KEY_SCOPES = [
+  "ingest_traces",
+  "ingest_logs",
+  "read_sql",
+  "write_datasets",
+  "run_evals",
+  "mcp",
+  "rollouts",
+] as const;
+
+export type ApiKeyScope = (typeof API_KEY_SCOPES)[number];
+
+export const API_KEY_SCOPE_LABELS: Record<ApiKeyScope, string> = {
+  ingest_traces: "Ingest traces, metrics and browser sessions",
+  ingest_logs: "Ingest logs",
+  read_sql: "Run SQL queries",
+  write_datasets: "Read and write datasets",
+  run_evals: "Run evaluations",
+  mcp: "MCP server",
+  rollouts: "Debugger sessions",
+};
+
 export type ProjectApiKey = {
   shorthand: string;
   projectId: string;
@@ -5,6 +28,9 @@ export type ProjectApiKey = {
   id: string;
   isIngestOnly: boolean;
   expiresAt: string | null;
+  scopes: ApiKeyScope[];
+  allowedCidrs: string[];
+  rateLimitPerMinute: number | null;
 };
 
 export type GenerateProjectApiKeyResponse = {
@@ -14,8 +40,13 @@ export type GenerateProjectApiKeyResponse = {
   shorthand: string;
   isIngestOnly: boolean;
   expiresAt: string | null;
+  scopes: ApiKeyScope[];
+  allowedCidrs: string[];
+  rateLimitPerMinute: number | null;
 };
 
+export type KeyType = "default" | "ingest_only" | "scoped";
+
 export const KEY_EXPIRATION_OPTIONS = [
   { label: "1 day", value: "1" },
   { label: "7 days", value: "7" },
@@ -258,6 +258,9 @@ export const SUMMARY_TRIGGER_SPANS_CACHE_KEY = "summary_trigger_spans";
 export const WORKSPACE_DEPLOYMENTS_CACHE_KEY = "workspace_deployment_config";
 export const WORKSPACE_DEPLOYMENTS_BY_WORKSPACE_CACHE_KEY = "workspace_deployment_config_by_ws";
 export const WORKSPACE_USAGE_WARNINGS_CACHE_KEY = "workspace_usage_warnings";
+// Must stay in sync with `PRICING_DISCOUNTS_CACHE_KEY` in `app-server/src/cache/keys.rs`.
+// Entries are keyed per project, so a workspace discount change clears every project.
+export const PRICING_DISCOUNTS_CACHE_KEY = "pricing_discounts";
 
 export const WORKSPACE_MEMBER_CACHE_KEY = (workspaceId: string, userId: string) =>
   `workspace_member:${workspaceId}:${userId}`;
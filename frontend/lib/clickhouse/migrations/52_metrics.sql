@@ -0,0 +1,82 @@
+-- OTLP metric data points. One row per data point; columns that don't apply to
+-- a metric kind keep their defaults (e.g. `value` is only set for gauges/sums,
+-- bucket columns only for histograms).
+CREATE TABLE IF NOT EXISTS default.metrics
+(
+    id UUID,
+    project_id UUID,
+    time DateTime64(9, 'UTC'),
+    start_time DateTime64(9, 'UTC'),
+    name LowCardinality(String),
+    description String DEFAULT '',
+    unit LowCardinality(String) DEFAULT '',
+    metric_type UInt8,
+    aggregation_temporality UInt8 DEFAULT 0,
+    is_monotonic Bool DEFAULT false,
+    value Float64 DEFAULT 0,
+    count UInt64 DEFAULT 0,
+    sum Float64 DEFAULT 0,
+    min Nullable(Float64),
+    max Nullable(Float64),
+    bucket_counts Array(UInt64),
+    explicit_bounds Array(Float64),
+    scale Int32 DEFAULT 0,
+    zero_count UInt64 DEFAULT 0,
+    positive_offset Int32 DEFAULT 0,
+    positive_bucket_counts Array(UInt64),
+    negative_offset Int32 DEFAULT 0,
+    negative_bucket_counts Array(UInt64),
+    quantiles Array(Float64),
+    quantile_values Array(Float64),
+    attributes String CODEC(ZSTD(3)),
+    resource_attributes String CODEC(ZSTD(3)),
+    scope_name String DEFAULT '',
+    scope_version String DEFAULT '',
+    flags UInt32 DEFAULT 0,
+    trace_id UUID,
+    span_id UUID,
+    size_bytes UInt64 DEFAULT 0
+)
+ENGINE = MergeTree()
+ORDER BY (project_id, name, time, id)
+SETTINGS index_granularity = 8192;
+
+CREATE VIEW IF NOT EXISTS default.metrics_v0 SQL SECURITY INVOKER AS
+SELECT
+    id,
+    project_id,
+    time,
+    start_time,
+    name,
+    description,
+    unit,
+    multiIf(
+        metric_type = 0, 'GAUGE',
+        metric_type = 1, 'SUM',
+        metric_type = 2, 'HISTOGRAM',
+        metric_type = 3, 'EXPONENTIAL_HISTOGRAM',
+        metric_type = 4, 'SUMMARY',
+        'UNKNOWN'
+    ) AS metric_type,
+    multiIf(
+        aggregation_temporality = 1, 'DELTA',
+        aggregation_temporality = 2, 'CUMULATIVE',
+        'UNSPECIFIED'
+    ) AS aggregation_temporality,
+    is_monotonic,
+    value,
+    count,
+    sum,
+    min,
+    max,
+    bucket_counts,
+    explicit_bounds,
+    quantiles,
+    quantile_values,
+    attributes,
+    resource_attributes,
+    scope_name,
+    trace_id,
+    span_id
+FROM metrics
+WHERE project_id={project_id:UUID};
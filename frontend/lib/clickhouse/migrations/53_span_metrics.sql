@@ -0,0 +1,137 @@
+-- Per-minute span rollups maintained by the app-server span processor.
+-- The processor pre-aggregates every ingest batch by (minute, model, provider,
+-- path, span_type, status, tags) and inserts into `span_metrics_ingest`, a Null
+-- table that stores nothing; `span_metrics_mv` turns those rows into aggregate
+-- states in `span_metrics`, where rows for the same minute and dimensions
+-- merge across batches.
+CREATE TABLE IF NOT EXISTS default.span_metrics_ingest
+(
+    project_id UUID,
+    minute DateTime('UTC'),
+    model String,
+    provider String,
+    path String,
+    span_type UInt8,
+    status String,
+    tags Array(String),
+    span_count UInt64,
+    input_tokens Int64,
+    output_tokens Int64,
+    total_tokens Int64,
+    input_cost Float64,
+    output_cost Float64,
+    total_cost Float64,
+    durations Array(Float64)
+)
+ENGINE = Null;
+
+CREATE TABLE IF NOT EXISTS default.span_metrics
+(
+    project_id UUID,
+    minute DateTime('UTC'),
+    model LowCardinality(String),
+    provider LowCardinality(String),
+    path String,
+    span_type UInt8,
+    status LowCardinality(String),
+    tags Array(String),
+    span_count SimpleAggregateFunction(sum, UInt64),
+    input_tokens SimpleAggregateFunction(sum, Int64),
+    output_tokens SimpleAggregateFunction(sum, Int64),
+    total_tokens SimpleAggregateFunction(sum, Int64),
+    input_cost SimpleAggregateFunction(sum, Float64),
+    output_cost SimpleAggregateFunction(sum, Float64),
+    total_cost SimpleAggregateFunction(sum, Float64),
+    duration_sum SimpleAggregateFunction(sum, Float64),
+    duration_min SimpleAggregateFunction(min, Float64),
+    duration_max SimpleAggregateFunction(max, Float64),
+    duration_quantiles AggregateFunction(quantilesTDigestArray(0.5, 0.9, 0.95, 0.99), Array(Float64))
+)
+ENGINE = AggregatingMergeTree()
+PARTITION BY toYYYYMM(minute)
+ORDER BY (project_id, minute, span_type, model, provider, status, path, tags)
+SETTINGS index_granularity = 8192;
+
+CREATE MATERIALIZED VIEW IF NOT EXISTS default.span_metrics_mv TO default.span_metrics AS
+SELECT
+    project_id,
+    minute,
+    model,
+    provider,
+    path,
+    span_type,
+    status,
+    tags,
+    sum(span_count) AS span_count,
+    sum(input_tokens) AS input_tokens,
+    sum(output_tokens) AS output_tokens,
+    sum(total_tokens) AS total_tokens,
+    sum(input_cost) AS input_cost,
+    sum(output_cost) AS output_cost,
+    sum(total_cost) AS total_cost,
+    sum(arraySum(durations)) AS duration_sum,
+    min(arrayMin(durations)) AS duration_min,
+    max(arrayMax(durations)) AS duration_max,
+    quantilesTDigestArrayState(0.5, 0.9, 0.95, 0.99)(durations) AS duration_quantiles
+FROM default.span_metrics_ingest
+GROUP BY project_id, minute, model, provider, path, span_type, status, tags;
+
+-- Only re-aggregatable columns are exposed: averages are duration_sum / span_count
+-- and percentiles come from merging the quantile states over the caller's range,
+-- e.g. quantilesTDigestArrayMerge(0.5, 0.9, 0.95, 0.99)(duration_quantiles).
+CREATE VIEW IF NOT EXISTS default.span_metrics_v0 SQL SECURITY INVOKER AS
+SELECT
+    project_id,
+    minute,
+    model,
+    provider,
+    path,
+    multiIf(
+        span_type = 0, 'DEFAULT',
+        span_type = 1, 'LLM',
+        span_type = 3, 'EXECUTOR',
+        span_type = 4, 'EVALUATOR',
+        span_type = 5, 'EVALUATION',
+        span_type = 6, 'TOOL',
+        span_type = 7, 'HUMAN_EVALUATOR',
+        span_type = 8, 'CACHED',
+        'UNKNOWN'
+    ) AS span_type,
+    status,
+    tags,
+    span_count,
+    input_tokens,
+    output_tokens,
+    total_tokens,
+    input_cost,
+    output_cost,
+    total_cost,
+    duration_sum,
+    duration_min AS min_duration,
+    duration_max AS max_duration,
+    duration_quantiles
+FROM (
+    SELECT
+        project_id,
+        minute,
+        model,
+        provider,
+        path,
+        span_type,
+        status,
+        tags,
+        sum(span_count) AS span_count,
+        sum(input_tokens) AS input_tokens,
+        sum(output_tokens) AS output_tokens,
+        sum(total_tokens) AS total_tokens,
+        sum(input_cost) AS input_cost,
+        sum(output_cost) AS output_cost,
+        sum(total_cost) AS total_cost,
+        sum(duration_sum) AS duration_sum,
+        min(duration_min) AS duration_min,
+        max(duration_max) AS duration_max,
+        quantilesTDigestArrayMergeState(0.5, 0.9, 0.95, 0.99)(duration_quantiles) AS duration_quantiles
+    FROM default.span_metrics
+    WHERE project_id={project_id:UUID}
+    GROUP BY project_id, minute, model, provider, path, span_type, status, tags
+);
@@ -0,0 +1,30 @@
+-- Immutable dataset versions. Creating a version copies the latest version of
+-- every datapoint in the dataset under a new `snapshot_id`; rows are never
+-- updated or deleted afterwards, so evaluations can keep reading the exact
+-- datapoint set they ran on while the dataset itself keeps changing.
+CREATE TABLE IF NOT EXISTS default.dataset_snapshot_datapoints
+(
+    `snapshot_id` UUID,
+    `id` UUID,
+    `dataset_id` UUID,
+    `project_id` UUID,
+    `created_at` DateTime64(9, 'UTC'),
+    `data` String CODEC(ZSTD(3)),
+    `target` String CODEC(ZSTD(3)),
+    `metadata` String CODEC(ZSTD(3))
+)
+ENGINE = MergeTree()
+ORDER BY (project_id, dataset_id, snapshot_id, toUInt128(id))
+SETTINGS index_granularity = 8192;
+
+CREATE VIEW IF NOT EXISTS dataset_snapshot_datapoints_v0 SQL SECURITY INVOKER AS
+    SELECT
+        snapshot_id,
+        id,
+        created_at,
+        dataset_id,
+        data,
+        target,
+        metadata
+    FROM dataset_snapshot_datapoints
+    WHERE project_id={project_id:UUID};
@@ -0,0 +1,92 @@
+-- OpenTelemetry span links: causal references to spans in the same or other
+-- traces (queue consumers, batch jobs, fan-in). `link_trace_ids` is derived
+-- so the incoming side of a link can be found without scanning `links`.
+ALTER TABLE spans
+    ADD COLUMN IF NOT EXISTS links Array(Tuple(trace_id UUID, span_id UUID, attributes String)) DEFAULT [] CODEC(ZSTD(3));
+
+ALTER TABLE spans
+    ADD COLUMN IF NOT EXISTS link_trace_ids Array(UUID) MATERIALIZED links.trace_id;
+
+ALTER TABLE spans ADD INDEX IF NOT EXISTS spans_link_trace_ids_bf_idx link_trace_ids TYPE bloom_filter;
+ALTER TABLE spans MATERIALIZE INDEX spans_link_trace_ids_bf_idx;
+
+DROP VIEW IF EXISTS spans_v0;
+CREATE VIEW IF NOT EXISTS spans_v0 SQL SECURITY INVOKER AS
+    SELECT
+        span_id,
+        name,
+        multiIf(
+            span_kind = 0, 'DEFAULT',
+            span_kind = 1, 'LLM',
+            span_kind = 3, 'EXECUTOR',
+            span_kind = 4, 'EVALUATOR',
+            span_kind = 5, 'EVALUATION',
+            span_kind = 6, 'TOOL',
+            span_kind = 7, 'HUMAN_EVALUATOR',
+            span_kind = 8, 'CACHED',
+            'UNKNOWN'
+        ) AS span_type,
+        start_time,
+        end_time,
+        end_time - start_time AS duration,
+        input_cost,
+        output_cost,
+        total_cost,
+        input_tokens,
+        output_tokens,
+        total_tokens,
+        request_model,
+        response_model,
+        model,
+        trace_id,
+        provider,
+        path,
+        if(
+            notEmpty(input_message_hashes),
+            '[' || arrayStringConcat(
+                arrayMap(
+                    h -> coalesce(
+                        dictGetOrNull('deduped_content_dict', 'content', tuple(project_id, h)),
+                        dictGetOrNull('llm_messages_dict', 'content', tuple(project_id, trace_id, h)),
+                        'null'
+                    ),
+                    input_message_hashes
+                ),
+                ','
+            ) || ']',
+            input
+        ) AS input,
+        if(
+            notEmpty(output_message_hashes),
+            '[' || arrayStringConcat(
+                arrayMap(
+                    h -> dictGetOrDefault(
+                        'deduped_content_dict',
+                        'content',
+                        tuple(project_id, h),
+                        'null'
+                    ),
+                    output_message_hashes
+                ),
+                ','
+            ) || ']',
+            output
+        ) AS output,
+        if(
+            tool_definitions_hash != toFixedString('', 32),
+            dictGetOrDefault(
+                'deduped_content_dict',
+                'content',
+                tuple(project_id, tool_definitions_hash),
+                ''
+            ),
+            ''
+        ) AS tool_definitions,
+        multiIf(status = 'error', 'error', status = 'success', 'success', 'success') AS status,
+        parent_span_id,
+        attributes,
+        tags_array AS tags,
+        events,
+        links
+    FROM spans
+    WHERE project_id = {project_id:UUID};
@@ -0,0 +1,56 @@
+-- Per-span log counts, so a span (and the trace around it) that logged
+-- warnings or errors can be flagged without scanning `logs`. Maintained by
+-- `span_log_counts_mv` on every insert into `logs`; rows for the same span
+-- are summed on merge. Exposed as its own logical table rather than on
+-- `spans`, so span queries that don't need the counts don't pay for them.
+CREATE TABLE IF NOT EXISTS default.span_log_counts
+(
+    project_id UUID,
+    trace_id UUID,
+    span_id UUID,
+    log_count UInt64,
+    warning_log_count UInt64,
+    error_log_count UInt64
+)
+ENGINE = SummingMergeTree()
+ORDER BY (project_id, trace_id, span_id)
+SETTINGS index_granularity = 8192;
+
+-- Backfill existing logs before creating the MV, so no log is counted by both.
+-- Logs ingested while the backfill runs are not counted.
+INSERT INTO default.span_log_counts
+SELECT
+    project_id,
+    trace_id,
+    span_id,
+    count() AS log_count,
+    countIf(severity_number BETWEEN 13 AND 16) AS warning_log_count,
+    countIf(severity_number >= 17) AS error_log_count
+FROM default.logs
+WHERE span_id != toUUID('00000000-0000-0000-0000-000000000000')
+GROUP BY project_id, trace_id, span_id;
+
+-- Severity numbers per the OTel logs data model: 13-16 WARN, 17-24 ERROR and FATAL.
+CREATE MATERIALIZED VIEW IF NOT EXISTS default.span_log_counts_mv TO default.span_log_counts AS
+SELECT
+    project_id,
+    trace_id,
+    span_id,
+    count() AS log_count,
+    countIf(severity_number BETWEEN 13 AND 16) AS warning_log_count,
+    countIf(severity_number >= 17) AS error_log_count
+FROM default.logs
+WHERE span_id != toUUID('00000000-0000-0000-0000-000000000000')
+GROUP BY project_id, trace_id, span_id;
+
+-- Rows for one span may not be merged yet, so sum them here.
+CREATE VIEW IF NOT EXISTS span_log_counts_v0 SQL SECURITY INVOKER AS
+    SELECT
+        trace_id,
+        span_id,
+        sum(log_count) AS log_count,
+        sum(warning_log_count) AS warning_log_count,
+        sum(error_log_count) AS error_log_count
+    FROM span_log_counts
+    WHERE project_id = {project_id:UUID}
+    GROUP BY trace_id, span_id;
@@ -0,0 +1 @@
+ALTER TABLE "traces" ADD COLUMN "sampling_decision" jsonb;
\ No newline at end of file
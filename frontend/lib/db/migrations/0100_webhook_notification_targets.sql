@@ -0,0 +1,21 @@
+ALTER TABLE "alert_targets" ADD COLUMN "url" text;--> statement-breakpoint
+ALTER TABLE "alert_targets" ADD COLUMN "signing_secret" text;--> statement-breakpoint
+ALTER TABLE "alert_targets" ADD COLUMN "signing_secret_nonce" text;--> statement-breakpoint
+ALTER TABLE "report_targets" ADD COLUMN "url" text;--> statement-breakpoint
+ALTER TABLE "report_targets" ADD COLUMN "signing_secret" text;--> statement-breakpoint
+ALTER TABLE "report_targets" ADD COLUMN "signing_secret_nonce" text;--> statement-breakpoint
+CREATE TABLE "notification_dead_letters" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"workspace_id" uuid NOT NULL,
+	"project_id" uuid,
+	"target_id" uuid NOT NULL,
+	"target_type" text NOT NULL,
+	"url" text NOT NULL,
+	"payload" jsonb NOT NULL,
+	"error" text NOT NULL,
+	"attempts" integer NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "notification_dead_letters" ADD CONSTRAINT "notification_dead_letters_workspace_id_fkey" FOREIGN KEY ("workspace_id") REFERENCES "workspaces"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+CREATE INDEX "notification_dead_letters_workspace_created_at_idx" ON "notification_dead_letters" USING btree ("workspace_id","created_at");
\ No newline at end of file
@@ -0,0 +1,12 @@
+CREATE TABLE "metric_alert_states" (
+	"alert_id" uuid PRIMARY KEY NOT NULL,
+	"project_id" uuid NOT NULL,
+	"firing" boolean DEFAULT false NOT NULL,
+	"last_value" double precision,
+	"last_evaluated_at" timestamp with time zone,
+	"last_notified_at" timestamp with time zone,
+	"snoozed_until" timestamp with time zone
+);
+--> statement-breakpoint
+ALTER TABLE "metric_alert_states" ADD CONSTRAINT "metric_alert_states_alert_id_fkey" FOREIGN KEY ("alert_id") REFERENCES "alerts"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "metric_alert_states" ADD CONSTRAINT "metric_alert_states_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;
\ No newline at end of file
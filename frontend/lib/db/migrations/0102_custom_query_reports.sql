@@ -0,0 +1,4 @@
+ALTER TABLE "reports" ADD COLUMN "project_id" uuid;--> statement-breakpoint
+ALTER TABLE "reports" ADD COLUMN "name" text;--> statement-breakpoint
+ALTER TABLE "reports" ADD COLUMN "metadata" jsonb DEFAULT '{}'::jsonb NOT NULL;--> statement-breakpoint
+ALTER TABLE "reports" ADD CONSTRAINT "reports_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;
\ No newline at end of file
@@ -0,0 +1,14 @@
+CREATE TABLE "dataset_versions" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"dataset_id" uuid NOT NULL,
+	"project_id" uuid NOT NULL,
+	"name" text,
+	"datapoint_count" bigint DEFAULT 0 NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "evaluations" ADD COLUMN "dataset_version_id" uuid;--> statement-breakpoint
+ALTER TABLE "dataset_versions" ADD CONSTRAINT "dataset_versions_dataset_id_fkey" FOREIGN KEY ("dataset_id") REFERENCES "datasets"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+ALTER TABLE "dataset_versions" ADD CONSTRAINT "dataset_versions_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+CREATE INDEX "dataset_versions_dataset_id_idx" ON "dataset_versions" USING btree ("dataset_id","created_at");--> statement-breakpoint
+ALTER TABLE "evaluations" ADD CONSTRAINT "evaluations_dataset_version_id_fkey" FOREIGN KEY ("dataset_version_id") REFERENCES "dataset_versions"("id") ON DELETE set null ON UPDATE no action;
\ No newline at end of file
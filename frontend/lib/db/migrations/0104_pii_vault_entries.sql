@@ -0,0 +1,12 @@
+CREATE TABLE "pii_vault_entries" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"project_id" uuid NOT NULL,
+	"pseudonym" text NOT NULL,
+	"label" text NOT NULL,
+	"nonce" text NOT NULL,
+	"value" text NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "pii_vault_entries" ADD CONSTRAINT "pii_vault_entries_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+CREATE UNIQUE INDEX "pii_vault_entries_project_id_pseudonym_idx" ON "pii_vault_entries" USING btree ("project_id","pseudonym");
\ No newline at end of file
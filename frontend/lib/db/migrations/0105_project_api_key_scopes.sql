@@ -0,0 +1,3 @@
+ALTER TABLE "project_api_keys" ADD COLUMN "scopes" text[] DEFAULT '{}'::text[] NOT NULL;--> statement-breakpoint
+ALTER TABLE "project_api_keys" ADD COLUMN "allowed_cidrs" text[] DEFAULT '{}'::text[] NOT NULL;--> statement-breakpoint
+ALTER TABLE "project_api_keys" ADD COLUMN "rate_limit_per_minute" integer;
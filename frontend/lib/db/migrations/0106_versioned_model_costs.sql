@@ -0,0 +1,17 @@
+CREATE TABLE "workspace_pricing_discounts" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"workspace_id" uuid NOT NULL,
+	"provider" text DEFAULT '' NOT NULL,
+	"multiplier" double precision NOT NULL,
+	"effective_from" timestamp with time zone DEFAULT '1970-01-01 00:00:00+00' NOT NULL,
+	CONSTRAINT "workspace_pricing_discounts_workspace_id_provider_effective_from_unique" UNIQUE("workspace_id","provider","effective_from")
+);
+--> statement-breakpoint
+ALTER TABLE "custom_model_costs" DROP CONSTRAINT "custom_model_costs_project_id_provider_model_unique";--> statement-breakpoint
+ALTER TABLE "model_costs" DROP CONSTRAINT "model_costs_model_unique";--> statement-breakpoint
+ALTER TABLE "custom_model_costs" ADD COLUMN "effective_from" timestamp with time zone DEFAULT '1970-01-01 00:00:00+00' NOT NULL;--> statement-breakpoint
+ALTER TABLE "model_costs" ADD COLUMN "effective_from" timestamp with time zone DEFAULT '1970-01-01 00:00:00+00' NOT NULL;--> statement-breakpoint
+ALTER TABLE "workspace_pricing_discounts" ADD CONSTRAINT "workspace_pricing_discounts_workspace_id_fkey" FOREIGN KEY ("workspace_id") REFERENCES "workspaces"("id") ON DELETE cascade ON UPDATE cascade;--> statement-breakpoint
+ALTER TABLE "custom_model_costs" ADD CONSTRAINT "custom_model_costs_project_id_provider_model_effective_from_unique" UNIQUE("project_id","provider","model","effective_from");--> statement-breakpoint
+ALTER TABLE "model_costs" ADD CONSTRAINT "model_costs_model_effective_from_unique" UNIQUE("model","effective_from");
@@ -0,0 +1,18 @@
+CREATE TABLE "cost_backfill_jobs" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"project_id" uuid NOT NULL,
+	"start_time" timestamp with time zone NOT NULL,
+	"end_time" timestamp with time zone NOT NULL,
+	"model" text,
+	"status" text DEFAULT 'running' NOT NULL,
+	"cursor_start_time" timestamp with time zone,
+	"cursor_span_id" uuid,
+	"processed_spans" bigint DEFAULT 0 NOT NULL,
+	"updated_spans" bigint DEFAULT 0 NOT NULL,
+	"error" text,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"updated_at" timestamp with time zone DEFAULT now() NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "cost_backfill_jobs" ADD CONSTRAINT "cost_backfill_jobs_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+CREATE INDEX "cost_backfill_jobs_project_id_idx" ON "cost_backfill_jobs" USING btree ("project_id" uuid_ops);
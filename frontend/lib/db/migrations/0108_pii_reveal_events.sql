@@ -0,0 +1,11 @@
+CREATE TABLE "pii_reveal_events" (
+	"id" uuid PRIMARY KEY DEFAULT gen_random_uuid() NOT NULL,
+	"created_at" timestamp with time zone DEFAULT now() NOT NULL,
+	"project_id" uuid NOT NULL,
+	"user_id" uuid NOT NULL,
+	"pseudonyms" text[] NOT NULL,
+	"revealed_count" integer NOT NULL
+);
+--> statement-breakpoint
+ALTER TABLE "pii_reveal_events" ADD CONSTRAINT "pii_reveal_events_project_id_fkey" FOREIGN KEY ("project_id") REFERENCES "projects"("id") ON DELETE cascade ON UPDATE no action;--> statement-breakpoint
+CREATE INDEX "pii_reveal_events_project_id_created_at_idx" ON "pii_reveal_events" USING btree ("project_id","created_at");
\ No newline at end of file
@@ -263,8 +263,11 @@ export const modelCosts = pgTable(
     updatedAt: timestamp("updated_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
     model: text().notNull(),
     costs: jsonb().default({}).notNull(),
+    effectiveFrom: timestamp("effective_from", { withTimezone: true, mode: "string" })
+      .default(sql`'1970-01-01 00:00:00+00'`)
+      .notNull(),
   },
-  (table) => [unique("model_costs_model_unique").on(table.model)]
+  (table) => [unique("model_costs_model_effective_from_unique").on(table.model, table.effectiveFrom)]
 );
 
 export const agents = pgTable(
@@ -594,6 +597,9 @@ export const customModelCosts = pgTable(
     provider: text().default("").notNull(),
     model: text().notNull(),
     costs: jsonb().default({}).notNull(),
+    effectiveFrom: timestamp("effective_from", { withTimezone: true, mode: "string" })
+      .default(sql`'1970-01-01 00:00:00+00'`)
+      .notNull(),
   },
   (table) => [
     foreignKey({
@@ -603,7 +609,12 @@ export const customModelCosts = pgTable(
     })
       .onUpdate("cascade")
       .onDelete("cascade"),
-    unique("custom_model_costs_project_id_provider_model_unique").on(table.projectId, table.provider, table.model),
+    unique("custom_model_costs_project_id_provider_model_effective_from_unique").on(
+      table.projectId,
+      table.provider,
+      table.model,
+      table.effectiveFrom
+    ),
   ]
 );
 
@@ -616,6 +627,11 @@ export const reports = pgTable(
     weekdays: integer().array().notNull(),
     hour: integer().notNull(),
     createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    // CUSTOM_QUERY reports: the project the saved query runs against, a display name,
+    // and the query with its rendering options.
+    projectId: uuid("project_id"),
+    name: text(),
+    metadata: jsonb().default({}).notNull(),
   },
   (table) => [
     foreignKey({
@@ -623,6 +639,11 @@ export const reports = pgTable(
       foreignColumns: [workspaces.id],
       name: "reports_workspace_id_fkey",
     }).onDelete("cascade"),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "reports_project_id_fkey",
+    }).onDelete("cascade"),
   ]
 );
 
@@ -637,6 +658,11 @@ export const reportTargets = pgTable(
     channelId: text("channel_id"),
     channelName: text("channel_name"),
     email: text(),
+    // WEBHOOK / TEAMS / DISCORD targets post to this URL.
+    url: text(),
+    // WEBHOOK only: HMAC-SHA256 signing secret, AEAD-encrypted with the workspace id as associated data.
+    signingSecret: text("signing_secret"),
+    signingSecretNonce: text("signing_secret_nonce"),
     createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
   },
   (table) => [
@@ -682,6 +708,7 @@ export const evaluations = pgTable(
     name: text().notNull(),
     groupId: text("group_id").default("default").notNull(),
     metadata: jsonb(),
+    datasetVersionId: uuid("dataset_version_id"),
   },
   (table) => [
     index("evaluations_project_id_hash_idx").using("hash", table.projectId.asc().nullsLast().op("uuid_ops")),
@@ -692,6 +719,11 @@ export const evaluations = pgTable(
     })
       .onUpdate("cascade")
       .onDelete("cascade"),
+    foreignKey({
+      columns: [table.datasetVersionId],
+      foreignColumns: [datasetVersions.id],
+      name: "evaluations_dataset_version_id_fkey",
+    }).onDelete("set null"),
     pgPolicy("select_by_next_api_key", {
       as: "permissive",
       for: "select",
@@ -754,6 +786,9 @@ export const projectApiKeys = pgTable(
     isIngestOnly: boolean("is_ingest_only").default(false).notNull(),
     userId: uuid("user_id"),
     expiresAt: timestamp("expires_at", { withTimezone: true, mode: "string" }),
+    scopes: text().array().default(sql`'{}'::text[]`).notNull(),
+    allowedCidrs: text("allowed_cidrs").array().default(sql`'{}'::text[]`).notNull(),
+    rateLimitPerMinute: integer("rate_limit_per_minute"),
   },
   (table) => [
     index("project_api_keys_hash_idx").using("hash", table.hash.asc().nullsLast().op("text_ops")),
@@ -783,6 +818,11 @@ export const alertTargets = pgTable(
     channelId: text("channel_id"),
     channelName: text("channel_name"),
     email: text(),
+    // WEBHOOK / TEAMS / DISCORD targets post to this URL.
+    url: text(),
+    // WEBHOOK only: HMAC-SHA256 signing secret, AEAD-encrypted with the workspace id as associated data.
+    signingSecret: text("signing_secret"),
+    signingSecretNonce: text("signing_secret_nonce"),
     createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
   },
   (table) => [
@@ -1454,6 +1494,7 @@ export const traces = pgTable(
     cacheCreationInputTokens: bigint("cache_creation_input_tokens", { mode: "number" }),
     // You can use { mode: "bigint" } if numbers are exceeding js number limitations
     reasoningTokens: bigint("reasoning_tokens", { mode: "number" }),
+    samplingDecision: jsonb("sampling_decision"),
   },
   (table) => [
     index("traces_project_id_idx").using("btree", table.projectId.asc().nullsLast().op("uuid_ops")),
@@ -1473,3 +1514,194 @@ export const traces = pgTable(
     }),
   ]
 );
+
+export const notificationDeadLetters = pgTable(
+  "notification_dead_letters",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    workspaceId: uuid("workspace_id").notNull(),
+    projectId: uuid("project_id"),
+    targetId: uuid("target_id").notNull(),
+    targetType: text("target_type").notNull(),
+    url: text().notNull(),
+    // The exact request body that failed to deliver, so it can be replayed.
+    payload: jsonb().notNull(),
+    error: text().notNull(),
+    attempts: integer().notNull(),
+  },
+  (table) => [
+    foreignKey({
+      columns: [table.workspaceId],
+      foreignColumns: [workspaces.id],
+      name: "notification_dead_letters_workspace_id_fkey",
+    }).onDelete("cascade"),
+    index("notification_dead_letters_workspace_created_at_idx").using(
+      "btree",
+      table.workspaceId.asc().nullsLast().op("uuid_ops"),
+      table.createdAt.asc().nullsLast().op("timestamptz_ops")
+    ),
+  ]
+);
+
+export const metricAlertStates = pgTable(
+  "metric_alert_states",
+  {
+    alertId: uuid("alert_id").primaryKey().notNull(),
+    projectId: uuid("project_id").notNull(),
+    // True while the threshold is breached and the breach has been notified.
+    firing: boolean().default(false).notNull(),
+    lastValue: doublePrecision("last_value"),
+    lastEvaluatedAt: timestamp("last_evaluated_at", { withTimezone: true, mode: "string" }),
+    lastNotifiedAt: timestamp("last_notified_at", { withTimezone: true, mode: "string" }),
+    snoozedUntil: timestamp("snoozed_until", { withTimezone: true, mode: "string" }),
+  },
+  (table) => [
+    foreignKey({
+      columns: [table.alertId],
+      foreignColumns: [alerts.id],
+      name: "metric_alert_states_alert_id_fkey",
+    }).onDelete("cascade"),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "metric_alert_states_project_id_fkey",
+    }).onDelete("cascade"),
+  ]
+);
+
+export const datasetVersions = pgTable(
+  "dataset_versions",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    datasetId: uuid("dataset_id").notNull(),
+    projectId: uuid("project_id").notNull(),
+    name: text(),
+    // You can use { mode: "bigint" } if numbers are exceeding js number limitations
+    datapointCount: bigint("datapoint_count", { mode: "number" }).default(0).notNull(),
+  },
+  (table) => [
+    index("dataset_versions_dataset_id_idx").using(
+      "btree",
+      table.datasetId.asc().nullsLast().op("uuid_ops"),
+      table.createdAt.asc().nullsLast().op("timestamptz_ops")
+    ),
+    foreignKey({
+      columns: [table.datasetId],
+      foreignColumns: [datasets.id],
+      name: "dataset_versions_dataset_id_fkey",
+    }).onDelete("cascade"),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "dataset_versions_project_id_fkey",
+    }).onDelete("cascade"),
+  ]
+);
+
+export const piiVaultEntries = pgTable(
+  "pii_vault_entries",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    projectId: uuid("project_id").notNull(),
+    pseudonym: text().notNull(),
+    label: text().notNull(),
+    nonce: text().notNull(),
+    value: text().notNull(),
+  },
+  (table) => [
+    uniqueIndex("pii_vault_entries_project_id_pseudonym_idx").using(
+      "btree",
+      table.projectId.asc().nullsLast().op("uuid_ops"),
+      table.pseudonym.asc().nullsLast().op("text_ops")
+    ),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "pii_vault_entries_project_id_fkey",
+    }).onDelete("cascade"),
+  ]
+);
+
+export const piiRevealEvents = pgTable(
+  "pii_reveal_events",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    projectId: uuid("project_id").notNull(),
+    userId: uuid("user_id").notNull(),
+    pseudonyms: text().array().notNull(),
+    revealedCount: integer("revealed_count").notNull(),
+  },
+  (table) => [
+    index("pii_reveal_events_project_id_created_at_idx").using(
+      "btree",
+      table.projectId.asc().nullsLast().op("uuid_ops"),
+      table.createdAt.asc().nullsLast().op("timestamptz_ops")
+    ),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "pii_reveal_events_project_id_fkey",
+    }).onDelete("cascade"),
+  ]
+);
+
+export const workspacePricingDiscounts = pgTable(
+  "workspace_pricing_discounts",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    workspaceId: uuid("workspace_id").notNull(),
+    provider: text().default("").notNull(),
+    multiplier: doublePrecision().notNull(),
+    effectiveFrom: timestamp("effective_from", { withTimezone: true, mode: "string" })
+      .default(sql`'1970-01-01 00:00:00+00'`)
+      .notNull(),
+  },
+  (table) => [
+    foreignKey({
+      columns: [table.workspaceId],
+      foreignColumns: [workspaces.id],
+      name: "workspace_pricing_discounts_workspace_id_fkey",
+    })
+      .onUpdate("cascade")
+      .onDelete("cascade"),
+    unique("workspace_pricing_discounts_workspace_id_provider_effective_from_unique").on(
+      table.workspaceId,
+      table.provider,
+      table.effectiveFrom
+    ),
+  ]
+);
+
+export const costBackfillJobs = pgTable(
+  "cost_backfill_jobs",
+  {
+    id: uuid().defaultRandom().primaryKey().notNull(),
+    projectId: uuid("project_id").notNull(),
+    startTime: timestamp("start_time", { withTimezone: true, mode: "string" }).notNull(),
+    endTime: timestamp("end_time", { withTimezone: true, mode: "string" }).notNull(),
+    model: text(),
+    status: text().default("running").notNull(),
+    cursorStartTime: timestamp("cursor_start_time", { withTimezone: true, mode: "string" }),
+    cursorSpanId: uuid("cursor_span_id"),
+    // You can use { mode: "bigint" } if numbers are exceeding js number limitations
+    processedSpans: bigint("processed_spans", { mode: "number" }).default(0).notNull(),
+    // You can use { mode: "bigint" } if numbers are exceeding js number limitations
+    updatedSpans: bigint("updated_spans", { mode: "number" }).default(0).notNull(),
+    error: text(),
+    createdAt: timestamp("created_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+    updatedAt: timestamp("updated_at", { withTimezone: true, mode: "string" }).defaultNow().notNull(),
+  },
+  (table) => [
+    index("cost_backfill_jobs_project_id_idx").using("btree", table.projectId.asc().nullsLast().op("uuid_ops")),
+    foreignKey({
+      columns: [table.projectId],
+      foreignColumns: [projects.id],
+      name: "cost_backfill_jobs_project_id_fkey",
+    }).onDelete("cascade"),
+  ]
+);
@@ -12,6 +12,7 @@ const VIEW_BACKING_TABLES: Record<string, string> = {
   traces_v0: "traces_replacing",
   dataset_datapoints_v0: "dataset_datapoints",
   dataset_datapoint_versions_v0: "dataset_datapoints",
+  dataset_snapshot_datapoints_v0: "dataset_snapshot_datapoints",
   evaluation_datapoints_v0: "evaluation_datapoints",
   signal_runs_v0: "signal_runs",
   signal_events_v0: "signal_events",
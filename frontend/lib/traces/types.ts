@@ -38,6 +38,18 @@ export type RealtimeSpan = {
   createdAt: string;
 };
 
+export type RealtimeLog = {
+  logId: string;
+  traceId: string;
+  spanId?: string;
+  time: string;
+  severityNumber: number;
+  severityText: string;
+  body: any;
+  attributes: Record<string, any>;
+  eventName: string;
+};
+
 export type Span = {
   spanId: string;
   parentSpanId?: string;
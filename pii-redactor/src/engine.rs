@@ -74,6 +74,9 @@ pub struct Span {
     pub start: usize,
     pub end: usize,
     pub label: String,
+    /// Model confidence: mean softmax probability of the span's tokens'
+    /// predicted labels. Always 1.0 for deterministic rule matches.
+    pub score: f32,
 }
 
 /// One tokenised window, ready for the batcher. The `encoding` keeps the
@@ -86,17 +89,20 @@ pub struct TokenizedWindow {
 }
 
 /// Internal batcher job: one window awaiting inference. The oneshot reply
-/// carries the per-token argmax labels for that window's row of the
-/// `(B, L, num_labels)` logits tensor — argmax happens inside the batcher
-/// task so the post-process (BIOES decode + char-offset mapping) doesn't
-/// hold up the GEMM thread.
+/// carries the per-token argmax labels (with their softmax probability) for
+/// that window's row of the `(B, L, num_labels)` logits tensor — argmax
+/// happens inside the batcher task so the post-process (BIOES decode +
+/// char-offset mapping) doesn't hold up the GEMM thread.
 struct Job {
     ids: Vec<u32>,
     mask: Vec<u32>,
     types: Vec<u32>,
-    reply: oneshot::Sender<Result<Vec<u32>>>,
+    reply: oneshot::Sender<Result<Vec<TokenLabel>>>,
 }
 
+/// Argmax label id of one token and its softmax probability.
+type TokenLabel = (u32, f32);
+
 /// Bound the Job channel at this multiple of `max_batch_size`. Sustained
 /// overload should backpressure gRPC handlers (their `send().await` parks)
 /// rather than queue unbounded memory. 8 leaves room for a few batches in
@@ -380,6 +386,7 @@ impl Engine {
                     start: s.start + win.text_byte_offset,
                     end: s.end + win.text_byte_offset,
                     label: s.label,
+                    score: s.score,
                 });
             }
         }
@@ -585,7 +592,7 @@ fn run_batch(session: &mut Session, needs_token_type_ids: bool, jobs: Vec<Job>)
         }
     }
 
-    let outputs = (|| -> Result<Vec<Vec<u32>>> {
+    let outputs = (|| -> Result<Vec<Vec<TokenLabel>>> {
         let input_ids_t = TensorRef::from_array_view(&input_ids)?;
         let mask_t = TensorRef::from_array_view(&attention_mask)?;
         let token_type_t = TensorRef::from_array_view(&token_type_ids)?;
@@ -682,7 +689,10 @@ fn clamp_char_boundary(text: &str, pos: usize, expand_right: bool) -> usize {
     }
 }
 
-fn argmax_per_token(row: ArrayView<f32, ndarray::Ix2>) -> Vec<u32> {
+/// Argmax over each token's logits, plus the softmax probability of the
+/// winning label (`1 / sum(exp(v - max))`), which feeds per-label
+/// confidence thresholds.
+fn argmax_per_token(row: ArrayView<f32, ndarray::Ix2>) -> Vec<TokenLabel> {
     row.outer_iter()
         .map(|tok| {
             let mut best = 0usize;
@@ -693,7 +703,13 @@ fn argmax_per_token(row: ArrayView<f32, ndarray::Ix2>) -> Vec<u32> {
                     best = i;
                 }
             }
-            best as u32
+            let partition: f32 = tok.iter().map(|v| (v - best_v).exp()).sum();
+            let prob = if partition.is_finite() && partition > 0.0 {
+                1.0 / partition
+            } else {
+                0.0
+            };
+            (best as u32, prob)
         })
         .collect()
 }
@@ -703,13 +719,36 @@ struct LabelSpan {
     start_token: usize,
     end_token: usize,
     label: String,
+    /// Sum of the member tokens' probabilities; divided by the token count
+    /// for the span's score.
+    score_sum: f32,
+}
+
+impl LabelSpan {
+    fn new(token: usize, label: &str, prob: f32) -> Self {
+        Self {
+            start_token: token,
+            end_token: token + 1,
+            label: label.to_string(),
+            score_sum: prob,
+        }
+    }
+
+    fn extend(&mut self, token: usize, prob: f32) {
+        self.end_token = token + 1;
+        self.score_sum += prob;
+    }
+
+    fn score(&self) -> f32 {
+        self.score_sum / (self.end_token - self.start_token) as f32
+    }
 }
 
-fn bioes_spans(token_labels: &[u32], labels: &LabelMap) -> Vec<LabelSpan> {
+fn bioes_spans(token_labels: &[TokenLabel], labels: &LabelMap) -> Vec<LabelSpan> {
     let mut out = Vec::new();
     let mut current: Option<LabelSpan> = None;
-    for (i, id) in token_labels.iter().enumerate() {
-        let raw = labels.lookup(*id);
+    for (i, &(id, prob)) in token_labels.iter().enumerate() {
+        let raw = labels.lookup(id);
         let (prefix, base) = split_bioes(raw);
         match prefix {
             "O" => {
@@ -721,52 +760,32 @@ fn bioes_spans(token_labels: &[u32], labels: &LabelMap) -> Vec<LabelSpan> {
                 if let Some(s) = current.take() {
                     out.push(s);
                 }
-                current = Some(LabelSpan {
-                    start_token: i,
-                    end_token: i + 1,
-                    label: base.to_string(),
-                });
+                current = Some(LabelSpan::new(i, base, prob));
             }
             "S" => {
                 if let Some(s) = current.take() {
                     out.push(s);
                 }
-                out.push(LabelSpan {
-                    start_token: i,
-                    end_token: i + 1,
-                    label: base.to_string(),
-                });
+                out.push(LabelSpan::new(i, base, prob));
             }
             "E" => match current.take() {
                 Some(mut s) if s.label == base => {
-                    s.end_token = i + 1;
+                    s.extend(i, prob);
                     out.push(s);
                 }
                 Some(s) => {
                     out.push(s);
-                    out.push(LabelSpan {
-                        start_token: i,
-                        end_token: i + 1,
-                        label: base.to_string(),
-                    });
+                    out.push(LabelSpan::new(i, base, prob));
                 }
-                None => out.push(LabelSpan {
-                    start_token: i,
-                    end_token: i + 1,
-                    label: base.to_string(),
-                }),
+                None => out.push(LabelSpan::new(i, base, prob)),
             },
             _ => match current.as_mut() {
-                Some(s) if s.label == base => s.end_token = i + 1,
+                Some(s) if s.label == base => s.extend(i, prob),
                 _ => {
                     if let Some(s) = current.take() {
                         out.push(s);
                     }
-                    current = Some(LabelSpan {
-                        start_token: i,
-                        end_token: i + 1,
-                        label: base.to_string(),
-                    });
+                    current = Some(LabelSpan::new(i, base, prob));
                 }
             },
         }
@@ -825,6 +844,7 @@ fn map_to_char_spans(encoding: &Encoding, spans: &[LabelSpan]) -> Vec<Span> {
                     start: a,
                     end: b,
                     label: s.label.clone(),
+                    score: s.score(),
                 });
             }
         }
@@ -833,7 +853,9 @@ fn map_to_char_spans(encoding: &Encoding, spans: &[LabelSpan]) -> Vec<Span> {
 }
 
 /// Sort + merge same-label adjacent/overlapping spans. Different-label
-/// overlaps are kept independent (callers can decide what to do).
+/// overlaps are kept independent (callers can decide what to do). A merged
+/// span keeps the highest score of its parts: a confident detection in one
+/// window shouldn't be diluted by a weaker one in the overlapping window.
 pub fn merge_spans(mut spans: Vec<Span>) -> Vec<Span> {
     if spans.len() <= 1 {
         return spans;
@@ -846,6 +868,7 @@ pub fn merge_spans(mut spans: Vec<Span>) -> Vec<Span> {
                 if s.end > prev.end {
                     prev.end = s.end;
                 }
+                prev.score = prev.score.max(s.score);
             }
             _ => out.push(s),
         }
@@ -1,7 +1,10 @@
 mod engine;
 mod json_walker;
 mod labels;
+mod policy;
 mod proto;
+mod pseudonym;
+mod rules;
 
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -14,10 +17,19 @@ use tonic::{Request, Response, Status};
 use tracing::{error, info};
 use tracing_subscriber::EnvFilter;
 
-use crate::engine::{Engine, EngineConfig};
-use crate::json_walker::{apply_spans_and_serialize, build_skip_keys, walk_and_render};
+use crate::engine::{Engine, EngineConfig, Span};
+use crate::json_walker::{
+    Detector, FieldFilter, Replacement, WalkedJson, apply_spans_and_serialize, build_skip_keys,
+    detect_leaf_spans, walk_and_render,
+};
+use crate::policy::{LabelPolicy, parse_field_paths};
 use crate::proto::pii_redactor_service_server::{PiiRedactorService, PiiRedactorServiceServer};
-use crate::proto::{RedactRequest, RedactResponse};
+use crate::proto::{
+    DetectResponse, Detector as ProtoDetector, Finding, RedactRequest, RedactResponse,
+    RedactionMode, TextFindings,
+};
+use crate::pseudonym::{DEFAULT_PSEUDONYM_FORMAT, Pseudonymizer};
+use crate::rules::RuleSet;
 
 const DEFAULT_PLACEHOLDER: &str = "[REDACTED_{LABEL}]";
 
@@ -77,12 +89,17 @@ struct GrpcServer {
     max_tokens_per_text: usize,
 }
 
-#[tonic::async_trait]
-impl PiiRedactorService for GrpcServer {
-    async fn redact(
-        &self,
-        request: Request<RedactRequest>,
-    ) -> Result<Response<RedactResponse>, Status> {
+/// Stages 1-2 of a request, shared by `Redact` and `Detect`: every text
+/// walked and rendered, with the model spans the label policy kept.
+struct Detected {
+    walked: Vec<WalkedJson>,
+    spans: Vec<Vec<Span>>,
+    rules: RuleSet,
+}
+
+impl GrpcServer {
+    /// Cheap up-front checks, before anything is parsed.
+    fn check_request(&self, req: &RedactRequest) -> Result<(), Status> {
         // Fast-fail if the batcher thread has died. Without this check,
         // every text would queue up on a closed channel and surface as a
         // generic INTERNAL error; UNAVAILABLE tells the gRPC client this
@@ -93,7 +110,6 @@ impl PiiRedactorService for GrpcServer {
                 "pii-redactor batcher is unhealthy; pod must be restarted",
             ));
         }
-        let req = request.into_inner();
         if req.texts.len() > self.max_texts_per_request {
             return Err(Status::resource_exhausted(format!(
                 "texts length {} exceeds PII_MAX_TEXTS_PER_REQUEST ({})",
@@ -101,12 +117,30 @@ impl PiiRedactorService for GrpcServer {
                 self.max_texts_per_request
             )));
         }
-        let placeholder = req
-            .placeholder_format
-            .filter(|s| !s.is_empty())
-            .unwrap_or_else(|| DEFAULT_PLACEHOLDER.to_string());
+        Ok(())
+    }
 
-        let skip_keys = build_skip_keys(&req.skip_keys);
+    async fn run_detection(&self, req: RedactRequest) -> Result<Detected, Status> {
+        let (skip_paths, redact_paths) = parse_field_paths(&req.skip_paths, &req.redact_paths)
+            .map_err(|e| Status::invalid_argument(format!("{e:#}")))?;
+        let filter = FieldFilter {
+            skip_keys: build_skip_keys(&req.skip_keys),
+            skip_paths,
+            redact_paths,
+        };
+        let policy = LabelPolicy::from_request(
+            &req.labels,
+            &req.min_confidence,
+            req.default_min_confidence,
+        )
+        .map_err(|e| Status::invalid_argument(format!("{e:#}")))?;
+        let mut rules = RuleSet::from_request(
+            &req.rule_detectors,
+            req.disable_rule_detectors,
+            &req.dictionaries,
+        )
+        .map_err(|e| Status::invalid_argument(format!("{e:#}")))?;
+        rules.retain_labels(&policy);
 
         // Stage 1: parse + walk + render each input. Reject any text whose
         // rendered tokenisation exceeds the per-text cap so we never commit
@@ -114,7 +148,7 @@ impl PiiRedactorService for GrpcServer {
         let mut walked = Vec::with_capacity(req.texts.len());
         let mut rendered = Vec::with_capacity(req.texts.len());
         for (i, text) in req.texts.into_iter().enumerate() {
-            let w = walk_and_render(&text, &skip_keys).map_err(|e| {
+            let w = walk_and_render(&text, &filter).map_err(|e| {
                 Status::invalid_argument(format!("texts[{i}]: {e:#}"))
             })?;
             let tokens = self.engine.count_tokens(&w.rendered).map_err(|e| {
@@ -133,22 +167,114 @@ impl PiiRedactorService for GrpcServer {
         // Stage 2: detect spans. The engine's batcher coalesces every
         // window of every text in this RPC AND any windows from concurrent
         // RPCs into shared `(B, L)` forward passes — no chunking knob here.
+        // Model spans the label policy rejects are dropped here.
         let engine = self.engine.clone();
-        let all_spans = engine
+        let mut spans = engine
             .detect_spans_batch(rendered)
             .await
             .map_err(|e| Status::internal(format!("detect failed: {e:#}")))?;
+        for text_spans in &mut spans {
+            text_spans.retain(|span| policy.allows_model_span(span));
+        }
+
+        Ok(Detected {
+            walked,
+            spans,
+            rules,
+        })
+    }
+}
+
+#[tonic::async_trait]
+impl PiiRedactorService for GrpcServer {
+    async fn redact(
+        &self,
+        request: Request<RedactRequest>,
+    ) -> Result<Response<RedactResponse>, Status> {
+        let mut req = request.into_inner();
+        self.check_request(&req)?;
+        let mode = RedactionMode::try_from(req.mode)
+            .map_err(|_| Status::invalid_argument(format!("unknown mode {}", req.mode)))?;
+        let placeholder = req.placeholder_format.take().filter(|s| !s.is_empty());
+        let (placeholder, mut pseudonymizer) = match mode {
+            RedactionMode::Placeholder => (
+                placeholder.unwrap_or_else(|| DEFAULT_PLACEHOLDER.to_string()),
+                None,
+            ),
+            RedactionMode::Pseudonym => {
+                let format =
+                    placeholder.unwrap_or_else(|| DEFAULT_PSEUDONYM_FORMAT.to_string());
+                let pseudonymizer =
+                    Pseudonymizer::new(&req.pseudonym_key, &format, req.return_pseudonyms)
+                        .map_err(|e| Status::invalid_argument(format!("{e:#}")))?;
+                (format, Some(pseudonymizer))
+            }
+        };
+
+        let Detected {
+            walked,
+            spans,
+            rules,
+        } = self.run_detection(req).await?;
 
         // Stage 3: route spans → leaves → rewrite tree → serialize.
+        // One pseudonymizer spans every text, so the returned mapping is
+        // deduplicated across the whole request.
         let mut out_texts = Vec::with_capacity(walked.len());
-        for (i, (w, spans)) in walked.into_iter().zip(all_spans.into_iter()).enumerate() {
-            let s = apply_spans_and_serialize(w, spans, &placeholder).map_err(|e| {
+        for (i, (w, spans)) in walked.into_iter().zip(spans.into_iter()).enumerate() {
+            let mut replacement = match pseudonymizer.as_mut() {
+                Some(pseudonymizer) => Replacement::Pseudonym(pseudonymizer),
+                None => Replacement::Placeholder(&placeholder),
+            };
+            let s = apply_spans_and_serialize(w, spans, &rules, &mut replacement).map_err(|e| {
                 Status::internal(format!("texts[{i}]: serializing redacted output: {e:#}"))
             })?;
             out_texts.push(s);
         }
 
-        Ok(Response::new(RedactResponse { texts: out_texts }))
+        Ok(Response::new(RedactResponse {
+            texts: out_texts,
+            pseudonyms: pseudonymizer
+                .map(Pseudonymizer::into_pseudonyms)
+                .unwrap_or_default(),
+        }))
+    }
+
+    async fn detect(
+        &self,
+        request: Request<RedactRequest>,
+    ) -> Result<Response<DetectResponse>, Status> {
+        let req = request.into_inner();
+        self.check_request(&req)?;
+        let Detected {
+            walked,
+            spans,
+            rules,
+        } = self.run_detection(req).await?;
+
+        // Stage 3 without the rewrite: report where each span landed.
+        let results = walked
+            .iter()
+            .zip(spans)
+            .map(|(w, spans)| TextFindings {
+                findings: detect_leaf_spans(w, spans, &rules)
+                    .into_iter()
+                    .map(|detection| Finding {
+                        label: detection.span.label,
+                        score: detection.span.score,
+                        pointer: detection.pointer,
+                        start: detection.span.start as u32,
+                        end: detection.span.end as u32,
+                        detector: match detection.span.detector {
+                            Detector::Model => ProtoDetector::Model,
+                            Detector::Rule => ProtoDetector::Rule,
+                        } as i32,
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Ok(Response::new(DetectResponse { results }))
     }
 }
 
@@ -0,0 +1,215 @@
+//! Per-request redaction policy: which labels get redacted, how confident the
+//! model must be, and which JSON fields are in scope.
+//!
+//! Everything here is opt-in narrowing. An empty policy redacts every label
+//! the model or the rules emit, at any confidence, in every field not
+//! dropped by `skip_keys` — the behaviour before policies existed.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Result, bail};
+
+use crate::engine::Span;
+
+/// Cap on `skip_paths` + `redact_paths`. Every path is checked against every
+/// object key on the walk, so this bounds the per-leaf cost.
+pub const MAX_FIELD_PATHS: usize = 256;
+
+/// Label allow list and per-label confidence thresholds. Labels compare
+/// case-insensitively, so callers can use the `{LABEL}` spelling
+/// (`PRIVATE_EMAIL`) or the model's (`private_email`).
+#[derive(Debug, Default)]
+pub struct LabelPolicy {
+    /// `None` = every label.
+    labels: Option<HashSet<String>>,
+    min_confidence: HashMap<String, f32>,
+    default_min_confidence: f32,
+}
+
+impl LabelPolicy {
+    pub fn from_request(
+        labels: &[String],
+        min_confidence: &HashMap<String, f32>,
+        default_min_confidence: f32,
+    ) -> Result<Self> {
+        for (label, threshold) in min_confidence
+            .iter()
+            .map(|(label, t)| (label.as_str(), *t))
+            .chain(std::iter::once(("default", default_min_confidence)))
+        {
+            if !(0.0..=1.0).contains(&threshold) {
+                bail!("min_confidence for {label:?} must be within [0, 1], got {threshold}");
+            }
+        }
+
+        let labels: HashSet<String> = labels
+            .iter()
+            .map(|label| label.trim().to_lowercase())
+            .filter(|label| !label.is_empty())
+            .collect();
+        Ok(Self {
+            labels: (!labels.is_empty()).then_some(labels),
+            min_confidence: min_confidence
+                .iter()
+                .map(|(label, t)| (label.trim().to_lowercase(), *t))
+                .collect(),
+            default_min_confidence,
+        })
+    }
+
+    pub fn allows_label(&self, label: &str) -> bool {
+        self.labels
+            .as_ref()
+            .is_none_or(|labels| labels.contains(&label.to_lowercase()))
+    }
+
+    /// Whether a model span passes the label filter and its label's
+    /// threshold. Rule matches are deterministic and only go through
+    /// [`Self::allows_label`].
+    pub fn allows_model_span(&self, span: &Span) -> bool {
+        let label = span.label.to_lowercase();
+        if self.labels.as_ref().is_some_and(|l| !l.contains(&label)) {
+            return false;
+        }
+        let threshold = self
+            .min_confidence
+            .get(&label)
+            .copied()
+            .unwrap_or(self.default_min_confidence);
+        span.score >= threshold
+    }
+}
+
+/// A `/`-separated path of object keys, e.g. `metadata/user/email`. Array
+/// indices are not part of a path (an array's elements sit at the array's
+/// own path), `*` matches any single key, and a path covers everything
+/// below it.
+#[derive(Debug, Clone)]
+pub struct FieldPath(Vec<String>);
+
+impl FieldPath {
+    pub fn parse(path: &str) -> Result<Self> {
+        let segments: Vec<String> = path
+            .trim()
+            .trim_start_matches('/')
+            .split('/')
+            .map(str::to_string)
+            .collect();
+        if segments.iter().any(String::is_empty) {
+            bail!("invalid field path {path:?}: segments must be non-empty");
+        }
+        Ok(Self(segments))
+    }
+
+    /// Whether this path is a prefix of `keys`.
+    pub fn covers(&self, keys: &[String]) -> bool {
+        self.0.len() <= keys.len()
+            && self
+                .0
+                .iter()
+                .zip(keys)
+                .all(|(segment, key)| segment == "*" || segment == key)
+    }
+}
+
+/// Parse `skip_paths` and `redact_paths` together so the cap applies to
+/// their sum.
+pub fn parse_field_paths(
+    skip_paths: &[String],
+    redact_paths: &[String],
+) -> Result<(Vec<FieldPath>, Vec<FieldPath>)> {
+    if skip_paths.len() + redact_paths.len() > MAX_FIELD_PATHS {
+        bail!(
+            "{} field paths exceed the limit of {MAX_FIELD_PATHS}",
+            skip_paths.len() + redact_paths.len()
+        );
+    }
+    let parse = |paths: &[String]| {
+        paths
+            .iter()
+            .map(|path| FieldPath::parse(path))
+            .collect::<Result<Vec<_>>>()
+    };
+    Ok((parse(skip_paths)?, parse(redact_paths)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(label: &str, score: f32) -> Span {
+        Span {
+            start: 0,
+            end: 1,
+            label: label.to_string(),
+            score,
+        }
+    }
+
+    fn keys(path: &[&str]) -> Vec<String> {
+        path.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn empty_policy_allows_everything() {
+        let policy = LabelPolicy::default();
+        assert!(policy.allows_label("private_person"));
+        assert!(policy.allows_model_span(&span("private_person", 0.0)));
+    }
+
+    #[test]
+    fn label_allow_list_is_case_insensitive() {
+        let policy = LabelPolicy::from_request(
+            &["PRIVATE_EMAIL".to_string(), "credit_card".to_string()],
+            &HashMap::new(),
+            0.0,
+        )
+        .unwrap();
+        assert!(policy.allows_label("private_email"));
+        assert!(policy.allows_label("CREDIT_CARD"));
+        assert!(!policy.allows_label("private_person"));
+        assert!(!policy.allows_model_span(&span("private_person", 1.0)));
+    }
+
+    #[test]
+    fn per_label_threshold_overrides_default() {
+        let policy = LabelPolicy::from_request(
+            &[],
+            &HashMap::from([("PRIVATE_PERSON".to_string(), 0.9)]),
+            0.5,
+        )
+        .unwrap();
+        assert!(!policy.allows_model_span(&span("private_person", 0.8)));
+        assert!(policy.allows_model_span(&span("private_person", 0.95)));
+        assert!(!policy.allows_model_span(&span("private_email", 0.4)));
+        assert!(policy.allows_model_span(&span("private_email", 0.6)));
+    }
+
+    #[test]
+    fn rejects_out_of_range_thresholds() {
+        assert!(LabelPolicy::from_request(&[], &HashMap::new(), 1.5).is_err());
+        assert!(
+            LabelPolicy::from_request(&[], &HashMap::from([("x".to_string(), -0.1)]), 0.0).is_err()
+        );
+        assert!(LabelPolicy::from_request(&[], &HashMap::new(), f32::NAN).is_err());
+    }
+
+    #[test]
+    fn field_path_covers_prefixes_with_wildcards() {
+        let path = FieldPath::parse("/metadata/*/email").unwrap();
+        assert!(path.covers(&keys(&["metadata", "user", "email"])));
+        assert!(path.covers(&keys(&["metadata", "user", "email", "domain"])));
+        assert!(!path.covers(&keys(&["metadata", "user"])));
+        assert!(!path.covers(&keys(&["metadata", "user", "name"])));
+
+        assert!(FieldPath::parse("a//b").is_err());
+        assert!(FieldPath::parse("").is_err());
+    }
+
+    #[test]
+    fn field_path_cap_counts_both_lists() {
+        let paths: Vec<String> = (0..MAX_FIELD_PATHS).map(|i| format!("k{i}")).collect();
+        assert!(parse_field_paths(&paths, &[]).is_ok());
+        assert!(parse_field_paths(&paths, &["extra".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,156 @@
+//! Consistent pseudonyms for the `PSEUDONYM` redaction mode.
+//!
+//! A fixed `[REDACTED_{LABEL}]` placeholder collapses every person in a
+//! conversation into the same token. Instead, each detected value is replaced
+//! by `{LABEL}_{HASH}` where the hash is an HMAC-SHA256 of the label and the
+//! value under a caller-supplied key. The app-server derives that key per
+//! project, so the same email maps to the same pseudonym across spans and
+//! traces of one project, but pseudonyms can't be correlated across projects
+//! or reversed without the key.
+//!
+//! The caller may ask for the pseudonym → original mapping back in the
+//! response (to store it encrypted for authorized reveal); we only collect it
+//! when asked, so originals don't leave the service otherwise.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::proto::Pseudonym;
+
+pub const DEFAULT_PSEUDONYM_FORMAT: &str = "{LABEL}_{HASH}";
+
+/// Shortest accepted HMAC key. The app-server sends a 32-byte derived key;
+/// anything much shorter is a caller bug.
+pub const MIN_KEY_BYTES: usize = 16;
+
+/// Hex characters of the HMAC kept in a pseudonym. 128 bits, so two distinct
+/// values of one label in a project never share a pseudonym in practice: a
+/// collision would make the vault reveal the wrong original.
+const HASH_HEX_LEN: usize = 32;
+
+pub struct Pseudonymizer {
+    mac: Hmac<Sha256>,
+    format: String,
+    /// Pseudonym → (label, original), when the caller asked for the mapping.
+    collected: Option<HashMap<String, (String, String)>>,
+}
+
+impl Pseudonymizer {
+    /// `format` must contain `{HASH}`; without it every value would collapse
+    /// to the same token, which is what placeholder mode is for.
+    pub fn new(key: &[u8], format: &str, collect: bool) -> Result<Self> {
+        if key.len() < MIN_KEY_BYTES {
+            bail!(
+                "pseudonym_key must be at least {MIN_KEY_BYTES} bytes, got {}",
+                key.len()
+            );
+        }
+        if !format.contains("{HASH}") {
+            bail!("placeholder_format must contain {{HASH}} in PSEUDONYM mode");
+        }
+        Ok(Self {
+            mac: Hmac::new_from_slice(key)?,
+            format: format.to_string(),
+            collected: collect.then(HashMap::new),
+        })
+    }
+
+    pub fn pseudonym(&mut self, label: &str, original: &str) -> String {
+        // The label is part of the MAC input so the same string detected
+        // under two labels doesn't yield two pseudonyms sharing a hash.
+        let mut mac = self.mac.clone();
+        mac.update(label.as_bytes());
+        mac.update(&[0]);
+        mac.update(original.as_bytes());
+        let digest = mac.finalize().into_bytes();
+        let hash: String = digest
+            .iter()
+            .take(HASH_HEX_LEN / 2)
+            .map(|b| format!("{b:02x}"))
+            .collect();
+
+        let pseudonym = self
+            .format
+            .replace("{LABEL}", &label.to_uppercase())
+            .replace("{HASH}", &hash);
+        if let Some(collected) = self.collected.as_mut() {
+            collected
+                .entry(pseudonym.clone())
+                .or_insert_with(|| (label.to_string(), original.to_string()));
+        }
+        pseudonym
+    }
+
+    /// The collected mapping, empty unless collection was requested.
+    pub fn into_pseudonyms(self) -> Vec<Pseudonym> {
+        self.collected
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(pseudonym, (label, original))| Pseudonym {
+                pseudonym,
+                label,
+                original,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"0123456789abcdef0123456789abcdef";
+
+    #[test]
+    fn same_value_same_pseudonym() {
+        let mut p = Pseudonymizer::new(KEY, DEFAULT_PSEUDONYM_FORMAT, false).unwrap();
+        let a = p.pseudonym("private_email", "alice@example.com");
+        let b = p.pseudonym("private_email", "alice@example.com");
+        let c = p.pseudonym("private_email", "bob@example.com");
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(a.starts_with("PRIVATE_EMAIL_"), "{a}");
+        assert_eq!(a.len(), "PRIVATE_EMAIL_".len() + HASH_HEX_LEN);
+    }
+
+    #[test]
+    fn pseudonyms_depend_on_key_and_label() {
+        let mut p = Pseudonymizer::new(KEY, "{HASH}", false).unwrap();
+        let mut other =
+            Pseudonymizer::new(b"fedcba9876543210fedcba9876543210", "{HASH}", false).unwrap();
+
+        assert_ne!(
+            p.pseudonym("private_person", "Alice"),
+            other.pseudonym("private_person", "Alice")
+        );
+        assert_ne!(
+            p.pseudonym("private_person", "Alice"),
+            p.pseudonym("custom", "Alice")
+        );
+    }
+
+    #[test]
+    fn collects_mapping_only_when_asked() {
+        let mut p = Pseudonymizer::new(KEY, DEFAULT_PSEUDONYM_FORMAT, true).unwrap();
+        let a = p.pseudonym("private_person", "Alice");
+        p.pseudonym("private_person", "Alice");
+        let pseudonyms = p.into_pseudonyms();
+        assert_eq!(pseudonyms.len(), 1);
+        assert_eq!(pseudonyms[0].pseudonym, a);
+        assert_eq!(pseudonyms[0].original, "Alice");
+
+        let mut p = Pseudonymizer::new(KEY, DEFAULT_PSEUDONYM_FORMAT, false).unwrap();
+        p.pseudonym("private_person", "Alice");
+        assert!(p.into_pseudonyms().is_empty());
+    }
+
+    #[test]
+    fn rejects_short_key_and_format_without_hash() {
+        assert!(Pseudonymizer::new(b"short", DEFAULT_PSEUDONYM_FORMAT, false).is_err());
+        assert!(Pseudonymizer::new(KEY, "[REDACTED_{LABEL}]", false).is_err());
+    }
+}